@@ -0,0 +1,108 @@
+use algorithm_problem_client::AtCoderClient;
+use async_std::task::block_on;
+use atcoder_problems_backend::cache;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, WholeContestCrawler};
+use atcoder_problems_backend::jobs::{
+    backup_database, invalidate_aggregate_caches, restore_database, run_update_step, update_users,
+    UpdateStep,
+};
+use atcoder_problems_backend::sql::{
+    connect, set_insert_chunk_size, JobRunClient, MaintenanceClient,
+};
+use atcoder_problems_backend::storage;
+use chrono::Utc;
+use log::{self, info};
+use std::env;
+use std::error::Error;
+
+const USAGE: &str = "Usage:
+    backend_cli backup                  Back up the database to the storage
+    backend_cli restore [<path>]        Restore the database from the backup, or the latest one
+    backend_cli update <step>           Run a step of batch_update
+    backend_cli recompute_user <user>   Recompute the aggregates of the user
+    backend_cli crawl_contest <contest> Crawl all the submissions of the contest
+    backend_cli reindex [<table>]       Rebuild the indexes of the table, or of all the tables
+    backend_cli crawl_lag               Print how far the crawlers are behind";
+
+/// The operational tasks, so that the operators do not run the SQL or the scripts by hand.
+fn main() -> Result<(), Box<dyn Error>> {
+    simple_logger::init_with_level(log::Level::Info)?;
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let config = Config::load()?;
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url.as_str();
+    match args.as_slice() {
+        ["backup"] => {
+            let storage = storage::connect(&config.storage, &config.s3)?;
+            let path = backup_database(url, storage.as_ref(), config.backup.retention_count)?;
+            info!("Backed up the database to {}", path);
+        }
+        ["restore", path @ ..] if path.len() <= 1 => {
+            let storage = storage::connect(&config.storage, &config.s3)?;
+            let path = restore_database(url, storage.as_ref(), path.first().copied())?;
+            info!("Restored the database from {}", path);
+        }
+        ["update", step] => {
+            let step = UpdateStep::parse(step).ok_or_else(|| {
+                let steps = UpdateStep::ALL.iter().map(|step| step.name());
+                format!(
+                    "`{}` is not a step, which is one of {}.",
+                    step,
+                    steps.collect::<Vec<_>>().join(", ")
+                )
+            })?;
+            run_update_step(&connect(url)?, step)?;
+            let cache = cache::connect(&config.cache)?;
+            block_on(invalidate_aggregate_caches(cache.as_ref()))?;
+        }
+        ["recompute_user", user_id] => {
+            update_users(&connect(url)?, &[user_id])?;
+            let cache = cache::connect(&config.cache)?;
+            block_on(invalidate_aggregate_caches(cache.as_ref()))?;
+        }
+        ["crawl_contest", contest_id] => {
+            set_request_interval(config.crawler.request_interval());
+            let crawler = WholeContestCrawler::new(connect(url)?, AtCoderClient, contest_id);
+            block_on(crawler.crawl())?;
+        }
+        ["reindex", table @ ..] if table.len() <= 1 => {
+            connect(url)?.rebuild_indexes(table.first().copied())?;
+            info!("Rebuilt the indexes");
+        }
+        ["crawl_lag"] => print_crawl_lag(url)?,
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+    Ok(())
+}
+
+fn print_crawl_lag(url: &str) -> Result<(), Box<dyn Error>> {
+    let conn = connect(url)?;
+    let now = Utc::now().timestamp();
+    match conn.load_latest_submission_epoch_second()? {
+        Some(epoch_second) => println!("latest submission: {} seconds ago", now - epoch_second),
+        None => println!("latest submission: none"),
+    }
+    for run in conn.load_latest_job_runs()? {
+        if !run.job_name.starts_with("crawl") {
+            continue;
+        }
+        let finished = match run.finished_epoch_second {
+            Some(finished) => format!("finished {} seconds ago", now - finished),
+            None => "not finished".to_owned(),
+        };
+        println!(
+            "{}: {}, started {} seconds ago, {}",
+            run.job_name,
+            run.status,
+            now - run.started_epoch_second,
+            finished
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::jobs::backup_submissions;
+use atcoder_problems_backend::storage;
+use diesel::{Connection, PgConnection};
+use log::{self, info};
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    simple_logger::init_with_level(log::Level::Info)?;
+
+    info!("Started!");
+    let config = Config::load()?;
+    let conn = PgConnection::establish(&config.database.url)?;
+    let storage = storage::connect(&config.storage, &config.s3)?;
+    backup_submissions(&conn, storage.as_ref())?;
+
+    info!("Done.");
+    Ok(())
+}
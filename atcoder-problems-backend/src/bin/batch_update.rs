@@ -1,15 +1,10 @@
-use atcoder_problems_backend as backend;
-
-use backend::sql::connect;
-use backend::sql::models::Submission;
-use backend::sql::{
-    AcceptedCountClient, LanguageCountClient, ProblemInfoUpdater, ProblemsSubmissionUpdater,
-    RatedPointSumClient, StreakUpdater, SubmissionClient, SubmissionRequest,
-};
+use atcoder_problems_backend::cache;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::jobs::{batch_update, invalidate_aggregate_caches};
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
 
 use log::{self, info};
 use simple_logger;
-use std::env;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -17,44 +12,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     info!("Started!");
 
     info!("Connecting to SQL ...");
-    let url = env::var("SQL_URL")?;
+    let config = Config::load()?;
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
     let conn = connect(&url)?;
-
-    info!("Loading submissions ...");
-    let mut all_accepted_submissions: Vec<Submission> =
-        conn.get_submissions(SubmissionRequest::AllAccepted)?;
-
-    info!(
-        "There are {} AC submissions.",
-        all_accepted_submissions.len()
-    );
-
-    info!("Sorting by id ...");
-    all_accepted_submissions.sort_by_key(|s| s.id);
-
-    info!("Executing update_accepted_count...");
-    conn.update_accepted_count(&all_accepted_submissions)?;
-
-    info!("Executing update_problem_solver_count...");
-    conn.update_solver_count()?;
-
-    info!("Executing update_submission_count...");
-    conn.update_submission_count()?;
-
-    info!("Executing update_rated_point_sums...");
-    conn.update_rated_point_sum(&all_accepted_submissions)?;
-
-    info!("Executing update_language_count...");
-    conn.update_language_count(&all_accepted_submissions)?;
-
-    info!("Executing update_submissions_of_problems...");
-    conn.update_submissions_of_problems()?;
-
-    info!("Executing update_problem_points...");
-    conn.update_problem_points()?;
-
-    info!("Executing update_streak_count...");
-    conn.update_streak_count(&all_accepted_submissions)?;
+    batch_update(&conn)?;
+    let cache = cache::connect(&config.cache)?;
+    async_std::task::block_on(invalidate_aggregate_caches(cache.as_ref()))?;
 
     info!("Finished");
     Ok(())
@@ -1,36 +1,46 @@
 use algorithm_problem_client::AtCoderClient;
-use atcoder_problems_backend::crawler::WholeContestCrawler;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, WholeContestCrawler};
 use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::shutdown;
 use atcoder_problems_backend::sql::models::Contest;
-use atcoder_problems_backend::sql::{connect, SimpleClient};
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size, SimpleClient};
 use log::{error, info};
-use std::{env, thread, time};
+use std::time::Duration;
 
 #[async_std::main]
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
 
-    loop {
+    while !shutdown::is_requested() {
         info!("Start new loop");
 
         match load_contest(&url) {
             Ok(contests) => {
                 for contest in contests.into_iter() {
+                    if shutdown::is_requested() {
+                        break;
+                    }
                     finish_one_contest(&url, &contest.id).await;
                 }
             }
             Err(e) => {
                 error!("Failed to load the contests: {:?}", e);
-                sleep_1sec();
+                shutdown::sleep(Duration::from_secs(1)).await;
             }
         }
     }
+    info!("Stopped");
 }
 
 async fn finish_one_contest(url: &str, contest_id: &str) {
-    loop {
+    while !shutdown::is_requested() {
         info!("Starting {}", contest_id);
         match crawl_one_contest(url, contest_id).await {
             Ok(_) => {
@@ -39,10 +49,11 @@ async fn finish_one_contest(url: &str, contest_id: &str) {
             }
             Err(e) => {
                 error!("Error while crawling {}: {:?}", contest_id, e);
-                sleep_1sec();
+                shutdown::sleep(Duration::from_secs(1)).await;
             }
         }
     }
+    info!("Stopped");
 }
 
 async fn crawl_one_contest(url: &str, contest_id: &str) -> Result<()> {
@@ -57,7 +68,3 @@ fn load_contest(url: &str) -> Result<Vec<Contest>> {
     let contests = db.load_contests()?;
     Ok(contests)
 }
-
-fn sleep_1sec() {
-    thread::sleep(time::Duration::from_millis(1000));
-}
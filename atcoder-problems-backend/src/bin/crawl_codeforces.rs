@@ -0,0 +1,27 @@
+use algorithm_problem_client::CodeforcesClient;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::CodeforcesCrawler;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+use log::info;
+use std::env;
+
+/// Crawls the Codeforces problems and the recent submissions. The whole submissions of the users
+/// given as the arguments are crawled as well.
+#[async_std::main]
+async fn main() -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    let db = connect(&url)?;
+    let crawler = CodeforcesCrawler::new(db, CodeforcesClient::default());
+    crawler.crawl_problems().await?;
+    crawler.crawl_recent_submissions().await?;
+    for handle in env::args().skip(1) {
+        crawler.crawl_user_submissions(&handle).await?;
+    }
+    info!("Finished");
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use algorithm_problem_client::AtCoderClient;
-use atcoder_problems_backend::crawler::{FixCrawler, VirtualContestCrawler};
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, FixCrawler, VirtualContestCrawler};
 use atcoder_problems_backend::error::Result;
-use atcoder_problems_backend::sql::connect;
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
 use chrono::Utc;
 use std::time::{Duration, Instant};
-use std::{env, thread};
 
 const FIX_RANGE_SECOND: i64 = 10 * 60;
 
@@ -28,10 +29,14 @@ async fn crawl(url: &str) -> Result<()> {
 #[async_std::main]
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).expect("Failed to initialize the logger.");
-    let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
     log::info!("Started");
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
 
-    loop {
+    while !shutdown::is_requested() {
         log::info!("Start new loop...");
         let now = Instant::now();
 
@@ -44,9 +49,10 @@ async fn main() {
         if elapsed_secs < 10 {
             let sleep_seconds = 10 - elapsed_secs;
             log::info!("Sleeping {} sec.", sleep_seconds);
-            thread::sleep(Duration::from_secs(sleep_seconds));
+            shutdown::sleep(Duration::from_secs(sleep_seconds)).await;
         }
 
         log::info!("Finished a loop");
     }
+    log::info!("Stopped");
 }
@@ -1,9 +1,11 @@
 use algorithm_problem_client::AtCoderClient;
-use atcoder_problems_backend::crawler::WholeContestCrawler;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, WholeContestCrawler};
 use atcoder_problems_backend::error::Result;
-use atcoder_problems_backend::sql::{connect, SimpleClient};
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size, SimpleClient};
 use log::info;
-use std::{env, thread, time};
+use std::time;
 
 const NEW_CONTEST_NUM: usize = 5;
 
@@ -26,13 +28,18 @@ async fn iteration(url: &str) -> Result<()> {
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
 
-    loop {
+    while !shutdown::is_requested() {
         info!("Start new loop");
         if let Err(e) = iteration(&url).await {
             log::error!("{:?}", e);
-            thread::sleep(time::Duration::from_millis(1000));
+            shutdown::sleep(time::Duration::from_millis(1000)).await;
         }
     }
+    info!("Stopped");
 }
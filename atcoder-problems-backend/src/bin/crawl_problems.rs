@@ -1,13 +1,15 @@
 use algorithm_problem_client::AtCoderClient;
+use atcoder_problems_backend::config::Config;
 use atcoder_problems_backend::crawler::ProblemCrawler;
-use atcoder_problems_backend::sql::connect;
-use std::env;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
 
 #[async_std::main]
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     log::info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
 
     let db = connect(&url).unwrap();
     let crawler = ProblemCrawler::new(db, AtCoderClient::default());
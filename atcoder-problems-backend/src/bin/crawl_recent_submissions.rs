@@ -1,8 +1,10 @@
 use algorithm_problem_client::AtCoderClient;
-use atcoder_problems_backend::crawler::RecentCrawler;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, RecentCrawler};
 use atcoder_problems_backend::error::Result;
-use atcoder_problems_backend::sql::connect;
-use std::{env, thread, time};
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+use std::time;
 
 async fn crawl(url: &str) -> Result<()> {
     let db = connect(url)?;
@@ -14,13 +16,18 @@ async fn crawl(url: &str) -> Result<()> {
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     log::info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
 
-    loop {
+    while !shutdown::is_requested() {
         log::info!("Start new loop");
         if let Err(e) = crawl(&url).await {
             log::error!("{:?}", e);
-            thread::sleep(time::Duration::from_millis(1000));
+            shutdown::sleep(time::Duration::from_millis(1000)).await;
         }
     }
+    log::info!("Stopped");
 }
@@ -0,0 +1,28 @@
+use algorithm_problem_client::AtCoderClient;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, WholeContestCrawler};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::internal::moderation_manager::ModerationManager;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+use log::info;
+
+#[async_std::main]
+async fn main() -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    let conn = connect(&url)?;
+    let contest_ids = conn.get_recrawl_requests()?;
+    info!("There are {} requested contests.", contest_ids.len());
+    for contest_id in contest_ids.into_iter() {
+        let crawler =
+            WholeContestCrawler::new(connect(&url)?, AtCoderClient::default(), &contest_id);
+        crawler.crawl().await?;
+        conn.complete_recrawl(&contest_id)?;
+    }
+    info!("Finished");
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use algorithm_problem_client::AtCoderClient;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, StandingsCrawler};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+use chrono::Utc;
+use log::info;
+use std::env;
+
+const MAX_CONTESTS_PER_RUN: i64 = 10;
+
+/// Crawls the official standings of the contests given as the arguments. Without the arguments,
+/// the standings of the ended contests which have no standings yet are crawled.
+#[async_std::main]
+async fn main() -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let db = connect(&config.database.url)?;
+    let crawler = StandingsCrawler::new(db, AtCoderClient::default());
+    let contest_ids = env::args().skip(1).collect::<Vec<_>>();
+    if contest_ids.is_empty() {
+        crawler
+            .crawl_missing(Utc::now().timestamp(), MAX_CONTESTS_PER_RUN)
+            .await?;
+    } else {
+        for contest_id in contest_ids.iter() {
+            crawler.crawl(contest_id).await?;
+        }
+    }
+    info!("Finished");
+    Ok(())
+}
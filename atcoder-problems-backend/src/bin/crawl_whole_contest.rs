@@ -1,7 +1,8 @@
 use algorithm_problem_client::AtCoderClient;
-use atcoder_problems_backend::crawler::WholeContestCrawler;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::{set_request_interval, WholeContestCrawler};
 use atcoder_problems_backend::error::Result;
-use atcoder_problems_backend::sql::connect;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
 use log::info;
 use std::env;
 
@@ -9,7 +10,10 @@ use std::env;
 async fn main() -> Result<()> {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL is not set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
     let contest_id = env::args().nth(1).expect("contest_id is not set.");
     let db = connect(&url)?;
     let crawler = WholeContestCrawler::new(db, AtCoderClient::default(), contest_id);
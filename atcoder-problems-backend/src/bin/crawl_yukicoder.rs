@@ -0,0 +1,27 @@
+use algorithm_problem_client::YukicoderClient;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::YukicoderCrawler;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+use chrono::Utc;
+use log::info;
+use std::env;
+
+/// Crawls the yukicoder problems and the problems solved by the users given as the arguments.
+#[async_std::main]
+async fn main() -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
+    let db = connect(&url)?;
+    let crawler = YukicoderCrawler::new(db, YukicoderClient::default());
+    crawler.crawl_problems().await?;
+    let now = Utc::now().timestamp();
+    for user_name in env::args().skip(1) {
+        crawler.crawl_user_submissions(&user_name, now).await?;
+    }
+    info!("Finished");
+    Ok(())
+}
@@ -1,12 +1,10 @@
-use atcoder_problems_backend::sql::{
-    AcceptedCountClient, LanguageCountClient, RatedPointSumClient, StreakUpdater, SubmissionClient,
-    SubmissionRequest,
-};
+use atcoder_problems_backend::cache;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::jobs::{delta_update, invalidate_aggregate_caches};
+use atcoder_problems_backend::sql::set_insert_chunk_size;
 use diesel::{Connection, PgConnection};
 use log::{self, info};
 use simple_logger;
-use std::collections::BTreeSet;
-use std::env;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -14,43 +12,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     info!("Started!");
 
     info!("Connecting to SQL ...");
-    let url = env::var("SQL_URL")?;
+    let config = Config::load()?;
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
     let conn = PgConnection::establish(&url)?;
-
-    info!("Loading submissions ...");
-    let request = SubmissionRequest::RecentAccepted { count: 1000 };
-    let recent_submissions = conn.get_submissions(request)?;
-
-    let user_ids = recent_submissions
-        .into_iter()
-        .map(|s| s.user_id)
-        .collect::<BTreeSet<_>>();
-    let user_ids = user_ids.iter().map(|s| s.as_str()).collect::<Vec<_>>();
-
-    info!("Loading submissions of {} users ...", user_ids.len());
-    let request = SubmissionRequest::UsersAccepted {
-        user_ids: &user_ids,
-    };
-    let mut user_accepted_submissions = conn.get_submissions(request)?;
-    info!("There are {} submissions.", user_accepted_submissions.len());
-
-    info!("Sorting by id ...");
-    user_accepted_submissions.sort_by_key(|s| s.id);
-
-    info!("Executing update_rated_point_sum...");
-    conn.update_rated_point_sum(&user_accepted_submissions)?;
-
-    info!("Executing update_accepted_count...");
-    conn.update_accepted_count(&user_accepted_submissions)?;
-
-    info!("Executing update_language_count...");
-    conn.update_language_count(&user_accepted_submissions)?;
-
-    info!("Executing update_streak_count...");
-    conn.update_streak_count(&user_accepted_submissions)?;
-
-    info!("Executing update_submission_count...");
-    conn.update_submission_count()?;
+    delta_update(&conn)?;
+    let cache = cache::connect(&config.cache)?;
+    async_std::task::block_on(invalidate_aggregate_caches(cache.as_ref()))?;
 
     info!("Finished");
     Ok(())
@@ -0,0 +1,56 @@
+use atcoder_problems_backend::config::{Config, EmailConfig};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::notification::{
+    DailyDigestGenerator, EmailChannel, HttpWebhookSender, NotificationDispatcher,
+    NotificationFeeder, SesEmailSender,
+};
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::connect;
+use chrono::Utc;
+use std::time::{Duration, Instant};
+
+const LOOP_INTERVAL_SECOND: u64 = 60;
+
+async fn dispatch(url: &str, email: &EmailConfig) -> Result<()> {
+    let now = Utc::now().timestamp();
+    let feeder = NotificationFeeder::new(connect(url)?);
+    let enqueued = feeder.feed(now)?;
+    log::info!("Enqueued {} notifications", enqueued);
+
+    let generator = DailyDigestGenerator::new(connect(url)?);
+    let enqueued = generator.generate(now)?;
+    log::info!("Enqueued {} daily digests", enqueued);
+
+    let mut dispatcher = NotificationDispatcher::new(connect(url)?, HttpWebhookSender);
+    if let Some(from_address) = email.from_address.as_ref() {
+        dispatcher = dispatcher.with_email(EmailChannel {
+            sender: Box::new(SesEmailSender::from_env(&email.ses_region, from_address)?),
+            api_url: email.api_url.clone(),
+        });
+    }
+    let delivered = dispatcher.dispatch(now).await?;
+    log::info!("Delivered {} notifications", delivered);
+    Ok(())
+}
+
+#[async_std::main]
+async fn main() {
+    simple_logger::init_with_level(log::Level::Info).expect("Failed to initialize the logger.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    let url = config.database.url;
+    log::info!("Started");
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
+
+    while !shutdown::is_requested() {
+        let now = Instant::now();
+        if let Err(e) = dispatch(&url, &config.email).await {
+            log::error!("{:?}", e);
+        }
+
+        let elapsed_secs = now.elapsed().as_secs();
+        if elapsed_secs < LOOP_INTERVAL_SECOND {
+            shutdown::sleep(Duration::from_secs(LOOP_INTERVAL_SECOND - elapsed_secs)).await;
+        }
+    }
+    log::info!("Stopped");
+}
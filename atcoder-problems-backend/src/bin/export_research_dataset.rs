@@ -0,0 +1,57 @@
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::research_dataset::{
+    ExportOptions, Pseudonymizer, ResearchDatasetExporter,
+};
+use atcoder_problems_backend::sql::models::Dump;
+use atcoder_problems_backend::sql::DumpClient;
+use atcoder_problems_backend::storage;
+use chrono::Utc;
+use diesel::{Connection, PgConnection};
+use log::{self, info};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::error::Error;
+
+const DATASET_PATH: &str = "/resources/research/submissions.jsonl";
+
+/// Exports the submissions with pseudonymized user ids for research use.
+/// `research.dataset_salt` must be kept secret and unchanged, so that the pseudonyms are stable
+/// across exports. `--without-length` and `--without-language` drop the columns.
+fn main() -> Result<(), Box<dyn Error>> {
+    simple_logger::init_with_level(log::Level::Info)?;
+
+    info!("Started!");
+    let config = Config::load()?;
+    let url = config.database.url;
+    let salt = config
+        .research
+        .dataset_salt
+        .ok_or("research.dataset_salt is not set.")?;
+    let args = env::args().collect::<Vec<_>>();
+    let options = ExportOptions {
+        include_length: !args.iter().any(|arg| arg == "--without-length"),
+        include_language: !args.iter().any(|arg| arg == "--without-language"),
+    };
+
+    let exporter = ResearchDatasetExporter::new(
+        PgConnection::establish(&url)?,
+        Pseudonymizer::new(salt),
+        options,
+    );
+    let data = exporter.export()?;
+
+    let storage = storage::connect(&config.storage, &config.s3)?;
+    let dump = Dump {
+        name: DATASET_PATH.trim_start_matches("/resources/").to_owned(),
+        url: storage.public_url(DATASET_PATH),
+        size: data.len() as i64,
+        sha256: hex::encode(Sha256::digest(&data)),
+        generated_epoch_second: Utc::now().timestamp(),
+    };
+    info!("Uploading {}", DATASET_PATH);
+    storage.put(DATASET_PATH, &data, "application/x-ndjson;charset=utf-8")?;
+    PgConnection::establish(&url)?.update_dump(&dump)?;
+
+    info!("Done.");
+    Ok(())
+}
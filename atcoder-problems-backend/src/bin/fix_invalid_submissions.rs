@@ -1,9 +1,10 @@
 use algorithm_problem_client::AtCoderClient;
+use atcoder_problems_backend::config::Config;
 use atcoder_problems_backend::crawler::FixCrawler;
+use atcoder_problems_backend::sql::set_insert_chunk_size;
 use chrono::Utc;
 use diesel::{Connection, PgConnection};
 use log::info;
-use std::env;
 
 const ONE_DAY: i64 = 24 * 3600;
 
@@ -11,7 +12,9 @@ const ONE_DAY: i64 = 24 * 3600;
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     info!("Started");
-    let url = env::var("SQL_URL").expect("SQL_URL must be set.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    let url = config.database.url;
     let conn = PgConnection::establish(&url).expect("Failed to connect PostgreSQL.");
     let now = Utc::now().timestamp();
     let crawler = FixCrawler::new(conn, AtCoderClient::default(), now - ONE_DAY);
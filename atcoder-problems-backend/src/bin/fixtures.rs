@@ -0,0 +1,47 @@
+use atcoder_problems_backend::fixtures::{FixtureOptions, Fixtures};
+use atcoder_problems_backend::sql::connect;
+use log::{self, info};
+use std::env;
+use std::error::Error;
+
+const USAGE: &str = "Usage:
+    fixtures <database_url> [<submissions> [<seed>]]
+
+    <database_url>  The test database to store the synthetic data
+    <submissions>   The number of the submissions to generate (default: 100000)
+    <seed>          The seed of the generator, with which the same data set is generated";
+
+/// Generates the synthetic contests and submissions into a test database, so that the
+/// performance of the updaters and the endpoints can be tested without a production dump. The
+/// database must not be the production one, whose contests would be overwritten.
+fn main() -> Result<(), Box<dyn Error>> {
+    simple_logger::init_with_level(log::Level::Info)?;
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let (url, options) = match args.as_slice() {
+        [url, options @ ..] if options.len() <= 2 => (url, options),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let mut fixture_options = FixtureOptions::default();
+    if let Some(submissions) = options.get(0) {
+        fixture_options.submission_count = submissions.parse()?;
+    }
+    if let Some(seed) = options.get(1) {
+        fixture_options.seed = seed.parse()?;
+    }
+
+    info!("Generating {:?} ...", fixture_options);
+    let fixtures = Fixtures::generate(&fixture_options);
+    info!(
+        "Storing {} submissions and {} rejudges ...",
+        fixtures.submissions.len(),
+        fixtures.rejudged.len()
+    );
+    fixtures.insert(&connect(url)?)?;
+    info!("Done.");
+    Ok(())
+}
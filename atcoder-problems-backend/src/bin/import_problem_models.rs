@@ -0,0 +1,23 @@
+use atcoder_problems_backend::cache;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::jobs::{import_problem_models, invalidate_problem_model_caches};
+use atcoder_problems_backend::sql::{connect, set_insert_chunk_size};
+
+#[async_std::main]
+async fn main() {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    log::info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_insert_chunk_size(config.database.insert_chunk_size);
+
+    let db = connect(&config.database.url).unwrap();
+    import_problem_models(&db, &config.jobs.problem_models_url)
+        .await
+        .expect("Failed to update problem models");
+    let cache = cache::connect(&config.cache).expect("Failed to connect to the cache.");
+    invalidate_problem_model_caches(cache.as_ref())
+        .await
+        .expect("Failed to invalidate the caches");
+
+    log::info!("Finished");
+}
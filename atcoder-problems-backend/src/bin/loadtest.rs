@@ -0,0 +1,80 @@
+use async_std::task::block_on;
+use atcoder_problems_backend::loadtest::{parse_recording, schedule, summarize, LoadTester};
+use log::{self, info};
+use std::env;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage:
+    loadtest <recording> <base_url> [<requests> [<concurrency>]]
+
+    <recording>    A JSON Lines file of {\"path\": ..., \"count\": ...} of the recorded requests
+    <base_url>     The server to test, e.g. https://staging.example.com
+    <requests>     The number of the requests to send (default: 1000)
+    <concurrency>  The number of the requests in flight at a time (default: 8)";
+const DEFAULT_REQUESTS: usize = 1000;
+const DEFAULT_CONCURRENCY: usize = 8;
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Replays the recorded distribution of the requests against a staging server, and prints the
+/// latency percentiles of each route. Do not run it against the production server.
+fn main() -> Result<(), Box<dyn Error>> {
+    simple_logger::init_with_level(log::Level::Info)?;
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let (recording, base_url, options) = match args.as_slice() {
+        [recording, base_url, options @ ..] if options.len() <= 2 => (recording, base_url, options),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let requests = match options.get(0) {
+        Some(requests) => requests.parse()?,
+        None => DEFAULT_REQUESTS,
+    };
+    let concurrency = match options.get(1) {
+        Some(concurrency) => concurrency.parse()?,
+        None => DEFAULT_CONCURRENCY,
+    };
+
+    let recording = parse_recording(&std::fs::read_to_string(recording)?)?;
+    let paths = schedule(&recording, requests);
+    info!(
+        "Sending {} requests to {} with the concurrency {} ...",
+        paths.len(),
+        base_url,
+        concurrency
+    );
+    let started = Instant::now();
+    let samples = block_on(LoadTester::new(base_url, concurrency).run(paths));
+    let elapsed = started.elapsed();
+
+    println!(
+        "{:<48} {:>8} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "route", "requests", "failures", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    for latency in summarize(&samples).iter() {
+        println!(
+            "{:<48} {:>8} {:>8} {:>10.1} {:>10.1} {:>10.1} {:>10.1}",
+            latency.route,
+            latency.request_count,
+            latency.failure_count,
+            millis(latency.p50),
+            millis(latency.p90),
+            millis(latency.p99),
+            millis(latency.max)
+        );
+    }
+    println!(
+        "{} requests in {:.1} s ({:.1} requests/s)",
+        samples.len(),
+        elapsed.as_secs_f64(),
+        samples.len() as f64 / elapsed.as_secs_f64()
+    );
+    Ok(())
+}
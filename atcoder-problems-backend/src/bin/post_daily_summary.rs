@@ -0,0 +1,51 @@
+use atcoder_problems_backend::bot::{
+    DailySummaryBot, DryRunPoster, Poster, Templates, TwitterPoster,
+};
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::connect;
+use chrono::Utc;
+use log::info;
+use std::env;
+
+async fn run<P: Poster>(url: &str, poster: P, templates: Templates) -> Result<()> {
+    let bot = DailySummaryBot::new(connect(url)?, poster, templates);
+    let count = bot.run(Utc::now().timestamp()).await?;
+    info!("Posted {} summaries", count);
+    Ok(())
+}
+
+/// Posts the daily summaries to Twitter with the access token in `bot.twitter_access_token`. The
+/// posts are only logged with `--dry-run`. The templates can be overridden in `[bot]`.
+#[async_std::main]
+async fn main() -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).unwrap();
+    info!("Started");
+    let config = Config::load().expect("Failed to load the configuration.");
+    let url = config.database.url;
+
+    let mut templates = Templates::default();
+    let overrides = vec![
+        (config.bot.shortest_header, &mut templates.shortest_header),
+        (config.bot.shortest_item, &mut templates.shortest_item),
+        (config.bot.streak_header, &mut templates.streak_header),
+        (config.bot.streak_item, &mut templates.streak_item),
+    ];
+    for (value, template) in overrides.into_iter() {
+        if let Some(value) = value {
+            *template = value;
+        }
+    }
+
+    if env::args().skip(1).any(|arg| arg == "--dry-run") {
+        run(&url, DryRunPoster, templates).await?;
+    } else {
+        let token = config
+            .bot
+            .twitter_access_token
+            .expect("bot.twitter_access_token or TWITTER_ACCESS_TOKEN is not set.");
+        run(&url, TwitterPoster::new(token), templates).await?;
+    }
+    info!("Finished");
+    Ok(())
+}
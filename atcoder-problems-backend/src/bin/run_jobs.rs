@@ -0,0 +1,60 @@
+use atcoder_problems_backend::cache;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::set_request_interval;
+use atcoder_problems_backend::jobs::{
+    BackupJob, BatchUpdateJob, ColdStartJob, DeltaUpdateJob, DumpJob, JobScheduler,
+    NewContestCrawlJob, ProblemCrawlJob, ProblemModelJob, PurgeJob, RecentCrawlJob,
+    SubmissionArchiveJob, UpdateTier, ValidationJob, VirtualContestArchiveJob,
+};
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::set_insert_chunk_size;
+use atcoder_problems_backend::storage;
+
+fn main() {
+    simple_logger::init_with_level(log::Level::Info).expect("Failed to initialize the logger.");
+    let config = Config::load().expect("Failed to load the configuration.");
+    set_request_interval(config.crawler.request_interval());
+    set_insert_chunk_size(config.database.insert_chunk_size);
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
+    log::info!("Started");
+
+    let cache = cache::connect(&config.cache).expect("Failed to connect to the cache.");
+    let storage =
+        storage::connect(&config.storage, &config.s3).expect("Failed to connect to the storage.");
+    let jobs = config.jobs;
+    let scheduler = JobScheduler::new(&config.database.url)
+        .add(RecentCrawlJob, jobs.recent_crawl_interval_second)
+        .add(NewContestCrawlJob, jobs.new_contest_crawl_interval_second)
+        .add(ProblemCrawlJob, jobs.problem_crawl_interval_second)
+        .add(
+            DeltaUpdateJob::new(UpdateTier::Fast, cache.clone()),
+            jobs.delta_update_interval_second,
+        )
+        .add(
+            DeltaUpdateJob::new(UpdateTier::Slow, cache.clone()),
+            jobs.slow_delta_update_interval_second,
+        )
+        .add(
+            BatchUpdateJob::new(cache.clone()),
+            jobs.batch_update_interval_second,
+        )
+        .add(DumpJob::new(storage.clone()), jobs.dump_interval_second)
+        .add(BackupJob::new(storage), jobs.backup_interval_second)
+        .add(
+            ProblemModelJob::new(jobs.problem_models_url, cache.clone()),
+            jobs.problem_model_interval_second,
+        )
+        .add(ValidationJob, jobs.validation_interval_second)
+        .add(
+            VirtualContestArchiveJob,
+            jobs.virtual_contest_archive_interval_second,
+        )
+        .add(PurgeJob, jobs.purge_interval_second)
+        .add(ColdStartJob::new(cache), jobs.cold_start_interval_second)
+        .add(
+            SubmissionArchiveJob::new(jobs.submission_archive_after_years),
+            jobs.submission_archive_interval_second,
+        );
+    scheduler.run().expect("Failed to run the jobs.");
+    log::info!("Stopped");
+}
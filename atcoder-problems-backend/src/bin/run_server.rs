@@ -1,21 +1,22 @@
-use std::env;
-
+use atcoder_problems_backend::config::Config;
 use atcoder_problems_backend::server::GitHubAuthentication;
-use atcoder_problems_backend::server::{initialize_pool, run_server};
+use atcoder_problems_backend::server::{initialize_pool, run_server_with_config};
+use atcoder_problems_backend::shutdown;
 
 #[async_std::main]
 async fn main() {
     simple_logger::init_with_level(log::Level::Info).unwrap();
-    let database_url = env::var("SQL_URL").expect("SQL_URL is not set.");
-    let port = 8080;
-
-    let client_id = env::var("CLIENT_ID").unwrap_or_else(|_| String::new());
-    let client_secret = env::var("CLIENT_SECRET").unwrap_or_else(|_| String::new());
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
+    let config = Config::load().expect("Failed to load the configuration.");
 
-    let auth = GitHubAuthentication::new(&client_id, &client_secret);
+    let auth = GitHubAuthentication::new(
+        &config.server.github_client_id,
+        &config.server.github_client_secret,
+    );
 
-    let pool = initialize_pool(database_url).expect("Failed to initialize the connection pool");
-    run_server(pool, auth, port)
+    let pool = initialize_pool(config.database.url.as_str())
+        .expect("Failed to initialize the connection pool");
+    run_server_with_config(pool, auth, &config)
         .await
         .expect("Failed to run server");
 }
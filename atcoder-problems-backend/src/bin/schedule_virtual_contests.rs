@@ -0,0 +1,33 @@
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::sql::connect;
+use atcoder_problems_backend::sql::internal::virtual_contest_template_manager::VirtualContestTemplateManager;
+use chrono::Utc;
+
+fn main() {
+    simple_logger::init_with_level(log::Level::Info).expect("Failed to initialize the logger.");
+    let url = Config::load()
+        .expect("Failed to load the configuration.")
+        .database
+        .url;
+    log::info!("Started");
+
+    let conn = connect(&url).expect("Failed to connect to the database.");
+    let now = Utc::now().timestamp();
+    let templates = conn.load_templates().expect("Failed to load templates.");
+    log::info!("Loaded {} templates", templates.len());
+
+    for template in templates.iter() {
+        match conn.instantiate_template(template, now) {
+            Ok(Some(contest_id)) => log::info!(
+                "Scheduled {} from template {} at {}",
+                contest_id,
+                template.id,
+                template.next_start_epoch_second(now)
+            ),
+            Ok(None) => {}
+            Err(e) => log::error!("Failed to instantiate template {}: {:?}", template.id, e),
+        }
+    }
+
+    log::info!("Finished");
+}
@@ -0,0 +1,39 @@
+use algorithm_problem_client::AtCoderClient;
+use atcoder_problems_backend::config::Config;
+use atcoder_problems_backend::crawler::UserVerificationCrawler;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::shutdown;
+use atcoder_problems_backend::sql::connect;
+use std::time::{Duration, Instant};
+
+const LOOP_INTERVAL_SECOND: u64 = 60;
+
+async fn verify(url: &str) -> Result<()> {
+    let conn = connect(url)?;
+    let crawler = UserVerificationCrawler::new(conn, AtCoderClient::default());
+    crawler.crawl().await
+}
+
+#[async_std::main]
+async fn main() {
+    simple_logger::init_with_level(log::Level::Info).expect("Failed to initialize the logger.");
+    let url = Config::load()
+        .expect("Failed to load the configuration.")
+        .database
+        .url;
+    log::info!("Started");
+    shutdown::listen_signals().expect("Failed to listen to the signals.");
+
+    while !shutdown::is_requested() {
+        let now = Instant::now();
+        if let Err(e) = verify(&url).await {
+            log::error!("{:?}", e);
+        }
+
+        let elapsed_secs = now.elapsed().as_secs();
+        if elapsed_secs < LOOP_INTERVAL_SECOND {
+            shutdown::sleep(Duration::from_secs(LOOP_INTERVAL_SECOND - elapsed_secs)).await;
+        }
+    }
+    log::info!("Stopped");
+}
@@ -0,0 +1,299 @@
+use crate::error::Result;
+use crate::sql::internal::moderation_manager::ModerationManager;
+use crate::sql::{SimpleClient, StreakUpdater, SubmissionClient, SubmissionRequest};
+
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+const TWITTER_API_URL: &str = "https://api.twitter.com/2/tweets";
+const MAX_POST_WEIGHT: usize = 280;
+const MAX_SHORTEST_ITEMS: usize = 20;
+const STREAK_LEADER_COUNT: usize = 5;
+const ONE_DAY_SECOND: i64 = 24 * 3600;
+
+#[async_trait]
+pub trait Poster {
+    async fn post(&self, text: &str) -> Result<()>;
+}
+
+/// Posts with the OAuth 2.0 access token of the bot account.
+pub struct TwitterPoster {
+    access_token: String,
+}
+
+impl TwitterPoster {
+    pub fn new(access_token: String) -> Self {
+        Self { access_token }
+    }
+}
+
+#[async_trait]
+impl Poster for TwitterPoster {
+    async fn post(&self, text: &str) -> Result<()> {
+        let response = surf::post(TWITTER_API_URL)
+            .set_header("authorization", format!("Bearer {}", self.access_token))
+            .body_json(&json!({ "text": text }))?
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(http_types::Error::from_str(
+                response.status(),
+                "Twitter rejected the post.",
+            ))
+        }
+    }
+}
+
+/// Logs the posts instead of posting them.
+pub struct DryRunPoster;
+
+#[async_trait]
+impl Poster for DryRunPoster {
+    async fn post(&self, text: &str) -> Result<()> {
+        log::info!("[dry-run]\n{}", text);
+        Ok(())
+    }
+}
+
+/// `{name}` in the templates is replaced with the field of the item.
+///
+/// - `shortest_item`: `user_id`, `problem_id`, `problem_title`, `contest_id`, `length` and
+///   `language`
+/// - `streak_item`: `rank`, `user_id` and `streak`
+pub struct Templates {
+    pub shortest_header: String,
+    pub shortest_item: String,
+    pub streak_header: String,
+    pub streak_item: String,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self {
+            shortest_header: "New shortest codes on AtCoder today:".to_owned(),
+            shortest_item: "{problem_title}: {user_id} ({length} bytes, {language})".to_owned(),
+            streak_header: "Longest streaks on AtCoder:".to_owned(),
+            streak_item: "{rank}. {user_id} ({streak} days)".to_owned(),
+        }
+    }
+}
+
+/// Posts the daily summaries built from the aggregate tables. The banned users are left out.
+pub struct DailySummaryBot<C, P> {
+    db: C,
+    poster: P,
+    templates: Templates,
+}
+
+impl<C, P> DailySummaryBot<C, P>
+where
+    C: SubmissionClient + SimpleClient + StreakUpdater + ModerationManager,
+    P: Poster,
+{
+    pub fn new(db: C, poster: P, templates: Templates) -> Self {
+        Self {
+            db,
+            poster,
+            templates,
+        }
+    }
+
+    /// Posts the summaries of the day ending at `now`, and returns the number of the posts.
+    pub async fn run(&self, now: i64) -> Result<usize> {
+        let posts = self.compose(now)?;
+        for post in posts.iter() {
+            self.poster.post(post).await?;
+        }
+        Ok(posts.len())
+    }
+
+    pub fn compose(&self, now: i64) -> Result<Vec<String>> {
+        let banned_users = self
+            .db
+            .get_banned_users()?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let titles = self
+            .db
+            .load_problems()?
+            .into_iter()
+            .map(|problem| (problem.id, problem.title))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut shortest = self
+            .db
+            .get_submissions(SubmissionRequest::ShortestFromTime {
+                from_second: now - ONE_DAY_SECOND,
+            })?
+            .into_iter()
+            .filter(|s| s.epoch_second < now && !banned_users.contains(&s.user_id))
+            .collect::<Vec<_>>();
+        shortest.sort_by_key(|s| (s.epoch_second, s.id));
+        let shortest_items = shortest
+            .iter()
+            .take(MAX_SHORTEST_ITEMS)
+            .map(|s| {
+                let title = titles.get(&s.problem_id).unwrap_or(&s.problem_id);
+                render(
+                    &self.templates.shortest_item,
+                    &[
+                        ("user_id", s.user_id.clone()),
+                        ("problem_id", s.problem_id.clone()),
+                        ("problem_title", title.clone()),
+                        ("contest_id", s.contest_id.clone()),
+                        ("length", s.length.to_string()),
+                        ("language", s.language.clone()),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let ranking_count = (STREAK_LEADER_COUNT + banned_users.len()) as i64;
+        let streak_items = self
+            .db
+            .load_streak_ranking(ranking_count)?
+            .into_iter()
+            .filter(|(user_id, _)| !banned_users.contains(user_id))
+            .take(STREAK_LEADER_COUNT)
+            .enumerate()
+            .map(|(i, (user_id, streak))| {
+                render(
+                    &self.templates.streak_item,
+                    &[
+                        ("rank", (i + 1).to_string()),
+                        ("user_id", user_id),
+                        ("streak", streak.to_string()),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut posts = pack(&self.templates.shortest_header, &shortest_items);
+        posts.extend(pack(&self.templates.streak_header, &streak_items));
+        Ok(posts)
+    }
+}
+
+/// Replaces `{name}` with the value of the field. Unknown names are left as they are.
+fn render(template: &str, fields: &[(&str, String)]) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let placeholder = &rest[open..];
+        let value = placeholder.find('}').and_then(|close| {
+            let name = &placeholder[1..close];
+            let (_, value) = fields.iter().find(|(field, _)| *field == name)?;
+            Some((value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                rendered.push_str(value);
+                rest = &placeholder[(close + 1)..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Twitter counts CJK characters and the others outside of the Latin scripts as two.
+fn weight(text: &str) -> usize {
+    text.chars()
+        .map(|c| if (c as u32) <= 0x10FF { 1 } else { 2 })
+        .sum()
+}
+
+fn truncate(text: &str, max_weight: usize) -> String {
+    if weight(text) <= max_weight {
+        return text.to_owned();
+    }
+    const ELLIPSIS: &str = "…";
+    let mut truncated = String::new();
+    let mut total = weight(ELLIPSIS);
+    for c in text.chars() {
+        total += weight(&c.to_string());
+        if total > max_weight {
+            break;
+        }
+        truncated.push(c);
+    }
+    truncated.push_str(ELLIPSIS);
+    truncated
+}
+
+/// Packs the items below the header into as few posts as possible.
+fn pack(header: &str, items: &[String]) -> Vec<String> {
+    let mut posts = vec![];
+    let mut current: Option<String> = None;
+    for item in items.iter() {
+        current = match current {
+            Some(post) if weight(&post) + 1 + weight(item) <= MAX_POST_WEIGHT => {
+                Some(format!("{}\n{}", post, item))
+            }
+            post => {
+                posts.extend(post);
+                Some(truncate(&format!("{}\n{}", header, item), MAX_POST_WEIGHT))
+            }
+        };
+    }
+    posts.extend(current);
+    posts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let fields = [
+            ("user_id", "kenkoooo".to_owned()),
+            ("length", "10".to_owned()),
+        ];
+        assert_eq!(
+            render("{user_id}: {length} bytes {unknown} {", &fields),
+            "kenkoooo: 10 bytes {unknown} {"
+        );
+        assert_eq!(
+            render("{user_id}", &[("user_id", "{length}".to_owned())]),
+            "{length}"
+        );
+    }
+
+    #[test]
+    fn test_weight() {
+        assert_eq!(weight("abc"), 3);
+        assert_eq!(weight("あいう"), 6);
+    }
+
+    #[test]
+    fn test_pack() {
+        assert!(pack("header", &[]).is_empty());
+
+        let items = (0..40)
+            .map(|i| format!("item {:02}", i))
+            .collect::<Vec<_>>();
+        let posts = pack("header", &items);
+        assert_eq!(posts.len(), 2);
+        assert!(posts.iter().all(|post| weight(post) <= MAX_POST_WEIGHT));
+        assert!(posts.iter().all(|post| post.starts_with("header\n")));
+        assert_eq!(
+            posts
+                .iter()
+                .map(|post| post.lines().count() - 1)
+                .sum::<usize>(),
+            40
+        );
+
+        let posts = pack("header", &["a".repeat(300)]);
+        assert_eq!(posts.len(), 1);
+        assert_eq!(weight(&posts[0]), MAX_POST_WEIGHT);
+    }
+}
@@ -0,0 +1,74 @@
+mod memory;
+mod redis;
+
+pub use self::redis::RedisCache;
+pub use memory::MemoryCache;
+
+use crate::config::CacheConfig;
+use crate::error::Result;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The prefix of the cached `/atcoder-api/v2/user_info` responses, which contain the ranks.
+pub const USER_INFO_PREFIX: &str = "user_info:";
+/// The prefix of the last `/atcoder-api/v2/user_info` responses, which are not invalidated by the
+/// updaters and are served while the database is unavailable.
+pub const STALE_USER_INFO_PREFIX: &str = "stale_user_info:";
+
+/// The prefix of the cached `/atcoder-api/v3/problems/detailed` responses.
+pub const DETAILED_PROBLEMS_PREFIX: &str = "detailed_problems:";
+
+/// The prefix of the cached pages of `/atcoder-api/v3/rated_point_sum_ranking` and
+/// `/atcoder-api/v3/holder_ranking/:kind`.
+pub const RANKING_PREFIX: &str = "ranking:";
+
+/// The prefix of the cached `/atcoder-api/v3/problem_models` responses, which are invalidated by
+/// the jobs updating the problem models.
+pub const PROBLEM_MODELS_PREFIX: &str = "problem_models:";
+
+/// A key-value cache of serialized values with TTLs.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()>;
+
+    /// Removes all the entries whose keys start with `prefix`.
+    async fn invalidate(&self, prefix: &str) -> Result<()>;
+}
+
+/// Returns the Redis cache if `redis_url` is set, or the in-memory cache otherwise.
+pub fn connect(config: &CacheConfig) -> Result<Arc<dyn Cache>> {
+    match config.redis_url.as_deref() {
+        Some(url) => Ok(Arc::new(RedisCache::open(url)?)),
+        None => Ok(Arc::new(MemoryCache::default())),
+    }
+}
+
+/// Returns the cached value. A broken cache is treated as a miss, so that the caller can still
+/// fall back to the database.
+pub async fn get_json<T: DeserializeOwned>(cache: &dyn Cache, key: &str) -> Option<T> {
+    match cache.get(key).await {
+        Ok(value) => value.and_then(|value| serde_json::from_str(&value).ok()),
+        Err(e) => {
+            log::error!("Failed to get {} from the cache: {:?}", key, e);
+            None
+        }
+    }
+}
+
+pub async fn set_json<T: Serialize>(cache: &dyn Cache, key: &str, value: &T, ttl: Duration) {
+    let value = match serde_json::to_string(value) {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!("Failed to serialize {}: {:?}", key, e);
+            return;
+        }
+    };
+    if let Err(e) = cache.set(key, &value, ttl).await {
+        log::error!("Failed to set {} to the cache: {:?}", key, e);
+    }
+}
@@ -0,0 +1,76 @@
+use super::Cache;
+use crate::error::Result;
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The cache in memory of the process, used when Redis is not configured.
+#[derive(Default)]
+pub struct MemoryCache {
+    entries: Mutex<BTreeMap<String, (String, Instant)>>,
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut entries = self.entries.lock().expect("The cache is poisoned.");
+        match entries.get(key) {
+            Some((value, expires_at)) if Instant::now() < *expires_at => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        let expires_at = Instant::now() + ttl;
+        self.entries
+            .lock()
+            .expect("The cache is poisoned.")
+            .insert(key.to_owned(), (value.to_owned(), expires_at));
+        Ok(())
+    }
+
+    async fn invalidate(&self, prefix: &str) -> Result<()> {
+        self.entries
+            .lock()
+            .expect("The cache is poisoned.")
+            .retain(|key, _| !key.starts_with(prefix));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_memory_cache() {
+        let cache = MemoryCache::default();
+        block_on(async {
+            let ttl = Duration::from_secs(60);
+            cache.set("user_info:a", "1", ttl).await.unwrap();
+            cache.set("user_info:b", "2", ttl).await.unwrap();
+            cache.set("other", "3", ttl).await.unwrap();
+            cache
+                .set("expired", "4", Duration::from_secs(0))
+                .await
+                .unwrap();
+            assert_eq!(
+                cache.get("user_info:a").await.unwrap(),
+                Some("1".to_owned())
+            );
+            assert_eq!(cache.get("expired").await.unwrap(), None);
+
+            cache.invalidate("user_info:").await.unwrap();
+            assert_eq!(cache.get("user_info:a").await.unwrap(), None);
+            assert_eq!(cache.get("user_info:b").await.unwrap(), None);
+            assert_eq!(cache.get("other").await.unwrap(), Some("3".to_owned()));
+        });
+    }
+}
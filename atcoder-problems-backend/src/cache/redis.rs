@@ -0,0 +1,126 @@
+use super::Cache;
+use crate::error::Result;
+
+use async_std::future::timeout;
+use async_std::sync::Mutex;
+use async_trait::async_trait;
+use http_types::StatusCode;
+use redis::aio::Connection;
+use redis::{cmd, Client, Cmd, FromRedisValue};
+use std::time::Duration;
+
+const TIMEOUT_MILLIS: u64 = 1000;
+const SCAN_COUNT: usize = 1000;
+
+/// The cache shared among the servers in Redis. It keeps a connection, and reconnects after an
+/// error. Each command times out after `TIMEOUT_MILLIS`, so that a slow Redis does not
+/// slow down the requests more than the database would.
+pub struct RedisCache {
+    client: Client,
+    connection: Mutex<Option<Connection>>,
+}
+
+impl RedisCache {
+    /// Opens `redis://[:password@]host[:port][/db]`. It does not connect until the first command,
+    /// so that the servers start while Redis is down.
+    pub fn open(url: &str) -> Result<Self> {
+        Ok(Self {
+            client: Client::open(url)?,
+            connection: Mutex::new(None),
+        })
+    }
+
+    async fn query<T: FromRedisValue>(&self, command: &Cmd) -> Result<T> {
+        let mut connection = self.connection.lock().await;
+        let result = timeout(Duration::from_millis(TIMEOUT_MILLIS), async {
+            if connection.is_none() {
+                *connection = Some(self.client.get_async_std_connection().await?);
+            }
+            let connected = connection.as_mut().expect("connected above");
+            command.query_async::<_, T>(connected).await
+        })
+        .await;
+        match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => {
+                *connection = None;
+                Err(e.into())
+            }
+            Err(_) => {
+                *connection = None;
+                Err(http_types::Error::from_str(
+                    StatusCode::GatewayTimeout,
+                    "Redis timed out",
+                ))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        self.query(cmd("GET").arg(key)).await
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        let ttl = std::cmp::max(ttl.as_millis() as u64, 1);
+        self.query(cmd("SET").arg(key).arg(value).arg("PX").arg(ttl))
+            .await
+    }
+
+    async fn invalidate(&self, prefix: &str) -> Result<()> {
+        let pattern = format!("{}*", escape_pattern(prefix));
+        let mut cursor = 0u64;
+        loop {
+            let (next_cursor, keys): (u64, Vec<String>) = self
+                .query(
+                    cmd("SCAN")
+                        .cursor_arg(cursor)
+                        .arg("MATCH")
+                        .arg(&pattern)
+                        .arg("COUNT")
+                        .arg(SCAN_COUNT),
+                )
+                .await?;
+            if !keys.is_empty() {
+                self.query::<()>(cmd("DEL").arg(keys)).await?;
+            }
+
+            cursor = next_cursor;
+            if cursor == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Escapes the glob characters of `SCAN MATCH`.
+fn escape_pattern(prefix: &str) -> String {
+    let mut escaped = String::new();
+    for c in prefix.chars() {
+        if "*?[]\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open() {
+        assert!(RedisCache::open("redis://localhost").is_ok());
+        assert!(RedisCache::open("redis://:secret@cache.example.com:6380/2").is_ok());
+        assert!(RedisCache::open("http://localhost").is_err());
+        assert!(RedisCache::open("redis://localhost/db").is_err());
+    }
+
+    #[test]
+    fn test_escape_pattern() {
+        assert_eq!(escape_pattern("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+    }
+}
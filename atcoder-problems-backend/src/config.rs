@@ -0,0 +1,741 @@
+mod toml;
+
+use crate::error::Result;
+use crate::jobs::DEFAULT_PROBLEM_MODELS_URL;
+use crate::server::client_ip::IpNetwork;
+use crate::sql::DEFAULT_INSERT_CHUNK_SIZE;
+
+use serde::Deserialize;
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// The configuration shared by all the binaries. It is loaded from the TOML file at
+/// `CONFIG_PATH` (`config.toml` by default), and the environment variables override it.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub server: ServerConfig,
+    pub crawler: CrawlerConfig,
+    pub jobs: JobsConfig,
+    pub s3: S3Config,
+    pub bot: BotConfig,
+    pub research: ResearchConfig,
+    pub cache: CacheConfig,
+    pub storage: StorageConfig,
+    pub backup: BackupConfig,
+    pub query: QueryConfig,
+    pub email: EmailConfig,
+    pub submission_code: SubmissionCodeConfig,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// `SQL_URL`
+    pub url: String,
+    /// The maximum number of the rows inserted by a statement. The chunks are made smaller if
+    /// they would exceed the limit of the bind parameters of PostgreSQL.
+    pub insert_chunk_size: usize,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            insert_chunk_size: DEFAULT_INSERT_CHUNK_SIZE,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// `PORT`
+    pub port: u16,
+    /// `CORS_ORIGINS`, separated by commas. `*` allows any origin.
+    pub cors_origins: Vec<String>,
+    /// `TRUSTED_PROXIES`, separated by commas. `X-Forwarded-For` is trusted only from the proxies
+    /// in these CIDR ranges, e.g. `10.0.0.0/8`.
+    pub trusted_proxies: Vec<String>,
+    /// `CLIENT_ID`
+    pub github_client_id: String,
+    /// `CLIENT_SECRET`
+    pub github_client_secret: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 8080,
+            cors_origins: vec!["*".to_owned()],
+            trusted_proxies: Vec::new(),
+            github_client_id: String::new(),
+            github_client_secret: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrawlerConfig {
+    /// The interval between the requests for the pages of the submissions.
+    pub request_interval_millis: u64,
+}
+
+impl CrawlerConfig {
+    pub fn request_interval(&self) -> Duration {
+        Duration::from_millis(self.request_interval_millis)
+    }
+}
+
+impl Default for CrawlerConfig {
+    fn default() -> Self {
+        Self {
+            request_interval_millis: 200,
+        }
+    }
+}
+
+/// The intervals of the jobs of `run_jobs`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    pub recent_crawl_interval_second: i64,
+    pub new_contest_crawl_interval_second: i64,
+    pub problem_crawl_interval_second: i64,
+    pub delta_update_interval_second: i64,
+    pub slow_delta_update_interval_second: i64,
+    pub batch_update_interval_second: i64,
+    pub dump_interval_second: i64,
+    pub backup_interval_second: i64,
+    pub validation_interval_second: i64,
+    pub problem_model_interval_second: i64,
+    pub virtual_contest_archive_interval_second: i64,
+    pub purge_interval_second: i64,
+    pub cold_start_interval_second: i64,
+    pub submission_archive_interval_second: i64,
+    /// The age of the submissions moved into `submissions_archive`. The archived submissions are
+    /// dropped from the aggregates by the next batch update. 0 disables the archival.
+    pub submission_archive_after_years: i64,
+    /// `PROBLEM_MODELS_URL`
+    pub problem_models_url: String,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        const MINUTE: i64 = 60;
+        const HOUR: i64 = 60 * MINUTE;
+        Self {
+            recent_crawl_interval_second: 0,
+            new_contest_crawl_interval_second: 10 * MINUTE,
+            problem_crawl_interval_second: HOUR,
+            delta_update_interval_second: 5 * MINUTE,
+            slow_delta_update_interval_second: HOUR,
+            batch_update_interval_second: 24 * HOUR,
+            dump_interval_second: HOUR,
+            backup_interval_second: 24 * HOUR,
+            validation_interval_second: 24 * HOUR,
+            problem_model_interval_second: 24 * HOUR,
+            virtual_contest_archive_interval_second: 10 * MINUTE,
+            purge_interval_second: 24 * HOUR,
+            cold_start_interval_second: HOUR,
+            submission_archive_interval_second: 24 * HOUR,
+            submission_archive_after_years: 0,
+            problem_models_url: DEFAULT_PROBLEM_MODELS_URL.to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+    /// `S3_BUCKET`
+    pub bucket: String,
+    /// `S3_REGION`
+    pub region: String,
+    /// The URL where the objects in the bucket are served.
+    pub public_url: String,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            bucket: "kenkoooo.com".to_owned(),
+            region: "ap-northeast-1".to_owned(),
+            public_url: "https://kenkoooo.com/atcoder".to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    /// `TWITTER_ACCESS_TOKEN`
+    pub twitter_access_token: Option<String>,
+    /// `BOT_SHORTEST_HEADER`, `BOT_SHORTEST_ITEM`, `BOT_STREAK_HEADER` and `BOT_STREAK_ITEM`
+    pub shortest_header: Option<String>,
+    pub shortest_item: Option<String>,
+    pub streak_header: Option<String>,
+    pub streak_item: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResearchConfig {
+    /// `RESEARCH_DATASET_SALT`
+    pub dataset_salt: Option<String>,
+}
+
+/// The cache of the API responses. The cache is in memory of each server unless `redis_url` is
+/// set, where the replicas share the cache in Redis.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// `REDIS_URL`, e.g. `redis://localhost:6379/0`
+    pub redis_url: Option<String>,
+    pub ttl_second: u64,
+}
+
+impl CacheConfig {
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_second)
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            ttl_second: 300,
+        }
+    }
+}
+
+/// Where the dumps and the backups are stored: `s3` stores them in the bucket of `[s3]`, and
+/// `local` stores them as the files under `local_root`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// `STORAGE_BACKEND`
+    pub backend: String,
+    /// `STORAGE_LOCAL_ROOT`
+    pub local_root: String,
+    /// The URL where the files under `local_root` are served.
+    pub local_public_url: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: "s3".to_owned(),
+            local_root: "dumps".to_owned(),
+            local_public_url: "http://localhost:8080/dumps".to_owned(),
+        }
+    }
+}
+
+/// The database backups of `backend_cli backup`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    /// The number of the latest backups to keep. The older ones are deleted after a backup.
+    pub retention_count: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { retention_count: 7 }
+    }
+}
+
+/// The database access of the API handlers. A query is cancelled after the timeout, and the
+/// circuit breaker rejects the queries for `breaker_open_second` seconds after
+/// `breaker_failure_threshold` consecutive timeouts.
+///
+/// The rankings and the submissions endpoints handle at most `*_concurrency` requests at once.
+/// The other requests wait for `queue_timeout_millis`, and then they are rejected.
+///
+/// A client can send `anonymous_daily_quota` requests to the public API a day per address, or
+/// `token_daily_quota` requests a day per API token.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueryConfig {
+    pub timeout_millis: u64,
+    /// The timeout of the endpoints which list the submissions.
+    pub submissions_timeout_millis: u64,
+    pub breaker_failure_threshold: u32,
+    pub breaker_open_second: u64,
+    pub rankings_concurrency: usize,
+    pub submissions_concurrency: usize,
+    pub queue_timeout_millis: u64,
+    pub anonymous_daily_quota: i64,
+    pub token_daily_quota: i64,
+}
+
+impl QueryConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_millis)
+    }
+
+    pub fn submissions_timeout(&self) -> Duration {
+        Duration::from_millis(self.submissions_timeout_millis)
+    }
+
+    pub fn breaker_open_duration(&self) -> Duration {
+        Duration::from_secs(self.breaker_open_second)
+    }
+
+    pub fn queue_timeout(&self) -> Duration {
+        Duration::from_millis(self.queue_timeout_millis)
+    }
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            timeout_millis: 5_000,
+            submissions_timeout_millis: 20_000,
+            breaker_failure_threshold: 5,
+            breaker_open_second: 30,
+            rankings_concurrency: 4,
+            submissions_concurrency: 4,
+            queue_timeout_millis: 1_000,
+            anonymous_daily_quota: 20_000,
+            token_daily_quota: 200_000,
+        }
+    }
+}
+
+/// The email deliveries of the notifications through Amazon SES. They are disabled unless
+/// `from_address` is set.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailConfig {
+    /// `EMAIL_FROM_ADDRESS`
+    pub from_address: Option<String>,
+    /// `EMAIL_SES_REGION`
+    pub ses_region: String,
+    /// The URL of the API server, where the links to verify and to unsubscribe the addresses
+    /// point.
+    pub api_url: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            from_address: None,
+            ses_region: "ap-northeast-1".to_owned(),
+            api_url: "https://kenkoooo.com/atcoder".to_owned(),
+        }
+    }
+}
+
+/// The source codes of the submissions fetched from AtCoder on demand. The fetches are spaced by
+/// `fetch_interval_millis` among all the requests, and a request which would wait longer than
+/// `max_wait_millis` for its turn is rejected. The codes longer than `max_code_bytes` are not
+/// served.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmissionCodeConfig {
+    pub atcoder_url: String,
+    pub max_code_bytes: usize,
+    pub fetch_interval_millis: u64,
+    pub max_wait_millis: u64,
+    pub fetch_timeout_millis: u64,
+}
+
+impl SubmissionCodeConfig {
+    pub fn fetch_interval(&self) -> Duration {
+        Duration::from_millis(self.fetch_interval_millis)
+    }
+
+    pub fn max_wait(&self) -> Duration {
+        Duration::from_millis(self.max_wait_millis)
+    }
+
+    pub fn fetch_timeout(&self) -> Duration {
+        Duration::from_millis(self.fetch_timeout_millis)
+    }
+}
+
+impl Default for SubmissionCodeConfig {
+    fn default() -> Self {
+        Self {
+            atcoder_url: "https://atcoder.jp".to_owned(),
+            max_code_bytes: 512 * 1024,
+            fetch_interval_millis: 1_000,
+            max_wait_millis: 5_000,
+            fetch_timeout_millis: 10_000,
+        }
+    }
+}
+
+impl Config {
+    /// Loads and validates the configuration. Every problem is reported in the error at once.
+    pub fn load() -> Result<Self> {
+        let path = env::var("CONFIG_PATH").ok();
+        let text = match &path {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|e| {
+                invalid(&format!("Failed to read the configuration {}: {}", path, e))
+            })?),
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Some(std::fs::read_to_string(DEFAULT_CONFIG_PATH)?)
+            }
+            None => None,
+        };
+        let mut config = match text {
+            Some(text) => Self::from_toml(&text).map_err(|message| {
+                let path = path.as_deref().unwrap_or(DEFAULT_CONFIG_PATH);
+                invalid(&format!("Invalid configuration in {}: {}", path, message))
+            })?,
+            None => Self::default(),
+        };
+        config
+            .override_with(|key| env::var(key).ok())
+            .map_err(|message| invalid(&format!("Invalid environment variable {}", message)))?;
+        config.validate().map_err(|problems| {
+            invalid(&format!(
+                "Invalid configuration:\n{}",
+                problems
+                    .iter()
+                    .map(|problem| format!("  - {}", problem))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))
+        })?;
+        Ok(config)
+    }
+
+    pub fn from_toml(text: &str) -> std::result::Result<Self, String> {
+        let value = toml::parse(text)?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    /// Overrides the values with the environment variables given by `var`.
+    pub fn override_with<F>(&mut self, var: F) -> std::result::Result<(), String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let set = |target: &mut String, key: &str| {
+            if let Some(value) = var(key) {
+                *target = value;
+            }
+        };
+        set(&mut self.database.url, "SQL_URL");
+        set(&mut self.server.github_client_id, "CLIENT_ID");
+        set(&mut self.server.github_client_secret, "CLIENT_SECRET");
+        set(&mut self.jobs.problem_models_url, "PROBLEM_MODELS_URL");
+        set(&mut self.s3.bucket, "S3_BUCKET");
+        set(&mut self.s3.region, "S3_REGION");
+        set(&mut self.storage.backend, "STORAGE_BACKEND");
+        set(&mut self.storage.local_root, "STORAGE_LOCAL_ROOT");
+        set(&mut self.email.ses_region, "EMAIL_SES_REGION");
+        if let Some(port) = var("PORT") {
+            self.server.port = port
+                .parse()
+                .map_err(|_| format!("PORT: `{}` is not a port number", port))?;
+        }
+        let split = |values: String| {
+            values
+                .split(',')
+                .map(|value| value.trim().to_owned())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+        };
+        if let Some(origins) = var("CORS_ORIGINS") {
+            self.server.cors_origins = split(origins);
+        }
+        if let Some(proxies) = var("TRUSTED_PROXIES") {
+            self.server.trusted_proxies = split(proxies);
+        }
+
+        let set_optional = |target: &mut Option<String>, key: &str| {
+            if let Some(value) = var(key) {
+                *target = Some(value);
+            }
+        };
+        set_optional(&mut self.bot.twitter_access_token, "TWITTER_ACCESS_TOKEN");
+        set_optional(&mut self.bot.shortest_header, "BOT_SHORTEST_HEADER");
+        set_optional(&mut self.bot.shortest_item, "BOT_SHORTEST_ITEM");
+        set_optional(&mut self.bot.streak_header, "BOT_STREAK_HEADER");
+        set_optional(&mut self.bot.streak_item, "BOT_STREAK_ITEM");
+        set_optional(&mut self.research.dataset_salt, "RESEARCH_DATASET_SALT");
+        set_optional(&mut self.cache.redis_url, "REDIS_URL");
+        set_optional(&mut self.email.from_address, "EMAIL_FROM_ADDRESS");
+        Ok(())
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = vec![];
+        if self.database.url.is_empty() {
+            problems.push("database.url must be set, or SQL_URL must be set.".to_owned());
+        } else if !self.database.url.starts_with("postgres://")
+            && !self.database.url.starts_with("postgresql://")
+        {
+            problems.push("database.url must start with postgres:// or postgresql://.".to_owned());
+        }
+        if self.database.insert_chunk_size == 0 {
+            problems.push("database.insert_chunk_size must be at least 1.".to_owned());
+        }
+
+        if self.server.port == 0 {
+            problems.push("server.port must not be 0.".to_owned());
+        }
+        for origin in self.server.cors_origins.iter() {
+            if origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://") {
+                problems.push(format!(
+                    "server.cors_origins: `{}` must be `*` or start with http:// or https://.",
+                    origin
+                ));
+            }
+        }
+        for proxy in self.server.trusted_proxies.iter() {
+            if IpNetwork::parse(proxy).is_none() {
+                problems.push(format!(
+                    "server.trusted_proxies: `{}` must be an address or a CIDR range.",
+                    proxy
+                ));
+            }
+        }
+        if self.server.github_client_id.is_empty() != self.server.github_client_secret.is_empty() {
+            problems.push(
+                "server.github_client_id and server.github_client_secret must be set together."
+                    .to_owned(),
+            );
+        }
+
+        let intervals = [
+            ("recent_crawl", self.jobs.recent_crawl_interval_second),
+            (
+                "new_contest_crawl",
+                self.jobs.new_contest_crawl_interval_second,
+            ),
+            ("problem_crawl", self.jobs.problem_crawl_interval_second),
+            ("delta_update", self.jobs.delta_update_interval_second),
+            (
+                "slow_delta_update",
+                self.jobs.slow_delta_update_interval_second,
+            ),
+            ("batch_update", self.jobs.batch_update_interval_second),
+            ("dump", self.jobs.dump_interval_second),
+            ("backup", self.jobs.backup_interval_second),
+            ("validation", self.jobs.validation_interval_second),
+            ("problem_model", self.jobs.problem_model_interval_second),
+            (
+                "virtual_contest_archive",
+                self.jobs.virtual_contest_archive_interval_second,
+            ),
+            ("purge", self.jobs.purge_interval_second),
+            ("cold_start", self.jobs.cold_start_interval_second),
+            (
+                "submission_archive",
+                self.jobs.submission_archive_interval_second,
+            ),
+        ];
+        for (name, interval) in intervals.iter() {
+            if *interval < 0 {
+                problems.push(format!(
+                    "jobs.{}_interval_second must not be negative.",
+                    name
+                ));
+            }
+        }
+        if self.jobs.submission_archive_after_years < 0 {
+            problems.push("jobs.submission_archive_after_years must not be negative.".to_owned());
+        }
+        if !self.jobs.problem_models_url.starts_with("http://")
+            && !self.jobs.problem_models_url.starts_with("https://")
+        {
+            problems.push("jobs.problem_models_url must be an HTTP(S) URL.".to_owned());
+        }
+
+        if self.s3.bucket.is_empty() {
+            problems.push("s3.bucket must not be empty.".to_owned());
+        }
+        if self.s3.region.is_empty() {
+            problems.push("s3.region must not be empty.".to_owned());
+        }
+        if self.research.dataset_salt.as_deref() == Some("") {
+            problems.push("research.dataset_salt must not be empty.".to_owned());
+        }
+        if let Some(redis_url) = self.cache.redis_url.as_deref() {
+            if let Err(e) = redis::Client::open(redis_url) {
+                problems.push(format!("cache.redis_url: {}", e));
+            }
+        }
+        match self.storage.backend.as_str() {
+            "s3" => {}
+            "local" => {
+                if self.storage.local_root.is_empty() {
+                    problems.push("storage.local_root must not be empty.".to_owned());
+                }
+            }
+            backend => problems.push(format!(
+                "storage.backend: `{}` must be `s3` or `local`.",
+                backend
+            )),
+        }
+        if self.backup.retention_count == 0 {
+            problems.push("backup.retention_count must be at least 1.".to_owned());
+        }
+        if self.query.timeout_millis == 0 || self.query.submissions_timeout_millis == 0 {
+            problems.push(
+                "query.timeout_millis and query.submissions_timeout_millis must not be 0."
+                    .to_owned(),
+            );
+        }
+        if self.query.breaker_failure_threshold == 0 {
+            problems.push("query.breaker_failure_threshold must be at least 1.".to_owned());
+        }
+        if self.query.rankings_concurrency == 0 || self.query.submissions_concurrency == 0 {
+            problems.push(
+                "query.rankings_concurrency and query.submissions_concurrency must be at least 1."
+                    .to_owned(),
+            );
+        }
+        if self.query.anonymous_daily_quota <= 0 || self.query.token_daily_quota <= 0 {
+            problems.push(
+                "query.anonymous_daily_quota and query.token_daily_quota must be at least 1."
+                    .to_owned(),
+            );
+        }
+        if self.email.from_address.is_some() {
+            if self.email.ses_region.is_empty() {
+                problems.push("email.ses_region must not be empty.".to_owned());
+            }
+            if !self.email.api_url.starts_with("https://") {
+                problems.push("email.api_url must be an HTTPS URL.".to_owned());
+            }
+        }
+        if !self.submission_code.atcoder_url.starts_with("http://")
+            && !self.submission_code.atcoder_url.starts_with("https://")
+        {
+            problems.push("submission_code.atcoder_url must be an HTTP(S) URL.".to_owned());
+        }
+        if self.submission_code.max_code_bytes == 0 {
+            problems.push("submission_code.max_code_bytes must be at least 1.".to_owned());
+        }
+        if self.submission_code.fetch_timeout_millis == 0 {
+            problems.push("submission_code.fetch_timeout_millis must not be 0.".to_owned());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+fn invalid(message: &str) -> http_types::Error {
+    http_types::Error::from_str(
+        http_types::StatusCode::InternalServerError,
+        message.to_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let config = Config::from_toml(
+            r#"
+            [database]
+            url = "postgresql://localhost/atcoder"
+
+            [server]
+            cors_origins = ["https://kenkoooo.com"]
+
+            [jobs]
+            dump_interval_second = 600
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.database.url, "postgresql://localhost/atcoder");
+        assert_eq!(config.server.port, 8080);
+        assert_eq!(config.server.cors_origins, vec!["https://kenkoooo.com"]);
+        assert_eq!(config.jobs.dump_interval_second, 600);
+        assert_eq!(config.jobs.batch_update_interval_second, 24 * 3600);
+        assert!(config.validate().is_ok());
+
+        let error = Config::from_toml("[server]\nprot = 8080").unwrap_err();
+        assert!(error.starts_with("unknown field `prot`"), "{}", error);
+    }
+
+    #[test]
+    fn test_override_with() {
+        let mut config = Config::default();
+        config
+            .override_with(|key| match key {
+                "SQL_URL" => Some("postgres://db".to_owned()),
+                "CORS_ORIGINS" => Some("https://a.com, https://b.com".to_owned()),
+                "TRUSTED_PROXIES" => Some("10.0.0.0/8,".to_owned()),
+                "PORT" => Some("3000".to_owned()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(config.database.url, "postgres://db");
+        assert_eq!(
+            config.server.cors_origins,
+            vec!["https://a.com", "https://b.com"]
+        );
+        assert_eq!(config.server.port, 3000);
+        assert_eq!(config.server.trusted_proxies, vec!["10.0.0.0/8"]);
+
+        assert!(config
+            .override_with(|key| if key == "PORT" {
+                Some("http".to_owned())
+            } else {
+                None
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = Config::default();
+        config.server.cors_origins = vec!["kenkoooo.com".to_owned()];
+        config.server.trusted_proxies = vec!["10.0.0.0/33".to_owned()];
+        config.server.github_client_id = "id".to_owned();
+        config.jobs.dump_interval_second = -1;
+        config.storage.backend = "gcs".to_owned();
+        assert_eq!(
+            config.validate().unwrap_err(),
+            vec![
+                "database.url must be set, or SQL_URL must be set.",
+                "server.cors_origins: `kenkoooo.com` must be `*` or start with http:// or https://.",
+                "server.trusted_proxies: `10.0.0.0/33` must be an address or a CIDR range.",
+                "server.github_client_id and server.github_client_secret must be set together.",
+                "jobs.dump_interval_second must not be negative.",
+                "storage.backend: `gcs` must be `s3` or `local`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        let config = Config::from_toml(include_str!("../config.example.toml")).unwrap();
+        let defaults = Config::default();
+        assert_eq!(
+            config.database.insert_chunk_size,
+            defaults.database.insert_chunk_size
+        );
+        assert_eq!(config.server, defaults.server);
+        assert_eq!(config.crawler, defaults.crawler);
+        assert_eq!(config.jobs, defaults.jobs);
+        assert_eq!(config.s3, defaults.s3);
+        assert_eq!(config.cache, defaults.cache);
+        assert_eq!(config.storage, defaults.storage);
+        assert_eq!(config.backup, defaults.backup);
+        assert_eq!(config.query, defaults.query);
+        assert_eq!(config.email, defaults.email);
+        assert_eq!(config.submission_code, defaults.submission_code);
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+use serde_json::{Map, Number, Value};
+
+/// Parses the subset of TOML used by the configuration files: `[table]` headers, `key = value`
+/// pairs, comments, and strings, integers, floats, booleans and arrays of them as values.
+pub(crate) fn parse(text: &str) -> std::result::Result<Value, String> {
+    let mut root = Map::new();
+    let mut table: Option<String> = None;
+    let mut lines = text.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let line_number = i + 1;
+        let mut line = strip_comment(line).trim().to_owned();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            if !line.ends_with(']') || line.starts_with("[[") {
+                return Err(format!("line {}: invalid table header", line_number));
+            }
+            let name = line[1..(line.len() - 1)].trim();
+            if !is_bare_key(name) {
+                return Err(format!(
+                    "line {}: invalid table name `{}`",
+                    line_number, name
+                ));
+            }
+            if root.contains_key(name) {
+                return Err(format!("line {}: duplicated table `{}`", line_number, name));
+            }
+            root.insert(name.to_owned(), Value::Object(Map::new()));
+            table = Some(name.to_owned());
+            continue;
+        }
+
+        // An array may span multiple lines.
+        while !is_balanced(&line) {
+            match lines.next() {
+                Some((_, next)) => {
+                    line.push(' ');
+                    line.push_str(strip_comment(next).trim());
+                }
+                None => return Err(format!("line {}: unclosed array", line_number)),
+            }
+        }
+
+        let (key, value) = match line.find('=') {
+            Some(position) => (line[..position].trim(), line[(position + 1)..].trim()),
+            None => return Err(format!("line {}: expected `key = value`", line_number)),
+        };
+        if !is_bare_key(key) {
+            return Err(format!("line {}: invalid key `{}`", line_number, key));
+        }
+        let value =
+            parse_value(value).map_err(|message| format!("line {}: {}", line_number, message))?;
+        let entries = match &table {
+            Some(name) => root
+                .get_mut(name)
+                .and_then(Value::as_object_mut)
+                .expect("The table is inserted with its header."),
+            None => &mut root,
+        };
+        if entries.insert(key.to_owned(), value).is_some() {
+            return Err(format!("line {}: duplicated key `{}`", line_number, key));
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Removes the comment which is not in a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+fn is_balanced(line: &str) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            _ => {}
+        }
+        escaped = false;
+    }
+    depth <= 0
+}
+
+fn parse_value(value: &str) -> std::result::Result<Value, String> {
+    let (parsed, rest) = parse_prefix(value)?;
+    if rest.trim().is_empty() {
+        Ok(parsed)
+    } else {
+        Err(format!("unexpected `{}` after the value", rest.trim()))
+    }
+}
+
+/// Parses the value at the beginning, and returns it with the rest.
+fn parse_prefix(value: &str) -> std::result::Result<(Value, &str), String> {
+    let value = value.trim_start();
+    if value.starts_with('"') {
+        parse_basic_string(value)
+    } else if let Some(literal) = value.strip_prefix('\'') {
+        match literal.find('\'') {
+            Some(end) => Ok((
+                Value::String(literal[..end].to_owned()),
+                &literal[(end + 1)..],
+            )),
+            None => Err("unclosed string".to_owned()),
+        }
+    } else if value.starts_with('[') {
+        parse_array(value)
+    } else {
+        let end = value
+            .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+            .unwrap_or(value.len());
+        let (token, rest) = value.split_at(end);
+        Ok((parse_scalar(token)?, rest))
+    }
+}
+
+fn parse_basic_string(value: &str) -> std::result::Result<(Value, &str), String> {
+    let mut parsed = String::new();
+    let mut chars = value.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Value::String(parsed), &value[(i + 1)..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => parsed.push('\n'),
+                Some((_, 't')) => parsed.push('\t'),
+                Some((_, '"')) => parsed.push('"'),
+                Some((_, '\\')) => parsed.push('\\'),
+                Some((_, c)) => return Err(format!("unsupported escape `\\{}`", c)),
+                None => break,
+            },
+            c => parsed.push(c),
+        }
+    }
+    Err("unclosed string".to_owned())
+}
+
+fn parse_array(value: &str) -> std::result::Result<(Value, &str), String> {
+    let mut items = Vec::new();
+    let mut rest = value[1..].trim_start();
+    loop {
+        if let Some(rest) = rest.strip_prefix(']') {
+            return Ok((Value::Array(items), rest));
+        }
+        if rest.is_empty() {
+            return Err("unclosed array".to_owned());
+        }
+        let (item, next) = parse_prefix(rest)?;
+        items.push(item);
+        rest = next.trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if !rest.starts_with(']') {
+            return Err("expected `,` or `]` in the array".to_owned());
+        }
+    }
+}
+
+fn parse_scalar(token: &str) -> std::result::Result<Value, String> {
+    match token {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    let number = token.replace('_', "");
+    if let Ok(integer) = number.parse::<i64>() {
+        return Ok(Value::Number(integer.into()));
+    }
+    number
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .ok_or_else(|| format!("invalid value `{}`", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let text = r#"
+            # comment
+            name = "a \"quoted\" # value" # comment
+            [server]
+            port = 8_080
+            ratio = 0.5
+            enabled = true
+            origins = [
+                "https://a.example.com", # comment
+                'https://b.example.com',
+            ]
+            empty = []
+        "#;
+        assert_eq!(
+            parse(text).unwrap(),
+            json!({
+                "name": "a \"quoted\" # value",
+                "server": {
+                    "port": 8080,
+                    "ratio": 0.5,
+                    "enabled": true,
+                    "origins": ["https://a.example.com", "https://b.example.com"],
+                    "empty": [],
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse("a = 1\na = 2").unwrap_err(),
+            "line 2: duplicated key `a`"
+        );
+        assert_eq!(
+            parse("[a\nb = 1").unwrap_err(),
+            "line 1: invalid table header"
+        );
+        assert_eq!(parse("a = \"b").unwrap_err(), "line 1: unclosed string");
+        assert_eq!(parse("a = [1, 2").unwrap_err(), "line 1: unclosed array");
+        assert_eq!(parse("a = yes").unwrap_err(), "line 1: invalid value `yes`");
+        assert_eq!(parse("a").unwrap_err(), "line 1: expected `key = value`");
+    }
+}
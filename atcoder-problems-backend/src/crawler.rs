@@ -1,21 +1,44 @@
+mod aoj_crawler;
+mod codeforces_crawler;
 mod fix_crawler;
 mod problem_crawler;
 mod recent_crawler;
+mod standings_crawler;
+mod user_verification_crawler;
 pub(crate) mod utils;
 mod virtual_contest_crawler;
 mod whole_contest_crawler;
+mod yukicoder_crawler;
 
+pub use aoj_crawler::{AojCrawler, AojFetcher};
+pub use codeforces_crawler::{CodeforcesCrawler, CodeforcesFetcher};
 pub use fix_crawler::FixCrawler;
 pub use problem_crawler::ProblemCrawler;
 pub use recent_crawler::RecentCrawler;
+pub use standings_crawler::{StandingsCrawler, StandingsFetcher};
+pub use user_verification_crawler::{AtCoderProfileFetcher, UserVerificationCrawler};
 pub use virtual_contest_crawler::VirtualContestCrawler;
 pub use whole_contest_crawler::WholeContestCrawler;
+pub use yukicoder_crawler::{YukicoderCrawler, YukicoderFetcher};
 
-use crate::error::Result;
-use crate::sql::models::{Contest, ContestProblem, Problem, Submission};
+use crate::error::{Error, Result};
+use crate::sql::models::{Contest, ContestProblem, Point, Problem, Submission};
 use algorithm_problem_client::{AtCoderClient, AtCoderProblem, AtCoderSubmission};
 use async_trait::async_trait;
 use log::info;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static REQUEST_INTERVAL_MILLIS: AtomicU64 = AtomicU64::new(200);
+
+/// Sets the interval between the requests for the pages of the submissions.
+pub fn set_request_interval(interval: Duration) {
+    REQUEST_INTERVAL_MILLIS.store(interval.as_millis() as u64, Ordering::SeqCst);
+}
+
+pub(crate) fn request_interval() -> Duration {
+    Duration::from_millis(REQUEST_INTERVAL_MILLIS.load(Ordering::SeqCst))
+}
 
 #[async_trait]
 pub trait AtCoderFetcher {
@@ -39,7 +62,7 @@ impl AtCoderFetcher for AtCoderClient {
                 contest_id: s.contest_id,
                 user_id: s.user_id,
                 language: s.language,
-                point: s.point,
+                point: Point::from(s.point),
                 length: s.length as i32,
                 result: s.result,
                 execution_time: s.execution_time.map(|t| t as i32),
@@ -49,7 +72,10 @@ impl AtCoderFetcher for AtCoderClient {
 
     async fn fetch_contests(&self, page: u32) -> Result<Vec<Contest>> {
         info!("Fetching contests page-{}", page);
-        let contests = self.fetch_atcoder_contests(page).await?;
+        let contests = self
+            .fetch_atcoder_contests(page)
+            .await
+            .map_err(Error::upstream)?;
         let contests = contests
             .into_iter()
             .map(|c| Contest {
@@ -68,7 +94,10 @@ impl AtCoderFetcher for AtCoderClient {
         contest_id: &str,
     ) -> Result<(Vec<Problem>, Vec<ContestProblem>)> {
         info!("Fetching problems from {} ...", contest_id);
-        let problems = self.fetch_problem_list(contest_id).await?;
+        let problems = self
+            .fetch_problem_list(contest_id)
+            .await
+            .map_err(Error::upstream)?;
         let problems = problems
             .into_iter()
             .map(convert_problem)
@@ -0,0 +1,285 @@
+use crate::error::{Error, Result};
+use crate::sql::models::{JudgeProblem, JudgeSubmission};
+use crate::sql::{Judge, JudgeClient};
+use algorithm_problem_client::AojClient;
+use async_trait::async_trait;
+use log::info;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://judgeapi.u-aizu.ac.jp";
+const PAGE_SIZE: u32 = 1000;
+
+#[async_trait]
+pub trait AojFetcher {
+    async fn fetch_aoj_problems(&self, page: u32, size: u32) -> Result<Vec<JudgeProblem>>;
+    async fn fetch_aoj_recent_submissions(&self) -> Result<Vec<JudgeSubmission>>;
+
+    /// Returns the accepted submissions of the user.
+    async fn fetch_aoj_user_solutions(
+        &self,
+        user_id: &str,
+        page: u32,
+        size: u32,
+    ) -> Result<Vec<JudgeSubmission>>;
+}
+
+/// The fields of the responses of `AojClient` are private, so the API is called directly.
+#[async_trait]
+impl AojFetcher for AojClient {
+    async fn fetch_aoj_problems(&self, page: u32, size: u32) -> Result<Vec<JudgeProblem>> {
+        let url = format!("{}/problems?page={}&size={}", BASE_URL, page, size);
+        let problems: Vec<ApiProblem> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(problems.into_iter().map(ApiProblem::convert).collect())
+    }
+
+    async fn fetch_aoj_recent_submissions(&self) -> Result<Vec<JudgeSubmission>> {
+        let url = format!("{}/submission_records/recent", BASE_URL);
+        let records: Vec<ApiSubmissionRecord> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(records
+            .into_iter()
+            .map(ApiSubmissionRecord::convert)
+            .collect())
+    }
+
+    async fn fetch_aoj_user_solutions(
+        &self,
+        user_id: &str,
+        page: u32,
+        size: u32,
+    ) -> Result<Vec<JudgeSubmission>> {
+        let url = format!(
+            "{}/solutions/users/{}?page={}&size={}",
+            BASE_URL, user_id, page, size
+        );
+        let solutions: Vec<ApiSolution> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(solutions.into_iter().map(ApiSolution::convert).collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiProblem {
+    id: String,
+    name: String,
+}
+
+impl ApiProblem {
+    fn convert(self) -> JudgeProblem {
+        JudgeProblem {
+            judge: Judge::Aoj.as_str().to_owned(),
+            id: self.id,
+            title: self.name,
+            difficulty: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiSubmissionRecord {
+    judge_id: i64,
+    user_id: String,
+    problem_id: String,
+    language: String,
+    status: i32,
+    submission_date: i64,
+}
+
+impl ApiSubmissionRecord {
+    fn convert(self) -> JudgeSubmission {
+        JudgeSubmission {
+            judge: Judge::Aoj.as_str().to_owned(),
+            id: self.judge_id,
+            epoch_second: self.submission_date / 1000,
+            problem_id: self.problem_id,
+            user_id: self.user_id,
+            language: self.language,
+            result: convert_status(self.status),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiSolution {
+    judge_id: i64,
+    user_id: String,
+    problem_id: String,
+    language: String,
+    submission_date: i64,
+}
+
+impl ApiSolution {
+    fn convert(self) -> JudgeSubmission {
+        JudgeSubmission {
+            judge: Judge::Aoj.as_str().to_owned(),
+            id: self.judge_id,
+            epoch_second: self.submission_date / 1000,
+            problem_id: self.problem_id,
+            user_id: self.user_id,
+            language: self.language,
+            result: "AC".to_owned(),
+        }
+    }
+}
+
+/// Converts the status code of AOJ into the AtCoder notation.
+fn convert_status(status: i32) -> String {
+    match status {
+        0 => "CE",
+        1 => "WA",
+        2 => "TLE",
+        3 => "MLE",
+        4 => "AC",
+        6 => "OLE",
+        7 => "RE",
+        8 => "PE",
+        _ => "WJ",
+    }
+    .to_owned()
+}
+
+pub struct AojCrawler<C, F> {
+    db: C,
+    fetcher: F,
+}
+
+impl<C, F> AojCrawler<C, F>
+where
+    C: JudgeClient,
+    F: AojFetcher,
+{
+    pub fn new(db: C, fetcher: F) -> Self {
+        Self { db, fetcher }
+    }
+
+    pub async fn crawl_problems(&self) -> Result<()> {
+        for page in 0.. {
+            info!("Fetching AOJ problems page-{} ...", page);
+            let problems = self.fetcher.fetch_aoj_problems(page, PAGE_SIZE).await?;
+            self.db.update_judge_problems(&problems)?;
+            if problems.len() < PAGE_SIZE as usize {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn crawl_recent_submissions(&self) -> Result<()> {
+        info!("Fetching recent AOJ submissions ...");
+        let submissions = self.fetcher.fetch_aoj_recent_submissions().await?;
+        info!("Inserting {} submissions ...", submissions.len());
+        self.db.update_judge_submissions(&submissions)?;
+        Ok(())
+    }
+
+    pub async fn crawl_user_submissions(&self, user_id: &str) -> Result<()> {
+        for page in 0.. {
+            info!("Fetching solutions of {} page-{} ...", user_id, page);
+            let submissions = self
+                .fetcher
+                .fetch_aoj_user_solutions(user_id, page, PAGE_SIZE)
+                .await?;
+            self.db.update_judge_submissions(&submissions)?;
+            if submissions.len() < PAGE_SIZE as usize {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_convert_submission_record() {
+        let response = r#"[
+            {
+                "judgeId": 4000000, "judgeType": 2, "userId": "kenkoooo",
+                "problemId": "ITP1_1_A", "submissionDate": 1577836800123,
+                "language": "C++14", "status": 4, "cpuTime": 0, "memory": 3000
+            },
+            {
+                "judgeId": 4000001, "judgeType": 2, "userId": "kenkoooo",
+                "problemId": "ITP1_1_B", "submissionDate": 1577836900000,
+                "language": "C++14", "status": 1, "cpuTime": 0, "memory": 3000
+            }
+        ]"#;
+        let records: Vec<ApiSubmissionRecord> = serde_json::from_str(response).unwrap();
+        let submissions = records
+            .into_iter()
+            .map(ApiSubmissionRecord::convert)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            submissions[0],
+            JudgeSubmission {
+                judge: "aoj".to_owned(),
+                id: 4000000,
+                epoch_second: 1577836800,
+                problem_id: "ITP1_1_A".to_owned(),
+                user_id: "kenkoooo".to_owned(),
+                language: "C++14".to_owned(),
+                result: "AC".to_owned(),
+            }
+        );
+        assert_eq!(submissions[1].result, "WA");
+    }
+
+    struct MockFetcher;
+    #[async_trait]
+    impl AojFetcher for MockFetcher {
+        async fn fetch_aoj_problems(&self, _: u32, _: u32) -> Result<Vec<JudgeProblem>> {
+            unimplemented!()
+        }
+        async fn fetch_aoj_recent_submissions(&self) -> Result<Vec<JudgeSubmission>> {
+            unimplemented!()
+        }
+        async fn fetch_aoj_user_solutions(
+            &self,
+            user_id: &str,
+            page: u32,
+            size: u32,
+        ) -> Result<Vec<JudgeSubmission>> {
+            let count = if page == 0 { size } else { 1 };
+            Ok((0..count)
+                .map(|id| JudgeSubmission {
+                    id: (page * size + id) as i64,
+                    user_id: user_id.to_owned(),
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockDB(RefCell<Vec<JudgeSubmission>>);
+    impl JudgeClient for MockDB {
+        fn update_judge_submissions(&self, submissions: &[JudgeSubmission]) -> Result<usize> {
+            self.0.borrow_mut().extend_from_slice(submissions);
+            Ok(submissions.len())
+        }
+        fn update_judge_problems(&self, _: &[JudgeProblem]) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_judge_accepted_submissions(
+            &self,
+            _: Judge,
+            _: &str,
+        ) -> Result<Vec<JudgeSubmission>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_crawl_user_submissions() {
+        let crawler = AojCrawler::new(MockDB::default(), MockFetcher);
+        block_on(crawler.crawl_user_submissions("kenkoooo")).unwrap();
+        assert_eq!(crawler.db.0.borrow().len(), PAGE_SIZE as usize + 1);
+    }
+}
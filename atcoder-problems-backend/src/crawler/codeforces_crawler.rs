@@ -0,0 +1,309 @@
+use crate::error::{Error, Result};
+use crate::sql::models::{JudgeProblem, JudgeSubmission};
+use crate::sql::{Judge, JudgeClient};
+use algorithm_problem_client::CodeforcesClient;
+use async_trait::async_trait;
+use log::info;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://codeforces.com/api";
+const RECENT_SUBMISSION_COUNT: u32 = 1000;
+
+#[async_trait]
+pub trait CodeforcesFetcher {
+    async fn fetch_codeforces_problems(&self) -> Result<Vec<JudgeProblem>>;
+    async fn fetch_codeforces_recent_submissions(&self, count: u32)
+        -> Result<Vec<JudgeSubmission>>;
+    async fn fetch_codeforces_user_submissions(&self, handle: &str)
+        -> Result<Vec<JudgeSubmission>>;
+}
+
+/// `CodeforcesClient` does not return the submission time, so the API is called directly.
+#[async_trait]
+impl CodeforcesFetcher for CodeforcesClient {
+    async fn fetch_codeforces_problems(&self) -> Result<Vec<JudgeProblem>> {
+        let url = format!("{}/problemset.problems", BASE_URL);
+        let response: ApiResponse<ProblemsResult> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(response
+            .result
+            .problems
+            .into_iter()
+            .filter_map(ApiProblem::convert)
+            .collect())
+    }
+
+    async fn fetch_codeforces_recent_submissions(
+        &self,
+        count: u32,
+    ) -> Result<Vec<JudgeSubmission>> {
+        let url = format!("{}/problemset.recentStatus?count={}", BASE_URL, count);
+        let response: ApiResponse<Vec<ApiSubmission>> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(convert_submissions(response.result))
+    }
+
+    async fn fetch_codeforces_user_submissions(
+        &self,
+        handle: &str,
+    ) -> Result<Vec<JudgeSubmission>> {
+        let url = format!("{}/user.status?handle={}", BASE_URL, handle);
+        let response: ApiResponse<Vec<ApiSubmission>> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(convert_submissions(response.result))
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct ProblemsResult {
+    problems: Vec<ApiProblem>,
+}
+
+#[derive(Deserialize)]
+struct ApiProblem {
+    #[serde(rename = "contestId")]
+    contest_id: Option<u32>,
+    index: String,
+    name: String,
+    rating: Option<u32>,
+}
+
+impl ApiProblem {
+    fn problem_id(&self) -> Option<String> {
+        self.contest_id
+            .map(|contest_id| format!("{}{}", contest_id, self.index))
+    }
+
+    fn convert(self) -> Option<JudgeProblem> {
+        Some(JudgeProblem {
+            judge: Judge::Codeforces.as_str().to_owned(),
+            id: self.problem_id()?,
+            title: format!("{}. {}", self.index, self.name),
+            difficulty: self.rating.map(f64::from),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiSubmission {
+    id: i64,
+    #[serde(rename = "creationTimeSeconds")]
+    creation_time_seconds: i64,
+    problem: ApiProblem,
+    author: ApiAuthor,
+    #[serde(rename = "programmingLanguage")]
+    programming_language: String,
+    verdict: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiAuthor {
+    members: Vec<ApiMember>,
+}
+
+#[derive(Deserialize)]
+struct ApiMember {
+    handle: String,
+}
+
+fn convert_submissions(submissions: Vec<ApiSubmission>) -> Vec<JudgeSubmission> {
+    submissions
+        .into_iter()
+        .filter_map(|s| {
+            Some(JudgeSubmission {
+                judge: Judge::Codeforces.as_str().to_owned(),
+                id: s.id,
+                epoch_second: s.creation_time_seconds,
+                problem_id: s.problem.problem_id()?,
+                user_id: s.author.members.into_iter().next()?.handle,
+                language: s.programming_language,
+                result: convert_verdict(s.verdict.as_deref()),
+            })
+        })
+        .collect()
+}
+
+/// Converts the verdict into the AtCoder notation so that the aggregations can be shared.
+fn convert_verdict(verdict: Option<&str>) -> String {
+    match verdict {
+        Some("OK") => "AC",
+        Some("WRONG_ANSWER") => "WA",
+        Some("TIME_LIMIT_EXCEEDED") | Some("IDLENESS_LIMIT_EXCEEDED") => "TLE",
+        Some("MEMORY_LIMIT_EXCEEDED") => "MLE",
+        Some("RUNTIME_ERROR") => "RE",
+        Some("COMPILATION_ERROR") => "CE",
+        Some(verdict) => verdict,
+        None => "WJ",
+    }
+    .to_owned()
+}
+
+pub struct CodeforcesCrawler<C, F> {
+    db: C,
+    fetcher: F,
+}
+
+impl<C, F> CodeforcesCrawler<C, F>
+where
+    C: JudgeClient,
+    F: CodeforcesFetcher,
+{
+    pub fn new(db: C, fetcher: F) -> Self {
+        Self { db, fetcher }
+    }
+
+    pub async fn crawl_problems(&self) -> Result<()> {
+        info!("Fetching Codeforces problems ...");
+        let problems = self.fetcher.fetch_codeforces_problems().await?;
+        info!("Inserting {} problems ...", problems.len());
+        self.db.update_judge_problems(&problems)?;
+        Ok(())
+    }
+
+    pub async fn crawl_recent_submissions(&self) -> Result<()> {
+        info!("Fetching recent Codeforces submissions ...");
+        let submissions = self
+            .fetcher
+            .fetch_codeforces_recent_submissions(RECENT_SUBMISSION_COUNT)
+            .await?;
+        info!("Inserting {} submissions ...", submissions.len());
+        self.db.update_judge_submissions(&submissions)?;
+        Ok(())
+    }
+
+    pub async fn crawl_user_submissions(&self, handle: &str) -> Result<()> {
+        info!("Fetching submissions of {} ...", handle);
+        let submissions = self
+            .fetcher
+            .fetch_codeforces_user_submissions(handle)
+            .await?;
+        info!("Inserting {} submissions ...", submissions.len());
+        self.db.update_judge_submissions(&submissions)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_convert_submissions() {
+        let response = r#"{
+            "status": "OK",
+            "result": [
+                {
+                    "id": 1, "contestId": 1000, "creationTimeSeconds": 100,
+                    "problem": {"contestId": 1000, "index": "A", "name": "Problem"},
+                    "author": {"members": [{"handle": "tourist"}]},
+                    "programmingLanguage": "GNU C++17", "verdict": "OK"
+                },
+                {
+                    "id": 2, "creationTimeSeconds": 200,
+                    "problem": {"contestId": 1000, "index": "B1", "name": "Problem"},
+                    "author": {"members": [{"handle": "tourist"}]},
+                    "programmingLanguage": "GNU C++17"
+                },
+                {
+                    "id": 3, "creationTimeSeconds": 300,
+                    "problem": {"problemsetName": "acmsguru", "index": "100", "name": "A+B"},
+                    "author": {"members": [{"handle": "tourist"}]},
+                    "programmingLanguage": "GNU C++17", "verdict": "OK"
+                }
+            ]
+        }"#;
+        let response: ApiResponse<Vec<ApiSubmission>> = serde_json::from_str(response).unwrap();
+        let submissions = convert_submissions(response.result);
+        assert_eq!(
+            submissions,
+            vec![
+                JudgeSubmission {
+                    judge: "codeforces".to_owned(),
+                    id: 1,
+                    epoch_second: 100,
+                    problem_id: "1000A".to_owned(),
+                    user_id: "tourist".to_owned(),
+                    language: "GNU C++17".to_owned(),
+                    result: "AC".to_owned(),
+                },
+                JudgeSubmission {
+                    judge: "codeforces".to_owned(),
+                    id: 2,
+                    epoch_second: 200,
+                    problem_id: "1000B1".to_owned(),
+                    user_id: "tourist".to_owned(),
+                    language: "GNU C++17".to_owned(),
+                    result: "WJ".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_verdict() {
+        assert_eq!(convert_verdict(Some("OK")), "AC");
+        assert_eq!(convert_verdict(Some("WRONG_ANSWER")), "WA");
+        assert_eq!(convert_verdict(Some("CHALLENGED")), "CHALLENGED");
+        assert_eq!(convert_verdict(None), "WJ");
+    }
+
+    struct MockFetcher;
+    #[async_trait]
+    impl CodeforcesFetcher for MockFetcher {
+        async fn fetch_codeforces_problems(&self) -> Result<Vec<JudgeProblem>> {
+            unimplemented!()
+        }
+        async fn fetch_codeforces_recent_submissions(
+            &self,
+            count: u32,
+        ) -> Result<Vec<JudgeSubmission>> {
+            assert_eq!(count, RECENT_SUBMISSION_COUNT);
+            Ok(vec![JudgeSubmission::default()])
+        }
+        async fn fetch_codeforces_user_submissions(
+            &self,
+            handle: &str,
+        ) -> Result<Vec<JudgeSubmission>> {
+            Ok(vec![JudgeSubmission {
+                user_id: handle.to_owned(),
+                ..Default::default()
+            }])
+        }
+    }
+
+    #[derive(Default)]
+    struct MockDB(RefCell<Vec<JudgeSubmission>>);
+    impl JudgeClient for MockDB {
+        fn update_judge_submissions(&self, submissions: &[JudgeSubmission]) -> Result<usize> {
+            self.0.borrow_mut().extend_from_slice(submissions);
+            Ok(submissions.len())
+        }
+        fn update_judge_problems(&self, _: &[JudgeProblem]) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_judge_accepted_submissions(
+            &self,
+            _: Judge,
+            _: &str,
+        ) -> Result<Vec<JudgeSubmission>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_crawl_submissions() {
+        let crawler = CodeforcesCrawler::new(MockDB::default(), MockFetcher);
+        block_on(crawler.crawl_recent_submissions()).unwrap();
+        block_on(crawler.crawl_user_submissions("tourist")).unwrap();
+        let stored = crawler.db.0.borrow();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[1].user_id, "tourist");
+    }
+}
@@ -107,6 +107,9 @@ mod tests {
         fn update_delta_submission_count(&self, _: &[Submission]) -> Result<()> {
             unimplemented!()
         }
+        fn load_submission_changes(&self, _: i64, _: i64) -> Result<Vec<(Submission, i64)>> {
+            unimplemented!()
+        }
     }
 
     use super::*;
@@ -2,8 +2,9 @@ use crate::crawler::AtCoderFetcher;
 use crate::error::Result;
 use crate::sql::models::{Contest, ContestProblem, Problem};
 use crate::sql::{ContestProblemClient, SimpleClient};
+use async_std::task;
 use std::collections::BTreeSet;
-use std::{thread, time};
+use std::time::Duration;
 
 pub struct ProblemCrawler<C, F> {
     db: C,
@@ -34,7 +35,7 @@ where
                     break;
                 }
             }
-            thread::sleep(time::Duration::from_millis(500));
+            task::sleep(Duration::from_millis(500)).await;
         }
 
         log::info!("There are {} contests.", contests.len());
@@ -58,7 +59,7 @@ where
                     log::error!("{:?}", e);
                 }
             }
-            thread::sleep(time::Duration::from_millis(500));
+            task::sleep(Duration::from_millis(500)).await;
         }
 
         Ok(())
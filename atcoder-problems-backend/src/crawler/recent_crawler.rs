@@ -1,9 +1,10 @@
-use crate::crawler::AtCoderFetcher;
+use crate::crawler::{request_interval, AtCoderFetcher};
 use crate::error::Result;
+use crate::shutdown;
 use crate::sql::{SimpleClient, SubmissionClient};
 
+use async_std::task;
 use log::info;
-use std::{thread, time};
 
 pub struct RecentCrawler<C, F> {
     db: C,
@@ -24,6 +25,10 @@ where
         let contests = self.db.load_contests()?;
         for contest in contests.into_iter() {
             for page in 1.. {
+                if shutdown::is_requested() {
+                    info!("Stopped before {}-{}", contest.id, page);
+                    return Ok(());
+                }
                 info!("Crawling {}-{} ...", contest.id, page);
                 let submissions = self.fetcher.fetch_submissions(&contest.id, page).await;
                 if submissions.is_empty() {
@@ -34,7 +39,7 @@ where
                 let min_id = submissions.iter().map(|s| s.id).min().unwrap();
                 let exists = self.db.count_stored_submissions(&[min_id])? != 0;
                 self.db.update_submissions(&submissions)?;
-                thread::sleep(time::Duration::from_millis(200));
+                task::sleep(request_interval()).await;
 
                 if exists {
                     info!("Finished crawling {}", contest.id);
@@ -52,7 +57,7 @@ where
 mod tests {
     use super::*;
     use crate::crawler::utils::MockFetcher;
-    use crate::sql::models::{Contest, Problem, Submission};
+    use crate::sql::models::{Contest, ContestRatedRange, Problem, Submission};
     use crate::sql::SubmissionRequest;
     use async_std::task::block_on;
 
@@ -104,6 +109,9 @@ mod tests {
             fn update_delta_submission_count(&self, _: &[Submission]) -> Result<()> {
                 unimplemented!()
             }
+            fn load_submission_changes(&self, _: i64, _: i64) -> Result<Vec<(Submission, i64)>> {
+                unimplemented!()
+            }
         }
         impl SimpleClient for MockDB {
             fn insert_contests(&self, _: &[Contest]) -> Result<usize> {
@@ -121,6 +129,9 @@ mod tests {
                     ..Default::default()
                 }])
             }
+            fn load_contest_rated_ranges(&self) -> Result<Vec<ContestRatedRange>> {
+                unimplemented!()
+            }
         }
 
         let crawler = RecentCrawler::new(MockDB, fetcher);
@@ -0,0 +1,240 @@
+use crate::crawler::request_interval;
+use crate::error::{Error, Result};
+use crate::sql::models::Standing;
+use crate::sql::StandingsClient;
+use algorithm_problem_client::AtCoderClient;
+use async_std::task;
+use async_trait::async_trait;
+use log::info;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const BASE_URL: &str = "https://atcoder.jp/contests";
+
+/// The scores in the standings are multiplied by 100 to avoid the decimals.
+const SCORE_SCALE: f64 = 100.0;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+#[async_trait]
+pub trait StandingsFetcher {
+    async fn fetch_standings(&self, contest_id: &str) -> Result<Vec<Standing>>;
+}
+
+/// `AtCoderClient` does not support the standings, so the JSON is fetched directly.
+#[async_trait]
+impl StandingsFetcher for AtCoderClient {
+    async fn fetch_standings(&self, contest_id: &str) -> Result<Vec<Standing>> {
+        let url = format!("{}/{}/standings/json", BASE_URL, contest_id);
+        let response: StandingsResponse =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(convert_standings(contest_id, response))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StandingsResponse {
+    standings_data: Vec<StandingsData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StandingsData {
+    rank: i32,
+    user_screen_name: String,
+    is_rated: bool,
+    task_results: BTreeMap<String, TaskResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TaskResult {
+    score: i64,
+    penalty: i32,
+    elapsed: i64,
+}
+
+fn convert_standings(contest_id: &str, response: StandingsResponse) -> Vec<Standing> {
+    response
+        .standings_data
+        .into_iter()
+        .flat_map(|data| {
+            let rank = data.rank;
+            let is_rated = data.is_rated;
+            let user_id = data.user_screen_name;
+            data.task_results
+                .into_iter()
+                .map(move |(problem_id, result)| Standing {
+                    contest_id: contest_id.to_owned(),
+                    user_id: user_id.clone(),
+                    problem_id,
+                    rank,
+                    is_rated,
+                    score: result.score as f64 / SCORE_SCALE,
+                    penalty: result.penalty,
+                    elapsed_second: if result.score > 0 {
+                        Some(result.elapsed / NANOS_PER_SECOND)
+                    } else {
+                        None
+                    },
+                })
+        })
+        .collect()
+}
+
+pub struct StandingsCrawler<C, F> {
+    db: C,
+    fetcher: F,
+}
+
+impl<C, F> StandingsCrawler<C, F>
+where
+    C: StandingsClient,
+    F: StandingsFetcher,
+{
+    pub fn new(db: C, fetcher: F) -> Self {
+        Self { db, fetcher }
+    }
+
+    pub async fn crawl(&self, contest_id: &str) -> Result<()> {
+        info!("Fetching the standings of {} ...", contest_id);
+        let standings = self.fetcher.fetch_standings(contest_id).await?;
+        info!("Inserting {} results ...", standings.len());
+        self.db.update_standings(&standings)?;
+        Ok(())
+    }
+
+    /// Crawls the standings of at most `limit` ended contests which have no standings yet.
+    /// A failure of a contest is logged and does not stop the others.
+    pub async fn crawl_missing(&self, now: i64, limit: i64) -> Result<()> {
+        let contest_ids = self.db.get_contests_without_standings(now, limit)?;
+        info!(
+            "There are {} contests without standings.",
+            contest_ids.len()
+        );
+        for contest_id in contest_ids.iter() {
+            if let Err(e) = self.crawl(contest_id).await {
+                log::error!("Failed to crawl the standings of {}: {:?}", contest_id, e);
+            }
+            task::sleep(request_interval()).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_convert_standings() {
+        let response = r#"{
+            "Fixed": true,
+            "TaskInfo": [
+                {"Assignment": "A", "TaskName": "A", "TaskScreenName": "abc001_a"},
+                {"Assignment": "B", "TaskName": "B", "TaskScreenName": "abc001_b"}
+            ],
+            "StandingsData": [
+                {
+                    "Rank": 1, "UserScreenName": "user1", "IsRated": true,
+                    "TaskResults": {
+                        "abc001_a": {"Count": 1, "Failure": 0, "Penalty": 0, "Score": 10000, "Elapsed": 60000000000, "Status": 1},
+                        "abc001_b": {"Count": 3, "Failure": 2, "Penalty": 2, "Score": 20050, "Elapsed": 300500000000, "Status": 1}
+                    }
+                },
+                {
+                    "Rank": 2, "UserScreenName": "user2", "IsRated": false,
+                    "TaskResults": {
+                        "abc001_a": {"Count": 2, "Failure": 2, "Penalty": 2, "Score": 0, "Elapsed": 0, "Status": 6}
+                    }
+                }
+            ]
+        }"#;
+        let response: StandingsResponse = serde_json::from_str(response).unwrap();
+        let standings = convert_standings("abc001", response);
+        assert_eq!(
+            standings,
+            vec![
+                Standing {
+                    contest_id: "abc001".to_owned(),
+                    user_id: "user1".to_owned(),
+                    problem_id: "abc001_a".to_owned(),
+                    rank: 1,
+                    is_rated: true,
+                    score: 100.0,
+                    penalty: 0,
+                    elapsed_second: Some(60),
+                },
+                Standing {
+                    contest_id: "abc001".to_owned(),
+                    user_id: "user1".to_owned(),
+                    problem_id: "abc001_b".to_owned(),
+                    rank: 1,
+                    is_rated: true,
+                    score: 200.5,
+                    penalty: 2,
+                    elapsed_second: Some(300),
+                },
+                Standing {
+                    contest_id: "abc001".to_owned(),
+                    user_id: "user2".to_owned(),
+                    problem_id: "abc001_a".to_owned(),
+                    rank: 2,
+                    is_rated: false,
+                    score: 0.0,
+                    penalty: 2,
+                    elapsed_second: None,
+                },
+            ]
+        );
+    }
+
+    struct MockFetcher;
+    #[async_trait]
+    impl StandingsFetcher for MockFetcher {
+        async fn fetch_standings(&self, contest_id: &str) -> Result<Vec<Standing>> {
+            match contest_id {
+                "broken" => Err(http_types::Error::from_str(
+                    http_types::StatusCode::NotFound,
+                    "not found",
+                )),
+                _ => Ok(vec![Standing {
+                    contest_id: contest_id.to_owned(),
+                    ..Default::default()
+                }]),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct MockDB(RefCell<Vec<Standing>>);
+    impl StandingsClient for MockDB {
+        fn update_standings(&self, standings: &[Standing]) -> Result<usize> {
+            self.0.borrow_mut().extend_from_slice(standings);
+            Ok(standings.len())
+        }
+        fn load_standings(&self, _: &str) -> Result<Vec<Standing>> {
+            unimplemented!()
+        }
+        fn get_contests_without_standings(&self, _: i64, limit: i64) -> Result<Vec<String>> {
+            assert_eq!(limit, 10);
+            Ok(vec![
+                "abc001".to_owned(),
+                "broken".to_owned(),
+                "abc002".to_owned(),
+            ])
+        }
+    }
+
+    #[test]
+    fn test_crawl_missing() {
+        let crawler = StandingsCrawler::new(MockDB::default(), MockFetcher);
+        block_on(crawler.crawl_missing(0, 10)).unwrap();
+        let stored = crawler.db.0.borrow();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].contest_id, "abc001");
+        assert_eq!(stored[1].contest_id, "abc002");
+    }
+}
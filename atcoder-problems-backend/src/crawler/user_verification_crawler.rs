@@ -0,0 +1,151 @@
+use crate::error::{Error, Result};
+use crate::sql::internal::user_verification_manager::UserVerificationManager;
+use algorithm_problem_client::AtCoderClient;
+use async_trait::async_trait;
+use chrono::Utc;
+use regex::Regex;
+
+/// Verifications expire an hour after they are started.
+const VERIFICATION_EXPIRE_SECOND: i64 = 3600;
+
+#[async_trait]
+pub trait AtCoderProfileFetcher {
+    /// Returns the affiliation field of the AtCoder user profile.
+    async fn fetch_affiliation(&self, user_id: &str) -> Result<Option<String>>;
+}
+
+#[async_trait]
+impl AtCoderProfileFetcher for AtCoderClient {
+    async fn fetch_affiliation(&self, user_id: &str) -> Result<Option<String>> {
+        let url = format!("https://atcoder.jp/users/{}?lang=en", user_id);
+        let html = surf::get(url)
+            .recv_string()
+            .await
+            .map_err(Error::upstream)?;
+        Ok(scrape_affiliation(&html))
+    }
+}
+
+fn scrape_affiliation(html: &str) -> Option<String> {
+    let re =
+        Regex::new(r"<th[^>]*>\s*(?:Affiliation|所属)\s*</th>\s*<td[^>]*>([^<]*)</td>").unwrap();
+    re.captures(html)
+        .map(|captures| captures[1].trim().to_owned())
+}
+
+pub struct UserVerificationCrawler<C, F> {
+    db: C,
+    fetcher: F,
+}
+
+impl<C, F> UserVerificationCrawler<C, F>
+where
+    C: UserVerificationManager,
+    F: AtCoderProfileFetcher,
+{
+    pub fn new(db: C, fetcher: F) -> Self {
+        Self { db, fetcher }
+    }
+
+    pub async fn crawl(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let verifications = self
+            .db
+            .get_pending_verifications(now - VERIFICATION_EXPIRE_SECOND)?;
+        log::info!("Checking {} verifications ...", verifications.len());
+        for verification in verifications.into_iter() {
+            let affiliation = match self
+                .fetcher
+                .fetch_affiliation(&verification.atcoder_user_id)
+                .await
+            {
+                Ok(affiliation) => affiliation,
+                Err(e) => {
+                    log::error!(
+                        "Error when fetching {}: {:?}",
+                        verification.atcoder_user_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let verified = affiliation
+                .map(|affiliation| affiliation.contains(&verification.verification_code))
+                .unwrap_or(false);
+            if verified {
+                log::info!("Verified {}", verification.atcoder_user_id);
+                self.db.complete_verification(
+                    &verification.internal_user_id,
+                    &verification.atcoder_user_id,
+                    now,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::internal::user_verification_manager::PendingVerification;
+    use async_std::task::block_on;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_scrape_affiliation() {
+        let html = r#"
+            <table class="dl-table">
+                <tr><th class="no-break">Country/Region</th><td>Japan</td></tr>
+                <tr><th class="no-break">Affiliation</th><td class="break-all"> verify-code </td></tr>
+            </table>"#;
+        assert_eq!(scrape_affiliation(html), Some("verify-code".to_owned()));
+        assert_eq!(scrape_affiliation("<table></table>"), None);
+    }
+
+    struct MockDB(RefCell<Vec<String>>);
+    impl UserVerificationManager for MockDB {
+        fn start_verification(&self, _: &str, _: &str, _: i64) -> Result<String> {
+            unimplemented!()
+        }
+        fn get_pending_verifications(&self, _: i64) -> Result<Vec<PendingVerification>> {
+            Ok(vec![
+                PendingVerification {
+                    internal_user_id: "1".to_owned(),
+                    atcoder_user_id: "user1".to_owned(),
+                    verification_code: "code1".to_owned(),
+                },
+                PendingVerification {
+                    internal_user_id: "2".to_owned(),
+                    atcoder_user_id: "user2".to_owned(),
+                    verification_code: "code2".to_owned(),
+                },
+            ])
+        }
+        fn complete_verification(&self, internal_user_id: &str, _: &str, _: i64) -> Result<()> {
+            self.0.borrow_mut().push(internal_user_id.to_owned());
+            Ok(())
+        }
+        fn get_verified_atcoder_user_id(&self, _: &str) -> Result<Option<String>> {
+            unimplemented!()
+        }
+    }
+
+    struct MockFetcher;
+    #[async_trait]
+    impl AtCoderProfileFetcher for MockFetcher {
+        async fn fetch_affiliation(&self, user_id: &str) -> Result<Option<String>> {
+            match user_id {
+                "user1" => Ok(Some("my code1".to_owned())),
+                _ => Ok(Some("other".to_owned())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_verification_crawler() {
+        let crawler = UserVerificationCrawler::new(MockDB(RefCell::new(Vec::new())), MockFetcher);
+        block_on(crawler.crawl()).unwrap();
+        assert_eq!(crawler.db.0.into_inner(), vec!["1".to_owned()]);
+    }
+}
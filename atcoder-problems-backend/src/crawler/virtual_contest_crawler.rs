@@ -1,10 +1,10 @@
-use crate::crawler::AtCoderFetcher;
+use crate::crawler::{request_interval, AtCoderFetcher};
 use crate::error::Result;
 use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
 use crate::sql::{ContestProblemClient, SubmissionClient};
+use async_std::task;
 use chrono::Utc;
 use std::collections::BTreeSet;
-use std::{thread, time};
 
 const CRAWLED_STREAK: usize = 3;
 
@@ -60,7 +60,7 @@ where
                 if streak >= CRAWLED_STREAK {
                     break;
                 }
-                thread::sleep(time::Duration::from_millis(200));
+                task::sleep(request_interval()).await;
             }
             log::info!("Finished {}", contest);
         }
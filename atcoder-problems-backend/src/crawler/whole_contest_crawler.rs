@@ -1,9 +1,10 @@
-use crate::crawler::AtCoderFetcher;
+use crate::crawler::{request_interval, AtCoderFetcher};
 use crate::error::Result;
+use crate::shutdown;
 use crate::sql::SubmissionClient;
 
+use async_std::task;
 use log::info;
-use std::{thread, time};
 
 pub struct WholeContestCrawler<C, F> {
     db: C,
@@ -26,6 +27,11 @@ where
 
     pub async fn crawl(&self) -> Result<()> {
         for page in 1.. {
+            // Every page fetched so far has been stored, so the crawl can stop here.
+            if shutdown::is_requested() {
+                info!("Stopped before {} {}", self.contest_id, page);
+                return Ok(());
+            }
             info!("Crawling {} {} ...", self.contest_id, page);
             let submissions = self.fetcher.fetch_submissions(&self.contest_id, page).await;
             if submissions.is_empty() {
@@ -34,7 +40,7 @@ where
             }
 
             self.db.update_submissions(&submissions)?;
-            thread::sleep(time::Duration::from_millis(200));
+            task::sleep(request_interval()).await;
         }
 
         info!("Finished");
@@ -75,6 +81,9 @@ mod tests {
         fn update_delta_submission_count(&self, _: &[Submission]) -> Result<()> {
             unimplemented!()
         }
+        fn load_submission_changes(&self, _: i64, _: i64) -> Result<Vec<(Submission, i64)>> {
+            unimplemented!()
+        }
     }
     #[test]
     fn whole_contest_crawler() {
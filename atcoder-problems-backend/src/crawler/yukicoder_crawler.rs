@@ -0,0 +1,210 @@
+use crate::error::{Error, Result};
+use crate::sql::models::{JudgeProblem, JudgeSubmission};
+use crate::sql::{Judge, JudgeClient};
+use algorithm_problem_client::YukicoderClient;
+use async_trait::async_trait;
+use log::info;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://yukicoder.me/api/v1";
+
+#[async_trait]
+pub trait YukicoderFetcher {
+    async fn fetch_yukicoder_problems(&self) -> Result<Vec<JudgeProblem>>;
+
+    /// Returns the numeric id of the user.
+    async fn fetch_yukicoder_user_id(&self, user_name: &str) -> Result<i64>;
+
+    /// Returns the ids of the problems solved by the user.
+    async fn fetch_yukicoder_solved_problems(&self, user_name: &str) -> Result<Vec<i64>>;
+}
+
+/// The fields of the responses of `YukicoderClient` are private, so the API is called directly.
+#[async_trait]
+impl YukicoderFetcher for YukicoderClient {
+    async fn fetch_yukicoder_problems(&self) -> Result<Vec<JudgeProblem>> {
+        let url = format!("{}/problems", BASE_URL);
+        let problems: Vec<ApiProblem> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(problems.into_iter().map(ApiProblem::convert).collect())
+    }
+
+    async fn fetch_yukicoder_user_id(&self, user_name: &str) -> Result<i64> {
+        let url = format!("{}/user/name/{}", BASE_URL, user_name);
+        let user: ApiUser = surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(user.id)
+    }
+
+    async fn fetch_yukicoder_solved_problems(&self, user_name: &str) -> Result<Vec<i64>> {
+        let url = format!("{}/solved/name/{}", BASE_URL, user_name);
+        let problems: Vec<ApiProblem> =
+            surf::get(url).recv_json().await.map_err(Error::upstream)?;
+        Ok(problems.into_iter().map(|p| p.problem_id).collect())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiProblem {
+    no: Option<i64>,
+    problem_id: i64,
+    title: String,
+    level: Option<f64>,
+}
+
+impl ApiProblem {
+    fn convert(self) -> JudgeProblem {
+        let title = match self.no {
+            Some(no) => format!("No.{} {}", no, self.title),
+            None => self.title,
+        };
+        JudgeProblem {
+            judge: Judge::Yukicoder.as_str().to_owned(),
+            id: self.problem_id.to_string(),
+            title,
+            difficulty: self.level.map(level_to_difficulty),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiUser {
+    id: i64,
+}
+
+/// Maps the level (0.5 to 6 stars) onto the AtCoder difficulty scale, where a star is worth
+/// 400 points and level 1 lands on 200.
+fn level_to_difficulty(level: f64) -> f64 {
+    (level - 0.5) * 400.0
+}
+
+pub struct YukicoderCrawler<C, F> {
+    db: C,
+    fetcher: F,
+}
+
+impl<C, F> YukicoderCrawler<C, F>
+where
+    C: JudgeClient,
+    F: YukicoderFetcher,
+{
+    pub fn new(db: C, fetcher: F) -> Self {
+        Self { db, fetcher }
+    }
+
+    pub async fn crawl_problems(&self) -> Result<()> {
+        info!("Fetching yukicoder problems ...");
+        let problems = self.fetcher.fetch_yukicoder_problems().await?;
+        info!("Inserting {} problems ...", problems.len());
+        self.db.update_judge_problems(&problems)?;
+        Ok(())
+    }
+
+    /// yukicoder does not publish the submissions, so each solved problem is stored as an
+    /// accepted submission at the time it is found first. The submission id combines the ids of
+    /// the user and the problem to be unique.
+    pub async fn crawl_user_submissions(&self, user_name: &str, now: i64) -> Result<()> {
+        info!("Fetching solved problems of {} ...", user_name);
+        let user_id = self.fetcher.fetch_yukicoder_user_id(user_name).await?;
+        let submissions = self
+            .fetcher
+            .fetch_yukicoder_solved_problems(user_name)
+            .await?
+            .into_iter()
+            .map(|problem_id| JudgeSubmission {
+                judge: Judge::Yukicoder.as_str().to_owned(),
+                id: (user_id << 32) | problem_id,
+                epoch_second: now,
+                problem_id: problem_id.to_string(),
+                user_id: user_name.to_owned(),
+                language: String::new(),
+                result: "AC".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        info!("Inserting {} submissions ...", submissions.len());
+        self.db.update_judge_submissions(&submissions)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task::block_on;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_convert_problem() {
+        let response = r#"[
+            {"No": 1, "ProblemId": 10, "Title": "道のショートカット", "Level": 3.5, "ProblemType": 0},
+            {"No": null, "ProblemId": 11, "Title": "unpublished", "Level": 1, "ProblemType": 0}
+        ]"#;
+        let problems: Vec<ApiProblem> = serde_json::from_str(response).unwrap();
+        let problems = problems
+            .into_iter()
+            .map(ApiProblem::convert)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                JudgeProblem {
+                    judge: "yukicoder".to_owned(),
+                    id: "10".to_owned(),
+                    title: "No.1 道のショートカット".to_owned(),
+                    difficulty: Some(1200.0),
+                },
+                JudgeProblem {
+                    judge: "yukicoder".to_owned(),
+                    id: "11".to_owned(),
+                    title: "unpublished".to_owned(),
+                    difficulty: Some(200.0),
+                },
+            ]
+        );
+    }
+
+    struct MockFetcher;
+    #[async_trait]
+    impl YukicoderFetcher for MockFetcher {
+        async fn fetch_yukicoder_problems(&self) -> Result<Vec<JudgeProblem>> {
+            unimplemented!()
+        }
+        async fn fetch_yukicoder_user_id(&self, _: &str) -> Result<i64> {
+            Ok(3)
+        }
+        async fn fetch_yukicoder_solved_problems(&self, _: &str) -> Result<Vec<i64>> {
+            Ok(vec![10, 11])
+        }
+    }
+
+    #[derive(Default)]
+    struct MockDB(RefCell<Vec<JudgeSubmission>>);
+    impl JudgeClient for MockDB {
+        fn update_judge_submissions(&self, submissions: &[JudgeSubmission]) -> Result<usize> {
+            self.0.borrow_mut().extend_from_slice(submissions);
+            Ok(submissions.len())
+        }
+        fn update_judge_problems(&self, _: &[JudgeProblem]) -> Result<usize> {
+            unimplemented!()
+        }
+        fn get_judge_accepted_submissions(
+            &self,
+            _: Judge,
+            _: &str,
+        ) -> Result<Vec<JudgeSubmission>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_crawl_user_submissions() {
+        let crawler = YukicoderCrawler::new(MockDB::default(), MockFetcher);
+        block_on(crawler.crawl_user_submissions("kenkoooo", 100)).unwrap();
+        let stored = crawler.db.0.borrow();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].id, (3 << 32) | 10);
+        assert_eq!(stored[1].problem_id, "11");
+        assert_eq!(stored[1].epoch_second, 100);
+    }
+}
@@ -1,19 +1,120 @@
-use serde::export::Formatter;
+use http_types::StatusCode;
+use std::fmt::Formatter;
 
 /// Since http_types::Error is a wrapper of anyhow::Error, but it can not be constructed from anyhow::Error,
 /// let's us http_types::Error as anyhow::Error.
 pub type Result<T> = std::result::Result<T, http_types::Error>;
 
+/// The errors reported to the clients. They are carried in `http_types::Error` as the other
+/// errors are, and the server turns them into the JSON bodies with the proper status codes.
 #[derive(Debug)]
-pub(crate) enum ErrorTypes {
-    InvalidRequest,
-    CookieNotFound,
+pub enum Error {
+    /// The request conflicts with the current state of the resource.
+    Conflict(String),
+    /// A query to the database failed. The message is logged, but not exposed to the clients.
+    Database(String),
+    /// The resource does not exist, or it is not visible to the user.
+    NotFound,
+    /// The user is not authenticated, or not allowed to do the operation.
+    Unauthorized,
+    /// Fetching the data from AtCoder or the other upstream services failed.
+    UpstreamCrawl(String),
+    /// The request violates a constraint.
+    Validation(String),
 }
 
-impl std::error::Error for ErrorTypes {}
+impl Error {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::Conflict(_) => StatusCode::Conflict,
+            Error::Database(_) => StatusCode::InternalServerError,
+            Error::NotFound => StatusCode::NotFound,
+            Error::Unauthorized => StatusCode::Unauthorized,
+            Error::UpstreamCrawl(_) => StatusCode::BadGateway,
+            Error::Validation(_) => StatusCode::BadRequest,
+        }
+    }
+
+    /// The identifier of the kind of the error in the JSON bodies.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Conflict(_) => "conflict",
+            Error::Database(_) => "database",
+            Error::NotFound => "not_found",
+            Error::Unauthorized => "unauthorized",
+            Error::UpstreamCrawl(_) => "upstream_crawl",
+            Error::Validation(_) => "validation",
+        }
+    }
+
+    /// The message shown to the clients.
+    pub fn message(&self) -> String {
+        match self {
+            Error::Database(_) => "The database is not available.".to_owned(),
+            error => error.to_string(),
+        }
+    }
+
+    /// Wraps a failure of a request to an upstream service.
+    pub fn upstream<E: std::fmt::Display>(error: E) -> http_types::Error {
+        Error::UpstreamCrawl(error.to_string()).into_http_error()
+    }
+
+    /// Converts into `http_types::Error` with the status code of the kind. `?` also converts it,
+    /// but with the status code 500.
+    pub fn into_http_error(self) -> http_types::Error {
+        let status = self.status();
+        http_types::Error::new(status, self)
+    }
+}
+
+impl std::error::Error for Error {}
 
-impl std::fmt::Display for ErrorTypes {
+impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::Conflict(message) => write!(f, "{}", message),
+            Error::Database(message) => write!(f, "The database failed: {}", message),
+            Error::NotFound => write!(f, "The resource is not found."),
+            Error::Unauthorized => write!(f, "The user is not authorized."),
+            Error::UpstreamCrawl(message) => write!(f, "The upstream failed: {}", message),
+            Error::Validation(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Classifies an error which is not an `Error` by its source, e.g. a failure of a query.
+pub(crate) fn classify(error: &http_types::Error) -> Option<Error> {
+    if let Some(error) = error.downcast_ref::<diesel::result::Error>() {
+        return Some(match error {
+            diesel::result::Error::NotFound => Error::NotFound,
+            error => Error::Database(error.to_string()),
+        });
+    }
+    if let Some(error) = error.downcast_ref::<diesel::r2d2::PoolError>() {
+        return Some(Error::Database(error.to_string()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let error = http_types::Error::from(diesel::result::Error::NotFound);
+        assert_eq!(classify(&error).unwrap().status(), StatusCode::NotFound);
+
+        let error = http_types::Error::from(diesel::result::Error::RollbackTransaction);
+        let error = classify(&error).unwrap();
+        assert_eq!(error.kind(), "database");
+        assert_eq!(error.message(), "The database is not available.");
+
+        let error = http_types::Error::from_str(StatusCode::BadRequest, "bad");
+        assert!(classify(&error).is_none());
+
+        let error = Error::Validation("too many lists".to_owned()).into_http_error();
+        assert_eq!(error.status(), StatusCode::BadRequest);
     }
 }
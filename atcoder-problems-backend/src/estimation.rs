@@ -0,0 +1,200 @@
+//! Estimates the performances and the ratings from the results of the problems, with the same
+//! model as the difficulties of the problems: a user of the rating `r` solves a problem of the
+//! difficulty `d` with the probability `1 / (1 + 6^((d - r) / 400))`.
+
+const MIN_PERFORMANCE: f64 = 0.0;
+const MAX_PERFORMANCE: f64 = 5000.0;
+const MIN_DIFFICULTY: f64 = -2000.0;
+const MAX_DIFFICULTY: f64 = 5000.0;
+const BISECTION_ITERATIONS: usize = 50;
+
+/// The weight of a new performance in the rating of a user who has taken many contests.
+const RATING_WEIGHT: f64 = 0.1;
+
+/// The correction subtracted from the performance of the first contest.
+const FIRST_CONTEST_CORRECTION: f64 = 1200.0;
+
+fn solve_probability(performance: f64, difficulty: f64) -> f64 {
+    1.0 / (1.0 + 6f64.powf((difficulty - performance) / 400.0))
+}
+
+/// Estimates the performance by the maximum likelihood of the results, which are pairs of the
+/// difficulty and whether the problem is solved. The estimation is clamped into
+/// `[MIN_PERFORMANCE, MAX_PERFORMANCE]`, and `None` is returned if there are no results.
+pub fn estimate_performance(results: &[(f64, bool)]) -> Option<f64> {
+    maximize_likelihood(results, MIN_PERFORMANCE, MAX_PERFORMANCE)
+}
+
+/// Estimates the difficulty of a problem by the maximum likelihood of the results of the users,
+/// which are pairs of the rating and whether the user solved the problem. The estimation is
+/// clamped into `[MIN_DIFFICULTY, MAX_DIFFICULTY]`, and `None` is returned if there are no
+/// results.
+pub fn estimate_difficulty(results: &[(f64, bool)]) -> Option<f64> {
+    // A user of the rating `r` fails to solve a problem of the difficulty `d` with the probability
+    // that a user of the rating `d` solves a problem of the difficulty `r`. So the difficulty is
+    // the performance against the users, where the users who failed count as the solved ones.
+    let flipped = results
+        .iter()
+        .map(|&(rating, solved)| (rating, !solved))
+        .collect::<Vec<_>>();
+    maximize_likelihood(&flipped, MIN_DIFFICULTY, MAX_DIFFICULTY)
+}
+
+fn maximize_likelihood(results: &[(f64, bool)], min: f64, max: f64) -> Option<f64> {
+    if results.is_empty() {
+        return None;
+    }
+
+    // The derivative of the log likelihood is proportional to the number of the solved problems
+    // minus the expected one, which decreases as the performance increases.
+    let gradient = |performance: f64| {
+        results
+            .iter()
+            .map(|&(difficulty, solved)| {
+                let expected = solve_probability(performance, difficulty);
+                if solved {
+                    1.0 - expected
+                } else {
+                    -expected
+                }
+            })
+            .sum::<f64>()
+    };
+
+    let (mut low, mut high) = (min, max);
+    if gradient(low) <= 0.0 {
+        return Some(low);
+    }
+    if gradient(high) >= 0.0 {
+        return Some(high);
+    }
+    for _ in 0..BISECTION_ITERATIONS {
+        let middle = (low + high) / 2.0;
+        if gradient(middle) > 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Simulates the rating after a contest of the given performance. `rating` is the current one,
+/// or `None` if the user has never taken a rated contest.
+pub fn simulate_rating(rating: Option<f64>, performance: f64) -> f64 {
+    let raw = match rating {
+        Some(rating) => rating + (performance - rating) * RATING_WEIGHT,
+        None => performance - FIRST_CONTEST_CORRECTION,
+    };
+    map_low_rating(raw)
+}
+
+/// Estimates the difficulty of a problem solved by `ac_user_count` users from the problems of the
+/// same contest with the known difficulties, which are pairs of the number of the solvers and the
+/// difficulty. The difficulty is interpolated linearly in the logarithm of the number of the
+/// solvers between the nearest known problems, or extrapolated from the nearest one with the slope
+/// of the model, where 6 times more solvers mean 400 lower difficulty.
+/// `None` is returned if no difficulty is known.
+pub fn interpolate_difficulty(known: &[(i32, f64)], ac_user_count: i32) -> Option<f64> {
+    let log_count = |count: i32| f64::from(count.max(0) + 1).ln();
+    let slope = -400.0 / 6f64.ln();
+    let target = log_count(ac_user_count);
+
+    let mut known = known
+        .iter()
+        .map(|&(count, difficulty)| (log_count(count), difficulty))
+        .collect::<Vec<_>>();
+    known.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let upper = known.iter().position(|&(x, _)| x >= target);
+    match upper {
+        Some(0) => {
+            let (x, difficulty) = known[0];
+            Some(difficulty + (target - x) * slope)
+        }
+        Some(i) => {
+            let (x0, d0) = known[i - 1];
+            let (x1, d1) = known[i];
+            Some(d0 + (d1 - d0) * (target - x0) / (x1 - x0))
+        }
+        None => {
+            let &(x, difficulty) = known.last()?;
+            Some(difficulty + (target - x) * slope)
+        }
+    }
+}
+
+/// Maps a rating below 400 into `(0, 400)` as AtCoder does, so that it never gets negative.
+fn map_low_rating(rating: f64) -> f64 {
+    if rating >= 400.0 {
+        rating
+    } else {
+        400.0 / ((400.0 - rating) / 400.0).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_performance() {
+        assert_eq!(estimate_performance(&[]), None);
+        assert_eq!(
+            estimate_performance(&[(800.0, true), (1600.0, true)]),
+            Some(MAX_PERFORMANCE)
+        );
+        assert_eq!(
+            estimate_performance(&[(800.0, false), (1600.0, false)]),
+            Some(MIN_PERFORMANCE)
+        );
+
+        let performance = estimate_performance(&[(1000.0, true), (2000.0, false)]).unwrap();
+        assert!((performance - 1500.0).abs() < 1e-6);
+
+        let better = estimate_performance(&[(1000.0, true), (2000.0, true), (3000.0, false)]);
+        assert!(better.unwrap() > performance);
+    }
+
+    #[test]
+    fn test_estimate_difficulty() {
+        assert_eq!(estimate_difficulty(&[]), None);
+        assert_eq!(
+            estimate_difficulty(&[(800.0, true), (1600.0, true)]),
+            Some(MIN_DIFFICULTY)
+        );
+        assert_eq!(
+            estimate_difficulty(&[(800.0, false), (1600.0, false)]),
+            Some(MAX_DIFFICULTY)
+        );
+
+        let difficulty = estimate_difficulty(&[(1000.0, true), (2000.0, false)]).unwrap();
+        assert!((difficulty - 1500.0).abs() < 1e-6);
+
+        let harder = estimate_difficulty(&[(1000.0, false), (2000.0, true), (3000.0, false)]);
+        assert!(harder.unwrap() > difficulty);
+    }
+
+    #[test]
+    fn test_interpolate_difficulty() {
+        assert_eq!(interpolate_difficulty(&[], 100), None);
+
+        let known = [(3599, 400.0), (599, 1200.0)];
+        assert!((interpolate_difficulty(&known, 3599).unwrap() - 400.0).abs() < 1e-6);
+        let middle = interpolate_difficulty(&known, 1000).unwrap();
+        assert!(middle > 400.0 && middle < 1200.0);
+
+        assert!((interpolate_difficulty(&known, 99).unwrap() - 1600.0).abs() < 1e-6);
+        assert!((interpolate_difficulty(&known, 21599).unwrap() - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_simulate_rating() {
+        assert!((simulate_rating(Some(1000.0), 2000.0) - 1100.0).abs() < 1e-6);
+        assert!((simulate_rating(Some(2000.0), 1000.0) - 1900.0).abs() < 1e-6);
+        assert!((simulate_rating(None, 2000.0) - 800.0).abs() < 1e-6);
+
+        let low = simulate_rating(None, 1200.0);
+        assert!(low > 0.0 && low < 400.0);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::Result;
+use crate::sql::FeatureFlagClient;
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+const CACHE_TTL_SECOND: u64 = 60;
+
+/// The features which can be toggled at runtime by `feature_flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Feature {
+    /// `/atcoder-api/v3/user/judge_summary`, which aggregates the submissions of all the judges.
+    JudgeSummary,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 1] = [Feature::JudgeSummary];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::JudgeSummary => "judge_summary",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Feature> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|feature| feature.name() == name)
+    }
+
+    /// Whether the feature is enabled while its flag has never been set.
+    pub fn default_enabled(self) -> bool {
+        match self {
+            Feature::JudgeSummary => true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FeatureState {
+    pub name: &'static str,
+    pub enabled: bool,
+}
+
+struct Cache {
+    flags: BTreeMap<String, bool>,
+    loaded_at: Instant,
+}
+
+/// The feature flags cached for `ttl`, so that checking a flag does not hit the database on every
+/// request. A toggle takes effect in the other processes after the cache expires.
+#[derive(Clone)]
+pub struct FeatureFlags {
+    ttl: Duration,
+    cache: Arc<RwLock<Option<Cache>>>,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(CACHE_TTL_SECOND))
+    }
+}
+
+impl FeatureFlags {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cache: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn is_enabled<C: FeatureFlagClient>(&self, conn: &C, feature: Feature) -> Result<bool> {
+        self.with_flags(conn, |flags| {
+            flags
+                .get(feature.name())
+                .copied()
+                .unwrap_or_else(|| feature.default_enabled())
+        })
+    }
+
+    /// Returns the states of all the features.
+    pub fn load_states<C: FeatureFlagClient>(&self, conn: &C) -> Result<Vec<FeatureState>> {
+        self.with_flags(conn, |flags| {
+            Feature::ALL
+                .iter()
+                .map(|&feature| FeatureState {
+                    name: feature.name(),
+                    enabled: flags
+                        .get(feature.name())
+                        .copied()
+                        .unwrap_or_else(|| feature.default_enabled()),
+                })
+                .collect()
+        })
+    }
+
+    /// Drops the cache, so that the next check sees the latest flags.
+    pub fn invalidate(&self) {
+        *self.cache.write().expect("The cache is poisoned.") = None;
+    }
+
+    fn with_flags<C, F, T>(&self, conn: &C, f: F) -> Result<T>
+    where
+        C: FeatureFlagClient,
+        F: FnOnce(&BTreeMap<String, bool>) -> T,
+    {
+        if let Some(cache) = self.cache.read().expect("The cache is poisoned.").as_ref() {
+            if cache.loaded_at.elapsed() < self.ttl {
+                return Ok(f(&cache.flags));
+            }
+        }
+
+        let flags = conn
+            .load_feature_flags()?
+            .into_iter()
+            .map(|flag| (flag.name, flag.enabled))
+            .collect::<BTreeMap<_, _>>();
+        let result = f(&flags);
+        *self.cache.write().expect("The cache is poisoned.") = Some(Cache {
+            flags,
+            loaded_at: Instant::now(),
+        });
+        Ok(result)
+    }
+}
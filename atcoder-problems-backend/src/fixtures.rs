@@ -0,0 +1,300 @@
+use crate::error::Result;
+use crate::sql::models::{Contest, ContestProblem, Point, Problem, Submission};
+use crate::sql::{ContestProblemClient, SimpleClient, SubmissionClient};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CONTEST_INTERVAL_SECOND: i64 = 7 * 24 * 3600;
+const CONTEST_DURATION_SECOND: i64 = 100 * 60;
+/// The exponent of the power law of the activities of the users: the `r`-th most active user
+/// submits in proportion to `1 / r^USER_ACTIVITY_EXPONENT`.
+const USER_ACTIVITY_EXPONENT: f64 = 1.1;
+/// The ratio of the submissions during the contests. The rest are the practices after them.
+const CONTEST_SUBMISSION_RATE: f64 = 0.4;
+const LANGUAGES: [(&str, u32); 5] = [
+    ("C++ (GCC 9.2.1)", 60),
+    ("Python (3.8.2)", 20),
+    ("PyPy3 (7.3.0)", 10),
+    ("Rust (1.42.0)", 5),
+    ("Java (OpenJDK 11.0.6)", 5),
+];
+const REJECTED_RESULTS: [&str; 4] = ["WA", "TLE", "RE", "CE"];
+
+/// The shape of the generated data set. The same options generate the same data set.
+#[derive(Debug, Clone)]
+pub struct FixtureOptions {
+    pub seed: u64,
+    pub user_count: usize,
+    pub contest_count: usize,
+    pub problems_per_contest: usize,
+    pub submission_count: usize,
+    /// The ratio of the submissions whose results are changed by the rejudges.
+    pub rejudge_rate: f64,
+    pub start_epoch_second: i64,
+}
+
+impl Default for FixtureOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            user_count: 1000,
+            contest_count: 50,
+            problems_per_contest: 6,
+            submission_count: 100_000,
+            rejudge_rate: 0.001,
+            start_epoch_second: 1_577_836_800,
+        }
+    }
+}
+
+/// The synthetic contests and submissions for the tests of the performance of the updaters and
+/// the endpoints. `rejudged` are the submissions in `submissions` with the results after the
+/// rejudges, which are stored after them like the crawlers do.
+#[derive(Debug, Default)]
+pub struct Fixtures {
+    pub contests: Vec<Contest>,
+    pub problems: Vec<Problem>,
+    pub contest_problems: Vec<ContestProblem>,
+    pub submissions: Vec<Submission>,
+    pub rejudged: Vec<Submission>,
+}
+
+impl Fixtures {
+    pub fn generate(options: &FixtureOptions) -> Self {
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let mut fixtures = Fixtures::default();
+        for i in 0..options.contest_count {
+            let contest = Contest {
+                id: format!("abc{:03}", i + 1),
+                start_epoch_second: options.start_epoch_second + i as i64 * CONTEST_INTERVAL_SECOND,
+                duration_second: CONTEST_DURATION_SECOND,
+                title: format!("AtCoder Beginner Contest {:03}", i + 1),
+                rate_change: " ~ 1999".to_owned(),
+            };
+            for j in 0..options.problems_per_contest {
+                let index = (b'a' + j as u8) as char;
+                let problem_id = format!("{}_{}", contest.id, index);
+                fixtures.problems.push(Problem {
+                    id: problem_id.clone(),
+                    contest_id: contest.id.clone(),
+                    title: format!("{}. Problem {}", index.to_ascii_uppercase(), index),
+                });
+                fixtures.contest_problems.push(ContestProblem {
+                    contest_id: contest.id.clone(),
+                    problem_id,
+                });
+            }
+            fixtures.contests.push(contest);
+        }
+        if fixtures.problems.is_empty() || options.user_count == 0 {
+            return fixtures;
+        }
+
+        let user_weights = power_law_cumulative_weights(options.user_count);
+        let language_weights = LANGUAGES
+            .iter()
+            .scan(0, |sum, &(_, weight)| {
+                *sum += weight;
+                Some(*sum as f64)
+            })
+            .collect::<Vec<_>>();
+        let end_epoch_second =
+            options.start_epoch_second + options.contest_count as i64 * CONTEST_INTERVAL_SECOND;
+        for _ in 0..options.submission_count {
+            let contest_index = rng.gen_range(0, options.contest_count);
+            let problem_index = rng.gen_range(0, options.problems_per_contest);
+            let contest = &fixtures.contests[contest_index];
+            let problem =
+                &fixtures.problems[contest_index * options.problems_per_contest + problem_index];
+            let epoch_second = if rng.gen_bool(CONTEST_SUBMISSION_RATE) {
+                contest.start_epoch_second + rng.gen_range(0, contest.duration_second)
+            } else {
+                let practice_start = contest.start_epoch_second + contest.duration_second;
+                rng.gen_range(practice_start, end_epoch_second.max(practice_start + 1))
+            };
+
+            // The later problems are harder.
+            let accepted_rate = 0.8 / (problem_index + 1) as f64;
+            let point = 100.0 * (problem_index + 1) as f64;
+            let (result, point) = if rng.gen_bool(accepted_rate) {
+                ("AC", point)
+            } else {
+                (
+                    REJECTED_RESULTS[rng.gen_range(0, REJECTED_RESULTS.len())],
+                    0.0,
+                )
+            };
+            let user = sample(&user_weights, &mut rng);
+            let language = sample(&language_weights, &mut rng);
+            fixtures.submissions.push(Submission {
+                id: 0,
+                epoch_second,
+                problem_id: problem.id.clone(),
+                contest_id: contest.id.clone(),
+                user_id: format!("user{:05}", user),
+                language: LANGUAGES[language].0.to_owned(),
+                point: Point::from(point),
+                length: rng.gen_range(100, 5000),
+                result: result.to_owned(),
+                execution_time: Some(rng.gen_range(1, 2000)),
+            });
+        }
+
+        // The submission ids increase with the time, as in AtCoder.
+        fixtures.submissions.sort_by_key(|s| s.epoch_second);
+        for (id, submission) in fixtures.submissions.iter_mut().enumerate() {
+            submission.id = id as i64 + 1;
+        }
+        for submission in fixtures.submissions.iter() {
+            if !rng.gen_bool(options.rejudge_rate) {
+                continue;
+            }
+            let mut rejudged = submission.clone();
+            if rejudged.result == "AC" {
+                rejudged.result = "WA".to_owned();
+                rejudged.point = Point::default();
+            } else {
+                let problem_index = fixtures
+                    .problems
+                    .iter()
+                    .position(|p| p.id == rejudged.problem_id)
+                    .unwrap_or(0)
+                    % options.problems_per_contest;
+                rejudged.result = "AC".to_owned();
+                rejudged.point = Point::from(100.0 * (problem_index + 1) as f64);
+            }
+            fixtures.rejudged.push(rejudged);
+        }
+        fixtures
+    }
+
+    /// Stores the fixtures, and then applies the rejudges.
+    pub fn insert<C>(&self, conn: &C) -> Result<()>
+    where
+        C: SimpleClient + ContestProblemClient + SubmissionClient,
+    {
+        conn.insert_contests(&self.contests)?;
+        conn.insert_problems(&self.problems)?;
+        conn.insert_contest_problem(&self.contest_problems)?;
+        conn.update_submissions(&self.submissions)?;
+        conn.update_submissions(&self.rejudged)?;
+        Ok(())
+    }
+}
+
+/// The cumulative weights of the power law, from the most active user.
+fn power_law_cumulative_weights(count: usize) -> Vec<f64> {
+    (1..=count)
+        .scan(0.0, |sum, rank| {
+            *sum += 1.0 / (rank as f64).powf(USER_ACTIVITY_EXPONENT);
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// Picks an index with the probability in proportion to its weight.
+fn sample<R: Rng>(cumulative_weights: &[f64], rng: &mut R) -> usize {
+    let total = cumulative_weights.last().copied().unwrap_or_default();
+    let x = rng.gen_range(0.0, total);
+    cumulative_weights
+        .iter()
+        .position(|&weight| x < weight)
+        .unwrap_or(cumulative_weights.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn options() -> FixtureOptions {
+        FixtureOptions {
+            user_count: 100,
+            contest_count: 5,
+            problems_per_contest: 4,
+            submission_count: 10_000,
+            rejudge_rate: 0.01,
+            ..FixtureOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let first = Fixtures::generate(&options());
+        let second = Fixtures::generate(&options());
+        assert_eq!(first.contests, second.contests);
+        let key = |s: &Submission| (s.id, s.epoch_second, s.user_id.clone(), s.result.clone());
+        assert_eq!(
+            first.submissions.iter().map(key).collect::<Vec<_>>(),
+            second.submissions.iter().map(key).collect::<Vec<_>>()
+        );
+
+        let another = Fixtures::generate(&FixtureOptions {
+            seed: 1,
+            ..options()
+        });
+        assert_ne!(
+            first.submissions.iter().map(key).collect::<Vec<_>>(),
+            another.submissions.iter().map(key).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let options = options();
+        let fixtures = Fixtures::generate(&options);
+        assert_eq!(fixtures.contests.len(), 5);
+        assert_eq!(fixtures.problems.len(), 20);
+        assert_eq!(fixtures.contest_problems.len(), 20);
+        assert_eq!(fixtures.submissions.len(), 10_000);
+        assert!(fixtures
+            .submissions
+            .windows(2)
+            .all(|w| w[0].id < w[1].id && w[0].epoch_second <= w[1].epoch_second));
+
+        let mut user_counts = BTreeMap::new();
+        for s in fixtures.submissions.iter() {
+            *user_counts.entry(s.user_id.as_str()).or_insert(0) += 1;
+        }
+        assert!(
+            user_counts["user00000"] > user_counts.get("user00099").unwrap_or(&0) * 10,
+            "The most active user submits far more than the least active one."
+        );
+
+        let during_contests = fixtures
+            .submissions
+            .iter()
+            .filter(|s| {
+                let contest = fixtures
+                    .contests
+                    .iter()
+                    .find(|c| c.id == s.contest_id)
+                    .unwrap();
+                s.epoch_second < contest.start_epoch_second + contest.duration_second
+            })
+            .count();
+        assert!(
+            during_contests > 3000,
+            "The submissions burst during the contests."
+        );
+
+        assert!(!fixtures.rejudged.is_empty());
+        for rejudged in fixtures.rejudged.iter() {
+            let original = &fixtures.submissions[rejudged.id as usize - 1];
+            assert_ne!(original.result, rejudged.result);
+        }
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let weights = vec![1.0, 1.0, 3.0];
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            counts[sample(&weights, &mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!(counts[2] > counts[0]);
+    }
+}
@@ -0,0 +1,32 @@
+mod backup;
+mod cold_start;
+mod crawl;
+mod dump;
+mod problem_models;
+mod purge;
+mod scheduler;
+mod submission_archive;
+mod update;
+mod validate;
+mod virtual_contest_archive;
+
+pub use backup::{
+    backup_database, backup_submissions, restore_database, BackupJob, DATABASE_BACKUP_PREFIX,
+    SUBMISSIONS_BACKUP_PATH,
+};
+pub use cold_start::{estimate_cold_start_difficulties, ColdStartJob};
+pub use crawl::{NewContestCrawlJob, ProblemCrawlJob, RecentCrawlJob};
+pub use dump::{dump_json, DumpJob};
+pub use problem_models::{
+    import_problem_models, invalidate_problem_model_caches, ProblemModelJob,
+    DEFAULT_PROBLEM_MODELS_URL,
+};
+pub use purge::{purge_deleted, PurgeJob};
+pub use scheduler::{Job, JobScheduler};
+pub use submission_archive::{archive_submissions, SubmissionArchiveJob};
+pub use update::{
+    batch_update, delta_update, invalidate_aggregate_caches, run_update_step, update_tier,
+    update_users, BatchUpdateJob, DeltaUpdateJob, UpdateStep, UpdateTier,
+};
+pub use validate::{validate, ValidationJob};
+pub use virtual_contest_archive::{archive_virtual_contests, VirtualContestArchiveJob};
@@ -0,0 +1,197 @@
+use super::Job;
+use crate::error::Result;
+use crate::sql::models::Submission;
+use crate::sql::{connect, SubmissionClient, SubmissionRequest};
+use crate::storage::Storage;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use http_types::StatusCode;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+pub const SUBMISSIONS_BACKUP_PATH: &str = "/backups/submissions.csv.gz";
+pub const DATABASE_BACKUP_PREFIX: &str = "/backups/database/";
+const BATCH_SIZE: i64 = 100_000;
+const HEADER: &str =
+    "id,epoch_second,problem_id,contest_id,user_id,language,point,length,result,execution_time";
+
+/// Backs up all the submissions as a gzipped CSV. The submissions are streamed to the storage
+/// in batches, since they do not fit in memory.
+pub fn backup_submissions(conn: &PgConnection, storage: &dyn Storage) -> Result<()> {
+    let writer = storage.create(SUBMISSIONS_BACKUP_PATH, "application/gzip")?;
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    writeln!(encoder, "{}", HEADER)?;
+
+    let mut from_id = -1;
+    let mut count = 0;
+    loop {
+        let submissions = conn.get_submissions(SubmissionRequest::AfterId {
+            from_id,
+            count: BATCH_SIZE,
+        })?;
+        let last_id = match submissions.last() {
+            Some(submission) => submission.id,
+            None => break,
+        };
+        count += submissions.len();
+        for submission in submissions.iter() {
+            write_row(&mut encoder, submission)?;
+        }
+        log::info!("Backed up submissions until {}", last_id);
+        from_id = last_id;
+    }
+
+    encoder.finish()?.finish()?;
+    log::info!("Backed up {} submissions", count);
+    Ok(())
+}
+
+fn write_row<W: Write>(writer: &mut W, submission: &Submission) -> Result<()> {
+    writeln!(
+        writer,
+        "{},{},{},{},{},{},{},{},{},{}",
+        submission.id,
+        submission.epoch_second,
+        escape(&submission.problem_id),
+        escape(&submission.contest_id),
+        escape(&submission.user_id),
+        escape(&submission.language),
+        submission.point,
+        submission.length,
+        escape(&submission.result),
+        submission
+            .execution_time
+            .map(|time| time.to_string())
+            .unwrap_or_default()
+    )?;
+    Ok(())
+}
+
+/// Quotes the field if it contains a comma, a quote or a line break.
+fn escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Streams a logical dump of the whole database from `pg_dump` to the storage, and deletes the
+/// backups except the latest `retention_count` ones. `pg_dump` takes a consistent snapshot, so
+/// the crawlers and the updaters may keep running. Returns the path of the backup.
+pub fn backup_database(
+    database_url: &str,
+    storage: &dyn Storage,
+    retention_count: usize,
+) -> Result<String> {
+    let path = format!(
+        "{}{}.dump",
+        DATABASE_BACKUP_PREFIX,
+        Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+    );
+    log::info!("Backing up the database to {}", path);
+    let mut child = Command::new("pg_dump")
+        .args(["--format=custom", "--no-owner", "--dbname", database_url])
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdout = child.stdout.take().expect("The stdout is piped.");
+    let mut writer = storage.create(&path, "application/octet-stream")?;
+    let size = io::copy(&mut stdout, &mut writer)?;
+    check_status("pg_dump", child.wait()?)?;
+    writer.finish()?;
+    log::info!("Backed up {} bytes", size);
+
+    let backups = storage.list(DATABASE_BACKUP_PREFIX)?;
+    let expired_count = backups.len().saturating_sub(retention_count);
+    for expired in backups.iter().take(expired_count) {
+        log::info!("Deleting the expired backup {}", expired);
+        storage.delete(expired)?;
+    }
+    Ok(path)
+}
+
+/// Restores the database from the backup at `path`, or the latest one if `path` is `None`.
+/// The existing tables in the backup are dropped, and everything is restored in a transaction.
+/// Returns the path of the restored backup.
+pub fn restore_database(
+    database_url: &str,
+    storage: &dyn Storage,
+    path: Option<&str>,
+) -> Result<String> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => storage.list(DATABASE_BACKUP_PREFIX)?.pop().ok_or_else(|| {
+            http_types::Error::from_str(StatusCode::NotFound, "There are no backups.")
+        })?,
+    };
+    let data = storage.get(&path)?.ok_or_else(|| {
+        http_types::Error::from_str(StatusCode::NotFound, format!("{} does not exist.", path))
+    })?;
+    log::info!("Restoring the database from {}", path);
+    let mut child = Command::new("pg_restore")
+        .args([
+            "--clean",
+            "--if-exists",
+            "--no-owner",
+            "--single-transaction",
+            "--dbname",
+            database_url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("The stdin is piped.");
+    stdin.write_all(&data)?;
+    drop(stdin);
+    check_status("pg_restore", child.wait()?)?;
+    Ok(path)
+}
+
+fn check_status(command: &str, status: std::process::ExitStatus) -> Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(http_types::Error::from_str(
+            StatusCode::InternalServerError,
+            format!("{} failed with {}", command, status),
+        ))
+    }
+}
+
+pub struct BackupJob {
+    storage: Arc<dyn Storage>,
+}
+
+impl BackupJob {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for BackupJob {
+    fn name(&self) -> &str {
+        "backup_submissions"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        backup_submissions(&connect(url)?, self.storage.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("C++14 (GCC 5.4.1)"), "C++14 (GCC 5.4.1)");
+        assert_eq!(escape("Rust, 1.42"), "\"Rust, 1.42\"");
+        assert_eq!(escape("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -0,0 +1,135 @@
+use super::{invalidate_problem_model_caches, Job};
+use crate::cache::Cache;
+use crate::error::Result;
+use crate::estimation::estimate_difficulty;
+use crate::sql::models::ProvisionalDifficulty;
+use crate::sql::{
+    connect, ContestProblemClient, ExcludedContestClient, ProblemModelClient, SimpleClient,
+    SubmissionClient, SubmissionRequest,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// The problems of the contests which have ended within this are estimated.
+const COLD_START_WINDOW_SECOND: i64 = 3 * 24 * 3600;
+
+/// The problems with fewer rated participants are not estimated.
+const MIN_SAMPLE_SIZE: usize = 10;
+
+/// Estimates the provisional difficulties of the problems of the contests which have ended
+/// recently, from whether the participants with the predicted ratings solved them during the
+/// contests. Only the problems without the difficulties, or with the provisional ones, are
+/// estimated, so that the difficulties of the problem models are never overwritten. The excluded
+/// contests are not estimated. Returns the number of the estimated problems.
+pub fn estimate_cold_start_difficulties(conn: &PgConnection, now: i64) -> Result<usize> {
+    let excluded = conn
+        .load_excluded_contests()?
+        .into_iter()
+        .map(|contest| contest.contest_id)
+        .collect::<BTreeSet<_>>();
+    let contests = conn
+        .load_contests()?
+        .into_iter()
+        .filter(|contest| {
+            let end_epoch_second = contest.start_epoch_second + contest.duration_second;
+            end_epoch_second <= now && end_epoch_second > now - COLD_START_WINDOW_SECOND
+        })
+        .filter(|contest| !excluded.contains(&contest.id))
+        .collect::<Vec<_>>();
+    if contests.is_empty() {
+        return Ok(0);
+    }
+    let contest_problems = conn.load_contest_problem()?;
+    let provisional = conn
+        .load_provisional_difficulties()?
+        .into_iter()
+        .map(|d| d.problem_id)
+        .collect::<BTreeSet<_>>();
+
+    let mut estimated = vec![];
+    for contest in contests.iter() {
+        let problem_ids = contest_problems
+            .iter()
+            .filter(|p| p.contest_id == contest.id)
+            .map(|p| p.problem_id.as_str())
+            .collect::<Vec<_>>();
+        let known = conn.load_difficulties(&problem_ids)?;
+        let targets = problem_ids
+            .iter()
+            .filter(|&&problem_id| {
+                !known.contains_key(problem_id) || provisional.contains(problem_id)
+            })
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            continue;
+        }
+
+        let submissions = conn.get_submissions(SubmissionRequest::ProblemsTime {
+            problem_ids: &problem_ids,
+            from_second: contest.start_epoch_second,
+            to_second: contest.start_epoch_second + contest.duration_second,
+        })?;
+        let participants = submissions
+            .iter()
+            .map(|s| s.user_id.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let ratings = conn.load_predicted_ratings(&participants)?;
+        let solved = submissions
+            .iter()
+            .filter(|s| s.verdict().is_accepted())
+            .map(|s| (s.user_id.as_str(), s.problem_id.as_str()))
+            .collect::<BTreeSet<_>>();
+
+        for &&problem_id in targets.iter() {
+            let results = ratings
+                .iter()
+                .map(|(user_id, &rating)| {
+                    (rating, solved.contains(&(user_id.as_str(), problem_id)))
+                })
+                .collect::<Vec<_>>();
+            if results.len() < MIN_SAMPLE_SIZE {
+                continue;
+            }
+            if let Some(difficulty) = estimate_difficulty(&results) {
+                estimated.push(ProvisionalDifficulty {
+                    problem_id: problem_id.to_owned(),
+                    difficulty,
+                    sample_size: results.len() as i32,
+                    estimated_epoch_second: now,
+                });
+            }
+        }
+    }
+
+    conn.save_provisional_difficulties(&estimated)?;
+    log::info!("Estimated {} provisional difficulties", estimated.len());
+    Ok(estimated.len())
+}
+
+pub struct ColdStartJob {
+    cache: Arc<dyn Cache>,
+}
+
+impl ColdStartJob {
+    pub fn new(cache: Arc<dyn Cache>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for ColdStartJob {
+    fn name(&self) -> &str {
+        "cold_start_difficulty"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        estimate_cold_start_difficulties(&connect(url)?, Utc::now().timestamp())?;
+        invalidate_problem_model_caches(self.cache.as_ref()).await
+    }
+}
@@ -0,0 +1,58 @@
+use super::Job;
+use crate::crawler::{ProblemCrawler, RecentCrawler, WholeContestCrawler};
+use crate::error::Result;
+use crate::sql::{connect, SimpleClient};
+
+use algorithm_problem_client::AtCoderClient;
+use async_trait::async_trait;
+
+const NEW_CONTEST_NUM: usize = 5;
+
+pub struct RecentCrawlJob;
+
+#[async_trait(?Send)]
+impl Job for RecentCrawlJob {
+    fn name(&self) -> &str {
+        "crawl_recent_submissions"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        let crawler = RecentCrawler::new(connect(url)?, AtCoderClient);
+        crawler.crawl().await
+    }
+}
+
+/// Crawls all the submissions of the latest contests.
+pub struct NewContestCrawlJob;
+
+#[async_trait(?Send)]
+impl Job for NewContestCrawlJob {
+    fn name(&self) -> &str {
+        "crawl_from_new_contests"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        let mut contests = connect(url)?.load_contests()?;
+        contests.sort_by_key(|c| std::cmp::Reverse(c.start_epoch_second));
+        for contest in contests.iter().take(NEW_CONTEST_NUM) {
+            log::info!("Starting {}", contest.id);
+            let crawler = WholeContestCrawler::new(connect(url)?, AtCoderClient, &contest.id);
+            crawler.crawl().await?;
+        }
+        Ok(())
+    }
+}
+
+pub struct ProblemCrawlJob;
+
+#[async_trait(?Send)]
+impl Job for ProblemCrawlJob {
+    fn name(&self) -> &str {
+        "crawl_problems"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        let crawler = ProblemCrawler::new(connect(url)?, AtCoderClient);
+        crawler.crawl().await
+    }
+}
@@ -0,0 +1,154 @@
+use super::Job;
+use crate::error::Result;
+use crate::sql::internal::moderation_manager::ModerationManager;
+use crate::sql::models::*;
+use crate::sql::schema::*;
+use crate::sql::{connect, DumpClient, LanguageCountClient, MergedProblemClient};
+use crate::storage::Storage;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use log::info;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// Uploads the static dumps to the storage and records them in the manifest.
+pub fn dump_json(conn: &PgConnection, storage: &dyn Storage) -> Result<()> {
+    // Banned users are excluded from the rankings.
+    let banned_users = conn
+        .get_banned_users()?
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    let is_ranked = |user_id: &String| !banned_users.contains(user_id);
+
+    let data_paths = vec![
+        (
+            conn.load_merged_problems()?.serialize_to_bytes()?,
+            "/resources/merged-problems.json",
+        ),
+        (
+            contests::table
+                .order_by(contests::id)
+                .load::<Contest>(conn)?
+                .serialize_to_bytes()?,
+            "/resources/contests.json",
+        ),
+        (
+            accepted_count::table
+                .order_by(accepted_count::user_id)
+                .load::<UserProblemCount>(conn)?
+                .into_iter()
+                .filter(|count| is_ranked(&count.user_id))
+                .collect::<Vec<_>>()
+                .serialize_to_bytes()?,
+            "/resources/ac.json",
+        ),
+        (
+            problems::table
+                .order_by(problems::id)
+                .load::<Problem>(conn)?
+                .serialize_to_bytes()?,
+            "/resources/problems.json",
+        ),
+        (
+            rated_point_sum::table
+                .order_by(rated_point_sum::user_id)
+                .load::<UserSum>(conn)?
+                .into_iter()
+                .filter(|count| is_ranked(&count.user_id))
+                .collect::<Vec<_>>()
+                .serialize_to_bytes()?,
+            "/resources/sums.json",
+        ),
+        (
+            conn.load_language_count()?
+                .into_iter()
+                .filter(|count| is_ranked(&count.user_id))
+                .collect::<Vec<_>>()
+                .serialize_to_bytes()?,
+            "/resources/lang.json",
+        ),
+        (
+            contest_problem::table
+                .order_by(contest_problem::problem_id)
+                .load::<ContestProblem>(conn)?
+                .serialize_to_bytes()?,
+            "/resources/contest-problem.json",
+        ),
+        (
+            max_streaks::table
+                .order_by(max_streaks::user_id)
+                .load::<UserStreak>(conn)?
+                .into_iter()
+                .filter(|count| is_ranked(&count.user_id))
+                .collect::<Vec<_>>()
+                .serialize_to_bytes()?,
+            "/resources/streaks.json",
+        ),
+    ];
+
+    // The manifest keeps the time when each dump was last changed.
+    let stored_hashes = conn
+        .load_dumps()?
+        .into_iter()
+        .map(|dump| (dump.name, dump.sha256))
+        .collect::<BTreeMap<_, _>>();
+    let now = Utc::now().timestamp();
+
+    for (data, path) in data_paths.into_iter() {
+        let dump = Dump {
+            name: path.trim_start_matches("/resources/").to_owned(),
+            url: storage.public_url(path),
+            size: data.len() as i64,
+            sha256: hex::encode(Sha256::digest(&data)),
+            generated_epoch_second: now,
+        };
+        info!("Uploading {}", path);
+        let updated = storage.update(path, &data, "application/json;charset=utf-8")?;
+        info!("Uploaded");
+        if updated || stored_hashes.get(&dump.name) != Some(&dump.sha256) {
+            conn.update_dump(&dump)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub struct DumpJob {
+    storage: Arc<dyn Storage>,
+}
+
+impl DumpJob {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for DumpJob {
+    fn name(&self) -> &str {
+        "dump_json"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        dump_json(&connect(url)?, self.storage.as_ref())
+    }
+}
+
+trait SerializeToBytes {
+    fn serialize_to_bytes(self) -> Result<Vec<u8>>;
+}
+
+impl<T> SerializeToBytes for T
+where
+    T: Serialize,
+{
+    fn serialize_to_bytes(self) -> Result<Vec<u8>> {
+        let vec = serde_json::to_vec(&self)?;
+        Ok(vec)
+    }
+}
@@ -0,0 +1,116 @@
+use super::Job;
+use crate::cache::{Cache, DETAILED_PROBLEMS_PREFIX, PROBLEM_MODELS_PREFIX};
+use crate::error::{Error, Result};
+use crate::sql::models::{ProblemModel, ProblemModelDiagnostics};
+use crate::sql::{connect, ContestProblemClient, ExcludedContestClient, ProblemModelClient};
+
+use async_trait::async_trait;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+pub const DEFAULT_PROBLEM_MODELS_URL: &str =
+    "https://kenkoooo.com/atcoder/resources/problem-models.json";
+
+#[derive(Deserialize)]
+struct RawProblemModel {
+    difficulty: Option<f64>,
+    is_experimental: Option<bool>,
+    irt_users: Option<i32>,
+    irt_loglikelihood: Option<f64>,
+    variance: Option<f64>,
+}
+
+/// Imports the difficulties fitted by the problem model estimator, and the diagnostics of the
+/// fits which have them. The problems of the excluded contests are skipped.
+pub async fn import_problem_models(conn: &PgConnection, models_url: &str) -> Result<()> {
+    let mut raw_models: BTreeMap<String, RawProblemModel> = surf::get(models_url)
+        .recv_json()
+        .await
+        .map_err(Error::upstream)?;
+    let excluded = conn
+        .load_excluded_contests()?
+        .into_iter()
+        .map(|contest| contest.contest_id)
+        .collect::<BTreeSet<_>>();
+    for contest_problem in conn.load_contest_problem()? {
+        if excluded.contains(&contest_problem.contest_id) {
+            raw_models.remove(&contest_problem.problem_id);
+        }
+    }
+    let diagnostics = raw_models
+        .iter()
+        .filter_map(|(problem_id, model)| {
+            Some(ProblemModelDiagnostics {
+                problem_id: problem_id.clone(),
+                sample_size: model.irt_users?,
+                log_likelihood: model.irt_loglikelihood,
+                variance: model.variance,
+            })
+        })
+        .collect::<Vec<_>>();
+    let models = raw_models
+        .into_iter()
+        .map(|(problem_id, model)| ProblemModel {
+            problem_id,
+            difficulty: model.difficulty,
+            is_experimental: model.is_experimental.unwrap_or(false),
+        })
+        .collect::<Vec<_>>();
+    log::info!(
+        "Fetched {} problem models with {} diagnostics",
+        models.len(),
+        diagnostics.len()
+    );
+
+    // The provisional difficulties are kept until the problem models have the difficulties.
+    let provisional = conn
+        .load_provisional_difficulties()?
+        .into_iter()
+        .map(|d| d.problem_id)
+        .collect::<BTreeSet<_>>();
+    let models = models
+        .into_iter()
+        .filter(|model| model.difficulty.is_some() || !provisional.contains(&model.problem_id))
+        .collect::<Vec<_>>();
+    let fitted = models
+        .iter()
+        .filter(|model| provisional.contains(&model.problem_id))
+        .map(|model| model.problem_id.as_str())
+        .collect::<Vec<_>>();
+
+    conn.update_problem_models(&models)?;
+    conn.delete_provisional_difficulties(&fitted)?;
+    conn.update_problem_model_diagnostics(&diagnostics)
+}
+
+/// Drops the cached responses which contain the difficulties, so that the servers sharing the
+/// cache serve the updated ones.
+pub async fn invalidate_problem_model_caches(cache: &dyn Cache) -> Result<()> {
+    cache.invalidate(PROBLEM_MODELS_PREFIX).await?;
+    cache.invalidate(DETAILED_PROBLEMS_PREFIX).await
+}
+
+pub struct ProblemModelJob {
+    models_url: String,
+    cache: Arc<dyn Cache>,
+}
+
+impl ProblemModelJob {
+    pub fn new(models_url: String, cache: Arc<dyn Cache>) -> Self {
+        Self { models_url, cache }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for ProblemModelJob {
+    fn name(&self) -> &str {
+        "import_problem_models"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        import_problem_models(&connect(url)?, &self.models_url).await?;
+        invalidate_problem_model_caches(self.cache.as_ref()).await
+    }
+}
@@ -0,0 +1,34 @@
+use super::Job;
+use crate::error::Result;
+use crate::sql::connect;
+use crate::sql::internal::problem_list_manager::ProblemListManager;
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::internal::DELETED_RETENTION_SECOND;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+
+/// Deletes the problem lists and the virtual contests which have been deleted longer than
+/// `DELETED_RETENTION_SECOND` before `now` permanently, and returns the number of them.
+pub fn purge_deleted(conn: &PgConnection, now: i64) -> Result<usize> {
+    let before = now - DELETED_RETENTION_SECOND;
+    let lists = conn.purge_deleted_lists(before)?;
+    let contests = conn.purge_deleted_contests(before)?;
+    log::info!("Purged {} lists and {} contests", lists, contests);
+    Ok(lists + contests)
+}
+
+pub struct PurgeJob;
+
+#[async_trait(?Send)]
+impl Job for PurgeJob {
+    fn name(&self) -> &str {
+        "purge"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        purge_deleted(&connect(url)?, Utc::now().timestamp())?;
+        Ok(())
+    }
+}
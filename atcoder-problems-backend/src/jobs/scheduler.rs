@@ -0,0 +1,167 @@
+use crate::error::Result;
+use crate::shutdown;
+use crate::sql::models::JobRun;
+use crate::sql::{connect, JobRunClient, JobStatus};
+
+use async_std::task::block_on;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const TICK_SECOND: u64 = 10;
+
+/// A periodic work of `JobScheduler`. Each run connects to the database at `url` by itself.
+#[async_trait(?Send)]
+pub trait Job {
+    fn name(&self) -> &str;
+    async fn run(&self, url: &str) -> Result<()>;
+}
+
+struct ScheduledJob {
+    job: Arc<dyn Job + Send + Sync>,
+    interval_second: i64,
+}
+
+/// Runs the jobs periodically, each in its own thread. The runs are recorded in `job_runs`,
+/// which also prevents a job from starting while its previous run has not finished.
+pub struct JobScheduler {
+    url: String,
+    jobs: Vec<ScheduledJob>,
+    running: Arc<AtomicUsize>,
+}
+
+impl JobScheduler {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            jobs: Vec::new(),
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Adds the job which starts `interval_second` seconds after the start of its previous run.
+    pub fn add<J: Job + Send + Sync + 'static>(mut self, job: J, interval_second: i64) -> Self {
+        self.jobs.push(ScheduledJob {
+            job: Arc::new(job),
+            interval_second,
+        });
+        self
+    }
+
+    /// Runs the jobs until the shutdown is requested, and then waits for the running jobs. The
+    /// runs left running are from a stopped scheduler, so they are marked as abandoned first.
+    /// Only one scheduler should run at a time.
+    pub fn run(&self) -> Result<()> {
+        let abandoned = connect(&self.url)?.abandon_running_job_runs(Utc::now().timestamp())?;
+        if abandoned > 0 {
+            log::warn!("Abandoned {} job runs", abandoned);
+        }
+        while !shutdown::is_requested() {
+            if let Err(e) = self.tick(Utc::now().timestamp()) {
+                log::error!("{:?}", e);
+            }
+            block_on(shutdown::sleep(Duration::from_secs(TICK_SECOND)));
+        }
+
+        log::info!("Waiting for {} jobs", self.running.load(Ordering::SeqCst));
+        while self.running.load(Ordering::SeqCst) > 0 {
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(())
+    }
+
+    /// Starts the jobs which are due at `now`, and returns the handles of their threads.
+    pub fn tick(&self, now: i64) -> Result<Vec<JoinHandle<()>>> {
+        let latest_runs = connect(&self.url)?
+            .load_latest_job_runs()?
+            .into_iter()
+            .map(|run| (run.job_name.clone(), run))
+            .collect::<BTreeMap<_, _>>();
+        let handles = self
+            .jobs
+            .iter()
+            .filter(|scheduled| {
+                is_due(
+                    latest_runs.get(scheduled.job.name()),
+                    scheduled.interval_second,
+                    now,
+                )
+            })
+            .map(|scheduled| {
+                let job = scheduled.job.clone();
+                let url = self.url.clone();
+                let running = self.running.clone();
+                running.fetch_add(1, Ordering::SeqCst);
+                thread::spawn(move || {
+                    if let Err(e) = execute(&url, job.as_ref(), now) {
+                        log::error!("{}: {:?}", job.name(), e);
+                    }
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        Ok(handles)
+    }
+}
+
+fn is_due(latest_run: Option<&JobRun>, interval_second: i64, now: i64) -> bool {
+    match latest_run {
+        Some(run) if run.status == JobStatus::Running.as_str() => false,
+        Some(run) => run.started_epoch_second + interval_second <= now,
+        None => true,
+    }
+}
+
+fn execute(url: &str, job: &(dyn Job + Send + Sync), now: i64) -> Result<()> {
+    let conn = connect(url)?;
+    let id = match conn.start_job_run(job.name(), now)? {
+        Some(id) => id,
+        None => {
+            log::info!("{} is still running", job.name());
+            return Ok(());
+        }
+    };
+
+    log::info!("Starting {}", job.name());
+    let result = block_on(job.run(url));
+    let finished = Utc::now().timestamp();
+    match result {
+        Ok(()) => {
+            log::info!("Finished {}", job.name());
+            conn.finish_job_run(id, JobStatus::Succeeded, None, finished)
+        }
+        Err(e) => {
+            log::error!("Failed {}: {:?}", job.name(), e);
+            let message = e.to_string();
+            conn.finish_job_run(id, JobStatus::Failed, Some(&message), finished)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job_run(status: JobStatus, started_epoch_second: i64) -> JobRun {
+        JobRun {
+            id: 1,
+            job_name: "job".to_owned(),
+            status: status.as_str().to_owned(),
+            message: None,
+            started_epoch_second,
+            finished_epoch_second: None,
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        assert!(is_due(None, 60, 0));
+        assert!(!is_due(Some(&job_run(JobStatus::Running, 0)), 60, 100));
+        assert!(!is_due(Some(&job_run(JobStatus::Succeeded, 0)), 60, 59));
+        assert!(is_due(Some(&job_run(JobStatus::Failed, 0)), 60, 60));
+    }
+}
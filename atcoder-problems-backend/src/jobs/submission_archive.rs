@@ -0,0 +1,44 @@
+use super::Job;
+use crate::error::Result;
+use crate::sql::{connect, SubmissionArchiveClient};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+
+const YEAR_SECOND: i64 = 365 * 24 * 3600;
+
+/// Moves the submissions older than `after_years` years before `now` into `submissions_archive`,
+/// and returns the number of them. The archived submissions are no longer read by the
+/// aggregates, so that the indexes of `submissions` are kept small.
+pub fn archive_submissions(conn: &PgConnection, after_years: i64, now: i64) -> Result<usize> {
+    let archived = conn.archive_submissions(now - after_years * YEAR_SECOND)?;
+    log::info!("Archived {} submissions", archived);
+    Ok(archived)
+}
+
+/// Archives the old submissions. The archival is disabled if `after_years` is 0.
+pub struct SubmissionArchiveJob {
+    after_years: i64,
+}
+
+impl SubmissionArchiveJob {
+    pub fn new(after_years: i64) -> Self {
+        Self { after_years }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for SubmissionArchiveJob {
+    fn name(&self) -> &str {
+        "submission_archive"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        if self.after_years == 0 {
+            return Ok(());
+        }
+        archive_submissions(&connect(url)?, self.after_years, Utc::now().timestamp())?;
+        Ok(())
+    }
+}
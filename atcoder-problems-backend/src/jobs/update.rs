@@ -0,0 +1,458 @@
+use super::Job;
+use crate::cache::{Cache, DETAILED_PROBLEMS_PREFIX, RANKING_PREFIX, USER_INFO_PREFIX};
+use crate::error::Result;
+use crate::sql::models::{Submission, Watermark};
+use crate::sql::{
+    connect, AcceptedCountClient, AchievementClient, ActiveUserClient, AdvisoryLockClient,
+    ContestCompletionClient, ContestStatsClient, DifficultyCountClient, JobRunClient,
+    LanguageCountClient, LanguageTrendClient, MarathonScoreClient, ProblemInfoUpdater,
+    ProblemsSubmissionUpdater, RankHistoryClient, RatedPointSumClient, ShadowTableClient,
+    SimilarProblemClient, SiteStatsClient, StreakUpdater, SubmissionClient, SubmissionRequest,
+    TableVersionClient, WatermarkClient,
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+use log::{info, warn};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A step of the updaters, which rebuilds one of the aggregate tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateStep {
+    AcceptedCount,
+    SolverCount,
+    SubmissionCount,
+    RatedPointSum,
+    LanguageCount,
+    SubmissionsOfProblems,
+    ProblemPoints,
+    StreakCount,
+    SolveTime,
+    DifficultyCount,
+    LanguageTrend,
+    ContestStats,
+    ContestCompletion,
+    MarathonScore,
+    LengthDistribution,
+    SiteStats,
+    ActiveUsers,
+    SimilarProblems,
+}
+
+impl UpdateStep {
+    /// The steps in the order of `batch_update`.
+    pub const ALL: [UpdateStep; 18] = [
+        UpdateStep::AcceptedCount,
+        UpdateStep::SolverCount,
+        UpdateStep::SubmissionCount,
+        UpdateStep::RatedPointSum,
+        UpdateStep::LanguageCount,
+        UpdateStep::SubmissionsOfProblems,
+        UpdateStep::ProblemPoints,
+        UpdateStep::StreakCount,
+        UpdateStep::SolveTime,
+        UpdateStep::DifficultyCount,
+        UpdateStep::LanguageTrend,
+        UpdateStep::ContestStats,
+        UpdateStep::ContestCompletion,
+        UpdateStep::MarathonScore,
+        UpdateStep::LengthDistribution,
+        UpdateStep::SiteStats,
+        UpdateStep::ActiveUsers,
+        UpdateStep::SimilarProblems,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateStep::AcceptedCount => "accepted_count",
+            UpdateStep::SolverCount => "solver_count",
+            UpdateStep::SubmissionCount => "submission_count",
+            UpdateStep::RatedPointSum => "rated_point_sum",
+            UpdateStep::LanguageCount => "language_count",
+            UpdateStep::SubmissionsOfProblems => "submissions_of_problems",
+            UpdateStep::ProblemPoints => "problem_points",
+            UpdateStep::StreakCount => "streak_count",
+            UpdateStep::SolveTime => "solve_time",
+            UpdateStep::DifficultyCount => "difficulty_count",
+            UpdateStep::LanguageTrend => "language_trend",
+            UpdateStep::ContestStats => "contest_stats",
+            UpdateStep::ContestCompletion => "contest_completion",
+            UpdateStep::MarathonScore => "marathon_score",
+            UpdateStep::LengthDistribution => "length_distribution",
+            UpdateStep::SiteStats => "site_stats",
+            UpdateStep::ActiveUsers => "active_users",
+            UpdateStep::SimilarProblems => "similar_problems",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<UpdateStep> {
+        Self::ALL.iter().copied().find(|step| step.name() == name)
+    }
+
+    /// Whether the step aggregates the AC submissions given to `run`. The other steps aggregate
+    /// in SQL.
+    pub fn uses_accepted_submissions(self) -> bool {
+        match self {
+            UpdateStep::AcceptedCount
+            | UpdateStep::RatedPointSum
+            | UpdateStep::LanguageCount
+            | UpdateStep::StreakCount
+            | UpdateStep::DifficultyCount
+            | UpdateStep::LanguageTrend
+            | UpdateStep::ContestCompletion
+            | UpdateStep::MarathonScore => true,
+            UpdateStep::SolverCount
+            | UpdateStep::SubmissionCount
+            | UpdateStep::SubmissionsOfProblems
+            | UpdateStep::ProblemPoints
+            | UpdateStep::SolveTime
+            | UpdateStep::ContestStats
+            | UpdateStep::LengthDistribution
+            | UpdateStep::SiteStats
+            | UpdateStep::ActiveUsers
+            | UpdateStep::SimilarProblems => false,
+        }
+    }
+
+    /// The tables which the step updates.
+    pub fn table_names(self) -> &'static [&'static str] {
+        match self {
+            UpdateStep::AcceptedCount => &["accepted_count"],
+            UpdateStep::SolverCount => &["solver"],
+            UpdateStep::SubmissionCount => &["submission_count"],
+            UpdateStep::RatedPointSum => &["rated_point_sum"],
+            UpdateStep::LanguageCount => &["language_count"],
+            UpdateStep::SubmissionsOfProblems => {
+                &["first", "first_practice", "fastest", "shortest"]
+            }
+            UpdateStep::ProblemPoints => &["points"],
+            UpdateStep::StreakCount => &["max_streaks"],
+            UpdateStep::SolveTime => &["solve_time"],
+            UpdateStep::DifficultyCount => &["difficulty_count"],
+            UpdateStep::LanguageTrend => &["language_trends"],
+            UpdateStep::ContestStats => &["contest_stats", "contest_problem_stats"],
+            UpdateStep::ContestCompletion => &["contest_completion"],
+            UpdateStep::MarathonScore => &["marathon_best_scores", "marathon_leaderboard"],
+            UpdateStep::LengthDistribution => &["length_distributions"],
+            UpdateStep::SiteStats => &["site_stats"],
+            UpdateStep::ActiveUsers => &["active_users"],
+            UpdateStep::SimilarProblems => &["similar_problems"],
+        }
+    }
+
+    /// The name of the advisory lock held while the step writes its tables.
+    fn lock_name(self) -> String {
+        format!("update_step:{}", self.name())
+    }
+
+    /// Runs the step with the AC submissions sorted by id, and bumps the versions of the updated
+    /// tables. The rows are upserted into the live tables.
+    ///
+    /// The step is skipped if another process is running or rebuilding it, and `false` is
+    /// returned so that the caller can run it for the same users again later.
+    pub fn run(self, conn: &PgConnection, accepted_submissions: &[Submission]) -> Result<bool> {
+        let ran = conn.try_with_advisory_lock(&self.lock_name(), || {
+            self.execute(conn, accepted_submissions)?;
+            conn.bump_table_versions(self.table_names(), Utc::now().timestamp())
+        })?;
+        if ran.is_none() {
+            warn!(
+                "Skipped update_{}, which is running elsewhere.",
+                self.name()
+            );
+        }
+        Ok(ran.is_some())
+    }
+
+    /// Rebuilds the tables of the step from all the AC submissions sorted by id in the shadow
+    /// tables, and swaps them with the live ones. Unlike `run`, the rows which are no longer
+    /// derived from the submissions are dropped.
+    ///
+    /// The rebuild waits for the other processes running the step to finish.
+    pub fn rebuild(self, conn: &PgConnection, accepted_submissions: &[Submission]) -> Result<()> {
+        conn.with_advisory_lock(&self.lock_name(), || {
+            conn.rebuild_in_shadow_tables(self.table_names(), || {
+                self.execute(conn, accepted_submissions)
+            })?;
+            conn.bump_table_versions(self.table_names(), Utc::now().timestamp())
+        })
+    }
+
+    fn execute(self, conn: &PgConnection, accepted_submissions: &[Submission]) -> Result<()> {
+        info!("Executing update_{}...", self.name());
+        match self {
+            UpdateStep::AcceptedCount => conn.update_accepted_count(accepted_submissions),
+            UpdateStep::SolverCount => conn.update_solver_count(),
+            UpdateStep::SubmissionCount => conn.update_submission_count(),
+            UpdateStep::RatedPointSum => conn.update_rated_point_sum(accepted_submissions),
+            UpdateStep::LanguageCount => conn.update_language_count(accepted_submissions),
+            UpdateStep::SubmissionsOfProblems => conn.update_submissions_of_problems(),
+            UpdateStep::ProblemPoints => conn.update_problem_points(),
+            UpdateStep::StreakCount => conn.update_streak_count(accepted_submissions),
+            UpdateStep::SolveTime => conn.update_solve_time(),
+            UpdateStep::DifficultyCount => conn.update_difficulty_count(accepted_submissions),
+            UpdateStep::LanguageTrend => conn.update_language_trends(accepted_submissions),
+            UpdateStep::ContestStats => conn.update_contest_stats(),
+            UpdateStep::ContestCompletion => conn.update_contest_completion(accepted_submissions),
+            UpdateStep::MarathonScore => conn.update_marathon_scores(accepted_submissions),
+            UpdateStep::LengthDistribution => conn.update_length_distribution(),
+            UpdateStep::SiteStats => conn.update_site_stats(Utc::now().timestamp()),
+            UpdateStep::ActiveUsers => conn.update_active_users(),
+            UpdateStep::SimilarProblems => conn.update_similar_problems(),
+        }
+    }
+}
+
+fn load_all_accepted_submissions(conn: &PgConnection) -> Result<Vec<Submission>> {
+    info!("Loading submissions ...");
+    let mut all_accepted_submissions: Vec<Submission> =
+        conn.get_submissions(SubmissionRequest::AllAccepted)?;
+
+    info!(
+        "There are {} AC submissions.",
+        all_accepted_submissions.len()
+    );
+
+    info!("Sorting by id ...");
+    all_accepted_submissions.sort_by_key(|s| s.id);
+    Ok(all_accepted_submissions)
+}
+
+/// Rebuilds all the aggregate tables from all the AC submissions, takes the snapshot of the
+/// ranks of the day, and gives the achievements by the rebuilt tables.
+pub fn batch_update(conn: &PgConnection) -> Result<()> {
+    let all_accepted_submissions = load_all_accepted_submissions(conn)?;
+    for step in UpdateStep::ALL.iter() {
+        step.rebuild(conn, &all_accepted_submissions)?;
+    }
+    let now = Utc::now().timestamp();
+    info!("Recording the rank history ...");
+    conn.record_rank_history(now)?;
+    info!("Updating the achievements ...");
+    conn.update_achievements(now)
+}
+
+/// Rebuilds one of the aggregate tables, as `batch_update` does.
+pub fn run_update_step(conn: &PgConnection, step: UpdateStep) -> Result<()> {
+    let all_accepted_submissions = if step.uses_accepted_submissions() {
+        load_all_accepted_submissions(conn)?
+    } else {
+        vec![]
+    };
+    step.rebuild(conn, &all_accepted_submissions)
+}
+
+/// A tier of the delta updates. The fast tier updates the cheap aggregates every few minutes, and
+/// the slow tier updates the expensive ones, e.g. the language counts and the great submissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateTier {
+    Fast,
+    Slow,
+}
+
+impl UpdateTier {
+    pub const ALL: [UpdateTier; 2] = [UpdateTier::Fast, UpdateTier::Slow];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateTier::Fast => "fast",
+            UpdateTier::Slow => "slow",
+        }
+    }
+
+    pub fn steps(self) -> &'static [UpdateStep] {
+        match self {
+            UpdateTier::Fast => &[
+                UpdateStep::RatedPointSum,
+                UpdateStep::AcceptedCount,
+                UpdateStep::StreakCount,
+                UpdateStep::DifficultyCount,
+                UpdateStep::ContestCompletion,
+                UpdateStep::SubmissionCount,
+                UpdateStep::ActiveUsers,
+                // The site statistics sum the counts above.
+                UpdateStep::SiteStats,
+            ],
+            UpdateTier::Slow => &[
+                UpdateStep::LanguageCount,
+                UpdateStep::SubmissionsOfProblems,
+                UpdateStep::MarathonScore,
+            ],
+        }
+    }
+
+    /// The maximum number of the AC submissions whose users are updated in a run. The slow tier
+    /// loads more, since it runs less often.
+    fn recent_count(self) -> i64 {
+        match self {
+            UpdateTier::Fast => 1000,
+            UpdateTier::Slow => 10000,
+        }
+    }
+}
+
+/// Runs the steps of the tier for the users who have got AC since the last run of the tier, and
+/// records the duration of the run in `update_tier_stats`. The first run of a tier starts from
+/// the recent AC submissions.
+///
+/// The watermark of the tier is kept if any of the steps was skipped, so that the next run
+/// covers the same users again.
+pub fn update_tier(conn: &PgConnection, tier: UpdateTier) -> Result<()> {
+    let started = Instant::now();
+    let watermark_name = format!("update_tier:{}", tier.name());
+    let watermark = conn.load_watermark(&watermark_name)?;
+    info!("Loading submissions ...");
+    let request = match watermark {
+        Some(watermark) => SubmissionRequest::AcceptedAfterId {
+            from_id: watermark.last_submission_id,
+            count: tier.recent_count(),
+        },
+        None => SubmissionRequest::RecentAccepted {
+            count: tier.recent_count(),
+        },
+    };
+    let recent_submissions = conn.get_submissions(request)?;
+    let next_watermark = Watermark::latest(&recent_submissions);
+
+    let user_ids = recent_submissions
+        .into_iter()
+        .map(|s| s.user_id)
+        .collect::<BTreeSet<_>>();
+    let user_ids = user_ids.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    let all_ran = run_steps_of_users(conn, &user_ids, tier.steps())?;
+
+    if !all_ran {
+        warn!(
+            "Keeping the watermark of the {} tier, since some steps were skipped.",
+            tier.name()
+        );
+    } else if let Some(next_watermark) = next_watermark {
+        if !conn.advance_watermark(&watermark_name, watermark.as_ref(), &next_watermark)? {
+            warn!(
+                "The watermark of the {} tier has been moved by another run.",
+                tier.name()
+            );
+        }
+    }
+
+    let duration_millis = started.elapsed().as_millis() as i64;
+    info!(
+        "The {} tier updated {} users in {} ms.",
+        tier.name(),
+        user_ids.len(),
+        duration_millis
+    );
+    conn.record_update_tier_run(
+        tier.name(),
+        user_ids.len() as i32,
+        duration_millis,
+        Utc::now().timestamp(),
+    )
+}
+
+/// Updates the aggregate tables of the users who have recently got AC in all the tiers.
+pub fn delta_update(conn: &PgConnection) -> Result<()> {
+    for tier in UpdateTier::ALL.iter() {
+        update_tier(conn, *tier)?;
+    }
+    Ok(())
+}
+
+/// Updates the aggregate tables of the users from all their AC submissions.
+pub fn update_users(conn: &PgConnection, user_ids: &[&str]) -> Result<()> {
+    let steps = UpdateTier::ALL
+        .iter()
+        .flat_map(|tier| tier.steps().iter().copied())
+        .filter(|step| step.uses_accepted_submissions())
+        .collect::<Vec<_>>();
+    run_steps_of_users(conn, user_ids, &steps)?;
+    Ok(())
+}
+
+/// Runs the steps with the AC submissions of the users, and returns whether all of them ran. The
+/// AC submissions are loaded only if some of the steps aggregate them.
+fn run_steps_of_users(
+    conn: &PgConnection,
+    user_ids: &[&str],
+    steps: &[UpdateStep],
+) -> Result<bool> {
+    let user_accepted_submissions = if steps.iter().any(|step| step.uses_accepted_submissions()) {
+        info!("Loading submissions of {} users ...", user_ids.len());
+        let request = SubmissionRequest::UsersAccepted { user_ids };
+        let mut user_accepted_submissions = conn.get_submissions(request)?;
+        info!("There are {} submissions.", user_accepted_submissions.len());
+
+        info!("Sorting by id ...");
+        user_accepted_submissions.sort_by_key(|s| s.id);
+        user_accepted_submissions
+    } else {
+        vec![]
+    };
+
+    let mut all_ran = true;
+    for step in steps.iter() {
+        all_ran &= step.run(conn, &user_accepted_submissions)?;
+    }
+    Ok(all_ran)
+}
+
+/// Drops the cached responses which are derived from the aggregate tables, so that the servers
+/// sharing the cache serve the updated ones.
+pub async fn invalidate_aggregate_caches(cache: &dyn Cache) -> Result<()> {
+    info!("Invalidating the caches ...");
+    cache.invalidate(USER_INFO_PREFIX).await?;
+    cache.invalidate(RANKING_PREFIX).await?;
+    cache.invalidate(DETAILED_PROBLEMS_PREFIX).await
+}
+
+pub struct BatchUpdateJob {
+    cache: Arc<dyn Cache>,
+}
+
+impl BatchUpdateJob {
+    pub fn new(cache: Arc<dyn Cache>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for BatchUpdateJob {
+    fn name(&self) -> &str {
+        "batch_update"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        batch_update(&connect(url)?)?;
+        invalidate_aggregate_caches(self.cache.as_ref()).await
+    }
+}
+
+/// Runs a tier of the delta updates, so that each tier has its own interval and its own runs.
+pub struct DeltaUpdateJob {
+    tier: UpdateTier,
+    cache: Arc<dyn Cache>,
+}
+
+impl DeltaUpdateJob {
+    pub fn new(tier: UpdateTier, cache: Arc<dyn Cache>) -> Self {
+        Self { tier, cache }
+    }
+}
+
+#[async_trait(?Send)]
+impl Job for DeltaUpdateJob {
+    fn name(&self) -> &str {
+        match self.tier {
+            UpdateTier::Fast => "delta_update",
+            UpdateTier::Slow => "slow_delta_update",
+        }
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        update_tier(&connect(url)?, self.tier)?;
+        invalidate_aggregate_caches(self.cache.as_ref()).await
+    }
+}
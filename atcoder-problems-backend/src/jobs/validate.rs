@@ -0,0 +1,43 @@
+use super::Job;
+use crate::error::Result;
+use crate::sql::models::ValidationViolation;
+use crate::sql::{connect, ValidationClient};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+use std::collections::BTreeMap;
+
+/// Checks the invariants of the aggregate tables, and replaces the stored violations with the
+/// found ones. The number of the violations of each check is logged, so that an aggregation bug
+/// is noticed before the users do.
+pub fn validate(conn: &PgConnection, now: i64) -> Result<Vec<ValidationViolation>> {
+    let violations = conn.find_violations(now)?;
+    conn.save_violations(&violations)?;
+
+    let counts = violations
+        .iter()
+        .fold(BTreeMap::new(), |mut counts, violation| {
+            *counts.entry(violation.check_name.as_str()).or_insert(0) += 1;
+            counts
+        });
+    for (check_name, count) in counts.iter() {
+        log::warn!("{} violations of {}", count, check_name);
+    }
+    log::info!("Found {} violations", violations.len());
+    Ok(violations)
+}
+
+pub struct ValidationJob;
+
+#[async_trait(?Send)]
+impl Job for ValidationJob {
+    fn name(&self) -> &str {
+        "validator"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        validate(&connect(url)?, Utc::now().timestamp())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use super::Job;
+use crate::error::Result;
+use crate::estimation::{estimate_performance, simulate_rating};
+use crate::server::standings::{
+    compute_standings, scoring_strategy, AtCoderScoring, StandingsEntry,
+};
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::internal::virtual_contest_result_manager::{
+    VirtualContestResult, VirtualContestResultManager,
+};
+use crate::sql::{connect, ProblemModelClient, SubmissionClient, SubmissionRequest};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::PgConnection;
+
+/// Archives the results of the virtual contests which have ended by `now`, and returns the number
+/// of the archived contests. A contest is archived only once, so that the results are frozen even
+/// if the submissions or the difficulties change later.
+pub fn archive_virtual_contests(conn: &PgConnection, now: i64) -> Result<usize> {
+    let contest_ids = conn.get_contests_to_archive(now)?;
+    for contest_id in contest_ids.iter() {
+        let results = compute_results(conn, contest_id)?;
+        conn.save_results(contest_id, &results, now)?;
+    }
+    log::info!("Archived {} virtual contests", contest_ids.len());
+    Ok(contest_ids.len())
+}
+
+/// Computes the individual standings of the participants, and estimates the performance of each
+/// participant from the problems with the difficulty.
+fn compute_results(conn: &PgConnection, contest_id: &str) -> Result<Vec<VirtualContestResult>> {
+    let info = conn.get_single_contest_info(contest_id)?;
+    let contest = conn.get_single_contest(contest_id)?;
+
+    let user_ids = contest
+        .participants
+        .iter()
+        .map(|user_id| user_id.as_str())
+        .collect::<Vec<_>>();
+    let problem_ids = contest
+        .problems
+        .iter()
+        .map(|p| p.id.as_str())
+        .collect::<Vec<_>>();
+    let end_epoch_second = contest.start_epoch_second + contest.duration_second;
+    let submissions = conn.get_submissions(SubmissionRequest::UsersProblemsTime {
+        user_ids: &user_ids,
+        problem_ids: &problem_ids,
+        from_second: contest.start_epoch_second,
+        to_second: end_epoch_second,
+    })?;
+    let difficulties = conn.load_difficulties(&problem_ids)?;
+    let ratings = conn.load_predicted_ratings(&user_ids)?;
+
+    let entries = contest
+        .participants
+        .iter()
+        .map(|user_id| StandingsEntry {
+            id: user_id.clone(),
+            name: user_id.clone(),
+            members: vec![user_id.clone()],
+        })
+        .collect();
+    let scoring =
+        scoring_strategy(info.scoring.as_deref()).unwrap_or_else(|| Box::new(AtCoderScoring));
+    let rows = compute_standings(
+        scoring.as_ref(),
+        entries,
+        &contest.problems,
+        contest.start_epoch_second,
+        end_epoch_second,
+        &submissions,
+    )?;
+
+    let results = rows
+        .iter()
+        .map(|row| {
+            let rank = rows
+                .iter()
+                .take_while(|other| {
+                    other.score > row.score
+                        || (other.score == row.score && other.penalty_second < row.penalty_second)
+                })
+                .count() as i64;
+            let solved_count = row.problems.values().filter(|r| r.accepted).count() as i32;
+            let problem_results = difficulties
+                .iter()
+                .map(|(problem_id, &difficulty)| {
+                    let solved = row
+                        .problems
+                        .get(problem_id)
+                        .map(|r| r.accepted)
+                        .unwrap_or(false);
+                    (difficulty, solved)
+                })
+                .collect::<Vec<_>>();
+            let performance = estimate_performance(&problem_results);
+            let simulated_rating = performance
+                .map(|performance| simulate_rating(ratings.get(&row.id).cloned(), performance));
+            VirtualContestResult {
+                user_id: row.id.clone(),
+                rank,
+                score: row.score,
+                penalty_second: row.penalty_second,
+                solved_count,
+                performance,
+                simulated_rating,
+            }
+        })
+        .collect();
+    Ok(results)
+}
+
+pub struct VirtualContestArchiveJob;
+
+#[async_trait(?Send)]
+impl Job for VirtualContestArchiveJob {
+    fn name(&self) -> &str {
+        "virtual_contest_archive"
+    }
+
+    async fn run(&self, url: &str) -> Result<()> {
+        archive_virtual_contests(&connect(url)?, Utc::now().timestamp())?;
+        Ok(())
+    }
+}
@@ -1,9 +1,21 @@
 #[macro_use]
 extern crate diesel;
 
+pub mod bot;
+pub mod cache;
+pub mod config;
 pub mod crawler;
 pub mod error;
-pub mod s3;
+pub mod estimation;
+pub mod feature_flags;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod jobs;
+pub mod loadtest;
+pub mod notification;
+pub mod research_dataset;
 pub mod server;
+pub mod shutdown;
 pub mod sql;
+pub mod storage;
 pub mod utils;
@@ -0,0 +1,249 @@
+use crate::error::Result;
+use crate::server::request_analytics::normalize_route;
+
+use async_std::task;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A path of the recorded requests and how many times it was requested, e.g.
+/// `{"path": "/atcoder-api/v3/user/ac_rank?user=kenkoooo", "count": 12}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecordedRequest {
+    pub path: String,
+    pub count: i64,
+}
+
+/// Parses the recording, which has a `RecordedRequest` in JSON in each line.
+pub fn parse_recording(recording: &str) -> Result<Vec<RecordedRequest>> {
+    let mut requests = vec![];
+    for line in recording.lines().filter(|line| !line.trim().is_empty()) {
+        requests.push(serde_json::from_str(line)?);
+    }
+    Ok(requests)
+}
+
+/// Orders `total` requests so that each path is requested in proportion to its recorded count.
+/// The order is interleaved by the smooth weighted round-robin rather than random, so that the
+/// runs of the same recording send the same requests in the same order.
+pub fn schedule(recording: &[RecordedRequest], total: usize) -> Vec<String> {
+    let recording = recording
+        .iter()
+        .filter(|request| request.count > 0)
+        .collect::<Vec<_>>();
+    let total_count = recording.iter().map(|request| request.count).sum::<i64>();
+    let mut current = vec![0; recording.len()];
+    let mut paths = Vec::with_capacity(total);
+    while !recording.is_empty() && paths.len() < total {
+        let mut next = 0;
+        for (i, request) in recording.iter().enumerate() {
+            current[i] += request.count;
+            if current[i] > current[next] {
+                next = i;
+            }
+        }
+        current[next] -= total_count;
+        paths.push(recording[next].path.clone());
+    }
+    paths
+}
+
+/// The latency of a request. The requests failed to connect or responded with 5xx are failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub route: String,
+    pub latency: Duration,
+    pub failed: bool,
+}
+
+/// The latency percentiles of the requests of a route, which is the path with the identifiers
+/// replaced as in the request analytics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointLatency {
+    pub route: String,
+    pub request_count: usize,
+    pub failure_count: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+pub fn summarize(samples: &[Sample]) -> Vec<EndpointLatency> {
+    let mut routes = BTreeMap::new();
+    for sample in samples.iter() {
+        routes
+            .entry(sample.route.as_str())
+            .or_insert_with(Vec::new)
+            .push(sample);
+    }
+    routes
+        .into_iter()
+        .map(|(route, samples)| {
+            let mut latencies = samples.iter().map(|s| s.latency).collect::<Vec<_>>();
+            latencies.sort();
+            EndpointLatency {
+                route: route.to_owned(),
+                request_count: samples.len(),
+                failure_count: samples.iter().filter(|s| s.failed).count(),
+                p50: percentile(&latencies, 50),
+                p90: percentile(&latencies, 90),
+                p99: percentile(&latencies, 99),
+                max: latencies.last().copied().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// The nearest-rank percentile of the sorted latencies.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::default();
+    }
+    let rank = (sorted.len() * p + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+/// Sends the requests to the server with `concurrency` requests in flight at a time.
+pub struct LoadTester {
+    base_url: String,
+    concurrency: usize,
+}
+
+impl LoadTester {
+    pub fn new(base_url: &str, concurrency: usize) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    pub async fn run(&self, paths: Vec<String>) -> Vec<Sample> {
+        let queue = Arc::new(Mutex::new(paths.into_iter()));
+        let workers = (0..self.concurrency)
+            .map(|_| {
+                let queue = queue.clone();
+                let base_url = self.base_url.clone();
+                task::spawn(async move {
+                    let mut samples = vec![];
+                    loop {
+                        let path = match queue.lock().unwrap().next() {
+                            Some(path) => path,
+                            None => break,
+                        };
+                        samples.push(send(&base_url, &path).await);
+                    }
+                    samples
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut samples = vec![];
+        for worker in workers.into_iter() {
+            samples.extend(worker.await);
+        }
+        samples
+    }
+}
+
+async fn send(base_url: &str, path: &str) -> Sample {
+    let route = normalize_route(path.split('?').next().unwrap_or_default());
+    let started = Instant::now();
+    let result: Result<_> = async {
+        let mut response = surf::get(format!("{}{}", base_url, path)).await?;
+        response.body_bytes().await?;
+        Ok(response.status())
+    }
+    .await;
+    let latency = started.elapsed();
+    let failed = match result {
+        Ok(status) => status.is_server_error(),
+        Err(e) => {
+            log::warn!("Failed to request {}: {:?}", path, e);
+            true
+        }
+    };
+    Sample {
+        route,
+        latency,
+        failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(path: &str, count: i64) -> RecordedRequest {
+        RecordedRequest {
+            path: path.to_owned(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_parse_recording() {
+        let recording = r#"
+{"path": "/atcoder-api/v3/user/ac_rank?user=a", "count": 3}
+
+{"path": "/resources/contests.json", "count": 1}
+"#;
+        assert_eq!(
+            parse_recording(recording).unwrap(),
+            vec![
+                recorded("/atcoder-api/v3/user/ac_rank?user=a", 3),
+                recorded("/resources/contests.json", 1),
+            ]
+        );
+        assert!(parse_recording("{\"path\": \"/\"}").is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let recording = vec![recorded("/a", 3), recorded("/b", 1), recorded("/c", 0)];
+        assert_eq!(
+            schedule(&recording, 8),
+            vec!["/a", "/a", "/b", "/a", "/a", "/a", "/b", "/a"]
+        );
+        assert!(schedule(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let sample = |route: &str, millis: u64, failed: bool| Sample {
+            route: route.to_owned(),
+            latency: Duration::from_millis(millis),
+            failed,
+        };
+        let mut samples = (1..=100)
+            .map(|millis| sample("/a/:id", millis, millis == 100))
+            .collect::<Vec<_>>();
+        samples.push(sample("/b", 7, false));
+
+        let latencies = summarize(&samples);
+        assert_eq!(
+            latencies,
+            vec![
+                EndpointLatency {
+                    route: "/a/:id".to_owned(),
+                    request_count: 100,
+                    failure_count: 1,
+                    p50: Duration::from_millis(50),
+                    p90: Duration::from_millis(90),
+                    p99: Duration::from_millis(99),
+                    max: Duration::from_millis(100),
+                },
+                EndpointLatency {
+                    route: "/b".to_owned(),
+                    request_count: 1,
+                    failure_count: 0,
+                    p50: Duration::from_millis(7),
+                    p90: Duration::from_millis(7),
+                    p99: Duration::from_millis(7),
+                    max: Duration::from_millis(7),
+                },
+            ]
+        );
+    }
+}
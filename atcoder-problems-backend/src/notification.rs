@@ -0,0 +1,11 @@
+mod digest;
+mod dispatcher;
+mod email;
+mod feeder;
+mod formatter;
+mod signature;
+
+pub use digest::DailyDigestGenerator;
+pub use dispatcher::{HttpWebhookSender, NotificationDispatcher, WebhookSender, SIGNATURE_HEADER};
+pub use email::{EmailChannel, EmailSender, SesEmailSender};
+pub use feeder::{NotificationFeeder, NOTIFICATION_WATERMARK};
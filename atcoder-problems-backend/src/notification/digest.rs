@@ -0,0 +1,251 @@
+use super::feeder::{get_first_ac, jst_day, to_deliveries, JST_OFFSET_SECOND, ONE_DAY_SECOND};
+use crate::error::Result;
+use crate::sql::internal::notification_manager::{EventType, NotificationManager};
+use crate::sql::internal::watch_list_manager::WatchListManager;
+use crate::sql::models::Submission;
+use crate::sql::{ProblemModelClient, SubmissionClient, SubmissionRequest};
+
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Generates the digests of the previous day in JST for the users subscribing
+/// `EventType::DailyDigest`, and enqueues them to the webhooks.
+pub struct DailyDigestGenerator<C> {
+    db: C,
+}
+
+impl<C> DailyDigestGenerator<C>
+where
+    C: SubmissionClient + ProblemModelClient + NotificationManager + WatchListManager,
+{
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    /// Generates the digests of the users which have not been generated for the previous day of
+    /// `now`, and returns the number of the enqueued notifications. It can run any number of
+    /// times a day, since each digest is generated only once.
+    pub fn generate(&self, now: i64) -> Result<usize> {
+        let day = jst_day(now) - 1;
+        let day_epoch_second = day * ONE_DAY_SECOND - JST_OFFSET_SECOND;
+
+        let subscriptions = self.db.get_subscriptions()?;
+        let mut user_subscriptions = BTreeMap::new();
+        for subscription in subscriptions
+            .iter()
+            .filter(|s| s.subscribes(EventType::DailyDigest))
+        {
+            user_subscriptions
+                .entry(subscription.atcoder_user_id.as_str())
+                .or_insert_with(Vec::new)
+                .push(subscription);
+        }
+        if user_subscriptions.is_empty() {
+            return Ok(0);
+        }
+
+        let difficulties = self
+            .db
+            .load_problem_models()?
+            .into_iter()
+            .filter_map(|model| Some((model.problem_id, model.difficulty?)))
+            .filter(|(_, difficulty)| difficulty.is_finite())
+            .collect::<BTreeMap<_, _>>();
+
+        let mut pending_users = vec![];
+        for (user_id, subscriptions) in user_subscriptions.into_iter() {
+            if self
+                .db
+                .get_daily_digest(user_id, day_epoch_second)?
+                .is_some()
+            {
+                continue;
+            }
+            let mut rival_ids = BTreeSet::new();
+            for subscription in subscriptions.iter() {
+                rival_ids.extend(self.db.get_watched_users(&subscription.internal_user_id)?);
+            }
+            rival_ids.remove(user_id);
+            pending_users.push((user_id, subscriptions, rival_ids));
+        }
+
+        // The AC submissions of all the users and their rivals are loaded at once, rather than
+        // once for each of them.
+        let user_ids = pending_users
+            .iter()
+            .flat_map(|(user_id, _, rival_ids)| {
+                std::iter::once(*user_id).chain(rival_ids.iter().map(|s| s.as_str()))
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let accepted = if user_ids.is_empty() {
+            vec![]
+        } else {
+            self.db.get_submissions(SubmissionRequest::UsersAccepted {
+                user_ids: &user_ids,
+            })?
+        };
+        let mut user_accepted = BTreeMap::new();
+        for submission in accepted.into_iter() {
+            user_accepted
+                .entry(submission.user_id.clone())
+                .or_insert_with(Vec::new)
+                .push(submission);
+        }
+
+        let mut deliveries = vec![];
+        for (user_id, subscriptions, rival_ids) in pending_users.into_iter() {
+            let digest = generate_digest(user_id, &rival_ids, day, &difficulties, &user_accepted);
+            let payload = json!({
+                "event": EventType::DailyDigest.as_str(),
+                "user_id": user_id,
+                "epoch_second": day_epoch_second,
+                "solved_problem_ids": digest.solved_problem_ids,
+                "streak": digest.streak,
+                "rivals": digest
+                    .rival_solved_counts
+                    .iter()
+                    .map(|(rival_id, count)| json!({"user_id": rival_id, "solved_count": count}))
+                    .collect::<Vec<_>>(),
+                "recommended_problem_id": digest.recommended_problem_id,
+            });
+            if self
+                .db
+                .save_daily_digest(user_id, day_epoch_second, &payload.to_string())?
+            {
+                deliveries.extend(to_deliveries(
+                    &subscriptions,
+                    EventType::DailyDigest,
+                    &payload,
+                ));
+            }
+        }
+
+        let count = deliveries.len();
+        if count > 0 {
+            let deliveries = deliveries
+                .into_iter()
+                .map(|(webhook_id, event_type, payload)| {
+                    (webhook_id, event_type, payload.to_string())
+                })
+                .collect::<Vec<_>>();
+            self.db.enqueue_deliveries(&deliveries, now)?;
+        }
+        Ok(count)
+    }
+}
+
+/// Generates the digest of the user from the AC submissions of the user and the rivals.
+fn generate_digest(
+    user_id: &str,
+    rival_ids: &BTreeSet<String>,
+    day: i64,
+    difficulties: &BTreeMap<String, f64>,
+    user_accepted: &BTreeMap<String, Vec<Submission>>,
+) -> Digest {
+    let accepted = |user_id: &str| {
+        user_accepted
+            .get(user_id)
+            .map(|submissions| submissions.as_slice())
+            .unwrap_or(&[])
+    };
+    let first_ac = get_first_ac(accepted(user_id));
+    let solved_problem_ids = solved_on(&first_ac, day);
+    let solved_days = first_ac
+        .values()
+        .map(|s| jst_day(s.epoch_second))
+        .collect::<BTreeSet<_>>();
+    let streak = (0..)
+        .take_while(|i| solved_days.contains(&(day - i)))
+        .count() as i64;
+
+    let rival_solved_counts = rival_ids
+        .iter()
+        .map(|rival_id| {
+            let count = solved_on(&get_first_ac(accepted(rival_id)), day).len();
+            (rival_id.clone(), count)
+        })
+        .collect();
+
+    Digest {
+        solved_problem_ids,
+        streak,
+        rival_solved_counts,
+        recommended_problem_id: recommend(&first_ac, difficulties),
+    }
+}
+
+struct Digest {
+    solved_problem_ids: Vec<String>,
+    streak: i64,
+    rival_solved_counts: Vec<(String, usize)>,
+    recommended_problem_id: Option<String>,
+}
+
+/// Returns the problems which were solved for the first time on the day.
+fn solved_on(first_ac: &BTreeMap<&str, &Submission>, day: i64) -> Vec<String> {
+    first_ac
+        .iter()
+        .filter(|(_, s)| jst_day(s.epoch_second) == day)
+        .map(|(&problem_id, _)| problem_id.to_owned())
+        .collect()
+}
+
+/// Recommends the easiest unsolved problem which is not easier than the hardest solved one, or
+/// the easiest unsolved problem if the user has solved none of the estimated problems.
+fn recommend(
+    first_ac: &BTreeMap<&str, &Submission>,
+    difficulties: &BTreeMap<String, f64>,
+) -> Option<String> {
+    let hardest = first_ac
+        .keys()
+        .filter_map(|&problem_id| difficulties.get(problem_id))
+        .cloned()
+        .fold(None, |max: Option<f64>, d| {
+            Some(max.map_or(d, |max| max.max(d)))
+        });
+    difficulties
+        .iter()
+        .filter(|(problem_id, &d)| {
+            !first_ac.contains_key(problem_id.as_str()) && hardest.map_or(true, |h| d >= h)
+        })
+        .min_by(|(a_id, a), (b_id, b)| a.total_cmp(b).then_with(|| a_id.cmp(b_id)))
+        .map(|(problem_id, _)| problem_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommend() {
+        let submissions = vec!["p1", "p2"]
+            .into_iter()
+            .map(|problem_id| Submission {
+                problem_id: problem_id.to_owned(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let first_ac = get_first_ac(&submissions);
+        let difficulties = vec![
+            ("p1", 800.0),
+            ("p2", 400.0),
+            ("p3", 300.0),
+            ("p4", 1200.0),
+            ("p5", 900.0),
+        ]
+        .into_iter()
+        .map(|(problem_id, d)| (problem_id.to_owned(), d))
+        .collect();
+        assert_eq!(recommend(&first_ac, &difficulties), Some("p5".to_owned()));
+        assert_eq!(
+            recommend(&BTreeMap::new(), &difficulties),
+            Some("p3".to_owned())
+        );
+
+        let mut difficulties = difficulties;
+        difficulties.insert("p0".to_owned(), f64::NAN);
+        assert_eq!(recommend(&first_ac, &difficulties), Some("p5".to_owned()));
+    }
+}
@@ -0,0 +1,138 @@
+use super::email::EmailChannel;
+use super::formatter::{format_body, format_email};
+use crate::error::Result;
+use crate::sql::internal::notification_manager::{
+    NotificationManager, PendingDelivery, WebhookFormat, EMAIL_URL_PREFIX,
+};
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+
+/// The receivers verify the payload with the HMAC-SHA256 of it keyed by the secret.
+pub const SIGNATURE_HEADER: &str = "X-AtCoder-Problems-Signature";
+
+const DISPATCH_BATCH_SIZE: i64 = 100;
+const MAX_ATTEMPT_COUNT: i32 = 5;
+const RETRY_INTERVAL_SECOND: i64 = 60;
+
+#[async_trait]
+pub trait WebhookSender {
+    async fn send(&self, url: &str, signature: &str, body: &str) -> Result<()>;
+}
+
+pub struct HttpWebhookSender;
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, url: &str, signature: &str, body: &str) -> Result<()> {
+        let response = surf::post(url)
+            .set_header("content-type", "application/json")
+            .set_header(SIGNATURE_HEADER, signature)
+            .body_string(body.to_owned())
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(http_types::Error::from_str(
+                response.status(),
+                "The webhook responded with an error.",
+            ))
+        }
+    }
+}
+
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_varkey(secret.as_bytes()).expect("HMAC accepts keys of any size.");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delivers the enqueued notifications in the format of each webhook. Failed deliveries are
+/// retried with exponential backoff until `MAX_ATTEMPT_COUNT` attempts.
+pub struct NotificationDispatcher<C, S> {
+    db: C,
+    sender: S,
+    email: Option<EmailChannel>,
+}
+
+impl<C, S> NotificationDispatcher<C, S>
+where
+    C: NotificationManager,
+    S: WebhookSender,
+{
+    pub fn new(db: C, sender: S) -> Self {
+        Self {
+            db,
+            sender,
+            email: None,
+        }
+    }
+
+    /// Enables the deliveries to the email addresses. They fail without it.
+    pub fn with_email(mut self, email: EmailChannel) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Returns the number of the delivered notifications.
+    pub async fn dispatch(&self, now: i64) -> Result<usize> {
+        let deliveries = self.db.get_due_deliveries(now, DISPATCH_BATCH_SIZE)?;
+        let mut delivered = 0;
+        for delivery in deliveries.iter() {
+            match self.deliver(delivery).await {
+                Ok(()) => {
+                    self.db.complete_delivery(delivery.id, now)?;
+                    delivered += 1;
+                }
+                Err(e) => {
+                    log::error!("Failed to deliver {}: {:?}", delivery.id, e);
+                    let attempt_count = delivery.attempt_count + 1;
+                    let next_attempt_epoch_second = if attempt_count >= MAX_ATTEMPT_COUNT {
+                        None
+                    } else {
+                        Some(now + (RETRY_INTERVAL_SECOND << (attempt_count - 1)))
+                    };
+                    self.db
+                        .fail_delivery(delivery.id, next_attempt_epoch_second)?;
+                }
+            }
+        }
+        Ok(delivered)
+    }
+
+    async fn deliver(&self, delivery: &PendingDelivery) -> Result<()> {
+        let format = WebhookFormat::parse(&delivery.format).unwrap_or(WebhookFormat::Json);
+        if format == WebhookFormat::Email {
+            return self.deliver_email(delivery).await;
+        }
+        let body = format_body(format, &delivery.payload)?;
+        let signature = sign(&delivery.secret, &body);
+        self.sender.send(&delivery.url, &signature, &body).await
+    }
+
+    async fn deliver_email(&self, delivery: &PendingDelivery) -> Result<()> {
+        let email = self.email.as_ref().ok_or_else(|| {
+            http_types::Error::from_str(500, "The email deliveries are not enabled.")
+        })?;
+        let address = delivery.url.trim_start_matches(EMAIL_URL_PREFIX);
+        // The secret of an email destination is its unsubscribe token.
+        let (subject, html) = format_email(&delivery.payload, &email.api_url, &delivery.secret)?;
+        email.sender.send(address, &subject, &html).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
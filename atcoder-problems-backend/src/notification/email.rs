@@ -0,0 +1,104 @@
+use super::signature::{SignedRequest, LONG_DATETIME};
+use crate::error::Result;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::env;
+
+const SES_SEND_EMAIL_PATH: &str = "/v2/email/outbound-emails";
+
+#[async_trait]
+pub trait EmailSender {
+    async fn send(&self, address: &str, subject: &str, html: &str) -> Result<()>;
+}
+
+/// Sends the emails with the SendEmail API of Amazon SES. The credentials are read from
+/// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.
+pub struct SesEmailSender {
+    region: String,
+    from_address: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+impl SesEmailSender {
+    pub fn from_env(region: &str, from_address: &str) -> Result<Self> {
+        Ok(Self {
+            region: region.to_owned(),
+            from_address: from_address.to_owned(),
+            access_key: env::var("AWS_ACCESS_KEY_ID")?,
+            secret_key: env::var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+
+    fn host(&self) -> String {
+        format!("email.{}.amazonaws.com", self.region)
+    }
+}
+
+#[async_trait]
+impl EmailSender for SesEmailSender {
+    async fn send(&self, address: &str, subject: &str, html: &str) -> Result<()> {
+        let body = json!({
+            "FromEmailAddress": self.from_address,
+            "Destination": { "ToAddresses": [address] },
+            "Content": {
+                "Simple": {
+                    "Subject": { "Data": subject, "Charset": "UTF-8" },
+                    "Body": { "Html": { "Data": html, "Charset": "UTF-8" } },
+                }
+            },
+        })
+        .to_string();
+
+        let now = Utc::now();
+        let mut headers = vec![
+            ("content-type", "application/json".to_owned()),
+            ("host", self.host()),
+            (
+                "x-amz-content-sha256",
+                hex::encode(Sha256::digest(body.as_bytes())),
+            ),
+            ("x-amz-date", now.format(LONG_DATETIME).to_string()),
+        ];
+        if let Some(token) = self.session_token.as_ref() {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let authorization = SignedRequest {
+            service: "ses",
+            method: "POST",
+            uri: SES_SEND_EMAIL_PATH,
+            query: "",
+            headers: &headers,
+        }
+        .authorization(&self.access_key, &self.secret_key, &self.region, &now);
+
+        let mut request = surf::post(format!("https://{}{}", self.host(), SES_SEND_EMAIL_PATH));
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.set_header(*name, value.as_str());
+        }
+        let response = request
+            .set_header("authorization", authorization)
+            .body_string(body)
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(http_types::Error::from_str(
+                response.status(),
+                "SES rejected the email.",
+            ))
+        }
+    }
+}
+
+/// The email destinations of the notifications. `api_url` is the URL of the API server, where
+/// the links to verify and to unsubscribe the addresses point.
+pub struct EmailChannel {
+    pub sender: Box<dyn EmailSender + Send + Sync>,
+    pub api_url: String,
+}
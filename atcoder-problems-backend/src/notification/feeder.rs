@@ -0,0 +1,284 @@
+use crate::error::Result;
+use crate::sql::internal::notification_manager::{EventType, NotificationManager, Subscription};
+use crate::sql::models::{Submission, Watermark};
+use crate::sql::{
+    ProblemModelClient, SimpleClient, SubmissionClient, SubmissionRequest, WatermarkClient,
+};
+
+use log::warn;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+const FEED_BATCH_SIZE: i64 = 1000;
+pub const NOTIFICATION_WATERMARK: &str = "notification";
+const STREAK_MILESTONES: [i64; 8] = [7, 30, 50, 100, 200, 365, 500, 1000];
+pub(super) const JST_OFFSET_SECOND: i64 = 9 * 3600;
+pub(super) const ONE_DAY_SECOND: i64 = 24 * 3600;
+
+type UserSubscriptions<'a> = BTreeMap<&'a str, Vec<&'a Subscription>>;
+type Delivery = (String, EventType, Value);
+
+/// Generates the notifications from the AC submissions stored by the crawlers.
+pub struct NotificationFeeder<C> {
+    db: C,
+}
+
+impl<C> NotificationFeeder<C>
+where
+    C: SubmissionClient + SimpleClient + ProblemModelClient + NotificationManager + WatermarkClient,
+{
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    /// Enqueues the notifications of the AC submissions stored since the last run, and returns
+    /// the number of them.
+    pub fn feed(&self, now: i64) -> Result<usize> {
+        let watermark = match self.db.load_watermark(NOTIFICATION_WATERMARK)? {
+            Some(watermark) => watermark,
+            None => {
+                // Starts from the latest submission instead of notifying the whole history.
+                let latest = self
+                    .db
+                    .get_submissions(SubmissionRequest::RecentAll { count: 1 })?;
+                let latest = Watermark::latest(&latest).unwrap_or(Watermark {
+                    last_epoch_second: 0,
+                    last_submission_id: 0,
+                });
+                self.db
+                    .advance_watermark(NOTIFICATION_WATERMARK, None, &latest)?;
+                return Ok(0);
+            }
+        };
+        let submissions = self
+            .db
+            .get_submissions(SubmissionRequest::AcceptedAfterId {
+                from_id: watermark.last_submission_id,
+                count: FEED_BATCH_SIZE,
+            })?;
+        let next_watermark = match Watermark::latest(&submissions) {
+            Some(next_watermark) => next_watermark,
+            None => return Ok(0),
+        };
+
+        let subscriptions = self.db.get_subscriptions()?;
+        let user_subscriptions =
+            subscriptions
+                .iter()
+                .fold(UserSubscriptions::new(), |mut map, subscription| {
+                    map.entry(subscription.atcoder_user_id.as_str())
+                        .or_insert_with(Vec::new)
+                        .push(subscription);
+                    map
+                });
+
+        let mut deliveries = self.feed_accepted(&submissions, &user_subscriptions)?;
+        deliveries.extend(self.feed_shortest(&submissions, &user_subscriptions)?);
+        let count = deliveries.len();
+        if count > 0 {
+            let deliveries = self.attach_problem_info(deliveries)?;
+            self.db.enqueue_deliveries(&deliveries, now)?;
+        }
+        if !self
+            .db
+            .advance_watermark(NOTIFICATION_WATERMARK, Some(&watermark), &next_watermark)?
+        {
+            warn!("The notification watermark has been moved by another feeder.");
+        }
+        Ok(count)
+    }
+
+    /// Attaches the titles and the difficulties of the problems for the formatters.
+    fn attach_problem_info(
+        &self,
+        deliveries: Vec<Delivery>,
+    ) -> Result<Vec<(String, EventType, String)>> {
+        let titles = self
+            .db
+            .load_problems()?
+            .into_iter()
+            .map(|problem| (problem.id, problem.title))
+            .collect::<BTreeMap<_, _>>();
+        let difficulties = self
+            .db
+            .load_problem_models()?
+            .into_iter()
+            .map(|model| (model.problem_id, model.difficulty))
+            .collect::<BTreeMap<_, _>>();
+        let deliveries = deliveries
+            .into_iter()
+            .map(|(webhook_id, event_type, mut payload)| {
+                let problem_id = payload["problem_id"].as_str().unwrap_or_default();
+                let title = titles.get(problem_id).cloned();
+                let difficulty = difficulties.get(problem_id).cloned().flatten();
+                payload["problem_title"] = json!(title);
+                payload["difficulty"] = json!(difficulty);
+                (webhook_id, event_type, payload.to_string())
+            })
+            .collect();
+        Ok(deliveries)
+    }
+
+    fn feed_accepted(
+        &self,
+        submissions: &[Submission],
+        user_subscriptions: &UserSubscriptions,
+    ) -> Result<Vec<Delivery>> {
+        let user_ids = submissions
+            .iter()
+            .map(|s| s.user_id.as_str())
+            .filter(|user_id| {
+                user_subscriptions
+                    .get(user_id)
+                    .map(|subscriptions| {
+                        subscriptions.iter().any(|s| {
+                            s.subscribes(EventType::NewAc)
+                                || s.subscribes(EventType::StreakMilestone)
+                        })
+                    })
+                    .unwrap_or(false)
+            })
+            .collect::<BTreeSet<_>>();
+
+        let mut deliveries = vec![];
+        for user_id in user_ids.into_iter() {
+            let accepted = self.db.get_submissions(SubmissionRequest::UsersAccepted {
+                user_ids: &[user_id],
+            })?;
+            let first_ac = get_first_ac(&accepted);
+
+            // The first AC submission of each day, which extends the streak.
+            let mut first_of_days = BTreeMap::new();
+            for s in first_ac.values() {
+                let first = first_of_days.entry(jst_day(s.epoch_second)).or_insert(*s);
+                if (s.epoch_second, s.id) < (first.epoch_second, first.id) {
+                    *first = *s;
+                }
+            }
+
+            let subscriptions = &user_subscriptions[user_id];
+            for s in submissions.iter().filter(|s| s.user_id == user_id) {
+                if first_ac.get(s.problem_id.as_str()).map(|f| f.id) != Some(s.id) {
+                    continue;
+                }
+                let payload = submission_payload(EventType::NewAc, s);
+                deliveries.extend(to_deliveries(subscriptions, EventType::NewAc, &payload));
+
+                let day = jst_day(s.epoch_second);
+                if first_of_days[&day].id != s.id {
+                    continue;
+                }
+                let streak = (0..)
+                    .take_while(|i| first_of_days.contains_key(&(day - i)))
+                    .count() as i64;
+                if STREAK_MILESTONES.contains(&streak) {
+                    let mut payload = submission_payload(EventType::StreakMilestone, s);
+                    payload["streak"] = json!(streak);
+                    deliveries.extend(to_deliveries(
+                        subscriptions,
+                        EventType::StreakMilestone,
+                        &payload,
+                    ));
+                }
+            }
+        }
+        Ok(deliveries)
+    }
+
+    fn feed_shortest(
+        &self,
+        submissions: &[Submission],
+        user_subscriptions: &UserSubscriptions,
+    ) -> Result<Vec<Delivery>> {
+        // The shortest new submission of each problem.
+        let mut new_shortest = BTreeMap::new();
+        for s in submissions.iter() {
+            let shortest = new_shortest.entry(s.problem_id.as_str()).or_insert(s);
+            if s.length < shortest.length {
+                *shortest = s;
+            }
+        }
+        let problem_ids = new_shortest.keys().cloned().collect::<Vec<_>>();
+        let current_shortest = self.db.get_submissions(SubmissionRequest::Shortest {
+            problem_ids: &problem_ids,
+        })?;
+
+        let mut deliveries = vec![];
+        for current in current_shortest.iter() {
+            let s = match new_shortest.get(current.problem_id.as_str()) {
+                Some(s) if s.user_id != current.user_id && s.length < current.length => s,
+                _ => continue,
+            };
+            let subscriptions = match user_subscriptions.get(current.user_id.as_str()) {
+                Some(subscriptions) => subscriptions,
+                None => continue,
+            };
+            let payload = json!({
+                "event": EventType::ShortestOvertaken.as_str(),
+                "user_id": current.user_id,
+                "problem_id": s.problem_id,
+                "contest_id": s.contest_id,
+                "submission_id": s.id,
+                "epoch_second": s.epoch_second,
+                "overtaken_by": s.user_id,
+                "length": s.length,
+                "previous_length": current.length,
+            });
+            deliveries.extend(to_deliveries(
+                subscriptions,
+                EventType::ShortestOvertaken,
+                &payload,
+            ));
+        }
+        Ok(deliveries)
+    }
+}
+
+pub(super) fn get_first_ac(accepted: &[Submission]) -> BTreeMap<&str, &Submission> {
+    accepted.iter().fold(BTreeMap::new(), |mut map, s| {
+        let first = map.entry(s.problem_id.as_str()).or_insert(s);
+        if (s.epoch_second, s.id) < (first.epoch_second, first.id) {
+            *first = s;
+        }
+        map
+    })
+}
+
+pub(super) fn jst_day(epoch_second: i64) -> i64 {
+    (epoch_second + JST_OFFSET_SECOND).div_euclid(ONE_DAY_SECOND)
+}
+
+fn submission_payload(event_type: EventType, s: &Submission) -> Value {
+    json!({
+        "event": event_type.as_str(),
+        "user_id": s.user_id,
+        "problem_id": s.problem_id,
+        "contest_id": s.contest_id,
+        "submission_id": s.id,
+        "epoch_second": s.epoch_second,
+    })
+}
+
+pub(super) fn to_deliveries(
+    subscriptions: &[&Subscription],
+    event_type: EventType,
+    payload: &Value,
+) -> Vec<Delivery> {
+    subscriptions
+        .iter()
+        .filter(|s| s.subscribes(event_type))
+        .map(|s| (s.webhook_id.clone(), event_type, payload.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jst_day() {
+        assert_eq!(jst_day(0), 0);
+        assert_eq!(jst_day(ONE_DAY_SECOND - JST_OFFSET_SECOND - 1), 0);
+        assert_eq!(jst_day(ONE_DAY_SECOND - JST_OFFSET_SECOND), 1);
+    }
+}
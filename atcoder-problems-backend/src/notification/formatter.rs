@@ -0,0 +1,387 @@
+use crate::error::Result;
+use crate::sql::internal::notification_manager::{EventType, WebhookFormat};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const ATCODER_URL: &str = "https://atcoder.jp";
+
+/// The payload generated by `NotificationFeeder`.
+#[derive(Deserialize)]
+struct Payload {
+    event: String,
+    user_id: String,
+    problem_id: String,
+    contest_id: String,
+    submission_id: i64,
+    problem_title: Option<String>,
+    difficulty: Option<f64>,
+    streak: Option<i64>,
+    overtaken_by: Option<String>,
+    length: Option<i32>,
+    previous_length: Option<i32>,
+}
+
+/// The payload generated by `DailyDigestGenerator`.
+#[derive(Deserialize)]
+struct DigestPayload {
+    user_id: String,
+    solved_problem_ids: Vec<String>,
+    streak: i64,
+    rivals: Vec<RivalActivity>,
+    recommended_problem_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RivalActivity {
+    user_id: String,
+    solved_count: i64,
+}
+
+impl DigestPayload {
+    fn title(&self) -> String {
+        format!("Daily digest of {}", self.user_id)
+    }
+
+    fn text(&self) -> String {
+        let mut lines = vec![format!(
+            "Solved {} new problems: {}",
+            self.solved_problem_ids.len(),
+            self.solved_problem_ids.join(", ")
+        )];
+        lines.push(format!("Streak: {} days", self.streak));
+        for rival in self.rivals.iter() {
+            lines.push(format!(
+                "{} solved {} new problems",
+                rival.user_id, rival.solved_count
+            ));
+        }
+        if let Some(problem_id) = self.recommended_problem_id.as_ref() {
+            lines.push(format!("Recommended: {}", problem_id));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Payload {
+    fn title(&self) -> &str {
+        self.problem_title.as_deref().unwrap_or(&self.problem_id)
+    }
+
+    fn text(&self) -> String {
+        match self.event.as_str() {
+            "new_ac" => format!("{} solved {}", self.user_id, self.title()),
+            "streak_milestone" => format!(
+                "{} reached a {}-day streak by solving {}",
+                self.user_id,
+                self.streak.unwrap_or_default(),
+                self.title()
+            ),
+            "shortest_overtaken" => format!(
+                "{} beat the shortest code of {} on {}: {} -> {} bytes",
+                self.overtaken_by.as_deref().unwrap_or_default(),
+                self.user_id,
+                self.title(),
+                self.previous_length.unwrap_or_default(),
+                self.length.unwrap_or_default()
+            ),
+            event => format!("{}: {}", self.user_id, event),
+        }
+    }
+
+    fn problem_url(&self) -> String {
+        format!(
+            "{}/contests/{}/tasks/{}",
+            ATCODER_URL, self.contest_id, self.problem_id
+        )
+    }
+
+    fn submission_url(&self) -> String {
+        format!(
+            "{}/contests/{}/submissions/{}",
+            ATCODER_URL, self.contest_id, self.submission_id
+        )
+    }
+
+    fn difficulty(&self) -> Option<i64> {
+        self.difficulty
+            .map(|difficulty| clip_difficulty(difficulty).round() as i64)
+    }
+}
+
+/// Renders the body of a delivery in the format of the webhook. The emails are rendered by
+/// `format_email`.
+pub(crate) fn format_body(format: WebhookFormat, payload: &str) -> Result<String> {
+    if format == WebhookFormat::Json {
+        return Ok(payload.to_owned());
+    }
+    let payload: Value = serde_json::from_str(payload)?;
+    if payload["event"] == EventType::DailyDigest.as_str() {
+        let digest: DigestPayload = serde_json::from_value(payload)?;
+        let body = match format {
+            WebhookFormat::Json | WebhookFormat::Email => unreachable!(),
+            WebhookFormat::Slack => format_digest_slack(&digest),
+            WebhookFormat::Discord => format_digest_discord(&digest),
+        };
+        return Ok(body.to_string());
+    }
+    let payload: Payload = serde_json::from_value(payload)?;
+    let body = match format {
+        WebhookFormat::Json | WebhookFormat::Email => unreachable!(),
+        WebhookFormat::Slack => format_slack(&payload),
+        WebhookFormat::Discord => format_discord(&payload),
+    };
+    Ok(body.to_string())
+}
+
+fn format_slack(payload: &Payload) -> Value {
+    let text = payload.text();
+    let mut fields = vec![];
+    if let Some(difficulty) = payload.difficulty() {
+        fields.push(json!({"title": "Difficulty", "value": difficulty.to_string(), "short": true}));
+    }
+    fields.push(json!({
+        "title": "Submission",
+        "value": format!("<{}|#{}>", payload.submission_url(), payload.submission_id),
+        "short": true,
+    }));
+
+    let mut attachment = json!({
+        "fallback": text,
+        "title": payload.title(),
+        "title_link": payload.problem_url(),
+        "fields": fields,
+    });
+    if let Some(color) = payload.difficulty().map(difficulty_color) {
+        attachment["color"] = json!(format!("#{:06X}", color));
+    }
+    json!({ "text": text, "attachments": [attachment] })
+}
+
+fn format_discord(payload: &Payload) -> Value {
+    let mut fields = vec![];
+    if let Some(difficulty) = payload.difficulty() {
+        fields.push(json!({"name": "Difficulty", "value": difficulty.to_string(), "inline": true}));
+    }
+    fields.push(json!({
+        "name": "Submission",
+        "value": format!("[#{}]({})", payload.submission_id, payload.submission_url()),
+        "inline": true,
+    }));
+
+    let mut embed = json!({
+        "title": payload.title(),
+        "url": payload.problem_url(),
+        "description": payload.text(),
+        "fields": fields,
+    });
+    if let Some(color) = payload.difficulty().map(difficulty_color) {
+        embed["color"] = json!(color);
+    }
+    json!({ "embeds": [embed] })
+}
+
+fn format_digest_slack(digest: &DigestPayload) -> Value {
+    let title = digest.title();
+    json!({
+        "text": title,
+        "attachments": [{ "fallback": title, "text": digest.text() }],
+    })
+}
+
+fn format_digest_discord(digest: &DigestPayload) -> Value {
+    json!({ "embeds": [{ "title": digest.title(), "description": digest.text() }] })
+}
+
+/// Renders the subject and the HTML body of an email. The emails except the verification one
+/// have the link to unsubscribe the address with `unsubscribe_token`.
+pub(crate) fn format_email(
+    payload: &str,
+    api_url: &str,
+    unsubscribe_token: &str,
+) -> Result<(String, String)> {
+    let payload: Value = serde_json::from_str(payload)?;
+    if payload["event"] == EventType::EmailVerification.as_str() {
+        let token = payload["token"].as_str().unwrap_or_default();
+        let url = format!("{}/internal-api/email/verify?token={}", api_url, token);
+        let html = format!(
+            "<p>Open the link below to receive the notifications of AtCoder Problems at this \
+            address.</p><p><a href=\"{url}\">{url}</a></p>",
+            url = escape_html(&url)
+        );
+        return Ok(("Verify your email address".to_owned(), html));
+    }
+
+    let (subject, content) = if payload["event"] == EventType::DailyDigest.as_str() {
+        let digest: DigestPayload = serde_json::from_value(payload)?;
+        let items = digest
+            .text()
+            .lines()
+            .map(|line| format!("<li>{}</li>", escape_html(line)))
+            .collect::<String>();
+        (digest.title(), format!("<ul>{}</ul>", items))
+    } else {
+        let payload: Payload = serde_json::from_value(payload)?;
+        let content = format!(
+            "<p>{}</p><p><a href=\"{}\">{}</a> / <a href=\"{}\">#{}</a></p>",
+            escape_html(&payload.text()),
+            escape_html(&payload.problem_url()),
+            escape_html(payload.title()),
+            escape_html(&payload.submission_url()),
+            payload.submission_id
+        );
+        (payload.text(), content)
+    };
+    let unsubscribe_url = format!(
+        "{}/internal-api/email/unsubscribe?token={}",
+        api_url, unsubscribe_token
+    );
+    let html = format!(
+        "<html><body>{}<hr><p><a href=\"{}\">Unsubscribe</a></p></body></html>",
+        content,
+        escape_html(&unsubscribe_url)
+    );
+    Ok((subject, html))
+}
+
+fn escape_html(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            '"' => "&quot;".to_owned(),
+            '\'' => "&#39;".to_owned(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Clips the difficulty into the positive range in the same way as the frontend.
+fn clip_difficulty(difficulty: f64) -> f64 {
+    if difficulty >= 400.0 {
+        difficulty
+    } else {
+        400.0 / (1.0 - difficulty / 400.0).exp()
+    }
+}
+
+/// Returns the RGB of the rating color of the difficulty.
+fn difficulty_color(difficulty: i64) -> u32 {
+    match difficulty {
+        d if d < 400 => 0x80_80_80,
+        d if d < 800 => 0x80_40_00,
+        d if d < 1200 => 0x00_80_00,
+        d if d < 1600 => 0x00_C0_C0,
+        d if d < 2000 => 0x00_00_FF,
+        d if d < 2400 => 0xC0_C0_00,
+        d if d < 2800 => 0xFF_80_00,
+        _ => 0xFF_00_00,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = r#"{
+        "event": "shortest_overtaken", "user_id": "alice", "problem_id": "abc100_a",
+        "contest_id": "abc100", "submission_id": 1, "epoch_second": 0,
+        "problem_title": "A. Happy Birthday!", "difficulty": 1300.0,
+        "overtaken_by": "bob", "length": 50, "previous_length": 100
+    }"#;
+
+    #[test]
+    fn test_format_json() {
+        assert_eq!(format_body(WebhookFormat::Json, PAYLOAD).unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn test_format_slack() {
+        let body = format_body(WebhookFormat::Slack, PAYLOAD).unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body["text"],
+            "bob beat the shortest code of alice on A. Happy Birthday!: 100 -> 50 bytes"
+        );
+        let attachment = &body["attachments"][0];
+        assert_eq!(attachment["title"], "A. Happy Birthday!");
+        assert_eq!(
+            attachment["title_link"],
+            "https://atcoder.jp/contests/abc100/tasks/abc100_a"
+        );
+        assert_eq!(attachment["color"], "#00C0C0");
+        assert_eq!(attachment["fields"][0]["value"], "1300");
+        assert_eq!(
+            attachment["fields"][1]["value"],
+            "<https://atcoder.jp/contests/abc100/submissions/1|#1>"
+        );
+    }
+
+    #[test]
+    fn test_format_discord() {
+        let payload = r#"{
+            "event": "new_ac", "user_id": "alice", "problem_id": "abc100_a",
+            "contest_id": "abc100", "submission_id": 1, "epoch_second": 0,
+            "problem_title": null, "difficulty": null
+        }"#;
+        let body = format_body(WebhookFormat::Discord, payload).unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["title"], "abc100_a");
+        assert_eq!(embed["description"], "alice solved abc100_a");
+        assert!(embed.get("color").is_none());
+        assert_eq!(embed["fields"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let payload = r#"{
+            "event": "daily_digest", "user_id": "alice", "epoch_second": 0,
+            "solved_problem_ids": ["abc100_a", "abc100_b"], "streak": 3,
+            "rivals": [{"user_id": "bob", "solved_count": 1}],
+            "recommended_problem_id": "abc100_c"
+        }"#;
+        let body = format_body(WebhookFormat::Discord, payload).unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["title"], "Daily digest of alice");
+        assert_eq!(
+            embed["description"],
+            "Solved 2 new problems: abc100_a, abc100_b\nStreak: 3 days\nbob solved 1 new problems\nRecommended: abc100_c"
+        );
+    }
+
+    #[test]
+    fn test_format_email() {
+        let (subject, html) = format_email(PAYLOAD, "https://example.com", "token").unwrap();
+        assert_eq!(
+            subject,
+            "bob beat the shortest code of alice on A. Happy Birthday!: 100 -> 50 bytes"
+        );
+        assert!(html.contains("<a href=\"https://atcoder.jp/contests/abc100/tasks/abc100_a\">"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/internal-api/email/unsubscribe?token=token\">"
+        ));
+
+        let payload = r#"{"event": "email_verification", "token": "abc"}"#;
+        let (_, html) = format_email(payload, "https://example.com", "token").unwrap();
+        assert!(html.contains("https://example.com/internal-api/email/verify?token=abc"));
+        assert!(!html.contains("unsubscribe"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_clip_difficulty() {
+        assert_eq!(clip_difficulty(1000.0), 1000.0);
+        assert_eq!(clip_difficulty(400.0), 400.0);
+        assert!((clip_difficulty(0.0) - 400.0 / std::f64::consts::E).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::Result;
+use crate::sql::models::{Point, Submission};
+use crate::sql::{SubmissionClient, SubmissionRequest};
+
+use hmac::{Hmac, Mac, NewMac};
+use serde::Serialize;
+use sha2::Sha256;
+
+const BATCH_SIZE: i64 = 100_000;
+const PSEUDONYM_LENGTH: usize = 32;
+
+/// Replaces the user ids with pseudonyms which are stable as long as the salt is kept, and can
+/// not be reversed without the salt.
+pub struct Pseudonymizer {
+    salt: String,
+}
+
+impl Pseudonymizer {
+    pub fn new(salt: String) -> Self {
+        Self { salt }
+    }
+
+    pub fn pseudonym(&self, user_id: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_varkey(self.salt.as_bytes()).expect("HMAC accepts any key.");
+        mac.update(user_id.as_bytes());
+        let mut pseudonym = hex::encode(mac.finalize().into_bytes());
+        pseudonym.truncate(PSEUDONYM_LENGTH);
+        pseudonym
+    }
+}
+
+pub struct ExportOptions {
+    pub include_length: bool,
+    pub include_language: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_length: true,
+            include_language: true,
+        }
+    }
+}
+
+/// A row of the dataset. The submission ids are left out, since the submission pages on AtCoder
+/// show the user ids.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AnonymizedSubmission {
+    pub epoch_second: i64,
+    pub problem_id: String,
+    pub contest_id: String,
+    pub user: String,
+    pub point: Point,
+    pub result: String,
+    pub execution_time: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<i32>,
+}
+
+pub struct ResearchDatasetExporter<C> {
+    db: C,
+    pseudonymizer: Pseudonymizer,
+    options: ExportOptions,
+}
+
+impl<C> ResearchDatasetExporter<C>
+where
+    C: SubmissionClient,
+{
+    pub fn new(db: C, pseudonymizer: Pseudonymizer, options: ExportOptions) -> Self {
+        Self {
+            db,
+            pseudonymizer,
+            options,
+        }
+    }
+
+    pub fn anonymize(&self, submission: Submission) -> AnonymizedSubmission {
+        AnonymizedSubmission {
+            user: self.pseudonymizer.pseudonym(&submission.user_id),
+            epoch_second: submission.epoch_second,
+            problem_id: submission.problem_id,
+            contest_id: submission.contest_id,
+            point: submission.point,
+            result: submission.result,
+            execution_time: submission.execution_time,
+            language: Some(submission.language).filter(|_| self.options.include_language),
+            length: Some(submission.length).filter(|_| self.options.include_length),
+        }
+    }
+
+    /// Exports all the submissions in the order of their ids as JSON Lines.
+    pub fn export(&self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let mut from_id = -1;
+        loop {
+            let submissions = self.db.get_submissions(SubmissionRequest::AfterId {
+                from_id,
+                count: BATCH_SIZE,
+            })?;
+            let last_id = match submissions.last() {
+                Some(submission) => submission.id,
+                None => break,
+            };
+            for submission in submissions.into_iter() {
+                serde_json::to_writer(&mut data, &self.anonymize(submission))?;
+                data.push(b'\n');
+            }
+            log::info!("Exported submissions until {}", last_id);
+            from_id = last_id;
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonym() {
+        let pseudonymizer = Pseudonymizer::new("salt".to_owned());
+        let pseudonym = pseudonymizer.pseudonym("kenkoooo");
+        assert_eq!(pseudonym.len(), PSEUDONYM_LENGTH);
+        assert_eq!(pseudonym, pseudonymizer.pseudonym("kenkoooo"));
+        assert_ne!(pseudonym, pseudonymizer.pseudonym("kenkoooo2"));
+        assert_ne!(
+            pseudonym,
+            Pseudonymizer::new("pepper".to_owned()).pseudonym("kenkoooo")
+        );
+    }
+}
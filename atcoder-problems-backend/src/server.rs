@@ -1,30 +1,96 @@
+use crate::cache::{self, Cache};
+use crate::config::{Config, QueryConfig, ServerConfig, SubmissionCodeConfig};
 use crate::error::Result;
+use crate::feature_flags::FeatureFlags;
 use crate::server::time_submissions::get_time_submissions;
 use crate::server::user_info::get_user_info;
 use crate::server::user_submissions::{
     get_recent_submissions, get_user_submissions, get_users_time_submissions,
 };
+use crate::shutdown;
 
+pub(crate) mod achievements;
+pub(crate) mod admin;
+pub(crate) mod api_quota;
+pub(crate) mod api_token;
+pub(crate) mod audit_log;
 pub(crate) mod auth;
+pub(crate) mod calendar;
+pub(crate) mod circuit_breaker;
+pub(crate) mod classroom;
+pub(crate) mod client_ip;
+pub(crate) mod concurrency_limit;
+pub(crate) mod contest_completion;
+pub(crate) mod contest_table;
+pub(crate) mod contests;
+pub(crate) mod cors;
+pub(crate) mod difficulty_count;
+pub(crate) mod dumps;
+pub(crate) mod error_response;
 use crate::server::problem_list::{
-    add_item, create_list, delete_item, delete_list, get_own_lists, get_single_list, update_item,
-    update_list,
+    add_item, create_list, delete_item, delete_list, export_list, generate_share_token,
+    get_own_lists, get_shared_list, get_single_list, import_list, restore_list, revoke_share_token,
+    update_item, update_list,
 };
+use crate::sql::internal::api_token_manager::Scope;
+use api_token::RequireScope;
+use async_std::net::TcpListener;
+use async_std::{io, task};
 use auth::get_token;
 pub use auth::{Authentication, GitHubAuthentication, GitHubUserResponse};
-use std::time::Duration;
+use circuit_breaker::CircuitBreaker;
+use client_ip::{ClientIp, PeerAddr};
+use concurrency_limit::ConcurrencyLimit;
+use diesel::connection::SimpleConnection;
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tide::StatusCode;
 
+pub(crate) mod feed;
+pub(crate) mod idempotency;
 pub(crate) mod internal_user;
+pub(crate) mod judge_summary;
+pub(crate) mod language_trends;
+pub(crate) mod marathon;
+pub(crate) mod notification;
 pub(crate) mod problem_list;
+pub(crate) mod problem_models;
+pub(crate) mod problem_note;
+pub(crate) mod problems;
 pub(crate) mod progress_reset;
+pub(crate) mod rank_history;
+pub(crate) mod ranking;
+pub(crate) mod request_analytics;
+pub(crate) mod site_stats;
+pub(crate) mod standings;
+pub(crate) mod submission_code;
+pub(crate) mod submission_stream;
 pub(crate) mod time_submissions;
+pub(crate) mod user_comparison;
+pub(crate) mod user_group;
 pub(crate) mod user_info;
 pub(crate) mod user_submissions;
 pub(crate) mod utils;
+pub(crate) mod validation;
+pub(crate) mod versions;
 pub(crate) mod virtual_contest;
+pub(crate) mod virtual_contest_announcement;
+pub(crate) mod virtual_contest_result;
+pub(crate) mod virtual_contest_team;
+pub(crate) mod virtual_contest_template;
+pub(crate) mod virtual_contest_training;
+pub(crate) mod watch_list;
+pub(crate) mod weakness;
 
-pub(crate) type Pool = diesel::r2d2::Pool<diesel::r2d2::ConnectionManager<diesel::PgConnection>>;
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DRAIN_TIMEOUT_SECOND: u64 = 30;
+const READER_POOL_SIZE: u32 = 15;
+
+type DieselPool = diesel::r2d2::Pool<diesel::r2d2::ConnectionManager<diesel::PgConnection>>;
 pub(crate) type PooledConnection =
     diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<diesel::PgConnection>>;
 
@@ -32,19 +98,69 @@ pub async fn run_server<A>(pool: Pool, authentication: A, port: u16) -> Result<(
 where
     A: Authentication + Send + Sync + 'static + Clone,
 {
-    let app_data = AppData::new(pool, authentication);
+    let config = Config {
+        server: ServerConfig {
+            port,
+            ..ServerConfig::default()
+        },
+        ..Config::default()
+    };
+    run_server_with_config(pool, authentication, &config).await
+}
+
+pub async fn run_server_with_config<A>(pool: Pool, authentication: A, config: &Config) -> Result<()>
+where
+    A: Authentication + Send + Sync + 'static + Clone,
+{
+    let cache = cache::connect(&config.cache)?;
+    let app_data = AppData::new(
+        pool,
+        authentication,
+        cache,
+        config.cache.ttl(),
+        &config.query,
+        &config.submission_code,
+    );
+    let rankings_limit = ConcurrencyLimit::new(
+        "rankings",
+        config.query.rankings_concurrency,
+        config.query.queue_timeout(),
+    );
+    let submissions_limit = ConcurrencyLimit::new(
+        "submissions",
+        config.query.submissions_concurrency,
+        config.query.queue_timeout(),
+    );
+    let analytics = request_analytics::RequestAnalytics::default();
     let mut api = tide::with_state(app_data.clone());
+    api.middleware(analytics.clone());
+    api.middleware(ClientIp::new(&config.server.trusted_proxies));
+    api.middleware(audit_log::AuditLog);
+    api.middleware(cors::Cors::new(config.server.cors_origins.clone()));
+    api.middleware(error_response::ErrorResponse);
 
     api.at("/internal-api").nest({
         let mut api = tide::with_state(app_data.clone());
         api.at("/authorize").get(get_token);
         api.at("/list").nest({
             let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::write(Scope::ListWrite));
+            api.middleware(idempotency::Idempotency);
             api.at("/my").get(get_own_lists);
             api.at("/get/:list_id").get(get_single_list);
             api.at("/create").post(create_list);
             api.at("/delete").post(delete_list);
+            api.at("/restore").post(restore_list);
             api.at("/update").post(update_list);
+            api.at("/export/:list_id").get(export_list);
+            api.at("/import").post(import_list);
+            api.at("/shared/:share_token").get(get_shared_list);
+            api.at("/share").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/generate").post(generate_share_token);
+                api.at("/revoke").post(revoke_share_token);
+                api
+            });
             api.at("/item").nest({
                 let mut api = tide::with_state(app_data.clone());
                 api.at("/add").post(add_item);
@@ -57,38 +173,220 @@ where
 
         api.at("/contest").nest({
             let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::write(Scope::ContestAdmin));
+            api.middleware(idempotency::Idempotency);
             api.at("/create").post(virtual_contest::create_contest);
+            api.at("/create_auto")
+                .post(virtual_contest::create_contest_with_auto_selection);
             api.at("/update").post(virtual_contest::update_contest);
             api.at("/item/update").post(virtual_contest::update_items);
+            api.at("/delete").post(virtual_contest::delete_contest);
+            api.at("/restore").post(virtual_contest::restore_contest);
             api.at("/get/:contest_id")
                 .get(virtual_contest::get_single_contest);
             api.at("/join").post(virtual_contest::join_contest);
+            api.at("/clone").post(virtual_contest::clone_contest);
             api.at("/my").get(virtual_contest::get_my_contests);
             api.at("/joined").get(virtual_contest::get_participated);
             api.at("/recent").get(virtual_contest::get_recent_contests);
+            api.at("/announcement").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/post")
+                    .post(virtual_contest_announcement::post_announcement);
+                api.at("/delete")
+                    .post(virtual_contest_announcement::delete_announcement);
+                api.at("/list/:contest_id")
+                    .get(virtual_contest_announcement::get_announcements);
+                api
+            });
+            api.at("/results/:contest_id")
+                .get(virtual_contest_result::get_results);
+            api.at("/training/progress/update")
+                .post(virtual_contest_training::update_training_progress);
+            api.at("/training/progress/:contest_id")
+                .get(virtual_contest_training::get_training_progress);
+            api.at("/template").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/create")
+                    .post(virtual_contest_template::create_template);
+                api.at("/delete")
+                    .post(virtual_contest_template::delete_template);
+                api.at("/my")
+                    .get(virtual_contest_template::get_my_templates);
+                api
+            });
+            api.at("/invite").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/generate")
+                    .post(virtual_contest::generate_invite_token);
+                api.at("/rotate").post(virtual_contest::rotate_invite_token);
+                api.at("/revoke").post(virtual_contest::revoke_invite_token);
+                api
+            });
+            api.at("/team").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/create").post(virtual_contest_team::create_team);
+                api.at("/update").post(virtual_contest_team::update_team);
+                api.at("/delete").post(virtual_contest_team::delete_team);
+                api.at("/list/:contest_id")
+                    .get(virtual_contest_team::get_teams);
+                api.at("/standings/:contest_id")
+                    .get(virtual_contest_team::get_team_standings);
+                api.at("/standings/:contest_id/stream")
+                    .get(virtual_contest_team::stream_team_standings);
+                api
+            });
             api
         });
 
         api.at("/user").nest({
             let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
             api.at("/get").get(internal_user::get);
             api.at("/update").post(internal_user::update);
+            api.at("/verification").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/start").post(internal_user::start_verification);
+                api.at("/get").get(internal_user::get_verification);
+                api
+            });
             api
         });
 
+        api.at("/account")
+            .middleware(RequireScope::login())
+            .delete(internal_user::delete_account);
+
         api.at("/progress_reset").nest({
             let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
             api.at("/list").get(progress_reset::get_progress_reset_list);
             api.at("/add").post(progress_reset::add_progress_reset_item);
             api.at("/delete")
                 .post(progress_reset::delete_progress_reset_item);
             api
         });
+        api.at("/note").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
+            api.at("/my").get(problem_note::get_notes);
+            api.at("/update").post(problem_note::update_note);
+            api.at("/tagged").get(problem_note::get_tagged_problems);
+            api.at("/tag").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/add").post(problem_note::add_tag);
+                api.at("/remove").post(problem_note::remove_tag);
+                api
+            });
+            api
+        });
+        api.at("/token").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login());
+            api.at("/create").post(api_token::create_token);
+            api.at("/list").get(api_token::get_tokens);
+            api.at("/revoke").post(api_token::revoke_token);
+            api
+        });
+        api.at("/admin").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login());
+            api.middleware(admin::RequireAdmin);
+            api.at("/contest/delete").post(admin::delete_contest);
+            api.at("/list/delete").post(admin::delete_list);
+            api.at("/ban").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/list").get(admin::get_banned_users);
+                api.at("/add").post(admin::ban_user);
+                api.at("/remove").post(admin::unban_user);
+                api
+            });
+            api.at("/recrawl").post(admin::request_recrawl);
+            api.at("/excluded_contests").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/list").get(admin::get_excluded_contests);
+                api.at("/add").post(admin::exclude_contest);
+                api.at("/remove").post(admin::include_contest);
+                api
+            });
+            api.at("/audit_log").get(audit_log::get_audit_logs);
+            api.at("/feature_flags").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/list").get(admin::get_feature_flags);
+                api.at("/update").post(admin::update_feature_flag);
+                api
+            });
+            api.at("/validation_violations")
+                .get(admin::get_validation_violations);
+            api.at("/update_tiers").get(admin::get_update_tier_stats);
+            api.at("/request_counts")
+                .get(request_analytics::get_request_counts);
+            api
+        });
+        api.at("/notification").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login());
+            api.at("/webhook/list").get(notification::get_webhooks);
+            api.at("/webhook/register")
+                .post(notification::register_webhook);
+            api.at("/webhook/delete").post(notification::delete_webhook);
+            api.at("/email/register").post(notification::register_email);
+            api
+        });
+        api.at("/email/verify").get(notification::verify_email);
+        api.at("/email/unsubscribe")
+            .get(notification::unsubscribe_email);
+        api.at("/watch").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
+            api.at("/list").get(watch_list::get_watched_users);
+            api.at("/add").post(watch_list::add_watched_user);
+            api.at("/delete").post(watch_list::delete_watched_user);
+            api.at("/feed").get(watch_list::get_watch_feed);
+            api
+        });
+        api.at("/group").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
+            api.at("/my").get(user_group::get_groups);
+            api.at("/create").post(user_group::create_group);
+            api.at("/update").post(user_group::update_group);
+            api.at("/delete").post(user_group::delete_group);
+            api.at("/member").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/add").post(user_group::add_members);
+                api.at("/delete").post(user_group::delete_member);
+                api
+            });
+            api
+        });
+        api.at("/group_ranking/:kind")
+            .get(user_group::get_group_ranking);
+        api.at("/classroom").nest({
+            let mut api = tide::with_state(app_data.clone());
+            api.middleware(RequireScope::login_write());
+            api.at("/assignment").nest({
+                let mut api = tide::with_state(app_data.clone());
+                api.at("/create").post(classroom::create_assignment);
+                api.at("/delete").post(classroom::delete_assignment);
+                api.at("/list").get(classroom::get_assignments);
+                api.at("/progress/:internal_assignment_id")
+                    .get(classroom::get_assignment_progress);
+                api
+            });
+            api
+        });
+        api.at("/submission/code/:submission_id")
+            .middleware(RequireScope::login())
+            .get(submission_code::get_submission_code);
         api
     });
     api.at("/atcoder-api").nest({
         let mut api = tide::with_state(app_data.clone());
-        api.at("/results").get(get_user_submissions);
+        api.middleware(api_quota::DailyQuota);
+        api.at("/results")
+            .middleware(submissions_limit.clone())
+            .get(get_user_submissions);
         api.at("/v2").nest({
             let mut api = tide::with_state(app_data.clone());
             api.at("/user_info").get(get_user_info);
@@ -96,31 +394,173 @@ where
         });
         api.at("/v3").nest({
             let mut api = tide::with_state(app_data.clone());
-            api.at("/from/:from").get(get_time_submissions);
-            api.at("/recent").get(get_recent_submissions);
-            api.at("/users_and_time").get(get_users_time_submissions);
+            api.at("/from/:from")
+                .middleware(submissions_limit.clone())
+                .get(get_time_submissions);
+            api.at("/recent")
+                .middleware(submissions_limit.clone())
+                .get(get_recent_submissions);
+            api.at("/users_and_time")
+                .middleware(submissions_limit.clone())
+                .get(get_users_time_submissions);
+            api.at("/submissions/stream")
+                .get(submission_stream::get_submission_stream);
+            api.at("/dumps").get(dumps::get_dumps);
+            api.at("/user/judge_summary")
+                .get(judge_summary::get_judge_summary);
+            api.at("/user/achievements")
+                .get(achievements::get_user_achievements);
+            api.at("/user/difficulty_count")
+                .get(difficulty_count::get_difficulty_count);
+            api.at("/user/contest_completion")
+                .get(contest_completion::get_contest_completion);
+            api.at("/user/contest_table")
+                .get(contest_table::get_contest_table);
+            api.at("/user/rank_history")
+                .get(rank_history::get_rank_history);
+            api.at("/user/marathon_scores")
+                .get(marathon::get_marathon_scores);
+            api.at("/user/holder_rank/:kind")
+                .middleware(rankings_limit.clone())
+                .get(ranking::get_users_holder_rank);
+            api.at("/user/unsolved")
+                .get(problems::get_unsolved_problems);
+            api.at("/user/weakness").get(weakness::get_weakness);
+            api.at("/contests").get(contests::get_contests);
+            api.at("/contest/stats").get(contests::get_contest_stats);
+            api.at("/contest/difficulties")
+                .get(contests::get_contest_difficulties);
+            api.at("/language_trends")
+                .get(language_trends::get_language_trends);
+            api.at("/marathon_leaderboard")
+                .get(marathon::get_marathon_leaderboard);
+            api.at("/rated_point_sum_ranking")
+                .middleware(rankings_limit.clone())
+                .get(ranking::get_rated_point_sum_ranking);
+            api.at("/holder_ranking/:kind")
+                .middleware(rankings_limit.clone())
+                .get(ranking::get_holder_ranking);
+            api.at("/compare").get(user_comparison::get_user_comparison);
+            api.at("/problem").get(problems::get_problem);
+            api.at("/problem/shortest_history")
+                .get(problems::get_shortest_history);
+            api.at("/problem/similar")
+                .get(problems::get_similar_problems);
+            api.at("/problems/search").get(problems::search_problems);
+            api.at("/problems/detailed")
+                .get(problems::get_detailed_problems);
+            api.at("/problem_models")
+                .get(problem_models::get_problem_models);
+            api.at("/versions").get(versions::get_versions);
+            api.at("/versions/stream").get(versions::stream_versions);
+            api.at("/internal/usage").get(api_quota::get_usage);
+            api.at("/site_stats").get(site_stats::get_site_stats);
             api
         });
         api
     });
+    api.at("/feed").nest({
+        let mut api = tide::with_state(app_data.clone());
+        api.at("/contests.atom").get(feed::get_contests_feed);
+        api.at("/problems.atom").get(feed::get_problems_feed);
+        api.at("/contests.ics").get(calendar::get_contests_calendar);
+        api.at("/calendar.ics").get(calendar::get_user_calendar);
+        api
+    });
     api.at("/healthcheck").get(|_| async move { Ok("") });
-    api.listen(format!("0.0.0.0:{}", port)).await?;
+    serve(api, config.server.port).await?;
+    if let Err(e) = analytics.flush(&app_data.pool) {
+        log::error!("Failed to store the request counts: {:?}", e);
+    }
     Ok(())
 }
 
+/// Serves the app until the shutdown is requested. Then it stops accepting new connections, and
+/// waits for the requests in flight for at most `DRAIN_TIMEOUT_SECOND` seconds.
+async fn serve<State>(app: tide::Server<State>, port: u16) -> Result<()>
+where
+    State: Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    log::info!("Server listening on {}", listener.local_addr()?);
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    while !shutdown::is_requested() {
+        let (stream, peer) = match io::timeout(ACCEPT_POLL_INTERVAL, listener.accept()).await {
+            Ok(accepted) => accepted,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => {
+                log::error!("{:?}", e);
+                continue;
+            }
+        };
+        let app = app.clone();
+        let in_flight = in_flight.clone();
+        task::spawn(async move {
+            let result = async_h1::accept(stream, |mut request| async {
+                request.ext_mut().insert(PeerAddr(peer.ip()));
+                in_flight.fetch_add(1, Ordering::SeqCst);
+                let response = app.respond(request).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                let mut response: http_types::Response = response?;
+                if shutdown::is_requested() {
+                    response.insert_header("connection", "close");
+                }
+                Ok(response)
+            })
+            .await;
+            if let Err(e) = result {
+                log::error!("{:?}", e);
+            }
+        });
+    }
+    drop(listener);
+
+    log::info!("Draining {} requests", in_flight.load(Ordering::SeqCst));
+    let deadline = Instant::now() + Duration::from_secs(DRAIN_TIMEOUT_SECOND);
+    while in_flight.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        task::sleep(ACCEPT_POLL_INTERVAL).await;
+    }
+    log::info!("Server stopped");
+    Ok(())
+}
+
+/// The connections of the server to the database. It dereferences to the pool of diesel, and the
+/// server makes the pool of sqlx for `AsyncSubmissionClient` with `reader_options`, once it knows
+/// the query timeout.
+#[derive(Clone)]
+pub struct Pool {
+    diesel: DieselPool,
+    reader_options: PgConnectOptions,
+}
+
+impl Deref for Pool {
+    type Target = DieselPool;
+
+    fn deref(&self) -> &DieselPool {
+        &self.diesel
+    }
+}
+
 pub fn initialize_pool<S: Into<String>>(database_url: S) -> Result<Pool> {
+    let database_url = database_url.into();
+    let reader_options = database_url.parse::<PgConnectOptions>()?;
     let manager = diesel::r2d2::ConnectionManager::<diesel::PgConnection>::new(database_url);
     let pool = diesel::r2d2::Pool::builder()
         .max_lifetime(Some(Duration::from_secs(60 * 5)))
         .max_size(15)
         .build(manager)?;
-    Ok(pool)
+    Ok(Pool {
+        diesel: pool,
+        reader_options,
+    })
 }
 
 pub(crate) trait CommonResponse {
     fn ok() -> Self;
     fn new_cors() -> Self;
     fn bad_request() -> Self;
+    fn forbidden() -> Self;
+    fn not_found() -> Self;
     fn internal_error() -> Self;
 }
 
@@ -132,32 +572,201 @@ impl CommonResponse for tide::Response {
         Self::ok().set_header("access-control-allow-origin", "*")
     }
     fn bad_request() -> Self {
-        Self::new(StatusCode::BadRequest)
+        error_response::json_error(
+            StatusCode::BadRequest,
+            "validation",
+            "The request is invalid.",
+            &[],
+        )
+    }
+    fn forbidden() -> Self {
+        error_response::json_error(
+            StatusCode::Forbidden,
+            "unauthorized",
+            "The operation is not allowed.",
+            &[],
+        )
+    }
+    fn not_found() -> Self {
+        error_response::json_error(
+            StatusCode::NotFound,
+            "not_found",
+            "The resource is not found.",
+            &[],
+        )
     }
     fn internal_error() -> Self {
-        Self::new(StatusCode::InternalServerError)
+        error_response::json_error(
+            StatusCode::InternalServerError,
+            "internal",
+            "Internal Server Error",
+            &[],
+        )
     }
 }
 
 pub(crate) struct AppData<A> {
     pub(crate) pool: Pool,
+    /// The connections of `AsyncSubmissionClient`, which cancel the statements taking longer than
+    /// `submissions_query_timeout`.
+    pub(crate) reader: PgPool,
     pub(crate) authentication: A,
+    pub(crate) feature_flags: FeatureFlags,
+    pub(crate) cache: Arc<dyn Cache>,
+    pub(crate) cache_ttl: Duration,
+    pub(crate) query_timeout: Duration,
+    pub(crate) submissions_query_timeout: Duration,
+    pub(crate) quota: api_quota::ApiQuota,
+    pub(crate) code_fetcher: submission_code::CodeFetcher,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl<A: Clone> Clone for AppData<A> {
     fn clone(&self) -> Self {
         Self {
             pool: self.pool.clone(),
+            reader: self.reader.clone(),
             authentication: self.authentication.clone(),
+            feature_flags: self.feature_flags.clone(),
+            cache: self.cache.clone(),
+            cache_ttl: self.cache_ttl,
+            query_timeout: self.query_timeout,
+            submissions_query_timeout: self.submissions_query_timeout,
+            quota: self.quota,
+            code_fetcher: self.code_fetcher.clone(),
+            breaker: self.breaker.clone(),
         }
     }
 }
 
 impl<A> AppData<A> {
-    fn new(pool: Pool, authentication: A) -> Self {
+    fn new(
+        pool: Pool,
+        authentication: A,
+        cache: Arc<dyn Cache>,
+        cache_ttl: Duration,
+        query: &QueryConfig,
+        submission_code: &SubmissionCodeConfig,
+    ) -> Self {
+        let timeout = query.submissions_timeout();
+        let reader_options = pool
+            .reader_options
+            .clone()
+            .options([("statement_timeout", timeout.as_millis().to_string())]);
+        let reader = PgPoolOptions::new()
+            .max_connections(READER_POOL_SIZE)
+            .connect_timeout(timeout)
+            .connect_lazy_with(reader_options);
         Self {
             pool,
+            reader,
             authentication,
+            feature_flags: FeatureFlags::default(),
+            cache,
+            cache_ttl,
+            query_timeout: query.timeout(),
+            submissions_query_timeout: query.submissions_timeout(),
+            quota: api_quota::ApiQuota::new(query),
+            code_fetcher: submission_code::CodeFetcher::new(submission_code),
+            breaker: Arc::new(CircuitBreaker::new(
+                query.breaker_failure_threshold,
+                query.breaker_open_duration(),
+            )),
+        }
+    }
+
+    /// Runs the blocking database access `f` on the thread pool for blocking tasks, so that a slow
+    /// query does not hold a thread which serves the other requests.
+    pub(crate) async fn with_conn<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&diesel::PgConnection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.with_conn_timeout(self.query_timeout, f).await
+    }
+
+    /// Runs `f` as `with_conn` does, and cancels the queries which take longer than `timeout`.
+    /// A timeout, or a connection not acquired within `timeout`, fails with
+    /// `503 Service Unavailable` and is counted by the circuit breaker. While the breaker is open,
+    /// it fails immediately without touching the database.
+    pub(crate) async fn with_conn_timeout<F, T>(&self, timeout: Duration, f: F) -> Result<T>
+    where
+        F: FnOnce(&diesel::PgConnection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        if !self.breaker.allows() {
+            return Err(unavailable("The database is overloaded."));
+        }
+        let pool = self.pool.clone();
+        let result = async_std::future::timeout(
+            timeout,
+            task::spawn_blocking(move || {
+                let conn = pool
+                    .get_timeout(timeout)
+                    .map_err(|e| unavailable(format!("No connection is available: {}", e)))?;
+                conn.batch_execute(&format!("SET statement_timeout = {}", timeout.as_millis()))?;
+                let result = f(&conn).map_err(|e| {
+                    if is_statement_timeout(&e) {
+                        unavailable("The query timed out.")
+                    } else {
+                        e
+                    }
+                });
+                conn.batch_execute("SET statement_timeout = 0")?;
+                result
+            }),
+        )
+        .await
+        .unwrap_or_else(|_| Err(unavailable("The query timed out.")));
+
+        match &result {
+            Err(e) if e.status() == StatusCode::ServiceUnavailable => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+        result
+    }
+
+    /// Runs the async read `query` with the circuit breaker of `with_conn_timeout`, and fails
+    /// with `503 Service Unavailable` after `submissions_query_timeout`.
+    pub(crate) async fn read<F, T>(&self, query: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        if !self.breaker.allows() {
+            return Err(unavailable("The database is overloaded."));
+        }
+        let result = async_std::future::timeout(self.submissions_query_timeout, query)
+            .await
+            .unwrap_or_else(|_| Err(unavailable("The query timed out.")))
+            .map_err(|e| match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::PoolTimedOut) => unavailable("No connection is available."),
+                _ if is_statement_timeout(&e) => unavailable("The query timed out."),
+                _ => e,
+            });
+
+        match &result {
+            Err(e) if e.status() == StatusCode::ServiceUnavailable => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+        result
+    }
+}
+
+fn unavailable<M>(message: M) -> http_types::Error
+where
+    M: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    http_types::Error::from_str(StatusCode::ServiceUnavailable, message)
+}
+
+fn is_statement_timeout(e: &http_types::Error) -> bool {
+    if let Some(sqlx::Error::Database(e)) = e.downcast_ref::<sqlx::Error>() {
+        return e.message().contains("statement timeout");
+    }
+    match e.downcast_ref::<diesel::result::Error>() {
+        Some(diesel::result::Error::DatabaseError(_, info)) => {
+            info.message().contains("statement timeout")
         }
+        _ => false,
     }
 }
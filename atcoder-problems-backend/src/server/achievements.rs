@@ -0,0 +1,25 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::AchievementClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the achievements which the user has earned, with the time when each one was earned.
+pub(crate) async fn get_user_achievements<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let achievements = request
+        .state()
+        .with_conn(move |conn| conn.load_user_achievements(&query.user))
+        .await?;
+    let response = Response::new_cors().body_json(&achievements)?;
+    Ok(response)
+}
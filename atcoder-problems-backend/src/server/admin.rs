@@ -0,0 +1,283 @@
+use crate::feature_flags::Feature;
+use crate::server::utils::authenticate;
+use crate::server::validation::{read_valid_json_body, Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::moderation_manager::ModerationManager;
+use crate::sql::{ExcludedContestClient, FeatureFlagClient, JobRunClient, ValidationClient};
+
+use chrono::Utc;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use tide::{Middleware, Next, Request, Response};
+
+/// Rejects requests from users without the admin flag.
+#[derive(Debug)]
+pub(crate) struct RequireAdmin;
+
+impl RequireAdmin {
+    async fn is_allowed<A: Authentication + Clone>(
+        &self,
+        request: &Request<AppData<A>>,
+    ) -> tide::Result<bool> {
+        let token = match request.cookie("token") {
+            Some(token) => token,
+            None => return Ok(false),
+        };
+        let client = request.state().authentication.clone();
+        let conn = request.state().pool.get()?;
+        let (conn, internal_user_id) = match authenticate(&client, conn, token.value()).await {
+            Ok(authenticated) => authenticated,
+            Err(_) => return Ok(false),
+        };
+        let is_admin = conn.is_admin(&internal_user_id)?;
+        Ok(is_admin)
+    }
+}
+
+impl<A> Middleware<AppData<A>> for RequireAdmin
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if self.is_allowed(&request).await? {
+                next.run(request).await
+            } else {
+                Ok(Response::forbidden())
+            }
+        })
+    }
+}
+
+pub(crate) async fn delete_contest<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.delete_contest(&query.contest_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_list<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_list_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_list_id", &self.internal_list_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.delete_list(&query.internal_list_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_banned_users<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let conn = request.state().pool.get()?;
+    let user_ids = conn.get_banned_users()?;
+    let response = Response::ok().body_json(&user_ids)?;
+    Ok(response)
+}
+
+pub(crate) async fn ban_user<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        user_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("user_id", &self.user_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.ban_user(&query.user_id, Utc::now().timestamp())?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn unban_user<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        user_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("user_id", &self.user_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.unban_user(&query.user_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+/// Queues the contest for `crawl_requested_contests`.
+pub(crate) async fn request_recrawl<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.request_recrawl(&query.contest_id, Utc::now().timestamp())?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_excluded_contests<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let conn = request.state().pool.get()?;
+    let contests = conn.load_excluded_contests()?;
+    let response = Response::ok().body_json(&contests)?;
+    Ok(response)
+}
+
+/// Excludes the contest from the aggregates. The aggregates already made are removed by the next
+/// runs of the updaters.
+pub(crate) async fn exclude_contest<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        reason: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.exclude_contest(&query.contest_id, &query.reason, Utc::now().timestamp())?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn include_contest<A>(mut request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let conn = request.state().pool.get()?;
+    conn.include_contest(&query.contest_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_feature_flags<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let conn = request.state().pool.get()?;
+    let states = request.state().feature_flags.load_states(&*conn)?;
+    let response = Response::ok().body_json(&states)?;
+    Ok(response)
+}
+
+/// Toggles the feature. It takes effect in this process immediately, and in the others after
+/// their caches expire.
+pub(crate) async fn update_feature_flag<A>(
+    mut request: Request<AppData<A>>,
+) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        name: String,
+        enabled: bool,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("name", &self.name);
+        }
+    }
+    let query: Q = read_valid_json_body(&mut request).await?;
+    let feature = match Feature::parse(&query.name) {
+        Some(feature) => feature,
+        None => return Ok(Response::bad_request()),
+    };
+    let conn = request.state().pool.get()?;
+    conn.set_feature_flag(feature.name(), query.enabled, Utc::now().timestamp())?;
+    request.state().feature_flags.invalidate();
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+/// Returns the violations found by the latest run of the validator.
+pub(crate) async fn get_validation_violations<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let conn = request.state().pool.get()?;
+    let violations = conn.load_violations()?;
+    let response = Response::ok().body_json(&violations)?;
+    Ok(response)
+}
+
+/// Returns the run counts and the durations of the tiers of the delta updates.
+pub(crate) async fn get_update_tier_stats<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let conn = request.state().pool.get()?;
+    let stats = conn.load_update_tier_stats()?;
+    let response = Response::ok().body_json(&stats)?;
+    Ok(response)
+}
@@ -0,0 +1,184 @@
+use crate::config::QueryConfig;
+use crate::server::client_ip::client_ip;
+use crate::server::error_response::json_error;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::internal::api_token_manager::{hash_token, ApiTokenManager};
+use crate::sql::internal::api_usage_manager::ApiUsageManager;
+
+use chrono::Utc;
+use serde::Serialize;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use tide::http::StatusCode;
+use tide::{Middleware, Next, Request, Response};
+
+const DAY_SECOND: i64 = 24 * 3600;
+const LIMIT_HEADER: &str = "x-ratelimit-limit";
+const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// The path of the usage itself, which is not counted, so that the clients can see it anytime.
+const USAGE_PATH: &str = "/v3/internal/usage";
+
+/// The daily quotas of the requests to the public API.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ApiQuota {
+    anonymous: i64,
+    token: i64,
+}
+
+impl ApiQuota {
+    pub(crate) fn new(query: &QueryConfig) -> Self {
+        Self {
+            anonymous: query.anonymous_daily_quota,
+            token: query.token_daily_quota,
+        }
+    }
+}
+
+/// Who the requests are counted for: an API token, or an anonymous client by its address.
+enum QuotaSubject {
+    Token { token_hash: String },
+    Anonymous(IpAddr),
+}
+
+impl QuotaSubject {
+    /// The known token of the request, or the address of the client if it has no known token.
+    fn resolve<A>(request: &Request<AppData<A>>) -> tide::Result<Option<Self>> {
+        if let Some(token) = request.cookie("token") {
+            let conn = request.state().pool.get()?;
+            if conn.find_token(token.value())?.is_some() {
+                return Ok(Some(QuotaSubject::Token {
+                    token_hash: hash_token(token.value()),
+                }));
+            }
+        }
+        Ok(client_ip(request).map(QuotaSubject::Anonymous))
+    }
+
+    fn key(&self) -> String {
+        match self {
+            QuotaSubject::Token { token_hash } => format!("token:{}", token_hash),
+            QuotaSubject::Anonymous(address) => format!("ip:{}", address),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            QuotaSubject::Token { .. } => "token",
+            QuotaSubject::Anonymous(_) => "anonymous",
+        }
+    }
+
+    fn limit(&self, quota: ApiQuota) -> i64 {
+        match self {
+            QuotaSubject::Token { .. } => quota.token,
+            QuotaSubject::Anonymous(_) => quota.anonymous,
+        }
+    }
+}
+
+/// The beginning of the day in UTC, when the quotas are reset.
+fn day_epoch_second(now: i64) -> i64 {
+    now - now.rem_euclid(DAY_SECOND)
+}
+
+/// Rejects the requests to the public API over the daily quota of the API token, or of the
+/// address of an anonymous client, with `429 Too Many Requests`.
+#[derive(Debug)]
+pub(crate) struct DailyQuota;
+
+impl<A> Middleware<AppData<A>> for DailyQuota
+where
+    A: Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if request.uri().path().ends_with(USAGE_PATH) {
+                return next.run(request).await;
+            }
+            let subject = match QuotaSubject::resolve(&request)? {
+                Some(subject) => subject,
+                None => return next.run(request).await,
+            };
+            let now = Utc::now().timestamp();
+            let day_epoch_second = day_epoch_second(now);
+            let used = request
+                .state()
+                .pool
+                .get()?
+                .count_api_usage(&subject.key(), day_epoch_second)?;
+            let limit = subject.limit(request.state().quota);
+            if used > limit {
+                let response = json_error(
+                    StatusCode::TooManyRequests,
+                    "rate_limited",
+                    "The daily quota of the requests is exhausted.",
+                    &[],
+                )
+                .set_header(LIMIT_HEADER, limit.to_string())
+                .set_header(REMAINING_HEADER, "0")
+                .set_header(
+                    "retry-after",
+                    (day_epoch_second + DAY_SECOND - now).to_string(),
+                );
+                return Ok(response);
+            }
+            let response = next.run(request).await?;
+            Ok(response
+                .set_header(LIMIT_HEADER, limit.to_string())
+                .set_header(REMAINING_HEADER, (limit - used).to_string()))
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Usage {
+    kind: &'static str,
+    limit: i64,
+    used: i64,
+    remaining: i64,
+    reset_epoch_second: i64,
+}
+
+/// Returns the daily quota of the client and how much of it is used today.
+pub(crate) async fn get_usage<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Send + Sync + 'static,
+{
+    let subject = match QuotaSubject::resolve(&request)? {
+        Some(subject) => subject,
+        None => return Ok(Response::not_found()),
+    };
+    let day_epoch_second = day_epoch_second(Utc::now().timestamp());
+    let used = request
+        .state()
+        .pool
+        .get()?
+        .get_api_usage(&subject.key(), day_epoch_second)?;
+    let limit = subject.limit(request.state().quota);
+    let response = Response::new_cors().body_json(&Usage {
+        kind: subject.kind(),
+        limit,
+        used,
+        remaining: (limit - used).max(0),
+        reset_epoch_second: day_epoch_second + DAY_SECOND,
+    })?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_epoch_second() {
+        assert_eq!(day_epoch_second(0), 0);
+        assert_eq!(day_epoch_second(DAY_SECOND - 1), 0);
+        assert_eq!(day_epoch_second(DAY_SECOND), DAY_SECOND);
+        assert_eq!(day_epoch_second(DAY_SECOND * 3 + 100), DAY_SECOND * 3);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::api_token_manager::{ApiTokenManager, Scope};
+
+use chrono::Utc;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use tide::http::Method;
+use tide::{Middleware, Next, Request, Response};
+
+/// Rejects requests with a named token which does not have the scope. Only writes are checked
+/// unless `all_methods` is set, and `scope` of `None` allows only the tokens issued on login.
+#[derive(Debug)]
+pub(crate) struct RequireScope {
+    scope: Option<Scope>,
+    all_methods: bool,
+}
+
+impl RequireScope {
+    /// Writes require the scope.
+    pub(crate) fn write(scope: Scope) -> Self {
+        Self {
+            scope: Some(scope),
+            all_methods: false,
+        }
+    }
+
+    /// Writes require a token issued on login.
+    pub(crate) fn login_write() -> Self {
+        Self {
+            scope: None,
+            all_methods: false,
+        }
+    }
+
+    /// All the requests require a token issued on login.
+    pub(crate) fn login() -> Self {
+        Self {
+            scope: None,
+            all_methods: true,
+        }
+    }
+
+    fn is_allowed<A>(&self, request: &Request<AppData<A>>) -> tide::Result<bool> {
+        if !self.all_methods && request.method() == Method::Get {
+            return Ok(true);
+        }
+        let token = match request.cookie("token") {
+            Some(token) => token,
+            None => return Ok(true),
+        };
+        let conn = request.state().pool.get()?;
+        let allowed = conn
+            .find_token(token.value())?
+            .map(|record| record.allows(self.scope))
+            .unwrap_or(true);
+        Ok(allowed)
+    }
+}
+
+impl<A> Middleware<AppData<A>> for RequireScope
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if self.is_allowed(&request)? {
+                next.run(request).await
+            } else {
+                Ok(Response::forbidden())
+            }
+        })
+    }
+}
+
+pub(crate) async fn create_token<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        name: String,
+        scopes: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("name", &self.name)
+                .varchars("scopes", &self.scopes);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let scopes = match query
+        .scopes
+        .iter()
+        .map(|scope| Scope::parse(scope))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(scopes) => scopes,
+        None => return Ok(Response::bad_request()),
+    };
+    let now = Utc::now().timestamp();
+    let (id, token) = conn.create_named_token(&internal_user_id, &query.name, &scopes, now)?;
+    let body = serde_json::json!({ "id": id, "token": token });
+    let response = Response::ok().body_json(&body)?;
+    Ok(response)
+}
+
+pub(crate) async fn get_tokens<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let tokens = conn.get_tokens(&internal_user_id)?;
+    let response = Response::ok().body_json(&tokens)?;
+    Ok(response)
+}
+
+pub(crate) async fn revoke_token<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("id", &self.id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.revoke_token(&internal_user_id, &query.id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
@@ -0,0 +1,119 @@
+use crate::server::client_ip::client_ip;
+use crate::server::utils::{authenticate, AuthenticatedUser};
+use crate::server::validation::read_capped_body;
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::audit_log_manager::AuditLogManager;
+
+use chrono::Utc;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use tide::http::Method;
+use tide::{Middleware, Next, Request, Response};
+
+const MAX_AUDIT_LOG_COUNT: i64 = 1000;
+
+/// Records the writes by authenticated users into the audit log. The authenticated user is passed
+/// to the handlers as `AuthenticatedUser`. A failure to record a write is logged, and does not
+/// fail the write.
+#[derive(Debug)]
+pub(crate) struct AuditLog;
+
+impl<A> Middleware<AppData<A>> for AuditLog
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        mut request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if request.method() == Method::Get {
+                return next.run(request).await;
+            }
+            let token = match request.cookie("token") {
+                Some(token) => token,
+                None => return next.run(request).await,
+            };
+            let client = request.state().authentication.clone();
+            let conn = match request.state().pool.get() {
+                Ok(conn) => conn,
+                Err(e) => {
+                    log::error!("Failed to record the audit log: {:?}", e);
+                    return next.run(request).await;
+                }
+            };
+            let internal_user_id = match authenticate(&client, conn, token.value()).await {
+                Ok((_, internal_user_id)) => internal_user_id,
+                Err(_) => return next.run(request).await,
+            };
+
+            let payload = read_capped_body(&mut request).await?;
+            let payload_summary = summarize_payload(&payload);
+            request.as_mut().set_body(payload);
+            let request = request.set_ext(AuthenticatedUser(internal_user_id.clone()));
+            let method = request.method().to_string();
+            let path = request.uri().path().to_owned();
+            let pool = request.state().pool.clone();
+            let client_ip = client_ip(&request).map(|address| address.to_string());
+
+            let result = next.run(request).await;
+            let status = match &result {
+                Ok(response) => response.status(),
+                Err(e) => e.status(),
+            };
+            let recorded = pool
+                .get()
+                .map_err(http_types::Error::from)
+                .and_then(|conn| {
+                    conn.record_audit_log(
+                        &internal_user_id,
+                        &method,
+                        &path,
+                        status as u16,
+                        &payload_summary,
+                        Utc::now().timestamp(),
+                        client_ip.as_deref(),
+                    )
+                });
+            if let Err(e) = recorded {
+                log::error!("Failed to record the audit log: {:?}", e);
+            }
+            result
+        })
+    }
+}
+
+/// Describes the body without its values, which may contain secrets, e.g. the secrets of the
+/// webhooks or the email addresses: the names of the fields of a JSON object, and the size.
+fn summarize_payload(payload: &[u8]) -> String {
+    match serde_json::from_slice::<serde_json::Value>(payload) {
+        Ok(serde_json::Value::Object(fields)) => {
+            let names = fields.keys().map(|name| name.as_str()).collect::<Vec<_>>();
+            format!("fields: {} ({} bytes)", names.join(", "), payload.len())
+        }
+        _ => format!("{} bytes", payload.len()),
+    }
+}
+
+pub(crate) async fn get_audit_logs<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        user: Option<String>,
+        keyword: Option<String>,
+        count: Option<i64>,
+    }
+    let query = request.query::<Q>()?;
+    let count = query
+        .count
+        .unwrap_or(MAX_AUDIT_LOG_COUNT)
+        .clamp(1, MAX_AUDIT_LOG_COUNT);
+    let conn = request.state().pool.get()?;
+    let entries = conn.get_audit_logs(query.user.as_deref(), query.keyword.as_deref(), count)?;
+    let response = Response::ok().body_json(&entries)?;
+    Ok(response)
+}
@@ -1,7 +1,9 @@
 use crate::error::Result;
 use crate::server::AppData;
+use crate::sql::internal::api_token_manager::ApiTokenManager;
 use crate::sql::internal::user_manager::UserManager;
 use async_trait::async_trait;
+use chrono::Utc;
 use cookie::Cookie;
 use serde::{Deserialize, Serialize};
 use tide::{Request, Response};
@@ -85,6 +87,7 @@ pub(crate) async fn get_token<A: Authentication + Clone>(
     let response = client.get_user_id(&token).await?;
     let internal_user_id = response.id.to_string();
     conn.register_user(&internal_user_id)?;
+    let token = conn.issue_token(&internal_user_id, Utc::now().timestamp())?;
 
     let cookie = Cookie::build("token", token).path("/").finish();
     let redirect_url = "https://kenkoooo.com/atcoder/#/login/user";
@@ -0,0 +1,194 @@
+use crate::error::Result;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::internal::api_token_manager::ApiTokenManager;
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::models::Contest;
+use crate::sql::SimpleClient;
+
+use chrono::{TimeZone, Utc};
+use diesel::PgConnection;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+const ATCODER_URL: &str = "https://atcoder.jp";
+const VIRTUAL_CONTEST_URL: &str = "https://kenkoooo.com/atcoder/#/contest/show";
+const MAX_LINE_OCTETS: usize = 75;
+
+struct Event {
+    uid: String,
+    summary: String,
+    url: String,
+    start_epoch_second: i64,
+    end_epoch_second: i64,
+}
+
+impl Event {
+    fn from_contest(contest: Contest) -> Self {
+        Self {
+            uid: format!("atcoder-{}@kenkoooo.com", contest.id),
+            url: format!("{}/contests/{}", ATCODER_URL, contest.id),
+            summary: contest.title,
+            start_epoch_second: contest.start_epoch_second,
+            end_epoch_second: contest.start_epoch_second + contest.duration_second,
+        }
+    }
+}
+
+/// Returns the official contests which have not ended yet.
+pub(crate) async fn get_contests_calendar<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let now = Utc::now().timestamp();
+    let events = request
+        .state()
+        .with_conn(move |conn| load_upcoming_contests(conn, now))
+        .await?;
+    Ok(calendar_response(to_ical(&events, now)))
+}
+
+/// Returns the upcoming official contests and the virtual contests which the user has joined.
+/// Calendar apps can not send cookies, so the user is authenticated by an API token passed in
+/// the query, e.g. a named token with the `read-only` scope.
+pub(crate) async fn get_user_calendar<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        token: String,
+    }
+    let query = request.query::<Query>()?;
+    let conn = request.state().pool.get()?;
+    let internal_user_id = match conn.find_token_user(&query.token)? {
+        Some(internal_user_id) => internal_user_id,
+        None => return Ok(Response::forbidden()),
+    };
+
+    let now = Utc::now().timestamp();
+    let mut events = load_upcoming_contests(&conn, now)?;
+    events.extend(
+        conn.get_participated_contest_info(&internal_user_id)?
+            .into_iter()
+            .map(|contest| Event {
+                uid: format!("virtual-{}@kenkoooo.com", contest.id),
+                url: format!("{}/{}", VIRTUAL_CONTEST_URL, contest.id),
+                summary: contest.title,
+                start_epoch_second: contest.start_epoch_second,
+                end_epoch_second: contest.start_epoch_second + contest.duration_second,
+            }),
+    );
+    events.sort_by_key(|event| event.start_epoch_second);
+    Ok(calendar_response(to_ical(&events, now)))
+}
+
+fn load_upcoming_contests(conn: &PgConnection, now: i64) -> Result<Vec<Event>> {
+    let mut contests = conn
+        .load_contests()?
+        .into_iter()
+        .filter(|contest| contest.start_epoch_second + contest.duration_second > now)
+        .collect::<Vec<_>>();
+    contests.sort_by_key(|contest| contest.start_epoch_second);
+    Ok(contests.into_iter().map(Event::from_contest).collect())
+}
+
+fn calendar_response(body: String) -> Response {
+    Response::new_cors()
+        .body_string(body)
+        .set_header("content-type", "text/calendar; charset=utf-8")
+}
+
+/// The times are written in UTC, so that calendar apps show them in the local time zone of the
+/// user without any VTIMEZONE definition.
+fn to_ical(events: &[Event], now: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//AtCoder Problems//Contests//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "X-WR-CALNAME:AtCoder Problems".to_owned(),
+    ];
+    for event in events.iter() {
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", to_ical_time(now)));
+        lines.push(format!(
+            "DTSTART:{}",
+            to_ical_time(event.start_epoch_second)
+        ));
+        lines.push(format!("DTEND:{}", to_ical_time(event.end_epoch_second)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("URL:{}", event.url));
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+fn to_ical_time(epoch_second: i64) -> String {
+    Utc.timestamp(epoch_second, 0)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+/// Folds the line into the lines of at most 75 octets without splitting multi-byte characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("short"), "short");
+        let line = "あ".repeat(30);
+        let folded = fold(&line);
+        let lines = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "あ".repeat(5)));
+    }
+
+    #[test]
+    fn test_to_ical() {
+        let events = [Event {
+            uid: "atcoder-abc100@kenkoooo.com".to_owned(),
+            summary: "AtCoder Beginner Contest 100".to_owned(),
+            url: "https://atcoder.jp/contests/abc100".to_owned(),
+            start_epoch_second: 1529154000,
+            end_epoch_second: 1529160000,
+        }];
+        let ical = to_ical(&events, 1529000000);
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.contains("\r\nDTSTART:20180616T130000Z\r\n"));
+        assert!(ical.contains("\r\nDTEND:20180616T144000Z\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Stops sending the queries to the database while it is overloaded.
+///
+/// The breaker opens after `failure_threshold` consecutive failures, and rejects every call until
+/// `open_duration` passes. Then it lets one trial call through: a success closes the breaker, and
+/// a failure opens it again.
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_duration,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether the call is allowed now.
+    pub(crate) fn allows(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => true,
+            Some(opened_at) if opened_at.elapsed() >= self.open_duration => {
+                // Holds the other calls until the trial call finishes.
+                state.opened_at = Some(Instant::now());
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+    }
+
+    pub(crate) fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold {
+            if state.opened_at.is_none() {
+                log::warn!(
+                    "The circuit breaker is opened after {} consecutive failures",
+                    state.consecutive_failures
+                );
+            }
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(3600));
+        breaker.record_failure();
+        assert!(breaker.allows());
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(breaker.allows(), "The failures must be consecutive.");
+        breaker.record_failure();
+        assert!(!breaker.allows());
+        breaker.record_success();
+        assert!(breaker.allows());
+    }
+
+    #[test]
+    fn test_trial_call() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(0));
+        breaker.record_failure();
+        assert!(breaker.allows());
+        breaker.record_failure();
+        assert!(breaker.allows());
+        breaker.record_success();
+        assert!(breaker.allows());
+
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(100));
+        breaker.record_failure();
+        assert!(!breaker.allows());
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(
+            breaker.allows(),
+            "A trial call is allowed after the duration."
+        );
+        assert!(!breaker.allows(), "Only one trial call is allowed at once.");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::classroom_manager::ClassroomManager;
+
+use chrono::Utc;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+const MAX_TITLE_LENGTH: usize = 255;
+
+/// Creates an assignment of the problems for a group of the coach.
+pub(crate) async fn create_assignment<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_group_id: String,
+        title: String,
+        deadline_epoch_second: i64,
+        problem_ids: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("title", &self.title, 0, MAX_TITLE_LENGTH);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let problem_ids = query
+        .problem_ids
+        .iter()
+        .map(|problem_id| problem_id.as_str())
+        .collect::<Vec<_>>();
+    let internal_assignment_id = conn.create_assignment(
+        &internal_user_id,
+        &query.internal_group_id,
+        &query.title,
+        query.deadline_epoch_second,
+        &problem_ids,
+        Utc::now().timestamp(),
+    )?;
+    let body = serde_json::json!({ "internal_assignment_id": internal_assignment_id });
+    let response = Response::ok().body_json(&body)?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_assignment<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_assignment_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_assignment_id", &self.internal_assignment_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_assignment(&internal_user_id, &query.internal_assignment_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_assignments<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        internal_group_id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let assignments = request
+        .state()
+        .with_conn(move |conn| conn.get_assignments(&query.internal_group_id))
+        .await?;
+    let response = Response::ok().body_json(&assignments)?;
+    Ok(response)
+}
+
+/// Returns the progress matrix of the assignment, which has a row for each member of the group
+/// and a cell for each problem. Anyone who knows the id of the assignment can see it, so that the
+/// members can check their own progress.
+pub(crate) async fn get_assignment_progress<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let internal_assignment_id = request.param::<String>("internal_assignment_id")?;
+    let progress = request
+        .state()
+        .with_conn(move |conn| conn.get_assignment_progress(&internal_assignment_id))
+        .await?;
+    let response = Response::ok().body_json(&progress)?;
+    Ok(response)
+}
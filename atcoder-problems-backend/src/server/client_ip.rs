@@ -0,0 +1,199 @@
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use tide::{Middleware, Next, Request};
+
+const FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// The address of the peer of the connection, which is set by the server for each request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PeerAddr(pub(crate) IpAddr);
+
+/// The address of the client resolved by `ClientIp`.
+#[derive(Debug, Clone, Copy)]
+struct ResolvedClientIp(IpAddr);
+
+/// A range of addresses in the CIDR notation, e.g. `10.0.0.0/8`. A single address is the range
+/// of the full prefix length.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IpNetwork {
+    address: IpAddr,
+    prefix_length: u8,
+}
+
+impl IpNetwork {
+    pub(crate) fn parse(network: &str) -> Option<Self> {
+        let mut parts = network.trim().splitn(2, '/');
+        let address = parts.next()?.parse::<IpAddr>().ok()?;
+        let max_length = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix_length = match parts.next() {
+            Some(length) => length.parse::<u8>().ok()?,
+            None => max_length,
+        };
+        if prefix_length > max_length {
+            return None;
+        }
+        Some(Self {
+            address,
+            prefix_length,
+        })
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        match (self.address, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Resolves the address of the client. `X-Forwarded-For` is trusted only when the peer is one of
+/// the trusted proxies, and the addresses in it are read from the right, skipping the trusted
+/// proxies, so that a client cannot spoof its address by sending the header by itself.
+#[derive(Debug)]
+pub(crate) struct ClientIp {
+    trusted_proxies: Vec<IpNetwork>,
+}
+
+impl ClientIp {
+    /// Creates the middleware trusting the given CIDR ranges. The invalid ones are ignored, since
+    /// they are rejected by the validation of the configuration.
+    pub(crate) fn new(trusted_proxies: &[String]) -> Self {
+        Self {
+            trusted_proxies: trusted_proxies
+                .iter()
+                .filter_map(|network| IpNetwork::parse(network))
+                .collect(),
+        }
+    }
+
+    fn is_trusted(&self, address: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|network| network.contains(address))
+    }
+
+    fn resolve(&self, peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        let mut client = peer;
+        if !self.is_trusted(client) {
+            return client;
+        }
+        let hops = forwarded_for.unwrap_or("").rsplit(',');
+        for hop in hops {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(address) => {
+                    client = address;
+                    if !self.is_trusted(address) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        client
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<State> for ClientIp {
+    fn handle<'a>(
+        &'a self,
+        request: Request<State>,
+        next: Next<'a, State>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            let peer = match request.ext::<PeerAddr>() {
+                Some(&PeerAddr(peer)) => peer,
+                None => return next.run(request).await,
+            };
+            let forwarded_for = request
+                .header(FORWARDED_FOR)
+                .map(|values| {
+                    values
+                        .iter()
+                        .map(|value| value.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .filter(|value| !value.is_empty());
+            let client = self.resolve(peer, forwarded_for.as_deref());
+            next.run(request.set_ext(ResolvedClientIp(client))).await
+        })
+    }
+}
+
+/// Returns the address of the client resolved by `ClientIp`.
+pub(crate) fn client_ip<State>(request: &Request<State>) -> Option<IpAddr> {
+    request
+        .ext::<ResolvedClientIp>()
+        .map(|&ResolvedClientIp(address)| address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ip_network() {
+        let network = IpNetwork::parse("10.0.0.0/8").unwrap();
+        assert!(network.contains(ip("10.1.2.3")));
+        assert!(!network.contains(ip("11.0.0.1")));
+        assert!(!network.contains(ip("::1")));
+
+        assert!(IpNetwork::parse("0.0.0.0/0")
+            .unwrap()
+            .contains(ip("192.168.0.1")));
+        assert!(IpNetwork::parse("192.168.0.1")
+            .unwrap()
+            .contains(ip("192.168.0.1")));
+        assert!(IpNetwork::parse("fd00::/8")
+            .unwrap()
+            .contains(ip("fd12::1")));
+
+        assert_eq!(IpNetwork::parse("10.0.0.0/33"), None);
+        assert_eq!(IpNetwork::parse("localhost"), None);
+        assert_eq!(IpNetwork::parse("10.0.0.0/x"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let client_ip = ClientIp::new(&["10.0.0.0/8".to_owned(), "invalid".to_owned()]);
+
+        // The header from an untrusted peer is ignored.
+        assert_eq!(
+            client_ip.resolve(ip("1.1.1.1"), Some("2.2.2.2")),
+            ip("1.1.1.1")
+        );
+        assert_eq!(client_ip.resolve(ip("10.0.0.1"), None), ip("10.0.0.1"));
+        assert_eq!(
+            client_ip.resolve(ip("10.0.0.1"), Some("2.2.2.2")),
+            ip("2.2.2.2")
+        );
+        // The spoofed address on the left of the real one is ignored.
+        assert_eq!(
+            client_ip.resolve(ip("10.0.0.1"), Some("3.3.3.3, 2.2.2.2, 10.0.0.2")),
+            ip("2.2.2.2")
+        );
+        assert_eq!(
+            client_ip.resolve(ip("10.0.0.1"), Some("unknown, 10.0.0.2")),
+            ip("10.0.0.2")
+        );
+    }
+}
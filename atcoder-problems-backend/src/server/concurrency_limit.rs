@@ -0,0 +1,147 @@
+use crate::server::error_response::json_error;
+
+use async_std::task;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tide::http::StatusCode;
+use tide::{Middleware, Next, Request};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const RETRY_AFTER_SECOND: &str = "1";
+
+/// Limits the number of the requests handled at once by a group of the routes, so that a heavy
+/// endpoint being hammered does not take all the connections of the pool.
+///
+/// A request over the limit waits for a slot for at most `queue_timeout`, and then it is shed
+/// with `503 Service Unavailable`. The clones share the slots, so that the routes of a group are
+/// limited together.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencyLimit {
+    group: &'static str,
+    limit: usize,
+    queue_timeout: Duration,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// A slot of `ConcurrencyLimit`, which is released when dropped.
+struct Permit {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(group: &'static str, limit: usize, queue_timeout: Duration) -> Self {
+        Self {
+            group,
+            limit,
+            queue_timeout,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn try_acquire(&self) -> Option<Permit> {
+        let mut current = self.in_flight.load(Ordering::SeqCst);
+        while current < self.limit {
+            match self.in_flight.compare_exchange(
+                current,
+                current + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => {
+                    return Some(Permit {
+                        in_flight: self.in_flight.clone(),
+                    })
+                }
+                Err(actual) => current = actual,
+            }
+        }
+        None
+    }
+
+    async fn acquire(&self) -> Option<Permit> {
+        let deadline = Instant::now() + self.queue_timeout;
+        loop {
+            if let Some(permit) = self.try_acquire() {
+                return Some(permit);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            task::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<State> for ConcurrencyLimit {
+    fn handle<'a>(
+        &'a self,
+        request: Request<State>,
+        next: Next<'a, State>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            let _permit = match self.acquire().await {
+                Some(permit) => permit,
+                None => {
+                    log::warn!(
+                        "Shed a request to {}: {} requests are in flight",
+                        self.group,
+                        self.limit
+                    );
+                    let response = json_error(
+                        StatusCode::ServiceUnavailable,
+                        "unavailable",
+                        "Too many requests are in progress. Please retry later.",
+                        &[],
+                    )
+                    .set_header("retry-after", RETRY_AFTER_SECOND);
+                    return Ok(response);
+                }
+            };
+            next.run(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_acquire() {
+        let limit = ConcurrencyLimit::new("test", 2, Duration::from_secs(0));
+        let first = limit.try_acquire().unwrap();
+        let shared = limit.clone();
+        let second = shared.try_acquire().unwrap();
+        assert!(limit.try_acquire().is_none(), "The clones share the slots.");
+        drop(first);
+        assert!(limit.try_acquire().is_some());
+        drop(second);
+        assert_eq!(limit.in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_acquire_waits_for_a_slot() {
+        task::block_on(async {
+            let limit = ConcurrencyLimit::new("test", 1, Duration::from_millis(500));
+            let permit = limit.try_acquire().unwrap();
+            let waiting = limit.clone();
+            let waiter = task::spawn(async move { waiting.acquire().await.is_some() });
+            task::sleep(Duration::from_millis(50)).await;
+            drop(permit);
+            assert!(waiter.await);
+
+            let limit = ConcurrencyLimit::new("test", 1, Duration::from_millis(50));
+            let _permit = limit.try_acquire().unwrap();
+            assert!(limit.acquire().await.is_none());
+        });
+    }
+}
@@ -0,0 +1,28 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::ContestCompletionClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the numbers of the problems solved by the user in each contest, so that the contest
+/// tables can show the fully solved contests without all the submissions of the user.
+pub(crate) async fn get_contest_completion<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+        #[serde(default)]
+        completed: bool,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let completion = request
+        .state()
+        .with_conn(move |conn| conn.load_users_contest_completion(&query.user, query.completed))
+        .await?;
+    let response = Response::new_cors().body_json(&completion)?;
+    Ok(response)
+}
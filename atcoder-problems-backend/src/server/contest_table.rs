@@ -0,0 +1,160 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::ContestCategory;
+use crate::sql::{ContestProblemClient, SimpleClient, SubmissionClient, SubmissionRequest};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tide::{Request, Response};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SolveStatus {
+    /// The first AC is before the end of the contest.
+    AcInContest,
+    /// The first AC is after the end of the contest.
+    AcAfter,
+    /// Submitted, but not accepted yet.
+    Tried,
+    Untouched,
+}
+
+/// The submissions of the user to a problem.
+#[derive(Default)]
+struct ProblemSubmissions {
+    first_ac_epoch_second: Option<i64>,
+    tried: bool,
+}
+
+impl ProblemSubmissions {
+    fn status(&self, contest_end_epoch_second: i64) -> SolveStatus {
+        match self.first_ac_epoch_second {
+            Some(ac) if ac < contest_end_epoch_second => SolveStatus::AcInContest,
+            Some(_) => SolveStatus::AcAfter,
+            None if self.tried => SolveStatus::Tried,
+            None => SolveStatus::Untouched,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProblemCell {
+    problem_id: String,
+    status: SolveStatus,
+    first_ac_epoch_second: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ContestRow {
+    contest_id: String,
+    title: String,
+    start_epoch_second: i64,
+    problems: Vec<ProblemCell>,
+}
+
+/// Returns the contest table of the category from the newest contest, where each problem has
+/// the solve status of the user, so that the table can be drawn without all the submissions.
+pub(crate) async fn get_contest_table<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+        category: Option<ContestCategory>,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let category = query.category;
+    let state = request.state();
+    let (contests, contest_problems, submissions) = state
+        .with_conn_timeout(state.submissions_query_timeout, move |conn| {
+            let contests = conn.load_contests()?;
+            let contest_problems = conn.load_contest_problem()?;
+            let submissions = conn.get_submissions(SubmissionRequest::UserAll {
+                user_id: &query.user,
+            })?;
+            Ok((contests, contest_problems, submissions))
+        })
+        .await?;
+
+    let mut problem_submissions = BTreeMap::new();
+    for submission in submissions.into_iter() {
+        let entry = problem_submissions
+            .entry(submission.problem_id.clone())
+            .or_insert_with(ProblemSubmissions::default);
+        entry.tried = true;
+        if submission.verdict().is_accepted() {
+            entry.first_ac_epoch_second = Some(
+                entry
+                    .first_ac_epoch_second
+                    .map_or(submission.epoch_second, |ac| {
+                        ac.min(submission.epoch_second)
+                    }),
+            );
+        }
+    }
+    let mut problems_of_contest = BTreeMap::new();
+    for contest_problem in contest_problems.into_iter() {
+        problems_of_contest
+            .entry(contest_problem.contest_id)
+            .or_insert_with(Vec::new)
+            .push(contest_problem.problem_id);
+    }
+
+    let untouched = ProblemSubmissions::default();
+    let mut contests = contests
+        .into_iter()
+        .filter(|contest| category.into_iter().all(|c| contest.category() == c))
+        .collect::<Vec<_>>();
+    contests.sort_by(|a, b| {
+        b.start_epoch_second
+            .cmp(&a.start_epoch_second)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    let rows = contests
+        .into_iter()
+        .map(|contest| {
+            let end_epoch_second = contest.start_epoch_second + contest.duration_second;
+            let mut problem_ids = problems_of_contest.remove(&contest.id).unwrap_or_default();
+            problem_ids.sort();
+            let problems = problem_ids
+                .into_iter()
+                .map(|problem_id| {
+                    let submissions = problem_submissions.get(&problem_id).unwrap_or(&untouched);
+                    ProblemCell {
+                        status: submissions.status(end_epoch_second),
+                        first_ac_epoch_second: submissions.first_ac_epoch_second,
+                        problem_id,
+                    }
+                })
+                .collect();
+            ContestRow {
+                contest_id: contest.id,
+                title: contest.title,
+                start_epoch_second: contest.start_epoch_second,
+                problems,
+            }
+        })
+        .collect::<Vec<_>>();
+    let response = Response::new_cors().body_json(&rows)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let status = |first_ac_epoch_second, tried| {
+            ProblemSubmissions {
+                first_ac_epoch_second,
+                tried,
+            }
+            .status(100)
+        };
+        assert_eq!(status(Some(99), true), SolveStatus::AcInContest);
+        assert_eq!(status(Some(100), true), SolveStatus::AcAfter);
+        assert_eq!(status(None, true), SolveStatus::Tried);
+        assert_eq!(status(None, false), SolveStatus::Untouched);
+    }
+}
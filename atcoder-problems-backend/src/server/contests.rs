@@ -0,0 +1,181 @@
+use crate::estimation::interpolate_difficulty;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::{ContestCategory, ContestProblemModel, ContestProblemStats};
+use crate::sql::{ContestStatsClient, ProblemModelClient, SimpleClient};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tide::{Request, Response};
+
+const DEFAULT_CONTESTS_PER_PAGE: usize = 100;
+const MAX_CONTESTS_PER_PAGE: usize = 1000;
+const JST_OFFSET_SECOND: i64 = 9 * 3600;
+
+/// Lists the contests from the newest one, filtered by the category, the start date in JST,
+/// whether they are rated and the rating which they are rated for. The number of the matched contests is in `x-total-count`, so that the
+/// clients can tell how many pages there are.
+pub(crate) async fn get_contests<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        category: Option<ContestCategory>,
+        from: Option<String>,
+        rated: Option<bool>,
+        rated_for: Option<i32>,
+        page: Option<usize>,
+        per_page: Option<usize>,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let from_epoch_second = match query.from.as_deref().map(parse_jst_date) {
+        Some(Some(from_epoch_second)) => Some(from_epoch_second),
+        Some(None) => return Ok(Response::bad_request()),
+        None => None,
+    };
+    let page = query.page.unwrap_or(0);
+    let per_page = query.per_page.unwrap_or(DEFAULT_CONTESTS_PER_PAGE);
+    if per_page == 0 || per_page > MAX_CONTESTS_PER_PAGE {
+        return Ok(Response::bad_request());
+    }
+
+    let mut contests = request
+        .state()
+        .with_conn(|conn| conn.load_contests())
+        .await?
+        .into_iter()
+        .filter(|contest| {
+            query.category.into_iter().all(|c| contest.category() == c)
+                && from_epoch_second
+                    .into_iter()
+                    .all(|from| contest.start_epoch_second >= from)
+                && query
+                    .rated
+                    .into_iter()
+                    .all(|rated| contest.is_rated() == rated)
+                && query.rated_for.into_iter().all(|rating| {
+                    contest
+                        .rated_range()
+                        .map_or(false, |range| range.contains(rating))
+                })
+        })
+        .collect::<Vec<_>>();
+    contests.sort_by(|a, b| {
+        b.start_epoch_second
+            .cmp(&a.start_epoch_second)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    let total_count = contests.len();
+    let contests = contests
+        .into_iter()
+        .skip(page.saturating_mul(per_page))
+        .take(per_page)
+        .collect::<Vec<_>>();
+    let response = Response::new_cors()
+        .body_json(&contests)?
+        .set_header("x-total-count", total_count.to_string())
+        .set_header("access-control-expose-headers", "x-total-count");
+    Ok(response)
+}
+
+/// Parses `YYYY-MM-DD` as the beginning of the day in JST.
+#[derive(Serialize)]
+struct ContestStatsResponse {
+    contest_id: String,
+    participant_count: i32,
+    full_solve_count: i32,
+    problems: Vec<ContestProblemStats>,
+}
+
+/// Returns the numbers of the users who took part in the contest, who solved all its problems,
+/// and who submitted to and solved each problem during the contest, for the post-contest analysis.
+pub(crate) async fn get_contest_stats<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let stats = request
+        .state()
+        .with_conn(move |conn| conn.load_contest_stats(&query.id))
+        .await?;
+    match stats {
+        Some((stats, problems)) => {
+            let response = Response::new_cors().body_json(&ContestStatsResponse {
+                contest_id: stats.contest_id,
+                participant_count: stats.participant_count,
+                full_solve_count: stats.full_solve_count,
+                problems,
+            })?;
+            Ok(response)
+        }
+        None => Ok(Response::not_found()),
+    }
+}
+
+#[derive(Serialize)]
+struct ContestDifficulty {
+    problem_id: String,
+    difficulty: Option<f64>,
+    is_interpolated: bool,
+}
+
+/// Returns the difficulties of the problems of the contest. The problems without the model yet,
+/// e.g. those of a contest which has just ended, get the difficulties interpolated from the
+/// numbers of the in-contest solvers of the other problems.
+pub(crate) async fn get_contest_difficulties<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let models = request
+        .state()
+        .with_conn(move |conn| conn.load_contest_problem_models(&query.id))
+        .await?;
+    if models.is_empty() {
+        return Ok(Response::not_found());
+    }
+    let response = Response::new_cors().body_json(&estimate_difficulties(models))?;
+    Ok(response)
+}
+
+fn estimate_difficulties(models: Vec<ContestProblemModel>) -> Vec<ContestDifficulty> {
+    let known = models
+        .iter()
+        .filter_map(|model| Some((model.ac_user_count?, model.difficulty?)))
+        .collect::<Vec<_>>();
+    models
+        .into_iter()
+        .map(|model| match model.difficulty {
+            Some(difficulty) => ContestDifficulty {
+                problem_id: model.problem_id,
+                difficulty: Some(difficulty),
+                is_interpolated: false,
+            },
+            None => {
+                let difficulty = model
+                    .ac_user_count
+                    .and_then(|count| interpolate_difficulty(&known, count));
+                ContestDifficulty {
+                    problem_id: model.problem_id,
+                    difficulty,
+                    is_interpolated: difficulty.is_some(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn parse_jst_date(date: &str) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(date.and_hms(0, 0, 0).timestamp() - JST_OFFSET_SECOND)
+}
@@ -0,0 +1,51 @@
+use std::future::Future;
+use std::pin::Pin;
+use tide::{Middleware, Next, Request};
+
+const ALLOW_ORIGIN: &str = "access-control-allow-origin";
+
+/// Restricts the `access-control-allow-origin: *` set by `CommonResponse::new_cors` to the
+/// allowed origins. Any origin is allowed if `*` is in the origins.
+#[derive(Debug)]
+pub(crate) struct Cors {
+    origins: Vec<String>,
+}
+
+impl Cors {
+    pub(crate) fn new(origins: Vec<String>) -> Self {
+        Self { origins }
+    }
+
+    fn allows_any(&self) -> bool {
+        self.origins.iter().any(|origin| origin == "*")
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<State> for Cors {
+    fn handle<'a>(
+        &'a self,
+        request: Request<State>,
+        next: Next<'a, State>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if self.allows_any() {
+                return next.run(request).await;
+            }
+            let origin = request
+                .header("origin")
+                .map(|values| values.last().as_str().to_owned())
+                .filter(|origin| self.origins.contains(origin));
+            let mut response = next.run(request).await?;
+            if response.header(ALLOW_ORIGIN).is_none() {
+                return Ok(response);
+            }
+            response.remove_header(ALLOW_ORIGIN);
+            Ok(match origin {
+                Some(origin) => response
+                    .set_header(ALLOW_ORIGIN, origin)
+                    .set_header("vary", "origin"),
+                None => response,
+            })
+        })
+    }
+}
@@ -0,0 +1,26 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::DifficultyCountClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the numbers of the problems solved by the user in each difficulty bucket, so that the
+/// user pages do not need the problem models and all the submissions to draw the histogram.
+pub(crate) async fn get_difficulty_count<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let count = request
+        .state()
+        .with_conn(move |conn| conn.load_users_difficulty_count(&query.user))
+        .await?;
+    let response = Response::new_cors().body_json(&count)?;
+    Ok(response)
+}
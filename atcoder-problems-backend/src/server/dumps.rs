@@ -0,0 +1,37 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::DumpClient;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+use tide::{Request, Response};
+
+#[derive(Serialize)]
+struct DumpEntry {
+    name: String,
+    url: String,
+    size: i64,
+    sha256: String,
+    generated_at: String,
+}
+
+/// Lists the static dumps, so that mirrors can check the hashes of the downloaded files and skip
+/// the unchanged ones.
+pub(crate) async fn get_dumps<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let dumps = request
+        .state()
+        .with_conn(|conn| conn.load_dumps())
+        .await?
+        .into_iter()
+        .map(|dump| DumpEntry {
+            name: dump.name,
+            url: dump.url,
+            size: dump.size,
+            sha256: dump.sha256,
+            generated_at: Utc
+                .timestamp(dump.generated_epoch_second, 0)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        })
+        .collect::<Vec<_>>();
+    let response = Response::new_cors().body_json(&dumps)?;
+    Ok(response)
+}
@@ -0,0 +1,95 @@
+use crate::error::{classify, Error};
+use crate::server::validation::{BodyError, FieldError};
+
+use std::future::Future;
+use std::pin::Pin;
+use tide::http::StatusCode;
+use tide::{Middleware, Next, Request, Response};
+
+/// Builds the JSON body of an error, e.g.
+/// `{"kind": "validation", "error": "...", "fields": [{"field": "title", "message": "..."}]}`.
+pub(crate) fn json_error(
+    status: StatusCode,
+    kind: &str,
+    message: &str,
+    fields: &[FieldError],
+) -> Response {
+    let body = serde_json::json!({
+        "kind": kind,
+        "error": message,
+        "fields": fields,
+    });
+    Response::new(status)
+        .body_string(body.to_string())
+        .set_header("content-type", "application/json")
+}
+
+/// The kind of an error which is known only by its status code.
+fn status_kind(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BadRequest | StatusCode::UnprocessableEntity => "validation",
+        StatusCode::Unauthorized | StatusCode::Forbidden => "unauthorized",
+        StatusCode::NotFound => "not_found",
+        StatusCode::Conflict => "conflict",
+        StatusCode::PayloadTooLarge => "too_large",
+        StatusCode::TooManyRequests => "rate_limited",
+        StatusCode::BadGateway => "upstream_crawl",
+        StatusCode::ServiceUnavailable => "unavailable",
+        _ => "internal",
+    }
+}
+
+fn describe(error: &Error) -> Response {
+    json_error(error.status(), error.kind(), &error.message(), &[])
+}
+
+fn into_response(error: &http_types::Error) -> Response {
+    if let Some(body_error) = error.downcast_ref::<BodyError>() {
+        return json_error(
+            body_error.status(),
+            "validation",
+            &body_error.to_string(),
+            body_error.fields(),
+        );
+    }
+    if let Some(error) = error.downcast_ref::<Error>() {
+        return describe(error);
+    }
+    if let Some(error) = classify(error) {
+        return describe(&error);
+    }
+
+    let status = error.status();
+    let message = if status.is_server_error() {
+        status.canonical_reason().to_owned()
+    } else {
+        error.to_string()
+    };
+    json_error(status, status_kind(status), &message, &[])
+}
+
+/// Turns the errors of the handlers into the responses with the JSON bodies, so that the clients
+/// always get the kind and the description of the error.
+#[derive(Debug)]
+pub(crate) struct ErrorResponse;
+
+impl<State: Send + Sync + 'static> Middleware<State> for ErrorResponse {
+    fn handle<'a>(
+        &'a self,
+        request: Request<State>,
+        next: Next<'a, State>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            match next.run(request).await {
+                Ok(response) => Ok(response),
+                Err(error) => {
+                    let response = into_response(&error);
+                    if response.status().is_server_error() {
+                        log::error!("{:?}", error);
+                    }
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,168 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::Contest;
+use crate::sql::SimpleClient;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use std::collections::BTreeMap;
+use tide::{Request, Response};
+
+const FEED_URL: &str = "https://kenkoooo.com/atcoder/feed";
+const ATCODER_URL: &str = "https://atcoder.jp";
+const ENTRY_COUNT: usize = 50;
+
+struct Entry {
+    id: String,
+    title: String,
+    summary: String,
+    updated_epoch_second: i64,
+}
+
+pub(crate) async fn get_contests_feed<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let mut contests = request
+        .state()
+        .with_conn(|conn| conn.load_contests())
+        .await?;
+    contests.sort_by_key(|c| std::cmp::Reverse((c.start_epoch_second, c.id.clone())));
+    let entries = contests
+        .into_iter()
+        .take(ENTRY_COUNT)
+        .map(|contest| Entry {
+            id: format!("{}/contests/{}", ATCODER_URL, contest.id),
+            summary: format!(
+                "{} starts at {}",
+                contest.title,
+                to_rfc3339(contest.start_epoch_second)
+            ),
+            title: contest.title,
+            updated_epoch_second: contest.start_epoch_second,
+        })
+        .collect::<Vec<_>>();
+    let body = to_atom("contests", "AtCoder Problems: New Contests", &entries);
+    Ok(atom_response(body))
+}
+
+/// The problems are dated by the start of their contests, since the problems are published then.
+pub(crate) async fn get_problems_feed<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let (contests, problems) = request
+        .state()
+        .with_conn(|conn| Ok((conn.load_contests()?, conn.load_problems()?)))
+        .await?;
+    let contests = contests
+        .into_iter()
+        .map(|contest| (contest.id.clone(), contest))
+        .collect::<BTreeMap<_, Contest>>();
+    let mut problems = problems
+        .into_iter()
+        .filter_map(|problem| {
+            let contest = contests.get(&problem.contest_id)?;
+            Some((problem, contest))
+        })
+        .collect::<Vec<_>>();
+    problems.sort_by_key(|(problem, contest)| {
+        std::cmp::Reverse((contest.start_epoch_second, problem.id.clone()))
+    });
+    let entries = problems
+        .into_iter()
+        .take(ENTRY_COUNT)
+        .map(|(problem, contest)| Entry {
+            id: format!(
+                "{}/contests/{}/tasks/{}",
+                ATCODER_URL, problem.contest_id, problem.id
+            ),
+            title: problem.title,
+            summary: contest.title.clone(),
+            updated_epoch_second: contest.start_epoch_second,
+        })
+        .collect::<Vec<_>>();
+    let body = to_atom("problems", "AtCoder Problems: New Problems", &entries);
+    Ok(atom_response(body))
+}
+
+fn atom_response(body: String) -> Response {
+    Response::new_cors()
+        .body_string(body)
+        .set_header("content-type", "application/atom+xml; charset=utf-8")
+}
+
+fn to_atom(name: &str, title: &str, entries: &[Entry]) -> String {
+    let feed_url = format!("{}/{}.atom", FEED_URL, name);
+    let updated = entries
+        .iter()
+        .map(|entry| entry.updated_epoch_second)
+        .max()
+        .unwrap_or(0);
+
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.push('\n');
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    xml.push('\n');
+    xml.push_str(&format!("  <id>{}</id>\n", escape(&feed_url)));
+    xml.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    xml.push_str(&format!(
+        "  <link rel=\"self\" href=\"{}\"/>\n",
+        escape(&feed_url)
+    ));
+    xml.push_str(&format!("  <updated>{}</updated>\n", to_rfc3339(updated)));
+    xml.push_str("  <author><name>AtCoder Problems</name></author>\n");
+    for entry in entries.iter() {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <id>{}</id>\n", escape(&entry.id)));
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&entry.title)));
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", escape(&entry.id)));
+        xml.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            to_rfc3339(entry.updated_epoch_second)
+        ));
+        xml.push_str(&format!(
+            "    <summary>{}</summary>\n",
+            escape(&entry.summary)
+        ));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn to_rfc3339(epoch_second: i64) -> String {
+    Utc.timestamp(epoch_second, 0)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"A & B <"C">"#), "A &amp; B &lt;&quot;C&quot;&gt;");
+    }
+
+    #[test]
+    fn test_to_atom() {
+        let entries = [Entry {
+            id: "https://atcoder.jp/contests/abc100".to_owned(),
+            title: "AtCoder Beginner Contest 100".to_owned(),
+            summary: "summary".to_owned(),
+            updated_epoch_second: 1529154000,
+        }];
+        let xml = to_atom("contests", "title", &entries);
+        assert!(xml.contains("<id>https://kenkoooo.com/atcoder/feed/contests.atom</id>"));
+        assert!(xml.contains("<updated>2018-06-16T13:00:00Z</updated>"));
+        assert_eq!(xml.matches("<entry>").count(), 1);
+    }
+}
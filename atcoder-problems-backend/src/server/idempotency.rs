@@ -0,0 +1,150 @@
+use crate::error::Error;
+use crate::server::utils::{authenticate, AuthenticatedUser};
+use crate::server::validation::read_capped_body;
+use crate::server::{AppData, Authentication};
+use crate::sql::internal::idempotency_key_manager::{IdempotencyKeyManager, IdempotencyState};
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+use tide::http::{Method, StatusCode};
+use tide::{Middleware, Next, Request, Response};
+
+const IDEMPOTENCY_KEY: &str = "idempotency-key";
+const IDEMPOTENCY_REPLAYED: &str = "idempotency-replayed";
+const MAX_KEY_LENGTH: usize = 255;
+
+fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    for part in [method.as_bytes(), path.as_bytes(), body].iter() {
+        hasher.update(part);
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Makes the writes with an `Idempotency-Key` header safe to retry. The successful response of the
+/// first request is stored, and the retries with the same key and the same body get it back
+/// without running the handler again. A key reused with another request is rejected.
+#[derive(Debug)]
+pub(crate) struct Idempotency;
+
+impl<A> Middleware<AppData<A>> for Idempotency
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        mut request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            if request.method() == Method::Get {
+                return next.run(request).await;
+            }
+            let key = match request.header(IDEMPOTENCY_KEY) {
+                Some(values) => values.last().as_str().to_owned(),
+                None => return next.run(request).await,
+            };
+            if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+                return Err(Error::Validation(format!(
+                    "The idempotency key must have 1 to {} characters.",
+                    MAX_KEY_LENGTH
+                ))
+                .into_http_error());
+            }
+            let authenticated = request
+                .ext::<AuthenticatedUser>()
+                .map(|AuthenticatedUser(internal_user_id)| internal_user_id.clone());
+            let (conn, internal_user_id) = match authenticated {
+                Some(internal_user_id) => (request.state().pool.get()?, internal_user_id),
+                None => {
+                    let token = match request.cookie("token") {
+                        Some(token) => token,
+                        None => return next.run(request).await,
+                    };
+                    let client = request.state().authentication.clone();
+                    let conn = request.state().pool.get()?;
+                    authenticate(&client, conn, token.value()).await?
+                }
+            };
+
+            let body = read_capped_body(&mut request).await?;
+            let method = request.method().to_string();
+            let hash = request_hash(&method, request.uri().path(), &body);
+            request.as_mut().set_body(body);
+            let state =
+                conn.claim_idempotency_key(&internal_user_id, &key, &hash, Utc::now().timestamp())?;
+            // The handler takes another connection from the pool.
+            drop(conn);
+            match state {
+                IdempotencyState::New => {}
+                IdempotencyState::Completed { status, body } => {
+                    let status = StatusCode::try_from(status)?;
+                    let response = Response::new(status)
+                        .body_string(body)
+                        .set_header("content-type", "application/json")
+                        .set_header(IDEMPOTENCY_REPLAYED, "true");
+                    return Ok(response);
+                }
+                IdempotencyState::InFlight => {
+                    return Err(http_types::Error::from_str(
+                        StatusCode::Conflict,
+                        "The request with the idempotency key is in progress.",
+                    ));
+                }
+                IdempotencyState::Mismatched => {
+                    return Err(http_types::Error::from_str(
+                        StatusCode::UnprocessableEntity,
+                        "The idempotency key has been used with another request.",
+                    ));
+                }
+            }
+
+            // Only the successful responses are stored, so that the failed requests can be retried.
+            let pool = request.state().pool.clone();
+            let mut response = match next.run(request).await {
+                Ok(response) if response.status().is_success() => response,
+                result => {
+                    pool.get()?
+                        .release_idempotency_key(&internal_user_id, &key)?;
+                    return result;
+                }
+            };
+            let body = response.as_mut().take_body().into_string().await?;
+            response.as_mut().set_body(body.clone());
+            pool.get()?.complete_idempotency_key(
+                &internal_user_id,
+                &key,
+                response.status() as u16,
+                &body,
+            )?;
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_hash() {
+        let hash = request_hash("POST", "/internal-api/list/create", br#"{"list_name":"a"}"#);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(
+            hash,
+            request_hash("POST", "/internal-api/list/create", br#"{"list_name":"a"}"#)
+        );
+        assert_ne!(
+            hash,
+            request_hash("POST", "/internal-api/list/create", br#"{"list_name":"b"}"#)
+        );
+        assert_ne!(
+            hash,
+            request_hash("POST", "/internal-api/list/update", br#"{"list_name":"a"}"#)
+        );
+    }
+}
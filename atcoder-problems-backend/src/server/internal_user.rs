@@ -1,9 +1,13 @@
 use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
 use crate::server::{AppData, Authentication, CommonResponse};
 
 use crate::sql::internal::user_manager::UserManager;
+use crate::sql::internal::user_verification_manager::UserVerificationManager;
+use chrono::Utc;
 use serde::Deserialize;
 use tide::{Request, Response};
+use uuid::Uuid;
 
 pub(crate) async fn update<A: Authentication + Clone + Send + Sync + 'static>(
     request: Request<AppData<A>>,
@@ -12,6 +16,11 @@ pub(crate) async fn update<A: Authentication + Clone + Send + Sync + 'static>(
     struct Q {
         atcoder_user_id: String,
     }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("atcoder_user_id", &self.atcoder_user_id);
+        }
+    }
     let (body, conn, user_id) = request.post_unpack::<Q>().await?;
     conn.update_internal_user_info(&user_id, &body.atcoder_user_id)?;
     Ok(Response::ok().body_json(&serde_json::json!({}))?)
@@ -24,3 +33,41 @@ pub(crate) async fn get<A: Authentication + Clone + Send + Sync + 'static>(
     let info = conn.get_internal_user_info(&user_id)?;
     Ok(Response::ok().body_json(&info)?)
 }
+
+/// Starts a verification of the registered AtCoder user id. The user proves the ownership by
+/// putting the returned code in the affiliation field of the AtCoder profile.
+pub(crate) async fn start_verification<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let (conn, user_id) = request.get_unpack().await?;
+    let verification_code = format!("atcoder-problems-{}", Uuid::new_v4().to_simple());
+    let now = Utc::now().timestamp();
+    let atcoder_user_id = conn.start_verification(&user_id, &verification_code, now)?;
+    let body = serde_json::json!({
+        "atcoder_user_id": atcoder_user_id,
+        "verification_code": verification_code,
+    });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn get_verification<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let (conn, user_id) = request.get_unpack().await?;
+    let verified_atcoder_user_id = conn.get_verified_atcoder_user_id(&user_id)?;
+    let body = serde_json::json!({ "verified_atcoder_user_id": verified_atcoder_user_id });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn delete_account<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        transfer_contests: Option<bool>,
+    }
+    let query = request.query::<Query>()?;
+    let (conn, user_id) = request.get_unpack().await?;
+    conn.delete_user(&user_id, query.transfer_contests.unwrap_or(false))?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
@@ -0,0 +1,160 @@
+use crate::feature_flags::Feature;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::streak::get_max_streak;
+use crate::sql::{Judge, JudgeClient, SubmissionClient, SubmissionRequest};
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tide::{Request, Response};
+
+#[derive(Deserialize)]
+struct Query {
+    atcoder: Option<String>,
+    codeforces: Option<String>,
+    aoj: Option<String>,
+    yukicoder: Option<String>,
+    judge: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JudgeUserSummary {
+    judge: &'static str,
+    user_id: String,
+    accepted_count: usize,
+    max_streak: i64,
+}
+
+#[derive(Serialize)]
+struct UserSummary {
+    accepted_count: usize,
+    max_streak: i64,
+    judges: Vec<JudgeUserSummary>,
+}
+
+/// Returns the AC count and the longest streak of the users on each judge and the merged ones.
+/// `judge` restricts them to the single judge, e.g. `judge=atcoder` for the AtCoder-only ones.
+/// It is not found while `Feature::JudgeSummary` is disabled.
+pub(crate) async fn get_judge_summary<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let query = request.query::<Query>()?;
+    let judge_filter = match query.judge.as_deref() {
+        Some(judge) => match Judge::parse(judge) {
+            Some(judge) => Some(judge),
+            None => return Ok(Response::bad_request()),
+        },
+        None => None,
+    };
+    let accounts = vec![
+        (Judge::AtCoder, query.atcoder),
+        (Judge::Codeforces, query.codeforces),
+        (Judge::Aoj, query.aoj),
+        (Judge::Yukicoder, query.yukicoder),
+    ]
+    .into_iter()
+    .filter_map(|(judge, user_id)| match user_id {
+        Some(user_id) if judge_filter.map(|f| f == judge).unwrap_or(true) => Some((judge, user_id)),
+        _ => None,
+    })
+    .collect::<Vec<_>>();
+
+    let feature_flags = request.state().feature_flags.clone();
+    let accepted = request
+        .state()
+        .with_conn(move |conn| {
+            if !feature_flags.is_enabled(conn, Feature::JudgeSummary)? {
+                return Ok(None);
+            }
+            let mut accepted = vec![];
+            for (judge, user_id) in accounts {
+                let submissions = match judge {
+                    Judge::AtCoder => conn
+                        .get_submissions(SubmissionRequest::UsersAccepted {
+                            user_ids: &[&user_id],
+                        })?
+                        .into_iter()
+                        .map(|s| (s.problem_id, s.epoch_second))
+                        .collect::<Vec<_>>(),
+                    _ => conn
+                        .get_judge_accepted_submissions(judge, &user_id)?
+                        .into_iter()
+                        .map(|s| (s.problem_id, s.epoch_second))
+                        .collect::<Vec<_>>(),
+                };
+                accepted.push((judge, user_id, submissions));
+            }
+            Ok(Some(accepted))
+        })
+        .await?;
+    let accepted = match accepted {
+        Some(accepted) => accepted,
+        None => return Ok(Response::not_found()),
+    };
+
+    let mut judges = vec![];
+    let mut merged_first_ac = BTreeMap::new();
+    for (judge, user_id, accepted) in accepted {
+        let first_ac = get_first_ac(accepted);
+        judges.push(JudgeUserSummary {
+            judge: judge.as_str(),
+            user_id,
+            accepted_count: first_ac.len(),
+            max_streak: get_streak(first_ac.values()),
+        });
+        merged_first_ac.extend(
+            first_ac
+                .into_iter()
+                .map(|(problem_id, epoch_second)| ((judge, problem_id), epoch_second)),
+        );
+    }
+
+    let summary = UserSummary {
+        accepted_count: merged_first_ac.len(),
+        max_streak: get_streak(merged_first_ac.values()),
+        judges,
+    };
+    let response = Response::new_cors().body_json(&summary)?;
+    Ok(response)
+}
+
+fn get_first_ac(accepted: Vec<(String, i64)>) -> BTreeMap<String, i64> {
+    accepted
+        .into_iter()
+        .fold(BTreeMap::new(), |mut map, (problem_id, epoch_second)| {
+            let first = map.entry(problem_id).or_insert(epoch_second);
+            if *first > epoch_second {
+                *first = epoch_second;
+            }
+            map
+        })
+}
+
+fn get_streak<'a>(epoch_seconds: impl Iterator<Item = &'a i64>) -> i64 {
+    let dates = epoch_seconds
+        .map(|&epoch_second| Utc.timestamp(epoch_second, 0))
+        .collect::<Vec<_>>();
+    if dates.is_empty() {
+        0
+    } else {
+        get_max_streak(dates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_ac_and_streak() {
+        const DAY: i64 = 24 * 3600;
+        let first_ac = get_first_ac(vec![
+            ("a".to_owned(), 3 * DAY),
+            ("a".to_owned(), DAY),
+            ("b".to_owned(), 2 * DAY),
+            ("c".to_owned(), 5 * DAY),
+        ]);
+        assert_eq!(first_ac["a"], DAY);
+        assert_eq!(first_ac.len(), 3);
+        assert_eq!(get_streak(first_ac.values()), 2);
+        assert_eq!(get_streak([].iter()), 0);
+    }
+}
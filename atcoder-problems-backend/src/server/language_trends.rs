@@ -0,0 +1,24 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::LanguageTrendClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the monthly numbers of the AC submissions in each language, or in `language` only, so
+/// that the clients can chart the trends of the languages on AtCoder.
+pub(crate) async fn get_language_trends<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        language: Option<String>,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let trends = request
+        .state()
+        .with_conn(move |conn| conn.load_language_trends(query.language.as_deref()))
+        .await?;
+    let response = Response::new_cors().body_json(&trends)?;
+    Ok(response)
+}
@@ -0,0 +1,43 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::MarathonScoreClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the best scores of the user for the problems of the heuristic contests.
+pub(crate) async fn get_marathon_scores<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let scores = request
+        .state()
+        .with_conn(move |conn| conn.load_users_marathon_scores(&query.user))
+        .await?;
+    let response = Response::new_cors().body_json(&scores)?;
+    Ok(response)
+}
+
+/// Returns the users of the heuristic contest ranked by the sum of their best scores.
+pub(crate) async fn get_marathon_leaderboard<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        contest: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let leaderboard = request
+        .state()
+        .with_conn(move |conn| conn.load_marathon_leaderboard(&query.contest))
+        .await?;
+    let response = Response::new_cors().body_json(&leaderboard)?;
+    Ok(response)
+}
@@ -0,0 +1,169 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::notification_manager::{EventType, NotificationManager, WebhookFormat};
+
+use chrono::Utc;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// The length of `internal_webhooks.url`.
+const MAX_URL_LENGTH: usize = 2048;
+
+pub(crate) async fn register_webhook<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        url: String,
+        secret: String,
+        event_types: Vec<String>,
+        format: Option<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .length("url", &self.url, 1, MAX_URL_LENGTH)
+                .varchar("secret", &self.secret)
+                .varchars("event_types", &self.event_types)
+                .varchar("format", self.format.as_deref().unwrap_or(""));
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let event_types = match query
+        .event_types
+        .iter()
+        .map(|event_type| EventType::parse(event_type))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(event_types) => event_types,
+        None => return Ok(Response::bad_request()),
+    };
+    let format = match query.format.as_deref().map(WebhookFormat::parse) {
+        None => WebhookFormat::Json,
+        Some(Some(format)) => format,
+        Some(None) => return Ok(Response::bad_request()),
+    };
+    let now = Utc::now().timestamp();
+    let id = conn.register_webhook(
+        &internal_user_id,
+        &query.url,
+        &query.secret,
+        &event_types,
+        format,
+        now,
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({ "id": id }))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_webhooks<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let webhooks = conn.get_webhooks(&internal_user_id)?;
+    let response = Response::ok().body_json(&webhooks)?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_webhook<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("id", &self.id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_webhook(&internal_user_id, &query.id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+/// Registers the email address as a destination of the notifications, and sends the
+/// verification email to it.
+pub(crate) async fn register_email<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        address: String,
+        event_types: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("address", &self.address)
+                .varchars("event_types", &self.event_types);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let event_types = match query
+        .event_types
+        .iter()
+        .map(|event_type| EventType::parse(event_type))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(event_types) => event_types,
+        None => return Ok(Response::bad_request()),
+    };
+    let now = Utc::now().timestamp();
+    let (id, verification_token) =
+        conn.register_email(&internal_user_id, &query.address, &event_types, now)?;
+    let payload = serde_json::json!({
+        "event": EventType::EmailVerification.as_str(),
+        "token": verification_token,
+    });
+    conn.enqueue_deliveries(
+        &[(
+            id.clone(),
+            EventType::EmailVerification,
+            payload.to_string(),
+        )],
+        now,
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({ "id": id }))?;
+    Ok(response)
+}
+
+/// Verifies the email address by the token in the verification email. It does not require
+/// logging in, since it is opened from the email.
+pub(crate) async fn verify_email<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        token: String,
+    }
+    let query = match request.query::<Q>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let conn = request.state().pool.get()?;
+    conn.verify_email(&query.token)?;
+    let response = Response::ok().body_string("The email address has been verified.".to_owned());
+    Ok(response)
+}
+
+/// Stops the notifications to the email address by the token in the emails.
+pub(crate) async fn unsubscribe_email<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        token: String,
+    }
+    let query = match request.query::<Q>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let conn = request.state().pool.get()?;
+    conn.unsubscribe_email(&query.token)?;
+    let response =
+        Response::ok().body_string("The email address has been unsubscribed.".to_owned());
+    Ok(response)
+}
@@ -1,9 +1,15 @@
 use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
 use crate::server::{AppData, Authentication, CommonResponse};
 use crate::sql::internal::problem_list_manager::ProblemListManager;
 
-use serde::Deserialize;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use tide::{Request, Response};
+use uuid::Uuid;
+
+const MAX_LIST_NAME_LENGTH: usize = 255;
+const MAX_ITEM_MEMO_LENGTH: usize = 255;
 
 pub(crate) async fn get_own_lists<A: Authentication + Clone + Send + Sync + 'static>(
     request: Request<AppData<A>>,
@@ -31,6 +37,11 @@ pub(crate) async fn create_list<A: Authentication + Clone + Send + Sync + 'stati
     struct Query {
         list_name: String,
     }
+    impl Validate for Query {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("list_name", &self.list_name, 0, MAX_LIST_NAME_LENGTH);
+        }
+    }
     let (query, conn, internal_user_id) = request.post_unpack::<Query>().await?;
     let internal_list_id = conn.create_list(&internal_user_id, &query.list_name)?;
     let body = serde_json::json!({ "internal_list_id": internal_list_id });
@@ -46,8 +57,40 @@ where
     struct Q {
         internal_list_id: String,
     }
-    let (query, conn, _) = request.post_unpack::<Q>().await?;
-    conn.delete_list(&query.internal_list_id)?;
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_list_id", &self.internal_list_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_list(
+        &query.internal_list_id,
+        &internal_user_id,
+        Utc::now().timestamp(),
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn restore_list<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_list_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_list_id", &self.internal_list_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.restore_list(
+        &query.internal_list_id,
+        &internal_user_id,
+        Utc::now().timestamp(),
+    )?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
@@ -61,8 +104,13 @@ where
         internal_list_id: String,
         name: String,
     }
-    let (query, conn, _) = request.post_unpack::<Q>().await?;
-    conn.update_list(&query.internal_list_id, &query.name)?;
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("name", &self.name, 0, MAX_LIST_NAME_LENGTH);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.update_list(&query.internal_list_id, &internal_user_id, &query.name)?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
@@ -75,9 +123,21 @@ where
     struct Q {
         internal_list_id: String,
         problem_id: String,
+        memo: Option<String>,
     }
-    let (query, conn, _) = request.post_unpack::<Q>().await?;
-    conn.add_item(&query.internal_list_id, &query.problem_id)?;
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            let memo = self.memo.as_deref().unwrap_or("");
+            validator.length("memo", memo, 0, MAX_ITEM_MEMO_LENGTH);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.add_item(
+        &query.internal_list_id,
+        &internal_user_id,
+        &query.problem_id,
+        query.memo.as_deref(),
+    )?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
@@ -92,9 +152,19 @@ where
         problem_id: String,
         memo: String,
     }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("memo", &self.memo, 0, MAX_ITEM_MEMO_LENGTH);
+        }
+    }
 
-    let (query, conn, _) = request.post_unpack::<Q>().await?;
-    conn.update_item(&query.internal_list_id, &query.problem_id, &query.memo)?;
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.update_item(
+        &query.internal_list_id,
+        &internal_user_id,
+        &query.problem_id,
+        &query.memo,
+    )?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
@@ -108,8 +178,191 @@ where
         internal_list_id: String,
         problem_id: String,
     }
-    let (query, conn, _) = request.post_unpack::<Q>().await?;
-    conn.delete_item(&query.internal_list_id, &query.problem_id)?;
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("internal_list_id", &self.internal_list_id)
+                .varchar("problem_id", &self.problem_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_item(
+        &query.internal_list_id,
+        &internal_user_id,
+        &query.problem_id,
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn generate_share_token<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_list_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_list_id", &self.internal_list_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let share_token = match conn.get_share_token(&query.internal_list_id, &internal_user_id)? {
+        Some(share_token) => share_token,
+        None => {
+            let share_token = Uuid::new_v4().to_string();
+            conn.update_share_token(
+                &query.internal_list_id,
+                &internal_user_id,
+                Some(&share_token),
+            )?;
+            share_token
+        }
+    };
+    let body = serde_json::json!({ "share_token": share_token });
+    let response = Response::ok().body_json(&body)?;
+    Ok(response)
+}
+
+pub(crate) async fn revoke_share_token<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_list_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_list_id", &self.internal_list_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.update_share_token(&query.internal_list_id, &internal_user_id, None)?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
+
+pub(crate) async fn get_shared_list<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        user: Option<String>,
+    }
+    let share_token = request.param::<String>("share_token")?;
+    let query = request.query::<Query>()?;
+    let conn = request.state().pool.get()?;
+    let list = conn.get_shared_list(&share_token, query.user.as_deref())?;
+    let response = Response::ok().body_json(&list)?;
+    Ok(response)
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportedList {
+    internal_list_name: String,
+    items: Vec<ExportedItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportedItem {
+    problem_id: String,
+    #[serde(default)]
+    memo: String,
+}
+
+/// Exports a list as JSON (`format=json`, the default) or as problem ids separated by new lines
+/// (`format=text`).
+pub(crate) async fn export_list<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        format: Option<String>,
+    }
+    let list_id = request.param::<String>("list_id")?;
+    let query = request.query::<Query>()?;
+    let conn = request.state().pool.get()?;
+    let list = conn.get_single_list(&list_id)?;
+    match query.format.as_deref() {
+        None | Some("json") => {
+            let exported = ExportedList {
+                internal_list_name: list.internal_list_name,
+                items: list
+                    .items
+                    .into_iter()
+                    .map(|item| ExportedItem {
+                        problem_id: item.problem_id,
+                        memo: item.memo,
+                    })
+                    .collect(),
+            };
+            let response = Response::ok().body_json(&exported)?;
+            Ok(response)
+        }
+        Some("text") => {
+            let text = list
+                .items
+                .into_iter()
+                .map(|item| item.problem_id + "\n")
+                .collect::<String>();
+            Ok(Response::ok().body_string(text))
+        }
+        Some(_) => Ok(Response::bad_request()),
+    }
+}
+
+/// Creates a new list from the data exported by `export_list`.
+pub(crate) async fn import_list<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        format: String,
+        data: String,
+        list_name: Option<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("format", &self.format)
+                .varchar("list_name", self.list_name.as_deref().unwrap_or(""));
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let imported = match query.format.as_str() {
+        "json" => match serde_json::from_str::<ExportedList>(&query.data) {
+            Ok(imported) => imported,
+            Err(_) => return Ok(Response::bad_request()),
+        },
+        "text" => ExportedList {
+            internal_list_name: String::new(),
+            items: query
+                .data
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .map(|problem_id| ExportedItem {
+                    problem_id: problem_id.to_owned(),
+                    memo: String::new(),
+                })
+                .collect(),
+        },
+        _ => return Ok(Response::bad_request()),
+    };
+    let list_name = query.list_name.unwrap_or(imported.internal_list_name);
+    let items = imported
+        .items
+        .into_iter()
+        .map(|item| (item.problem_id, item.memo))
+        .collect::<Vec<_>>();
+    let internal_list_id = conn.import_list(&internal_user_id, &list_name, &items)?;
+    let body = serde_json::json!({ "internal_list_id": internal_list_id });
+    let response = Response::ok().body_json(&body)?;
+    Ok(response)
+}
@@ -0,0 +1,79 @@
+use crate::cache::{self, PROBLEM_MODELS_PREFIX};
+use crate::error::Result;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::ProblemModelClient;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tide::{Request, Response};
+
+/// The difficulties fitted with fewer users than this are not reliable.
+const MIN_CONFIDENT_SAMPLE_SIZE: i32 = 100;
+
+#[derive(Serialize, Deserialize)]
+struct ProblemModelResponse {
+    problem_id: String,
+    difficulty: Option<f64>,
+    is_experimental: bool,
+    sample_size: Option<i32>,
+    log_likelihood: Option<f64>,
+    variance: Option<f64>,
+    /// Whether the difficulty should be marked as experimental: the estimator says so, or the fit
+    /// has too few users or no diagnostics at all.
+    is_low_confidence: bool,
+}
+
+/// Returns the problem models with the diagnostics of their fits, so that the frontend can mark
+/// the difficulties of low confidence. They are cached until the jobs update the problem models.
+pub(crate) async fn get_problem_models<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let key = format!("{}all", PROBLEM_MODELS_PREFIX);
+    let cache = request.state().cache.as_ref();
+    let models = match cache::get_json::<Vec<ProblemModelResponse>>(cache, &key).await {
+        Some(models) => models,
+        None => {
+            let models = load_problem_models(&request).await?;
+            cache::set_json(cache, &key, &models, request.state().cache_ttl).await;
+            models
+        }
+    };
+    let response = Response::new_cors()
+        .set_header("Cache-Control", "max-age=300")
+        .body_json(&models)?;
+    Ok(response)
+}
+
+async fn load_problem_models<A>(
+    request: &Request<AppData<A>>,
+) -> Result<Vec<ProblemModelResponse>> {
+    let (models, diagnostics) = request
+        .state()
+        .with_conn(|conn| {
+            let models = conn.load_problem_models()?;
+            let diagnostics = conn.load_problem_model_diagnostics()?;
+            Ok((models, diagnostics))
+        })
+        .await?;
+    let mut diagnostics = diagnostics
+        .into_iter()
+        .map(|d| (d.problem_id.clone(), d))
+        .collect::<BTreeMap<_, _>>();
+    let mut models = models
+        .into_iter()
+        .map(|model| {
+            let diagnostics = diagnostics.remove(&model.problem_id);
+            let sample_size = diagnostics.as_ref().map(|d| d.sample_size);
+            ProblemModelResponse {
+                is_low_confidence: model.is_experimental
+                    || sample_size.map_or(true, |size| size < MIN_CONFIDENT_SAMPLE_SIZE),
+                problem_id: model.problem_id,
+                difficulty: model.difficulty,
+                is_experimental: model.is_experimental,
+                sample_size,
+                log_likelihood: diagnostics.as_ref().and_then(|d| d.log_likelihood),
+                variance: diagnostics.as_ref().and_then(|d| d.variance),
+            }
+        })
+        .collect::<Vec<_>>();
+    models.sort_by(|a, b| a.problem_id.cmp(&b.problem_id));
+    Ok(models)
+}
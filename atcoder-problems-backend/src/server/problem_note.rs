@@ -0,0 +1,99 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::problem_note_manager::ProblemNoteManager;
+
+use chrono::Utc;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+pub(crate) async fn get_notes<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let notes = conn.get_notes(&internal_user_id)?;
+    let response = Response::ok().body_json(&notes)?;
+    Ok(response)
+}
+
+pub(crate) async fn update_note<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        problem_id: String,
+        note: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("problem_id", &self.problem_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let now = Utc::now().timestamp();
+    conn.update_note(&internal_user_id, &query.problem_id, &query.note, now)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn add_tag<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        problem_id: String,
+        tag: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("problem_id", &self.problem_id)
+                .varchar("tag", &self.tag);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let now = Utc::now().timestamp();
+    conn.add_tag(&internal_user_id, &query.problem_id, &query.tag, now)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn remove_tag<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        problem_id: String,
+        tag: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("problem_id", &self.problem_id)
+                .varchar("tag", &self.tag);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.remove_tag(&internal_user_id, &query.problem_id, &query.tag)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn get_tagged_problems<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        tag: String,
+    }
+    let query = request.query::<Query>()?;
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let problems = conn.get_tagged_problems(&internal_user_id, &query.tag)?;
+    let response = Response::ok().body_json(&problems)?;
+    Ok(response)
+}
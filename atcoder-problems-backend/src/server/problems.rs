@@ -0,0 +1,197 @@
+use crate::cache::{self, DETAILED_PROBLEMS_PREFIX};
+use crate::server::versions::{entity_tag, is_not_modified};
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::{ContestCategory, DetailedProblem};
+use crate::sql::{
+    MergedProblemClient, ProblemDetailClient, ProblemSearchClient, ProblemSearchRequest,
+    SimilarProblemClient, SimpleClient, TableVersionClient,
+};
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tide::{Request, Response, StatusCode};
+
+/// The tables which the detailed problems are derived from, other than `problems` and
+/// `problem_models`. The changes of those two are served after the cache expires.
+const DETAILED_PROBLEMS_TABLES: [&str; 5] = ["first", "fastest", "shortest", "points", "solver"];
+
+/// Searches the problems by the words in the titles, the difficulty and whether the users have
+/// solved them, so that the clients do not filter all the problems by themselves.
+pub(crate) async fn search_problems<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        q: Option<String>,
+        difficulty_from: Option<f64>,
+        difficulty_to: Option<f64>,
+        solved_by: Option<String>,
+        not_solved_by: Option<String>,
+    }
+    let query = request.query::<Query>()?;
+    let problems = request
+        .state()
+        .with_conn(move |conn| {
+            conn.search_problems(&ProblemSearchRequest {
+                text: query.q.as_deref().unwrap_or("").trim(),
+                difficulty_from: query.difficulty_from,
+                difficulty_to: query.difficulty_to,
+                solved_by: non_empty(&query.solved_by),
+                not_solved_by: non_empty(&query.not_solved_by),
+            })
+        })
+        .await?;
+    let response = Response::new_cors().body_json(&problems)?;
+    Ok(response)
+}
+
+/// Lists all the problems merged with their points, difficulties, solver counts and
+/// first/fastest/shortest submissions, so that the clients do not join the dumps by themselves.
+pub(crate) async fn get_detailed_problems<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let versions = request
+        .state()
+        .with_conn(|conn| conn.load_table_versions())
+        .await?;
+    let tag = entity_tag(&versions, &DETAILED_PROBLEMS_TABLES);
+    if is_not_modified(&request, &tag) {
+        let response = Response::new_cors()
+            .set_status(StatusCode::NotModified)
+            .set_header("etag", tag);
+        return Ok(response);
+    }
+
+    let key = format!("{}{}", DETAILED_PROBLEMS_PREFIX, tag.trim_matches('"'));
+    let cache = request.state().cache.as_ref();
+    let problems = match cache::get_json::<Vec<DetailedProblem>>(cache, &key).await {
+        Some(problems) => problems,
+        None => {
+            let problems = request
+                .state()
+                .with_conn(|conn| conn.load_detailed_problems())
+                .await?;
+            cache::set_json(cache, &key, &problems, request.state().cache_ttl).await;
+            problems
+        }
+    };
+    let response = Response::new_cors()
+        .body_json(&problems)?
+        .set_header("etag", tag);
+    Ok(response)
+}
+
+/// Returns the details of a problem for the problem pages and the bots.
+pub(crate) async fn get_problem<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let problem = request
+        .state()
+        .with_conn(move |conn| conn.load_problem_detail(&query.id))
+        .await?;
+    match problem {
+        Some(problem) => Ok(Response::new_cors().body_json(&problem)?),
+        None => Ok(Response::not_found()),
+    }
+}
+
+/// Returns the changes of the shortest submission of a problem, for the record progressions.
+pub(crate) async fn get_shortest_history<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let history = request
+        .state()
+        .with_conn(move |conn| conn.load_shortest_history(&query.id))
+        .await?;
+    let response = Response::new_cors().body_json(&history)?;
+    Ok(response)
+}
+
+/// Returns the problems frequently solved by the same users around the same time as a problem,
+/// for the suggestions on the problem pages.
+pub(crate) async fn get_similar_problems<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let problems = request
+        .state()
+        .with_conn(move |conn| conn.load_similar_problems(&query.id))
+        .await?;
+    let response = Response::new_cors().body_json(&problems)?;
+    Ok(response)
+}
+
+/// Lists the problems which the user has not solved, ordered by difficulty, for the training
+/// tables and the recommendations. They can be filtered by the difficulty and the category of
+/// their contests.
+pub(crate) async fn get_unsolved_problems<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+        difficulty_from: Option<f64>,
+        difficulty_to: Option<f64>,
+        category: Option<ContestCategory>,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) if !query.user.is_empty() => query,
+        _ => return Ok(Response::bad_request()),
+    };
+    let problems = request
+        .state()
+        .with_conn(move |conn| {
+            let problems = conn.load_unsolved_problems(
+                &query.user,
+                query.difficulty_from,
+                query.difficulty_to,
+            )?;
+            let categories = match query.category {
+                Some(_) => conn
+                    .load_contests()?
+                    .into_iter()
+                    .map(|contest| {
+                        let category = contest.category();
+                        (contest.id, category)
+                    })
+                    .collect::<BTreeMap<_, _>>(),
+                None => BTreeMap::new(),
+            };
+            let problems = problems
+                .into_iter()
+                .filter(|problem| {
+                    query
+                        .category
+                        .into_iter()
+                        .all(|category| categories.get(&problem.contest_id) == Some(&category))
+                })
+                .collect::<Vec<_>>();
+            Ok(problems)
+        })
+        .await?;
+    let response = Response::new_cors().body_json(&problems)?;
+    Ok(response)
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|value| !value.is_empty())
+}
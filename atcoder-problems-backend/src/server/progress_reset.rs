@@ -1,4 +1,5 @@
 use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
 use crate::server::{AppData, Authentication, CommonResponse};
 use crate::sql::internal::progress_reset_manager::ProgressResetManager;
 use serde::Deserialize;
@@ -27,6 +28,11 @@ where
         problem_id: String,
         reset_epoch_second: i64,
     }
+    impl Validate for Query {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("problem_id", &self.problem_id);
+        }
+    }
     let (query, conn, internal_user_id) = request.post_unpack::<Query>().await?;
     conn.add_item(
         &internal_user_id,
@@ -46,6 +52,11 @@ where
     struct Query {
         problem_id: String,
     }
+    impl Validate for Query {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("problem_id", &self.problem_id);
+        }
+    }
     let (query, conn, internal_user_id) = request.post_unpack::<Query>().await?;
     conn.remove_item(&internal_user_id, &query.problem_id)?;
     Ok(Response::ok())
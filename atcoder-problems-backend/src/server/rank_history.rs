@@ -0,0 +1,24 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::RankHistoryClient;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the daily snapshots of the AC count rank and the rated point sum rank of the user,
+/// from the oldest one.
+pub(crate) async fn get_rank_history<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let history = request
+        .state()
+        .with_conn(move |conn| conn.load_rank_history(&query.user))
+        .await?;
+    let response = Response::new_cors().body_json(&history)?;
+    Ok(response)
+}
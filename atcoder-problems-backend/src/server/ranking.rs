@@ -0,0 +1,187 @@
+use crate::cache::{self, RANKING_PREFIX};
+use crate::error::Result;
+use crate::server::{AppData, CommonResponse};
+use crate::sql::{HolderKind, HolderRankingClient, RatedPointSumClient, ACTIVE_USER_PERIOD_SECOND};
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tide::{Request, Response};
+
+/// The maximum number of the users returned by a ranking request.
+const MAX_RANKING_RANGE: i64 = 1000;
+
+#[derive(Deserialize)]
+struct RankingRange {
+    from: i64,
+    to: i64,
+}
+
+impl RankingRange {
+    fn is_valid(&self) -> bool {
+        0 <= self.from && self.from <= self.to && self.to - self.from <= MAX_RANKING_RANGE
+    }
+}
+
+/// Limits a ranking to the active users with `active_only=true`, since the all-time rankings are
+/// dominated by the retired accounts.
+#[derive(Deserialize)]
+struct RankingFilter {
+    #[serde(default)]
+    active_only: bool,
+}
+
+impl RankingFilter {
+    fn active_since(&self, now: i64) -> Option<i64> {
+        if self.active_only {
+            Some(now - ACTIVE_USER_PERIOD_SECOND)
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_ranking_filter<A>(request: &Request<AppData<A>>) -> Option<RankingFilter> {
+    request.query::<RankingFilter>().ok()
+}
+
+/// Returns the ranking page cached under `key`, or loads and caches it. The cached pages are
+/// invalidated by the updaters of the aggregate tables.
+async fn load_cached_ranking<A, T, F>(
+    request: &Request<AppData<A>>,
+    key: &str,
+    load: F,
+) -> Result<Vec<T>>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce(&PgConnection) -> Result<Vec<T>> + Send + 'static,
+{
+    let cache = request.state().cache.as_ref();
+    if let Some(ranking) = cache::get_json::<Vec<T>>(cache, key).await {
+        return Ok(ranking);
+    }
+    let ranking = request.state().with_conn(load).await?;
+    cache::set_json(cache, key, &ranking, request.state().cache_ttl).await;
+    Ok(ranking)
+}
+
+/// Returns the users from the `from`-th to the `to`-th (exclusive) in the ranking of the rated
+/// point sum. The ranks are computed in the database, so that the users of the same point sum have
+/// the same rank on any page.
+pub(crate) async fn get_rated_point_sum_ranking<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    let range = match request.query::<RankingRange>() {
+        Ok(range) if range.is_valid() => range,
+        _ => return Ok(Response::bad_request()),
+    };
+    let filter = match parse_ranking_filter(&request) {
+        Some(filter) => filter,
+        None => return Ok(Response::bad_request()),
+    };
+    let key = format!(
+        "{}rated_point_sum:{}:{}:{}",
+        RANKING_PREFIX, range.from, range.to, filter.active_only
+    );
+    let active_since = filter.active_since(Utc::now().timestamp());
+    let ranking = load_cached_ranking(&request, &key, move |conn| {
+        conn.load_rated_point_sum_in_range(range.from, range.to, active_since)
+    })
+    .await?;
+    let response = Response::new_cors().body_json(&ranking)?;
+    Ok(response)
+}
+
+fn parse_holder_kind<A>(request: &Request<AppData<A>>) -> Option<HolderKind> {
+    let kind = request.param::<String>("kind").ok()?;
+    HolderKind::from_name(&kind)
+}
+
+/// Returns the users from the `from`-th to the `to`-th (exclusive) in the ranking of the numbers
+/// of the problems of which they hold the `kind` submissions, one of `first`, `first_practice`,
+/// `fastest` and `shortest`.
+pub(crate) async fn get_holder_ranking<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let kind = match parse_holder_kind(&request) {
+        Some(kind) => kind,
+        None => return Ok(Response::bad_request()),
+    };
+    let range = match request.query::<RankingRange>() {
+        Ok(range) if range.is_valid() => range,
+        _ => return Ok(Response::bad_request()),
+    };
+    let filter = match parse_ranking_filter(&request) {
+        Some(filter) => filter,
+        None => return Ok(Response::bad_request()),
+    };
+    let key = format!(
+        "{}holder:{}:{}:{}:{}",
+        RANKING_PREFIX,
+        kind.table(),
+        range.from,
+        range.to,
+        filter.active_only
+    );
+    let active_since = filter.active_since(Utc::now().timestamp());
+    let ranking = load_cached_ranking(&request, &key, move |conn| {
+        conn.load_holder_ranking(kind, range.from, range.to, active_since)
+    })
+    .await?;
+    let response = Response::new_cors().body_json(&ranking)?;
+    Ok(response)
+}
+
+/// Returns the rank of `user` in the ranking of the `kind` submissions, so that the clients can
+/// show it without searching the whole ranking.
+pub(crate) async fn get_users_holder_rank<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let kind = match parse_holder_kind(&request) {
+        Some(kind) => kind,
+        None => return Ok(Response::bad_request()),
+    };
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let active_since = match parse_ranking_filter(&request) {
+        Some(filter) => filter.active_since(Utc::now().timestamp()),
+        None => return Ok(Response::bad_request()),
+    };
+    let rank = request
+        .state()
+        .with_conn(move |conn| conn.get_users_holder_rank(kind, &query.user, active_since))
+        .await?;
+    match rank {
+        Some(rank) => Ok(Response::new_cors().body_json(&rank)?),
+        None => Ok(Response::not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranking_range() {
+        assert!(RankingRange { from: 0, to: 0 }.is_valid());
+        assert!(RankingRange { from: 10, to: 1010 }.is_valid());
+        assert!(!RankingRange { from: 10, to: 1011 }.is_valid());
+        assert!(!RankingRange { from: 10, to: 9 }.is_valid());
+        assert!(!RankingRange { from: -1, to: 9 }.is_valid());
+    }
+
+    #[test]
+    fn test_ranking_filter() {
+        let now = ACTIVE_USER_PERIOD_SECOND + 100;
+        assert_eq!(RankingFilter { active_only: false }.active_since(now), None);
+        assert_eq!(
+            RankingFilter { active_only: true }.active_since(now),
+            Some(100)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::error::Result;
+use crate::server::{AppData, CommonResponse, Pool};
+use crate::sql::models::RequestCount;
+use crate::sql::RequestCountClient;
+
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tide::{Middleware, Next, Request, Response};
+
+const CLIENT_HEADER: &str = "x-client";
+const UNKNOWN_CLIENT: &str = "unknown";
+const MAX_CLIENT_LENGTH: usize = 64;
+const MAX_ROUTE_LENGTH: usize = 255;
+const HOUR_SECOND: i64 = 3600;
+const DEFAULT_REQUEST_COUNT_HOURS: i64 = 24;
+
+/// The counts of the requests of each hour, route and client, which are not stored yet.
+type Counts = BTreeMap<(i64, String, String), i64>;
+
+/// Counts the requests of each route from each client app, which is named by the `X-Client`
+/// header, so that the maintainers can see which tools drive the load before breaking the API.
+///
+/// The counts are kept in memory and stored hourly: the first request of an hour stores the
+/// counts of the previous hours. The rest is stored when the server stops.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestAnalytics {
+    counts: Arc<Mutex<Counts>>,
+}
+
+impl RequestAnalytics {
+    /// Counts a request, and returns the counts of the hours before it, which are finished.
+    fn count(&self, now: i64, route: String, client: String) -> Vec<RequestCount> {
+        let hour_epoch_second = now - now.rem_euclid(HOUR_SECOND);
+        let mut counts = self.counts.lock().unwrap();
+        *counts
+            .entry((hour_epoch_second, route, client))
+            .or_insert(0) += 1;
+        let current = counts.split_off(&(hour_epoch_second, String::new(), String::new()));
+        into_request_counts(std::mem::replace(&mut *counts, current))
+    }
+
+    /// Stores all the counts in memory.
+    pub(crate) fn flush(&self, pool: &Pool) -> Result<()> {
+        let counts = std::mem::take(&mut *self.counts.lock().unwrap());
+        store(pool, &into_request_counts(counts))
+    }
+}
+
+fn store(pool: &Pool, counts: &[RequestCount]) -> Result<()> {
+    if !counts.is_empty() {
+        pool.get()?.add_request_counts(counts)?;
+    }
+    Ok(())
+}
+
+fn into_request_counts(counts: Counts) -> Vec<RequestCount> {
+    counts
+        .into_iter()
+        .map(
+            |((hour_epoch_second, route, client), request_count)| RequestCount {
+                hour_epoch_second,
+                route,
+                client,
+                request_count,
+            },
+        )
+        .collect()
+}
+
+/// The path with the identifiers replaced with `:id`, so that e.g. each contest does not make a
+/// route of its own. A segment is an identifier if it has a digit, except the versions like `v3`.
+pub(crate) fn normalize_route(path: &str) -> String {
+    let route = path
+        .split('/')
+        .map(|segment| {
+            let is_version = segment.len() > 1
+                && segment.starts_with('v')
+                && segment[1..].bytes().all(|c| c.is_ascii_digit());
+            if !is_version && segment.bytes().any(|c| c.is_ascii_digit()) {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    route.chars().take(MAX_ROUTE_LENGTH).collect()
+}
+
+/// The client app named by the `X-Client` header, e.g. `my-extension/1.2`.
+fn client_name(header: Option<&str>) -> String {
+    let client = header
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .filter(|c| c.is_ascii_graphic() || *c == ' ')
+        .take(MAX_CLIENT_LENGTH)
+        .collect::<String>();
+    if client.is_empty() {
+        UNKNOWN_CLIENT.to_owned()
+    } else {
+        client
+    }
+}
+
+impl<A> Middleware<AppData<A>> for RequestAnalytics
+where
+    A: Send + Sync + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        request: Request<AppData<A>>,
+        next: Next<'a, AppData<A>>,
+    ) -> Pin<Box<dyn Future<Output = tide::Result> + Send + 'a>> {
+        Box::pin(async move {
+            let route = normalize_route(request.uri().path());
+            let client = client_name(
+                request
+                    .header(CLIENT_HEADER)
+                    .map(|values| values.last().as_str()),
+            );
+            let finished = self.count(Utc::now().timestamp(), route, client);
+            // The analytics must not fail the requests.
+            if let Err(e) = store(&request.state().pool, &finished) {
+                log::error!("Failed to store the request counts: {:?}", e);
+            }
+            next.run(request).await
+        })
+    }
+}
+
+/// Returns the request counts of each hour, route and client app of the last `hours` hours.
+pub(crate) async fn get_request_counts<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        hours: Option<i64>,
+    }
+    let query = request.query::<Query>()?;
+    let hours = query.hours.unwrap_or(DEFAULT_REQUEST_COUNT_HOURS).max(1);
+    let now = Utc::now().timestamp();
+    let from_epoch_second = now - now.rem_euclid(HOUR_SECOND) - (hours - 1) * HOUR_SECOND;
+    let conn = request.state().pool.get()?;
+    let counts = conn.load_request_counts(from_epoch_second)?;
+    let response = Response::ok().body_json(&counts)?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_route() {
+        assert_eq!(
+            normalize_route("/atcoder-api/v3/user/ac_rank"),
+            "/atcoder-api/v3/user/ac_rank"
+        );
+        assert_eq!(
+            normalize_route("/internal-api/contest/get/4c5a3e2b-0d1f"),
+            "/internal-api/contest/get/:id"
+        );
+        assert_eq!(
+            normalize_route("/internal-api/contest/results/abc151"),
+            "/internal-api/contest/results/:id"
+        );
+    }
+
+    #[test]
+    fn test_client_name() {
+        assert_eq!(client_name(None), "unknown");
+        assert_eq!(client_name(Some("  ")), "unknown");
+        assert_eq!(client_name(Some(" my-tool/1.0 ")), "my-tool/1.0");
+        assert_eq!(client_name(Some(&"a".repeat(100))).len(), MAX_CLIENT_LENGTH);
+    }
+
+    #[test]
+    fn test_count() {
+        let analytics = RequestAnalytics::default();
+        let count = |now, route: &str| analytics.count(now, route.to_owned(), "tool".to_owned());
+        assert!(count(3600, "/a").is_empty());
+        assert!(count(7199, "/a").is_empty());
+        assert!(count(7199, "/b").is_empty());
+        assert_eq!(
+            count(7200, "/a"),
+            vec![
+                RequestCount {
+                    hour_epoch_second: 3600,
+                    route: "/a".to_owned(),
+                    client: "tool".to_owned(),
+                    request_count: 2,
+                },
+                RequestCount {
+                    hour_epoch_second: 3600,
+                    route: "/b".to_owned(),
+                    client: "tool".to_owned(),
+                    request_count: 1,
+                },
+            ]
+        );
+        assert!(count(7300, "/a").is_empty());
+        assert_eq!(analytics.counts.lock().unwrap().len(), 1);
+    }
+}
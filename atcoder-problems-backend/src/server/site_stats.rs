@@ -0,0 +1,37 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::SiteStats;
+use crate::sql::SiteStatsClient;
+
+use chrono::Utc;
+use serde::Serialize;
+use tide::{Request, Response};
+
+#[derive(Serialize)]
+struct SiteStatsResponse {
+    #[serde(flatten)]
+    stats: SiteStats,
+    /// The seconds since the latest crawled submission, which grows while the crawlers stop.
+    crawl_lag_second: Option<i64>,
+}
+
+/// Returns the statistics of the whole site for the status page.
+pub(crate) async fn get_site_stats<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let stats = request
+        .state()
+        .with_conn(|conn| conn.load_site_stats())
+        .await?;
+    match stats {
+        Some(stats) => {
+            let now = Utc::now().timestamp();
+            let crawl_lag_second = stats
+                .latest_submission_epoch_second
+                .map(|latest| (now - latest).max(0));
+            let response = Response::new_cors().body_json(&SiteStatsResponse {
+                stats,
+                crawl_lag_second,
+            })?;
+            Ok(response)
+        }
+        None => Ok(Response::not_found()),
+    }
+}
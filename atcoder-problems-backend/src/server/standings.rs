@@ -0,0 +1,323 @@
+use crate::error::Error::Conflict;
+use crate::error::Result;
+use crate::sql::internal::virtual_contest_manager::VirtualContestItem;
+use crate::sql::models::{Point, Submission, Verdict};
+
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+const ATCODER_PENALTY_SECOND: i64 = 300;
+const ICPC_PENALTY_SECOND: i64 = 1200;
+
+pub(crate) struct StandingsEntry {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) members: Vec<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct ProblemResult {
+    pub(crate) accepted: bool,
+    penalties: usize,
+    point: Point,
+    elapsed_second: Option<i64>,
+    solver: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct StandingsRow {
+    pub(crate) id: String,
+    name: String,
+    members: Vec<String>,
+    pub(crate) score: f64,
+    pub(crate) penalty_second: i64,
+    pub(crate) problems: BTreeMap<String, ProblemResult>,
+}
+
+/// Computes the score and the penalty of an entry from its results.
+/// Entries are ranked by the score descending, and then by the penalty ascending.
+pub(crate) trait ScoringStrategy {
+    fn score(&self, problems: &BTreeMap<String, ProblemResult>) -> (f64, i64);
+}
+
+/// The sum of points, with the time of the last AC plus 5 minutes for each wrong submission.
+pub(crate) struct AtCoderScoring;
+
+impl ScoringStrategy for AtCoderScoring {
+    fn score(&self, problems: &BTreeMap<String, ProblemResult>) -> (f64, i64) {
+        let accepted = problems.values().filter(|r| r.accepted).collect::<Vec<_>>();
+        let score = accepted.iter().map(|r| r.point).sum::<Point>().as_f64();
+        let last_accepted = accepted
+            .iter()
+            .filter_map(|r| r.elapsed_second)
+            .max()
+            .unwrap_or(0);
+        let penalties = accepted.iter().map(|r| r.penalties as i64).sum::<i64>();
+        (score, last_accepted + penalties * ATCODER_PENALTY_SECOND)
+    }
+}
+
+/// The number of solved problems, with the sum of the solving times plus 20 minutes for each
+/// wrong submission.
+pub(crate) struct IcpcScoring;
+
+impl ScoringStrategy for IcpcScoring {
+    fn score(&self, problems: &BTreeMap<String, ProblemResult>) -> (f64, i64) {
+        let accepted = problems.values().filter(|r| r.accepted).collect::<Vec<_>>();
+        let penalty = accepted
+            .iter()
+            .map(|r| r.elapsed_second.unwrap_or(0) + r.penalties as i64 * ICPC_PENALTY_SECOND)
+            .sum::<i64>();
+        (accepted.len() as f64, penalty)
+    }
+}
+
+/// The number of solved problems, with the time of the last AC.
+pub(crate) struct SolveCountScoring;
+
+impl ScoringStrategy for SolveCountScoring {
+    fn score(&self, problems: &BTreeMap<String, ProblemResult>) -> (f64, i64) {
+        let accepted = problems.values().filter(|r| r.accepted).collect::<Vec<_>>();
+        let last_accepted = accepted
+            .iter()
+            .filter_map(|r| r.elapsed_second)
+            .max()
+            .unwrap_or(0);
+        (accepted.len() as f64, last_accepted)
+    }
+}
+
+/// Returns the scoring strategy of the given name, or `None` if the name is unknown.
+/// AtCoder-style scoring is used when no name is specified.
+pub(crate) fn scoring_strategy(name: Option<&str>) -> Option<Box<dyn ScoringStrategy>> {
+    match name {
+        None | Some("atcoder") => Some(Box::new(AtCoderScoring)),
+        Some("icpc") => Some(Box::new(IcpcScoring)),
+        Some("solve_count") => Some(Box::new(SolveCountScoring)),
+        Some(_) => None,
+    }
+}
+
+/// Computes standings, where each entry may consist of several members and a problem is solved by
+/// an entry when any of its members gets AC. A user can not be a member of several entries, since
+/// the submissions of the user would be counted for all of them.
+pub(crate) fn compute_standings(
+    scoring: &dyn ScoringStrategy,
+    entries: Vec<StandingsEntry>,
+    problems: &[VirtualContestItem],
+    start_epoch_second: i64,
+    end_epoch_second: i64,
+    submissions: &[Submission],
+) -> Result<Vec<StandingsRow>> {
+    let mut member_to_entry = BTreeMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        for member in entry.members.iter() {
+            if member_to_entry.insert(member.as_str(), i).is_some() {
+                return Err(
+                    Conflict(format!("{} is a member of more than one team.", member))
+                        .into_http_error(),
+                );
+            }
+        }
+    }
+    let problem_points = problems
+        .iter()
+        .map(|p| (p.id.as_str(), p.point))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut submissions = submissions
+        .iter()
+        .filter(|s| s.epoch_second >= start_epoch_second && s.epoch_second <= end_epoch_second)
+        .filter(|s| problem_points.contains_key(s.problem_id.as_str()))
+        .collect::<Vec<_>>();
+    submissions.sort_by_key(|s| (s.epoch_second, s.id));
+
+    let mut results = (0..entries.len())
+        .map(|_| BTreeMap::new())
+        .collect::<Vec<_>>();
+    for submission in submissions.into_iter() {
+        let entry = match member_to_entry.get(submission.user_id.as_str()) {
+            Some(&entry) => entry,
+            None => continue,
+        };
+        let result = results[entry]
+            .entry(submission.problem_id.clone())
+            .or_insert(ProblemResult {
+                accepted: false,
+                penalties: 0,
+                point: Point::default(),
+                elapsed_second: None,
+                solver: None,
+            });
+        if result.accepted {
+            continue;
+        }
+        let verdict = submission.verdict();
+        if verdict.is_accepted() {
+            let user_defined_point = problem_points
+                .get(submission.problem_id.as_str())
+                .cloned()
+                .flatten();
+            result.accepted = true;
+            result.point = user_defined_point
+                .map(|point| Point::from(point as f64))
+                .unwrap_or(submission.point);
+            result.elapsed_second = Some(submission.epoch_second - start_epoch_second);
+            result.solver = Some(submission.user_id.clone());
+        } else if verdict != Verdict::CompileError {
+            result.penalties += 1;
+        }
+    }
+
+    let mut rows = entries
+        .into_iter()
+        .zip(results)
+        .map(|(entry, problems)| {
+            let (score, penalty_second) = scoring.score(&problems);
+            StandingsRow {
+                id: entry.id,
+                name: entry.name,
+                members: entry.members,
+                score,
+                penalty_second,
+                problems,
+            }
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then(a.penalty_second.cmp(&b.penalty_second))
+    });
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(
+        id: i64,
+        epoch_second: i64,
+        user_id: &str,
+        problem_id: &str,
+        result: &str,
+    ) -> Submission {
+        Submission {
+            id,
+            epoch_second,
+            user_id: user_id.to_owned(),
+            problem_id: problem_id.to_owned(),
+            result: result.to_owned(),
+            point: Point::from(100.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_team_standings() {
+        let entries = vec![
+            StandingsEntry {
+                id: "team_1".to_owned(),
+                name: "Team 1".to_owned(),
+                members: vec!["user_a".to_owned(), "user_b".to_owned()],
+            },
+            StandingsEntry {
+                id: "team_2".to_owned(),
+                name: "Team 2".to_owned(),
+                members: vec!["user_c".to_owned()],
+            },
+        ];
+        let problems = vec![
+            VirtualContestItem {
+                id: "problem_1".to_owned(),
+                point: None,
+                order: None,
+            },
+            VirtualContestItem {
+                id: "problem_2".to_owned(),
+                point: Some(500),
+                order: None,
+            },
+        ];
+        let submissions = vec![
+            submission(1, 110, "user_a", "problem_1", "WA"),
+            submission(2, 120, "user_b", "problem_1", "AC"),
+            submission(3, 130, "user_a", "problem_1", "AC"),
+            submission(4, 140, "user_c", "problem_1", "CE"),
+            submission(5, 150, "user_c", "problem_1", "AC"),
+            submission(6, 160, "user_c", "problem_2", "AC"),
+            submission(7, 170, "user_a", "problem_3", "AC"),
+            submission(8, 500, "user_a", "problem_2", "AC"),
+            submission(9, 180, "user_x", "problem_2", "AC"),
+        ];
+
+        let rows =
+            compute_standings(&AtCoderScoring, entries, &problems, 100, 200, &submissions).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(rows[0].id, "team_2");
+        assert_eq!(rows[0].score, 600.0);
+        assert_eq!(rows[0].penalty_second, 60);
+        assert_eq!(rows[0].problems["problem_1"].penalties, 0);
+
+        assert_eq!(rows[1].id, "team_1");
+        assert_eq!(rows[1].score, 100.0);
+        assert_eq!(rows[1].penalty_second, 20 + ATCODER_PENALTY_SECOND);
+        assert_eq!(
+            rows[1].problems["problem_1"],
+            ProblemResult {
+                accepted: true,
+                penalties: 1,
+                point: Point::from(100.0),
+                elapsed_second: Some(20),
+                solver: Some("user_b".to_owned()),
+            }
+        );
+        assert!(!rows[1].problems.contains_key("problem_2"));
+    }
+
+    #[test]
+    fn test_compute_standings_with_duplicate_member() {
+        let entries = vec![
+            StandingsEntry {
+                id: "team_1".to_owned(),
+                name: "Team 1".to_owned(),
+                members: vec!["user_a".to_owned(), "user_b".to_owned()],
+            },
+            StandingsEntry {
+                id: "team_2".to_owned(),
+                name: "Team 2".to_owned(),
+                members: vec!["user_b".to_owned()],
+            },
+        ];
+        let error = compute_standings(&AtCoderScoring, entries, &[], 100, 200, &[]).unwrap_err();
+        assert_eq!(error.status(), http_types::StatusCode::Conflict);
+    }
+
+    #[test]
+    fn test_scoring_strategies() {
+        let result =
+            |accepted: bool, penalties: usize, point: f64, elapsed_second: i64| ProblemResult {
+                accepted,
+                penalties,
+                point: Point::from(point),
+                elapsed_second: if accepted { Some(elapsed_second) } else { None },
+                solver: None,
+            };
+        let mut problems = BTreeMap::new();
+        problems.insert("problem_1".to_owned(), result(true, 1, 100.0, 600));
+        problems.insert("problem_2".to_owned(), result(true, 0, 300.0, 1800));
+        problems.insert("problem_3".to_owned(), result(false, 3, 0.0, 0));
+
+        assert_eq!(AtCoderScoring.score(&problems), (400.0, 1800 + 300));
+        assert_eq!(IcpcScoring.score(&problems), (2.0, 600 + 1200 + 1800));
+        assert_eq!(SolveCountScoring.score(&problems), (2.0, 1800));
+
+        assert!(scoring_strategy(None).is_some());
+        assert!(scoring_strategy(Some("icpc")).is_some());
+        assert!(scoring_strategy(Some("unknown")).is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::config::SubmissionCodeConfig;
+use crate::error::Error::{self, NotFound};
+use crate::server::error_response::json_error;
+use crate::server::utils::RequestUnpack;
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::SubmissionCodeClient;
+
+use async_std::io::ReadExt;
+use chrono::Utc;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tide::http::StatusCode;
+use tide::{Request, Response};
+
+const RETRY_AFTER_SECOND: &str = "1";
+/// A byte of a code is escaped into at most 6 bytes in a page, e.g. `&quot;` for `"`.
+const MAX_ESCAPED_BYTES_PER_CODE_BYTE: usize = 6;
+/// The rest of a submission page, which is far shorter than this.
+const MAX_PAGE_OVERHEAD_BYTES: usize = 1024 * 1024;
+
+/// Spaces the fetches from AtCoder by `interval` among all the requests. A request waits for its
+/// turn for at most `max_wait`, so that a burst of the requests does not queue up for long.
+#[derive(Debug, Clone)]
+pub(crate) struct CodeFetcher {
+    atcoder_url: String,
+    max_code_bytes: usize,
+    interval: Duration,
+    max_wait: Duration,
+    timeout: Duration,
+    next_slot: Arc<Mutex<Instant>>,
+    code_regex: Regex,
+}
+
+impl CodeFetcher {
+    pub(crate) fn new(config: &SubmissionCodeConfig) -> Self {
+        Self {
+            atcoder_url: config.atcoder_url.trim_end_matches('/').to_owned(),
+            max_code_bytes: config.max_code_bytes,
+            interval: config.fetch_interval(),
+            max_wait: config.max_wait(),
+            timeout: config.fetch_timeout(),
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+            code_regex: submission_code_regex(),
+        }
+    }
+
+    /// Reserves the next turn to fetch, and returns how long to wait for it, or `None` if the
+    /// turn is too far.
+    fn reserve(&self, now: Instant) -> Option<Duration> {
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let slot = (*next_slot).max(now);
+        let wait = slot - now;
+        if wait > self.max_wait {
+            return None;
+        }
+        *next_slot = slot + self.interval;
+        Some(wait)
+    }
+
+    async fn fetch(&self, contest_id: &str, submission_id: i64) -> tide::Result<String> {
+        let url = format!(
+            "{}/contests/{}/submissions/{}",
+            self.atcoder_url, contest_id, submission_id
+        );
+        let max_page_bytes =
+            self.max_code_bytes * MAX_ESCAPED_BYTES_PER_CODE_BYTE + MAX_PAGE_OVERHEAD_BYTES;
+        let page = async_std::future::timeout(self.timeout, read_capped_page(url, max_page_bytes))
+            .await
+            .map_err(Error::upstream)??;
+        if page.len() > max_page_bytes {
+            return Err(http_types::Error::from_str(
+                StatusCode::PayloadTooLarge,
+                format!("The page is longer than {} bytes.", max_page_bytes),
+            ));
+        }
+        let html = String::from_utf8_lossy(&page);
+        let code = scrape_submission_code(&self.code_regex, &html)
+            .ok_or_else(|| Error::upstream("The submission code is not found in the page."))?;
+        if code.len() > self.max_code_bytes {
+            return Err(http_types::Error::from_str(
+                StatusCode::PayloadTooLarge,
+                format!("The code is longer than {} bytes.", self.max_code_bytes),
+            ));
+        }
+        Ok(code)
+    }
+}
+
+/// Reads at most `max_bytes + 1` bytes of the page, so that a huge page is not buffered. The page
+/// is too large if more than `max_bytes` bytes are returned.
+async fn read_capped_page(url: String, max_bytes: usize) -> tide::Result<Vec<u8>> {
+    let response = surf::get(url).await.map_err(Error::upstream)?;
+    let mut bytes = vec![];
+    response
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(Error::upstream)?;
+    Ok(bytes)
+}
+
+/// Matches the escaped code in a submission page.
+fn submission_code_regex() -> Regex {
+    Regex::new(r#"(?s)<pre id="submission-code"[^>]*>(.*?)</pre>"#).unwrap()
+}
+
+/// Extracts the code from a submission page with `re` of `submission_code_regex`.
+fn scrape_submission_code(re: &Regex, html: &str) -> Option<String> {
+    re.captures(html)
+        .map(|captures| unescape_html(&captures[1]))
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#039;", "'")
+        .replace("&amp;", "&")
+}
+
+#[derive(Serialize)]
+struct SubmissionCode {
+    submission_id: i64,
+    contest_id: String,
+    code: String,
+    fetched_epoch_second: i64,
+}
+
+/// Returns the source code of a public submission. The code is fetched from AtCoder at the first
+/// request, and the later requests are served from the database.
+pub(crate) async fn get_submission_code<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let submission_id = match request.param::<i64>("submission_id") {
+        Ok(submission_id) => submission_id,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let state = request.state().clone();
+    let (conn, _) = request.get_unpack().await?;
+    let now = Utc::now().timestamp();
+    let contest_id = conn
+        .load_public_submission_contest(submission_id, now)?
+        .ok_or_else(|| NotFound.into_http_error())?;
+    let (code, fetched_epoch_second) = match conn.load_submission_code(submission_id)? {
+        Some(code) => code,
+        None => {
+            drop(conn);
+            let wait = match state.code_fetcher.reserve(Instant::now()) {
+                Some(wait) => wait,
+                None => {
+                    let response = json_error(
+                        StatusCode::TooManyRequests,
+                        "rate_limited",
+                        "Too many codes are being fetched. Please retry later.",
+                        &[],
+                    )
+                    .set_header("retry-after", RETRY_AFTER_SECOND);
+                    return Ok(response);
+                }
+            };
+            async_std::task::sleep(wait).await;
+            let code = state.code_fetcher.fetch(&contest_id, submission_id).await?;
+            let saved = code.clone();
+            state
+                .with_conn(move |conn| conn.save_submission_code(submission_id, &saved, now))
+                .await?;
+            (code, now)
+        }
+    };
+    let response = Response::ok().body_json(&SubmissionCode {
+        submission_id,
+        contest_id,
+        code,
+        fetched_epoch_second,
+    })?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrape_submission_code() {
+        let html = r#"
+            <div class="div-btn-copy"></div>
+            <pre id="submission-code" class="prettyprint linenums" data-ace-mode="c_cpp">#include &lt;iostream&gt;
+int main() { std::cout &lt;&lt; &quot;&amp;&#39;&quot; &lt;&lt; std::endl; }
+</pre>"#;
+        let re = submission_code_regex();
+        assert_eq!(
+            scrape_submission_code(&re, html).unwrap(),
+            "#include <iostream>\nint main() { std::cout << \"&'\" << std::endl; }\n"
+        );
+        assert_eq!(unescape_html("&amp;lt;"), "&lt;");
+        assert_eq!(scrape_submission_code(&re, "<pre></pre>"), None);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let fetcher = CodeFetcher::new(&SubmissionCodeConfig {
+            fetch_interval_millis: 1_000,
+            max_wait_millis: 1_500,
+            ..SubmissionCodeConfig::default()
+        });
+        let now = Instant::now();
+        assert_eq!(fetcher.reserve(now), Some(Duration::from_secs(0)));
+        assert_eq!(fetcher.reserve(now), Some(Duration::from_secs(1)));
+        assert_eq!(fetcher.reserve(now), None, "The turn is 2 seconds later.");
+        assert_eq!(
+            fetcher.reserve(now + Duration::from_secs(3)),
+            Some(Duration::from_secs(0))
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::models::Submission;
+use crate::sql::SubmissionClient;
+
+use serde::{Deserialize, Serialize};
+use tide::{Request, Response};
+
+const DEFAULT_STREAM_BATCH_SIZE: i64 = 1000;
+const MAX_STREAM_BATCH_SIZE: i64 = 5000;
+
+#[derive(Serialize)]
+struct SubmissionBatch {
+    submissions: Vec<Submission>,
+    /// The cursor to resume from the next batch. It is the given one if the batch is empty.
+    cursor: String,
+}
+
+/// Returns a batch of the submissions in the order of their latest changes after `cursor`, or
+/// from the first change without it, so that the mirrors can replicate the whole submissions
+/// table by following the returned cursors. The submissions updated by the rejudges come again
+/// with their new results. Each batch has at most `count` submissions.
+pub(crate) async fn get_submission_stream<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        cursor: Option<String>,
+        count: Option<i64>,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let sequence = match query.cursor.as_deref() {
+        Some(cursor) => match decode_cursor(cursor) {
+            Some(sequence) => sequence,
+            None => return Ok(Response::bad_request()),
+        },
+        None => 0,
+    };
+    let count = query.count.unwrap_or(DEFAULT_STREAM_BATCH_SIZE);
+    if count <= 0 || count > MAX_STREAM_BATCH_SIZE {
+        return Ok(Response::bad_request());
+    }
+
+    let state = request.state();
+    let changes = state
+        .with_conn_timeout(state.submissions_query_timeout, move |conn| {
+            conn.load_submission_changes(sequence, count)
+        })
+        .await?;
+    let cursor = match changes.last() {
+        Some(&(_, last)) => encode_cursor(last),
+        None => encode_cursor(sequence),
+    };
+    let submissions = changes
+        .into_iter()
+        .map(|(submission, _)| submission)
+        .collect::<Vec<_>>();
+    let response = Response::new_cors().body_json(&SubmissionBatch {
+        submissions,
+        cursor,
+    })?;
+    Ok(response)
+}
+
+/// Encodes the sequence number of a change into the cursor. The clients must not rely on the
+/// format.
+fn encode_cursor(sequence: i64) -> String {
+    hex::encode(sequence.to_string())
+}
+
+fn decode_cursor(cursor: &str) -> Option<i64> {
+    let decoded = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+    decoded.parse::<i64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        assert_eq!(decode_cursor(&encode_cursor(42)), Some(42));
+        assert_eq!(decode_cursor(&encode_cursor(0)), Some(0));
+        assert_eq!(decode_cursor(""), None);
+        assert_eq!(decode_cursor("xyz"), None);
+        assert_eq!(decode_cursor(&hex::encode("1:2")), None);
+    }
+}
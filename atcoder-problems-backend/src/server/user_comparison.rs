@@ -0,0 +1,215 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::difficulty_count::{difficulty_bucket, DIFFICULTY_BUCKET_WIDTH};
+use crate::sql::{ProblemModelClient, SimpleClient, SubmissionClient, SubmissionRequest};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tide::{Request, Response};
+
+/// The maximum number of the users compared at once.
+const MAX_COMPARED_USERS: usize = 10;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ComparedUser {
+    user_id: String,
+    accepted_count: usize,
+    /// The problems which the user has solved and none of the other users has solved.
+    only_solved: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct DifficultyBucket {
+    difficulty_from: i64,
+    difficulty_to: i64,
+    /// The numbers of the problems in the bucket which each user has solved.
+    solved_counts: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct UserComparison {
+    users: Vec<ComparedUser>,
+    difficulty_buckets: Vec<DifficultyBucket>,
+    /// The problems which none of the users has solved.
+    unsolved_by_all: Vec<String>,
+}
+
+/// Compares the problems solved by the users in `users`, a comma separated list of at most
+/// `MAX_COMPARED_USERS` user ids, so that the clients do not download the submissions of all the
+/// users to compare them.
+pub(crate) async fn get_user_comparison<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        users: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let mut user_ids = vec![];
+    for user_id in query.users.split(',').map(|user_id| user_id.trim()) {
+        if !user_id.is_empty() && !user_ids.iter().any(|u| u == user_id) {
+            user_ids.push(user_id.to_owned());
+        }
+    }
+    if user_ids.is_empty() || user_ids.len() > MAX_COMPARED_USERS {
+        return Ok(Response::bad_request());
+    }
+
+    let (solved, problems, models) = request
+        .state()
+        .with_conn(move |conn| {
+            let user_ids = user_ids.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+            let submissions = conn.get_submissions(SubmissionRequest::UsersAccepted {
+                user_ids: &user_ids,
+            })?;
+            let mut solved = user_ids
+                .iter()
+                .map(|&user_id| (user_id.to_owned(), BTreeSet::new()))
+                .collect::<Vec<_>>();
+            for submission in submissions {
+                if let Some((_, problem_ids)) = solved
+                    .iter_mut()
+                    .find(|(user_id, _)| *user_id == submission.user_id)
+                {
+                    problem_ids.insert(submission.problem_id);
+                }
+            }
+            let problems = conn
+                .load_problems()?
+                .into_iter()
+                .map(|problem| problem.id)
+                .collect::<Vec<_>>();
+            let models = conn
+                .load_problem_models()?
+                .into_iter()
+                .filter_map(|model| Some((model.problem_id, model.difficulty?)))
+                .collect::<BTreeMap<_, _>>();
+            Ok((solved, problems, models))
+        })
+        .await?;
+
+    let comparison = compare(&solved, &problems, &models);
+    let response = Response::new_cors().body_json(&comparison)?;
+    Ok(response)
+}
+
+fn compare(
+    solved: &[(String, BTreeSet<String>)],
+    problems: &[String],
+    difficulties: &BTreeMap<String, f64>,
+) -> UserComparison {
+    let users = solved
+        .iter()
+        .map(|(user_id, problem_ids)| ComparedUser {
+            user_id: user_id.clone(),
+            accepted_count: problem_ids.len(),
+            only_solved: problem_ids
+                .iter()
+                .filter(|&problem_id| {
+                    solved
+                        .iter()
+                        .all(|(other, others)| other == user_id || !others.contains(problem_id))
+                })
+                .cloned()
+                .collect(),
+        })
+        .collect();
+
+    let mut buckets = BTreeMap::new();
+    for &difficulty in difficulties.values() {
+        buckets
+            .entry(difficulty_bucket(difficulty))
+            .or_insert_with(|| {
+                solved
+                    .iter()
+                    .map(|(user_id, _)| (user_id.clone(), 0))
+                    .collect::<BTreeMap<_, _>>()
+            });
+    }
+    for (user_id, problem_ids) in solved {
+        for problem_id in problem_ids {
+            if let Some(&difficulty) = difficulties.get(problem_id) {
+                if let Some(count) = buckets
+                    .get_mut(&difficulty_bucket(difficulty))
+                    .and_then(|counts| counts.get_mut(user_id))
+                {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    let difficulty_buckets = buckets
+        .into_iter()
+        .map(|(difficulty_from, solved_counts)| DifficultyBucket {
+            difficulty_from,
+            difficulty_to: difficulty_from + DIFFICULTY_BUCKET_WIDTH,
+            solved_counts,
+        })
+        .collect();
+
+    let mut unsolved_by_all = problems
+        .iter()
+        .filter(|&problem_id| {
+            solved
+                .iter()
+                .all(|(_, problem_ids)| !problem_ids.contains(problem_id))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    unsolved_by_all.sort();
+
+    UserComparison {
+        users,
+        difficulty_buckets,
+        unsolved_by_all,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let solved = vec![
+            (
+                "a".to_owned(),
+                vec!["p1", "p2", "p3"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            ),
+            (
+                "b".to_owned(),
+                vec!["p2", "p4"].into_iter().map(String::from).collect(),
+            ),
+        ];
+        let problems = vec!["p1", "p2", "p3", "p4", "p5", "p6"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let difficulties = vec![("p1", -100.0), ("p2", 399.0), ("p4", 1200.0), ("p5", 500.0)]
+            .into_iter()
+            .map(|(problem_id, difficulty)| (problem_id.to_owned(), difficulty))
+            .collect();
+
+        let comparison = compare(&solved, &problems, &difficulties);
+        assert_eq!(comparison.users[0].accepted_count, 3);
+        assert_eq!(comparison.users[0].only_solved, vec!["p1", "p3"]);
+        assert_eq!(comparison.users[1].only_solved, vec!["p4"]);
+        assert_eq!(comparison.unsolved_by_all, vec!["p5", "p6"]);
+
+        let buckets = comparison
+            .difficulty_buckets
+            .iter()
+            .map(|b| {
+                (
+                    b.difficulty_from,
+                    b.solved_counts["a"],
+                    b.solved_counts["b"],
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(buckets, vec![(0, 2, 1), (400, 0, 0), (1200, 0, 1)]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::user_group_manager::{GroupRankingKind, UserGroupManager};
+
+use chrono::Utc;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+const MAX_GROUP_NAME_LENGTH: usize = 255;
+const MAX_ADDED_MEMBER_NUM: usize = 200;
+
+pub(crate) async fn get_groups<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let groups = conn.get_groups(&internal_user_id)?;
+    let response = Response::ok().body_json(&groups)?;
+    Ok(response)
+}
+
+pub(crate) async fn create_group<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        group_name: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("group_name", &self.group_name, 0, MAX_GROUP_NAME_LENGTH);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let internal_group_id = conn.create_group(&internal_user_id, &query.group_name)?;
+    let body = serde_json::json!({ "internal_group_id": internal_group_id });
+    let response = Response::ok().body_json(&body)?;
+    Ok(response)
+}
+
+pub(crate) async fn update_group<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_group_id: String,
+        group_name: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.length("group_name", &self.group_name, 0, MAX_GROUP_NAME_LENGTH);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.update_group(
+        &query.internal_group_id,
+        &internal_user_id,
+        &query.group_name,
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_group<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_group_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("internal_group_id", &self.internal_group_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_group(&query.internal_group_id, &internal_user_id)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn add_members<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_group_id: String,
+        user_ids: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.count("user_ids", self.user_ids.len(), MAX_ADDED_MEMBER_NUM);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    let user_ids = query
+        .user_ids
+        .iter()
+        .map(|user_id| user_id.trim())
+        .collect::<Vec<_>>();
+    conn.add_group_members(&query.internal_group_id, &internal_user_id, &user_ids)?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_member<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        internal_group_id: String,
+        user_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("internal_group_id", &self.internal_group_id)
+                .varchar("user_id", &self.user_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.remove_group_member(
+        &query.internal_group_id,
+        &internal_user_id,
+        query.user_id.trim(),
+    )?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+/// Returns the members of the group ranked by `kind`, one of `ac`, `points`, `streak` and
+/// `weekly`. Anyone who knows the id of the group can see the ranking, as the standings of a
+/// virtual contest.
+pub(crate) async fn get_group_ranking<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        internal_group_id: String,
+    }
+    let kind = match request
+        .param::<String>("kind")
+        .ok()
+        .and_then(|kind| GroupRankingKind::from_name(&kind))
+    {
+        Some(kind) => kind,
+        None => return Ok(Response::bad_request()),
+    };
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let now = Utc::now().timestamp();
+    let ranking = request
+        .state()
+        .with_conn(move |conn| conn.load_group_ranking(&query.internal_group_id, kind, now))
+        .await?;
+    let response = Response::new_cors().body_json(&ranking)?;
+    Ok(response)
+}
@@ -1,38 +1,101 @@
+use crate::cache::{self, STALE_USER_INFO_PREFIX, USER_INFO_PREFIX};
+use crate::server::versions::{entity_tag, is_not_modified};
 use crate::server::{AppData, CommonResponse};
+use crate::sql::models::Point;
 
-use crate::sql::{AcceptedCountClient, RatedPointSumClient};
+use crate::sql::{AcceptedCountClient, RatedPointSumClient, TableVersionClient};
 use serde::{Deserialize, Serialize};
-use tide::{Request, Response};
+use std::time::Duration;
+use tide::{Request, Response, StatusCode};
+
+/// The tables which the user info is derived from.
+const USER_INFO_TABLES: [&str; 2] = ["accepted_count", "rated_point_sum"];
+const STALE_USER_INFO_TTL: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Deserialize)]
 struct Query {
     user: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct UserInfo {
     user_id: String,
     accepted_count: i32,
     accepted_count_rank: i64,
-    rated_point_sum: f64,
+    rated_point_sum: Point,
     rated_point_sum_rank: i64,
 }
 
 pub(crate) async fn get_user_info<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
-    let conn = request.state().pool.get()?;
     let query = request.query::<Query>()?;
-    let user_id = query.user;
-    let accepted_count = conn.get_users_accepted_count(&user_id).unwrap_or(0);
-    let accepted_count_rank = conn.get_accepted_count_rank(accepted_count)?;
-    let rated_point_sum = conn.get_users_rated_point_sum(&user_id).unwrap_or(0.0);
-    let rated_point_sum_rank = conn.get_rated_point_sum_rank(rated_point_sum)?;
+    let stale_key = format!("{}{}", STALE_USER_INFO_PREFIX, query.user);
+    match respond_user_info(&request, query, &stale_key).await {
+        Err(e) if e.status() == StatusCode::ServiceUnavailable => {
+            // The database is overloaded, so the last response is served even if it is stale.
+            let cache = request.state().cache.as_ref();
+            match cache::get_json::<UserInfo>(cache, &stale_key).await {
+                Some(user_info) => Ok(Response::new_cors()
+                    .body_json(&user_info)?
+                    .set_header("warning", "110 - \"Response is Stale\"")),
+                None => Err(e),
+            }
+        }
+        result => result,
+    }
+}
+
+async fn respond_user_info<A>(
+    request: &Request<AppData<A>>,
+    query: Query,
+    stale_key: &str,
+) -> tide::Result<Response> {
+    let versions = request
+        .state()
+        .with_conn(|conn| conn.load_table_versions())
+        .await?;
+    let tag = entity_tag(&versions, &USER_INFO_TABLES);
+    if is_not_modified(request, &tag) {
+        let response = Response::new_cors()
+            .set_status(StatusCode::NotModified)
+            .set_header("etag", tag);
+        return Ok(response);
+    }
+
+    // The key contains the versions, so that a cached response is never served after the
+    // tables are updated, even before the stale ones are invalidated.
+    let key = format!(
+        "{}{}:{}",
+        USER_INFO_PREFIX,
+        tag.trim_matches('"'),
+        query.user
+    );
+    let cache = request.state().cache.as_ref();
+    if let Some(user_info) = cache::get_json::<UserInfo>(cache, &key).await {
+        return Ok(Response::new_cors()
+            .body_json(&user_info)?
+            .set_header("etag", tag));
+    }
 
-    let user_info = UserInfo {
-        user_id,
-        accepted_count,
-        accepted_count_rank,
-        rated_point_sum,
-        rated_point_sum_rank,
-    };
-    let user_info = Response::new_cors().body_json(&user_info)?;
+    let user_info = request
+        .state()
+        .with_conn(move |conn| {
+            let user_id = query.user;
+            let accepted_count = conn.get_users_accepted_count(&user_id).unwrap_or(0);
+            let accepted_count_rank = conn.get_accepted_count_rank(accepted_count)?;
+            let rated_point_sum = conn.get_users_rated_point_sum(&user_id).unwrap_or_default();
+            let rated_point_sum_rank = conn.get_rated_point_sum_rank(rated_point_sum)?;
+            Ok(UserInfo {
+                user_id,
+                accepted_count,
+                accepted_count_rank,
+                rated_point_sum,
+                rated_point_sum_rank,
+            })
+        })
+        .await?;
+    cache::set_json(cache, &key, &user_info, request.state().cache_ttl).await;
+    cache::set_json(cache, stale_key, &user_info, STALE_USER_INFO_TTL).await;
+    let user_info = Response::new_cors()
+        .body_json(&user_info)?
+        .set_header("etag", tag);
     Ok(user_info)
 }
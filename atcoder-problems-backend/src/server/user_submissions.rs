@@ -1,6 +1,7 @@
 use crate::server::{AppData, CommonResponse};
-use crate::sql::{SubmissionClient, SubmissionRequest};
+use crate::sql::{AsyncSubmissionClient, SubmissionArchiveClient, SubmissionRequest};
 use serde::Deserialize;
+use std::collections::BTreeSet;
 use tide::{Request, Response};
 
 pub(crate) async fn get_user_submissions<A>(
@@ -9,11 +10,33 @@ pub(crate) async fn get_user_submissions<A>(
     #[derive(Deserialize, Debug)]
     struct Query {
         user: String,
+        language: Option<String>,
+        #[serde(default)]
+        include_archived: bool,
     }
-    let conn = request.state().pool.get()?;
     let query = request.query::<Query>()?;
-    let user_id = &query.user;
-    let submissions = conn.get_submissions(SubmissionRequest::UserAll { user_id })?;
+    let state = request.state();
+    let user_id = query.user.as_str();
+    let request = match query.language.as_deref() {
+        Some(simplified_language) => SubmissionRequest::UserLanguage {
+            user_id,
+            simplified_language,
+        },
+        None => SubmissionRequest::UserAll { user_id },
+    };
+    let mut submissions = state.read(state.reader.get_submissions(request)).await?;
+    if query.include_archived {
+        let user_id = query.user.clone();
+        let language = query.language.clone();
+        let archived = state
+            .with_conn_timeout(state.submissions_query_timeout, move |conn| {
+                conn.get_user_archived_submissions(&user_id, language.as_deref())
+            })
+            .await?;
+        // A submission crawled again after the archival is in both tables.
+        let ids = submissions.iter().map(|s| s.id).collect::<BTreeSet<_>>();
+        submissions.extend(archived.into_iter().filter(|s| !ids.contains(&s.id)));
+    }
     let response = Response::new_cors()
         .set_header("Cache-Control", "max-age=300")
         .body_json(&submissions)?;
@@ -23,8 +46,14 @@ pub(crate) async fn get_user_submissions<A>(
 pub(crate) async fn get_recent_submissions<A>(
     request: Request<AppData<A>>,
 ) -> tide::Result<Response> {
-    let conn = request.state().pool.get()?;
-    let submissions = conn.get_submissions(SubmissionRequest::RecentAll { count: 1000 })?;
+    let state = request.state();
+    let submissions = state
+        .read(
+            state
+                .reader
+                .get_submissions(SubmissionRequest::RecentAll { count: 1000 }),
+        )
+        .await?;
     let response = Response::ok().body_json(&submissions)?;
     Ok(response)
 }
@@ -40,20 +69,26 @@ pub(crate) async fn get_users_time_submissions<A>(
         to: i64,
     }
 
-    let conn = request.state().pool.get()?;
     let query = request.query::<Query>()?;
+    let state = request.state();
     let user_ids = query.users.split(',').map(|s| s.trim()).collect::<Vec<_>>();
     let problem_ids = query
         .problems
         .split(',')
         .map(|s| s.trim())
         .collect::<Vec<_>>();
-    let submissions = conn.get_submissions(SubmissionRequest::UsersProblemsTime {
-        user_ids: &user_ids,
-        problem_ids: &problem_ids,
-        from_second: query.from,
-        to_second: query.to,
-    })?;
+    let submissions = state
+        .read(
+            state
+                .reader
+                .get_submissions(SubmissionRequest::UsersProblemsTime {
+                    user_ids: &user_ids,
+                    problem_ids: &problem_ids,
+                    from_second: query.from,
+                    to_second: query.to,
+                }),
+        )
+        .await?;
     let response = Response::ok().body_json(&submissions)?;
     Ok(response)
 }
@@ -1,15 +1,60 @@
 use crate::error::Result;
+use crate::server::validation::{read_valid_json_body, Validate};
 use crate::server::{AppData, Authentication, PooledConnection};
 
-use crate::error::ErrorTypes::CookieNotFound;
+use crate::error::Error::Unauthorized;
+use crate::sql::internal::api_token_manager::ApiTokenManager;
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use tide::Request;
 
+/// Returns the internal user id of the token. Tokens issued by this server are looked up in the
+/// database, and the others are treated as GitHub access tokens.
+pub(crate) async fn authenticate<A: Authentication>(
+    client: &A,
+    conn: PooledConnection,
+    token: &str,
+) -> Result<(PooledConnection, String)> {
+    if let Some(internal_user_id) = conn.find_token_user(token)? {
+        return Ok((conn, internal_user_id));
+    }
+    let response = client.get_user_id(token).await?;
+    Ok((conn, response.id.to_string()))
+}
+
+/// Returns the internal user id of the token in the cookie, or `None` if the request has no valid
+/// token, for the endpoints which are open to anyone but show more to the authenticated users.
+pub(crate) async fn optional_user<A: Authentication + Clone>(
+    request: &Request<AppData<A>>,
+) -> Result<Option<String>> {
+    if let Some(AuthenticatedUser(internal_user_id)) = request.ext::<AuthenticatedUser>() {
+        return Ok(Some(internal_user_id.clone()));
+    }
+    let token = match request.cookie("token") {
+        Some(token) => token,
+        None => return Ok(None),
+    };
+    let client = request.state().authentication.clone();
+    let conn = request.state().pool.get()?;
+    let user = authenticate(&client, conn, token.value())
+        .await
+        .ok()
+        .map(|(_, internal_user_id)| internal_user_id);
+    Ok(user)
+}
+
+/// The user authenticated by a middleware, which the handlers use instead of authenticating the
+/// user again.
+#[derive(Debug, Clone)]
+pub(crate) struct AuthenticatedUser(pub(crate) String);
+
 #[async_trait]
 pub(crate) trait RequestUnpack {
     async fn get_unpack(self) -> Result<(PooledConnection, String)>;
-    async fn post_unpack<Body: DeserializeOwned + Send + Sync + 'static>(
+
+    /// Reads the JSON body, which is rejected unless it satisfies its constraints, and
+    /// authenticates the user.
+    async fn post_unpack<Body: DeserializeOwned + Validate + Send + Sync + 'static>(
         self,
     ) -> Result<(Body, PooledConnection, String)>;
 }
@@ -19,20 +64,31 @@ impl<A: Authentication + Clone + Send + Sync + 'static> RequestUnpack for Reques
     async fn get_unpack(self) -> Result<(PooledConnection, String)> {
         let client = self.state().authentication.clone();
         let request = self;
-        let token = request.cookie("token").ok_or_else(|| CookieNotFound)?;
+        if let Some(AuthenticatedUser(internal_user_id)) = request.ext::<AuthenticatedUser>() {
+            let conn = request.state().pool.get()?;
+            return Ok((conn, internal_user_id.clone()));
+        }
+        let token = request
+            .cookie("token")
+            .ok_or_else(|| Unauthorized.into_http_error())?;
         let conn = request.state().pool.get()?;
-        let response = client.get_user_id(&token.value()).await?;
-        Ok((conn, response.id.to_string()))
+        authenticate(&client, conn, token.value()).await
     }
-    async fn post_unpack<Body: DeserializeOwned + Send + Sync + 'static>(
+    async fn post_unpack<Body: DeserializeOwned + Validate + Send + Sync + 'static>(
         self,
     ) -> Result<(Body, PooledConnection, String)> {
         let client = self.state().authentication.clone();
         let mut request = self;
-        let body: Body = request.body_json().await?;
-        let token = request.cookie("token").ok_or_else(|| CookieNotFound)?;
+        let body: Body = read_valid_json_body(&mut request).await?;
+        if let Some(AuthenticatedUser(internal_user_id)) = request.ext::<AuthenticatedUser>() {
+            let conn = request.state().pool.get()?;
+            return Ok((body, conn, internal_user_id.clone()));
+        }
+        let token = request
+            .cookie("token")
+            .ok_or_else(|| Unauthorized.into_http_error())?;
         let conn = request.state().pool.get()?;
-        let response = client.get_user_id(&token.value()).await?;
-        Ok((body, conn, response.id.to_string()))
+        let (conn, internal_user_id) = authenticate(&client, conn, token.value()).await?;
+        Ok((body, conn, internal_user_id))
     }
 }
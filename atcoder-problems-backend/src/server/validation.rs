@@ -0,0 +1,209 @@
+use crate::error::Result;
+
+use async_std::io::ReadExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tide::http::StatusCode;
+use tide::Request;
+
+/// The maximum size of the body of a request.
+pub(crate) const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// The maximum length of the values stored in the `VARCHAR(255)` columns.
+pub(crate) const MAX_VARCHAR_LENGTH: usize = 255;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FieldError {
+    field: String,
+    message: String,
+}
+
+/// A rejected request body. It is turned into a response with the JSON description of the fields
+/// by `ErrorResponse`.
+#[derive(Debug)]
+pub(crate) enum BodyError {
+    TooLarge,
+    Malformed(String),
+    Invalid(Vec<FieldError>),
+}
+
+impl std::error::Error for BodyError {}
+
+impl std::fmt::Display for BodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyError::TooLarge => write!(f, "the body exceeds {} bytes", MAX_BODY_BYTES),
+            BodyError::Malformed(message) => write!(f, "the body is malformed: {}", message),
+            BodyError::Invalid(errors) => write!(f, "{} fields are invalid", errors.len()),
+        }
+    }
+}
+
+impl BodyError {
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            BodyError::TooLarge => StatusCode::PayloadTooLarge,
+            BodyError::Malformed(_) | BodyError::Invalid(_) => StatusCode::BadRequest,
+        }
+    }
+
+    pub(crate) fn fields(&self) -> &[FieldError] {
+        match self {
+            BodyError::Invalid(errors) => errors.as_slice(),
+            _ => &[],
+        }
+    }
+
+    fn into_error(self) -> http_types::Error {
+        let status = self.status();
+        http_types::Error::new(status, self)
+    }
+}
+
+/// Collects the violations of the constraints of the fields.
+#[derive(Debug, Default)]
+pub(crate) struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub(crate) fn check(&mut self, field: &str, valid: bool, message: &str) -> &mut Self {
+        if !valid {
+            self.errors.push(FieldError {
+                field: field.to_owned(),
+                message: message.to_owned(),
+            });
+        }
+        self
+    }
+
+    pub(crate) fn length(&mut self, field: &str, value: &str, min: usize, max: usize) -> &mut Self {
+        let length = value.chars().count();
+        self.check(
+            field,
+            min <= length && length <= max,
+            &format!("must have {} to {} characters", min, max),
+        )
+    }
+
+    /// A value stored in a `VARCHAR(255)` column.
+    pub(crate) fn varchar(&mut self, field: &str, value: &str) -> &mut Self {
+        self.length(field, value, 0, MAX_VARCHAR_LENGTH)
+    }
+
+    /// The values stored in a `VARCHAR(255)[]` column, or in the rows of a `VARCHAR(255)` column.
+    pub(crate) fn varchars(&mut self, field: &str, values: &[String]) -> &mut Self {
+        self.check(
+            field,
+            values
+                .iter()
+                .all(|value| value.chars().count() <= MAX_VARCHAR_LENGTH),
+            &format!("must have at most {} characters each", MAX_VARCHAR_LENGTH),
+        )
+    }
+
+    pub(crate) fn range(&mut self, field: &str, value: i64, min: i64, max: i64) -> &mut Self {
+        self.check(
+            field,
+            min <= value && value <= max,
+            &format!("must be between {} and {}", min, max),
+        )
+    }
+
+    pub(crate) fn count(&mut self, field: &str, count: usize, max: usize) -> &mut Self {
+        self.check(
+            field,
+            count <= max,
+            &format!("must have at most {} items", max),
+        )
+    }
+
+    fn finish(self) -> std::result::Result<(), BodyError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(BodyError::Invalid(self.errors))
+        }
+    }
+}
+
+/// The constraints of a request body, which are checked before the body reaches the database.
+pub(crate) trait Validate {
+    fn validate(&self, validator: &mut Validator);
+}
+
+/// Reads at most `MAX_BODY_BYTES + 1` bytes of the body, so that a body without `content-length`,
+/// e.g. a chunked one, is not buffered without a limit. The body is too large if more than
+/// `MAX_BODY_BYTES` bytes are returned.
+pub(crate) async fn read_capped_body<State>(request: &mut Request<State>) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    request
+        .as_mut()
+        .take_body()
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .await?;
+    Ok(bytes)
+}
+
+/// Reads the JSON body of at most `MAX_BODY_BYTES` bytes.
+pub(crate) async fn read_json_body<State, Body>(request: &mut Request<State>) -> Result<Body>
+where
+    Body: DeserializeOwned,
+{
+    let declared_length = request
+        .header("content-length")
+        .and_then(|values| values.last().as_str().parse::<usize>().ok());
+    if declared_length.map_or(false, |length| length > MAX_BODY_BYTES) {
+        return Err(BodyError::TooLarge.into_error());
+    }
+    let bytes = read_capped_body(request).await?;
+    if bytes.len() > MAX_BODY_BYTES {
+        return Err(BodyError::TooLarge.into_error());
+    }
+    serde_json::from_slice(&bytes).map_err(|e| BodyError::Malformed(e.to_string()).into_error())
+}
+
+/// Reads the JSON body and checks its constraints.
+pub(crate) async fn read_valid_json_body<State, Body>(request: &mut Request<State>) -> Result<Body>
+where
+    Body: DeserializeOwned + Validate,
+{
+    let body: Body = read_json_body(request).await?;
+    let mut validator = Validator::default();
+    body.validate(&mut validator);
+    validator.finish().map_err(BodyError::into_error)?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator() {
+        let mut validator = Validator::default();
+        validator
+            .length("title", "", 1, 10)
+            .length("memo", "メモ", 0, 2)
+            .range("duration_second", 0, 1, 100)
+            .count("problems", 3, 2)
+            .varchar("name", &"a".repeat(MAX_VARCHAR_LENGTH))
+            .varchars(
+                "members",
+                &["a".to_owned(), "b".repeat(MAX_VARCHAR_LENGTH + 1)],
+            )
+            .check("mode", true, "unused");
+        let errors = match validator.finish() {
+            Err(BodyError::Invalid(errors)) => errors,
+            _ => unreachable!(),
+        };
+        let fields = errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec!["title", "duration_second", "problems", "members"]
+        );
+        assert_eq!(errors[0].message, "must have 1 to 10 characters");
+
+        assert!(Validator::default().finish().is_ok());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::Result;
+use crate::server::{AppData, CommonResponse, Pool};
+use crate::sql::models::TableVersion;
+use crate::sql::TableVersionClient;
+
+use async_std::io::BufReader;
+use async_std::task;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tide::{Request, Response};
+
+const VERSIONS_EVENT: &str = "versions";
+const VERSIONS_STREAM_INTERVAL_SECOND: u64 = 5;
+const VERSIONS_STREAM_DURATION_SECOND: u64 = 60 * 60;
+
+/// Lists the versions of the tables, so that the clients can tell which cached responses are
+/// stale.
+pub(crate) async fn get_versions<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let versions = request
+        .state()
+        .with_conn(|conn| conn.load_table_versions())
+        .await?;
+    let response = Response::new_cors().body_json(&versions)?;
+    Ok(response)
+}
+
+/// Streams the versions of the tables as Server-Sent Events.
+///
+/// The first event contains all versions, and each following event contains only the versions
+/// which have been bumped since the previous event. The stream is closed after
+/// `VERSIONS_STREAM_DURATION_SECOND` seconds, and the clients are expected to reconnect.
+pub(crate) async fn stream_versions<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    let pool = request.state().pool.clone();
+    let (sender, encoder) = async_sse::encode();
+    task::spawn(async move {
+        if let Err(e) = push_versions(pool, sender).await {
+            log::error!("Failed to stream versions: {:?}", e);
+        }
+    });
+    let response = Response::new_cors()
+        .body(BufReader::new(encoder))
+        .set_header("content-type", "text/event-stream")
+        .set_header("cache-control", "no-cache");
+    Ok(response)
+}
+
+async fn push_versions(pool: Pool, sender: async_sse::Sender) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(VERSIONS_STREAM_DURATION_SECOND);
+    let mut sent_versions = BTreeMap::new();
+    loop {
+        let versions = pool.get()?.load_table_versions()?;
+        let changed_versions = versions
+            .into_iter()
+            .filter(|version| sent_versions.get(&version.name) != Some(&version.version))
+            .collect::<Vec<_>>();
+        if !changed_versions.is_empty() {
+            for version in changed_versions.iter() {
+                sent_versions.insert(version.name.clone(), version.version);
+            }
+            let data = serde_json::to_vec(&changed_versions)?;
+            sender.send(VERSIONS_EVENT, &data, None).await;
+        }
+
+        if Instant::now() > deadline {
+            return Ok(());
+        }
+        task::sleep(Duration::from_secs(VERSIONS_STREAM_INTERVAL_SECOND)).await;
+    }
+}
+
+/// Builds the entity tag of a response derived from the tables, which changes whenever any of
+/// the tables is updated. The tables which have never been updated are at version 0.
+pub(crate) fn entity_tag(versions: &[TableVersion], table_names: &[&str]) -> String {
+    let versions = table_names
+        .iter()
+        .map(|name| {
+            versions
+                .iter()
+                .find(|version| version.name == *name)
+                .map(|version| version.version)
+                .unwrap_or(0)
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    format!("\"{}\"", versions.join("-"))
+}
+
+/// Whether the client already has the response with the entity tag.
+pub(crate) fn is_not_modified<A>(request: &Request<AppData<A>>, tag: &str) -> bool {
+    request
+        .header("if-none-match")
+        .map(|values| {
+            values.iter().any(|value| {
+                value
+                    .as_str()
+                    .split(',')
+                    .map(|candidate| candidate.trim())
+                    .any(|candidate| candidate == tag || candidate == "*")
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_tag() {
+        let versions = vec![
+            TableVersion {
+                name: "accepted_count".to_owned(),
+                version: 3,
+                updated_epoch_second: 100,
+            },
+            TableVersion {
+                name: "points".to_owned(),
+                version: 5,
+                updated_epoch_second: 200,
+            },
+        ];
+        assert_eq!(
+            entity_tag(&versions, &["accepted_count", "rated_point_sum"]),
+            "\"3-0\""
+        );
+        assert_eq!(entity_tag(&versions, &["points"]), "\"5\"");
+    }
+}
@@ -1,9 +1,32 @@
-use crate::server::utils::RequestUnpack;
+use crate::server::standings::scoring_strategy;
+use crate::server::utils::{optional_user, RequestUnpack};
+use crate::server::validation::{Validate, Validator};
 use crate::server::{AppData, Authentication, CommonResponse};
-use crate::sql::internal::virtual_contest_manager::{VirtualContestItem, VirtualContestManager};
+use crate::sql::internal::virtual_contest_manager::{
+    VirtualContestItem, VirtualContestManager, MAX_PROBLEM_NUM_PER_CONTEST,
+};
+use crate::sql::internal::virtual_contest_training_manager::{
+    VirtualContestTrainingManager, TRAINING_MODE,
+};
+use crate::sql::ProblemModelClient;
 
+use chrono::Utc;
 use serde::Deserialize;
 use tide::{Request, Response};
+use uuid::Uuid;
+
+const MAX_AUTO_SELECTED_PROBLEM_NUM: usize = 30;
+const MAX_TITLE_LENGTH: usize = 255;
+const MAX_MEMO_LENGTH: usize = 255;
+pub(crate) const MAX_DURATION_SECOND: i64 = 366 * 24 * 3600;
+const MAX_PARTICIPANT_NUM: usize = 1000;
+
+fn validate_contest(validator: &mut Validator, title: &str, memo: &str, duration_second: i64) {
+    validator
+        .length("title", title, 1, MAX_TITLE_LENGTH)
+        .length("memo", memo, 0, MAX_MEMO_LENGTH)
+        .range("duration_second", duration_second, 0, MAX_DURATION_SECOND);
+}
 
 pub(crate) async fn create_contest<A>(request: Request<AppData<A>>) -> tide::Result<Response>
 where
@@ -17,8 +40,18 @@ where
         duration_second: i64,
         mode: Option<String>,
         is_public: Option<bool>,
+        invite_only: Option<bool>,
+        scoring: Option<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validate_contest(validator, &self.title, &self.memo, self.duration_second);
+        }
     }
     let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    if scoring_strategy(q.scoring.as_deref()).is_none() {
+        return Ok(Response::bad_request());
+    }
     let contest_id = conn.create_contest(
         &q.title,
         &q.memo,
@@ -28,11 +61,85 @@ where
         q.mode.as_deref(),
         q.is_public.unwrap_or(true),
     )?;
+    if q.scoring.is_some() {
+        conn.update_scoring(&contest_id, &user_id, q.scoring.as_deref())?;
+    }
+    if let Some(invite_only) = q.invite_only {
+        conn.update_invite_only(&contest_id, &user_id, invite_only)?;
+    }
     let body = serde_json::json!({ "contest_id": contest_id });
     let response = Response::ok().body_json(&body)?;
     Ok(response)
 }
 
+pub(crate) async fn create_contest_with_auto_selection<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        title: String,
+        memo: String,
+        start_epoch_second: i64,
+        duration_second: i64,
+        mode: Option<String>,
+        is_public: Option<bool>,
+        problem_count: usize,
+        lower_difficulty: f64,
+        upper_difficulty: f64,
+        participants: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validate_contest(validator, &self.title, &self.memo, self.duration_second);
+            validator.count("participants", self.participants.len(), MAX_PARTICIPANT_NUM);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    if q.problem_count == 0 || q.problem_count > MAX_AUTO_SELECTED_PROBLEM_NUM {
+        return Ok(Response::bad_request());
+    }
+    let participants = q
+        .participants
+        .iter()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>();
+    let problem_ids = conn.select_unsolved_problems(
+        &participants,
+        q.lower_difficulty,
+        q.upper_difficulty,
+        q.problem_count,
+    )?;
+    if problem_ids.is_empty() {
+        return Ok(Response::bad_request());
+    }
+
+    let contest_id = conn.create_contest(
+        &q.title,
+        &q.memo,
+        &user_id,
+        q.start_epoch_second,
+        q.duration_second,
+        q.mode.as_deref(),
+        q.is_public.unwrap_or(true),
+    )?;
+    let problems = problem_ids
+        .iter()
+        .enumerate()
+        .map(|(i, problem_id)| VirtualContestItem {
+            id: problem_id.clone(),
+            point: None,
+            order: Some(i as i64),
+        })
+        .collect::<Vec<_>>();
+    conn.update_items(&contest_id, &problems, &user_id)?;
+
+    let body = serde_json::json!({ "contest_id": contest_id, "problems": problem_ids });
+    Ok(Response::ok().body_json(&body)?)
+}
+
 pub(crate) async fn update_contest<A>(request: Request<AppData<A>>) -> tide::Result<Response>
 where
     A: Authentication + Clone + Send + Sync + 'static,
@@ -46,9 +153,19 @@ where
         duration_second: i64,
         mode: Option<String>,
         is_public: Option<bool>,
+        invite_only: Option<bool>,
+        scoring: Option<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validate_contest(validator, &self.title, &self.memo, self.duration_second);
+        }
     }
 
-    let (q, conn, _) = request.post_unpack::<Q>().await?;
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    if scoring_strategy(q.scoring.as_deref()).is_none() {
+        return Ok(Response::bad_request());
+    }
     conn.update_contest(
         &q.id,
         &q.title,
@@ -58,6 +175,12 @@ where
         q.mode.as_deref(),
         q.is_public.unwrap_or(true),
     )?;
+    if q.scoring.is_some() {
+        conn.update_scoring(&q.id, &user_id, q.scoring.as_deref())?;
+    }
+    if let Some(invite_only) = q.invite_only {
+        conn.update_invite_only(&q.id, &user_id, invite_only)?;
+    }
     Ok(Response::ok().body_json(&serde_json::json!({}))?)
 }
 
@@ -69,12 +192,51 @@ pub(crate) async fn update_items<A: Authentication + Clone + Send + Sync + 'stat
         contest_id: String,
         problems: Vec<VirtualContestItem>,
     }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.count("problems", self.problems.len(), MAX_PROBLEM_NUM_PER_CONTEST);
+        }
+    }
 
     let (q, conn, user_id) = request.post_unpack::<Q>().await?;
     conn.update_items(&q.contest_id, &q.problems, &user_id)?;
     Ok(Response::ok().body_json(&serde_json::json!({}))?)
 }
 
+pub(crate) async fn delete_contest<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_contest(&q.contest_id, &user_id, Utc::now().timestamp())?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn restore_contest<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.restore_contest(&q.contest_id, &user_id, Utc::now().timestamp())?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
 pub(crate) async fn get_my_contests<A: Authentication + Clone + Send + Sync + 'static>(
     request: Request<AppData<A>>,
 ) -> tide::Result<Response> {
@@ -91,10 +253,32 @@ pub(crate) async fn get_participated<A: Authentication + Clone + Send + Sync + '
     Ok(Response::ok().body_json(&contests)?)
 }
 
-pub(crate) async fn get_single_contest<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
-    let conn = request.state().pool.get()?;
+/// Returns the contest to anyone if it is public, and otherwise only to its owner, its
+/// participants, and the requests with its invite token. The problems of a training contest
+/// which the user has not unlocked yet are hidden from anyone but its owner.
+pub(crate) async fn get_single_contest<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
     let contest_id = request.param::<String>("contest_id")?;
-    let contest = conn.get_single_contest(&contest_id)?;
+    let query = request.query::<Query>()?;
+    let user = optional_user(&request).await?;
+    let conn = request.state().pool.get()?;
+    conn.check_contest_access(&contest_id, user.as_deref(), query.invite_token.as_deref())?;
+    let info = conn.get_single_contest_info(&contest_id)?;
+    let mut contest = conn.get_single_contest(&contest_id)?;
+    if info.mode.as_deref() == Some(TRAINING_MODE)
+        && user.as_deref() != Some(info.owner_user_id.as_str())
+    {
+        let progress = conn.get_training_progress(&contest_id, user.as_deref())?;
+        contest
+            .problems
+            .retain(|problem| progress.unlocked.contains(&problem.id));
+    }
     let response = Response::ok().body_json(&contest)?;
     Ok(response)
 }
@@ -112,9 +296,98 @@ pub(crate) async fn join_contest<A: Authentication + Clone + Send + Sync + 'stat
     #[derive(Deserialize)]
     struct Q {
         contest_id: String,
+        invite_token: Option<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("contest_id", &self.contest_id)
+                .varchar("invite_token", self.invite_token.as_deref().unwrap_or(""));
+        }
     }
     let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.check_contest_access(&q.contest_id, Some(&user_id), q.invite_token.as_deref())?;
     conn.join_contest(&q.contest_id, &user_id)?;
     let response = Response::ok().body_json(&serde_json::json!({}))?;
     Ok(response)
 }
+
+pub(crate) async fn generate_invite_token<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    if !conn.is_contest_owner(&q.contest_id, &user_id)? {
+        return Ok(Response::bad_request());
+    }
+    let invite_token = match conn.get_single_contest_info(&q.contest_id)?.invite_token {
+        Some(invite_token) => invite_token,
+        None => {
+            let invite_token = Uuid::new_v4().to_string();
+            conn.update_invite_token(&q.contest_id, &user_id, Some(&invite_token))?;
+            invite_token
+        }
+    };
+    let body = serde_json::json!({ "invite_token": invite_token });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn rotate_invite_token<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    let invite_token = Uuid::new_v4().to_string();
+    conn.update_invite_token(&q.contest_id, &user_id, Some(&invite_token))?;
+    let body = serde_json::json!({ "invite_token": invite_token });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn revoke_invite_token<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.update_invite_token(&q.contest_id, &user_id, None)?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn clone_contest<A: Authentication + Clone + Send + Sync + 'static>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        id: String,
+        invite_token: Option<String>,
+    }
+    let query = request.query::<Query>()?;
+    let (conn, user_id) = request.get_unpack().await?;
+    conn.check_contest_access(&query.id, Some(&user_id), query.invite_token.as_deref())?;
+    let contest_id = conn.clone_contest(&query.id, &user_id)?;
+    let body = serde_json::json!({ "contest_id": contest_id });
+    Ok(Response::ok().body_json(&body)?)
+}
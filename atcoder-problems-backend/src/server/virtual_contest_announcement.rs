@@ -0,0 +1,64 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::virtual_contest_announcement_manager::VirtualContestAnnouncementManager;
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+
+use chrono::Utc;
+use serde::Deserialize;
+use tide::{Request, Response};
+
+pub(crate) async fn post_announcement<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        message: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    let now = Utc::now().timestamp();
+    let announcement_id = conn.post_announcement(&q.contest_id, &user_id, &q.message, now)?;
+    let body = serde_json::json!({ "announcement_id": announcement_id });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn delete_announcement<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        announcement_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("contest_id", &self.contest_id)
+                .varchar("announcement_id", &self.announcement_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_announcement(&q.contest_id, &user_id, &q.announcement_id)?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn get_announcements<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
+    let conn = request.state().pool.get()?;
+    let contest_id = request.param::<String>("contest_id")?;
+    let query = request.query::<Query>()?;
+    conn.check_contest_access(&contest_id, None, query.invite_token.as_deref())?;
+    let announcements = conn.get_announcements(&contest_id)?;
+    Ok(Response::ok().body_json(&announcements)?)
+}
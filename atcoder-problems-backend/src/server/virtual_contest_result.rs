@@ -0,0 +1,22 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::internal::virtual_contest_result_manager::VirtualContestResultManager;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+/// Returns the archived results of an ended contest, or 404 if it is not archived yet.
+pub(crate) async fn get_results<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
+    let conn = request.state().pool.get()?;
+    let contest_id = request.param::<String>("contest_id")?;
+    let query = request.query::<Query>()?;
+    conn.check_contest_access(&contest_id, None, query.invite_token.as_deref())?;
+    match conn.get_results(&contest_id)? {
+        Some(results) => Ok(Response::ok().body_json(&results)?),
+        None => Ok(Response::not_found()),
+    }
+}
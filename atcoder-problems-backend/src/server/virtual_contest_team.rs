@@ -0,0 +1,296 @@
+use crate::error::Result;
+use crate::server::standings::{
+    compute_standings, scoring_strategy, AtCoderScoring, StandingsEntry, StandingsRow,
+};
+use crate::server::utils::{optional_user, RequestUnpack};
+use crate::server::validation::{Validate, Validator, MAX_VARCHAR_LENGTH};
+use crate::server::{AppData, Authentication, CommonResponse, Pool};
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::internal::virtual_contest_team_manager::VirtualContestTeamManager;
+use crate::sql::{SubmissionClient, SubmissionRequest};
+
+use async_std::io::BufReader;
+use async_std::{future, task};
+use chrono::Utc;
+use diesel::PgConnection;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tide::{Request, Response};
+
+const STANDINGS_EVENT: &str = "standings";
+const STANDINGS_STREAM_INTERVAL_SECOND: u64 = 5;
+const STANDINGS_STREAM_DURATION_SECOND: u64 = 60 * 60;
+/// The client is regarded as gone if it does not read an event in this time.
+const STANDINGS_SEND_TIMEOUT_SECOND: u64 = 30;
+
+pub(crate) async fn create_team<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        team_name: String,
+        members: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("contest_id", &self.contest_id)
+                .length("team_name", &self.team_name, 1, MAX_VARCHAR_LENGTH)
+                .varchars("members", &self.members);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    let team_id = conn.create_team(&q.contest_id, &user_id, &q.team_name, &q.members)?;
+    let body = serde_json::json!({ "team_id": team_id });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn update_team<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        team_id: String,
+        team_name: String,
+        members: Vec<String>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("contest_id", &self.contest_id)
+                .varchar("team_id", &self.team_id)
+                .length("team_name", &self.team_name, 1, MAX_VARCHAR_LENGTH)
+                .varchars("members", &self.members);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.update_team(
+        &q.contest_id,
+        &user_id,
+        &q.team_id,
+        &q.team_name,
+        &q.members,
+    )?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn delete_team<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+        team_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .varchar("contest_id", &self.contest_id)
+                .varchar("team_id", &self.team_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_team(&q.contest_id, &user_id, &q.team_id)?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn get_teams<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
+    let contest_id = request.param::<String>("contest_id")?;
+    let query = request.query::<Query>()?;
+    let user = optional_user(&request).await?;
+    let conn = request.state().pool.get()?;
+    conn.check_contest_access(&contest_id, user.as_deref(), query.invite_token.as_deref())?;
+    let teams = conn.get_teams(&contest_id)?;
+    Ok(Response::ok().body_json(&teams)?)
+}
+
+pub(crate) async fn get_team_standings<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
+    let conn = request.state().pool.get()?;
+    let contest_id = request.param::<String>("contest_id")?;
+    let query = request.query::<Query>()?;
+    conn.check_contest_access(&contest_id, None, query.invite_token.as_deref())?;
+    let (_, standings) = load_team_standings(&conn, &contest_id)?;
+    Ok(Response::ok().body_json(&standings)?)
+}
+
+/// Streams the team standings, or the standings of the participants if the contest has no teams,
+/// as Server-Sent Events while the contest is running.
+///
+/// The first event contains all rows, and each following event contains only the rows which have
+/// changed since the previous event, and `{"id": ..., "removed": true}` for the rows which have
+/// been removed. The stream is closed when the contest ends, when the client stops reading, or
+/// after `STANDINGS_STREAM_DURATION_SECOND` seconds, and the clients are expected to reconnect.
+pub(crate) async fn stream_team_standings<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        invite_token: Option<String>,
+    }
+    let pool = request.state().pool.clone();
+    let contest_id = request.param::<String>("contest_id")?;
+    let query = request.query::<Query>()?;
+    pool.get()?
+        .check_contest_access(&contest_id, None, query.invite_token.as_deref())?;
+
+    let (sender, encoder) = async_sse::encode();
+    task::spawn(async move {
+        if let Err(e) = push_team_standings(pool, &contest_id, sender).await {
+            log::error!("Failed to stream standings of {}: {:?}", contest_id, e);
+        }
+    });
+    let response = Response::ok()
+        .body(BufReader::new(encoder))
+        .set_header("content-type", "text/event-stream")
+        .set_header("cache-control", "no-cache");
+    Ok(response)
+}
+
+async fn push_team_standings(
+    pool: Pool,
+    contest_id: &str,
+    sender: async_sse::Sender,
+) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(STANDINGS_STREAM_DURATION_SECOND);
+    let mut sent_rows = BTreeMap::new();
+    loop {
+        let conn = pool.get()?;
+        let (end_epoch_second, standings) = load_team_standings(&conn, contest_id)?;
+        drop(conn);
+        let rows = standings
+            .iter()
+            .map(|row| Ok((row.id.clone(), serde_json::to_value(row)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let changed_rows = standings_delta(&mut sent_rows, rows);
+        if !changed_rows.is_empty() {
+            let data = serde_json::to_vec(&changed_rows)?;
+            let timeout = Duration::from_secs(STANDINGS_SEND_TIMEOUT_SECOND);
+            future::timeout(timeout, sender.send(STANDINGS_EVENT, &data, None)).await?;
+        }
+
+        if Utc::now().timestamp() > end_epoch_second || Instant::now() > deadline {
+            return Ok(());
+        }
+        task::sleep(Duration::from_secs(STANDINGS_STREAM_INTERVAL_SECOND)).await;
+    }
+}
+
+/// Returns the rows which differ from the ones in `sent_rows`, and the removals of the rows which
+/// are in `sent_rows` but not in `rows`, and then updates `sent_rows` to `rows`.
+fn standings_delta(
+    sent_rows: &mut BTreeMap<String, Value>,
+    rows: Vec<(String, Value)>,
+) -> Vec<Value> {
+    let mut changed_rows = Vec::new();
+    let mut current_rows = BTreeMap::new();
+    for (id, row) in rows.into_iter() {
+        if sent_rows.get(&id) != Some(&row) {
+            changed_rows.push(row.clone());
+        }
+        current_rows.insert(id, row);
+    }
+    for id in sent_rows.keys() {
+        if !current_rows.contains_key(id) {
+            changed_rows.push(serde_json::json!({ "id": id, "removed": true }));
+        }
+    }
+    *sent_rows = current_rows;
+    changed_rows
+}
+
+/// Computes the standings of the teams, or of the participants if the contest has no teams.
+fn load_team_standings(conn: &PgConnection, contest_id: &str) -> Result<(i64, Vec<StandingsRow>)> {
+    let contest = conn.get_single_contest_info(contest_id)?;
+    let problems = conn.get_contest_items(contest_id)?;
+    let teams = conn.get_teams(contest_id)?;
+    let entries = if teams.is_empty() {
+        conn.get_single_contest(contest_id)?
+            .participants
+            .into_iter()
+            .map(|user_id| StandingsEntry {
+                id: user_id.clone(),
+                name: user_id.clone(),
+                members: vec![user_id],
+            })
+            .collect::<Vec<_>>()
+    } else {
+        teams
+            .into_iter()
+            .map(|team| StandingsEntry {
+                id: team.id,
+                name: team.team_name,
+                members: team.members,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let user_ids = entries
+        .iter()
+        .flat_map(|entry| entry.members.iter().map(|m| m.as_str()))
+        .collect::<Vec<_>>();
+    let problem_ids = problems.iter().map(|p| p.id.as_str()).collect::<Vec<_>>();
+    let end_epoch_second = contest.end_epoch_second().unwrap_or(i64::MAX);
+    let submissions = conn.get_submissions(SubmissionRequest::UsersProblemsTime {
+        user_ids: &user_ids,
+        problem_ids: &problem_ids,
+        from_second: contest.start_epoch_second,
+        to_second: end_epoch_second,
+    })?;
+
+    let scoring =
+        scoring_strategy(contest.scoring.as_deref()).unwrap_or_else(|| Box::new(AtCoderScoring));
+    let standings = compute_standings(
+        scoring.as_ref(),
+        entries,
+        &problems,
+        contest.start_epoch_second,
+        end_epoch_second,
+        &submissions,
+    )?;
+    Ok((end_epoch_second, standings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_standings_delta() {
+        let mut sent_rows = BTreeMap::new();
+        let rows = vec![
+            ("a".to_owned(), json!({"id": "a", "score": 100.0})),
+            ("b".to_owned(), json!({"id": "b", "score": 0.0})),
+        ];
+        assert_eq!(standings_delta(&mut sent_rows, rows.clone()).len(), 2);
+        assert!(standings_delta(&mut sent_rows, rows).is_empty());
+
+        let rows = vec![("b".to_owned(), json!({"id": "b", "score": 200.0}))];
+        assert_eq!(
+            standings_delta(&mut sent_rows, rows),
+            vec![
+                json!({"id": "b", "score": 200.0}),
+                json!({"id": "a", "removed": true})
+            ]
+        );
+        assert_eq!(sent_rows.keys().collect::<Vec<_>>(), vec!["b"]);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator, MAX_VARCHAR_LENGTH};
+use crate::server::virtual_contest::MAX_DURATION_SECOND;
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::virtual_contest_manager::MAX_PROBLEM_NUM_PER_CONTEST;
+use crate::sql::internal::virtual_contest_template_manager::VirtualContestTemplateManager;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+pub(crate) async fn create_template<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        title: String,
+        memo: String,
+        weekday: i32,
+        start_minute_of_day: i32,
+        duration_second: i64,
+        mode: Option<String>,
+        is_public: Option<bool>,
+        problem_difficulties: Vec<f64>,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator
+                .length("title", &self.title, 1, MAX_VARCHAR_LENGTH)
+                .varchar("memo", &self.memo)
+                .varchar("mode", self.mode.as_deref().unwrap_or(""))
+                .range("weekday", self.weekday.into(), 0, 6)
+                .range(
+                    "start_minute_of_day",
+                    self.start_minute_of_day.into(),
+                    0,
+                    24 * 60 - 1,
+                )
+                .range(
+                    "duration_second",
+                    self.duration_second,
+                    0,
+                    MAX_DURATION_SECOND,
+                )
+                .count(
+                    "problem_difficulties",
+                    self.problem_difficulties.len(),
+                    MAX_PROBLEM_NUM_PER_CONTEST,
+                );
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    let template_id = conn.create_template(
+        &user_id,
+        &q.title,
+        &q.memo,
+        q.weekday,
+        q.start_minute_of_day,
+        q.duration_second,
+        q.mode.as_deref(),
+        q.is_public.unwrap_or(true),
+        &q.problem_difficulties,
+    )?;
+    let body = serde_json::json!({ "template_id": template_id });
+    Ok(Response::ok().body_json(&body)?)
+}
+
+pub(crate) async fn delete_template<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        template_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("template_id", &self.template_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    conn.delete_template(&user_id, &q.template_id)?;
+    Ok(Response::ok().body_json(&serde_json::json!({}))?)
+}
+
+pub(crate) async fn get_my_templates<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, user_id) = request.get_unpack().await?;
+    let templates = conn.get_own_templates(&user_id)?;
+    Ok(Response::ok().body_json(&templates)?)
+}
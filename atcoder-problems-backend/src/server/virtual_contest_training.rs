@@ -0,0 +1,38 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::virtual_contest_training_manager::VirtualContestTrainingManager;
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+pub(crate) async fn get_training_progress<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let contest_id = request.param::<String>("contest_id")?;
+    let (conn, user_id) = request.get_unpack().await?;
+    let progress = conn.get_training_progress(&contest_id, Some(&user_id))?;
+    Ok(Response::ok().body_json(&progress)?)
+}
+
+/// Records the problems newly solved by the user, which unlocks the following problems.
+pub(crate) async fn update_training_progress<A>(
+    request: Request<AppData<A>>,
+) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        contest_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("contest_id", &self.contest_id);
+        }
+    }
+    let (q, conn, user_id) = request.post_unpack::<Q>().await?;
+    let progress = conn.update_training_progress(&q.contest_id, &user_id)?;
+    Ok(Response::ok().body_json(&progress)?)
+}
@@ -0,0 +1,80 @@
+use crate::server::utils::RequestUnpack;
+use crate::server::validation::{Validate, Validator};
+use crate::server::{AppData, Authentication, CommonResponse};
+use crate::sql::internal::watch_list_manager::WatchListManager;
+use crate::sql::{SubmissionClient, SubmissionRequest};
+
+use serde::Deserialize;
+use tide::{Request, Response};
+
+const MAX_FEED_SIZE: i64 = 100;
+
+pub(crate) async fn get_watched_users<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let users = conn.get_watched_users(&internal_user_id)?;
+    let response = Response::ok().body_json(&users)?;
+    Ok(response)
+}
+
+pub(crate) async fn add_watched_user<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        user_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("user_id", &self.user_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.add_watched_user(&internal_user_id, query.user_id.trim())?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+pub(crate) async fn delete_watched_user<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Q {
+        user_id: String,
+    }
+    impl Validate for Q {
+        fn validate(&self, validator: &mut Validator) {
+            validator.varchar("user_id", &self.user_id);
+        }
+    }
+    let (query, conn, internal_user_id) = request.post_unpack::<Q>().await?;
+    conn.remove_watched_user(&internal_user_id, query.user_id.trim())?;
+    let response = Response::ok().body_json(&serde_json::json!({}))?;
+    Ok(response)
+}
+
+/// Returns the recent accepted submissions of the watched users, the newest first.
+pub(crate) async fn get_watch_feed<A>(request: Request<AppData<A>>) -> tide::Result<Response>
+where
+    A: Authentication + Clone + Send + Sync + 'static,
+{
+    #[derive(Deserialize)]
+    struct Query {
+        count: Option<i64>,
+    }
+    let query = request.query::<Query>()?;
+    let (conn, internal_user_id) = request.get_unpack().await?;
+    let count = query.count.unwrap_or(MAX_FEED_SIZE).clamp(0, MAX_FEED_SIZE);
+    let users = conn.get_watched_users(&internal_user_id)?;
+    let user_ids = users.iter().map(|user| user.as_str()).collect::<Vec<_>>();
+    let submissions = conn.get_submissions(SubmissionRequest::UsersRecentAccepted {
+        user_ids: &user_ids,
+        count,
+    })?;
+    let response = Response::ok().body_json(&submissions)?;
+    Ok(response)
+}
@@ -0,0 +1,274 @@
+use crate::server::{AppData, CommonResponse};
+use crate::sql::difficulty_count::{difficulty_bucket, DIFFICULTY_BUCKET_WIDTH};
+use crate::sql::models::ContestCategory;
+use crate::sql::{
+    AcceptedCountClient, ContestCompletionClient, ContestProblemClient, DifficultyCountClient,
+    PeerStats, PeerStatsClient, ProblemModelClient, SimpleClient,
+};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tide::{Request, Response};
+
+/// The peers of a user are the users whose accepted counts differ from the user's one by at most
+/// 1/`PEER_MARGIN_DIVISOR` of it, or by `MIN_PEER_MARGIN` for the beginners.
+const PEER_MARGIN_DIVISOR: i32 = 5;
+const MIN_PEER_MARGIN: i32 = 10;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct DifficultyWeakness {
+    difficulty_from: i64,
+    difficulty_to: i64,
+    problem_count: i64,
+    solve_rate: f64,
+    /// The average solve rate of the peers, or `None` if the user has no peers.
+    peer_solve_rate: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CategoryWeakness {
+    category: ContestCategory,
+    problem_count: i64,
+    solve_rate: f64,
+    peer_solve_rate: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Weakness {
+    user_id: String,
+    accepted_count: i32,
+    min_peer_accepted_count: i32,
+    max_peer_accepted_count: i32,
+    peer_count: i64,
+    difficulties: Vec<DifficultyWeakness>,
+    categories: Vec<CategoryWeakness>,
+}
+
+/// Compares the solve rates of the user in each difficulty bucket and each contest category with
+/// the average ones of the users of similar accepted counts, for the suggestions of what to
+/// practice. The rates are computed from the aggregate tables, not from the submissions.
+pub(crate) async fn get_weakness<A>(request: Request<AppData<A>>) -> tide::Result<Response> {
+    #[derive(Deserialize)]
+    struct Query {
+        user: String,
+    }
+    let query = match request.query::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok(Response::bad_request()),
+    };
+    let weakness = request
+        .state()
+        .with_conn(move |conn| {
+            let accepted_count = conn.get_users_accepted_count(&query.user).unwrap_or(0);
+            let (min_peer_accepted_count, max_peer_accepted_count) = peer_range(accepted_count);
+            let peers = conn.load_peer_stats(
+                &query.user,
+                min_peer_accepted_count,
+                max_peer_accepted_count,
+            )?;
+            let user = PeerStats {
+                user_count: 1,
+                difficulty_counts: conn
+                    .load_users_difficulty_count(&query.user)?
+                    .into_iter()
+                    .map(|count| (count.difficulty_from, count.problem_count as i64))
+                    .collect(),
+                contest_solved_counts: conn
+                    .load_users_contest_completion(&query.user, false)?
+                    .into_iter()
+                    .map(|completion| (completion.contest_id, completion.solved_count as i64))
+                    .collect(),
+            };
+
+            let mut bucket_problem_counts = BTreeMap::new();
+            for model in conn.load_problem_models()?.into_iter() {
+                if let Some(difficulty) = model.difficulty {
+                    *bucket_problem_counts
+                        .entry(difficulty_bucket(difficulty))
+                        .or_insert(0) += 1;
+                }
+            }
+            let categories = conn
+                .load_contests()?
+                .into_iter()
+                .map(|contest| (contest.id.clone(), contest.category()))
+                .collect::<BTreeMap<_, _>>();
+            let mut contest_problem_counts = BTreeMap::new();
+            for contest_problem in conn.load_contest_problem()?.into_iter() {
+                if let Some(&category) = categories.get(&contest_problem.contest_id) {
+                    contest_problem_counts
+                        .entry(contest_problem.contest_id)
+                        .or_insert((category, 0))
+                        .1 += 1;
+                }
+            }
+
+            let (difficulties, categories) = analyze(
+                &user,
+                &peers,
+                &bucket_problem_counts,
+                &contest_problem_counts,
+            );
+            Ok(Weakness {
+                user_id: query.user,
+                accepted_count,
+                min_peer_accepted_count,
+                max_peer_accepted_count,
+                peer_count: peers.user_count,
+                difficulties,
+                categories,
+            })
+        })
+        .await?;
+    let response = Response::new_cors().body_json(&weakness)?;
+    Ok(response)
+}
+
+fn peer_range(accepted_count: i32) -> (i32, i32) {
+    let margin = (accepted_count / PEER_MARGIN_DIVISOR).max(MIN_PEER_MARGIN);
+    ((accepted_count - margin).max(0), accepted_count + margin)
+}
+
+/// The average solve rate of `user_count` users who have solved `solved_count` problems in total
+/// out of `problem_count` problems each.
+fn solve_rate(solved_count: i64, user_count: i64, problem_count: i64) -> Option<f64> {
+    if user_count == 0 || problem_count == 0 {
+        None
+    } else {
+        Some(solved_count as f64 / (user_count * problem_count) as f64)
+    }
+}
+
+fn analyze(
+    user: &PeerStats,
+    peers: &PeerStats,
+    bucket_problem_counts: &BTreeMap<i64, i64>,
+    contest_problem_counts: &BTreeMap<String, (ContestCategory, i64)>,
+) -> (Vec<DifficultyWeakness>, Vec<CategoryWeakness>) {
+    let difficulties = bucket_problem_counts
+        .iter()
+        .map(|(&difficulty_from, &problem_count)| {
+            let solved = |stats: &PeerStats| {
+                let solved_count = stats.difficulty_counts.get(&difficulty_from).copied();
+                solve_rate(solved_count.unwrap_or(0), stats.user_count, problem_count)
+            };
+            DifficultyWeakness {
+                difficulty_from,
+                difficulty_to: difficulty_from + DIFFICULTY_BUCKET_WIDTH,
+                problem_count,
+                solve_rate: solved(user).unwrap_or(0.0),
+                peer_solve_rate: solved(peers),
+            }
+        })
+        .collect();
+
+    // The numbers of the problems, the ones solved by the user and the ones solved by the peers.
+    let mut category_counts = BTreeMap::new();
+    for (contest_id, &(category, problem_count)) in contest_problem_counts.iter() {
+        let counts = category_counts.entry(category).or_insert((0, 0, 0));
+        counts.0 += problem_count;
+        counts.1 += user.contest_solved_counts.get(contest_id).unwrap_or(&0);
+        counts.2 += peers.contest_solved_counts.get(contest_id).unwrap_or(&0);
+    }
+    let categories = category_counts
+        .into_iter()
+        .map(
+            |(category, (problem_count, user_solved, peer_solved))| CategoryWeakness {
+                category,
+                problem_count,
+                solve_rate: solve_rate(user_solved, user.user_count, problem_count).unwrap_or(0.0),
+                peer_solve_rate: solve_rate(peer_solved, peers.user_count, problem_count),
+            },
+        )
+        .collect();
+    (difficulties, categories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_range() {
+        assert_eq!(peer_range(0), (0, 10));
+        assert_eq!(peer_range(30), (20, 40));
+        assert_eq!(peer_range(1000), (800, 1200));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let user = PeerStats {
+            user_count: 1,
+            difficulty_counts: vec![(0, 8)].into_iter().collect(),
+            contest_solved_counts: vec![("abc001".to_owned(), 4), ("arc001".to_owned(), 1)]
+                .into_iter()
+                .collect(),
+        };
+        let peers = PeerStats {
+            user_count: 2,
+            difficulty_counts: vec![(0, 10), (400, 4)].into_iter().collect(),
+            contest_solved_counts: vec![("abc001".to_owned(), 6), ("abc002".to_owned(), 2)]
+                .into_iter()
+                .collect(),
+        };
+        let bucket_problem_counts = vec![(0, 10), (400, 4)].into_iter().collect();
+        let contest_problem_counts = vec![
+            ("abc001".to_owned(), (ContestCategory::Abc, 4)),
+            ("abc002".to_owned(), (ContestCategory::Abc, 4)),
+            ("arc001".to_owned(), (ContestCategory::Arc, 4)),
+        ]
+        .into_iter()
+        .collect();
+
+        let (difficulties, categories) = analyze(
+            &user,
+            &peers,
+            &bucket_problem_counts,
+            &contest_problem_counts,
+        );
+        assert_eq!(
+            difficulties,
+            vec![
+                DifficultyWeakness {
+                    difficulty_from: 0,
+                    difficulty_to: 400,
+                    problem_count: 10,
+                    solve_rate: 0.8,
+                    peer_solve_rate: Some(0.5),
+                },
+                DifficultyWeakness {
+                    difficulty_from: 400,
+                    difficulty_to: 800,
+                    problem_count: 4,
+                    solve_rate: 0.0,
+                    peer_solve_rate: Some(0.5),
+                },
+            ]
+        );
+        assert_eq!(
+            categories,
+            vec![
+                CategoryWeakness {
+                    category: ContestCategory::Abc,
+                    problem_count: 8,
+                    solve_rate: 0.5,
+                    peer_solve_rate: Some(0.5),
+                },
+                CategoryWeakness {
+                    category: ContestCategory::Arc,
+                    problem_count: 4,
+                    solve_rate: 0.25,
+                    peer_solve_rate: Some(0.0),
+                },
+            ]
+        );
+
+        let (difficulties, _) = analyze(
+            &user,
+            &PeerStats::default(),
+            &bucket_problem_counts,
+            &contest_problem_counts,
+        );
+        assert_eq!(difficulties[0].peer_solve_rate, None);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::error::Result;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const POLL_INTERVAL_MILLIS: u64 = 100;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Requests the shutdown on SIGTERM and SIGINT. The servers and the workers check the request
+/// between units of work, so that they finish the work in flight before exiting.
+pub fn listen_signals() -> Result<()> {
+    for &signal in [libc::SIGTERM, libc::SIGINT].iter() {
+        // Only storing to the atomic is async-signal-safe here, so the handler does not log.
+        unsafe {
+            signal_hook_registry::register(signal, || REQUESTED.store(true, Ordering::SeqCst))
+        }?;
+    }
+    Ok(())
+}
+
+pub fn request() {
+    if !REQUESTED.swap(true, Ordering::SeqCst) {
+        log::info!("Shutdown requested");
+    }
+}
+
+pub fn is_requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Sleeps for `duration` unless the shutdown is requested, and returns whether it is requested.
+pub async fn sleep(duration: Duration) -> bool {
+    let poll_interval = Duration::from_millis(POLL_INTERVAL_MILLIS);
+    let mut slept = Duration::from_secs(0);
+    while !is_requested() && slept < duration {
+        let interval = std::cmp::min(poll_interval, duration - slept);
+        async_std::task::sleep(interval).await;
+        slept += interval;
+    }
+    is_requested()
+}
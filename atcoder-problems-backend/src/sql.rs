@@ -2,35 +2,170 @@ pub mod models;
 pub mod schema;
 
 mod accepted_count;
+mod achievement;
+mod active_users;
+mod advisory_lock_client;
+mod contest_completion;
 mod contest_problem;
+mod contest_stats;
+pub(crate) mod difficulty_count;
+mod dump_client;
+mod excluded_contest_client;
+mod feature_flag_client;
+mod holder_ranking;
+mod job_run_client;
+mod judge_client;
 mod language_count;
+mod language_trend;
+mod maintenance_client;
+mod marathon_score;
+mod merged_problem_client;
+mod peer_stats;
+mod problem_detail_client;
 mod problem_info;
+mod problem_model;
+mod problem_search_client;
 mod problems_submissions;
+mod rank_history;
 mod rated_point_sum;
+mod request_count_client;
+mod shadow_table_client;
+mod similar_problem;
 mod simple_client;
-mod streak;
+mod site_stats;
+mod standings_client;
+pub(crate) mod streak;
+mod submission_archive_client;
 mod submission_client;
+mod submission_code_client;
+mod table_version_client;
+mod validation_client;
+mod watermark_client;
 
 pub mod internal;
 
 const FIRST_AGC_EPOCH_SECOND: i64 = 1_468_670_400;
 const UNRATED_STATE: &str = "-";
-const MAX_INSERT_ROWS: usize = 10_000;
+/// The maximum number of the bind parameters of a statement in PostgreSQL.
+const MAX_BIND_PARAMETERS: usize = 65_535;
+pub const DEFAULT_INSERT_CHUNK_SIZE: usize = 10_000;
+
+static INSERT_CHUNK_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_INSERT_CHUNK_SIZE);
 
 pub use accepted_count::AcceptedCountClient;
+pub use achievement::{Achievement, AchievementClient};
+pub use active_users::{ActiveUserClient, ACTIVE_USER_PERIOD_SECOND};
+pub use advisory_lock_client::AdvisoryLockClient;
+pub use contest_completion::ContestCompletionClient;
 pub use contest_problem::ContestProblemClient;
+pub use contest_stats::ContestStatsClient;
+pub use difficulty_count::DifficultyCountClient;
+pub use dump_client::DumpClient;
+pub use excluded_contest_client::ExcludedContestClient;
+pub use feature_flag_client::FeatureFlagClient;
+pub use holder_ranking::{HolderKind, HolderRankingClient};
+pub use job_run_client::{JobRunClient, JobStatus};
+pub use judge_client::{Judge, JudgeClient};
 pub use language_count::LanguageCountClient;
+pub use language_trend::LanguageTrendClient;
+pub use maintenance_client::MaintenanceClient;
+pub use marathon_score::MarathonScoreClient;
+pub use merged_problem_client::MergedProblemClient;
+pub use peer_stats::{PeerStats, PeerStatsClient};
+pub use problem_detail_client::ProblemDetailClient;
 pub use problem_info::ProblemInfoUpdater;
+pub use problem_model::ProblemModelClient;
+pub use problem_search_client::{ProblemSearchClient, ProblemSearchRequest};
 pub use problems_submissions::ProblemsSubmissionUpdater;
+pub use rank_history::RankHistoryClient;
 pub use rated_point_sum::RatedPointSumClient;
+pub use request_count_client::RequestCountClient;
+pub use shadow_table_client::ShadowTableClient;
+pub use similar_problem::SimilarProblemClient;
 pub use simple_client::SimpleClient;
+pub use site_stats::SiteStatsClient;
+pub use standings_client::StandingsClient;
 pub use streak::StreakUpdater;
-pub use submission_client::{SubmissionClient, SubmissionRequest};
+pub use submission_archive_client::SubmissionArchiveClient;
+pub use submission_client::{AsyncSubmissionClient, SubmissionClient, SubmissionRequest};
+pub use submission_code_client::SubmissionCodeClient;
+pub use table_version_client::TableVersionClient;
+pub use validation_client::ValidationClient;
+pub use watermark_client::WatermarkClient;
 
 use crate::error::Result;
+use crate::sql::models::Verdict;
+use crate::sql::schema::result_codes;
+use crate::utils::SplitToSegments;
+use diesel::dsl;
+use diesel::prelude::*;
 use diesel::{Connection, PgConnection};
+use http_types::StatusCode;
+use std::cmp;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub fn connect(url: &str) -> Result<PgConnection> {
     let connection = PgConnection::establish(url)?;
     Ok(connection)
 }
+
+/// Sets the maximum number of the rows which a statement of a bulk insertion inserts.
+pub fn set_insert_chunk_size(size: usize) {
+    INSERT_CHUNK_SIZE.store(size, Ordering::SeqCst);
+}
+
+/// Splits the rows of a bulk insertion into the chunks, each of which is inserted by a statement.
+/// A chunk has at most the chunk size of rows, and binds at most `MAX_BIND_PARAMETERS`
+/// parameters, where each row binds `columns` parameters.
+fn insert_chunks<T>(rows: &[T], columns: usize) -> Vec<&[T]> {
+    let size = cmp::min(
+        INSERT_CHUNK_SIZE.load(Ordering::SeqCst),
+        MAX_BIND_PARAMETERS / columns,
+    );
+    rows.split_into_segments(cmp::max(size, 1))
+}
+
+/// The subquery of the raw results of the accepted submissions in `result_codes`, with which the
+/// queries filter the accepted submissions.
+fn accepted_results() -> dsl::Select<
+    dsl::Filter<result_codes::table, dsl::Eq<result_codes::verdict, &'static str>>,
+    result_codes::raw_result,
+> {
+    result_codes::table
+        .filter(result_codes::verdict.eq(Verdict::Accepted.name()))
+        .select(result_codes::raw_result)
+}
+
+/// Checks the table name which is formatted into a query, since it cannot be bound as a parameter.
+fn validate_table_name(table: &str) -> Result<()> {
+    if !table.is_empty()
+        && table
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(http_types::Error::from_str(
+            StatusCode::BadRequest,
+            format!("`{}` is not a table name.", table),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_chunks() {
+        let rows = (0..25_000).collect::<Vec<usize>>();
+        let chunks = insert_chunks(&rows, 2);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].len(), DEFAULT_INSERT_CHUNK_SIZE);
+
+        let chunks = insert_chunks(&rows, 10);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[0].len(), 6_553);
+        assert!(insert_chunks(&[] as &[usize], 10).is_empty());
+    }
+}
@@ -1,8 +1,7 @@
-use super::MAX_INSERT_ROWS;
+use super::insert_chunks;
 use crate::error::Result;
 use crate::sql::models::{Submission, UserProblemCount};
-use crate::sql::schema::accepted_count;
-use crate::utils::SplitToSegments;
+use crate::sql::schema::{accepted_count, internal_banned_users};
 use diesel::dsl::*;
 use diesel::pg::upsert::excluded;
 use diesel::prelude::*;
@@ -12,6 +11,9 @@ use std::collections::{BTreeMap, BTreeSet};
 pub trait AcceptedCountClient {
     fn load_accepted_count(&self) -> Result<Vec<UserProblemCount>>;
     fn get_users_accepted_count(&self, user_id: &str) -> Option<i32>;
+
+    /// Returns the number of the users who have solved more problems than `accepted_count`. The
+    /// banned users are not counted.
     fn get_accepted_count_rank(&self, accepted_count: i32) -> Result<i64>;
     fn update_accepted_count(&self, submissions: &[Submission]) -> Result<()>;
 }
@@ -37,6 +39,9 @@ impl AcceptedCountClient for PgConnection {
     fn get_accepted_count_rank(&self, accepted_count: i32) -> Result<i64> {
         let rank = accepted_count::table
             .filter(accepted_count::problem_count.gt(accepted_count))
+            .filter(not(accepted_count::user_id.eq_any(
+                internal_banned_users::table.select(internal_banned_users::user_id),
+            )))
             .select(count_star())
             .first::<i64>(self)?;
         Ok(rank)
@@ -61,10 +66,7 @@ impl AcceptedCountClient for PgConnection {
             })
             .collect::<Vec<_>>();
 
-        for segment in accepted_count
-            .split_into_segments(MAX_INSERT_ROWS)
-            .into_iter()
-        {
+        for segment in insert_chunks(&accepted_count, 2).into_iter() {
             insert_into(accepted_count::table)
                 .values(segment)
                 .on_conflict(accepted_count::user_id)
@@ -0,0 +1,101 @@
+use super::models::UserAchievement;
+use super::schema::achievements;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+use diesel::{sql_query, PgConnection};
+
+/// The badges which the users earn by the rules evaluated on the aggregate tables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievement {
+    /// Solved new problems on 100 consecutive days.
+    Streak100,
+    /// Solved all the problems A to C of the ABCs.
+    AbcAToC,
+    /// Held the shortest submissions of 100 problems.
+    Shortest100,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 3] = [
+        Achievement::Streak100,
+        Achievement::AbcAToC,
+        Achievement::Shortest100,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::Streak100 => "streak_100",
+            Achievement::AbcAToC => "abc_a_to_c",
+            Achievement::Shortest100 => "shortest_100",
+        }
+    }
+
+    /// The query of the ids of the users who satisfy the rule at the moment.
+    fn user_ids_query(self) -> &'static str {
+        match self {
+            Achievement::Streak100 => "SELECT user_id FROM max_streaks WHERE streak >= 100",
+            Achievement::AbcAToC => {
+                r"
+                WITH targets AS (
+                    SELECT DISTINCT contest_problem.problem_id
+                    FROM contest_problem
+                    INNER JOIN problems ON problems.id = contest_problem.problem_id
+                    WHERE contest_problem.contest_id ~ '^abc[0-9]{3}$'
+                    AND LEFT(problems.title, 2) IN ('A.', 'B.', 'C.')
+                )
+                SELECT submissions.user_id
+                FROM submissions
+                INNER JOIN targets ON targets.problem_id = submissions.problem_id
+                WHERE submissions.result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                GROUP BY submissions.user_id
+                HAVING COUNT(DISTINCT submissions.problem_id) = (SELECT COUNT(*) FROM targets)"
+            }
+            Achievement::Shortest100 => {
+                r"
+                SELECT submissions.user_id
+                FROM shortest
+                INNER JOIN submissions ON submissions.id = shortest.submission_id
+                GROUP BY submissions.user_id
+                HAVING COUNT(*) >= 100"
+            }
+        }
+    }
+}
+
+pub trait AchievementClient {
+    /// Gives the achievements to the users who newly satisfy their rules at `now`. The earned
+    /// achievements are kept with the time of the first evaluation which gave them.
+    fn update_achievements(&self, now: i64) -> Result<()>;
+
+    /// Returns the achievements of the user in the order of the earned time.
+    fn load_user_achievements(&self, user_id: &str) -> Result<Vec<UserAchievement>>;
+}
+
+impl AchievementClient for PgConnection {
+    fn update_achievements(&self, now: i64) -> Result<()> {
+        for achievement in Achievement::ALL.iter() {
+            let query = format!(
+                r"
+                INSERT INTO achievements (user_id, achievement, epoch_second)
+                SELECT user_id, $1, $2 FROM ({user_ids}) AS earned
+                ON CONFLICT (user_id, achievement) DO NOTHING",
+                user_ids = achievement.user_ids_query()
+            );
+            sql_query(query)
+                .bind::<Text, _>(achievement.name())
+                .bind::<BigInt, _>(now)
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_user_achievements(&self, user_id: &str) -> Result<Vec<UserAchievement>> {
+        let earned = achievements::table
+            .filter(achievements::user_id.eq(user_id))
+            .order_by((achievements::epoch_second, achievements::achievement))
+            .load::<UserAchievement>(self)?;
+        Ok(earned)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::error::Result;
+
+use diesel::connection::SimpleConnection;
+use diesel::PgConnection;
+
+/// The users who have submitted in this period are active, e.g. in the rankings of the active
+/// users.
+pub const ACTIVE_USER_PERIOD_SECOND: i64 = 90 * 24 * 3600;
+
+pub trait ActiveUserClient {
+    /// Updates the times of the latest submissions of the users who have submitted since the
+    /// latest one already aggregated, so that only the recent submissions are read.
+    fn update_active_users(&self) -> Result<()>;
+}
+
+impl ActiveUserClient for PgConnection {
+    fn update_active_users(&self) -> Result<()> {
+        self.batch_execute(
+            r"
+                INSERT INTO active_users (user_id, last_submission_epoch_second)
+                    SELECT user_id, MAX(epoch_second)
+                    FROM submissions
+                    WHERE epoch_second >= (
+                        SELECT COALESCE(MAX(last_submission_epoch_second), 0) FROM active_users
+                    )
+                    GROUP BY user_id
+                ON CONFLICT (user_id) DO UPDATE
+                SET last_submission_epoch_second = GREATEST(
+                    active_users.last_submission_epoch_second,
+                    EXCLUDED.last_submission_epoch_second
+                );
+            ",
+        )?;
+        Ok(())
+    }
+}
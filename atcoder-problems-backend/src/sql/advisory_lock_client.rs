@@ -0,0 +1,64 @@
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Integer, Text};
+use diesel::PgConnection;
+
+/// The first key of the advisory locks of this application, which keeps them apart from the
+/// locks of the other applications sharing the database.
+const LOCK_NAMESPACE: i32 = 1_801;
+
+sql_function!(fn hashtext(name: Text) -> Integer);
+sql_function!(fn pg_try_advisory_lock(namespace: Integer, key: Integer) -> Bool);
+sql_function!(fn pg_advisory_unlock(namespace: Integer, key: Integer) -> Bool);
+
+/// Runs the closures holding the session level advisory locks of the names, so that the processes
+/// sharing the database do not run them at the same time. The locks are released when the
+/// closures return, or when the connections are closed.
+pub trait AdvisoryLockClient {
+    /// Waits until the lock of the name is released by the others, and runs `f` holding it.
+    fn with_advisory_lock<T, F>(&self, name: &str, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>;
+
+    /// Runs `f` holding the lock of the name, or returns `None` without running it if the lock
+    /// is held by another connection.
+    fn try_with_advisory_lock<T, F>(&self, name: &str, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce() -> Result<T>;
+}
+
+impl AdvisoryLockClient for PgConnection {
+    fn with_advisory_lock<T, F>(&self, name: &str, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        // `pg_advisory_lock` returns `void`, which can not be selected with the query builder.
+        diesel::sql_query("SELECT pg_advisory_lock($1, hashtext($2))")
+            .bind::<Integer, _>(LOCK_NAMESPACE)
+            .bind::<Text, _>(name)
+            .execute(self)?;
+        let result = f();
+        unlock(self, name)?;
+        result
+    }
+
+    fn try_with_advisory_lock<T, F>(&self, name: &str, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let locked = diesel::select(pg_try_advisory_lock(LOCK_NAMESPACE, hashtext(name)))
+            .get_result::<bool>(self)?;
+        if !locked {
+            return Ok(None);
+        }
+        let result = f();
+        unlock(self, name)?;
+        result.map(Some)
+    }
+}
+
+fn unlock(conn: &PgConnection, name: &str) -> Result<()> {
+    diesel::select(pg_advisory_unlock(LOCK_NAMESPACE, hashtext(name))).execute(conn)?;
+    Ok(())
+}
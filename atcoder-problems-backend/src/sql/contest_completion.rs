@@ -0,0 +1,102 @@
+use super::insert_chunks;
+use super::models::{Submission, UserContestCompletion};
+use super::schema::{contest_completion, contest_problem};
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub trait ContestCompletionClient {
+    /// Counts the problems of each contest solved by each user. A problem shared by some contests
+    /// is counted in each of them.
+    fn update_contest_completion(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Returns the counts of the user in the order of the contest ids. Only the fully solved
+    /// contests are returned if `completed_only` is set.
+    fn load_users_contest_completion(
+        &self,
+        user_id: &str,
+        completed_only: bool,
+    ) -> Result<Vec<UserContestCompletion>>;
+}
+
+impl ContestCompletionClient for PgConnection {
+    fn update_contest_completion(&self, submissions: &[Submission]) -> Result<()> {
+        let contest_problems = contest_problem::table
+            .select((contest_problem::contest_id, contest_problem::problem_id))
+            .load::<(String, String)>(self)?;
+        let mut problem_count = BTreeMap::new();
+        let mut contests_of_problem = BTreeMap::new();
+        for (contest_id, problem_id) in contest_problems.iter() {
+            *problem_count.entry(contest_id.as_str()).or_insert(0) += 1;
+            contests_of_problem
+                .entry(problem_id.as_str())
+                .or_insert_with(Vec::new)
+                .push(contest_id.as_str());
+        }
+
+        let contest_completion = submissions
+            .iter()
+            .flat_map(|s| {
+                let contests = contests_of_problem
+                    .get(s.problem_id.as_str())
+                    .map(|contests| contests.as_slice())
+                    .unwrap_or(&[]);
+                contests
+                    .iter()
+                    .map(move |contest_id| (s.user_id.as_str(), *contest_id, s.problem_id.as_str()))
+            })
+            .fold(
+                BTreeMap::new(),
+                |mut map, (user_id, contest_id, problem_id)| {
+                    map.entry((user_id, contest_id))
+                        .or_insert_with(BTreeSet::new)
+                        .insert(problem_id);
+                    map
+                },
+            )
+            .into_iter()
+            .map(|((user_id, contest_id), set)| {
+                (
+                    contest_completion::user_id.eq(user_id),
+                    contest_completion::contest_id.eq(contest_id),
+                    contest_completion::solved_count.eq(set.len() as i32),
+                    contest_completion::problem_count.eq(problem_count[contest_id]),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for segment in insert_chunks(&contest_completion, 4).into_iter() {
+            insert_into(contest_completion::table)
+                .values(segment)
+                .on_conflict((contest_completion::user_id, contest_completion::contest_id))
+                .do_update()
+                .set((
+                    contest_completion::solved_count.eq(excluded(contest_completion::solved_count)),
+                    contest_completion::problem_count
+                        .eq(excluded(contest_completion::problem_count)),
+                ))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_users_contest_completion(
+        &self,
+        user_id: &str,
+        completed_only: bool,
+    ) -> Result<Vec<UserContestCompletion>> {
+        let mut query = contest_completion::table
+            .filter(contest_completion::user_id.eq(user_id))
+            .order_by(contest_completion::contest_id)
+            .into_boxed();
+        if completed_only {
+            query = query
+                .filter(contest_completion::solved_count.eq(contest_completion::problem_count));
+        }
+        let completion = query.load::<UserContestCompletion>(self)?;
+        Ok(completion)
+    }
+}
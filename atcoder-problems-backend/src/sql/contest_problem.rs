@@ -1,3 +1,4 @@
+use super::insert_chunks;
 use crate::error::Result;
 use crate::sql::models::ContestProblem;
 use crate::sql::schema::contest_problem;
@@ -13,12 +14,15 @@ pub trait ContestProblemClient {
 
 impl ContestProblemClient for PgConnection {
     fn insert_contest_problem(&self, contest_problems: &[ContestProblem]) -> Result<usize> {
-        let result = insert_into(contest_problem::table)
-            .values(contest_problems)
-            .on_conflict((contest_problem::contest_id, contest_problem::problem_id))
-            .do_nothing()
-            .execute(self)?;
-        Ok(result)
+        let mut count = 0;
+        for segment in insert_chunks(contest_problems, 2).into_iter() {
+            count += insert_into(contest_problem::table)
+                .values(segment)
+                .on_conflict((contest_problem::contest_id, contest_problem::problem_id))
+                .do_nothing()
+                .execute(self)?;
+        }
+        Ok(count)
     }
 
     fn load_contest_problem(&self) -> Result<Vec<ContestProblem>> {
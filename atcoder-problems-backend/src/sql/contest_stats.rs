@@ -0,0 +1,100 @@
+use super::models::{ContestProblemStats, ContestStats};
+use super::schema::{contest_problem_stats, contest_stats};
+use crate::error::Result;
+
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+pub trait ContestStatsClient {
+    /// Aggregates the submissions during the contests: the numbers of the submitters and the
+    /// users who solved all the problems of each contest, and the numbers of the submitters and
+    /// the users who solved each problem.
+    fn update_contest_stats(&self) -> Result<()>;
+
+    /// Returns the statistics of the contest and its problems, or `None` if no one submitted
+    /// during the contest.
+    fn load_contest_stats(
+        &self,
+        contest_id: &str,
+    ) -> Result<Option<(ContestStats, Vec<ContestProblemStats>)>>;
+}
+
+impl ContestStatsClient for PgConnection {
+    fn update_contest_stats(&self) -> Result<()> {
+        self.batch_execute(
+            r"
+                CREATE TEMPORARY TABLE in_contest_submissions AS
+                    SELECT submissions.contest_id, submissions.problem_id, submissions.user_id,
+                        submissions.result
+                    FROM submissions
+                    INNER JOIN contests ON contests.id = submissions.contest_id
+                    WHERE submissions.epoch_second >= contests.start_epoch_second
+                    AND submissions.epoch_second
+                        < contests.start_epoch_second + contests.duration_second;
+
+                INSERT INTO contest_stats (contest_id, participant_count, full_solve_count)
+                    SELECT participants.contest_id, participants.user_count,
+                        COALESCE(full_solvers.user_count, 0)
+                    FROM (
+                        SELECT contest_id, COUNT(DISTINCT user_id) AS user_count
+                        FROM in_contest_submissions
+                        GROUP BY contest_id
+                    ) AS participants
+                    LEFT JOIN (
+                        SELECT solved.contest_id, COUNT(*) AS user_count
+                        FROM (
+                            SELECT contest_id, user_id, COUNT(DISTINCT problem_id) AS problem_count
+                            FROM in_contest_submissions
+                            WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                            GROUP BY contest_id, user_id
+                        ) AS solved
+                        INNER JOIN (
+                            SELECT contest_id, COUNT(*) AS problem_count
+                            FROM contest_problem
+                            GROUP BY contest_id
+                        ) AS problems ON problems.contest_id = solved.contest_id
+                        WHERE solved.problem_count >= problems.problem_count
+                        GROUP BY solved.contest_id
+                    ) AS full_solvers ON full_solvers.contest_id = participants.contest_id
+                ON CONFLICT (contest_id) DO UPDATE
+                SET participant_count = EXCLUDED.participant_count,
+                    full_solve_count = EXCLUDED.full_solve_count;
+
+                INSERT INTO contest_problem_stats
+                    (contest_id, problem_id, submitter_count, ac_user_count)
+                    SELECT contest_id, problem_id, COUNT(DISTINCT user_id),
+                        COUNT(DISTINCT user_id) FILTER (WHERE result IN (
+                            SELECT raw_result FROM result_codes WHERE verdict = 'accepted'
+                        ))
+                    FROM in_contest_submissions
+                    GROUP BY contest_id, problem_id
+                ON CONFLICT (contest_id, problem_id) DO UPDATE
+                SET submitter_count = EXCLUDED.submitter_count,
+                    ac_user_count = EXCLUDED.ac_user_count;
+
+                DROP TABLE in_contest_submissions;
+            ",
+        )?;
+        Ok(())
+    }
+
+    fn load_contest_stats(
+        &self,
+        contest_id: &str,
+    ) -> Result<Option<(ContestStats, Vec<ContestProblemStats>)>> {
+        let stats = match contest_stats::table
+            .find(contest_id)
+            .first::<ContestStats>(self)
+            .optional()?
+        {
+            Some(stats) => stats,
+            None => return Ok(None),
+        };
+        let problems = contest_problem_stats::table
+            .filter(contest_problem_stats::contest_id.eq(contest_id))
+            .order_by(contest_problem_stats::problem_id)
+            .load::<ContestProblemStats>(self)?;
+        Ok(Some((stats, problems)))
+    }
+}
@@ -0,0 +1,100 @@
+use super::insert_chunks;
+use super::models::{Submission, UserDifficultyCount};
+use super::schema::{difficulty_count, problem_models};
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The width of a difficulty bucket, which is the same as the width of a rating color.
+pub(crate) const DIFFICULTY_BUCKET_WIDTH: i64 = 400;
+
+pub trait DifficultyCountClient {
+    /// Counts the problems solved by each user in each difficulty bucket. The problems without
+    /// the difficulty are not counted.
+    fn update_difficulty_count(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Returns the counts of the user in the ascending order of the buckets.
+    fn load_users_difficulty_count(&self, user_id: &str) -> Result<Vec<UserDifficultyCount>>;
+}
+
+impl DifficultyCountClient for PgConnection {
+    fn update_difficulty_count(&self, submissions: &[Submission]) -> Result<()> {
+        let difficulties = problem_models::table
+            .filter(problem_models::difficulty.is_not_null())
+            .select((problem_models::problem_id, problem_models::difficulty))
+            .load::<(String, Option<f64>)>(self)?
+            .into_iter()
+            .filter_map(|(problem_id, difficulty)| Some((problem_id, difficulty?)))
+            .collect::<BTreeMap<_, _>>();
+
+        let difficulty_count = submissions
+            .iter()
+            .filter_map(|s| {
+                let difficulty = difficulties.get(&s.problem_id)?;
+                Some((
+                    s.user_id.as_str(),
+                    difficulty_bucket(*difficulty),
+                    s.problem_id.as_str(),
+                ))
+            })
+            .fold(
+                BTreeMap::new(),
+                |mut map, (user_id, difficulty_from, problem_id)| {
+                    map.entry((user_id, difficulty_from))
+                        .or_insert_with(BTreeSet::new)
+                        .insert(problem_id);
+                    map
+                },
+            )
+            .into_iter()
+            .map(|((user_id, difficulty_from), set)| {
+                (
+                    difficulty_count::user_id.eq(user_id),
+                    difficulty_count::difficulty_from.eq(difficulty_from),
+                    difficulty_count::problem_count.eq(set.len() as i32),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for segment in insert_chunks(&difficulty_count, 3).into_iter() {
+            insert_into(difficulty_count::table)
+                .values(segment)
+                .on_conflict((difficulty_count::user_id, difficulty_count::difficulty_from))
+                .do_update()
+                .set(difficulty_count::problem_count.eq(excluded(difficulty_count::problem_count)))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_users_difficulty_count(&self, user_id: &str) -> Result<Vec<UserDifficultyCount>> {
+        let count = difficulty_count::table
+            .filter(difficulty_count::user_id.eq(user_id))
+            .order_by(difficulty_count::difficulty_from)
+            .load::<UserDifficultyCount>(self)?;
+        Ok(count)
+    }
+}
+
+/// Returns the lower bound of the bucket of `difficulty`. The negative difficulties are in the
+/// lowest bucket from 0.
+pub(crate) fn difficulty_bucket(difficulty: f64) -> i64 {
+    let difficulty = difficulty.max(0.0) as i64;
+    difficulty / DIFFICULTY_BUCKET_WIDTH * DIFFICULTY_BUCKET_WIDTH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_bucket() {
+        assert_eq!(difficulty_bucket(-1.0), 0);
+        assert_eq!(difficulty_bucket(399.9), 0);
+        assert_eq!(difficulty_bucket(400.0), 400);
+        assert_eq!(difficulty_bucket(2799.0), 2400);
+    }
+}
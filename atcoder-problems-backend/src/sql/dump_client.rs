@@ -0,0 +1,34 @@
+use super::models::Dump;
+use super::schema::dumps;
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+pub trait DumpClient {
+    fn update_dump(&self, dump: &Dump) -> Result<()>;
+    fn load_dumps(&self) -> Result<Vec<Dump>>;
+}
+
+impl DumpClient for PgConnection {
+    fn update_dump(&self, dump: &Dump) -> Result<()> {
+        insert_into(dumps::table)
+            .values(dump)
+            .on_conflict(dumps::name)
+            .do_update()
+            .set((
+                dumps::url.eq(excluded(dumps::url)),
+                dumps::size.eq(excluded(dumps::size)),
+                dumps::sha256.eq(excluded(dumps::sha256)),
+                dumps::generated_epoch_second.eq(excluded(dumps::generated_epoch_second)),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_dumps(&self) -> Result<Vec<Dump>> {
+        let dumps = dumps::table.order_by(dumps::name).load::<Dump>(self)?;
+        Ok(dumps)
+    }
+}
@@ -0,0 +1,44 @@
+use super::models::ExcludedContest;
+use super::schema::excluded_contests;
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+
+/// The contests excluded from the aggregates of the points, the difficulties and the first, fastest
+/// and shortest submissions, e.g. April Fools contests and experimental rounds.
+pub trait ExcludedContestClient {
+    fn exclude_contest(&self, contest_id: &str, reason: &str, now: i64) -> Result<()>;
+    fn include_contest(&self, contest_id: &str) -> Result<()>;
+    fn load_excluded_contests(&self) -> Result<Vec<ExcludedContest>>;
+}
+
+impl ExcludedContestClient for PgConnection {
+    fn exclude_contest(&self, contest_id: &str, reason: &str, now: i64) -> Result<()> {
+        insert_into(excluded_contests::table)
+            .values(ExcludedContest {
+                contest_id: contest_id.to_owned(),
+                reason: reason.to_owned(),
+                created_epoch_second: now,
+            })
+            .on_conflict(excluded_contests::contest_id)
+            .do_update()
+            .set(excluded_contests::reason.eq(excluded(excluded_contests::reason)))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn include_contest(&self, contest_id: &str) -> Result<()> {
+        delete(excluded_contests::table.filter(excluded_contests::contest_id.eq(contest_id)))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_excluded_contests(&self) -> Result<Vec<ExcludedContest>> {
+        let contests = excluded_contests::table
+            .order_by(excluded_contests::contest_id)
+            .load::<ExcludedContest>(self)?;
+        Ok(contests)
+    }
+}
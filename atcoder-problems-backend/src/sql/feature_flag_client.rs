@@ -0,0 +1,39 @@
+use super::models::FeatureFlag;
+use super::schema::feature_flags;
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+pub trait FeatureFlagClient {
+    fn set_feature_flag(&self, name: &str, enabled: bool, now: i64) -> Result<()>;
+    fn load_feature_flags(&self) -> Result<Vec<FeatureFlag>>;
+}
+
+impl FeatureFlagClient for PgConnection {
+    fn set_feature_flag(&self, name: &str, enabled: bool, now: i64) -> Result<()> {
+        insert_into(feature_flags::table)
+            .values(FeatureFlag {
+                name: name.to_owned(),
+                enabled,
+                updated_epoch_second: now,
+            })
+            .on_conflict(feature_flags::name)
+            .do_update()
+            .set((
+                feature_flags::enabled.eq(excluded(feature_flags::enabled)),
+                feature_flags::updated_epoch_second
+                    .eq(excluded(feature_flags::updated_epoch_second)),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_feature_flags(&self) -> Result<Vec<FeatureFlag>> {
+        let flags = feature_flags::table
+            .order_by(feature_flags::name)
+            .load::<FeatureFlag>(self)?;
+        Ok(flags)
+    }
+}
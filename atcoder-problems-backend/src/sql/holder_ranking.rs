@@ -0,0 +1,129 @@
+use super::models::RankedUserCount;
+use crate::error::Result;
+
+use diesel::sql_types::{BigInt, Nullable, Text};
+use diesel::{sql_query, OptionalExtension, PgConnection, RunQueryDsl};
+
+/// The kinds of the submissions held by the users for each problem.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HolderKind {
+    First,
+    /// The first AC submissions after the contests.
+    FirstPractice,
+    Fastest,
+    Shortest,
+}
+
+impl HolderKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "first" => Some(HolderKind::First),
+            "first_practice" => Some(HolderKind::FirstPractice),
+            "fastest" => Some(HolderKind::Fastest),
+            "shortest" => Some(HolderKind::Shortest),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn table(self) -> &'static str {
+        match self {
+            HolderKind::First => "first",
+            HolderKind::FirstPractice => "first_practice",
+            HolderKind::Fastest => "fastest",
+            HolderKind::Shortest => "shortest",
+        }
+    }
+}
+
+/// Generates the query of the users ranked by the numbers of the problems of which they hold the
+/// submissions in `table`. The users of the same count have the same rank. The banned users are
+/// not ranked, and only the users who have submitted since the parameter `active_since` are
+/// ranked if it is not `NULL`.
+fn generate_ranked_counts_query(table: &str, active_since: &str) -> String {
+    format!(
+        r"
+        WITH ranked AS (
+            SELECT user_id, count, DENSE_RANK() OVER (ORDER BY count DESC) - 1 AS rank
+            FROM (
+                SELECT submissions.user_id, COUNT(*) AS count
+                FROM {table}
+                INNER JOIN submissions ON submissions.id = {table}.submission_id
+                WHERE submissions.user_id NOT IN (SELECT user_id FROM internal_banned_users)
+                AND ({active_since}::BIGINT IS NULL OR submissions.user_id IN (
+                    SELECT user_id FROM active_users
+                    WHERE last_submission_epoch_second >= {active_since}
+                ))
+                GROUP BY submissions.user_id
+            ) AS counts
+        )",
+        table = table,
+        active_since = active_since
+    )
+}
+
+pub trait HolderRankingClient {
+    /// Returns the users from the `from`-th to the `to`-th (exclusive) in the order of the
+    /// numbers of the problems of which they hold the submissions of `kind`. The banned users are
+    /// not ranked, and only the users who have submitted since `active_since` are ranked if it is
+    /// given.
+    fn load_holder_ranking(
+        &self,
+        kind: HolderKind,
+        from: i64,
+        to: i64,
+        active_since: Option<i64>,
+    ) -> Result<Vec<RankedUserCount>>;
+
+    /// Returns the rank of the user in the ranking of `kind`, or `None` if the user holds no
+    /// submission of `kind`.
+    fn get_users_holder_rank(
+        &self,
+        kind: HolderKind,
+        user_id: &str,
+        active_since: Option<i64>,
+    ) -> Result<Option<RankedUserCount>>;
+}
+
+impl HolderRankingClient for PgConnection {
+    fn load_holder_ranking(
+        &self,
+        kind: HolderKind,
+        from: i64,
+        to: i64,
+        active_since: Option<i64>,
+    ) -> Result<Vec<RankedUserCount>> {
+        let query = format!(
+            r"{ranked}
+            SELECT user_id, count, rank FROM ranked
+            ORDER BY count DESC, user_id
+            OFFSET $1 LIMIT $2",
+            ranked = generate_ranked_counts_query(kind.table(), "$3")
+        );
+        let ranking = sql_query(query)
+            .bind::<BigInt, _>(from)
+            .bind::<BigInt, _>(to - from)
+            .bind::<Nullable<BigInt>, _>(active_since)
+            .load::<RankedUserCount>(self)?;
+        Ok(ranking)
+    }
+
+    fn get_users_holder_rank(
+        &self,
+        kind: HolderKind,
+        user_id: &str,
+        active_since: Option<i64>,
+    ) -> Result<Option<RankedUserCount>> {
+        let query = format!(
+            r"{ranked}
+            SELECT user_id, count, rank FROM ranked
+            WHERE user_id = $1",
+            ranked = generate_ranked_counts_query(kind.table(), "$2")
+        );
+        let rank = sql_query(query)
+            .bind::<Text, _>(user_id)
+            .bind::<Nullable<BigInt>, _>(active_since)
+            .get_result::<RankedUserCount>(self)
+            .optional()?;
+        Ok(rank)
+    }
+}
@@ -1,4 +1,24 @@
+pub(crate) mod api_token_manager;
+pub(crate) mod api_usage_manager;
+pub(crate) mod audit_log_manager;
+pub mod classroom_manager;
+pub(crate) mod idempotency_key_manager;
+pub mod moderation_manager;
+pub mod notification_manager;
 pub(crate) mod problem_list_manager;
+pub(crate) mod problem_note_manager;
 pub(crate) mod progress_reset_manager;
+pub mod user_group_manager;
 pub(crate) mod user_manager;
+pub mod user_verification_manager;
+pub(crate) mod virtual_contest_announcement_manager;
 pub mod virtual_contest_manager;
+pub mod virtual_contest_result_manager;
+pub(crate) mod virtual_contest_team_manager;
+pub mod virtual_contest_template_manager;
+pub(crate) mod virtual_contest_training_manager;
+pub mod watch_list_manager;
+
+/// The deleted problem lists and virtual contests can be restored within this period, and are
+/// purged afterwards.
+pub const DELETED_RETENTION_SECOND: i64 = 30 * 24 * 3600;
@@ -0,0 +1,230 @@
+use crate::error::Result;
+use crate::sql::schema::internal_api_tokens as t_table;
+
+use crate::error::Error::Validation;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection, Queryable};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const MAX_NAMED_TOKEN_NUM: i64 = 20;
+
+/// Only the hashes of the tokens are stored, so that leaked rows can not be used as tokens.
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Scopes limit what a named token can write. Any token can read, and the tokens issued on login
+/// have no scope limitation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Scope {
+    ReadOnly,
+    ContestAdmin,
+    ListWrite,
+}
+
+impl Scope {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Scope::ReadOnly => "read-only",
+            Scope::ContestAdmin => "contest-admin",
+            Scope::ListWrite => "list-write",
+        }
+    }
+
+    pub(crate) fn parse(scope: &str) -> Option<Self> {
+        match scope {
+            "read-only" => Some(Scope::ReadOnly),
+            "contest-admin" => Some(Scope::ContestAdmin),
+            "list-write" => Some(Scope::ListWrite),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Queryable)]
+pub(crate) struct ApiTokenInfo {
+    id: String,
+    name: String,
+    /// `None` for the tokens issued on login.
+    scopes: Option<Vec<String>>,
+    created_epoch_second: i64,
+}
+
+pub(crate) struct TokenRecord {
+    pub(crate) internal_user_id: String,
+    scopes: Option<Vec<String>>,
+}
+
+impl TokenRecord {
+    /// Returns whether the token can perform writes which require `scope`, or writes which are
+    /// allowed to only the tokens issued on login if `scope` is `None`.
+    pub(crate) fn allows(&self, scope: Option<Scope>) -> bool {
+        match (&self.scopes, scope) {
+            (None, _) => true,
+            (Some(scopes), Some(scope)) => scopes.iter().any(|s| s == scope.as_str()),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+pub(crate) trait ApiTokenManager {
+    /// Issues a new API token of the internal user, and returns the raw token.
+    fn issue_token(&self, internal_user_id: &str, now: i64) -> Result<String>;
+
+    /// Issues a new API token limited to the scopes, and returns the id and the raw token.
+    fn create_named_token(
+        &self,
+        internal_user_id: &str,
+        name: &str,
+        scopes: &[Scope],
+        now: i64,
+    ) -> Result<(String, String)>;
+    fn get_tokens(&self, internal_user_id: &str) -> Result<Vec<ApiTokenInfo>>;
+    fn revoke_token(&self, internal_user_id: &str, token_id: &str) -> Result<()>;
+
+    /// Returns the record of the token, or `None` if the token is unknown.
+    fn find_token(&self, token: &str) -> Result<Option<TokenRecord>>;
+
+    /// Returns the internal user id of the token, or `None` if the token is unknown.
+    fn find_token_user(&self, token: &str) -> Result<Option<String>> {
+        let record = self.find_token(token)?;
+        Ok(record.map(|record| record.internal_user_id))
+    }
+}
+
+fn insert_token(
+    conn: &PgConnection,
+    internal_user_id: &str,
+    name: &str,
+    scopes: Option<Vec<&str>>,
+    now: i64,
+) -> Result<(String, String)> {
+    let id = Uuid::new_v4().to_string();
+    let token = format!(
+        "{}{}",
+        Uuid::new_v4().to_simple(),
+        Uuid::new_v4().to_simple()
+    );
+    insert_into(t_table::table)
+        .values((
+            t_table::token_hash.eq(hash_token(&token)),
+            t_table::id.eq(&id),
+            t_table::internal_user_id.eq(internal_user_id),
+            t_table::name.eq(name),
+            t_table::scopes.eq(scopes),
+            t_table::created_epoch_second.eq(now),
+        ))
+        .execute(conn)?;
+    Ok((id, token))
+}
+
+impl ApiTokenManager for PgConnection {
+    fn issue_token(&self, internal_user_id: &str, now: i64) -> Result<String> {
+        let (_, token) = insert_token(self, internal_user_id, "", None, now)?;
+        Ok(token)
+    }
+
+    fn create_named_token(
+        &self,
+        internal_user_id: &str,
+        name: &str,
+        scopes: &[Scope],
+        now: i64,
+    ) -> Result<(String, String)> {
+        if name.is_empty() || scopes.is_empty() {
+            return Err(
+                Validation("The name and the scopes are required.".to_owned()).into_http_error(),
+            );
+        }
+        let count = t_table::table
+            .filter(t_table::internal_user_id.eq(internal_user_id))
+            .filter(t_table::scopes.is_not_null())
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_NAMED_TOKEN_NUM {
+            return Err(Validation(format!(
+                "A user can have at most {} tokens.",
+                MAX_NAMED_TOKEN_NUM
+            ))
+            .into_http_error());
+        }
+        let scopes = scopes.iter().map(|scope| scope.as_str()).collect();
+        insert_token(self, internal_user_id, name, Some(scopes), now)
+    }
+
+    fn get_tokens(&self, internal_user_id: &str) -> Result<Vec<ApiTokenInfo>> {
+        let tokens = t_table::table
+            .filter(t_table::internal_user_id.eq(internal_user_id))
+            .order_by(t_table::created_epoch_second)
+            .select((
+                t_table::id,
+                t_table::name,
+                t_table::scopes,
+                t_table::created_epoch_second,
+            ))
+            .load::<ApiTokenInfo>(self)?;
+        Ok(tokens)
+    }
+
+    fn revoke_token(&self, internal_user_id: &str, token_id: &str) -> Result<()> {
+        delete(
+            t_table::table.filter(
+                t_table::id
+                    .eq(token_id)
+                    .and(t_table::internal_user_id.eq(internal_user_id)),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn find_token(&self, token: &str) -> Result<Option<TokenRecord>> {
+        let record = t_table::table
+            .filter(t_table::token_hash.eq(hash_token(token)))
+            .select((t_table::internal_user_id, t_table::scopes))
+            .first::<(String, Option<Vec<String>>)>(self)
+            .optional()?
+            .map(|(internal_user_id, scopes)| TokenRecord {
+                internal_user_id,
+                scopes,
+            });
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        assert_eq!(
+            hash_token("token"),
+            "3c469e9d6c5875d37a43f353d4f88e61fcf812c66eee3457465a40b0da4153e0"
+        );
+    }
+
+    #[test]
+    fn test_token_scopes() {
+        let login = TokenRecord {
+            internal_user_id: "user".to_owned(),
+            scopes: None,
+        };
+        assert!(login.allows(None));
+        assert!(login.allows(Some(Scope::ContestAdmin)));
+
+        let named = TokenRecord {
+            internal_user_id: "user".to_owned(),
+            scopes: Some(vec![Scope::ListWrite.as_str().to_owned()]),
+        };
+        assert!(!named.allows(None));
+        assert!(named.allows(Some(Scope::ListWrite)));
+        assert!(!named.allows(Some(Scope::ContestAdmin)));
+
+        assert_eq!(Scope::parse("contest-admin"), Some(Scope::ContestAdmin));
+        assert_eq!(Scope::parse("admin"), None);
+    }
+}
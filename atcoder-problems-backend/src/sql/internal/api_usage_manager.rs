@@ -0,0 +1,47 @@
+use crate::error::Result;
+use crate::sql::schema::internal_api_usage as u_table;
+
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+
+pub(crate) trait ApiUsageManager {
+    /// Counts a request of the subject on the day, and returns the number of the requests of the
+    /// day including it. The counts of the previous days are forgotten.
+    fn count_api_usage(&self, subject: &str, day_epoch_second: i64) -> Result<i64>;
+
+    /// Returns the number of the requests of the subject on the day.
+    fn get_api_usage(&self, subject: &str, day_epoch_second: i64) -> Result<i64>;
+}
+
+impl ApiUsageManager for PgConnection {
+    fn count_api_usage(&self, subject: &str, day_epoch_second: i64) -> Result<i64> {
+        delete(
+            u_table::table
+                .filter(u_table::subject.eq(subject))
+                .filter(u_table::day_epoch_second.lt(day_epoch_second)),
+        )
+        .execute(self)?;
+        let count = insert_into(u_table::table)
+            .values((
+                u_table::subject.eq(subject),
+                u_table::day_epoch_second.eq(day_epoch_second),
+                u_table::request_count.eq(1),
+            ))
+            .on_conflict((u_table::subject, u_table::day_epoch_second))
+            .do_update()
+            .set(u_table::request_count.eq(u_table::request_count + 1))
+            .returning(u_table::request_count)
+            .get_result::<i64>(self)?;
+        Ok(count)
+    }
+
+    fn get_api_usage(&self, subject: &str, day_epoch_second: i64) -> Result<i64> {
+        let count = u_table::table
+            .filter(u_table::subject.eq(subject))
+            .filter(u_table::day_epoch_second.eq(day_epoch_second))
+            .select(u_table::request_count)
+            .first::<i64>(self)
+            .optional()?;
+        Ok(count.unwrap_or(0))
+    }
+}
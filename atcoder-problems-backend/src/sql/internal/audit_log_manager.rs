@@ -0,0 +1,106 @@
+use crate::error::Result;
+use crate::sql::schema::internal_audit_log;
+
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+use serde::Serialize;
+
+const MAX_PAYLOAD_SUMMARY_LENGTH: usize = 1000;
+
+#[derive(Debug, Serialize, Queryable)]
+pub(crate) struct AuditLogEntry {
+    id: i64,
+    internal_user_id: String,
+    method: String,
+    path: String,
+    status: i32,
+    payload_summary: String,
+    epoch_second: i64,
+    client_ip: Option<String>,
+}
+
+pub(crate) trait AuditLogManager {
+    /// Records a write by the user. The summary of the payload, which must not contain the values
+    /// of the payload, is truncated to keep the log small.
+    #[allow(clippy::too_many_arguments)]
+    fn record_audit_log(
+        &self,
+        internal_user_id: &str,
+        method: &str,
+        path: &str,
+        status: u16,
+        payload_summary: &str,
+        epoch_second: i64,
+        client_ip: Option<&str>,
+    ) -> Result<()>;
+
+    /// Returns the latest entries, optionally only of the user or containing the keyword in the
+    /// path or the summary of the payload.
+    fn get_audit_logs(
+        &self,
+        internal_user_id: Option<&str>,
+        keyword: Option<&str>,
+        count: i64,
+    ) -> Result<Vec<AuditLogEntry>>;
+}
+
+impl AuditLogManager for PgConnection {
+    fn record_audit_log(
+        &self,
+        internal_user_id: &str,
+        method: &str,
+        path: &str,
+        status: u16,
+        payload_summary: &str,
+        epoch_second: i64,
+        client_ip: Option<&str>,
+    ) -> Result<()> {
+        let payload_summary = payload_summary
+            .chars()
+            .take(MAX_PAYLOAD_SUMMARY_LENGTH)
+            .collect::<String>();
+        insert_into(internal_audit_log::table)
+            .values((
+                internal_audit_log::internal_user_id.eq(internal_user_id),
+                internal_audit_log::method.eq(method),
+                internal_audit_log::path.eq(path),
+                internal_audit_log::status.eq(status as i32),
+                internal_audit_log::payload_summary.eq(payload_summary),
+                internal_audit_log::epoch_second.eq(epoch_second),
+                internal_audit_log::client_ip.eq(client_ip),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_audit_logs(
+        &self,
+        internal_user_id: Option<&str>,
+        keyword: Option<&str>,
+        count: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let mut query = internal_audit_log::table
+            .order_by(internal_audit_log::id.desc())
+            .limit(count)
+            .into_boxed();
+        if let Some(internal_user_id) = internal_user_id {
+            query = query.filter(internal_audit_log::internal_user_id.eq(internal_user_id));
+        }
+        if let Some(keyword) = keyword {
+            let pattern = format!(
+                "%{}%",
+                keyword
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            query = query.filter(
+                internal_audit_log::path
+                    .like(pattern.clone())
+                    .or(internal_audit_log::payload_summary.like(pattern)),
+            );
+        }
+        let entries = query.load::<AuditLogEntry>(self)?;
+        Ok(entries)
+    }
+}
@@ -0,0 +1,323 @@
+use crate::error::Result;
+use crate::sql::accepted_results;
+use crate::sql::internal::user_group_manager::check_group_owner;
+use crate::sql::schema::{
+    internal_assignment_problems as p_table, internal_assignments as a_table,
+    internal_user_group_members as m_table, problems, submissions,
+};
+
+use crate::error::Error::{NotFound, Validation};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+const MAX_ASSIGNMENT_NUM: i64 = 256;
+const MAX_PROBLEM_NUM: usize = 100;
+
+/// An assignment of the problems to the members of a group, which should be solved by the
+/// deadline.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Assignment {
+    pub internal_assignment_id: String,
+    pub internal_group_id: String,
+    pub title: String,
+    pub deadline_epoch_second: i64,
+    pub created_epoch_second: i64,
+    pub problem_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentStatus {
+    /// The first AC is before the deadline, including the ones before the assignment.
+    OnTime,
+    /// The first AC is after the deadline.
+    Late,
+    Unsolved,
+}
+
+impl AssignmentStatus {
+    fn of(first_ac_epoch_second: Option<i64>, deadline_epoch_second: i64) -> Self {
+        match first_ac_epoch_second {
+            Some(ac) if ac <= deadline_epoch_second => AssignmentStatus::OnTime,
+            Some(_) => AssignmentStatus::Late,
+            None => AssignmentStatus::Unsolved,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ProblemProgress {
+    pub problem_id: String,
+    pub first_ac_epoch_second: Option<i64>,
+    pub status: AssignmentStatus,
+}
+
+/// A row of the progress matrix, which has a cell for each problem of the assignment.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MemberProgress {
+    pub user_id: String,
+    pub on_time_count: usize,
+    pub problems: Vec<ProblemProgress>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AssignmentProgress {
+    pub assignment: Assignment,
+    pub members: Vec<MemberProgress>,
+}
+
+pub trait ClassroomManager {
+    /// Creates an assignment of the problems for the group of the coach, and returns its id.
+    fn create_assignment(
+        &self,
+        internal_user_id: &str,
+        internal_group_id: &str,
+        title: &str,
+        deadline_epoch_second: i64,
+        problem_ids: &[&str],
+        now: i64,
+    ) -> Result<String>;
+    fn delete_assignment(&self, internal_user_id: &str, internal_assignment_id: &str)
+        -> Result<()>;
+
+    /// Returns the assignments of the group, from the one of the earliest deadline.
+    fn get_assignments(&self, internal_group_id: &str) -> Result<Vec<Assignment>>;
+
+    /// Returns the progress of each member of the group on the assignment, computed from their
+    /// first AC submissions.
+    fn get_assignment_progress(&self, internal_assignment_id: &str) -> Result<AssignmentProgress>;
+}
+
+fn load_assignments(
+    conn: &PgConnection,
+    rows: Vec<(String, String, String, i64, i64)>,
+) -> Result<Vec<Assignment>> {
+    let assignment_ids = rows.iter().map(|row| row.0.as_str()).collect::<Vec<_>>();
+    let mut problem_ids = BTreeMap::new();
+    for (internal_assignment_id, problem_id) in p_table::table
+        .filter(p_table::internal_assignment_id.eq_any(assignment_ids))
+        .select((p_table::internal_assignment_id, p_table::problem_id))
+        .order_by((p_table::internal_assignment_id, p_table::problem_id))
+        .load::<(String, String)>(conn)?
+    {
+        problem_ids
+            .entry(internal_assignment_id)
+            .or_insert_with(Vec::new)
+            .push(problem_id);
+    }
+    let assignments = rows
+        .into_iter()
+        .map(
+            |(internal_assignment_id, internal_group_id, title, deadline, created)| Assignment {
+                problem_ids: problem_ids
+                    .remove(&internal_assignment_id)
+                    .unwrap_or_default(),
+                internal_assignment_id,
+                internal_group_id,
+                title,
+                deadline_epoch_second: deadline,
+                created_epoch_second: created,
+            },
+        )
+        .collect();
+    Ok(assignments)
+}
+
+impl ClassroomManager for PgConnection {
+    fn create_assignment(
+        &self,
+        internal_user_id: &str,
+        internal_group_id: &str,
+        title: &str,
+        deadline_epoch_second: i64,
+        problem_ids: &[&str],
+        now: i64,
+    ) -> Result<String> {
+        let problem_set = problem_ids.iter().collect::<BTreeSet<_>>();
+        if problem_ids.is_empty() || problem_ids.len() > MAX_PROBLEM_NUM {
+            return Err(Validation(format!(
+                "An assignment must have 1 to {} problems.",
+                MAX_PROBLEM_NUM
+            ))
+            .into_http_error());
+        }
+        if problem_set.len() != problem_ids.len() {
+            return Err(Validation("The problems are duplicated.".to_owned()).into_http_error());
+        }
+        check_group_owner(self, internal_group_id, internal_user_id)?;
+        let known_problem_count = problems::table
+            .filter(problems::id.eq_any(problem_ids))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if known_problem_count as usize != problem_ids.len() {
+            return Err(
+                Validation("Some of the problems do not exist.".to_owned()).into_http_error()
+            );
+        }
+        let count = a_table::table
+            .filter(a_table::internal_group_id.eq(internal_group_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_ASSIGNMENT_NUM {
+            return Err(Validation(format!(
+                "A group can have at most {} assignments.",
+                MAX_ASSIGNMENT_NUM
+            ))
+            .into_http_error());
+        }
+
+        let internal_assignment_id = uuid::Uuid::new_v4().to_string();
+        self.transaction::<_, http_types::Error, _>(|| {
+            insert_into(a_table::table)
+                .values((
+                    a_table::internal_assignment_id.eq(internal_assignment_id.as_str()),
+                    a_table::internal_group_id.eq(internal_group_id),
+                    a_table::title.eq(title),
+                    a_table::deadline_epoch_second.eq(deadline_epoch_second),
+                    a_table::created_epoch_second.eq(now),
+                ))
+                .execute(self)?;
+            insert_into(p_table::table)
+                .values(
+                    problem_ids
+                        .iter()
+                        .map(|problem_id| {
+                            (
+                                p_table::internal_assignment_id.eq(internal_assignment_id.as_str()),
+                                p_table::problem_id.eq(*problem_id),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .execute(self)?;
+            Ok(())
+        })?;
+        Ok(internal_assignment_id)
+    }
+
+    fn delete_assignment(
+        &self,
+        internal_user_id: &str,
+        internal_assignment_id: &str,
+    ) -> Result<()> {
+        let internal_group_id = a_table::table
+            .find(internal_assignment_id)
+            .select(a_table::internal_group_id)
+            .first::<String>(self)
+            .optional()?
+            .ok_or_else(|| NotFound.into_http_error())?;
+        check_group_owner(self, &internal_group_id, internal_user_id)?;
+        delete(a_table::table.find(internal_assignment_id)).execute(self)?;
+        Ok(())
+    }
+
+    fn get_assignments(&self, internal_group_id: &str) -> Result<Vec<Assignment>> {
+        let rows = a_table::table
+            .filter(a_table::internal_group_id.eq(internal_group_id))
+            .select((
+                a_table::internal_assignment_id,
+                a_table::internal_group_id,
+                a_table::title,
+                a_table::deadline_epoch_second,
+                a_table::created_epoch_second,
+            ))
+            .order_by((
+                a_table::deadline_epoch_second,
+                a_table::internal_assignment_id,
+            ))
+            .load::<(String, String, String, i64, i64)>(self)?;
+        load_assignments(self, rows)
+    }
+
+    fn get_assignment_progress(&self, internal_assignment_id: &str) -> Result<AssignmentProgress> {
+        let rows = a_table::table
+            .find(internal_assignment_id)
+            .select((
+                a_table::internal_assignment_id,
+                a_table::internal_group_id,
+                a_table::title,
+                a_table::deadline_epoch_second,
+                a_table::created_epoch_second,
+            ))
+            .load::<(String, String, String, i64, i64)>(self)?;
+        let assignment = load_assignments(self, rows)?
+            .pop()
+            .ok_or_else(|| NotFound.into_http_error())?;
+        let members = m_table::table
+            .filter(m_table::internal_group_id.eq(&assignment.internal_group_id))
+            .select(m_table::user_id)
+            .order_by(m_table::user_id)
+            .load::<String>(self)?;
+
+        let mut first_acs = BTreeMap::new();
+        for (user_id, problem_id, epoch_second) in submissions::table
+            .filter(submissions::user_id.eq_any(&members))
+            .filter(submissions::problem_id.eq_any(&assignment.problem_ids))
+            .filter(submissions::result.eq_any(accepted_results()))
+            .select((
+                submissions::user_id,
+                submissions::problem_id,
+                submissions::epoch_second,
+            ))
+            .load::<(String, String, i64)>(self)?
+        {
+            let first_ac = first_acs
+                .entry((user_id, problem_id))
+                .or_insert(epoch_second);
+            *first_ac = (*first_ac).min(epoch_second);
+        }
+
+        let members = members
+            .into_iter()
+            .map(|user_id| {
+                let problems = assignment
+                    .problem_ids
+                    .iter()
+                    .map(|problem_id| {
+                        let first_ac_epoch_second = first_acs
+                            .get(&(user_id.clone(), problem_id.clone()))
+                            .copied();
+                        ProblemProgress {
+                            problem_id: problem_id.clone(),
+                            first_ac_epoch_second,
+                            status: AssignmentStatus::of(
+                                first_ac_epoch_second,
+                                assignment.deadline_epoch_second,
+                            ),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                MemberProgress {
+                    on_time_count: problems
+                        .iter()
+                        .filter(|problem| problem.status == AssignmentStatus::OnTime)
+                        .count(),
+                    user_id,
+                    problems,
+                }
+            })
+            .collect();
+        Ok(AssignmentProgress {
+            assignment,
+            members,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            AssignmentStatus::of(Some(100), 100),
+            AssignmentStatus::OnTime
+        );
+        assert_eq!(AssignmentStatus::of(Some(101), 100), AssignmentStatus::Late);
+        assert_eq!(AssignmentStatus::of(None, 100), AssignmentStatus::Unsolved);
+    }
+}
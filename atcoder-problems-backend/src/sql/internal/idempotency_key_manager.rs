@@ -0,0 +1,142 @@
+use crate::error::Result;
+use crate::sql::schema::internal_idempotency_keys as k_table;
+
+use diesel::prelude::*;
+use diesel::{delete, insert_into, update, PgConnection};
+
+/// The keys are forgotten after a day, so that the clients can reuse them afterwards.
+const IDEMPOTENCY_KEY_TTL_SECOND: i64 = 24 * 3600;
+/// A request which has not completed in this time is regarded as abandoned, e.g. by a server
+/// which has crashed, and its key is taken over by the retries.
+const IN_FLIGHT_TIMEOUT_SECOND: i64 = 60;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum IdempotencyState {
+    /// The key is claimed by this request.
+    New,
+    /// The request with the key is being processed.
+    InFlight,
+    /// The request with the key has completed with the response.
+    Completed { status: u16, body: String },
+    /// The key has been used with another request.
+    Mismatched,
+}
+
+pub(crate) trait IdempotencyKeyManager {
+    /// Claims the key for the request of the hash, or returns the state of the request which has
+    /// claimed the key. The key of an abandoned request of the same hash is claimed again.
+    fn claim_idempotency_key(
+        &self,
+        internal_user_id: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+    ) -> Result<IdempotencyState>;
+
+    /// Stores the response of the request which has claimed the key.
+    fn complete_idempotency_key(
+        &self,
+        internal_user_id: &str,
+        key: &str,
+        status: u16,
+        body: &str,
+    ) -> Result<()>;
+
+    /// Releases the key of a failed request, so that the request can be retried.
+    fn release_idempotency_key(&self, internal_user_id: &str, key: &str) -> Result<()>;
+}
+
+impl IdempotencyKeyManager for PgConnection {
+    fn claim_idempotency_key(
+        &self,
+        internal_user_id: &str,
+        key: &str,
+        request_hash: &str,
+        now: i64,
+    ) -> Result<IdempotencyState> {
+        delete(
+            k_table::table
+                .filter(k_table::internal_user_id.eq(internal_user_id))
+                .filter(k_table::created_epoch_second.lt(now - IDEMPOTENCY_KEY_TTL_SECOND)),
+        )
+        .execute(self)?;
+
+        let inserted = insert_into(k_table::table)
+            .values((
+                k_table::internal_user_id.eq(internal_user_id),
+                k_table::idempotency_key.eq(key),
+                k_table::request_hash.eq(request_hash),
+                k_table::created_epoch_second.eq(now),
+            ))
+            .on_conflict_do_nothing()
+            .execute(self)?;
+        if inserted > 0 {
+            return Ok(IdempotencyState::New);
+        }
+        let taken_over = update(
+            k_table::table
+                .filter(k_table::internal_user_id.eq(internal_user_id))
+                .filter(k_table::idempotency_key.eq(key))
+                .filter(k_table::request_hash.eq(request_hash))
+                .filter(k_table::status.is_null())
+                .filter(k_table::created_epoch_second.lt(now - IN_FLIGHT_TIMEOUT_SECOND)),
+        )
+        .set(k_table::created_epoch_second.eq(now))
+        .execute(self)?;
+        if taken_over > 0 {
+            return Ok(IdempotencyState::New);
+        }
+
+        let (stored_hash, status, body) = k_table::table
+            .filter(k_table::internal_user_id.eq(internal_user_id))
+            .filter(k_table::idempotency_key.eq(key))
+            .select((
+                k_table::request_hash,
+                k_table::status,
+                k_table::response_body,
+            ))
+            .first::<(String, Option<i32>, Option<String>)>(self)?;
+        let state = if stored_hash != request_hash {
+            IdempotencyState::Mismatched
+        } else {
+            match status {
+                Some(status) => IdempotencyState::Completed {
+                    status: status as u16,
+                    body: body.unwrap_or_default(),
+                },
+                None => IdempotencyState::InFlight,
+            }
+        };
+        Ok(state)
+    }
+
+    fn complete_idempotency_key(
+        &self,
+        internal_user_id: &str,
+        key: &str,
+        status: u16,
+        body: &str,
+    ) -> Result<()> {
+        update(
+            k_table::table
+                .filter(k_table::internal_user_id.eq(internal_user_id))
+                .filter(k_table::idempotency_key.eq(key)),
+        )
+        .set((
+            k_table::status.eq(status as i32),
+            k_table::response_body.eq(body),
+        ))
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn release_idempotency_key(&self, internal_user_id: &str, key: &str) -> Result<()> {
+        delete(
+            k_table::table
+                .filter(k_table::internal_user_id.eq(internal_user_id))
+                .filter(k_table::idempotency_key.eq(key)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+}
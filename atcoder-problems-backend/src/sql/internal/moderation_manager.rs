@@ -0,0 +1,130 @@
+use crate::error::Result;
+use crate::sql::schema::{
+    contests, internal_banned_users, internal_problem_lists, internal_recrawl_requests,
+    internal_users, internal_virtual_contests,
+};
+
+use crate::error::Error::{NotFound, Validation};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+
+const MAX_USER_ID_LENGTH: usize = 255;
+
+pub trait ModerationManager {
+    fn is_admin(&self, internal_user_id: &str) -> Result<bool>;
+
+    /// Deletes the virtual contest regardless of its owner.
+    fn delete_contest(&self, contest_id: &str) -> Result<()>;
+    /// Deletes the problem list regardless of its owner.
+    fn delete_list(&self, internal_list_id: &str) -> Result<()>;
+
+    /// Excludes the AtCoder user from the rankings.
+    fn ban_user(&self, user_id: &str, banned_epoch_second: i64) -> Result<()>;
+    fn unban_user(&self, user_id: &str) -> Result<()>;
+    fn get_banned_users(&self) -> Result<Vec<String>>;
+
+    /// Requests the whole submissions of the contest to be crawled again.
+    fn request_recrawl(&self, contest_id: &str, requested_epoch_second: i64) -> Result<()>;
+    fn get_recrawl_requests(&self) -> Result<Vec<String>>;
+    fn complete_recrawl(&self, contest_id: &str) -> Result<()>;
+}
+
+impl ModerationManager for PgConnection {
+    fn is_admin(&self, internal_user_id: &str) -> Result<bool> {
+        let is_admin = internal_users::table
+            .filter(internal_users::internal_user_id.eq(internal_user_id))
+            .select(internal_users::is_admin)
+            .first::<bool>(self)
+            .optional()?;
+        Ok(is_admin.unwrap_or(false))
+    }
+
+    fn delete_contest(&self, contest_id: &str) -> Result<()> {
+        let count = delete(
+            internal_virtual_contests::table.filter(internal_virtual_contests::id.eq(contest_id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn delete_list(&self, internal_list_id: &str) -> Result<()> {
+        let count = delete(
+            internal_problem_lists::table
+                .filter(internal_problem_lists::internal_list_id.eq(internal_list_id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn ban_user(&self, user_id: &str, banned_epoch_second: i64) -> Result<()> {
+        if user_id.is_empty() || user_id.len() > MAX_USER_ID_LENGTH {
+            return Err(Validation("The user id is invalid.".to_owned()).into_http_error());
+        }
+        insert_into(internal_banned_users::table)
+            .values((
+                internal_banned_users::user_id.eq(user_id),
+                internal_banned_users::banned_epoch_second.eq(banned_epoch_second),
+            ))
+            .on_conflict(internal_banned_users::user_id)
+            .do_nothing()
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn unban_user(&self, user_id: &str) -> Result<()> {
+        delete(internal_banned_users::table.filter(internal_banned_users::user_id.eq(user_id)))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_banned_users(&self) -> Result<Vec<String>> {
+        let user_ids = internal_banned_users::table
+            .select(internal_banned_users::user_id)
+            .order_by(internal_banned_users::user_id)
+            .load::<String>(self)?;
+        Ok(user_ids)
+    }
+
+    fn request_recrawl(&self, contest_id: &str, requested_epoch_second: i64) -> Result<()> {
+        let count = contests::table
+            .filter(contests::id.eq(contest_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        insert_into(internal_recrawl_requests::table)
+            .values((
+                internal_recrawl_requests::contest_id.eq(contest_id),
+                internal_recrawl_requests::requested_epoch_second.eq(requested_epoch_second),
+            ))
+            .on_conflict(internal_recrawl_requests::contest_id)
+            .do_nothing()
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_recrawl_requests(&self) -> Result<Vec<String>> {
+        let contest_ids = internal_recrawl_requests::table
+            .select(internal_recrawl_requests::contest_id)
+            .order_by(internal_recrawl_requests::requested_epoch_second)
+            .load::<String>(self)?;
+        Ok(contest_ids)
+    }
+
+    fn complete_recrawl(&self, contest_id: &str) -> Result<()> {
+        delete(
+            internal_recrawl_requests::table
+                .filter(internal_recrawl_requests::contest_id.eq(contest_id)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+use crate::error::Result;
+use crate::sql::schema::internal_daily_digests as dd_table;
+use crate::sql::schema::internal_users;
+use crate::sql::schema::internal_webhook_deliveries as d_table;
+use crate::sql::schema::internal_webhooks as w_table;
+
+use crate::error::Error::{NotFound, Validation};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, update, PgConnection, Queryable};
+use serde::Serialize;
+use uuid::Uuid;
+
+const MAX_WEBHOOK_NUM: i64 = 10;
+const MAX_URL_LENGTH: usize = 2048;
+const MAX_SECRET_LENGTH: usize = 255;
+const MAX_EMAIL_ADDRESS_LENGTH: usize = 254;
+const SLACK_URL_PREFIX: &str = "https://hooks.slack.com/";
+/// The URLs of the email destinations are the addresses with this prefix.
+pub const EMAIL_URL_PREFIX: &str = "mailto:";
+const DISCORD_URL_PREFIXES: [&str; 2] = [
+    "https://discord.com/api/webhooks/",
+    "https://discordapp.com/api/webhooks/",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EventType {
+    /// The first AC of the user on a problem.
+    NewAc,
+    /// The streak of the user reaches one of the milestones.
+    StreakMilestone,
+    /// The shortest submission of the user is beaten by another user.
+    ShortestOvertaken,
+    /// The summary of the activities of the user and the rivals on the previous day.
+    DailyDigest,
+    /// The confirmation of a newly registered email address. It is sent to the unverified
+    /// address and cannot be subscribed.
+    EmailVerification,
+}
+
+impl EventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventType::NewAc => "new_ac",
+            EventType::StreakMilestone => "streak_milestone",
+            EventType::ShortestOvertaken => "shortest_overtaken",
+            EventType::DailyDigest => "daily_digest",
+            EventType::EmailVerification => "email_verification",
+        }
+    }
+
+    pub fn parse(event_type: &str) -> Option<Self> {
+        match event_type {
+            "new_ac" => Some(EventType::NewAc),
+            "streak_milestone" => Some(EventType::StreakMilestone),
+            "shortest_overtaken" => Some(EventType::ShortestOvertaken),
+            "daily_digest" => Some(EventType::DailyDigest),
+            _ => None,
+        }
+    }
+}
+
+/// The body format of the deliveries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// The raw payload signed with the secret.
+    Json,
+    /// A message for Slack incoming webhooks.
+    Slack,
+    /// A message for Discord webhooks.
+    Discord,
+    /// An HTML email to the verified address.
+    Email,
+}
+
+impl WebhookFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookFormat::Json => "json",
+            WebhookFormat::Slack => "slack",
+            WebhookFormat::Discord => "discord",
+            WebhookFormat::Email => "email",
+        }
+    }
+
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "json" => Some(WebhookFormat::Json),
+            "slack" => Some(WebhookFormat::Slack),
+            "discord" => Some(WebhookFormat::Discord),
+            "email" => Some(WebhookFormat::Email),
+            _ => None,
+        }
+    }
+
+    fn accepts(self, url: &str) -> bool {
+        match self {
+            WebhookFormat::Json => url.starts_with("https://"),
+            WebhookFormat::Slack => url.starts_with(SLACK_URL_PREFIX),
+            WebhookFormat::Discord => DISCORD_URL_PREFIXES
+                .iter()
+                .any(|prefix| url.starts_with(prefix)),
+            // The addresses are registered by `register_email` to be verified.
+            WebhookFormat::Email => false,
+        }
+    }
+}
+
+/// The secret is never returned once registered.
+#[derive(Debug, Serialize, Queryable)]
+pub struct WebhookInfo {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub format: String,
+    pub created_epoch_second: i64,
+}
+
+#[derive(Debug)]
+pub struct Subscription {
+    pub webhook_id: String,
+    pub internal_user_id: String,
+    pub atcoder_user_id: String,
+    pub event_types: Vec<String>,
+}
+
+impl Subscription {
+    pub fn subscribes(&self, event_type: EventType) -> bool {
+        self.event_types.iter().any(|e| e == event_type.as_str())
+    }
+}
+
+#[derive(Debug, Queryable)]
+pub struct PendingDelivery {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub format: String,
+    pub event_type: String,
+    pub payload: String,
+    pub attempt_count: i32,
+}
+
+pub trait NotificationManager {
+    fn register_webhook(
+        &self,
+        internal_user_id: &str,
+        url: &str,
+        secret: &str,
+        event_types: &[EventType],
+        format: WebhookFormat,
+        now: i64,
+    ) -> Result<String>;
+    fn get_webhooks(&self, internal_user_id: &str) -> Result<Vec<WebhookInfo>>;
+    fn delete_webhook(&self, internal_user_id: &str, webhook_id: &str) -> Result<()>;
+
+    /// Registers the email address as an unverified destination, and returns its id and the
+    /// verification token. It receives no notification until it is verified by `verify_email`.
+    /// The secret of the destination is the token to unsubscribe it without logging in.
+    fn register_email(
+        &self,
+        internal_user_id: &str,
+        address: &str,
+        event_types: &[EventType],
+        now: i64,
+    ) -> Result<(String, String)>;
+    fn verify_email(&self, verification_token: &str) -> Result<()>;
+    fn unsubscribe_email(&self, unsubscribe_token: &str) -> Result<()>;
+
+    /// Returns the verified webhooks of the users who have registered their AtCoder user ids.
+    fn get_subscriptions(&self) -> Result<Vec<Subscription>>;
+
+    fn enqueue_deliveries(
+        &self,
+        deliveries: &[(String, EventType, String)],
+        now: i64,
+    ) -> Result<()>;
+    fn get_due_deliveries(&self, now: i64, count: i64) -> Result<Vec<PendingDelivery>>;
+    fn complete_delivery(&self, delivery_id: i64, now: i64) -> Result<()>;
+
+    /// Records the failed attempt. The delivery is given up if `next_attempt_epoch_second` is
+    /// `None`.
+    fn fail_delivery(&self, delivery_id: i64, next_attempt_epoch_second: Option<i64>)
+        -> Result<()>;
+
+    /// Returns the payload of the digest of the user for the day starting at
+    /// `day_epoch_second`, if it has been generated.
+    fn get_daily_digest(
+        &self,
+        atcoder_user_id: &str,
+        day_epoch_second: i64,
+    ) -> Result<Option<String>>;
+
+    /// Stores the digest of the user for the day. Returns `false` without overwriting it if the
+    /// digest of the day has already been stored.
+    fn save_daily_digest(
+        &self,
+        atcoder_user_id: &str,
+        day_epoch_second: i64,
+        payload: &str,
+    ) -> Result<bool>;
+}
+
+/// The verification emails are not subscribable.
+fn is_subscribable(event_types: &[EventType]) -> bool {
+    !event_types.is_empty() && !event_types.contains(&EventType::EmailVerification)
+}
+
+fn is_email_address(address: &str) -> bool {
+    let mut parts = address.split('@');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(local), Some(domain), None) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && address.len() <= MAX_EMAIL_ADDRESS_LENGTH
+                && address.chars().all(|c| c.is_ascii_graphic())
+        }
+        _ => false,
+    }
+}
+
+fn check_webhook_count(conn: &PgConnection, internal_user_id: &str) -> Result<()> {
+    let count = w_table::table
+        .filter(w_table::internal_user_id.eq(internal_user_id))
+        .select(count_star())
+        .first::<i64>(conn)?;
+    if count >= MAX_WEBHOOK_NUM {
+        return Err(Validation(format!(
+            "A user can have at most {} webhooks.",
+            MAX_WEBHOOK_NUM
+        ))
+        .into_http_error());
+    }
+    Ok(())
+}
+
+impl NotificationManager for PgConnection {
+    fn register_webhook(
+        &self,
+        internal_user_id: &str,
+        url: &str,
+        secret: &str,
+        event_types: &[EventType],
+        format: WebhookFormat,
+        now: i64,
+    ) -> Result<String> {
+        if !format.accepts(url)
+            || url.len() > MAX_URL_LENGTH
+            || secret.is_empty()
+            || secret.len() > MAX_SECRET_LENGTH
+            || !is_subscribable(event_types)
+        {
+            return Err(Validation("The webhook is invalid.".to_owned()).into_http_error());
+        }
+        check_webhook_count(self, internal_user_id)?;
+
+        let id = Uuid::new_v4().to_string();
+        let event_types = event_types
+            .iter()
+            .map(|event_type| event_type.as_str())
+            .collect::<Vec<_>>();
+        insert_into(w_table::table)
+            .values((
+                w_table::id.eq(&id),
+                w_table::internal_user_id.eq(internal_user_id),
+                w_table::url.eq(url),
+                w_table::secret.eq(secret),
+                w_table::event_types.eq(event_types),
+                w_table::format.eq(format.as_str()),
+                w_table::created_epoch_second.eq(now),
+            ))
+            .execute(self)?;
+        Ok(id)
+    }
+
+    fn get_webhooks(&self, internal_user_id: &str) -> Result<Vec<WebhookInfo>> {
+        let webhooks = w_table::table
+            .filter(w_table::internal_user_id.eq(internal_user_id))
+            .order_by(w_table::created_epoch_second)
+            .select((
+                w_table::id,
+                w_table::url,
+                w_table::event_types,
+                w_table::format,
+                w_table::created_epoch_second,
+            ))
+            .load::<WebhookInfo>(self)?;
+        Ok(webhooks)
+    }
+
+    fn delete_webhook(&self, internal_user_id: &str, webhook_id: &str) -> Result<()> {
+        let count = delete(
+            w_table::table
+                .filter(w_table::internal_user_id.eq(internal_user_id))
+                .filter(w_table::id.eq(webhook_id)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn register_email(
+        &self,
+        internal_user_id: &str,
+        address: &str,
+        event_types: &[EventType],
+        now: i64,
+    ) -> Result<(String, String)> {
+        if !is_email_address(address) || !is_subscribable(event_types) {
+            return Err(
+                Validation("The email address or the events are invalid.".to_owned())
+                    .into_http_error(),
+            );
+        }
+        check_webhook_count(self, internal_user_id)?;
+
+        let id = Uuid::new_v4().to_string();
+        let verification_token = Uuid::new_v4().to_string();
+        let unsubscribe_token = Uuid::new_v4().to_string();
+        let event_types = event_types
+            .iter()
+            .map(|event_type| event_type.as_str())
+            .collect::<Vec<_>>();
+        insert_into(w_table::table)
+            .values((
+                w_table::id.eq(&id),
+                w_table::internal_user_id.eq(internal_user_id),
+                w_table::url.eq(format!("{}{}", EMAIL_URL_PREFIX, address)),
+                w_table::secret.eq(unsubscribe_token),
+                w_table::event_types.eq(event_types),
+                w_table::format.eq(WebhookFormat::Email.as_str()),
+                w_table::created_epoch_second.eq(now),
+                w_table::verification_token.eq(&verification_token),
+            ))
+            .execute(self)?;
+        Ok((id, verification_token))
+    }
+
+    fn verify_email(&self, verification_token: &str) -> Result<()> {
+        let count =
+            update(w_table::table.filter(w_table::verification_token.eq(verification_token)))
+                .set(w_table::verification_token.eq(None::<String>))
+                .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn unsubscribe_email(&self, unsubscribe_token: &str) -> Result<()> {
+        let count = delete(
+            w_table::table
+                .filter(w_table::format.eq(WebhookFormat::Email.as_str()))
+                .filter(w_table::secret.eq(unsubscribe_token)),
+        )
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn get_subscriptions(&self) -> Result<Vec<Subscription>> {
+        let subscriptions = w_table::table
+            .inner_join(internal_users::table)
+            .filter(w_table::verification_token.is_null())
+            .select((
+                w_table::id,
+                internal_users::internal_user_id,
+                internal_users::atcoder_user_id,
+                w_table::event_types,
+            ))
+            .load::<(String, String, Option<String>, Vec<String>)>(self)?
+            .into_iter()
+            .filter_map(
+                |(webhook_id, internal_user_id, atcoder_user_id, event_types)| {
+                    Some(Subscription {
+                        webhook_id,
+                        internal_user_id,
+                        atcoder_user_id: atcoder_user_id?,
+                        event_types,
+                    })
+                },
+            )
+            .collect();
+        Ok(subscriptions)
+    }
+
+    fn enqueue_deliveries(
+        &self,
+        deliveries: &[(String, EventType, String)],
+        now: i64,
+    ) -> Result<()> {
+        let values = deliveries
+            .iter()
+            .map(|(webhook_id, event_type, payload)| {
+                (
+                    d_table::webhook_id.eq(webhook_id),
+                    d_table::event_type.eq(event_type.as_str()),
+                    d_table::payload.eq(payload),
+                    d_table::next_attempt_epoch_second.eq(now),
+                )
+            })
+            .collect::<Vec<_>>();
+        insert_into(d_table::table).values(values).execute(self)?;
+        Ok(())
+    }
+
+    fn get_due_deliveries(&self, now: i64, count: i64) -> Result<Vec<PendingDelivery>> {
+        let deliveries = d_table::table
+            .inner_join(w_table::table)
+            .filter(d_table::next_attempt_epoch_second.le(now))
+            .order_by(d_table::id)
+            .limit(count)
+            .select((
+                d_table::id,
+                w_table::url,
+                w_table::secret,
+                w_table::format,
+                d_table::event_type,
+                d_table::payload,
+                d_table::attempt_count,
+            ))
+            .load::<PendingDelivery>(self)?;
+        Ok(deliveries)
+    }
+
+    fn complete_delivery(&self, delivery_id: i64, now: i64) -> Result<()> {
+        update(d_table::table.filter(d_table::id.eq(delivery_id)))
+            .set((
+                d_table::attempt_count.eq(d_table::attempt_count + 1),
+                d_table::next_attempt_epoch_second.eq(None::<i64>),
+                d_table::delivered_epoch_second.eq(now),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn fail_delivery(
+        &self,
+        delivery_id: i64,
+        next_attempt_epoch_second: Option<i64>,
+    ) -> Result<()> {
+        update(d_table::table.filter(d_table::id.eq(delivery_id)))
+            .set((
+                d_table::attempt_count.eq(d_table::attempt_count + 1),
+                d_table::next_attempt_epoch_second.eq(next_attempt_epoch_second),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_daily_digest(
+        &self,
+        atcoder_user_id: &str,
+        day_epoch_second: i64,
+    ) -> Result<Option<String>> {
+        let payload = dd_table::table
+            .filter(dd_table::atcoder_user_id.eq(atcoder_user_id))
+            .filter(dd_table::day_epoch_second.eq(day_epoch_second))
+            .select(dd_table::payload)
+            .first::<String>(self)
+            .optional()?;
+        Ok(payload)
+    }
+
+    fn save_daily_digest(
+        &self,
+        atcoder_user_id: &str,
+        day_epoch_second: i64,
+        payload: &str,
+    ) -> Result<bool> {
+        let count = insert_into(dd_table::table)
+            .values((
+                dd_table::atcoder_user_id.eq(atcoder_user_id),
+                dd_table::day_epoch_second.eq(day_epoch_second),
+                dd_table::payload.eq(payload),
+            ))
+            .on_conflict((dd_table::atcoder_user_id, dd_table::day_epoch_second))
+            .do_nothing()
+            .execute(self)?;
+        Ok(count > 0)
+    }
+}
@@ -1,11 +1,14 @@
 use crate::error::Result;
+use crate::sql::accepted_results;
+use crate::sql::internal::DELETED_RETENTION_SECOND;
 use crate::sql::schema::*;
 
-use crate::error::ErrorTypes::InvalidRequest;
+use crate::error::Error::{NotFound, Validation};
+use diesel::dsl::count_star;
 use diesel::prelude::*;
 use diesel::{delete, insert_into, update, PgConnection};
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 const MAX_LIST_NUM: usize = 256;
 const MAX_ITEM_NUM: usize = 1024;
@@ -13,28 +16,108 @@ const MAX_ITEM_NUM: usize = 1024;
 #[derive(Serialize)]
 pub(crate) struct ProblemList {
     internal_list_id: String,
-    internal_list_name: String,
+    pub(crate) internal_list_name: String,
     internal_user_id: String,
-    items: Vec<ListItem>,
+    pub(crate) items: Vec<ListItem>,
 }
 
 #[derive(Serialize)]
 pub(crate) struct ListItem {
+    pub(crate) problem_id: String,
+    pub(crate) memo: String,
+}
+
+/// A problem list viewed through its share link, with the solve status of the viewer.
+#[derive(Serialize)]
+pub(crate) struct SharedProblemList {
+    internal_list_name: String,
+    items: Vec<SharedListItem>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SharedListItem {
     problem_id: String,
     memo: String,
+    solved: bool,
 }
 
 pub(crate) trait ProblemListManager {
     fn get_list(&self, internal_user_id: &str) -> Result<Vec<ProblemList>>;
     fn get_single_list(&self, internal_list_id: &str) -> Result<ProblemList>;
 
+    fn is_list_owner(&self, internal_list_id: &str, internal_user_id: &str) -> Result<bool>;
+
     fn create_list(&self, internal_user_id: &str, name: &str) -> Result<String>;
-    fn update_list(&self, internal_list_id: &str, name: &str) -> Result<()>;
-    fn delete_list(&self, internal_list_id: &str) -> Result<()>;
 
-    fn add_item(&self, internal_list_id: &str, problem_id: &str) -> Result<()>;
-    fn update_item(&self, internal_list_id: &str, problem_id: &str, memo: &str) -> Result<()>;
-    fn delete_item(&self, internal_list_id: &str, problem_id: &str) -> Result<()>;
+    /// Creates a new list with the given items, and returns the id of the list.
+    /// Fails without creating anything if any of the problem ids is not in the problems table.
+    fn import_list(
+        &self,
+        internal_user_id: &str,
+        name: &str,
+        items: &[(String, String)],
+    ) -> Result<String>;
+    fn update_list(&self, internal_list_id: &str, internal_user_id: &str, name: &str)
+        -> Result<()>;
+    /// Marks the list as deleted. It is hidden, but can be restored by `restore_list` within
+    /// `DELETED_RETENTION_SECOND`.
+    fn delete_list(&self, internal_list_id: &str, internal_user_id: &str, now: i64) -> Result<()>;
+    fn restore_list(&self, internal_list_id: &str, internal_user_id: &str, now: i64) -> Result<()>;
+    /// Deletes the lists which have been deleted before `before` permanently.
+    fn purge_deleted_lists(&self, before: i64) -> Result<usize>;
+
+    fn add_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+        memo: Option<&str>,
+    ) -> Result<()>;
+    fn update_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+        memo: &str,
+    ) -> Result<()>;
+    fn delete_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+    ) -> Result<()>;
+
+    fn get_share_token(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+    ) -> Result<Option<String>>;
+    fn update_share_token(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        share_token: Option<&str>,
+    ) -> Result<()>;
+
+    /// Returns the list of the share token, where each item is marked as solved if
+    /// `atcoder_user_id` has an AC on it.
+    fn get_shared_list(
+        &self,
+        share_token: &str,
+        atcoder_user_id: Option<&str>,
+    ) -> Result<SharedProblemList>;
+}
+
+fn check_list_owner(
+    conn: &PgConnection,
+    internal_list_id: &str,
+    internal_user_id: &str,
+) -> Result<()> {
+    if conn.is_list_owner(internal_list_id, internal_user_id)? {
+        Ok(())
+    } else {
+        Err(NotFound.into_http_error())
+    }
 }
 
 impl ProblemListManager for PgConnection {
@@ -45,6 +128,7 @@ impl ProblemListManager for PgConnection {
                     .eq(internal_problem_list_items::internal_list_id)),
             )
             .filter(internal_problem_lists::internal_user_id.eq(internal_user_id))
+            .filter(internal_problem_lists::deleted_epoch_second.is_null())
             .select((
                 internal_problem_lists::internal_list_id,
                 internal_problem_lists::internal_list_name,
@@ -82,6 +166,7 @@ impl ProblemListManager for PgConnection {
                     .eq(internal_problem_list_items::internal_list_id)),
             )
             .filter(internal_problem_lists::internal_list_id.eq(internal_list_id))
+            .filter(internal_problem_lists::deleted_epoch_second.is_null())
             .select((
                 internal_problem_lists::internal_list_id,
                 internal_problem_lists::internal_list_name,
@@ -110,15 +195,31 @@ impl ProblemListManager for PgConnection {
                 },
             )
             .next()
-            .ok_or_else(|| InvalidRequest)?;
+            .ok_or_else(|| NotFound.into_http_error())?;
         Ok(list)
     }
 
+    fn is_list_owner(&self, internal_list_id: &str, internal_user_id: &str) -> Result<bool> {
+        let count = internal_problem_lists::table
+            .filter(
+                internal_problem_lists::internal_list_id
+                    .eq(internal_list_id)
+                    .and(internal_problem_lists::internal_user_id.eq(internal_user_id)),
+            )
+            .filter(internal_problem_lists::deleted_epoch_second.is_null())
+            .select(count_star())
+            .first::<i64>(self)?;
+        Ok(count > 0)
+    }
+
     fn create_list(&self, internal_user_id: &str, name: &str) -> Result<String> {
         let new_list_id = uuid::Uuid::new_v4().to_string();
         let list = self.get_list(internal_user_id)?;
         if list.len() >= MAX_LIST_NUM {
-            return Err(http_types::Error::from(InvalidRequest));
+            return Err(
+                Validation(format!("A user can have at most {} lists.", MAX_LIST_NUM))
+                    .into_http_error(),
+            );
         }
         insert_into(internal_problem_lists::table)
             .values(vec![(
@@ -129,7 +230,62 @@ impl ProblemListManager for PgConnection {
             .execute(self)?;
         Ok(new_list_id)
     }
-    fn update_list(&self, internal_list_id: &str, name: &str) -> Result<()> {
+    fn import_list(
+        &self,
+        internal_user_id: &str,
+        name: &str,
+        items: &[(String, String)],
+    ) -> Result<String> {
+        if items.len() > MAX_ITEM_NUM {
+            return Err(Validation(format!(
+                "A list can have at most {} problems.",
+                MAX_ITEM_NUM
+            ))
+            .into_http_error());
+        }
+        let problem_ids = items
+            .iter()
+            .map(|(problem_id, _)| problem_id.as_str())
+            .collect::<BTreeSet<_>>();
+        if problem_ids.len() != items.len() {
+            return Err(Validation("The problems are duplicated.".to_owned()).into_http_error());
+        }
+        let known_problem_count = problems::table
+            .filter(problems::id.eq_any(problem_ids.iter()))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if known_problem_count as usize != problem_ids.len() {
+            return Err(
+                Validation("Some of the problems do not exist.".to_owned()).into_http_error()
+            );
+        }
+
+        self.transaction::<_, http_types::Error, _>(|| {
+            let internal_list_id = self.create_list(internal_user_id, name)?;
+            let values = items
+                .iter()
+                .map(|(problem_id, memo)| {
+                    (
+                        internal_problem_list_items::internal_list_id.eq(internal_list_id.as_str()),
+                        internal_problem_list_items::problem_id.eq(problem_id.as_str()),
+                        internal_problem_list_items::memo.eq(memo.as_str()),
+                    )
+                })
+                .collect::<Vec<_>>();
+            insert_into(internal_problem_list_items::table)
+                .values(values)
+                .execute(self)?;
+            Ok(internal_list_id)
+        })
+    }
+
+    fn update_list(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        name: &str,
+    ) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
         update(
             internal_problem_lists::table
                 .filter(internal_problem_lists::internal_list_id.eq(internal_list_id)),
@@ -138,33 +294,84 @@ impl ProblemListManager for PgConnection {
         .execute(self)?;
         Ok(())
     }
-    fn delete_list(&self, internal_list_id: &str) -> Result<()> {
-        delete(
+    fn delete_list(&self, internal_list_id: &str, internal_user_id: &str, now: i64) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
+        update(
             internal_problem_lists::table
                 .filter(internal_problem_lists::internal_list_id.eq(internal_list_id)),
         )
+        .set(internal_problem_lists::deleted_epoch_second.eq(now))
         .execute(self)?;
         Ok(())
     }
+    fn restore_list(&self, internal_list_id: &str, internal_user_id: &str, now: i64) -> Result<()> {
+        if self.get_list(internal_user_id)?.len() >= MAX_LIST_NUM {
+            return Err(
+                Validation(format!("A user can have at most {} lists.", MAX_LIST_NUM))
+                    .into_http_error(),
+            );
+        }
+        let count = update(
+            internal_problem_lists::table
+                .filter(internal_problem_lists::internal_list_id.eq(internal_list_id))
+                .filter(internal_problem_lists::internal_user_id.eq(internal_user_id))
+                .filter(
+                    internal_problem_lists::deleted_epoch_second.ge(now - DELETED_RETENTION_SECOND),
+                ),
+        )
+        .set(internal_problem_lists::deleted_epoch_second.eq(None::<i64>))
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+    fn purge_deleted_lists(&self, before: i64) -> Result<usize> {
+        let count = delete(
+            internal_problem_lists::table
+                .filter(internal_problem_lists::deleted_epoch_second.lt(before)),
+        )
+        .execute(self)?;
+        Ok(count)
+    }
 
-    fn add_item(&self, internal_list_id: &str, problem_id: &str) -> Result<()> {
+    fn add_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+        memo: Option<&str>,
+    ) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
         let problems = internal_problem_list_items::table
             .filter(internal_problem_list_items::internal_list_id.eq(internal_list_id))
             .select(internal_problem_list_items::problem_id)
             .load::<String>(self)?;
         if problems.len() >= MAX_ITEM_NUM {
-            return Err(http_types::Error::from(InvalidRequest));
+            return Err(Validation(format!(
+                "A list can have at most {} problems.",
+                MAX_ITEM_NUM
+            ))
+            .into_http_error());
         }
         insert_into(internal_problem_list_items::table)
             .values(vec![(
                 internal_problem_list_items::internal_list_id.eq(internal_list_id),
                 internal_problem_list_items::problem_id.eq(problem_id),
+                internal_problem_list_items::memo.eq(memo.unwrap_or("")),
             )])
             .execute(self)?;
         Ok(())
     }
 
-    fn update_item(&self, internal_list_id: &str, problem_id: &str, memo: &str) -> Result<()> {
+    fn update_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+        memo: &str,
+    ) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
         update(
             internal_problem_list_items::table.filter(
                 internal_problem_list_items::internal_list_id
@@ -176,7 +383,13 @@ impl ProblemListManager for PgConnection {
         .execute(self)?;
         Ok(())
     }
-    fn delete_item(&self, internal_list_id: &str, problem_id: &str) -> Result<()> {
+    fn delete_item(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        problem_id: &str,
+    ) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
         delete(
             internal_problem_list_items::table.filter(
                 internal_problem_list_items::internal_list_id
@@ -187,4 +400,77 @@ impl ProblemListManager for PgConnection {
         .execute(self)?;
         Ok(())
     }
+
+    fn get_share_token(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+    ) -> Result<Option<String>> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
+        let share_token = internal_problem_lists::table
+            .filter(internal_problem_lists::internal_list_id.eq(internal_list_id))
+            .select(internal_problem_lists::share_token)
+            .first::<Option<String>>(self)?;
+        Ok(share_token)
+    }
+
+    fn update_share_token(
+        &self,
+        internal_list_id: &str,
+        internal_user_id: &str,
+        share_token: Option<&str>,
+    ) -> Result<()> {
+        check_list_owner(self, internal_list_id, internal_user_id)?;
+        update(
+            internal_problem_lists::table
+                .filter(internal_problem_lists::internal_list_id.eq(internal_list_id)),
+        )
+        .set(internal_problem_lists::share_token.eq(share_token))
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_shared_list(
+        &self,
+        share_token: &str,
+        atcoder_user_id: Option<&str>,
+    ) -> Result<SharedProblemList> {
+        let internal_list_id = internal_problem_lists::table
+            .filter(internal_problem_lists::share_token.eq(share_token))
+            .filter(internal_problem_lists::deleted_epoch_second.is_null())
+            .select(internal_problem_lists::internal_list_id)
+            .first::<String>(self)
+            .optional()?
+            .ok_or_else(|| NotFound.into_http_error())?;
+        let list = self.get_single_list(&internal_list_id)?;
+
+        let solved_problems = match atcoder_user_id {
+            Some(atcoder_user_id) => submissions::table
+                .filter(submissions::user_id.eq(atcoder_user_id))
+                .filter(submissions::result.eq_any(accepted_results()))
+                .filter(
+                    submissions::problem_id
+                        .eq_any(list.items.iter().map(|item| item.problem_id.as_str())),
+                )
+                .select(submissions::problem_id)
+                .distinct()
+                .load::<String>(self)?
+                .into_iter()
+                .collect::<BTreeSet<_>>(),
+            None => BTreeSet::new(),
+        };
+        let items = list
+            .items
+            .into_iter()
+            .map(|item| SharedListItem {
+                solved: solved_problems.contains(&item.problem_id),
+                problem_id: item.problem_id,
+                memo: item.memo,
+            })
+            .collect();
+        Ok(SharedProblemList {
+            internal_list_name: list.internal_list_name,
+            items,
+        })
+    }
 }
@@ -0,0 +1,213 @@
+use crate::error::Result;
+use crate::sql::schema::{internal_problem_notes as n_table, internal_problem_tags as t_table};
+
+use crate::error::Error::Validation;
+use diesel::dsl::count_star;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const MAX_NOTE_LENGTH: usize = 1000;
+const MAX_TAG_LENGTH: usize = 32;
+const MAX_TAG_NUM_PER_PROBLEM: i64 = 16;
+
+#[derive(Serialize)]
+pub(crate) struct ProblemNote {
+    problem_id: String,
+    note: String,
+    updated_epoch_second: Option<i64>,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct TaggedProblem {
+    problem_id: String,
+    tagged_epoch_second: i64,
+}
+
+pub(crate) trait ProblemNoteManager {
+    /// Saves the note of the problem, or removes it if `note` is empty.
+    fn update_note(
+        &self,
+        internal_user_id: &str,
+        problem_id: &str,
+        note: &str,
+        epoch_second: i64,
+    ) -> Result<()>;
+    fn add_tag(
+        &self,
+        internal_user_id: &str,
+        problem_id: &str,
+        tag: &str,
+        epoch_second: i64,
+    ) -> Result<()>;
+    fn remove_tag(&self, internal_user_id: &str, problem_id: &str, tag: &str) -> Result<()>;
+
+    /// Returns all the problems which have a note or a tag.
+    fn get_notes(&self, internal_user_id: &str) -> Result<Vec<ProblemNote>>;
+
+    /// Returns the problems with the tag, the least recently tagged first.
+    fn get_tagged_problems(&self, internal_user_id: &str, tag: &str) -> Result<Vec<TaggedProblem>>;
+}
+
+impl ProblemNoteManager for PgConnection {
+    fn update_note(
+        &self,
+        internal_user_id: &str,
+        problem_id: &str,
+        note: &str,
+        epoch_second: i64,
+    ) -> Result<()> {
+        if note.chars().count() > MAX_NOTE_LENGTH {
+            return Err(Validation(format!(
+                "A note can have at most {} characters.",
+                MAX_NOTE_LENGTH
+            ))
+            .into_http_error());
+        }
+        if note.is_empty() {
+            delete(
+                n_table::table.filter(
+                    n_table::internal_user_id
+                        .eq(internal_user_id)
+                        .and(n_table::problem_id.eq(problem_id)),
+                ),
+            )
+            .execute(self)?;
+            return Ok(());
+        }
+        insert_into(n_table::table)
+            .values((
+                n_table::internal_user_id.eq(internal_user_id),
+                n_table::problem_id.eq(problem_id),
+                n_table::note.eq(note),
+                n_table::updated_epoch_second.eq(epoch_second),
+            ))
+            .on_conflict((n_table::internal_user_id, n_table::problem_id))
+            .do_update()
+            .set((
+                n_table::note.eq(excluded(n_table::note)),
+                n_table::updated_epoch_second.eq(excluded(n_table::updated_epoch_second)),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn add_tag(
+        &self,
+        internal_user_id: &str,
+        problem_id: &str,
+        tag: &str,
+        epoch_second: i64,
+    ) -> Result<()> {
+        let tag = tag.trim();
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(Validation(format!(
+                "A tag must have 1 to {} characters.",
+                MAX_TAG_LENGTH
+            ))
+            .into_http_error());
+        }
+        let count = t_table::table
+            .filter(
+                t_table::internal_user_id
+                    .eq(internal_user_id)
+                    .and(t_table::problem_id.eq(problem_id)),
+            )
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_TAG_NUM_PER_PROBLEM {
+            return Err(Validation(format!(
+                "A problem can have at most {} tags.",
+                MAX_TAG_NUM_PER_PROBLEM
+            ))
+            .into_http_error());
+        }
+        insert_into(t_table::table)
+            .values((
+                t_table::internal_user_id.eq(internal_user_id),
+                t_table::problem_id.eq(problem_id),
+                t_table::tag.eq(tag),
+                t_table::tagged_epoch_second.eq(epoch_second),
+            ))
+            .on_conflict((t_table::internal_user_id, t_table::problem_id, t_table::tag))
+            .do_nothing()
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn remove_tag(&self, internal_user_id: &str, problem_id: &str, tag: &str) -> Result<()> {
+        delete(
+            t_table::table.filter(
+                t_table::internal_user_id
+                    .eq(internal_user_id)
+                    .and(t_table::problem_id.eq(problem_id))
+                    .and(t_table::tag.eq(tag.trim())),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_notes(&self, internal_user_id: &str) -> Result<Vec<ProblemNote>> {
+        let notes = n_table::table
+            .filter(n_table::internal_user_id.eq(internal_user_id))
+            .select((
+                n_table::problem_id,
+                n_table::note,
+                n_table::updated_epoch_second,
+            ))
+            .load::<(String, String, i64)>(self)?;
+        let tags = t_table::table
+            .filter(t_table::internal_user_id.eq(internal_user_id))
+            .order_by(t_table::tag)
+            .select((t_table::problem_id, t_table::tag))
+            .load::<(String, String)>(self)?;
+
+        let mut map = BTreeMap::new();
+        for (problem_id, note, updated_epoch_second) in notes.into_iter() {
+            map.insert(
+                problem_id.clone(),
+                ProblemNote {
+                    problem_id,
+                    note,
+                    updated_epoch_second: Some(updated_epoch_second),
+                    tags: Vec::new(),
+                },
+            );
+        }
+        for (problem_id, tag) in tags.into_iter() {
+            map.entry(problem_id.clone())
+                .or_insert(ProblemNote {
+                    problem_id,
+                    note: String::new(),
+                    updated_epoch_second: None,
+                    tags: Vec::new(),
+                })
+                .tags
+                .push(tag);
+        }
+        Ok(map.into_values().collect())
+    }
+
+    fn get_tagged_problems(&self, internal_user_id: &str, tag: &str) -> Result<Vec<TaggedProblem>> {
+        let problems = t_table::table
+            .filter(
+                t_table::internal_user_id
+                    .eq(internal_user_id)
+                    .and(t_table::tag.eq(tag.trim())),
+            )
+            .order_by((t_table::tagged_epoch_second, t_table::problem_id))
+            .select((t_table::problem_id, t_table::tagged_epoch_second))
+            .load::<(String, i64)>(self)?
+            .into_iter()
+            .map(|(problem_id, tagged_epoch_second)| TaggedProblem {
+                problem_id,
+                tagged_epoch_second,
+            })
+            .collect();
+        Ok(problems)
+    }
+}
@@ -0,0 +1,302 @@
+use crate::error::Result;
+use crate::sql::models::Point;
+use crate::sql::schema::{internal_user_group_members as m_table, internal_user_groups as g_table};
+
+use crate::error::Error::{NotFound, Validation};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Numeric, Text, Varchar};
+use diesel::{delete, insert_into, sql_query, update, PgConnection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const MAX_GROUP_NUM: i64 = 32;
+const MAX_MEMBER_NUM: usize = 200;
+const MAX_USER_ID_LENGTH: usize = 255;
+
+/// The new problems solved in this period are counted as the weekly progress.
+const WEEK_SECOND: i64 = 7 * 24 * 3600;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct UserGroup {
+    pub internal_group_id: String,
+    pub group_name: String,
+    pub members: Vec<String>,
+}
+
+/// The statistics by which the members of a group are ranked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupRankingKind {
+    AcceptedCount,
+    RatedPointSum,
+    MaxStreak,
+    /// The number of the problems newly solved in the last week.
+    WeeklyAcceptedCount,
+}
+
+impl GroupRankingKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ac" => Some(GroupRankingKind::AcceptedCount),
+            "points" => Some(GroupRankingKind::RatedPointSum),
+            "streak" => Some(GroupRankingKind::MaxStreak),
+            "weekly" => Some(GroupRankingKind::WeeklyAcceptedCount),
+            _ => None,
+        }
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            GroupRankingKind::AcceptedCount => "accepted_count",
+            GroupRankingKind::RatedPointSum => "rated_point_sum",
+            GroupRankingKind::MaxStreak => "max_streak",
+            GroupRankingKind::WeeklyAcceptedCount => "weekly_accepted_count",
+        }
+    }
+}
+
+/// A member of a group with the statistics from the aggregates. The members without the
+/// aggregates have zeros.
+#[derive(Debug, PartialEq, QueryableByName, Serialize)]
+pub struct GroupRankingEntry {
+    #[sql_type = "Varchar"]
+    pub user_id: String,
+    #[sql_type = "BigInt"]
+    pub accepted_count: i64,
+    #[sql_type = "Numeric"]
+    pub rated_point_sum: Point,
+    #[sql_type = "BigInt"]
+    pub max_streak: i64,
+    #[sql_type = "BigInt"]
+    pub weekly_accepted_count: i64,
+    #[sql_type = "BigInt"]
+    pub rank: i64,
+}
+
+pub trait UserGroupManager {
+    fn create_group(&self, internal_user_id: &str, group_name: &str) -> Result<String>;
+    fn update_group(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        group_name: &str,
+    ) -> Result<()>;
+    fn delete_group(&self, internal_group_id: &str, internal_user_id: &str) -> Result<()>;
+    fn get_groups(&self, internal_user_id: &str) -> Result<Vec<UserGroup>>;
+
+    fn add_group_members(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        user_ids: &[&str],
+    ) -> Result<()>;
+    fn remove_group_member(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        user_id: &str,
+    ) -> Result<()>;
+
+    /// Returns the members of the group in the order of `kind`, where the members of the same
+    /// value have the same rank. The banned members are not ranked.
+    fn load_group_ranking(
+        &self,
+        internal_group_id: &str,
+        kind: GroupRankingKind,
+        now: i64,
+    ) -> Result<Vec<GroupRankingEntry>>;
+}
+
+pub(crate) fn check_group_owner(
+    conn: &PgConnection,
+    internal_group_id: &str,
+    internal_user_id: &str,
+) -> Result<()> {
+    let count = g_table::table
+        .filter(g_table::internal_group_id.eq(internal_group_id))
+        .filter(g_table::internal_user_id.eq(internal_user_id))
+        .select(count_star())
+        .first::<i64>(conn)?;
+    if count > 0 {
+        Ok(())
+    } else {
+        Err(NotFound.into_http_error())
+    }
+}
+
+impl UserGroupManager for PgConnection {
+    fn create_group(&self, internal_user_id: &str, group_name: &str) -> Result<String> {
+        let count = g_table::table
+            .filter(g_table::internal_user_id.eq(internal_user_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_GROUP_NUM {
+            return Err(
+                Validation(format!("A user can have at most {} groups.", MAX_GROUP_NUM))
+                    .into_http_error(),
+            );
+        }
+        let internal_group_id = uuid::Uuid::new_v4().to_string();
+        insert_into(g_table::table)
+            .values((
+                g_table::internal_group_id.eq(internal_group_id.as_str()),
+                g_table::internal_user_id.eq(internal_user_id),
+                g_table::group_name.eq(group_name),
+            ))
+            .execute(self)?;
+        Ok(internal_group_id)
+    }
+
+    fn update_group(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        group_name: &str,
+    ) -> Result<()> {
+        check_group_owner(self, internal_group_id, internal_user_id)?;
+        update(g_table::table.filter(g_table::internal_group_id.eq(internal_group_id)))
+            .set(g_table::group_name.eq(group_name))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn delete_group(&self, internal_group_id: &str, internal_user_id: &str) -> Result<()> {
+        check_group_owner(self, internal_group_id, internal_user_id)?;
+        delete(g_table::table.filter(g_table::internal_group_id.eq(internal_group_id)))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn get_groups(&self, internal_user_id: &str) -> Result<Vec<UserGroup>> {
+        let rows = g_table::table
+            .left_join(m_table::table.on(m_table::internal_group_id.eq(g_table::internal_group_id)))
+            .filter(g_table::internal_user_id.eq(internal_user_id))
+            .select((
+                g_table::internal_group_id,
+                g_table::group_name,
+                m_table::user_id.nullable(),
+            ))
+            .order_by((g_table::internal_group_id, m_table::user_id))
+            .load::<(String, String, Option<String>)>(self)?;
+        let mut groups = BTreeMap::new();
+        for (internal_group_id, group_name, user_id) in rows.into_iter() {
+            let group = groups
+                .entry(internal_group_id.clone())
+                .or_insert_with(|| UserGroup {
+                    internal_group_id,
+                    group_name,
+                    members: vec![],
+                });
+            group.members.extend(user_id);
+        }
+        Ok(groups.into_values().collect())
+    }
+
+    fn add_group_members(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        user_ids: &[&str],
+    ) -> Result<()> {
+        if user_ids
+            .iter()
+            .any(|user_id| user_id.is_empty() || user_id.len() > MAX_USER_ID_LENGTH)
+        {
+            return Err(Validation("The user id is invalid.".to_owned()).into_http_error());
+        }
+        check_group_owner(self, internal_group_id, internal_user_id)?;
+        self.transaction::<_, http_types::Error, _>(|| {
+            insert_into(m_table::table)
+                .values(
+                    user_ids
+                        .iter()
+                        .map(|user_id| {
+                            (
+                                m_table::internal_group_id.eq(internal_group_id),
+                                m_table::user_id.eq(*user_id),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .on_conflict_do_nothing()
+                .execute(self)?;
+            let count = m_table::table
+                .filter(m_table::internal_group_id.eq(internal_group_id))
+                .select(count_star())
+                .first::<i64>(self)?;
+            if count as usize > MAX_MEMBER_NUM {
+                return Err(Validation(format!(
+                    "A group can have at most {} members.",
+                    MAX_MEMBER_NUM
+                ))
+                .into_http_error());
+            }
+            Ok(())
+        })
+    }
+
+    fn remove_group_member(
+        &self,
+        internal_group_id: &str,
+        internal_user_id: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        check_group_owner(self, internal_group_id, internal_user_id)?;
+        delete(
+            m_table::table
+                .filter(m_table::internal_group_id.eq(internal_group_id))
+                .filter(m_table::user_id.eq(user_id)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn load_group_ranking(
+        &self,
+        internal_group_id: &str,
+        kind: GroupRankingKind,
+        now: i64,
+    ) -> Result<Vec<GroupRankingEntry>> {
+        let query = format!(
+            r"
+            WITH members AS (
+                SELECT user_id FROM internal_user_group_members WHERE internal_group_id = $1
+                AND user_id NOT IN (SELECT user_id FROM internal_banned_users)
+            ),
+            weekly AS (
+                SELECT user_id, COUNT(*) AS count
+                FROM (
+                    SELECT user_id, problem_id, MIN(epoch_second) AS epoch_second
+                    FROM submissions
+                    WHERE user_id IN (SELECT user_id FROM members)
+                    AND result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                    GROUP BY user_id, problem_id
+                ) AS first_ac
+                WHERE epoch_second >= $2
+                GROUP BY user_id
+            ),
+            stats AS (
+                SELECT
+                    members.user_id,
+                    COALESCE(accepted_count.problem_count, 0)::BIGINT AS accepted_count,
+                    COALESCE(rated_point_sum.point_sum, 0.0) AS rated_point_sum,
+                    COALESCE(max_streaks.streak, 0) AS max_streak,
+                    COALESCE(weekly.count, 0) AS weekly_accepted_count
+                FROM members
+                LEFT JOIN accepted_count ON accepted_count.user_id = members.user_id
+                LEFT JOIN rated_point_sum ON rated_point_sum.user_id = members.user_id
+                LEFT JOIN max_streaks ON max_streaks.user_id = members.user_id
+                LEFT JOIN weekly ON weekly.user_id = members.user_id
+            )
+            SELECT *, DENSE_RANK() OVER (ORDER BY {column} DESC) - 1 AS rank
+            FROM stats
+            ORDER BY {column} DESC, user_id",
+            column = kind.column()
+        );
+        let ranking = sql_query(query)
+            .bind::<Text, _>(internal_group_id)
+            .bind::<BigInt, _>(now - WEEK_SECOND)
+            .load::<GroupRankingEntry>(self)?;
+        Ok(ranking)
+    }
+}
@@ -2,7 +2,9 @@ use crate::error::Result;
 use crate::sql::schema::*;
 
 use diesel::prelude::*;
-use diesel::{insert_into, update, PgConnection};
+use diesel::{delete, insert_into, update, PgConnection};
+use internal_virtual_contest_participants as v_participants;
+use internal_virtual_contests as v_contests;
 use serde::Serialize;
 
 #[derive(Debug, QueryableByName, Queryable, Serialize)]
@@ -10,6 +12,7 @@ use serde::Serialize;
 pub(crate) struct InternalUserInfo {
     internal_user_id: String,
     atcoder_user_id: Option<String>,
+    is_admin: bool,
 }
 
 pub(crate) trait UserManager {
@@ -20,6 +23,10 @@ pub(crate) trait UserManager {
         atcoder_user_id: &str,
     ) -> Result<()>;
     fn get_internal_user_info(&self, internal_user_id: &str) -> Result<InternalUserInfo>;
+
+    /// Deletes the user and all the data owned by the user. If `transfer_contests` is set, the
+    /// virtual contests having other participants are handed over to one of them instead.
+    fn delete_user(&self, internal_user_id: &str, transfer_contests: bool) -> Result<()>;
 }
 
 impl UserManager for PgConnection {
@@ -46,4 +53,34 @@ impl UserManager for PgConnection {
             .first::<InternalUserInfo>(self)?;
         Ok(user_info)
     }
+
+    fn delete_user(&self, internal_user_id: &str, transfer_contests: bool) -> Result<()> {
+        self.transaction::<_, http_types::Error, _>(|| {
+            if transfer_contests {
+                let contest_ids = v_contests::table
+                    .filter(v_contests::internal_user_id.eq(internal_user_id))
+                    .select(v_contests::id)
+                    .load::<String>(self)?;
+                for contest_id in contest_ids.iter() {
+                    let new_owner = v_participants::table
+                        .filter(v_participants::internal_virtual_contest_id.eq(contest_id))
+                        .filter(v_participants::internal_user_id.ne(internal_user_id))
+                        .select(diesel::dsl::min(v_participants::internal_user_id))
+                        .first::<Option<String>>(self)?;
+                    if let Some(new_owner) = new_owner {
+                        update(v_contests::table.filter(v_contests::id.eq(contest_id)))
+                            .set(v_contests::internal_user_id.eq(new_owner))
+                            .execute(self)?;
+                    }
+                }
+            }
+
+            // Everything else owned by the user is removed by `ON DELETE CASCADE`.
+            delete(
+                internal_users::table.filter(internal_users::internal_user_id.eq(internal_user_id)),
+            )
+            .execute(self)?;
+            Ok(())
+        })
+    }
 }
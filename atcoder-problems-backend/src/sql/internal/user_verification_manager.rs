@@ -0,0 +1,121 @@
+use crate::error::Result;
+use crate::sql::schema::*;
+
+use crate::error::Error::Validation;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, update, PgConnection, Queryable};
+use internal_user_verifications as u_verifications;
+use internal_users as i_users;
+
+#[derive(Queryable, Debug, PartialEq)]
+pub struct PendingVerification {
+    pub internal_user_id: String,
+    pub atcoder_user_id: String,
+    pub verification_code: String,
+}
+
+pub trait UserVerificationManager {
+    /// Starts a verification of the AtCoder user id registered to the internal user with
+    /// `verification_code`, and returns the AtCoder user id.
+    fn start_verification(
+        &self,
+        internal_user_id: &str,
+        verification_code: &str,
+        now: i64,
+    ) -> Result<String>;
+
+    /// Returns the verifications which have not been completed and were started at or after
+    /// `created_after`.
+    fn get_pending_verifications(&self, created_after: i64) -> Result<Vec<PendingVerification>>;
+    fn complete_verification(
+        &self,
+        internal_user_id: &str,
+        atcoder_user_id: &str,
+        now: i64,
+    ) -> Result<()>;
+
+    /// Returns the AtCoder user id of the internal user if its ownership has been verified.
+    fn get_verified_atcoder_user_id(&self, internal_user_id: &str) -> Result<Option<String>>;
+}
+
+impl UserVerificationManager for PgConnection {
+    fn start_verification(
+        &self,
+        internal_user_id: &str,
+        verification_code: &str,
+        now: i64,
+    ) -> Result<String> {
+        let atcoder_user_id = i_users::table
+            .filter(i_users::internal_user_id.eq(internal_user_id))
+            .select(i_users::atcoder_user_id)
+            .first::<Option<String>>(self)?
+            .ok_or_else(|| {
+                Validation("The AtCoder user id is not set.".to_owned()).into_http_error()
+            })?;
+        insert_into(u_verifications::table)
+            .values((
+                u_verifications::internal_user_id.eq(internal_user_id),
+                u_verifications::atcoder_user_id.eq(&atcoder_user_id),
+                u_verifications::verification_code.eq(verification_code),
+                u_verifications::created_epoch_second.eq(now),
+            ))
+            .on_conflict(u_verifications::internal_user_id)
+            .do_update()
+            .set((
+                u_verifications::atcoder_user_id.eq(excluded(u_verifications::atcoder_user_id)),
+                u_verifications::verification_code.eq(excluded(u_verifications::verification_code)),
+                u_verifications::created_epoch_second
+                    .eq(excluded(u_verifications::created_epoch_second)),
+                u_verifications::verified_epoch_second.eq(None::<i64>),
+            ))
+            .execute(self)?;
+        Ok(atcoder_user_id)
+    }
+
+    fn get_pending_verifications(&self, created_after: i64) -> Result<Vec<PendingVerification>> {
+        let verifications = u_verifications::table
+            .filter(u_verifications::verified_epoch_second.is_null())
+            .filter(u_verifications::created_epoch_second.ge(created_after))
+            .select((
+                u_verifications::internal_user_id,
+                u_verifications::atcoder_user_id,
+                u_verifications::verification_code,
+            ))
+            .load::<PendingVerification>(self)?;
+        Ok(verifications)
+    }
+
+    fn complete_verification(
+        &self,
+        internal_user_id: &str,
+        atcoder_user_id: &str,
+        now: i64,
+    ) -> Result<()> {
+        update(
+            u_verifications::table.filter(
+                u_verifications::internal_user_id
+                    .eq(internal_user_id)
+                    .and(u_verifications::atcoder_user_id.eq(atcoder_user_id)),
+            ),
+        )
+        .set(u_verifications::verified_epoch_second.eq(now))
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_verified_atcoder_user_id(&self, internal_user_id: &str) -> Result<Option<String>> {
+        let atcoder_user_id = u_verifications::table
+            .inner_join(
+                i_users::table.on(i_users::internal_user_id
+                    .eq(u_verifications::internal_user_id)
+                    .and(i_users::atcoder_user_id.eq(u_verifications::atcoder_user_id.nullable()))),
+            )
+            .filter(u_verifications::internal_user_id.eq(internal_user_id))
+            .filter(u_verifications::verified_epoch_second.is_not_null())
+            .select(u_verifications::atcoder_user_id)
+            .first::<String>(self)
+            .optional()?;
+        Ok(atcoder_user_id)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::Result;
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::schema::*;
+
+use crate::error::Error::{Unauthorized, Validation};
+use diesel::expression::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection, Queryable};
+use internal_virtual_contest_announcements as v_announcements;
+use serde::Serialize;
+use uuid::Uuid;
+
+const MAX_ANNOUNCEMENT_NUM_PER_CONTEST: i64 = 100;
+const MAX_MESSAGE_LENGTH: usize = 1000;
+
+#[derive(Serialize, Queryable)]
+pub(crate) struct VirtualContestAnnouncement {
+    id: String,
+    message: String,
+    epoch_second: i64,
+}
+
+pub(crate) trait VirtualContestAnnouncementManager {
+    fn post_announcement(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        message: &str,
+        epoch_second: i64,
+    ) -> Result<String>;
+    fn delete_announcement(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        announcement_id: &str,
+    ) -> Result<()>;
+    fn get_announcements(&self, contest_id: &str) -> Result<Vec<VirtualContestAnnouncement>>;
+}
+
+impl VirtualContestAnnouncementManager for PgConnection {
+    fn post_announcement(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        message: &str,
+        epoch_second: i64,
+    ) -> Result<String> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        if message.is_empty() || message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(Validation(format!(
+                "The message must have 1 to {} characters.",
+                MAX_MESSAGE_LENGTH
+            ))
+            .into_http_error());
+        }
+        let count = v_announcements::table
+            .filter(v_announcements::internal_virtual_contest_id.eq(contest_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_ANNOUNCEMENT_NUM_PER_CONTEST {
+            return Err(Validation(format!(
+                "A contest can have at most {} announcements.",
+                MAX_ANNOUNCEMENT_NUM_PER_CONTEST
+            ))
+            .into_http_error());
+        }
+
+        let uuid = Uuid::new_v4().to_string();
+        insert_into(v_announcements::table)
+            .values(vec![(
+                v_announcements::id.eq(&uuid),
+                v_announcements::internal_virtual_contest_id.eq(contest_id),
+                v_announcements::message.eq(message),
+                v_announcements::epoch_second.eq(epoch_second),
+            )])
+            .execute(self)?;
+        Ok(uuid)
+    }
+
+    fn delete_announcement(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        announcement_id: &str,
+    ) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        delete(
+            v_announcements::table.filter(
+                v_announcements::id
+                    .eq(announcement_id)
+                    .and(v_announcements::internal_virtual_contest_id.eq(contest_id)),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_announcements(&self, contest_id: &str) -> Result<Vec<VirtualContestAnnouncement>> {
+        let announcements = v_announcements::table
+            .filter(v_announcements::internal_virtual_contest_id.eq(contest_id))
+            .order_by(v_announcements::epoch_second.desc())
+            .select((
+                v_announcements::id,
+                v_announcements::message,
+                v_announcements::epoch_second,
+            ))
+            .load::<VirtualContestAnnouncement>(self)?;
+        Ok(announcements)
+    }
+}
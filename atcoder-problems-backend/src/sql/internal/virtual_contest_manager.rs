@@ -1,9 +1,13 @@
 use crate::error::Result;
+use crate::sql::internal::DELETED_RETENTION_SECOND;
 use crate::sql::schema::*;
 
-use crate::error::ErrorTypes::InvalidRequest;
+use crate::error::Error::{NotFound, Unauthorized, Validation};
+use crate::sql::internal::virtual_contest_training_manager::TRAINING_MODE;
+use diesel::dsl::sql;
 use diesel::expression::dsl::count_star;
 use diesel::prelude::*;
+use diesel::sql_types::Bool;
 use diesel::Queryable;
 use diesel::{delete, insert_into, update, PgConnection};
 use internal_users as i_users;
@@ -14,8 +18,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
-const MAX_PROBLEM_NUM_PER_CONTEST: usize = 100;
+pub(crate) const MAX_PROBLEM_NUM_PER_CONTEST: usize = 100;
 const RECENT_CONTEST_NUM: i64 = 1000;
+/// The condition of the open-ended contests, whose `mode` is `TRAINING_MODE`. They are running
+/// once they start.
+pub(crate) const OPEN_ENDED_CONDITION: &str =
+    "internal_virtual_contests.mode IS NOT DISTINCT FROM 'training'";
 
 type VirtualContestTuple = (
     String,         //id
@@ -45,6 +53,29 @@ pub struct VirtualContestInfo {
 
     #[serde(skip_serializing)]
     pub(crate) is_public: bool,
+
+    #[serde(skip_serializing)]
+    pub(crate) invite_only: bool,
+
+    #[serde(skip_serializing)]
+    pub(crate) invite_token: Option<String>,
+
+    #[serde(skip_serializing)]
+    pub(crate) scoring: Option<String>,
+
+    #[serde(skip_serializing)]
+    pub(crate) deleted_epoch_second: Option<i64>,
+}
+
+impl VirtualContestInfo {
+    /// The end of the contest, or `None` if the contest is open-ended.
+    pub(crate) fn end_epoch_second(&self) -> Option<i64> {
+        if self.mode.as_deref() == Some(TRAINING_MODE) {
+            None
+        } else {
+            Some(self.start_epoch_second + self.duration_second)
+        }
+    }
 }
 
 #[deprecated(note = "want to migrate to VirtualContestInfo")]
@@ -58,14 +89,14 @@ pub struct VirtualContest {
     pub(crate) duration_second: i64,
     mode: Option<String>,
     pub(crate) problems: Vec<VirtualContestItem>,
-    participants: Vec<String>,
+    pub(crate) participants: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct VirtualContestItem {
     pub(crate) id: String,
-    point: Option<i64>,
-    order: Option<i64>,
+    pub(crate) point: Option<i64>,
+    pub(crate) order: Option<i64>,
 }
 
 pub trait VirtualContestManager {
@@ -93,7 +124,15 @@ pub trait VirtualContestManager {
     fn get_own_contests(&self, internal_user_id: &str) -> Result<Vec<VirtualContest>>;
     fn get_participated_contests(&self, internal_user_id: &str) -> Result<Vec<VirtualContest>>;
     fn get_single_contest(&self, contest_id: &str) -> Result<VirtualContest>;
+    fn get_single_contest_info(&self, contest_id: &str) -> Result<VirtualContestInfo>;
+    fn get_contest_items(&self, contest_id: &str) -> Result<Vec<VirtualContestItem>>;
+    fn is_contest_owner(&self, contest_id: &str, internal_user_id: &str) -> Result<bool>;
     fn get_recent_contest_info(&self) -> Result<Vec<VirtualContestInfo>>;
+    fn get_participated_contest_info(
+        &self,
+        internal_user_id: &str,
+    ) -> Result<Vec<VirtualContestInfo>>;
+    /// Returns the problems of the contests running at `time`, including the open-ended ones.
     fn get_running_contest_problems(&self, time: i64) -> Result<Vec<String>>;
 
     fn update_items(
@@ -104,6 +143,45 @@ pub trait VirtualContestManager {
     ) -> Result<()>;
 
     fn join_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<()>;
+
+    fn update_invite_token(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()>;
+    /// Sets whether the contest is open only to the owner, the participants and the users with
+    /// the invite token. It is independent of `is_public`, which lists the contest in the recent
+    /// contests.
+    fn update_invite_only(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_only: bool,
+    ) -> Result<()>;
+    fn check_contest_access(
+        &self,
+        contest_id: &str,
+        internal_user_id: Option<&str>,
+        invite_token: Option<&str>,
+    ) -> Result<()>;
+    fn update_scoring(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        scoring: Option<&str>,
+    ) -> Result<()>;
+
+    /// Copies the settings and the problems of the contest into a new private contest owned by
+    /// `internal_user_id`, and returns the id of the new contest.
+    fn clone_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<String>;
+
+    /// Marks the contest as deleted. It is hidden, but can be restored by `restore_contest` within
+    /// `DELETED_RETENTION_SECOND`.
+    fn delete_contest(&self, contest_id: &str, internal_user_id: &str, now: i64) -> Result<()>;
+    fn restore_contest(&self, contest_id: &str, internal_user_id: &str, now: i64) -> Result<()>;
+    /// Deletes the contests which have been deleted before `before` permanently.
+    fn purge_deleted_contests(&self, before: i64) -> Result<usize>;
 }
 
 impl VirtualContestManager for PgConnection {
@@ -165,6 +243,7 @@ impl VirtualContestManager for PgConnection {
                 i_users::table.on(v_participants::internal_user_id.eq(i_users::internal_user_id)),
             )
             .filter(v_contests::internal_user_id.eq(internal_user_id))
+            .filter(v_contests::deleted_epoch_second.is_null())
             .select((
                 v_contests::id,
                 v_contests::title,
@@ -199,6 +278,7 @@ impl VirtualContestManager for PgConnection {
                 i_users::table.on(v_participants::internal_user_id.eq(i_users::internal_user_id)),
             )
             .filter(v_contests::id.eq_any(participated_contest_ids))
+            .filter(v_contests::deleted_epoch_second.is_null())
             .select((
                 v_contests::id,
                 v_contests::title,
@@ -223,7 +303,12 @@ impl VirtualContestManager for PgConnection {
                 v_contests::table.on(v_items::internal_virtual_contest_id.eq(v_contests::id)),
             )
             .filter(v_contests::start_epoch_second.le(time))
-            .filter((v_contests::start_epoch_second + v_contests::duration_second).ge(time))
+            .filter(
+                (v_contests::start_epoch_second + v_contests::duration_second)
+                    .ge(time)
+                    .or(sql::<Bool>(OPEN_ENDED_CONDITION)),
+            )
+            .filter(v_contests::deleted_epoch_second.is_null())
             .select(v_items::problem_id)
             .load::<String>(self)?;
         Ok(problem_ids)
@@ -232,12 +317,31 @@ impl VirtualContestManager for PgConnection {
     fn get_recent_contest_info(&self) -> Result<Vec<VirtualContestInfo>> {
         let data = v_contests::table
             .filter(v_contests::is_public.eq(true))
+            .filter(v_contests::deleted_epoch_second.is_null())
             .order_by((v_contests::start_epoch_second + v_contests::duration_second).desc())
             .limit(RECENT_CONTEST_NUM)
             .load::<VirtualContestInfo>(self)?;
         Ok(data)
     }
 
+    fn get_participated_contest_info(
+        &self,
+        internal_user_id: &str,
+    ) -> Result<Vec<VirtualContestInfo>> {
+        let data = v_contests::table
+            .filter(
+                v_contests::id.eq_any(
+                    v_participants::table
+                        .filter(v_participants::internal_user_id.eq(internal_user_id))
+                        .select(v_participants::internal_virtual_contest_id),
+                ),
+            )
+            .filter(v_contests::deleted_epoch_second.is_null())
+            .order_by(v_contests::start_epoch_second)
+            .load::<VirtualContestInfo>(self)?;
+        Ok(data)
+    }
+
     fn get_single_contest(&self, contest_id: &str) -> Result<VirtualContest> {
         let data = v_contests::table
             .left_join(v_items::table.on(v_items::internal_virtual_contest_id.eq(v_contests::id)))
@@ -249,6 +353,7 @@ impl VirtualContestManager for PgConnection {
                 i_users::table.on(v_participants::internal_user_id.eq(i_users::internal_user_id)),
             )
             .filter(v_contests::id.eq(contest_id))
+            .filter(v_contests::deleted_epoch_second.is_null())
             .select((
                 v_contests::id,
                 v_contests::title,
@@ -267,10 +372,46 @@ impl VirtualContestManager for PgConnection {
         let contest = virtual_contests
             .into_iter()
             .next()
-            .ok_or_else(|| InvalidRequest)?;
+            .ok_or_else(|| NotFound.into_http_error())?;
         Ok(contest)
     }
 
+    fn get_single_contest_info(&self, contest_id: &str) -> Result<VirtualContestInfo> {
+        let info = v_contests::table
+            .filter(v_contests::id.eq(contest_id))
+            .filter(v_contests::deleted_epoch_second.is_null())
+            .first::<VirtualContestInfo>(self)?;
+        Ok(info)
+    }
+
+    fn get_contest_items(&self, contest_id: &str) -> Result<Vec<VirtualContestItem>> {
+        let items = v_items::table
+            .filter(v_items::internal_virtual_contest_id.eq(contest_id))
+            .select((
+                v_items::problem_id,
+                v_items::user_defined_point,
+                v_items::user_defined_order,
+            ))
+            .load::<(String, Option<i64>, Option<i64>)>(self)?
+            .into_iter()
+            .map(|(id, point, order)| VirtualContestItem { id, point, order })
+            .collect();
+        Ok(items)
+    }
+
+    fn is_contest_owner(&self, contest_id: &str, internal_user_id: &str) -> Result<bool> {
+        let count = v_contests::table
+            .filter(
+                v_contests::internal_user_id
+                    .eq(internal_user_id)
+                    .and(v_contests::id.eq(contest_id)),
+            )
+            .filter(v_contests::deleted_epoch_second.is_null())
+            .select(count_star())
+            .first::<i64>(self)?;
+        Ok(count > 0)
+    }
+
     fn update_items(
         &self,
         contest_id: &str,
@@ -278,7 +419,11 @@ impl VirtualContestManager for PgConnection {
         user_id: &str,
     ) -> Result<()> {
         if problems.len() > MAX_PROBLEM_NUM_PER_CONTEST {
-            return Err(http_types::Error::from(InvalidRequest));
+            return Err(Validation(format!(
+                "A contest can have at most {} problems.",
+                MAX_PROBLEM_NUM_PER_CONTEST
+            ))
+            .into_http_error());
         }
         v_contests::table
             .filter(
@@ -316,6 +461,140 @@ impl VirtualContestManager for PgConnection {
             .execute(self)?;
         Ok(())
     }
+
+    fn update_invite_token(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_token: Option<&str>,
+    ) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        update(v_contests::table.filter(v_contests::id.eq(contest_id)))
+            .set(v_contests::invite_token.eq(invite_token))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn update_invite_only(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        invite_only: bool,
+    ) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        update(v_contests::table.filter(v_contests::id.eq(contest_id)))
+            .set(v_contests::invite_only.eq(invite_only))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn check_contest_access(
+        &self,
+        contest_id: &str,
+        internal_user_id: Option<&str>,
+        invite_token: Option<&str>,
+    ) -> Result<()> {
+        let contest = self.get_single_contest_info(contest_id)?;
+        if !contest.invite_only {
+            return Ok(());
+        }
+        if let (Some(expected), Some(actual)) = (contest.invite_token.as_deref(), invite_token) {
+            if expected == actual {
+                return Ok(());
+            }
+        }
+        if let Some(internal_user_id) = internal_user_id {
+            if contest.owner_user_id == internal_user_id {
+                return Ok(());
+            }
+            let participated = v_participants::table
+                .filter(
+                    v_participants::internal_virtual_contest_id
+                        .eq(contest_id)
+                        .and(v_participants::internal_user_id.eq(internal_user_id)),
+                )
+                .select(count_star())
+                .first::<i64>(self)?;
+            if participated > 0 {
+                return Ok(());
+            }
+        }
+        Err(NotFound.into_http_error())
+    }
+
+    fn update_scoring(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        scoring: Option<&str>,
+    ) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        update(v_contests::table.filter(v_contests::id.eq(contest_id)))
+            .set(v_contests::scoring.eq(scoring))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn clone_contest(&self, contest_id: &str, internal_user_id: &str) -> Result<String> {
+        let contest = self.get_single_contest_info(contest_id)?;
+        let items = self.get_contest_items(contest_id)?;
+        self.transaction::<_, http_types::Error, _>(|| {
+            let new_contest_id = self.create_contest(
+                &contest.title,
+                &contest.memo,
+                internal_user_id,
+                contest.start_epoch_second,
+                contest.duration_second,
+                contest.mode.as_deref(),
+                false,
+            )?;
+            self.update_scoring(
+                &new_contest_id,
+                internal_user_id,
+                contest.scoring.as_deref(),
+            )?;
+            self.update_invite_only(&new_contest_id, internal_user_id, contest.invite_only)?;
+            self.update_items(&new_contest_id, &items, internal_user_id)?;
+            Ok(new_contest_id)
+        })
+    }
+
+    fn delete_contest(&self, contest_id: &str, internal_user_id: &str, now: i64) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(NotFound.into_http_error());
+        }
+        update(v_contests::table.filter(v_contests::id.eq(contest_id)))
+            .set(v_contests::deleted_epoch_second.eq(now))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn restore_contest(&self, contest_id: &str, internal_user_id: &str, now: i64) -> Result<()> {
+        let count = update(
+            v_contests::table
+                .filter(v_contests::id.eq(contest_id))
+                .filter(v_contests::internal_user_id.eq(internal_user_id))
+                .filter(v_contests::deleted_epoch_second.ge(now - DELETED_RETENTION_SECOND)),
+        )
+        .set(v_contests::deleted_epoch_second.eq(None::<i64>))
+        .execute(self)?;
+        if count == 0 {
+            return Err(NotFound.into_http_error());
+        }
+        Ok(())
+    }
+
+    fn purge_deleted_contests(&self, before: i64) -> Result<usize> {
+        let count = delete(v_contests::table.filter(v_contests::deleted_epoch_second.lt(before)))
+            .execute(self)?;
+        Ok(count)
+    }
 }
 
 fn construct_virtual_contests(data: Vec<VirtualContestTuple>) -> Vec<VirtualContest> {
@@ -0,0 +1,126 @@
+use crate::error::Result;
+use crate::sql::internal::virtual_contest_manager::OPEN_ENDED_CONDITION;
+use crate::sql::schema::*;
+
+use diesel::dsl::{not, sql};
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use diesel::{delete, insert_into, PgConnection, Queryable};
+use internal_virtual_contest_archives as v_archives;
+use internal_virtual_contest_results as v_results;
+use internal_virtual_contests as v_contests;
+use serde::Serialize;
+
+const MAX_ARCHIVED_CONTESTS_PER_RUN: i64 = 100;
+
+#[derive(Debug, Serialize, Queryable, PartialEq)]
+pub struct VirtualContestResult {
+    pub user_id: String,
+
+    /// The number of the participants ranked higher.
+    pub rank: i64,
+    pub score: f64,
+    pub penalty_second: i64,
+    pub solved_count: i32,
+    pub performance: Option<f64>,
+    pub simulated_rating: Option<f64>,
+}
+
+pub trait VirtualContestResultManager {
+    /// Returns the ids of the contests which have ended by `now` and are not archived yet. The
+    /// open-ended contests never end.
+    fn get_contests_to_archive(&self, now: i64) -> Result<Vec<String>>;
+
+    /// Replaces the results of the contest with the given ones, and marks it as archived.
+    fn save_results(
+        &self,
+        contest_id: &str,
+        results: &[VirtualContestResult],
+        now: i64,
+    ) -> Result<()>;
+
+    /// Loads the archived results ordered by the rank, or `None` if the contest is not archived.
+    fn get_results(&self, contest_id: &str) -> Result<Option<Vec<VirtualContestResult>>>;
+}
+
+impl VirtualContestResultManager for PgConnection {
+    fn get_contests_to_archive(&self, now: i64) -> Result<Vec<String>> {
+        let archived = v_archives::table.select(v_archives::internal_virtual_contest_id);
+        let contest_ids = v_contests::table
+            .filter((v_contests::start_epoch_second + v_contests::duration_second).le(now))
+            .filter(not(sql::<Bool>(OPEN_ENDED_CONDITION)))
+            .filter(not(v_contests::id.eq_any(archived)))
+            .filter(v_contests::deleted_epoch_second.is_null())
+            .order_by(v_contests::start_epoch_second)
+            .limit(MAX_ARCHIVED_CONTESTS_PER_RUN)
+            .select(v_contests::id)
+            .load::<String>(self)?;
+        Ok(contest_ids)
+    }
+
+    fn save_results(
+        &self,
+        contest_id: &str,
+        results: &[VirtualContestResult],
+        now: i64,
+    ) -> Result<()> {
+        let rows = results
+            .iter()
+            .map(|result| {
+                (
+                    v_results::internal_virtual_contest_id.eq(contest_id),
+                    v_results::user_id.eq(&result.user_id),
+                    v_results::rank.eq(result.rank),
+                    v_results::score.eq(result.score),
+                    v_results::penalty_second.eq(result.penalty_second),
+                    v_results::solved_count.eq(result.solved_count),
+                    v_results::performance.eq(result.performance),
+                    v_results::simulated_rating.eq(result.simulated_rating),
+                )
+            })
+            .collect::<Vec<_>>();
+        self.transaction::<_, http_types::Error, _>(|| {
+            delete(v_results::table)
+                .filter(v_results::internal_virtual_contest_id.eq(contest_id))
+                .execute(self)?;
+            if !rows.is_empty() {
+                insert_into(v_results::table).values(rows).execute(self)?;
+            }
+            insert_into(v_archives::table)
+                .values(vec![(
+                    v_archives::internal_virtual_contest_id.eq(contest_id),
+                    v_archives::archived_epoch_second.eq(now),
+                )])
+                .on_conflict(v_archives::internal_virtual_contest_id)
+                .do_nothing()
+                .execute(self)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn get_results(&self, contest_id: &str) -> Result<Option<Vec<VirtualContestResult>>> {
+        let archived = v_archives::table
+            .filter(v_archives::internal_virtual_contest_id.eq(contest_id))
+            .select(v_archives::archived_epoch_second)
+            .first::<i64>(self)
+            .optional()?;
+        if archived.is_none() {
+            return Ok(None);
+        }
+        let results = v_results::table
+            .filter(v_results::internal_virtual_contest_id.eq(contest_id))
+            .order_by((v_results::rank, v_results::user_id))
+            .select((
+                v_results::user_id,
+                v_results::rank,
+                v_results::score,
+                v_results::penalty_second,
+                v_results::solved_count,
+                v_results::performance,
+                v_results::simulated_rating,
+            ))
+            .load::<VirtualContestResult>(self)?;
+        Ok(Some(results))
+    }
+}
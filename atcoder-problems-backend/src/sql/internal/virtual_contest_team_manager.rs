@@ -0,0 +1,208 @@
+use crate::error::Result;
+use crate::sql::internal::virtual_contest_manager::VirtualContestManager;
+use crate::sql::schema::*;
+
+use crate::error::Error::{Conflict, NotFound, Unauthorized, Validation};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error::DatabaseError};
+use diesel::{delete, insert_into, PgConnection};
+use internal_virtual_contest_team_members as t_members;
+use internal_virtual_contest_teams as v_teams;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const MAX_TEAM_NUM_PER_CONTEST: usize = 200;
+const MAX_MEMBER_NUM_PER_TEAM: usize = 10;
+
+#[derive(Serialize)]
+pub(crate) struct VirtualContestTeam {
+    pub(crate) id: String,
+    pub(crate) team_name: String,
+    pub(crate) members: Vec<String>,
+}
+
+pub(crate) trait VirtualContestTeamManager {
+    fn create_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_name: &str,
+        members: &[String],
+    ) -> Result<String>;
+    fn update_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_id: &str,
+        team_name: &str,
+        members: &[String],
+    ) -> Result<()>;
+    fn delete_team(&self, contest_id: &str, internal_user_id: &str, team_id: &str) -> Result<()>;
+    fn get_teams(&self, contest_id: &str) -> Result<Vec<VirtualContestTeam>>;
+}
+
+impl VirtualContestTeamManager for PgConnection {
+    fn create_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_name: &str,
+        members: &[String],
+    ) -> Result<String> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        if members.len() > MAX_MEMBER_NUM_PER_TEAM {
+            return Err(Validation(format!(
+                "A team can have at most {} members.",
+                MAX_MEMBER_NUM_PER_TEAM
+            ))
+            .into_http_error());
+        }
+        let team_count = self.get_teams(contest_id)?.len();
+        if team_count >= MAX_TEAM_NUM_PER_CONTEST {
+            return Err(Validation(format!(
+                "A contest can have at most {} teams.",
+                MAX_TEAM_NUM_PER_CONTEST
+            ))
+            .into_http_error());
+        }
+
+        let team_id = Uuid::new_v4().to_string();
+        self.transaction::<_, http_types::Error, _>(|| {
+            insert_into(v_teams::table)
+                .values(vec![(
+                    v_teams::id.eq(&team_id),
+                    v_teams::internal_virtual_contest_id.eq(contest_id),
+                    v_teams::team_name.eq(team_name),
+                )])
+                .execute(self)?;
+            insert_members(self, contest_id, &team_id, members)?;
+            Ok(())
+        })?;
+        Ok(team_id)
+    }
+
+    fn update_team(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+        team_id: &str,
+        team_name: &str,
+        members: &[String],
+    ) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        if members.len() > MAX_MEMBER_NUM_PER_TEAM {
+            return Err(Validation(format!(
+                "A team can have at most {} members.",
+                MAX_MEMBER_NUM_PER_TEAM
+            ))
+            .into_http_error());
+        }
+        self.transaction::<_, http_types::Error, _>(|| {
+            let updated = diesel::update(
+                v_teams::table.filter(
+                    v_teams::id
+                        .eq(team_id)
+                        .and(v_teams::internal_virtual_contest_id.eq(contest_id)),
+                ),
+            )
+            .set(v_teams::team_name.eq(team_name))
+            .execute(self)?;
+            if updated == 0 {
+                return Err(NotFound.into_http_error());
+            }
+            delete(t_members::table.filter(t_members::team_id.eq(team_id))).execute(self)?;
+            insert_members(self, contest_id, team_id, members)?;
+            Ok(())
+        })
+    }
+
+    fn delete_team(&self, contest_id: &str, internal_user_id: &str, team_id: &str) -> Result<()> {
+        if !self.is_contest_owner(contest_id, internal_user_id)? {
+            return Err(Unauthorized.into_http_error());
+        }
+        delete(
+            v_teams::table.filter(
+                v_teams::id
+                    .eq(team_id)
+                    .and(v_teams::internal_virtual_contest_id.eq(contest_id)),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_teams(&self, contest_id: &str) -> Result<Vec<VirtualContestTeam>> {
+        let data = v_teams::table
+            .left_join(t_members::table.on(t_members::team_id.eq(v_teams::id)))
+            .filter(v_teams::internal_virtual_contest_id.eq(contest_id))
+            .select((
+                v_teams::id,
+                v_teams::team_name,
+                t_members::atcoder_user_id.nullable(),
+            ))
+            .load::<(String, String, Option<String>)>(self)?;
+
+        let mut map = BTreeMap::new();
+        for (team_id, team_name, member) in data.into_iter() {
+            let team = map
+                .entry(team_id)
+                .or_insert_with(|| (team_name, Vec::new()));
+            if let Some(member) = member {
+                team.1.push(member);
+            }
+        }
+        let teams = map
+            .into_iter()
+            .map(|(id, (team_name, mut members))| {
+                members.sort();
+                VirtualContestTeam {
+                    id,
+                    team_name,
+                    members,
+                }
+            })
+            .collect();
+        Ok(teams)
+    }
+}
+
+/// Inserts the members of the team. A user who is already a member of another team of the contest
+/// is a conflict.
+fn insert_members(
+    conn: &PgConnection,
+    contest_id: &str,
+    team_id: &str,
+    members: &[String],
+) -> Result<()> {
+    let mut members = members.iter().map(|m| m.trim()).collect::<Vec<_>>();
+    members.sort();
+    members.dedup();
+    insert_into(t_members::table)
+        .values(
+            members
+                .into_iter()
+                .filter(|member| !member.is_empty())
+                .map(|member| {
+                    (
+                        t_members::team_id.eq(team_id),
+                        t_members::internal_virtual_contest_id.eq(contest_id),
+                        t_members::atcoder_user_id.eq(member),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .execute(conn)
+        .map_err(|e| match e {
+            DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                Conflict("A user can be a member of only one team of a contest.".to_owned())
+                    .into_http_error()
+            }
+            e => e.into(),
+        })?;
+    Ok(())
+}
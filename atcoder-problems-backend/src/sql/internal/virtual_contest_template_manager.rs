@@ -0,0 +1,287 @@
+use crate::error::Result;
+use crate::sql::internal::virtual_contest_announcement_manager::VirtualContestAnnouncementManager;
+use crate::sql::internal::virtual_contest_manager::{VirtualContestItem, VirtualContestManager};
+use crate::sql::schema::*;
+use crate::sql::ProblemModelClient;
+
+use crate::error::Error::Validation;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, update, PgConnection, Queryable};
+use internal_users as i_users;
+use internal_virtual_contest_templates as v_templates;
+use serde::Serialize;
+use uuid::Uuid;
+
+const MAX_TEMPLATE_NUM_PER_USER: usize = 20;
+const MAX_PROBLEM_NUM_PER_TEMPLATE: usize = 30;
+const DIFFICULTY_TOLERANCE: f64 = 200.0;
+const MAX_CANDIDATE_NUM: usize = 100;
+
+const DAY_SECOND: i64 = 24 * 3600;
+const JST_OFFSET_SECOND: i64 = 9 * 3600;
+/// 1970-01-01 was Thursday, which is the 3rd day of a week starting from Monday.
+const EPOCH_WEEKDAY_FROM_MONDAY: i64 = 3;
+
+#[derive(Serialize, Queryable)]
+pub struct VirtualContestTemplate {
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub(crate) internal_user_id: String,
+    pub(crate) title: String,
+    pub(crate) memo: String,
+
+    /// 0 for Monday, 6 for Sunday in JST.
+    pub(crate) weekday: i32,
+    /// Minutes from 00:00 JST.
+    pub(crate) start_minute_of_day: i32,
+    pub(crate) duration_second: i64,
+    pub(crate) mode: Option<String>,
+    pub(crate) is_public: bool,
+    pub(crate) problem_difficulties: Vec<f64>,
+    pub(crate) last_start_epoch_second: Option<i64>,
+}
+
+impl VirtualContestTemplate {
+    pub fn next_start_epoch_second(&self, now: i64) -> i64 {
+        next_occurrence(
+            i64::from(self.weekday),
+            i64::from(self.start_minute_of_day),
+            now,
+        )
+    }
+}
+
+pub trait VirtualContestTemplateManager {
+    fn create_template(
+        &self,
+        internal_user_id: &str,
+        title: &str,
+        memo: &str,
+        weekday: i32,
+        start_minute_of_day: i32,
+        duration_second: i64,
+        mode: Option<&str>,
+        is_public: bool,
+        problem_difficulties: &[f64],
+    ) -> Result<String>;
+    fn delete_template(&self, internal_user_id: &str, template_id: &str) -> Result<()>;
+    fn get_own_templates(&self, internal_user_id: &str) -> Result<Vec<VirtualContestTemplate>>;
+    fn load_templates(&self) -> Result<Vec<VirtualContestTemplate>>;
+
+    /// Creates the next occurrence of the template as a virtual contest unless it has been
+    /// created already, and returns the id of the created contest. The contest is created with an
+    /// announcement of the template, so that the participants know where it comes from.
+    ///
+    /// It fails if no problems are selected for the template, rather than creating an empty
+    /// contest.
+    fn instantiate_template(
+        &self,
+        template: &VirtualContestTemplate,
+        now: i64,
+    ) -> Result<Option<String>>;
+}
+
+impl VirtualContestTemplateManager for PgConnection {
+    fn create_template(
+        &self,
+        internal_user_id: &str,
+        title: &str,
+        memo: &str,
+        weekday: i32,
+        start_minute_of_day: i32,
+        duration_second: i64,
+        mode: Option<&str>,
+        is_public: bool,
+        problem_difficulties: &[f64],
+    ) -> Result<String> {
+        if !(0..7).contains(&weekday)
+            || !(0..24 * 60).contains(&start_minute_of_day)
+            || duration_second <= 0
+            || problem_difficulties.is_empty()
+            || problem_difficulties.len() > MAX_PROBLEM_NUM_PER_TEMPLATE
+        {
+            return Err(Validation("The template is invalid.".to_owned()).into_http_error());
+        }
+        if self.get_own_templates(internal_user_id)?.len() >= MAX_TEMPLATE_NUM_PER_USER {
+            return Err(Validation(format!(
+                "A user can have at most {} templates.",
+                MAX_TEMPLATE_NUM_PER_USER
+            ))
+            .into_http_error());
+        }
+
+        let uuid = Uuid::new_v4().to_string();
+        insert_into(v_templates::table)
+            .values(vec![(
+                v_templates::id.eq(&uuid),
+                v_templates::internal_user_id.eq(internal_user_id),
+                v_templates::title.eq(title),
+                v_templates::memo.eq(memo),
+                v_templates::weekday.eq(weekday),
+                v_templates::start_minute_of_day.eq(start_minute_of_day),
+                v_templates::duration_second.eq(duration_second),
+                v_templates::mode.eq(mode),
+                v_templates::is_public.eq(is_public),
+                v_templates::problem_difficulties.eq(problem_difficulties),
+            )])
+            .execute(self)?;
+        Ok(uuid)
+    }
+
+    fn delete_template(&self, internal_user_id: &str, template_id: &str) -> Result<()> {
+        delete(
+            v_templates::table.filter(
+                v_templates::id
+                    .eq(template_id)
+                    .and(v_templates::internal_user_id.eq(internal_user_id)),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_own_templates(&self, internal_user_id: &str) -> Result<Vec<VirtualContestTemplate>> {
+        let templates = v_templates::table
+            .filter(v_templates::internal_user_id.eq(internal_user_id))
+            .load::<VirtualContestTemplate>(self)?;
+        Ok(templates)
+    }
+
+    fn load_templates(&self) -> Result<Vec<VirtualContestTemplate>> {
+        let templates = v_templates::table.load::<VirtualContestTemplate>(self)?;
+        Ok(templates)
+    }
+
+    fn instantiate_template(
+        &self,
+        template: &VirtualContestTemplate,
+        now: i64,
+    ) -> Result<Option<String>> {
+        let start_epoch_second = template.next_start_epoch_second(now);
+        if let Some(last) = template.last_start_epoch_second {
+            if last >= start_epoch_second {
+                return Ok(None);
+            }
+        }
+
+        let owner_atcoder_ids = i_users::table
+            .filter(i_users::internal_user_id.eq(&template.internal_user_id))
+            .select(i_users::atcoder_user_id)
+            .load::<Option<String>>(self)?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let owner_atcoder_ids = owner_atcoder_ids
+            .iter()
+            .map(|id| id.as_str())
+            .collect::<Vec<_>>();
+
+        let mut problems: Vec<VirtualContestItem> = Vec::new();
+        for &difficulty in template.problem_difficulties.iter() {
+            let candidates = self.select_unsolved_problems(
+                &owner_atcoder_ids,
+                difficulty - DIFFICULTY_TOLERANCE,
+                difficulty + DIFFICULTY_TOLERANCE,
+                MAX_CANDIDATE_NUM,
+            )?;
+
+            // Candidates are sorted by difficulty, so ones around the middle are close to the target.
+            let middle = candidates.len() / 2;
+            let mut indices = (0..candidates.len()).collect::<Vec<_>>();
+            indices.sort_by_key(|&i| (i as i64 - middle as i64).abs());
+            let selected = indices
+                .into_iter()
+                .map(|i| &candidates[i])
+                .find(|&candidate| problems.iter().all(|p| &p.id != candidate));
+            if let Some(problem_id) = selected {
+                problems.push(VirtualContestItem {
+                    id: problem_id.clone(),
+                    point: None,
+                    order: Some(problems.len() as i64),
+                });
+            }
+        }
+
+        if problems.is_empty() {
+            return Err(Validation(format!(
+                "No problems are selected for the template {}.",
+                template.id
+            ))
+            .into_http_error());
+        }
+
+        self.transaction::<_, http_types::Error, _>(|| {
+            // The template is locked, so that the schedulers running at the same time do not
+            // create the same occurrence twice.
+            let last_start_epoch_second = v_templates::table
+                .filter(v_templates::id.eq(&template.id))
+                .select(v_templates::last_start_epoch_second)
+                .for_update()
+                .first::<Option<i64>>(self)?;
+            if let Some(last) = last_start_epoch_second {
+                if last >= start_epoch_second {
+                    return Ok(None);
+                }
+            }
+
+            let contest_id = self.create_contest(
+                &template.title,
+                &template.memo,
+                &template.internal_user_id,
+                start_epoch_second,
+                template.duration_second,
+                template.mode.as_deref(),
+                template.is_public,
+            )?;
+            self.update_items(&contest_id, &problems, &template.internal_user_id)?;
+            self.post_announcement(
+                &contest_id,
+                &template.internal_user_id,
+                &format!(
+                    "This contest is scheduled from the weekly template \"{}\".",
+                    template.title
+                ),
+                now,
+            )?;
+            update(v_templates::table.filter(v_templates::id.eq(&template.id)))
+                .set(v_templates::last_start_epoch_second.eq(start_epoch_second))
+                .execute(self)?;
+            Ok(Some(contest_id))
+        })
+    }
+}
+
+/// Returns the first time strictly after `now` that is on `weekday` at `start_minute_of_day` in JST.
+fn next_occurrence(weekday: i64, start_minute_of_day: i64, now: i64) -> i64 {
+    let today = (now + JST_OFFSET_SECOND).div_euclid(DAY_SECOND);
+    (today..)
+        .filter(|day| (day + EPOCH_WEEKDAY_FROM_MONDAY).rem_euclid(7) == weekday)
+        .map(|day| day * DAY_SECOND + start_minute_of_day * 60 - JST_OFFSET_SECOND)
+        .find(|&start| start > now)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_occurrence() {
+        // 2020-06-06 (Sat) 12:00:00 JST
+        let now = 1_591_412_400;
+        let saturday = 5;
+        let sunday = 6;
+
+        // 2020-06-06 (Sat) 21:00:00 JST
+        assert_eq!(next_occurrence(saturday, 21 * 60, now), 1_591_444_800);
+        // 2020-06-13 (Sat) 09:00:00 JST
+        assert_eq!(next_occurrence(saturday, 9 * 60, now), 1_592_006_400);
+        // 2020-06-07 (Sun) 00:00:00 JST
+        assert_eq!(next_occurrence(sunday, 0, now), 1_591_455_600);
+        // exactly at the start time, the next week is returned.
+        assert_eq!(
+            next_occurrence(saturday, 12 * 60, now),
+            now + 7 * DAY_SECOND
+        );
+    }
+}
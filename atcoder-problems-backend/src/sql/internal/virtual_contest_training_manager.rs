@@ -0,0 +1,156 @@
+use crate::error::Result;
+use crate::sql::accepted_results;
+use crate::sql::internal::virtual_contest_manager::{
+    VirtualContestInfo, VirtualContestItem, VirtualContestManager,
+};
+use crate::sql::schema::*;
+
+use crate::error::Error::Validation;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection, Queryable};
+use internal_users as i_users;
+use internal_virtual_contest_training_progress as t_progress;
+use serde::Serialize;
+
+pub(crate) const TRAINING_MODE: &str = "training";
+
+#[derive(Serialize, Queryable)]
+pub(crate) struct SolvedTrainingProblem {
+    problem_id: String,
+    solved_epoch_second: i64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct TrainingProgress {
+    solved: Vec<SolvedTrainingProblem>,
+    pub(crate) unlocked: Vec<String>,
+}
+
+/// Problems of a training contest are unlocked one by one in their order: the first problem, and
+/// the problem after each solved one. A problem is solved when the user gets AC after it has been
+/// unlocked. Training contests have no end.
+pub(crate) trait VirtualContestTrainingManager {
+    /// Returns the progress of the user recorded by `update_training_progress`, or the progress
+    /// of nobody if `internal_user_id` is `None`.
+    fn get_training_progress(
+        &self,
+        contest_id: &str,
+        internal_user_id: Option<&str>,
+    ) -> Result<TrainingProgress>;
+
+    /// Records the problems newly solved by the user, and returns the progress of the user.
+    fn update_training_progress(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+    ) -> Result<TrainingProgress>;
+}
+
+impl VirtualContestTrainingManager for PgConnection {
+    fn get_training_progress(
+        &self,
+        contest_id: &str,
+        internal_user_id: Option<&str>,
+    ) -> Result<TrainingProgress> {
+        let (_, problems) = load_training_problems(self, contest_id)?;
+        let solved = match internal_user_id {
+            Some(internal_user_id) => load_solved_problems(self, contest_id, internal_user_id)?,
+            None => vec![],
+        };
+        let mut unlocked = Vec::new();
+        for problem in problems.into_iter() {
+            let is_solved = solved.iter().any(|p| p.problem_id == problem.id);
+            unlocked.push(problem.id);
+            if !is_solved {
+                break;
+            }
+        }
+        Ok(TrainingProgress { solved, unlocked })
+    }
+
+    fn update_training_progress(
+        &self,
+        contest_id: &str,
+        internal_user_id: &str,
+    ) -> Result<TrainingProgress> {
+        let (contest, problems) = load_training_problems(self, contest_id)?;
+        let atcoder_user_id = i_users::table
+            .filter(i_users::internal_user_id.eq(internal_user_id))
+            .select(i_users::atcoder_user_id)
+            .first::<Option<String>>(self)?;
+        let mut solved = load_solved_problems(self, contest_id, internal_user_id)?;
+
+        let mut unlocked = Vec::new();
+        let mut unlocked_epoch_second = contest.start_epoch_second;
+        for problem in problems.into_iter() {
+            unlocked.push(problem.id.clone());
+            if let Some(solved_problem) = solved.iter().find(|p| p.problem_id == problem.id) {
+                unlocked_epoch_second = solved_problem.solved_epoch_second;
+                continue;
+            }
+            let atcoder_user_id = match atcoder_user_id.as_ref() {
+                Some(atcoder_user_id) => atcoder_user_id,
+                None => break,
+            };
+            let solved_epoch_second = submissions::table
+                .filter(submissions::user_id.eq(atcoder_user_id))
+                .filter(submissions::problem_id.eq(&problem.id))
+                .filter(submissions::result.eq_any(accepted_results()))
+                .filter(submissions::epoch_second.ge(unlocked_epoch_second))
+                .select(diesel::dsl::min(submissions::epoch_second))
+                .first::<Option<i64>>(self)?;
+            match solved_epoch_second {
+                Some(solved_epoch_second) => {
+                    insert_into(t_progress::table)
+                        .values(vec![(
+                            t_progress::internal_virtual_contest_id.eq(contest_id),
+                            t_progress::internal_user_id.eq(internal_user_id),
+                            t_progress::problem_id.eq(&problem.id),
+                            t_progress::solved_epoch_second.eq(solved_epoch_second),
+                        )])
+                        .on_conflict_do_nothing()
+                        .execute(self)?;
+                    solved.push(SolvedTrainingProblem {
+                        problem_id: problem.id,
+                        solved_epoch_second,
+                    });
+                    unlocked_epoch_second = solved_epoch_second;
+                }
+                None => break,
+            }
+        }
+        Ok(TrainingProgress { solved, unlocked })
+    }
+}
+
+/// Loads the training contest and its problems in the order of unlocking.
+fn load_training_problems(
+    conn: &PgConnection,
+    contest_id: &str,
+) -> Result<(VirtualContestInfo, Vec<VirtualContestItem>)> {
+    let contest = conn.get_single_contest_info(contest_id)?;
+    if contest.mode.as_deref() != Some(TRAINING_MODE) {
+        return Err(
+            Validation("The contest is not in the training mode.".to_owned()).into_http_error(),
+        );
+    }
+    let mut problems = conn.get_contest_items(contest_id)?;
+    problems.sort_by(|a, b| {
+        (a.order.is_none(), a.order, &a.id).cmp(&(b.order.is_none(), b.order, &b.id))
+    });
+    Ok((contest, problems))
+}
+
+fn load_solved_problems(
+    conn: &PgConnection,
+    contest_id: &str,
+    internal_user_id: &str,
+) -> Result<Vec<SolvedTrainingProblem>> {
+    let solved = t_progress::table
+        .filter(t_progress::internal_virtual_contest_id.eq(contest_id))
+        .filter(t_progress::internal_user_id.eq(internal_user_id))
+        .order_by(t_progress::solved_epoch_second)
+        .select((t_progress::problem_id, t_progress::solved_epoch_second))
+        .load::<SolvedTrainingProblem>(conn)?;
+    Ok(solved)
+}
@@ -0,0 +1,65 @@
+use crate::error::Result;
+use crate::sql::schema::internal_watched_users as w_table;
+
+use crate::error::Error::Validation;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::{delete, insert_into, PgConnection};
+
+const MAX_WATCHED_USER_NUM: i64 = 100;
+const MAX_USER_ID_LENGTH: usize = 255;
+
+pub trait WatchListManager {
+    fn add_watched_user(&self, internal_user_id: &str, watched_user_id: &str) -> Result<()>;
+    fn remove_watched_user(&self, internal_user_id: &str, watched_user_id: &str) -> Result<()>;
+    fn get_watched_users(&self, internal_user_id: &str) -> Result<Vec<String>>;
+}
+
+impl WatchListManager for PgConnection {
+    fn add_watched_user(&self, internal_user_id: &str, watched_user_id: &str) -> Result<()> {
+        if watched_user_id.is_empty() || watched_user_id.len() > MAX_USER_ID_LENGTH {
+            return Err(Validation("The user id is invalid.".to_owned()).into_http_error());
+        }
+        let count = w_table::table
+            .filter(w_table::internal_user_id.eq(internal_user_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if count >= MAX_WATCHED_USER_NUM {
+            return Err(Validation(format!(
+                "A user can watch at most {} users.",
+                MAX_WATCHED_USER_NUM
+            ))
+            .into_http_error());
+        }
+        insert_into(w_table::table)
+            .values((
+                w_table::internal_user_id.eq(internal_user_id),
+                w_table::watched_user_id.eq(watched_user_id),
+            ))
+            .on_conflict((w_table::internal_user_id, w_table::watched_user_id))
+            .do_nothing()
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn remove_watched_user(&self, internal_user_id: &str, watched_user_id: &str) -> Result<()> {
+        delete(
+            w_table::table.filter(
+                w_table::internal_user_id
+                    .eq(internal_user_id)
+                    .and(w_table::watched_user_id.eq(watched_user_id)),
+            ),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn get_watched_users(&self, internal_user_id: &str) -> Result<Vec<String>> {
+        let users = w_table::table
+            .filter(w_table::internal_user_id.eq(internal_user_id))
+            .order_by(w_table::watched_user_id)
+            .select(w_table::watched_user_id)
+            .load::<String>(self)?;
+        Ok(users)
+    }
+}
@@ -0,0 +1,147 @@
+use super::models::{JobRun, UpdateTierStats};
+use super::schema::{job_runs, update_tier_stats};
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, update, PgConnection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    /// The process running the job stopped before the job finished.
+    Abandoned,
+}
+
+impl JobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+            JobStatus::Abandoned => "abandoned",
+        }
+    }
+}
+
+pub trait JobRunClient {
+    /// Records the start of a run and returns its id, or `None` if the job is still running.
+    fn start_job_run(&self, job_name: &str, now: i64) -> Result<Option<i32>>;
+    fn finish_job_run(
+        &self,
+        id: i32,
+        status: JobStatus,
+        message: Option<&str>,
+        now: i64,
+    ) -> Result<()>;
+    fn abandon_running_job_runs(&self, now: i64) -> Result<usize>;
+    /// Returns the latest run of each job.
+    fn load_latest_job_runs(&self) -> Result<Vec<JobRun>>;
+
+    /// Records a run of a tier of the delta updates.
+    fn record_update_tier_run(
+        &self,
+        tier: &str,
+        user_count: i32,
+        duration_millis: i64,
+        now: i64,
+    ) -> Result<()>;
+    fn load_update_tier_stats(&self) -> Result<Vec<UpdateTierStats>>;
+}
+
+impl JobRunClient for PgConnection {
+    fn start_job_run(&self, job_name: &str, now: i64) -> Result<Option<i32>> {
+        // The unique index on the running jobs rejects the overlapping run.
+        let ids = insert_into(job_runs::table)
+            .values((
+                job_runs::job_name.eq(job_name),
+                job_runs::status.eq(JobStatus::Running.as_str()),
+                job_runs::started_epoch_second.eq(now),
+            ))
+            .on_conflict_do_nothing()
+            .returning(job_runs::id)
+            .get_results::<i32>(self)?;
+        Ok(ids.into_iter().next())
+    }
+
+    fn finish_job_run(
+        &self,
+        id: i32,
+        status: JobStatus,
+        message: Option<&str>,
+        now: i64,
+    ) -> Result<()> {
+        update(job_runs::table.filter(job_runs::id.eq(id)))
+            .set((
+                job_runs::status.eq(status.as_str()),
+                job_runs::message.eq(message),
+                job_runs::finished_epoch_second.eq(now),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn abandon_running_job_runs(&self, now: i64) -> Result<usize> {
+        let count =
+            update(job_runs::table.filter(job_runs::status.eq(JobStatus::Running.as_str())))
+                .set((
+                    job_runs::status.eq(JobStatus::Abandoned.as_str()),
+                    job_runs::finished_epoch_second.eq(now),
+                ))
+                .execute(self)?;
+        Ok(count)
+    }
+
+    fn load_latest_job_runs(&self) -> Result<Vec<JobRun>> {
+        let runs = job_runs::table
+            .distinct_on(job_runs::job_name)
+            .order_by((
+                job_runs::job_name,
+                job_runs::started_epoch_second.desc(),
+                job_runs::id.desc(),
+            ))
+            .load::<JobRun>(self)?;
+        Ok(runs)
+    }
+
+    fn record_update_tier_run(
+        &self,
+        tier: &str,
+        user_count: i32,
+        duration_millis: i64,
+        now: i64,
+    ) -> Result<()> {
+        insert_into(update_tier_stats::table)
+            .values((
+                update_tier_stats::tier.eq(tier),
+                update_tier_stats::run_count.eq(1),
+                update_tier_stats::total_duration_millis.eq(duration_millis),
+                update_tier_stats::last_duration_millis.eq(duration_millis),
+                update_tier_stats::last_user_count.eq(user_count),
+                update_tier_stats::last_finished_epoch_second.eq(now),
+            ))
+            .on_conflict(update_tier_stats::tier)
+            .do_update()
+            .set((
+                update_tier_stats::run_count.eq(update_tier_stats::run_count + 1),
+                update_tier_stats::total_duration_millis
+                    .eq(update_tier_stats::total_duration_millis + duration_millis),
+                update_tier_stats::last_duration_millis
+                    .eq(excluded(update_tier_stats::last_duration_millis)),
+                update_tier_stats::last_user_count.eq(excluded(update_tier_stats::last_user_count)),
+                update_tier_stats::last_finished_epoch_second
+                    .eq(excluded(update_tier_stats::last_finished_epoch_second)),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_update_tier_stats(&self) -> Result<Vec<UpdateTierStats>> {
+        let stats = update_tier_stats::table
+            .order_by(update_tier_stats::tier)
+            .load::<UpdateTierStats>(self)?;
+        Ok(stats)
+    }
+}
@@ -0,0 +1,93 @@
+use super::{accepted_results, insert_chunks};
+use crate::error::Result;
+use crate::sql::models::{JudgeProblem, JudgeSubmission};
+use crate::sql::schema::{judge_problems, judge_submissions};
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Judge {
+    AtCoder,
+    Codeforces,
+    Aoj,
+    Yukicoder,
+}
+
+impl Judge {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Judge::AtCoder => "atcoder",
+            Judge::Codeforces => "codeforces",
+            Judge::Aoj => "aoj",
+            Judge::Yukicoder => "yukicoder",
+        }
+    }
+
+    pub fn parse(judge: &str) -> Option<Self> {
+        match judge {
+            "atcoder" => Some(Judge::AtCoder),
+            "codeforces" => Some(Judge::Codeforces),
+            "aoj" => Some(Judge::Aoj),
+            "yukicoder" => Some(Judge::Yukicoder),
+            _ => None,
+        }
+    }
+}
+
+/// Stores the submissions and the problems of the judges other than AtCoder, whose data live in
+/// `submissions` and `problems`.
+pub trait JudgeClient {
+    fn update_judge_submissions(&self, submissions: &[JudgeSubmission]) -> Result<usize>;
+    fn update_judge_problems(&self, problems: &[JudgeProblem]) -> Result<usize>;
+    fn get_judge_accepted_submissions(
+        &self,
+        judge: Judge,
+        user_id: &str,
+    ) -> Result<Vec<JudgeSubmission>>;
+}
+
+impl JudgeClient for PgConnection {
+    fn update_judge_submissions(&self, submissions: &[JudgeSubmission]) -> Result<usize> {
+        let mut count = 0;
+        for segment in insert_chunks(submissions, 7).into_iter() {
+            count += insert_into(judge_submissions::table)
+                .values(segment)
+                .on_conflict((judge_submissions::judge, judge_submissions::id))
+                .do_update()
+                .set(judge_submissions::result.eq(excluded(judge_submissions::result)))
+                .execute(self)?;
+        }
+        Ok(count)
+    }
+
+    fn update_judge_problems(&self, problems: &[JudgeProblem]) -> Result<usize> {
+        let mut count = 0;
+        for segment in insert_chunks(problems, 4).into_iter() {
+            count += insert_into(judge_problems::table)
+                .values(segment)
+                .on_conflict((judge_problems::judge, judge_problems::id))
+                .do_update()
+                .set((
+                    judge_problems::title.eq(excluded(judge_problems::title)),
+                    judge_problems::difficulty.eq(excluded(judge_problems::difficulty)),
+                ))
+                .execute(self)?;
+        }
+        Ok(count)
+    }
+
+    fn get_judge_accepted_submissions(
+        &self,
+        judge: Judge,
+        user_id: &str,
+    ) -> Result<Vec<JudgeSubmission>> {
+        let submissions = judge_submissions::table
+            .filter(judge_submissions::judge.eq(judge.as_str()))
+            .filter(judge_submissions::user_id.eq(user_id))
+            .filter(judge_submissions::result.eq_any(accepted_results()))
+            .load::<JudgeSubmission>(self)?;
+        Ok(submissions)
+    }
+}
@@ -1,8 +1,7 @@
+use super::insert_chunks;
 use super::models::{Submission, UserLanguageCount};
 use super::schema::language_count;
-use super::MAX_INSERT_ROWS;
 use crate::error::Result;
-use crate::utils::SplitToSegments;
 
 use diesel::dsl::*;
 use diesel::pg::upsert::excluded;
@@ -18,7 +17,7 @@ pub trait LanguageCountClient {
 
 impl LanguageCountClient for PgConnection {
     fn update_language_count(&self, submissions: &[Submission]) -> Result<()> {
-        let re = Regex::new(r"\d* \(.*\)").unwrap();
+        let re = language_version_regex();
         let language_count = submissions
             .iter()
             .map(|s| {
@@ -31,11 +30,7 @@ impl LanguageCountClient for PgConnection {
             .fold(
                 BTreeMap::new(),
                 |mut map, (user_id, problem_id, language)| {
-                    let simplified_language = if language.len() >= 5 && &language[..5] == "Perl6" {
-                        "Perl6".to_string()
-                    } else {
-                        re.replace(&language, "").to_string()
-                    };
+                    let simplified_language = simplify_language(&re, language);
                     map.entry((user_id, simplified_language))
                         .or_insert_with(BTreeSet::new)
                         .insert(problem_id);
@@ -52,10 +47,7 @@ impl LanguageCountClient for PgConnection {
             })
             .collect::<Vec<_>>();
 
-        for segment in language_count
-            .split_into_segments(MAX_INSERT_ROWS)
-            .into_iter()
-        {
+        for segment in insert_chunks(&language_count, 3).into_iter() {
             insert_into(language_count::table)
                 .values(segment)
                 .on_conflict((language_count::user_id, language_count::simplified_language))
@@ -73,3 +65,20 @@ impl LanguageCountClient for PgConnection {
         Ok(count)
     }
 }
+
+/// Matches the versions and the compilers in the language names, e.g. `3 (3.8.2)` of
+/// `Python3 (3.8.2)`.
+pub(crate) fn language_version_regex() -> Regex {
+    Regex::new(r"\d* \(.*\)").unwrap()
+}
+
+/// Simplifies the language name by removing the version and the compiler with `re` of
+/// `language_version_regex`, so that the submissions of the different versions are counted
+/// together.
+pub(crate) fn simplify_language(re: &Regex, language: &str) -> String {
+    if language.len() >= 5 && &language[..5] == "Perl6" {
+        "Perl6".to_string()
+    } else {
+        re.replace(language, "").to_string()
+    }
+}
@@ -0,0 +1,76 @@
+use super::insert_chunks;
+use super::language_count::{language_version_regex, simplify_language};
+use super::models::{LanguageTrend, Submission};
+use super::schema::language_trends;
+use crate::error::Result;
+
+use chrono::{FixedOffset, TimeZone};
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+use std::collections::{BTreeMap, BTreeSet};
+
+const JST_OFFSET_SECOND: i32 = 9 * 3600;
+
+pub trait LanguageTrendClient {
+    /// Counts the AC submissions and the users who got them in each simplified language in each
+    /// month in JST, from all the AC submissions.
+    fn update_language_trends(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Returns the monthly counts of the language, or all the languages if it is `None`, in the
+    /// order of the months.
+    fn load_language_trends(&self, language: Option<&str>) -> Result<Vec<LanguageTrend>>;
+}
+
+impl LanguageTrendClient for PgConnection {
+    fn update_language_trends(&self, submissions: &[Submission]) -> Result<()> {
+        let re = language_version_regex();
+        let jst = FixedOffset::east(JST_OFFSET_SECOND);
+        let language_trends = submissions
+            .iter()
+            .fold(BTreeMap::new(), |mut map, s| {
+                let month = jst.timestamp(s.epoch_second, 0).format("%Y-%m").to_string();
+                let (count, user_ids) = map
+                    .entry((month, simplify_language(&re, &s.language)))
+                    .or_insert_with(|| (0_i64, BTreeSet::new()));
+                *count += 1;
+                user_ids.insert(s.user_id.as_str());
+                map
+            })
+            .into_iter()
+            .map(|((month, language), (count, user_ids))| {
+                (
+                    language_trends::month.eq(month),
+                    language_trends::simplified_language.eq(language),
+                    language_trends::submission_count.eq(count),
+                    language_trends::user_count.eq(user_ids.len() as i32),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for segment in insert_chunks(&language_trends, 4).into_iter() {
+            insert_into(language_trends::table)
+                .values(segment)
+                .on_conflict((language_trends::month, language_trends::simplified_language))
+                .do_update()
+                .set((
+                    language_trends::submission_count
+                        .eq(excluded(language_trends::submission_count)),
+                    language_trends::user_count.eq(excluded(language_trends::user_count)),
+                ))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_language_trends(&self, language: Option<&str>) -> Result<Vec<LanguageTrend>> {
+        let mut query = language_trends::table
+            .order_by((language_trends::month, language_trends::simplified_language))
+            .into_boxed();
+        if let Some(language) = language {
+            query = query.filter(language_trends::simplified_language.eq(language));
+        }
+        let trends = query.load::<LanguageTrend>(self)?;
+        Ok(trends)
+    }
+}
@@ -0,0 +1,34 @@
+use super::schema::submissions;
+use super::validate_table_name;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::{sql_query, PgConnection};
+
+pub trait MaintenanceClient {
+    /// Rebuilds the indexes of the table, or of all the tables if `table` is `None`.
+    fn rebuild_indexes(&self, table: Option<&str>) -> Result<()>;
+    /// Returns the time of the latest crawled submission.
+    fn load_latest_submission_epoch_second(&self) -> Result<Option<i64>>;
+}
+
+impl MaintenanceClient for PgConnection {
+    fn rebuild_indexes(&self, table: Option<&str>) -> Result<()> {
+        let query = match table {
+            Some(table) => {
+                validate_table_name(table)?;
+                format!("REINDEX TABLE {}", table)
+            }
+            None => "REINDEX SCHEMA public".to_owned(),
+        };
+        sql_query(query).execute(self)?;
+        Ok(())
+    }
+
+    fn load_latest_submission_epoch_second(&self) -> Result<Option<i64>> {
+        let epoch_second = submissions::table
+            .select(diesel::dsl::max(submissions::epoch_second))
+            .first::<Option<i64>>(self)?;
+        Ok(epoch_second)
+    }
+}
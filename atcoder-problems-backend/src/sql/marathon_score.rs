@@ -0,0 +1,126 @@
+use super::insert_chunks;
+use super::models::{Contest, MarathonBestScore, MarathonRank, Submission};
+use super::schema::{contests, marathon_best_scores, marathon_leaderboard};
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Text};
+use diesel::{insert_into, sql_query, Connection, PgConnection};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub trait MarathonScoreClient {
+    /// Aggregates the best scores of the users for the problems of the heuristic contests from
+    /// the AC submissions sorted by id, and ranks the users of the updated contests again.
+    fn update_marathon_scores(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Returns the best scores of the user in the order of the contest ids and the problem ids.
+    fn load_users_marathon_scores(&self, user_id: &str) -> Result<Vec<MarathonBestScore>>;
+
+    /// Returns the leaderboard of the heuristic contest in the order of the rank.
+    fn load_marathon_leaderboard(&self, contest_id: &str) -> Result<Vec<MarathonRank>>;
+}
+
+impl MarathonScoreClient for PgConnection {
+    fn update_marathon_scores(&self, submissions: &[Submission]) -> Result<()> {
+        let heuristic_contest_ids = contests::table
+            .load::<Contest>(self)?
+            .into_iter()
+            .filter(|contest| contest.is_heuristic())
+            .map(|contest| contest.id)
+            .collect::<BTreeSet<_>>();
+
+        // The submissions are sorted by id, so that the first submission of the best score is
+        // kept.
+        let mut best_scores = BTreeMap::new();
+        for submission in submissions
+            .iter()
+            .filter(|s| heuristic_contest_ids.contains(&s.contest_id))
+        {
+            let key = (submission.user_id.as_str(), submission.problem_id.as_str());
+            let is_better = best_scores
+                .get(&key)
+                .map_or(true, |best: &&Submission| best.point < submission.point);
+            if is_better {
+                best_scores.insert(key, submission);
+            }
+        }
+        let best_scores = best_scores
+            .into_iter()
+            .map(|(_, s)| MarathonBestScore {
+                user_id: s.user_id.clone(),
+                problem_id: s.problem_id.clone(),
+                contest_id: s.contest_id.clone(),
+                score: s.point.as_f64(),
+                submission_id: s.id,
+                epoch_second: s.epoch_second,
+            })
+            .collect::<Vec<_>>();
+        let contest_ids = best_scores
+            .iter()
+            .map(|score| score.contest_id.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        self.transaction::<_, http_types::Error, _>(|| {
+            for segment in insert_chunks(&best_scores, 6).into_iter() {
+                insert_into(marathon_best_scores::table)
+                    .values(segment)
+                    .on_conflict((
+                        marathon_best_scores::user_id,
+                        marathon_best_scores::problem_id,
+                    ))
+                    .do_update()
+                    .set((
+                        marathon_best_scores::contest_id
+                            .eq(excluded(marathon_best_scores::contest_id)),
+                        marathon_best_scores::score.eq(excluded(marathon_best_scores::score)),
+                        marathon_best_scores::submission_id
+                            .eq(excluded(marathon_best_scores::submission_id)),
+                        marathon_best_scores::epoch_second
+                            .eq(excluded(marathon_best_scores::epoch_second)),
+                    ))
+                    .execute(self)?;
+            }
+
+            sql_query("DELETE FROM marathon_leaderboard WHERE contest_id = ANY($1)")
+                .bind::<Array<Text>, _>(&contest_ids)
+                .execute(self)?;
+            sql_query(
+                r"
+                INSERT INTO marathon_leaderboard (contest_id, user_id, score, rank)
+                    SELECT
+                        contest_id,
+                        user_id,
+                        SUM(score),
+                        RANK() OVER (PARTITION BY contest_id ORDER BY SUM(score) DESC)
+                    FROM marathon_best_scores
+                    WHERE contest_id = ANY($1)
+                    GROUP BY contest_id, user_id",
+            )
+            .bind::<Array<Text>, _>(&contest_ids)
+            .execute(self)?;
+            Ok(())
+        })
+    }
+
+    fn load_users_marathon_scores(&self, user_id: &str) -> Result<Vec<MarathonBestScore>> {
+        let scores = marathon_best_scores::table
+            .filter(marathon_best_scores::user_id.eq(user_id))
+            .order_by((
+                marathon_best_scores::contest_id,
+                marathon_best_scores::problem_id,
+            ))
+            .load::<MarathonBestScore>(self)?;
+        Ok(scores)
+    }
+
+    fn load_marathon_leaderboard(&self, contest_id: &str) -> Result<Vec<MarathonRank>> {
+        let leaderboard = marathon_leaderboard::table
+            .filter(marathon_leaderboard::contest_id.eq(contest_id))
+            .order_by((marathon_leaderboard::rank, marathon_leaderboard::user_id))
+            .load::<MarathonRank>(self)?;
+        Ok(leaderboard)
+    }
+}
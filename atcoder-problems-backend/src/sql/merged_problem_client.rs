@@ -0,0 +1,69 @@
+use super::models::{DetailedProblem, MergedProblem};
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::{sql_query, PgConnection};
+
+pub trait MergedProblemClient {
+    /// Returns the problems merged with their points, solver counts and first/fastest/shortest
+    /// submissions, ordered by id.
+    fn load_merged_problems(&self) -> Result<Vec<MergedProblem>>;
+
+    /// Returns the merged problems with their difficulties, ordered by id.
+    fn load_detailed_problems(&self) -> Result<Vec<DetailedProblem>>;
+}
+
+impl MergedProblemClient for PgConnection {
+    fn load_merged_problems(&self) -> Result<Vec<MergedProblem>> {
+        let problems = self
+            .load_detailed_problems()?
+            .into_iter()
+            .map(|problem| problem.merged)
+            .collect();
+        Ok(problems)
+    }
+
+    fn load_detailed_problems(&self) -> Result<Vec<DetailedProblem>> {
+        let problems = sql_query(
+            r"
+            SELECT
+                problems.id,
+                problems.contest_id,
+                problems.title,
+                shortest.submission_id AS shortest_submission_id,
+                shortest.problem_id AS shortest_problem_id,
+                shortest.contest_id AS shortest_contest_id,
+                shortest_submissions.user_id AS shortest_user_id,
+                fastest.submission_id AS fastest_submission_id,
+                fastest.problem_id AS fastest_problem_id,
+                fastest.contest_id AS fastest_contest_id,
+                fastest_submissions.user_id AS fastest_user_id,
+                first.submission_id AS first_submission_id,
+                first.problem_id AS first_problem_id,
+                first.contest_id AS first_contest_id,
+                first_submissions.user_id AS first_user_id,
+                shortest_submissions.length AS source_code_length,
+                fastest_submissions.execution_time AS execution_time,
+                points.point,
+                points.predict,
+                solver.user_count AS solver_count,
+                problem_models.difficulty,
+                problem_models.is_experimental
+            FROM
+                problems
+                LEFT JOIN shortest ON shortest.problem_id = problems.id
+                LEFT JOIN fastest ON fastest.problem_id = problems.id
+                LEFT JOIN first ON first.problem_id = problems.id
+                LEFT JOIN submissions AS shortest_submissions ON shortest.submission_id = shortest_submissions.id
+                LEFT JOIN submissions AS fastest_submissions ON fastest.submission_id = fastest_submissions.id
+                LEFT JOIN submissions AS first_submissions ON first.submission_id = first_submissions.id
+                LEFT JOIN points ON points.problem_id = problems.id
+                LEFT JOIN solver ON solver.problem_id = problems.id
+                LEFT JOIN problem_models ON problem_models.problem_id = problems.id
+                ORDER BY problems.id;
+            ",
+        )
+        .load::<DetailedProblem>(self)?;
+        Ok(problems)
+    }
+}
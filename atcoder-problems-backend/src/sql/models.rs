@@ -1,8 +1,16 @@
 use super::schema::*;
 use super::{FIRST_AGC_EPOCH_SECOND, UNRATED_STATE};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use diesel::deserialize::{self, FromSql};
+use diesel::pg::Pg;
+use diesel::serialize::{self, Output, ToSql};
 use diesel::sql_types::*;
 use diesel::Queryable;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::io::Write;
+use std::iter::Sum;
+use std::ops::Add;
 
 #[derive(Default, Debug, Eq, PartialEq, Queryable, Insertable, Serialize)]
 pub struct Contest {
@@ -15,10 +23,138 @@ pub struct Contest {
 
 impl Contest {
     pub fn is_rated(&self) -> bool {
-        self.start_epoch_second >= FIRST_AGC_EPOCH_SECOND && self.rate_change != UNRATED_STATE
+        self.rated_range().is_some()
+    }
+
+    /// The range of the ratings of the rated participants, or `None` if the contest is unrated.
+    /// The contests before the first AGC are unrated, since the current rating started with it.
+    pub fn rated_range(&self) -> Option<RatedRange> {
+        if self.start_epoch_second < FIRST_AGC_EPOCH_SECOND {
+            return None;
+        }
+        RatedRange::parse(&self.rate_change)
+    }
+
+    /// Classifies the contest in the same way as the contest table of the frontend.
+    pub fn category(&self) -> ContestCategory {
+        if self.is_numbered("abc") {
+            ContestCategory::Abc
+        } else if self.is_numbered("arc") {
+            ContestCategory::Arc
+        } else if self.is_numbered("agc") {
+            ContestCategory::Agc
+        } else if self.is_rated() {
+            ContestCategory::OtherRated
+        } else if self.id.starts_with("past") {
+            ContestCategory::Past
+        } else if self.id.starts_with("joi") {
+            ContestCategory::Joi
+        } else if self.id.starts_with("jag") || self.id.starts_with("JAG") {
+            ContestCategory::Jag
+        } else if self.is_marathon() {
+            ContestCategory::Marathon
+        } else {
+            ContestCategory::Other
+        }
+    }
+
+    /// Whether the contest is a heuristic one, e.g. AHC, whose points are the scores of the
+    /// optimization rather than the points of the problems.
+    pub fn is_heuristic(&self) -> bool {
+        self.is_numbered("ahc") || self.is_marathon()
+    }
+
+    fn is_numbered(&self, prefix: &str) -> bool {
+        self.id.len() == prefix.len() + 3
+            && self.id.starts_with(prefix)
+            && self.id[prefix.len()..].bytes().all(|c| c.is_ascii_digit())
+    }
+
+    fn is_marathon(&self) -> bool {
+        const MARATHON_IDS: [&str; 4] = [
+            "caddi2019",
+            "pakencamp-2019-day2",
+            "kuronekoyamato-contest2019",
+            "wn2017_1",
+        ];
+        self.title.starts_with("Chokudai Contest")
+            || self.title.contains("ハーフマラソン")
+            || self.title.starts_with("HACK TO THE FUTURE")
+            || self.title.contains("Asprova")
+            || self.id.starts_with("future-meets-you-contest")
+            || self.id.starts_with("hokudai-hitachi")
+            || MARATHON_IDS.contains(&self.id.as_str())
     }
 }
 
+/// The bounds of the ratings of the rated participants of a contest, both of which are inclusive
+/// and `None` if unbounded, e.g. ` ~ 1999` of ABC is `max_rating: Some(1999)`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize)]
+pub struct RatedRange {
+    pub min_rating: Option<i32>,
+    pub max_rating: Option<i32>,
+}
+
+impl RatedRange {
+    /// Parses `rate_change` of a contest, which is `-` if unrated, `All` if rated for everyone,
+    /// and `min ~ max` with either of the bounds omitted otherwise. The same as the trigger
+    /// `parse_contest_rate_change` of the database.
+    pub fn parse(rate_change: &str) -> Option<RatedRange> {
+        if rate_change.trim() == UNRATED_STATE {
+            return None;
+        }
+        let mut bounds = rate_change.splitn(2, '~');
+        match (bounds.next(), bounds.next()) {
+            (Some(min), Some(max)) => Some(RatedRange {
+                min_rating: min.trim().parse().ok(),
+                max_rating: max.trim().parse().ok(),
+            }),
+            _ => Some(RatedRange::default()),
+        }
+    }
+
+    pub fn contains(&self, rating: i32) -> bool {
+        self.min_rating.map_or(true, |min| min <= rating)
+            && self.max_rating.map_or(true, |max| rating <= max)
+    }
+}
+
+/// The rated range of a contest stored by the database, which is `rated: false` and the bounds
+/// `None` if the contest is unrated.
+#[derive(Debug, PartialEq, QueryableByName, Serialize)]
+pub struct ContestRatedRange {
+    #[sql_type = "Varchar"]
+    pub contest_id: String,
+    #[sql_type = "Bool"]
+    pub rated: bool,
+    #[sql_type = "Nullable<Integer>"]
+    pub min_rating: Option<i32>,
+    #[sql_type = "Nullable<Integer>"]
+    pub max_rating: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum ContestCategory {
+    #[serde(rename = "ABC")]
+    Abc,
+    #[serde(rename = "ARC")]
+    Arc,
+    #[serde(rename = "AGC")]
+    Agc,
+    #[serde(rename = "Other Rated Contests")]
+    OtherRated,
+    #[serde(rename = "PAST")]
+    Past,
+    #[serde(rename = "JOI")]
+    Joi,
+    #[serde(rename = "JAG")]
+    Jag,
+    #[serde(rename = "Marathon")]
+    Marathon,
+    #[serde(rename = "Other Contests")]
+    Other,
+}
+
 #[derive(Debug, Eq, PartialEq, Queryable, Insertable, Serialize)]
 pub struct Problem {
     pub id: String,
@@ -26,6 +162,35 @@ pub struct Problem {
     pub title: String,
 }
 
+#[derive(Debug, PartialEq, Queryable, Insertable, Serialize)]
+pub struct ProblemModel {
+    pub problem_id: String,
+    pub difficulty: Option<f64>,
+    pub is_experimental: bool,
+}
+
+/// How well the difficulty of a problem is fitted: the number of the users in the fit, the log
+/// likelihood of their results, and the variance of the estimated difficulty.
+#[derive(Debug, PartialEq, Queryable, Insertable, Serialize)]
+#[table_name = "problem_model_diagnostics"]
+pub struct ProblemModelDiagnostics {
+    pub problem_id: String,
+    pub sample_size: i32,
+    pub log_likelihood: Option<f64>,
+    pub variance: Option<f64>,
+}
+
+/// A difficulty estimated from the results of the participants of the contest, until the problem
+/// models have the difficulty of the problem.
+#[derive(Debug, PartialEq, Queryable, Insertable, Serialize)]
+#[table_name = "provisional_difficulties"]
+pub struct ProvisionalDifficulty {
+    pub problem_id: String,
+    pub difficulty: f64,
+    pub sample_size: i32,
+    pub estimated_epoch_second: i64,
+}
+
 #[derive(Debug, Queryable, Insertable, Clone, Serialize, Default, Deserialize)]
 pub struct Submission {
     pub id: i64,
@@ -34,12 +199,182 @@ pub struct Submission {
     pub contest_id: String,
     pub user_id: String,
     pub language: String,
-    pub point: f64,
+    pub point: Point,
     pub length: i32,
     pub result: String,
     pub execution_time: Option<i32>,
 }
 
+impl Submission {
+    pub fn verdict(&self) -> Verdict {
+        Verdict::from_result(&self.result)
+    }
+}
+
+/// The canonical verdict of the raw result of a submission. `result_codes` in the database has
+/// the same mapping as `Verdict::RESULT_CODES`, so that the queries can find the verdicts too.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer,
+    TimeLimitExceeded,
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
+    QueryLimitExceeded,
+    RuntimeError,
+    CompileError,
+    PresentationError,
+    InternalError,
+    /// A final result without any of the verdicts above, e.g. `NG`.
+    Other,
+    /// The submission is still being judged, e.g. `WJ` or `3/10`, or the result is unknown.
+    Pending,
+}
+
+impl Verdict {
+    /// The raw results of the final verdicts. The others are pending.
+    pub const RESULT_CODES: [(&'static str, Verdict); 11] = [
+        ("AC", Verdict::Accepted),
+        ("WA", Verdict::WrongAnswer),
+        ("TLE", Verdict::TimeLimitExceeded),
+        ("MLE", Verdict::MemoryLimitExceeded),
+        ("OLE", Verdict::OutputLimitExceeded),
+        ("QLE", Verdict::QueryLimitExceeded),
+        ("RE", Verdict::RuntimeError),
+        ("CE", Verdict::CompileError),
+        ("PE", Verdict::PresentationError),
+        ("IE", Verdict::InternalError),
+        ("NG", Verdict::Other),
+    ];
+
+    pub fn from_result(result: &str) -> Verdict {
+        Verdict::RESULT_CODES
+            .iter()
+            .find(|(code, _)| *code == result)
+            .map(|(_, verdict)| *verdict)
+            .unwrap_or(Verdict::Pending)
+    }
+
+    /// The name of the verdict in `result_codes`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Accepted => "accepted",
+            Verdict::WrongAnswer => "wrong_answer",
+            Verdict::TimeLimitExceeded => "time_limit_exceeded",
+            Verdict::MemoryLimitExceeded => "memory_limit_exceeded",
+            Verdict::OutputLimitExceeded => "output_limit_exceeded",
+            Verdict::QueryLimitExceeded => "query_limit_exceeded",
+            Verdict::RuntimeError => "runtime_error",
+            Verdict::CompileError => "compile_error",
+            Verdict::PresentationError => "presentation_error",
+            Verdict::InternalError => "internal_error",
+            Verdict::Other => "other",
+            Verdict::Pending => "pending",
+        }
+    }
+
+    pub fn is_accepted(self) -> bool {
+        self == Verdict::Accepted
+    }
+
+    /// Whether the result will not change, so that the submission does not have to be crawled
+    /// again.
+    pub fn is_final(self) -> bool {
+        self != Verdict::Pending
+    }
+}
+
+/// A point of a problem, or a sum of the points, in the hundredths. The points have at most 2
+/// decimal places, e.g. the partial scores of `150.25`, and they are stored in the
+/// `NUMERIC(18, 2)` columns, so that the points and their sums are kept exactly. It is serialized
+/// as a JSON number.
+#[derive(
+    Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, AsExpression, FromSqlRow,
+)]
+#[sql_type = "Numeric"]
+pub struct Point(i64);
+
+impl Point {
+    /// The scale factor from the points to the hundredths.
+    const SCALE: i64 = 100;
+
+    pub fn from_hundredths(hundredths: i64) -> Self {
+        Point(hundredths)
+    }
+
+    pub fn hundredths(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+}
+
+/// Rounds the point to the hundredths, e.g. the points of the crawled submissions.
+impl From<f64> for Point {
+    fn from(point: f64) -> Self {
+        Point((point * Self::SCALE as f64).round() as i64)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point(self.0 + rhs.0)
+    }
+}
+
+impl Sum for Point {
+    fn sum<I: Iterator<Item = Point>>(iter: I) -> Point {
+        Point(iter.map(|point| point.0).sum())
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_f64())
+    }
+}
+
+impl Serialize for Point {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Point {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Point::from)
+    }
+}
+
+impl FromSql<Numeric, Pg> for Point {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let decimal = BigDecimal::from_sql(bytes)?;
+        let (hundredths, _) = decimal.with_scale(2).into_bigint_and_exponent();
+        let hundredths = hundredths
+            .to_i64()
+            .ok_or_else(|| format!("The point {} is out of the range.", decimal))?;
+        Ok(Point(hundredths))
+    }
+}
+
+impl ToSql<Numeric, Pg> for Point {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        ToSql::<Numeric, Pg>::to_sql(&BigDecimal::new(self.0.into(), 2), out)
+    }
+}
+
+/// A raw result and the name of its verdict in `result_codes`.
+#[derive(Debug, Eq, PartialEq, Queryable)]
+pub struct ResultCode {
+    pub raw_result: String,
+    pub verdict: String,
+}
+
 #[derive(Debug, Eq, PartialEq, Queryable, Serialize)]
 pub struct UserLanguageCount {
     pub user_id: String,
@@ -57,13 +392,103 @@ pub struct UserProblemCount {
     pub problem_count: i32,
 }
 
+/// The numbers of the AC submissions in a language and the users who got them in a month in JST,
+/// e.g. `2020-01`.
+#[derive(Debug, Eq, PartialEq, Queryable, Serialize)]
+pub struct LanguageTrend {
+    pub month: String,
+    #[serde(rename = "language")]
+    pub simplified_language: String,
+    #[serde(rename = "count")]
+    pub submission_count: i64,
+    pub user_count: i32,
+}
+
+/// The number of the problems solved by a user whose difficulties are in the bucket from
+/// `difficulty_from`.
+#[derive(Debug, Eq, PartialEq, Queryable, Serialize)]
+pub struct UserDifficultyCount {
+    pub user_id: String,
+    pub difficulty_from: i64,
+    #[serde(rename = "count")]
+    pub problem_count: i32,
+}
+
+/// The number of the problems of a contest solved by a user, out of `problem_count` problems of
+/// the contest.
+#[derive(Debug, Eq, PartialEq, Queryable, Serialize)]
+pub struct UserContestCompletion {
+    pub user_id: String,
+    pub contest_id: String,
+    pub solved_count: i32,
+    pub problem_count: i32,
+}
+
+/// The best score of a user for a problem of a heuristic contest, and the first submission which
+/// got it.
+#[derive(Debug, PartialEq, Queryable, Insertable, Serialize)]
+#[table_name = "marathon_best_scores"]
+pub struct MarathonBestScore {
+    pub user_id: String,
+    pub problem_id: String,
+    pub contest_id: String,
+    pub score: f64,
+    pub submission_id: i64,
+    pub epoch_second: i64,
+}
+
+/// A user on the leaderboard of a heuristic contest, ranked by the sum of the best scores.
+#[derive(Debug, PartialEq, Queryable, Serialize)]
+pub struct MarathonRank {
+    pub contest_id: String,
+    pub user_id: String,
+    pub score: f64,
+    pub rank: i64,
+}
+
 #[derive(Debug, Queryable, Serialize)]
 pub struct UserSum {
     pub user_id: String,
-    pub point_sum: f64,
+    pub point_sum: Point,
+}
+
+/// A user in the ranking of the numbers of the first, fastest or shortest submissions held by
+/// the users. `rank` starts from 0 and the users of the same count share it.
+#[derive(Debug, PartialEq, QueryableByName, Serialize, Deserialize)]
+pub struct RankedUserCount {
+    #[sql_type = "Varchar"]
+    pub user_id: String,
+    #[sql_type = "BigInt"]
+    pub count: i64,
+    #[sql_type = "BigInt"]
+    pub rank: i64,
 }
 
-#[derive(Debug, QueryableByName, Serialize)]
+/// A user in the ranking of the rated point sum. `rank` starts from 0 and the users of the same
+/// point sum share it.
+#[derive(Debug, PartialEq, QueryableByName, Serialize, Deserialize)]
+pub struct RankedUserSum {
+    #[sql_type = "Varchar"]
+    pub user_id: String,
+    #[sql_type = "Numeric"]
+    pub point_sum: Point,
+    #[sql_type = "BigInt"]
+    pub rank: i64,
+}
+
+/// A problem of a contest with its difficulty and the number of the users who solved it during the
+/// contest, which are `None` if the problem has no model or no one submitted to it.
+#[derive(Debug, PartialEq, QueryableByName)]
+pub struct ContestProblemModel {
+    #[sql_type = "Varchar"]
+    pub problem_id: String,
+    #[sql_type = "Nullable<Float8>"]
+    pub difficulty: Option<f64>,
+    #[sql_type = "Nullable<Integer>"]
+    pub ac_user_count: Option<i32>,
+}
+
+#[derive(Debug, QueryableByName, Serialize, Deserialize)]
 pub struct MergedProblem {
     #[sql_type = "Varchar"]
     pub id: String,
@@ -99,14 +524,102 @@ pub struct MergedProblem {
     source_code_length: Option<i32>,
     #[sql_type = "Nullable<Int4>"]
     execution_time: Option<i32>,
-    #[sql_type = "Nullable<Float8>"]
-    point: Option<f64>,
+    #[sql_type = "Nullable<Numeric>"]
+    point: Option<Point>,
     #[sql_type = "Nullable<Float8>"]
     predict: Option<f64>,
     #[sql_type = "Nullable<Int4>"]
     solver_count: Option<i32>,
 }
 
+/// A merged problem with its difficulty, which is `None` if it has not been estimated.
+#[derive(Debug, QueryableByName, Serialize, Deserialize)]
+pub struct DetailedProblem {
+    #[diesel(embed)]
+    #[serde(flatten)]
+    pub merged: MergedProblem,
+    #[sql_type = "Nullable<Float8>"]
+    pub difficulty: Option<f64>,
+    #[sql_type = "Nullable<Bool>"]
+    pub is_experimental: Option<bool>,
+}
+
+/// A problem with its difficulty, point, solver count and the current top submissions.
+#[derive(Debug, Serialize)]
+pub struct ProblemDetail {
+    #[serde(flatten)]
+    pub problem: Problem,
+    pub difficulty: Option<f64>,
+    pub is_experimental: Option<bool>,
+    pub point: Option<Point>,
+    pub solver_count: Option<i32>,
+    /// The median of the seconds from the first submission to the first AC in practice.
+    pub solve_time_median_second: Option<f64>,
+    /// The number of the users whose solve times are aggregated.
+    pub solve_time_user_count: Option<i32>,
+    pub first: Option<Submission>,
+    /// The first AC submission after the contest.
+    pub first_practice: Option<Submission>,
+    pub fastest: Option<Submission>,
+    pub shortest: Option<Submission>,
+    pub solver_trend: Vec<DailySolverCount>,
+    pub length_distribution: Option<LengthDistribution>,
+}
+
+/// The distribution of the lengths of the shortest AC submissions of the users who solved a
+/// problem. `bucket_counts[i]` is the number of the users whose lengths are in
+/// `[min_length + i * bucket_width, min_length + (i + 1) * bucket_width)`.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct LengthDistribution {
+    #[serde(skip)]
+    pub problem_id: String,
+    pub user_count: i32,
+    pub min_length: i32,
+    pub first_quartile: f64,
+    pub median: f64,
+    pub third_quartile: f64,
+    pub max_length: i32,
+    pub bucket_width: i32,
+    pub bucket_counts: Vec<i32>,
+}
+
+/// The number of the users who solved a problem for the first time on the day starting at
+/// `epoch_second`.
+#[derive(Debug, PartialEq, QueryableByName, Serialize)]
+pub struct DailySolverCount {
+    #[sql_type = "BigInt"]
+    pub epoch_second: i64,
+    #[sql_type = "BigInt"]
+    pub new_solver_count: i64,
+}
+
+/// A change of the shortest submission of a problem. The previous one is `None` for the first
+/// record of the problem.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct ShortestRecord {
+    pub problem_id: String,
+    pub epoch_second: i64,
+    pub previous_submission_id: Option<i64>,
+    pub previous_user_id: Option<String>,
+    pub previous_length: Option<i32>,
+    pub submission_id: i64,
+    pub user_id: String,
+    pub length: i32,
+}
+
+/// A problem found by the problem search, with its difficulty if it is estimated.
+#[derive(Debug, PartialEq, QueryableByName, Serialize)]
+pub struct ProblemSearchResult {
+    #[sql_type = "Varchar"]
+    pub id: String,
+    #[sql_type = "Varchar"]
+    pub contest_id: String,
+    #[sql_type = "Varchar"]
+    pub title: String,
+    #[sql_type = "Nullable<Float8>"]
+    pub difficulty: Option<f64>,
+}
+
 #[derive(PartialEq, Debug, Queryable, Serialize, Insertable)]
 #[table_name = "contest_problem"]
 pub struct ContestProblem {
@@ -114,9 +627,238 @@ pub struct ContestProblem {
     pub problem_id: String,
 }
 
+/// The numbers of the users who submitted during a contest, and who solved all its problems
+/// during the contest.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct ContestStats {
+    pub contest_id: String,
+    pub participant_count: i32,
+    pub full_solve_count: i32,
+}
+
+/// The numbers of the users who submitted to a problem during a contest, and who solved it
+/// during the contest.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct ContestProblemStats {
+    pub contest_id: String,
+    pub problem_id: String,
+    pub submitter_count: i32,
+    pub ac_user_count: i32,
+}
+
 #[derive(PartialEq, Debug, Queryable, Serialize, Insertable)]
 #[table_name = "max_streaks"]
 pub struct UserStreak {
     pub user_id: String,
     pub streak: i64,
 }
+
+/// A submission to a judge other than AtCoder. `result` is normalized to the AtCoder notation,
+/// e.g. `AC` for accepted submissions.
+#[derive(Default, Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct JudgeSubmission {
+    pub judge: String,
+    pub id: i64,
+    pub epoch_second: i64,
+    pub problem_id: String,
+    pub user_id: String,
+    pub language: String,
+    pub result: String,
+}
+
+/// A result of a user for a problem in the official standings of an AtCoder contest. `penalty` is
+/// the number of the wrong submissions, and `elapsed_second` is the time of the last score update
+/// from the start of the contest, which is `None` when the user got no score.
+#[derive(Default, Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct Standing {
+    pub contest_id: String,
+    pub user_id: String,
+    pub problem_id: String,
+    pub rank: i32,
+    pub is_rated: bool,
+    pub score: f64,
+    pub penalty: i32,
+    pub elapsed_second: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct JudgeProblem {
+    pub judge: String,
+    pub id: String,
+    pub title: String,
+    pub difficulty: Option<f64>,
+}
+
+/// A static dump uploaded by `dump_json`.
+#[derive(Default, Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct Dump {
+    pub name: String,
+    pub url: String,
+    pub size: i64,
+    pub sha256: String,
+    pub generated_epoch_second: i64,
+}
+
+/// A runtime toggle of a feature. The features without a row use their defaults.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct FeatureFlag {
+    pub name: String,
+    pub enabled: bool,
+    pub updated_epoch_second: i64,
+}
+
+/// A problem frequently solved by the same users around the same time as `problem_id`.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct SimilarProblem {
+    pub problem_id: String,
+    pub similar_problem_id: String,
+    pub co_solver_count: i64,
+    pub score: f64,
+}
+
+/// A contest excluded from the aggregates, with the reason for the admins.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct ExcludedContest {
+    pub contest_id: String,
+    pub reason: String,
+    pub created_epoch_second: i64,
+}
+
+/// A run of a periodic job of `JobScheduler`.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct JobRun {
+    pub id: i32,
+    pub job_name: String,
+    pub status: String,
+    pub message: Option<String>,
+    pub started_epoch_second: i64,
+    pub finished_epoch_second: Option<i64>,
+}
+
+/// The runs of a tier of the delta updates: how many times it has run, how long it took, and how
+/// many users it updated last time.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct UpdateTierStats {
+    pub tier: String,
+    pub run_count: i64,
+    pub total_duration_millis: i64,
+    pub last_duration_millis: i64,
+    pub last_user_count: i32,
+    pub last_finished_epoch_second: i64,
+}
+
+/// The statistics of the whole site. `accepted_count` is the number of the pairs of the users
+/// and the problems they solved, and the active users are those who submitted in the last 7 or
+/// 30 days.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct SiteStats {
+    pub submission_count: i64,
+    pub accepted_count: i64,
+    pub weekly_active_user_count: i32,
+    pub monthly_active_user_count: i32,
+    pub problem_count: i32,
+    pub latest_submission_epoch_second: Option<i64>,
+    pub updated_epoch_second: i64,
+}
+
+/// The number of the requests of a route from a client app in the hour which starts at
+/// `hour_epoch_second`.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+#[table_name = "request_counts"]
+pub struct RequestCount {
+    pub hour_epoch_second: i64,
+    pub route: String,
+    pub client: String,
+    pub request_count: i64,
+}
+
+/// A violation of an invariant of the aggregate tables found by the validator.
+#[derive(Debug, Clone, PartialEq, Queryable, QueryableByName, Insertable, Serialize)]
+#[table_name = "validation_violations"]
+pub struct ValidationViolation {
+    pub check_name: String,
+    pub subject: String,
+    pub message: String,
+    pub detected_epoch_second: i64,
+}
+
+/// The version of a table, which is incremented every time the table is updated.
+#[derive(Debug, Clone, PartialEq, Queryable, Insertable, Serialize)]
+pub struct TableVersion {
+    pub name: String,
+    pub version: i64,
+    pub updated_epoch_second: i64,
+}
+
+/// The ranks of a user in the snapshot taken on the day starting at `epoch_second` in JST. The
+/// ranks are the numbers of the users ranked higher, and `None` if the user was not ranked.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct RankHistory {
+    pub user_id: String,
+    pub epoch_second: i64,
+    pub accepted_count_rank: Option<i64>,
+    pub rated_point_sum_rank: Option<i64>,
+}
+
+/// An achievement earned by a user at `epoch_second`.
+#[derive(Debug, Clone, PartialEq, Queryable, Serialize)]
+pub struct UserAchievement {
+    pub user_id: String,
+    pub achievement: String,
+    pub epoch_second: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let points = vec![Point::from(150.25), Point::from(99.75), Point::from(100.0)];
+        assert_eq!(points.into_iter().sum::<Point>(), Point::from(350.0));
+        assert_eq!(Point::from(0.1) + Point::from(0.2), Point::from(0.3));
+        assert_eq!(Point::from(33.33).hundredths(), 3333);
+        assert_eq!(Point::from(150.750_000_01), Point::from_hundredths(15075));
+        assert_eq!(Point::from_hundredths(3333).as_f64(), 33.33);
+        assert_eq!(Point::from_hundredths(15075).to_string(), "150.75");
+        assert_eq!(serde_json::to_string(&Point::from(100.0)).unwrap(), "100.0");
+        assert_eq!(
+            serde_json::from_str::<Point>("150.25").unwrap(),
+            Point::from_hundredths(15025)
+        );
+    }
+
+    #[test]
+    fn test_parse_rated_range() {
+        let range = |min_rating, max_rating| {
+            Some(RatedRange {
+                min_rating,
+                max_rating,
+            })
+        };
+        assert_eq!(RatedRange::parse("-"), None);
+        assert_eq!(RatedRange::parse("All"), range(None, None));
+        assert_eq!(RatedRange::parse(" ~ 1999"), range(None, Some(1999)));
+        assert_eq!(RatedRange::parse("1200 ~ "), range(Some(1200), None));
+        assert_eq!(
+            RatedRange::parse("1200 ~ 2799"),
+            range(Some(1200), Some(2799))
+        );
+
+        let abc = RatedRange::parse(" ~ 1999").unwrap();
+        assert!(abc.contains(1999));
+        assert!(!abc.contains(2000));
+        let arc = RatedRange::parse("1200 ~ 2799").unwrap();
+        assert!(!arc.contains(1199));
+        assert!(arc.contains(1200));
+        assert!(RatedRange::default().contains(4000));
+    }
+}
+
+/// The last submission processed by an incremental updater. The next run of the updater starts
+/// from the submissions after it.
+#[derive(Debug, Clone, Copy, PartialEq, Queryable, Serialize)]
+pub struct Watermark {
+    pub last_epoch_second: i64,
+    pub last_submission_id: i64,
+}
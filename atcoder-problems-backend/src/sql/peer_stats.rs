@@ -0,0 +1,104 @@
+use super::schema::accepted_count;
+use crate::error::Result;
+
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Int4, Varchar};
+use diesel::{sql_query, PgConnection};
+use std::collections::BTreeMap;
+
+/// The sums of the aggregates of the peers of a user, i.e. the other users whose accepted counts
+/// are in a range.
+#[derive(Debug, Default, PartialEq)]
+pub struct PeerStats {
+    pub user_count: i64,
+    /// The numbers of the problems solved by the peers in each difficulty bucket.
+    pub difficulty_counts: BTreeMap<i64, i64>,
+    /// The numbers of the problems of each contest solved by the peers.
+    pub contest_solved_counts: BTreeMap<String, i64>,
+}
+
+#[derive(QueryableByName)]
+struct DifficultySum {
+    #[sql_type = "BigInt"]
+    difficulty_from: i64,
+    #[sql_type = "BigInt"]
+    problem_count: i64,
+}
+
+#[derive(QueryableByName)]
+struct ContestSum {
+    #[sql_type = "Varchar"]
+    contest_id: String,
+    #[sql_type = "BigInt"]
+    solved_count: i64,
+}
+
+pub trait PeerStatsClient {
+    /// Sums the difficulty counts and the contest completions of the users other than `user_id`
+    /// whose accepted counts are from `min_accepted_count` to `max_accepted_count`.
+    fn load_peer_stats(
+        &self,
+        user_id: &str,
+        min_accepted_count: i32,
+        max_accepted_count: i32,
+    ) -> Result<PeerStats>;
+}
+
+impl PeerStatsClient for PgConnection {
+    fn load_peer_stats(
+        &self,
+        user_id: &str,
+        min_accepted_count: i32,
+        max_accepted_count: i32,
+    ) -> Result<PeerStats> {
+        let user_count = accepted_count::table
+            .filter(accepted_count::problem_count.between(min_accepted_count, max_accepted_count))
+            .filter(accepted_count::user_id.ne(user_id))
+            .select(count_star())
+            .first::<i64>(self)?;
+        if user_count == 0 {
+            return Ok(PeerStats::default());
+        }
+
+        let peers = r"
+            SELECT user_id FROM accepted_count
+            WHERE problem_count BETWEEN $1 AND $2 AND user_id <> $3
+        ";
+        let difficulty_counts = sql_query(format!(
+            r"
+            SELECT difficulty_from, SUM(problem_count) AS problem_count
+            FROM difficulty_count
+            WHERE user_id IN ({peers})
+            GROUP BY difficulty_from",
+            peers = peers
+        ))
+        .bind::<Int4, _>(min_accepted_count)
+        .bind::<Int4, _>(max_accepted_count)
+        .bind::<Varchar, _>(user_id)
+        .load::<DifficultySum>(self)?
+        .into_iter()
+        .map(|sum| (sum.difficulty_from, sum.problem_count))
+        .collect();
+        let contest_solved_counts = sql_query(format!(
+            r"
+            SELECT contest_id, SUM(solved_count) AS solved_count
+            FROM contest_completion
+            WHERE user_id IN ({peers})
+            GROUP BY contest_id",
+            peers = peers
+        ))
+        .bind::<Int4, _>(min_accepted_count)
+        .bind::<Int4, _>(max_accepted_count)
+        .bind::<Varchar, _>(user_id)
+        .load::<ContestSum>(self)?
+        .into_iter()
+        .map(|sum| (sum.contest_id, sum.solved_count))
+        .collect();
+        Ok(PeerStats {
+            user_count,
+            difficulty_counts,
+            contest_solved_counts,
+        })
+    }
+}
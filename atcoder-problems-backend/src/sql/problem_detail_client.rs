@@ -0,0 +1,151 @@
+use super::models::{
+    DailySolverCount, LengthDistribution, Point, Problem, ProblemDetail, ShortestRecord, Submission,
+};
+use super::schema::{
+    fastest, first, first_practice, length_distributions, points, problem_models, problems,
+    shortest, shortest_history, solve_time, solver, submissions,
+};
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use diesel::{sql_query, PgConnection};
+
+pub trait ProblemDetailClient {
+    /// Returns the problem with its difficulty, point, solver count, solve time, the
+    /// first/fastest/shortest submissions, the solver trend and the distribution of the lengths,
+    /// or `None` if the problem does not exist.
+    fn load_problem_detail(&self, problem_id: &str) -> Result<Option<ProblemDetail>>;
+
+    /// Returns the changes of the shortest submission of the problem, from the oldest one.
+    fn load_shortest_history(&self, problem_id: &str) -> Result<Vec<ShortestRecord>>;
+}
+
+impl ProblemDetailClient for PgConnection {
+    fn load_problem_detail(&self, problem_id: &str) -> Result<Option<ProblemDetail>> {
+        let problem = match problems::table
+            .find(problem_id)
+            .first::<Problem>(self)
+            .optional()?
+        {
+            Some(problem) => problem,
+            None => return Ok(None),
+        };
+        let model = problem_models::table
+            .find(problem_id)
+            .select((problem_models::difficulty, problem_models::is_experimental))
+            .first::<(Option<f64>, bool)>(self)
+            .optional()?;
+        let point = points::table
+            .find(problem_id)
+            .select(points::point)
+            .first::<Option<Point>>(self)
+            .optional()?
+            .flatten();
+        let solver_count = solver::table
+            .find(problem_id)
+            .select(solver::user_count)
+            .first::<i32>(self)
+            .optional()?;
+        let solve_time = solve_time::table
+            .find(problem_id)
+            .select((solve_time::median_second, solve_time::user_count))
+            .first::<(f64, i32)>(self)
+            .optional()?;
+        let first = load_submission(
+            self,
+            first::table
+                .find(problem_id)
+                .select(first::submission_id)
+                .first::<i64>(self)
+                .optional()?,
+        )?;
+        let first_practice = load_submission(
+            self,
+            first_practice::table
+                .find(problem_id)
+                .select(first_practice::submission_id)
+                .first::<i64>(self)
+                .optional()?,
+        )?;
+        let fastest = load_submission(
+            self,
+            fastest::table
+                .find(problem_id)
+                .select(fastest::submission_id)
+                .first::<i64>(self)
+                .optional()?,
+        )?;
+        let shortest = load_submission(
+            self,
+            shortest::table
+                .find(problem_id)
+                .select(shortest::submission_id)
+                .first::<i64>(self)
+                .optional()?,
+        )?;
+
+        // The new solvers are counted on the days of their first AC submissions in JST.
+        let solver_trend = sql_query(
+            r"
+            SELECT
+                (first_ac.epoch_second + 32400) / 86400 * 86400 - 32400 AS epoch_second,
+                COUNT(*) AS new_solver_count
+            FROM (
+                SELECT MIN(epoch_second) AS epoch_second
+                FROM submissions
+                WHERE problem_id = $1 AND result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                GROUP BY user_id
+            ) AS first_ac
+            GROUP BY 1
+            ORDER BY 1",
+        )
+        .bind::<Text, _>(problem_id)
+        .load::<DailySolverCount>(self)?;
+
+        let length_distribution = length_distributions::table
+            .find(problem_id)
+            .first::<LengthDistribution>(self)
+            .optional()?;
+
+        Ok(Some(ProblemDetail {
+            problem,
+            difficulty: model.and_then(|(difficulty, _)| difficulty),
+            is_experimental: model.map(|(_, is_experimental)| is_experimental),
+            point,
+            solver_count,
+            solve_time_median_second: solve_time.map(|(median_second, _)| median_second),
+            solve_time_user_count: solve_time.map(|(_, user_count)| user_count),
+            first,
+            first_practice,
+            fastest,
+            shortest,
+            solver_trend,
+            length_distribution,
+        }))
+    }
+
+    fn load_shortest_history(&self, problem_id: &str) -> Result<Vec<ShortestRecord>> {
+        let history = shortest_history::table
+            .filter(shortest_history::problem_id.eq(problem_id))
+            .order_by((
+                shortest_history::epoch_second,
+                shortest_history::submission_id,
+            ))
+            .load::<ShortestRecord>(self)?;
+        Ok(history)
+    }
+}
+
+fn load_submission(conn: &PgConnection, submission_id: Option<i64>) -> Result<Option<Submission>> {
+    match submission_id {
+        Some(submission_id) => {
+            let submission = submissions::table
+                .find(submission_id)
+                .first::<Submission>(conn)
+                .optional()?;
+            Ok(submission)
+        }
+        None => Ok(None),
+    }
+}
@@ -5,6 +5,14 @@ use diesel::PgConnection;
 pub trait ProblemInfoUpdater {
     fn update_solver_count(&self) -> Result<()>;
     fn update_problem_points(&self) -> Result<()>;
+
+    /// Aggregates the median of the time from the first submission to the first AC of the users
+    /// who solved each problem in practice, i.e. whose first submissions are after the contest.
+    fn update_solve_time(&self) -> Result<()>;
+
+    /// Aggregates the distribution of the lengths of the shortest AC submissions of the users who
+    /// solved each problem, so that the golfers can see how close to the shortest they are.
+    fn update_length_distribution(&self) -> Result<()>;
 }
 
 impl ProblemInfoUpdater for PgConnection {
@@ -14,7 +22,7 @@ impl ProblemInfoUpdater for PgConnection {
                 INSERT INTO solver (user_count, problem_id)
                     SELECT COUNT(DISTINCT(user_id)), problem_id
                     FROM submissions
-                    WHERE result = 'AC'
+                    WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
                     GROUP BY problem_id
                 ON CONFLICT (problem_id) DO UPDATE
                 SET user_count = EXCLUDED.user_count;
@@ -26,12 +34,18 @@ impl ProblemInfoUpdater for PgConnection {
     fn update_problem_points(&self) -> Result<()> {
         self.batch_execute(
             r"
+                DELETE FROM points WHERE problem_id IN (
+                    SELECT problem_id FROM contest_problem
+                    WHERE contest_id IN (SELECT contest_id FROM excluded_contests)
+                );
                 INSERT INTO points (problem_id, point)
                     SELECT submissions.problem_id, MAX(submissions.point)
                     FROM submissions
                     INNER JOIN contests ON contests.id = submissions.contest_id
-                    WHERE contests.start_epoch_second >= 1468670400
-                    AND contests.rate_change != '-'
+                    WHERE contests.rated
+                    -- The scores of AHC are not the points of the problems.
+                    AND contests.id !~ '^ahc[0-9]{3}$'
+                    AND contests.id NOT IN (SELECT contest_id FROM excluded_contests)
                     GROUP BY submissions.problem_id
                 ON CONFLICT (problem_id) DO UPDATE
                 SET point = EXCLUDED.point;
@@ -39,4 +53,95 @@ impl ProblemInfoUpdater for PgConnection {
         )?;
         Ok(())
     }
+
+    fn update_solve_time(&self) -> Result<()> {
+        self.batch_execute(
+            r"
+                INSERT INTO solve_time (problem_id, median_second, user_count)
+                    SELECT
+                        problem_id,
+                        PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY first_ac - first_submission),
+                        COUNT(*)
+                    FROM (
+                        SELECT
+                            submissions.problem_id,
+                            MIN(submissions.epoch_second) AS first_submission,
+                            MIN(submissions.epoch_second)
+                                FILTER (WHERE submissions.result IN (
+                                    SELECT raw_result FROM result_codes WHERE verdict = 'accepted'
+                                )) AS first_ac,
+                            MIN(contests.start_epoch_second + contests.duration_second) AS end_second
+                        FROM submissions
+                        INNER JOIN contests ON contests.id = submissions.contest_id
+                        GROUP BY submissions.problem_id, submissions.user_id
+                    ) AS practice
+                    WHERE first_ac IS NOT NULL AND first_submission >= end_second
+                    GROUP BY problem_id
+                ON CONFLICT (problem_id) DO UPDATE
+                SET median_second = EXCLUDED.median_second, user_count = EXCLUDED.user_count;
+            ",
+        )?;
+        Ok(())
+    }
+
+    fn update_length_distribution(&self) -> Result<()> {
+        // The 10 buckets of the same width cover the lengths from the minimum to the maximum.
+        self.batch_execute(
+            r"
+                WITH shortest_lengths AS (
+                    SELECT problem_id, MIN(length) AS length
+                    FROM submissions
+                    WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                    GROUP BY problem_id, user_id
+                ),
+                stats AS (
+                    SELECT
+                        problem_id,
+                        COUNT(*)::INT AS user_count,
+                        MIN(length) AS min_length,
+                        PERCENTILE_CONT(0.25) WITHIN GROUP (ORDER BY length) AS first_quartile,
+                        PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY length) AS median,
+                        PERCENTILE_CONT(0.75) WITHIN GROUP (ORDER BY length) AS third_quartile,
+                        MAX(length) AS max_length,
+                        (MAX(length) - MIN(length)) / 10 + 1 AS bucket_width
+                    FROM shortest_lengths
+                    GROUP BY problem_id
+                ),
+                histograms AS (
+                    SELECT problem_id, ARRAY_AGG(user_count ORDER BY bucket) AS bucket_counts
+                    FROM (
+                        SELECT stats.problem_id, buckets.bucket, COUNT(shortest_lengths.length)::INT AS user_count
+                        FROM stats
+                        CROSS JOIN generate_series(0, 9) AS buckets (bucket)
+                        LEFT JOIN shortest_lengths
+                            ON shortest_lengths.problem_id = stats.problem_id
+                            AND (shortest_lengths.length - stats.min_length) / stats.bucket_width = buckets.bucket
+                        GROUP BY stats.problem_id, buckets.bucket
+                    ) AS counts
+                    GROUP BY problem_id
+                )
+                INSERT INTO length_distributions (
+                    problem_id, user_count, min_length, first_quartile, median, third_quartile,
+                    max_length, bucket_width, bucket_counts
+                )
+                    SELECT
+                        stats.problem_id, stats.user_count, stats.min_length, stats.first_quartile,
+                        stats.median, stats.third_quartile, stats.max_length, stats.bucket_width,
+                        histograms.bucket_counts
+                    FROM stats
+                    INNER JOIN histograms ON histograms.problem_id = stats.problem_id
+                ON CONFLICT (problem_id) DO UPDATE
+                SET
+                    user_count = EXCLUDED.user_count,
+                    min_length = EXCLUDED.min_length,
+                    first_quartile = EXCLUDED.first_quartile,
+                    median = EXCLUDED.median,
+                    third_quartile = EXCLUDED.third_quartile,
+                    max_length = EXCLUDED.max_length,
+                    bucket_width = EXCLUDED.bucket_width,
+                    bucket_counts = EXCLUDED.bucket_counts;
+            ",
+        )?;
+        Ok(())
+    }
 }
@@ -0,0 +1,229 @@
+use super::models::{
+    ContestProblemModel, ProblemModel, ProblemModelDiagnostics, ProvisionalDifficulty,
+};
+use super::schema::{
+    predicted_rating, problem_model_diagnostics, problem_models, provisional_difficulties,
+    submissions,
+};
+use super::{accepted_results, insert_chunks};
+use crate::error::Result;
+
+use diesel::dsl::*;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use diesel::{sql_query, Connection, PgConnection};
+use std::collections::BTreeMap;
+
+pub trait ProblemModelClient {
+    fn update_problem_models(&self, models: &[ProblemModel]) -> Result<()>;
+    fn load_problem_models(&self) -> Result<Vec<ProblemModel>>;
+    fn update_problem_model_diagnostics(
+        &self,
+        diagnostics: &[ProblemModelDiagnostics],
+    ) -> Result<()>;
+    fn load_problem_model_diagnostics(&self) -> Result<Vec<ProblemModelDiagnostics>>;
+
+    /// Saves the provisional difficulties, and sets them to the problem models as the
+    /// experimental ones.
+    fn save_provisional_difficulties(&self, difficulties: &[ProvisionalDifficulty]) -> Result<()>;
+    fn load_provisional_difficulties(&self) -> Result<Vec<ProvisionalDifficulty>>;
+
+    /// Forgets the provisional difficulties of the problems, which the problem models have.
+    fn delete_provisional_difficulties(&self, problem_ids: &[&str]) -> Result<()>;
+
+    /// Loads the difficulties of the given problems. The problems without the difficulty are not
+    /// included.
+    fn load_difficulties(&self, problem_ids: &[&str]) -> Result<BTreeMap<String, f64>>;
+
+    /// Loads the ratings of the given users predicted by the same model as the difficulties.
+    /// The users without the rating are not included.
+    fn load_predicted_ratings(&self, user_ids: &[&str]) -> Result<BTreeMap<String, f64>>;
+
+    /// Loads the problems of the contest with their difficulties and the numbers of the users who
+    /// solved them during the contest.
+    fn load_contest_problem_models(&self, contest_id: &str) -> Result<Vec<ContestProblemModel>>;
+
+    /// Selects at most `count` problems whose difficulty is in `[lower, upper]` and which none of
+    /// `user_ids` has solved yet. The selected problems are spread evenly over the range and
+    /// returned in ascending order of difficulty.
+    fn select_unsolved_problems(
+        &self,
+        user_ids: &[&str],
+        lower_difficulty: f64,
+        upper_difficulty: f64,
+        count: usize,
+    ) -> Result<Vec<String>>;
+}
+
+impl ProblemModelClient for PgConnection {
+    fn update_problem_models(&self, models: &[ProblemModel]) -> Result<()> {
+        for segment in insert_chunks(models, 3).into_iter() {
+            insert_into(problem_models::table)
+                .values(segment)
+                .on_conflict(problem_models::problem_id)
+                .do_update()
+                .set((
+                    problem_models::difficulty.eq(excluded(problem_models::difficulty)),
+                    problem_models::is_experimental.eq(excluded(problem_models::is_experimental)),
+                ))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_problem_models(&self) -> Result<Vec<ProblemModel>> {
+        let models = problem_models::table.load::<ProblemModel>(self)?;
+        Ok(models)
+    }
+
+    fn update_problem_model_diagnostics(
+        &self,
+        diagnostics: &[ProblemModelDiagnostics],
+    ) -> Result<()> {
+        for segment in insert_chunks(diagnostics, 4).into_iter() {
+            insert_into(problem_model_diagnostics::table)
+                .values(segment)
+                .on_conflict(problem_model_diagnostics::problem_id)
+                .do_update()
+                .set((
+                    problem_model_diagnostics::sample_size
+                        .eq(excluded(problem_model_diagnostics::sample_size)),
+                    problem_model_diagnostics::log_likelihood
+                        .eq(excluded(problem_model_diagnostics::log_likelihood)),
+                    problem_model_diagnostics::variance
+                        .eq(excluded(problem_model_diagnostics::variance)),
+                ))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_problem_model_diagnostics(&self) -> Result<Vec<ProblemModelDiagnostics>> {
+        let diagnostics = problem_model_diagnostics::table
+            .order_by(problem_model_diagnostics::problem_id)
+            .load::<ProblemModelDiagnostics>(self)?;
+        Ok(diagnostics)
+    }
+
+    fn save_provisional_difficulties(&self, difficulties: &[ProvisionalDifficulty]) -> Result<()> {
+        let models = difficulties
+            .iter()
+            .map(|d| ProblemModel {
+                problem_id: d.problem_id.clone(),
+                difficulty: Some(d.difficulty),
+                is_experimental: true,
+            })
+            .collect::<Vec<_>>();
+        self.transaction::<_, http_types::Error, _>(|| {
+            for segment in insert_chunks(difficulties, 4).into_iter() {
+                insert_into(provisional_difficulties::table)
+                    .values(segment)
+                    .on_conflict(provisional_difficulties::problem_id)
+                    .do_update()
+                    .set((
+                        provisional_difficulties::difficulty
+                            .eq(excluded(provisional_difficulties::difficulty)),
+                        provisional_difficulties::sample_size
+                            .eq(excluded(provisional_difficulties::sample_size)),
+                        provisional_difficulties::estimated_epoch_second
+                            .eq(excluded(provisional_difficulties::estimated_epoch_second)),
+                    ))
+                    .execute(self)?;
+            }
+            self.update_problem_models(&models)
+        })
+    }
+
+    fn load_provisional_difficulties(&self) -> Result<Vec<ProvisionalDifficulty>> {
+        let difficulties = provisional_difficulties::table
+            .order_by(provisional_difficulties::problem_id)
+            .load::<ProvisionalDifficulty>(self)?;
+        Ok(difficulties)
+    }
+
+    fn delete_provisional_difficulties(&self, problem_ids: &[&str]) -> Result<()> {
+        delete(
+            provisional_difficulties::table
+                .filter(provisional_difficulties::problem_id.eq_any(problem_ids)),
+        )
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn load_difficulties(&self, problem_ids: &[&str]) -> Result<BTreeMap<String, f64>> {
+        let difficulties = problem_models::table
+            .filter(problem_models::problem_id.eq_any(problem_ids))
+            .filter(problem_models::difficulty.is_not_null())
+            .select((problem_models::problem_id, problem_models::difficulty))
+            .load::<(String, Option<f64>)>(self)?
+            .into_iter()
+            .filter_map(|(problem_id, difficulty)| Some((problem_id, difficulty?)))
+            .collect();
+        Ok(difficulties)
+    }
+
+    fn load_predicted_ratings(&self, user_ids: &[&str]) -> Result<BTreeMap<String, f64>> {
+        let ratings = predicted_rating::table
+            .filter(predicted_rating::user_id.eq_any(user_ids))
+            .filter(predicted_rating::rating.is_not_null())
+            .select((predicted_rating::user_id, predicted_rating::rating))
+            .load::<(String, Option<f64>)>(self)?
+            .into_iter()
+            .filter_map(|(user_id, rating)| Some((user_id, rating?)))
+            .collect();
+        Ok(ratings)
+    }
+
+    fn load_contest_problem_models(&self, contest_id: &str) -> Result<Vec<ContestProblemModel>> {
+        let models = sql_query(
+            r"
+            SELECT contest_problem.problem_id, problem_models.difficulty,
+                contest_problem_stats.ac_user_count
+            FROM contest_problem
+            LEFT JOIN problem_models ON problem_models.problem_id = contest_problem.problem_id
+            LEFT JOIN contest_problem_stats
+                ON contest_problem_stats.contest_id = contest_problem.contest_id
+                AND contest_problem_stats.problem_id = contest_problem.problem_id
+            WHERE contest_problem.contest_id = $1
+            ORDER BY contest_problem.problem_id",
+        )
+        .bind::<Text, _>(contest_id)
+        .load::<ContestProblemModel>(self)?;
+        Ok(models)
+    }
+
+    fn select_unsolved_problems(
+        &self,
+        user_ids: &[&str],
+        lower_difficulty: f64,
+        upper_difficulty: f64,
+        count: usize,
+    ) -> Result<Vec<String>> {
+        // The solved problems are excluded in the database, so that only the candidates are
+        // loaded rather than all the AC submissions of the users.
+        let solved = submissions::table
+            .filter(submissions::user_id.eq_any(user_ids))
+            .filter(submissions::result.eq_any(accepted_results()))
+            .select(submissions::problem_id);
+        let candidates = problem_models::table
+            .filter(problem_models::difficulty.ge(lower_difficulty))
+            .filter(problem_models::difficulty.le(upper_difficulty))
+            .filter(problem_models::problem_id.ne_all(solved))
+            .order_by((problem_models::difficulty, problem_models::problem_id))
+            .select(problem_models::problem_id)
+            .load::<String>(self)?;
+
+        if candidates.len() <= count {
+            return Ok(candidates);
+        }
+        let selected = match count {
+            0 => Vec::new(),
+            1 => vec![candidates[candidates.len() / 2].clone()],
+            _ => (0..count)
+                .map(|i| candidates[i * (candidates.len() - 1) / (count - 1)].clone())
+                .collect(),
+        };
+        Ok(selected)
+    }
+}
@@ -0,0 +1,100 @@
+use super::models::ProblemSearchResult;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Nullable, Text};
+use diesel::{sql_query, PgConnection};
+
+/// The maximum number of the problems returned by a search.
+const MAX_SEARCH_RESULTS: i64 = 1000;
+
+/// The conditions of the problem search. The empty `text` matches all the problems, and the
+/// conditions of `None` are ignored.
+#[derive(Debug, Default)]
+pub struct ProblemSearchRequest<'a> {
+    /// Matches the words in the titles, or the id.
+    pub text: &'a str,
+    pub difficulty_from: Option<f64>,
+    pub difficulty_to: Option<f64>,
+    /// Matches the problems which the user has solved.
+    pub solved_by: Option<&'a str>,
+    /// Matches the problems which the user has not solved.
+    pub not_solved_by: Option<&'a str>,
+}
+
+pub trait ProblemSearchClient {
+    /// Returns the problems matching all the conditions, ordered by id.
+    fn search_problems(&self, request: &ProblemSearchRequest) -> Result<Vec<ProblemSearchResult>>;
+
+    /// Returns the problems which the user has not solved and whose difficulties are in
+    /// `[difficulty_from, difficulty_to]`, ordered by difficulty. The problems without the
+    /// difficulty are at the end, and are excluded if either bound is given.
+    fn load_unsolved_problems(
+        &self,
+        user_id: &str,
+        difficulty_from: Option<f64>,
+        difficulty_to: Option<f64>,
+    ) -> Result<Vec<ProblemSearchResult>>;
+}
+
+impl ProblemSearchClient for PgConnection {
+    fn search_problems(&self, request: &ProblemSearchRequest) -> Result<Vec<ProblemSearchResult>> {
+        let problems = sql_query(
+            r"
+            SELECT p.id, p.contest_id, p.title, m.difficulty
+            FROM problems AS p
+            LEFT JOIN problem_models AS m ON m.problem_id = p.id
+            WHERE (
+                $1 = ''
+                OR to_tsvector('simple', p.title) @@ plainto_tsquery('simple', $1)
+                OR p.id = $1
+            )
+            AND ($2::FLOAT8 IS NULL OR m.difficulty >= $2)
+            AND ($3::FLOAT8 IS NULL OR m.difficulty <= $3)
+            AND ($4::VARCHAR IS NULL OR EXISTS (
+                SELECT 1 FROM submissions AS s
+                WHERE s.user_id = $4 AND s.problem_id = p.id AND s.result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+            ))
+            AND ($5::VARCHAR IS NULL OR NOT EXISTS (
+                SELECT 1 FROM submissions AS s
+                WHERE s.user_id = $5 AND s.problem_id = p.id AND s.result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+            ))
+            ORDER BY p.id
+            LIMIT $6",
+        )
+        .bind::<Text, _>(request.text)
+        .bind::<Nullable<Double>, _>(request.difficulty_from)
+        .bind::<Nullable<Double>, _>(request.difficulty_to)
+        .bind::<Nullable<Text>, _>(request.solved_by)
+        .bind::<Nullable<Text>, _>(request.not_solved_by)
+        .bind::<BigInt, _>(MAX_SEARCH_RESULTS)
+        .load::<ProblemSearchResult>(self)?;
+        Ok(problems)
+    }
+
+    fn load_unsolved_problems(
+        &self,
+        user_id: &str,
+        difficulty_from: Option<f64>,
+        difficulty_to: Option<f64>,
+    ) -> Result<Vec<ProblemSearchResult>> {
+        let problems = sql_query(
+            r"
+            SELECT p.id, p.contest_id, p.title, m.difficulty
+            FROM problems AS p
+            LEFT JOIN problem_models AS m ON m.problem_id = p.id
+            WHERE ($2::FLOAT8 IS NULL OR m.difficulty >= $2)
+            AND ($3::FLOAT8 IS NULL OR m.difficulty <= $3)
+            AND NOT EXISTS (
+                SELECT 1 FROM submissions AS s
+                WHERE s.user_id = $1 AND s.problem_id = p.id AND s.result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+            )
+            ORDER BY m.difficulty NULLS LAST, p.id",
+        )
+        .bind::<Text, _>(user_id)
+        .bind::<Nullable<Double>, _>(difficulty_from)
+        .bind::<Nullable<Double>, _>(difficulty_to)
+        .load::<ProblemSearchResult>(self)?;
+        Ok(problems)
+    }
+}
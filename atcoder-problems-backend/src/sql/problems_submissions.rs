@@ -4,27 +4,47 @@ use diesel;
 use diesel::connection::SimpleConnection;
 use diesel::PgConnection;
 
-fn generate_query(table: &str, column: &str) -> String {
+/// The submissions after the start of the contest, which include the ones in the contest.
+const SINCE_CONTEST_START: &str = "contests.start_epoch_second";
+/// The submissions after the end of the contest, i.e. the ones in practice.
+const SINCE_CONTEST_END: &str = "contests.start_epoch_second + contests.duration_second";
+
+/// Generates the subquery of the ids of the AC submissions after `since` of the contests which
+/// are the minimum of `column` for each problem. The first one is chosen from the ties. The
+/// submissions of the excluded contests are ignored.
+fn generate_best_submission_ids(column: &str, since: &str) -> String {
     format!(
         r"
-                INSERT INTO {table}
-                (submission_id, problem_id, contest_id)
-                SELECT id, problem_id, contest_id FROM submissions
-                WHERE id IN
-                (
                     SELECT MIN(submissions.id) FROM submissions
                     LEFT JOIN contests ON contests.id=contest_id
-                    WHERE result='AC'
-                    AND contests.start_epoch_second < submissions.epoch_second
+                    WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                    AND {since} < submissions.epoch_second
+                    AND contest_id NOT IN (SELECT contest_id FROM excluded_contests)
                     AND (problem_id, submissions.{column}) IN
                     (
                         SELECT problem_id, MIN(submissions.{column}) FROM submissions
                         LEFT JOIN contests ON contests.id=contest_id
-                        WHERE result='AC'
-                        AND contests.start_epoch_second < submissions.epoch_second
+                        WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                        AND {since} < submissions.epoch_second
+                        AND contest_id NOT IN (SELECT contest_id FROM excluded_contests)
                         GROUP BY problem_id
                     )
-                    GROUP BY problem_id
+                    GROUP BY problem_id",
+        column = column,
+        since = since
+    )
+}
+
+fn generate_query(table: &str, column: &str, since: &str) -> String {
+    format!(
+        r"
+                DELETE FROM {table}
+                WHERE contest_id IN (SELECT contest_id FROM excluded_contests);
+                INSERT INTO {table}
+                (submission_id, problem_id, contest_id)
+                SELECT id, problem_id, contest_id FROM submissions
+                WHERE id IN
+                ({best_submission_ids}
                 )
                 ON CONFLICT (problem_id)
                 DO UPDATE SET
@@ -32,19 +52,52 @@ fn generate_query(table: &str, column: &str) -> String {
                         problem_id=EXCLUDED.problem_id,
                         submission_id=EXCLUDED.submission_id;",
         table = table,
-        column = column
+        best_submission_ids = generate_best_submission_ids(column, since)
+    )
+}
+
+/// Generates the query which records the changes of the shortest submissions. It compares the
+/// new ones with `public.shortest`, which has the previous ones even while `shortest` is rebuilt
+/// in the shadow table, so it runs before `shortest` is updated.
+fn generate_shortest_history_query() -> String {
+    format!(
+        r"
+                INSERT INTO shortest_history
+                (problem_id, epoch_second, previous_submission_id, previous_user_id,
+                 previous_length, submission_id, user_id, length)
+                SELECT
+                    best.problem_id, best.epoch_second, previous.id, previous.user_id,
+                    previous.length, best.id, best.user_id, best.length
+                FROM submissions AS best
+                LEFT JOIN public.shortest ON public.shortest.problem_id = best.problem_id
+                LEFT JOIN submissions AS previous ON previous.id = public.shortest.submission_id
+                WHERE best.id IN
+                ({best_submission_ids}
+                )
+                AND (previous.id IS NULL OR previous.id <> best.id)
+                ON CONFLICT (problem_id, submission_id) DO NOTHING;",
+        best_submission_ids = generate_best_submission_ids("length", SINCE_CONTEST_START)
     )
 }
 
 pub trait ProblemsSubmissionUpdater {
+    /// Updates the first, fastest and shortest submissions of the problems, and the first ones in
+    /// practice after the contests. The changes of the shortest ones are recorded in
+    /// `shortest_history`.
     fn update_submissions_of_problems(&self) -> Result<()>;
 }
 
 impl ProblemsSubmissionUpdater for PgConnection {
     fn update_submissions_of_problems(&self) -> Result<()> {
-        self.batch_execute(&generate_query("first", "id"))?;
-        self.batch_execute(&generate_query("fastest", "execution_time"))?;
-        self.batch_execute(&generate_query("shortest", "length"))?;
+        self.batch_execute(&generate_query("first", "id", SINCE_CONTEST_START))?;
+        self.batch_execute(&generate_query("first_practice", "id", SINCE_CONTEST_END))?;
+        self.batch_execute(&generate_query(
+            "fastest",
+            "execution_time",
+            SINCE_CONTEST_START,
+        ))?;
+        self.batch_execute(&generate_shortest_history_query())?;
+        self.batch_execute(&generate_query("shortest", "length", SINCE_CONTEST_START))?;
         Ok(())
     }
 }
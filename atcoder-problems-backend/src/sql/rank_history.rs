@@ -0,0 +1,58 @@
+use super::models::RankHistory;
+use super::schema::rank_history;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel::{sql_query, PgConnection};
+
+const DAY_SECOND: i64 = 24 * 3600;
+const JST_OFFSET_SECOND: i64 = 9 * 3600;
+
+pub trait RankHistoryClient {
+    /// Takes the snapshot of the AC count ranks and the rated point sum ranks of all the users
+    /// for the day of `now` in JST. The snapshot of the same day is overwritten, so that it has
+    /// the ranks of the last update of the day.
+    fn record_rank_history(&self, now: i64) -> Result<()>;
+
+    /// Returns the snapshots of the ranks of the user, from the oldest one.
+    fn load_rank_history(&self, user_id: &str) -> Result<Vec<RankHistory>>;
+}
+
+impl RankHistoryClient for PgConnection {
+    fn record_rank_history(&self, now: i64) -> Result<()> {
+        let day = (now + JST_OFFSET_SECOND).div_euclid(DAY_SECOND) * DAY_SECOND - JST_OFFSET_SECOND;
+        sql_query(
+            r"
+            INSERT INTO rank_history
+                (user_id, epoch_second, accepted_count_rank, rated_point_sum_rank)
+            SELECT
+                COALESCE(a.user_id, p.user_id),
+                $1,
+                a.rank,
+                p.rank
+            FROM (
+                SELECT user_id, RANK() OVER (ORDER BY problem_count DESC) - 1 AS rank
+                FROM accepted_count
+            ) AS a
+            FULL OUTER JOIN (
+                SELECT user_id, RANK() OVER (ORDER BY point_sum DESC) - 1 AS rank
+                FROM rated_point_sum
+            ) AS p ON p.user_id = a.user_id
+            ON CONFLICT (user_id, epoch_second) DO UPDATE SET
+                accepted_count_rank = EXCLUDED.accepted_count_rank,
+                rated_point_sum_rank = EXCLUDED.rated_point_sum_rank",
+        )
+        .bind::<BigInt, _>(day)
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn load_rank_history(&self, user_id: &str) -> Result<Vec<RankHistory>> {
+        let history = rank_history::table
+            .filter(rank_history::user_id.eq(user_id))
+            .order_by(rank_history::epoch_second)
+            .load::<RankHistory>(self)?;
+        Ok(history)
+    }
+}
@@ -1,31 +1,45 @@
-use super::models::Submission;
-use super::schema::{contest_problem, contests, rated_point_sum};
-use super::MAX_INSERT_ROWS;
-use super::{FIRST_AGC_EPOCH_SECOND, UNRATED_STATE};
+use super::insert_chunks;
+use super::models::{Point, RankedUserSum, Submission};
+use super::schema::{contest_problem, contests, internal_banned_users, rated_point_sum};
 use crate::error::Result;
-use crate::utils::SplitToSegments;
 
-use crate::sql::models::ContestProblem;
+use crate::sql::models::{Contest, ContestProblem};
 use diesel::dsl::*;
 use diesel::pg::upsert::excluded;
 use diesel::prelude::*;
-use diesel::PgConnection;
+use diesel::sql_types::{BigInt, Nullable};
+use diesel::{sql_query, PgConnection};
+use std::cmp;
 use std::collections::{BTreeMap, BTreeSet};
 
 pub trait RatedPointSumClient {
     fn update_rated_point_sum(&self, ac_submissions: &[Submission]) -> Result<()>;
-    fn get_users_rated_point_sum(&self, user_id: &str) -> Option<f64>;
-    fn get_rated_point_sum_rank(&self, point: f64) -> Result<i64>;
+    fn get_users_rated_point_sum(&self, user_id: &str) -> Option<Point>;
+
+    /// Returns the number of the users with more rated points than `point`. The banned users are
+    /// not counted.
+    fn get_rated_point_sum_rank(&self, point: Point) -> Result<i64>;
+
+    /// Returns the users from the `from`-th to the `to`-th (exclusive) in the order of the rated
+    /// point sum. The users of the same rated point sum have the same rank, and the next rank is
+    /// the following integer. The banned users are not ranked, and only the users who have
+    /// submitted since `active_since` are ranked if it is given.
+    fn load_rated_point_sum_in_range(
+        &self,
+        from: i64,
+        to: i64,
+        active_since: Option<i64>,
+    ) -> Result<Vec<RankedUserSum>>;
 }
 
 impl RatedPointSumClient for PgConnection {
     fn update_rated_point_sum(&self, ac_submissions: &[Submission]) -> Result<()> {
+        // The scores of the heuristic contests are not the points of the problems.
         let rated_contest_ids = contests::table
-            .filter(contests::start_epoch_second.ge(FIRST_AGC_EPOCH_SECOND))
-            .filter(contests::rate_change.ne(UNRATED_STATE))
-            .select(contests::id)
-            .load::<String>(self)?
+            .load::<Contest>(self)?
             .into_iter()
+            .filter(|contest| contest.is_rated() && !contest.is_heuristic())
+            .map(|contest| contest.id)
             .collect::<BTreeSet<_>>();
         let rated_problem_ids = contest_problem::table
             .load::<ContestProblem>(self)?
@@ -34,30 +48,32 @@ impl RatedPointSumClient for PgConnection {
             .map(|p| p.problem_id)
             .collect::<BTreeSet<_>>();
 
+        // The best point of the ACs of each problem is counted, since a problem may have a partial
+        // score.
         let rated_point_sum = ac_submissions
             .iter()
             .filter(|s| rated_problem_ids.contains(&s.problem_id))
             .map(|s| (s.user_id.as_str(), s.problem_id.as_str(), s.point))
             .fold(BTreeMap::new(), |mut map, (user_id, problem_id, point)| {
-                map.entry(user_id)
+                let best = map
+                    .entry(user_id)
                     .or_insert_with(BTreeMap::new)
-                    .insert(problem_id, point as u32);
+                    .entry(problem_id)
+                    .or_insert_with(Point::default);
+                *best = cmp::max(*best, point);
                 map
             })
             .into_iter()
-            .map(|(user_id, set)| {
-                let sum = set.into_iter().map(|(_, point)| point).sum::<u32>();
+            .map(|(user_id, points)| {
+                let sum = points.into_iter().map(|(_, point)| point).sum::<Point>();
                 (
                     rated_point_sum::user_id.eq(user_id),
-                    rated_point_sum::point_sum.eq(f64::from(sum)),
+                    rated_point_sum::point_sum.eq(sum),
                 )
             })
             .collect::<Vec<_>>();
 
-        for segment in rated_point_sum
-            .split_into_segments(MAX_INSERT_ROWS)
-            .into_iter()
-        {
+        for segment in insert_chunks(&rated_point_sum, 2).into_iter() {
             insert_into(rated_point_sum::table)
                 .values(segment)
                 .on_conflict(rated_point_sum::user_id)
@@ -68,20 +84,47 @@ impl RatedPointSumClient for PgConnection {
         Ok(())
     }
 
-    fn get_users_rated_point_sum(&self, user_id: &str) -> Option<f64> {
+    fn get_users_rated_point_sum(&self, user_id: &str) -> Option<Point> {
         let sum = rated_point_sum::table
             .filter(rated_point_sum::user_id.eq(user_id))
             .select(rated_point_sum::point_sum)
-            .first::<f64>(self)
+            .first::<Point>(self)
             .ok()?;
         Some(sum)
     }
 
-    fn get_rated_point_sum_rank(&self, rated_point_sum: f64) -> Result<i64> {
+    fn get_rated_point_sum_rank(&self, rated_point_sum: Point) -> Result<i64> {
         let rank = rated_point_sum::table
             .filter(rated_point_sum::point_sum.gt(rated_point_sum))
+            .filter(not(rated_point_sum::user_id.eq_any(
+                internal_banned_users::table.select(internal_banned_users::user_id),
+            )))
             .select(count_star())
             .first::<i64>(self)?;
         Ok(rank)
     }
+
+    fn load_rated_point_sum_in_range(
+        &self,
+        from: i64,
+        to: i64,
+        active_since: Option<i64>,
+    ) -> Result<Vec<RankedUserSum>> {
+        let ranking = sql_query(
+            r"
+            SELECT user_id, point_sum, DENSE_RANK() OVER (ORDER BY point_sum DESC) - 1 AS rank
+            FROM rated_point_sum
+            WHERE user_id NOT IN (SELECT user_id FROM internal_banned_users)
+            AND ($3::BIGINT IS NULL OR user_id IN (
+                SELECT user_id FROM active_users WHERE last_submission_epoch_second >= $3
+            ))
+            ORDER BY point_sum DESC, user_id
+            OFFSET $1 LIMIT $2",
+        )
+        .bind::<BigInt, _>(from)
+        .bind::<BigInt, _>(to - from)
+        .bind::<Nullable<BigInt>, _>(active_since)
+        .load::<RankedUserSum>(self)?;
+        Ok(ranking)
+    }
 }
@@ -0,0 +1,49 @@
+use super::insert_chunks;
+use super::models::RequestCount;
+use super::schema::request_counts;
+use crate::error::Result;
+
+use diesel::dsl::insert_into;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+pub trait RequestCountClient {
+    /// Adds the counts to the stored ones of the same hours, routes and clients.
+    fn add_request_counts(&self, counts: &[RequestCount]) -> Result<()>;
+
+    /// Loads the counts of the hours since `from_epoch_second`, from the latest hour.
+    fn load_request_counts(&self, from_epoch_second: i64) -> Result<Vec<RequestCount>>;
+}
+
+impl RequestCountClient for PgConnection {
+    fn add_request_counts(&self, counts: &[RequestCount]) -> Result<()> {
+        for chunk in insert_chunks(counts, 4) {
+            insert_into(request_counts::table)
+                .values(chunk)
+                .on_conflict((
+                    request_counts::hour_epoch_second,
+                    request_counts::route,
+                    request_counts::client,
+                ))
+                .do_update()
+                .set(request_counts::request_count.eq(
+                    request_counts::request_count + excluded(request_counts::request_count),
+                ))
+                .execute(self)?;
+        }
+        Ok(())
+    }
+
+    fn load_request_counts(&self, from_epoch_second: i64) -> Result<Vec<RequestCount>> {
+        let counts = request_counts::table
+            .filter(request_counts::hour_epoch_second.ge(from_epoch_second))
+            .order_by((
+                request_counts::hour_epoch_second.desc(),
+                request_counts::route,
+                request_counts::client,
+            ))
+            .load::<RequestCount>(self)?;
+        Ok(counts)
+    }
+}
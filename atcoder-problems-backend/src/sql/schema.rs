@@ -1,3 +1,10 @@
+table! {
+    active_users (user_id) {
+        user_id -> Varchar,
+        last_submission_epoch_second -> Int8,
+    }
+}
+
 table! {
     accepted_count (user_id) {
         user_id -> Varchar,
@@ -22,6 +29,74 @@ table! {
     }
 }
 
+table! {
+    contest_stats (contest_id) {
+        contest_id -> Varchar,
+        participant_count -> Int4,
+        full_solve_count -> Int4,
+    }
+}
+
+table! {
+    contest_problem_stats (contest_id, problem_id) {
+        contest_id -> Varchar,
+        problem_id -> Varchar,
+        submitter_count -> Int4,
+        ac_user_count -> Int4,
+    }
+}
+
+table! {
+    length_distributions (problem_id) {
+        problem_id -> Varchar,
+        user_count -> Int4,
+        min_length -> Int4,
+        first_quartile -> Float8,
+        median -> Float8,
+        third_quartile -> Float8,
+        max_length -> Int4,
+        bucket_width -> Int4,
+        bucket_counts -> Array<Int4>,
+    }
+}
+
+table! {
+    marathon_best_scores (user_id, problem_id) {
+        user_id -> Varchar,
+        problem_id -> Varchar,
+        contest_id -> Varchar,
+        score -> Float8,
+        submission_id -> Int8,
+        epoch_second -> Int8,
+    }
+}
+
+table! {
+    marathon_leaderboard (contest_id, user_id) {
+        contest_id -> Varchar,
+        user_id -> Varchar,
+        score -> Float8,
+        rank -> Int8,
+    }
+}
+
+table! {
+    contest_completion (user_id, contest_id) {
+        user_id -> Varchar,
+        contest_id -> Varchar,
+        solved_count -> Int4,
+        problem_count -> Int4,
+    }
+}
+
+table! {
+    difficulty_count (user_id, difficulty_from) {
+        user_id -> Varchar,
+        difficulty_from -> Int8,
+        problem_count -> Int4,
+    }
+}
+
 table! {
     fastest (problem_id) {
         contest_id -> Varchar,
@@ -38,6 +113,14 @@ table! {
     }
 }
 
+table! {
+    first_practice (problem_id) {
+        contest_id -> Varchar,
+        problem_id -> Varchar,
+        submission_id -> Int8,
+    }
+}
+
 table! {
     language_count (user_id, simplified_language) {
         user_id -> Varchar,
@@ -46,10 +129,19 @@ table! {
     }
 }
 
+table! {
+    language_trends (month, simplified_language) {
+        month -> Varchar,
+        simplified_language -> Varchar,
+        submission_count -> Int8,
+        user_count -> Int4,
+    }
+}
+
 table! {
     points (problem_id) {
         problem_id -> Varchar,
-        point -> Nullable<Float8>,
+        point -> Nullable<Numeric>,
         predict -> Nullable<Float8>,
     }
 }
@@ -61,6 +153,32 @@ table! {
     }
 }
 
+table! {
+    problem_models (problem_id) {
+        problem_id -> Varchar,
+        difficulty -> Nullable<Float8>,
+        is_experimental -> Bool,
+    }
+}
+
+table! {
+    problem_model_diagnostics (problem_id) {
+        problem_id -> Varchar,
+        sample_size -> Int4,
+        log_likelihood -> Nullable<Float8>,
+        variance -> Nullable<Float8>,
+    }
+}
+
+table! {
+    provisional_difficulties (problem_id) {
+        problem_id -> Varchar,
+        difficulty -> Float8,
+        sample_size -> Int4,
+        estimated_epoch_second -> Int8,
+    }
+}
+
 table! {
     problems (id) {
         id -> Varchar,
@@ -72,7 +190,16 @@ table! {
 table! {
     rated_point_sum (user_id) {
         user_id -> Varchar,
-        point_sum -> Float8,
+        point_sum -> Numeric,
+    }
+}
+
+table! {
+    similar_problems (problem_id, similar_problem_id) {
+        problem_id -> Varchar,
+        similar_problem_id -> Varchar,
+        co_solver_count -> Int8,
+        score -> Float8,
     }
 }
 
@@ -91,6 +218,52 @@ table! {
     }
 }
 
+table! {
+    shortest_history (problem_id, submission_id) {
+        problem_id -> Varchar,
+        epoch_second -> Int8,
+        previous_submission_id -> Nullable<Int8>,
+        previous_user_id -> Nullable<Varchar>,
+        previous_length -> Nullable<Int4>,
+        submission_id -> Int8,
+        user_id -> Varchar,
+        length -> Int4,
+    }
+}
+
+table! {
+    site_stats (updated_epoch_second) {
+        submission_count -> Int8,
+        accepted_count -> Int8,
+        weekly_active_user_count -> Int4,
+        monthly_active_user_count -> Int4,
+        problem_count -> Int4,
+        latest_submission_epoch_second -> Nullable<Int8>,
+        updated_epoch_second -> Int8,
+    }
+}
+
+table! {
+    solve_time (problem_id) {
+        problem_id -> Varchar,
+        median_second -> Float8,
+        user_count -> Int4,
+    }
+}
+
+table! {
+    standings (contest_id, user_id, problem_id) {
+        contest_id -> Varchar,
+        user_id -> Varchar,
+        problem_id -> Varchar,
+        rank -> Int4,
+        is_rated -> Bool,
+        score -> Float8,
+        penalty -> Int4,
+        elapsed_second -> Nullable<Int8>,
+    }
+}
+
 table! {
     submissions (id) {
         id -> Int8,
@@ -99,7 +272,7 @@ table! {
         contest_id -> Varchar,
         user_id -> Varchar,
         language -> Varchar,
-        point -> Float8,
+        point -> Numeric,
         length -> Int4,
         result -> Varchar,
         execution_time -> Nullable<Int4>,
@@ -120,22 +293,205 @@ table! {
     }
 }
 
+table! {
+    judge_submissions (judge, id) {
+        judge -> Varchar,
+        id -> Int8,
+        epoch_second -> Int8,
+        problem_id -> Varchar,
+        user_id -> Varchar,
+        language -> Varchar,
+        result -> Varchar,
+    }
+}
+
+table! {
+    dumps (name) {
+        name -> Varchar,
+        url -> Varchar,
+        size -> Int8,
+        sha256 -> Varchar,
+        generated_epoch_second -> Int8,
+    }
+}
+
+table! {
+    excluded_contests (contest_id) {
+        contest_id -> Varchar,
+        reason -> Text,
+        created_epoch_second -> Int8,
+    }
+}
+
+table! {
+    feature_flags (name) {
+        name -> Varchar,
+        enabled -> Bool,
+        updated_epoch_second -> Int8,
+    }
+}
+
+table! {
+    request_counts (hour_epoch_second, route, client) {
+        hour_epoch_second -> Int8,
+        route -> Varchar,
+        client -> Varchar,
+        request_count -> Int8,
+    }
+}
+
+table! {
+    update_tier_stats (tier) {
+        tier -> Varchar,
+        run_count -> Int8,
+        total_duration_millis -> Int8,
+        last_duration_millis -> Int8,
+        last_user_count -> Int4,
+        last_finished_epoch_second -> Int8,
+    }
+}
+
+table! {
+    updater_watermarks (name) {
+        name -> Varchar,
+        last_epoch_second -> Int8,
+        last_submission_id -> Int8,
+    }
+}
+
+table! {
+    result_codes (raw_result) {
+        raw_result -> Varchar,
+        verdict -> Varchar,
+    }
+}
+
+table! {
+    submissions_archive (id) {
+        id -> Int8,
+        epoch_second -> Int8,
+        problem_id -> Varchar,
+        contest_id -> Varchar,
+        user_id -> Varchar,
+        language -> Varchar,
+        point -> Numeric,
+        length -> Int4,
+        result -> Varchar,
+        execution_time -> Nullable<Int4>,
+    }
+}
+
+table! {
+    job_runs (id) {
+        id -> Int4,
+        job_name -> Varchar,
+        status -> Varchar,
+        message -> Nullable<Text>,
+        started_epoch_second -> Int8,
+        finished_epoch_second -> Nullable<Int8>,
+    }
+}
+
+table! {
+    judge_problems (judge, id) {
+        judge -> Varchar,
+        id -> Varchar,
+        title -> Varchar,
+        difficulty -> Nullable<Float8>,
+    }
+}
+
+table! {
+    validation_violations (check_name, subject) {
+        check_name -> Varchar,
+        subject -> Varchar,
+        message -> Text,
+        detected_epoch_second -> Int8,
+    }
+}
+
+table! {
+    table_versions (name) {
+        name -> Varchar,
+        version -> Int8,
+        updated_epoch_second -> Int8,
+    }
+}
+
+table! {
+    achievements (user_id, achievement) {
+        user_id -> Varchar,
+        achievement -> Varchar,
+        epoch_second -> Int8,
+    }
+}
+
+table! {
+    rank_history (user_id, epoch_second) {
+        user_id -> Varchar,
+        epoch_second -> Int8,
+        accepted_count_rank -> Nullable<Int8>,
+        rated_point_sum_rank -> Nullable<Int8>,
+    }
+}
+
+table! {
+    submission_codes (submission_id) {
+        submission_id -> Int8,
+        code -> Text,
+        fetched_epoch_second -> Int8,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
     accepted_count,
+    active_users,
     contests,
     contest_problem,
+    contest_completion,
+    contest_problem_stats,
+    contest_stats,
+    difficulty_count,
+    dumps,
+    excluded_contests,
     fastest,
+    feature_flags,
     first,
+    first_practice,
+    job_runs,
+    judge_problems,
+    judge_submissions,
     language_count,
+    language_trends,
+    length_distributions,
+    marathon_best_scores,
+    marathon_leaderboard,
     max_streaks,
     points,
     predicted_rating,
+    problem_model_diagnostics,
+    problem_models,
     problems,
+    provisional_difficulties,
+    rank_history,
     rated_point_sum,
+    request_counts,
+    result_codes,
     shortest,
+    shortest_history,
+    similar_problems,
+    site_stats,
+    solve_time,
     solver,
+    standings,
     submissions,
     submission_count,
+    submission_codes,
+    submissions_archive,
+    table_versions,
+    update_tier_stats,
+    updater_watermarks,
+    validation_violations,
 );
 
 // internal tables
@@ -143,6 +499,7 @@ table! {
     internal_users (internal_user_id) {
         internal_user_id -> Varchar,
         atcoder_user_id -> Nullable<Varchar>,
+        is_admin -> Bool,
     }
 }
 
@@ -151,6 +508,8 @@ table! {
         internal_list_id -> Varchar,
         internal_user_id -> Varchar,
         internal_list_name -> Varchar,
+        share_token -> Nullable<Varchar>,
+        deleted_epoch_second -> Nullable<Int8>,
     }
 }
 
@@ -169,6 +528,30 @@ allow_tables_to_appear_in_same_query!(
     internal_virtual_contests,
     internal_virtual_contest_items,
     internal_virtual_contest_participants,
+    internal_virtual_contest_teams,
+    internal_virtual_contest_team_members,
+    internal_virtual_contest_templates,
+    internal_virtual_contest_announcements,
+    internal_virtual_contest_training_progress,
+    internal_virtual_contest_archives,
+    internal_virtual_contest_results,
+    internal_problem_notes,
+    internal_problem_tags,
+    internal_watched_users,
+    internal_user_verifications,
+    internal_api_tokens,
+    internal_banned_users,
+    internal_recrawl_requests,
+    internal_audit_log,
+    internal_webhooks,
+    internal_webhook_deliveries,
+    internal_daily_digests,
+    internal_idempotency_keys,
+    internal_api_usage,
+    internal_user_groups,
+    internal_user_group_members,
+    internal_assignments,
+    internal_assignment_problems,
 );
 
 table! {
@@ -181,6 +564,10 @@ table! {
         duration_second -> Int8,
         mode -> Nullable<Varchar>,
         is_public -> Bool,
+        invite_only -> Bool,
+        invite_token -> Nullable<Varchar>,
+        scoring -> Nullable<Varchar>,
+        deleted_epoch_second -> Nullable<Int8>,
     }
 }
 
@@ -200,6 +587,76 @@ table! {
     }
 }
 
+table! {
+    internal_virtual_contest_teams (id) {
+        id -> Varchar,
+        internal_virtual_contest_id -> Varchar,
+        team_name -> Varchar,
+    }
+}
+
+table! {
+    internal_virtual_contest_team_members (team_id, atcoder_user_id) {
+        team_id -> Varchar,
+        internal_virtual_contest_id -> Varchar,
+        atcoder_user_id -> Varchar,
+    }
+}
+
+table! {
+    internal_virtual_contest_announcements (id) {
+        id -> Varchar,
+        internal_virtual_contest_id -> Varchar,
+        message -> Text,
+        epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_virtual_contest_training_progress (internal_virtual_contest_id, internal_user_id, problem_id) {
+        internal_virtual_contest_id -> Varchar,
+        internal_user_id -> Varchar,
+        problem_id -> Varchar,
+        solved_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_virtual_contest_archives (internal_virtual_contest_id) {
+        internal_virtual_contest_id -> Varchar,
+        archived_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_virtual_contest_results (internal_virtual_contest_id, user_id) {
+        internal_virtual_contest_id -> Varchar,
+        user_id -> Varchar,
+        rank -> Int8,
+        score -> Float8,
+        penalty_second -> Int8,
+        solved_count -> Int4,
+        performance -> Nullable<Float8>,
+        simulated_rating -> Nullable<Float8>,
+    }
+}
+
+table! {
+    internal_virtual_contest_templates (id) {
+        id -> Varchar,
+        internal_user_id -> Varchar,
+        title -> Varchar,
+        memo -> Varchar,
+        weekday -> Int4,
+        start_minute_of_day -> Int4,
+        duration_second -> Int8,
+        mode -> Nullable<Varchar>,
+        is_public -> Bool,
+        problem_difficulties -> Array<Float8>,
+        last_start_epoch_second -> Nullable<Int8>,
+    }
+}
+
 table! {
     internal_progress_reset (internal_user_id, problem_id) {
         internal_user_id -> Varchar,
@@ -207,3 +664,164 @@ table! {
         reset_epoch_second -> Int8,
     }
 }
+
+table! {
+    internal_problem_notes (internal_user_id, problem_id) {
+        internal_user_id -> Varchar,
+        problem_id -> Varchar,
+        note -> Text,
+        updated_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_problem_tags (internal_user_id, problem_id, tag) {
+        internal_user_id -> Varchar,
+        problem_id -> Varchar,
+        tag -> Varchar,
+        tagged_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_watched_users (internal_user_id, watched_user_id) {
+        internal_user_id -> Varchar,
+        watched_user_id -> Varchar,
+    }
+}
+
+table! {
+    internal_user_verifications (internal_user_id) {
+        internal_user_id -> Varchar,
+        atcoder_user_id -> Varchar,
+        verification_code -> Varchar,
+        created_epoch_second -> Int8,
+        verified_epoch_second -> Nullable<Int8>,
+    }
+}
+
+table! {
+    internal_api_tokens (token_hash) {
+        token_hash -> Varchar,
+        id -> Varchar,
+        internal_user_id -> Varchar,
+        name -> Varchar,
+        scopes -> Nullable<Array<Varchar>>,
+        created_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_banned_users (user_id) {
+        user_id -> Varchar,
+        banned_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_recrawl_requests (contest_id) {
+        contest_id -> Varchar,
+        requested_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_audit_log (id) {
+        id -> Int8,
+        internal_user_id -> Varchar,
+        method -> Varchar,
+        path -> Varchar,
+        status -> Int4,
+        payload_summary -> Text,
+        epoch_second -> Int8,
+        client_ip -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    internal_webhooks (id) {
+        id -> Varchar,
+        internal_user_id -> Varchar,
+        url -> Varchar,
+        secret -> Varchar,
+        event_types -> Array<Varchar>,
+        format -> Varchar,
+        created_epoch_second -> Int8,
+        verification_token -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    internal_webhook_deliveries (id) {
+        id -> Int8,
+        webhook_id -> Varchar,
+        event_type -> Varchar,
+        payload -> Text,
+        attempt_count -> Int4,
+        next_attempt_epoch_second -> Nullable<Int8>,
+        delivered_epoch_second -> Nullable<Int8>,
+    }
+}
+
+table! {
+    internal_daily_digests (atcoder_user_id, day_epoch_second) {
+        atcoder_user_id -> Varchar,
+        day_epoch_second -> Int8,
+        payload -> Text,
+    }
+}
+
+table! {
+    internal_idempotency_keys (internal_user_id, idempotency_key) {
+        internal_user_id -> Varchar,
+        idempotency_key -> Varchar,
+        request_hash -> Varchar,
+        status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        created_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_api_usage (subject, day_epoch_second) {
+        subject -> Varchar,
+        day_epoch_second -> Int8,
+        request_count -> Int8,
+    }
+}
+
+table! {
+    internal_user_groups (internal_group_id) {
+        internal_group_id -> Varchar,
+        internal_user_id -> Varchar,
+        group_name -> Varchar,
+    }
+}
+
+table! {
+    internal_user_group_members (internal_group_id, user_id) {
+        internal_group_id -> Varchar,
+        user_id -> Varchar,
+    }
+}
+
+table! {
+    internal_assignments (internal_assignment_id) {
+        internal_assignment_id -> Varchar,
+        internal_group_id -> Varchar,
+        title -> Varchar,
+        deadline_epoch_second -> Int8,
+        created_epoch_second -> Int8,
+    }
+}
+
+table! {
+    internal_assignment_problems (internal_assignment_id, problem_id) {
+        internal_assignment_id -> Varchar,
+        problem_id -> Varchar,
+    }
+}
+
+joinable!(internal_webhook_deliveries -> internal_webhooks (webhook_id));
+joinable!(internal_webhooks -> internal_users (internal_user_id));
+joinable!(internal_idempotency_keys -> internal_users (internal_user_id));
@@ -0,0 +1,61 @@
+use super::validate_table_name;
+use crate::error::Result;
+
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, PgConnection};
+
+/// The schema of the shadow tables, which has the tables of the same names as the live ones.
+const SHADOW_SCHEMA: &str = "shadow";
+
+pub trait ShadowTableClient {
+    /// Rebuilds the tables from scratch without exposing the intermediate states to the readers.
+    ///
+    /// `rebuild` writes into the empty shadow tables, since the unqualified names of the tables
+    /// refer to the shadow ones on this connection while it runs. The other tables are still the
+    /// live ones. Then the rows of the live tables are replaced with the shadow ones in a
+    /// transaction, so that the readers see either all the old rows or all the new rows. The live
+    /// tables themselves are kept with their grants and the views depending on them.
+    fn rebuild_in_shadow_tables<F>(&self, tables: &[&str], rebuild: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<()>;
+}
+
+impl ShadowTableClient for PgConnection {
+    fn rebuild_in_shadow_tables<F>(&self, tables: &[&str], rebuild: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<()>,
+    {
+        for table in tables.iter() {
+            validate_table_name(table)?;
+        }
+        self.batch_execute(&format!("CREATE SCHEMA IF NOT EXISTS {}", SHADOW_SCHEMA))?;
+        for table in tables.iter() {
+            self.batch_execute(&format!(
+                r"
+                DROP TABLE IF EXISTS {schema}.{table};
+                CREATE TABLE {schema}.{table} (LIKE public.{table} INCLUDING ALL);",
+                schema = SHADOW_SCHEMA,
+                table = table
+            ))?;
+        }
+
+        self.batch_execute(&format!("SET search_path TO {}, public", SHADOW_SCHEMA))?;
+        let result = rebuild();
+        self.batch_execute("RESET search_path")?;
+        result?;
+
+        self.transaction::<_, http_types::Error, _>(|| {
+            for table in tables.iter() {
+                self.batch_execute(&format!(
+                    r"
+                    TRUNCATE public.{table};
+                    INSERT INTO public.{table} SELECT * FROM {schema}.{table};
+                    DROP TABLE {schema}.{table};",
+                    schema = SHADOW_SCHEMA,
+                    table = table
+                ))?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use super::models::SimilarProblem;
+use super::schema::similar_problems;
+use crate::error::Result;
+
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+/// The first ACs of a user to two problems within this period are solved together.
+const CO_SOLVE_WINDOW_SECOND: i64 = 24 * 3600;
+/// The pairs solved together by fewer users are too noisy to be suggested.
+const MIN_CO_SOLVER_COUNT: i64 = 3;
+const MAX_SIMILAR_PROBLEMS: i64 = 10;
+
+pub trait SimilarProblemClient {
+    /// Finds the problems whose first ACs are close to the ones of each problem among the same
+    /// users, and keeps the most associated ones of each problem.
+    fn update_similar_problems(&self) -> Result<()>;
+
+    /// Returns the problems similar to the problem, from the most similar one.
+    fn load_similar_problems(&self, problem_id: &str) -> Result<Vec<SimilarProblem>>;
+}
+
+impl SimilarProblemClient for PgConnection {
+    fn update_similar_problems(&self) -> Result<()> {
+        // The score is the cosine similarity of the sets of the users, which does not favor the
+        // problems solved by everyone.
+        self.batch_execute(&format!(
+            r"
+                DELETE FROM similar_problems;
+                WITH first_ac AS (
+                    SELECT user_id, problem_id, MIN(epoch_second) AS epoch_second
+                    FROM submissions
+                    WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')
+                    GROUP BY user_id, problem_id
+                ),
+                solver_counts AS (
+                    SELECT problem_id, COUNT(*) AS user_count FROM first_ac GROUP BY problem_id
+                ),
+                co_solves AS (
+                    SELECT a.problem_id, b.problem_id AS similar_problem_id, COUNT(*) AS co_solver_count
+                    FROM first_ac AS a
+                    INNER JOIN first_ac AS b
+                        ON b.user_id = a.user_id
+                        AND b.problem_id <> a.problem_id
+                        AND b.epoch_second BETWEEN a.epoch_second - {window} AND a.epoch_second + {window}
+                    GROUP BY a.problem_id, b.problem_id
+                    HAVING COUNT(*) >= {min_count}
+                ),
+                scored AS (
+                    SELECT
+                        co_solves.problem_id,
+                        co_solves.similar_problem_id,
+                        co_solves.co_solver_count,
+                        co_solves.co_solver_count
+                            / SQRT(a.user_count::DOUBLE PRECISION * b.user_count) AS score
+                    FROM co_solves
+                    INNER JOIN solver_counts AS a ON a.problem_id = co_solves.problem_id
+                    INNER JOIN solver_counts AS b ON b.problem_id = co_solves.similar_problem_id
+                )
+                INSERT INTO similar_problems (problem_id, similar_problem_id, co_solver_count, score)
+                    SELECT problem_id, similar_problem_id, co_solver_count, score
+                    FROM (
+                        SELECT
+                            *,
+                            ROW_NUMBER() OVER (
+                                PARTITION BY problem_id ORDER BY score DESC, similar_problem_id
+                            ) AS rank
+                        FROM scored
+                    ) AS ranked
+                    WHERE rank <= {max_count};
+            ",
+            window = CO_SOLVE_WINDOW_SECOND,
+            min_count = MIN_CO_SOLVER_COUNT,
+            max_count = MAX_SIMILAR_PROBLEMS
+        ))?;
+        Ok(())
+    }
+
+    fn load_similar_problems(&self, problem_id: &str) -> Result<Vec<SimilarProblem>> {
+        let problems = similar_problems::table
+            .filter(similar_problems::problem_id.eq(problem_id))
+            .order_by((
+                similar_problems::score.desc(),
+                similar_problems::similar_problem_id,
+            ))
+            .load::<SimilarProblem>(self)?;
+        Ok(problems)
+    }
+}
@@ -1,3 +1,4 @@
+use super::insert_chunks;
 use super::models::*;
 use super::schema::*;
 use crate::error::Result;
@@ -5,6 +6,7 @@ use crate::error::Result;
 use diesel::dsl::insert_into;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::sql_query;
 
 pub trait SimpleClient {
     fn insert_contests(&self, values: &[Contest]) -> Result<usize>;
@@ -12,25 +14,34 @@ pub trait SimpleClient {
 
     fn load_problems(&self) -> Result<Vec<Problem>>;
     fn load_contests(&self) -> Result<Vec<Contest>>;
+
+    /// Loads the rated ranges of the contests parsed from their rate changes by the database.
+    fn load_contest_rated_ranges(&self) -> Result<Vec<ContestRatedRange>>;
 }
 
 impl SimpleClient for PgConnection {
     fn insert_contests(&self, values: &[Contest]) -> Result<usize> {
-        let result = insert_into(contests::table)
-            .values(values)
-            .on_conflict(contests::id)
-            .do_nothing()
-            .execute(self)?;
-        Ok(result)
+        let mut count = 0;
+        for segment in insert_chunks(values, 5).into_iter() {
+            count += insert_into(contests::table)
+                .values(segment)
+                .on_conflict(contests::id)
+                .do_nothing()
+                .execute(self)?;
+        }
+        Ok(count)
     }
 
     fn insert_problems(&self, values: &[Problem]) -> Result<usize> {
-        let result = insert_into(problems::table)
-            .values(values)
-            .on_conflict(problems::id)
-            .do_nothing()
-            .execute(self)?;
-        Ok(result)
+        let mut count = 0;
+        for segment in insert_chunks(values, 3).into_iter() {
+            count += insert_into(problems::table)
+                .values(segment)
+                .on_conflict(problems::id)
+                .do_nothing()
+                .execute(self)?;
+        }
+        Ok(count)
     }
 
     fn load_problems(&self) -> Result<Vec<Problem>> {
@@ -42,4 +53,20 @@ impl SimpleClient for PgConnection {
         let contests = contests::table.load::<Contest>(self)?;
         Ok(contests)
     }
+
+    fn load_contest_rated_ranges(&self) -> Result<Vec<ContestRatedRange>> {
+        let ranges = sql_query(
+            r"
+            SELECT
+                id AS contest_id,
+                rated,
+                rated_min_rating AS min_rating,
+                rated_max_rating AS max_rating
+            FROM contests
+            ORDER BY id
+            ",
+        )
+        .load::<ContestRatedRange>(self)?;
+        Ok(ranges)
+    }
 }
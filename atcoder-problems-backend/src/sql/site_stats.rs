@@ -0,0 +1,52 @@
+use super::models::SiteStats;
+use super::schema::site_stats;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel::{delete, sql_query, PgConnection};
+
+const WEEK_SECOND: i64 = 7 * 24 * 3600;
+const MONTH_SECOND: i64 = 30 * 24 * 3600;
+
+pub trait SiteStatsClient {
+    /// Aggregates the statistics of the whole site at `now`. The totals are the sums of the
+    /// aggregate tables of the users, so that the step is cheap enough to run with the fast tier.
+    fn update_site_stats(&self, now: i64) -> Result<()>;
+
+    /// Returns the statistics of the whole site, or `None` if they have not been aggregated yet.
+    fn load_site_stats(&self) -> Result<Option<SiteStats>>;
+}
+
+impl SiteStatsClient for PgConnection {
+    fn update_site_stats(&self, now: i64) -> Result<()> {
+        delete(site_stats::table).execute(self)?;
+        sql_query(
+            r"
+            INSERT INTO site_stats (
+                submission_count, accepted_count, weekly_active_user_count,
+                monthly_active_user_count, problem_count, latest_submission_epoch_second,
+                updated_epoch_second
+            )
+            SELECT
+                (SELECT COALESCE(SUM(count), 0)::BIGINT FROM submission_count),
+                (SELECT COALESCE(SUM(problem_count), 0)::BIGINT FROM accepted_count),
+                (SELECT COUNT(DISTINCT user_id)::INT FROM submissions WHERE epoch_second >= $1),
+                (SELECT COUNT(DISTINCT user_id)::INT FROM submissions WHERE epoch_second >= $2),
+                (SELECT COUNT(*)::INT FROM problems),
+                (SELECT MAX(epoch_second) FROM submissions),
+                $3
+            ",
+        )
+        .bind::<BigInt, _>(now - WEEK_SECOND)
+        .bind::<BigInt, _>(now - MONTH_SECOND)
+        .bind::<BigInt, _>(now)
+        .execute(self)?;
+        Ok(())
+    }
+
+    fn load_site_stats(&self) -> Result<Option<SiteStats>> {
+        let stats = site_stats::table.first::<SiteStats>(self).optional()?;
+        Ok(stats)
+    }
+}
@@ -0,0 +1,64 @@
+use super::insert_chunks;
+use crate::error::Result;
+use crate::sql::models::Standing;
+use crate::sql::schema::{contests, standings};
+
+use diesel::dsl::not;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+/// Stores the official standings of the AtCoder contests.
+pub trait StandingsClient {
+    fn update_standings(&self, standings: &[Standing]) -> Result<usize>;
+    fn load_standings(&self, contest_id: &str) -> Result<Vec<Standing>>;
+
+    /// Returns at most `limit` ids of the contests which have ended by `now` and whose standings
+    /// are not stored yet, from the newest one.
+    fn get_contests_without_standings(&self, now: i64, limit: i64) -> Result<Vec<String>>;
+}
+
+impl StandingsClient for PgConnection {
+    fn update_standings(&self, values: &[Standing]) -> Result<usize> {
+        let mut count = 0;
+        for segment in insert_chunks(values, 8).into_iter() {
+            count += insert_into(standings::table)
+                .values(segment)
+                .on_conflict((
+                    standings::contest_id,
+                    standings::user_id,
+                    standings::problem_id,
+                ))
+                .do_update()
+                .set((
+                    standings::rank.eq(excluded(standings::rank)),
+                    standings::is_rated.eq(excluded(standings::is_rated)),
+                    standings::score.eq(excluded(standings::score)),
+                    standings::penalty.eq(excluded(standings::penalty)),
+                    standings::elapsed_second.eq(excluded(standings::elapsed_second)),
+                ))
+                .execute(self)?;
+        }
+        Ok(count)
+    }
+
+    fn load_standings(&self, contest_id: &str) -> Result<Vec<Standing>> {
+        let values = standings::table
+            .filter(standings::contest_id.eq(contest_id))
+            .order_by((standings::rank, standings::user_id, standings::problem_id))
+            .load::<Standing>(self)?;
+        Ok(values)
+    }
+
+    fn get_contests_without_standings(&self, now: i64, limit: i64) -> Result<Vec<String>> {
+        let stored = standings::table.select(standings::contest_id);
+        let contest_ids = contests::table
+            .filter((contests::start_epoch_second + contests::duration_second).le(now))
+            .filter(not(contests::id.eq_any(stored)))
+            .order_by(contests::start_epoch_second.desc())
+            .limit(limit)
+            .select(contests::id)
+            .load::<String>(self)?;
+        Ok(contest_ids)
+    }
+}
@@ -1,8 +1,7 @@
 use super::models::Submission;
 use super::schema::max_streaks;
 use crate::error::Result;
-use crate::sql::MAX_INSERT_ROWS;
-use crate::utils::SplitToSegments;
+use crate::sql::insert_chunks;
 
 use chrono::Duration;
 use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Utc};
@@ -14,6 +13,9 @@ use std::collections::BTreeMap;
 
 pub trait StreakUpdater {
     fn update_streak_count(&self, submissions: &[Submission]) -> Result<()>;
+
+    /// Returns the user ids and the max streaks of the `count` users with the longest streaks.
+    fn load_streak_ranking(&self, count: i64) -> Result<Vec<(String, i64)>>;
 }
 
 impl StreakUpdater for PgConnection {
@@ -51,10 +53,7 @@ impl StreakUpdater for PgConnection {
             })
             .collect::<Vec<_>>();
 
-        for segment in user_max_streak
-            .split_into_segments(MAX_INSERT_ROWS)
-            .into_iter()
-        {
+        for segment in insert_chunks(&user_max_streak, 2).into_iter() {
             insert_into(max_streaks::table)
                 .values(segment)
                 .on_conflict(max_streaks::user_id)
@@ -64,9 +63,17 @@ impl StreakUpdater for PgConnection {
         }
         Ok(())
     }
+
+    fn load_streak_ranking(&self, count: i64) -> Result<Vec<(String, i64)>> {
+        let ranking = max_streaks::table
+            .order_by((max_streaks::streak.desc(), max_streaks::user_id))
+            .limit(count)
+            .load::<(String, i64)>(self)?;
+        Ok(ranking)
+    }
 }
 
-fn get_max_streak<Tz: TimeZone>(mut v: Vec<DateTime<Tz>>) -> i64 {
+pub(crate) fn get_max_streak<Tz: TimeZone>(mut v: Vec<DateTime<Tz>>) -> i64 {
     v.sort();
     let (_, max_streak) = (1..v.len()).fold((1, 1), |(current_streak, max_streak), i| {
         if v[i - 1].is_same_day_in_jst(&v[i]) {
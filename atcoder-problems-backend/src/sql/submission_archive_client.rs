@@ -0,0 +1,79 @@
+use crate::error::Result;
+use crate::sql::models::Submission;
+use crate::sql::schema::submissions_archive;
+
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Bool, Text};
+use diesel::PgConnection;
+
+/// The maximum number of the submissions moved by a statement, so that a statement does not
+/// hold the locks of too many rows.
+const ARCHIVE_CHUNK_SIZE: i64 = 10_000;
+
+pub trait SubmissionArchiveClient {
+    /// Moves the submissions before `before_epoch_second` from `submissions` into
+    /// `submissions_archive`, and returns the number of the moved submissions.
+    fn archive_submissions(&self, before_epoch_second: i64) -> Result<usize>;
+
+    /// Loads the archived submissions of the user, in the language simplified by
+    /// `simplify_language` if it is given.
+    fn get_user_archived_submissions(
+        &self,
+        user_id: &str,
+        simplified_language: Option<&str>,
+    ) -> Result<Vec<Submission>>;
+}
+
+impl SubmissionArchiveClient for PgConnection {
+    fn archive_submissions(&self, before_epoch_second: i64) -> Result<usize> {
+        let mut archived = 0;
+        loop {
+            // `submissions_archive` has the same columns as `submissions`. A submission archived
+            // before is updated as `update_submissions` does, since the crawler may have stored it
+            // again.
+            let moved = sql_query(
+                r"
+                WITH moved AS (
+                    DELETE FROM submissions WHERE id IN (
+                        SELECT id FROM submissions
+                        WHERE epoch_second < $1
+                        ORDER BY id
+                        LIMIT $2
+                    )
+                    RETURNING *
+                )
+                INSERT INTO submissions_archive SELECT * FROM moved
+                ON CONFLICT (id) DO UPDATE SET
+                    user_id = EXCLUDED.user_id,
+                    result = EXCLUDED.result,
+                    point = EXCLUDED.point,
+                    execution_time = EXCLUDED.execution_time",
+            )
+            .bind::<BigInt, _>(before_epoch_second)
+            .bind::<BigInt, _>(ARCHIVE_CHUNK_SIZE)
+            .execute(self)?;
+            archived += moved;
+            if moved == 0 {
+                return Ok(archived);
+            }
+        }
+    }
+
+    fn get_user_archived_submissions(
+        &self,
+        user_id: &str,
+        simplified_language: Option<&str>,
+    ) -> Result<Vec<Submission>> {
+        let mut query = submissions_archive::table
+            .filter(submissions_archive::user_id.eq(user_id))
+            .into_boxed();
+        if let Some(simplified_language) = simplified_language {
+            query = query
+                .filter(sql::<Bool>("simplified_language = ").bind::<Text, _>(simplified_language));
+        }
+        let submissions = query.load(self)?;
+        Ok(submissions)
+    }
+}
@@ -1,20 +1,30 @@
+use super::{accepted_results, insert_chunks};
 use crate::error::Result;
-use crate::sql::models::Submission;
-use crate::sql::schema::{submission_count, submissions};
+use crate::sql::models::{Point, Submission, Verdict};
+use crate::sql::schema::{result_codes, shortest, submission_count, submissions};
 
+use async_trait::async_trait;
 use diesel::connection::SimpleConnection;
-use diesel::dsl::insert_into;
+use diesel::dsl::{insert_into, sql};
 use diesel::pg::upsert::excluded;
 use diesel::prelude::*;
 use diesel::sql_query;
-use diesel::sql_types::Text;
+use diesel::sql_types::{BigInt, Bool, Text};
 use diesel::PgConnection;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::Row;
 use std::collections::BTreeMap;
 
+#[derive(Debug, Clone, Copy)]
 pub enum SubmissionRequest<'a> {
     UserAll {
         user_id: &'a str,
     },
+    /// The submissions of the user in the language simplified by `simplify_language`.
+    UserLanguage {
+        user_id: &'a str,
+        simplified_language: &'a str,
+    },
     UsersAccepted {
         user_ids: &'a [&'a str],
     },
@@ -28,6 +38,24 @@ pub enum SubmissionRequest<'a> {
     RecentAll {
         count: i64,
     },
+    UsersRecentAccepted {
+        user_ids: &'a [&'a str],
+        count: i64,
+    },
+    AcceptedAfterId {
+        from_id: i64,
+        count: i64,
+    },
+    AfterId {
+        from_id: i64,
+        count: i64,
+    },
+    /// The submissions after `(epoch_second, id)` in the order of `(epoch_second, id)`.
+    AfterTimeAndId {
+        epoch_second: i64,
+        id: i64,
+        count: i64,
+    },
     InvalidResult {
         from_second: i64,
     },
@@ -35,12 +63,23 @@ pub enum SubmissionRequest<'a> {
     ByIds {
         ids: &'a [i64],
     },
+    Shortest {
+        problem_ids: &'a [&'a str],
+    },
+    ShortestFromTime {
+        from_second: i64,
+    },
     UsersProblemsTime {
         user_ids: &'a [&'a str],
         problem_ids: &'a [&'a str],
         from_second: i64,
         to_second: i64,
     },
+    ProblemsTime {
+        problem_ids: &'a [&'a str],
+        from_second: i64,
+        to_second: i64,
+    },
 }
 
 pub trait SubmissionClient {
@@ -51,6 +90,15 @@ pub trait SubmissionClient {
     fn update_user_submission_count(&self, user_id: &str) -> Result<()>;
     fn update_delta_submission_count(&self, values: &[Submission]) -> Result<()>;
 
+    /// Returns at most `count` submissions inserted or updated after the change of
+    /// `after_sequence`, in the order of their latest changes, with the sequence numbers of the
+    /// changes. A rejudged submission is returned again after the rejudge.
+    fn load_submission_changes(
+        &self,
+        after_sequence: i64,
+        count: i64,
+    ) -> Result<Vec<(Submission, i64)>>;
+
     fn count_stored_submissions(&self, ids: &[i64]) -> Result<usize> {
         let submissions = self.get_submissions(SubmissionRequest::ByIds { ids })?;
         Ok(submissions.len())
@@ -63,13 +111,22 @@ impl SubmissionClient for PgConnection {
             SubmissionRequest::UserAll { user_id } => submissions::table
                 .filter(submissions::user_id.eq(user_id))
                 .load(self),
+            // `simplified_language` is maintained by a trigger, and it is not in the schema so
+            // that `Submission` does not have it.
+            SubmissionRequest::UserLanguage {
+                user_id,
+                simplified_language,
+            } => submissions::table
+                .filter(submissions::user_id.eq(user_id))
+                .filter(sql::<Bool>("simplified_language = ").bind::<Text, _>(simplified_language))
+                .load(self),
             SubmissionRequest::FromTime { from_second, count } => submissions::table
                 .filter(submissions::epoch_second.ge(from_second))
                 .order_by(submissions::epoch_second.asc())
                 .limit(count)
                 .load(self),
             SubmissionRequest::RecentAccepted { count } => submissions::table
-                .filter(submissions::result.eq("AC"))
+                .filter(submissions::result.eq_any(accepted_results()))
                 .order(submissions::id.desc())
                 .limit(count)
                 .load(self),
@@ -77,23 +134,71 @@ impl SubmissionClient for PgConnection {
                 .order(submissions::id.desc())
                 .limit(count)
                 .load(self),
+            SubmissionRequest::UsersRecentAccepted { user_ids, count } => submissions::table
+                .filter(submissions::result.eq_any(accepted_results()))
+                .filter(submissions::user_id.eq_any(user_ids))
+                .order(submissions::epoch_second.desc())
+                .limit(count)
+                .load(self),
             SubmissionRequest::UsersAccepted { user_ids } => submissions::table
-                .filter(submissions::result.eq("AC"))
+                .filter(submissions::result.eq_any(accepted_results()))
                 .filter(submissions::user_id.eq_any(user_ids))
                 .load(self),
+            SubmissionRequest::AcceptedAfterId { from_id, count } => submissions::table
+                .filter(submissions::result.eq_any(accepted_results()))
+                .filter(submissions::id.gt(from_id))
+                .order(submissions::id.asc())
+                .limit(count)
+                .load(self),
+            SubmissionRequest::AfterId { from_id, count } => submissions::table
+                .filter(submissions::id.gt(from_id))
+                .order(submissions::id.asc())
+                .limit(count)
+                .load(self),
+            SubmissionRequest::AfterTimeAndId {
+                epoch_second,
+                id,
+                count,
+            } => submissions::table
+                .filter(
+                    sql::<Bool>("(epoch_second, id) > (")
+                        .bind::<BigInt, _>(epoch_second)
+                        .sql(", ")
+                        .bind::<BigInt, _>(id)
+                        .sql(")"),
+                )
+                .order_by((submissions::epoch_second.asc(), submissions::id.asc()))
+                .limit(count)
+                .load(self),
             SubmissionRequest::AllAccepted => submissions::table
-                .filter(submissions::result.eq("AC"))
+                .filter(submissions::result.eq_any(accepted_results()))
                 .load(self),
             SubmissionRequest::InvalidResult { from_second } => submissions::table
-                .filter(submissions::result.ne_all(&[
-                    "AC", "WA", "TLE", "CE", "RE", "MLE", "OLE", "QLE", "IE", "NG",
-                ]))
+                // The results not in `result_codes` are still being judged, or unknown yet.
+                .filter(
+                    submissions::result
+                        .ne_all(result_codes::table.select(result_codes::raw_result)),
+                )
                 .filter(submissions::epoch_second.ge(from_second))
                 .order_by(submissions::id.desc())
                 .load(self),
             SubmissionRequest::ByIds { ids } => submissions::table
                 .filter(submissions::id.eq_any(ids))
                 .load::<Submission>(self),
+            SubmissionRequest::Shortest { problem_ids } => submissions::table
+                .filter(
+                    submissions::id.eq_any(
+                        shortest::table
+                            .filter(shortest::problem_id.eq_any(problem_ids))
+                            .select(shortest::submission_id),
+                    ),
+                )
+                .load::<Submission>(self),
+            SubmissionRequest::ShortestFromTime { from_second } => submissions::table
+                .filter(submissions::id.eq_any(shortest::table.select(shortest::submission_id)))
+                .filter(submissions::epoch_second.ge(from_second))
+                .order_by(submissions::id)
+                .load::<Submission>(self),
             SubmissionRequest::UsersProblemsTime {
                 user_ids,
                 problem_ids,
@@ -106,6 +211,15 @@ impl SubmissionClient for PgConnection {
                 .filter(submissions::epoch_second.le(to_second))
                 .limit(2000)
                 .load::<Submission>(self),
+            SubmissionRequest::ProblemsTime {
+                problem_ids,
+                from_second,
+                to_second,
+            } => submissions::table
+                .filter(submissions::problem_id.eq_any(problem_ids))
+                .filter(submissions::epoch_second.ge(from_second))
+                .filter(submissions::epoch_second.le(to_second))
+                .load::<Submission>(self),
         }?;
         Ok(submissions)
     }
@@ -119,17 +233,20 @@ impl SubmissionClient for PgConnection {
     }
 
     fn update_submissions(&self, values: &[Submission]) -> Result<usize> {
-        let count = insert_into(submissions::table)
-            .values(values)
-            .on_conflict(submissions::id)
-            .do_update()
-            .set((
-                submissions::user_id.eq(excluded(submissions::user_id)),
-                submissions::result.eq(excluded(submissions::result)),
-                submissions::point.eq(excluded(submissions::point)),
-                submissions::execution_time.eq(excluded(submissions::execution_time)),
-            ))
-            .execute(self)?;
+        let mut count = 0;
+        for segment in insert_chunks(values, 10).into_iter() {
+            count += insert_into(submissions::table)
+                .values(segment)
+                .on_conflict(submissions::id)
+                .do_update()
+                .set((
+                    submissions::user_id.eq(excluded(submissions::user_id)),
+                    submissions::result.eq(excluded(submissions::result)),
+                    submissions::point.eq(excluded(submissions::point)),
+                    submissions::execution_time.eq(excluded(submissions::execution_time)),
+                ))
+                .execute(self)?;
+        }
         Ok(count)
     }
 
@@ -170,12 +287,197 @@ impl SubmissionClient for PgConnection {
             })
             .collect::<Vec<_>>();
 
-        insert_into(submission_count::table)
-            .values(values)
-            .on_conflict(submission_count::user_id)
-            .do_update()
-            .set(submission_count::count.eq(excluded(submission_count::count)))
-            .execute(self)?;
+        for segment in insert_chunks(&values, 2).into_iter() {
+            insert_into(submission_count::table)
+                .values(segment)
+                .on_conflict(submission_count::user_id)
+                .do_update()
+                .set(submission_count::count.eq(excluded(submission_count::count)))
+                .execute(self)?;
+        }
         Ok(())
     }
+
+    fn load_submission_changes(
+        &self,
+        after_sequence: i64,
+        count: i64,
+    ) -> Result<Vec<(Submission, i64)>> {
+        // `change_sequence` is maintained by a trigger like `simplified_language`, and it is not
+        // in the schema so that `Submission` does not have it. The trigger serializes the writers,
+        // so no change with a smaller sequence number is committed after the returned ones.
+        let changes = submissions::table
+            .select((submissions::all_columns, sql::<BigInt>("change_sequence")))
+            .filter(sql::<Bool>("change_sequence > ").bind::<BigInt, _>(after_sequence))
+            .order(sql::<BigInt>("change_sequence"))
+            .limit(count)
+            .load::<(Submission, i64)>(self)?;
+        Ok(changes)
+    }
+}
+
+/// The reads of `SubmissionClient` done with the async queries of sqlx, for the API paths which
+/// read the submissions most often. They wait for the database without holding a thread.
+#[async_trait]
+pub trait AsyncSubmissionClient {
+    async fn get_submissions(&self, request: SubmissionRequest<'_>) -> Result<Vec<Submission>>;
+    async fn get_user_submission_count(&self, user_id: &str) -> Result<i64>;
+}
+
+/// Expands to a query of the submissions with the columns of `Submission`, followed by `$rest`.
+/// The point is read in hundredths, since NUMERIC is decoded only with the bigdecimal feature.
+macro_rules! select_submissions {
+    ($rest:literal) => {
+        concat!(
+            "SELECT id, epoch_second, problem_id, contest_id, user_id, language, ",
+            "(point * 100)::BIGINT AS point_hundredths, length, result, execution_time ",
+            "FROM submissions ",
+            $rest
+        )
+    };
+}
+
+#[async_trait]
+impl AsyncSubmissionClient for PgPool {
+    async fn get_submissions(&self, request: SubmissionRequest<'_>) -> Result<Vec<Submission>> {
+        let accepted = Verdict::Accepted.name();
+        let query = match request {
+            SubmissionRequest::UserAll { user_id } => {
+                sqlx::query(select_submissions!("WHERE user_id = $1")).bind(user_id)
+            }
+            SubmissionRequest::UserLanguage {
+                user_id,
+                simplified_language,
+            } => sqlx::query(select_submissions!(
+                "WHERE user_id = $1 AND simplified_language = $2"
+            ))
+            .bind(user_id)
+            .bind(simplified_language),
+            SubmissionRequest::FromTime { from_second, count } => sqlx::query(select_submissions!(
+                "WHERE epoch_second >= $1 ORDER BY epoch_second LIMIT $2"
+            ))
+            .bind(from_second)
+            .bind(count),
+            SubmissionRequest::RecentAccepted { count } => sqlx::query(select_submissions!(
+                "WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = $1) \
+                 ORDER BY id DESC LIMIT $2"
+            ))
+            .bind(accepted)
+            .bind(count),
+            SubmissionRequest::RecentAll { count } => {
+                sqlx::query(select_submissions!("ORDER BY id DESC LIMIT $1")).bind(count)
+            }
+            SubmissionRequest::UsersRecentAccepted { user_ids, count } => {
+                sqlx::query(select_submissions!(
+                    "WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = $1) \
+                     AND user_id = ANY($2) ORDER BY epoch_second DESC LIMIT $3"
+                ))
+                .bind(accepted)
+                .bind(user_ids)
+                .bind(count)
+            }
+            SubmissionRequest::UsersAccepted { user_ids } => sqlx::query(select_submissions!(
+                "WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = $1) \
+                 AND user_id = ANY($2)"
+            ))
+            .bind(accepted)
+            .bind(user_ids),
+            SubmissionRequest::AcceptedAfterId { from_id, count } => {
+                sqlx::query(select_submissions!(
+                    "WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = $1) \
+                     AND id > $2 ORDER BY id LIMIT $3"
+                ))
+                .bind(accepted)
+                .bind(from_id)
+                .bind(count)
+            }
+            SubmissionRequest::AfterId { from_id, count } => {
+                sqlx::query(select_submissions!("WHERE id > $1 ORDER BY id LIMIT $2"))
+                    .bind(from_id)
+                    .bind(count)
+            }
+            SubmissionRequest::AfterTimeAndId {
+                epoch_second,
+                id,
+                count,
+            } => sqlx::query(select_submissions!(
+                "WHERE (epoch_second, id) > ($1, $2) ORDER BY epoch_second, id LIMIT $3"
+            ))
+            .bind(epoch_second)
+            .bind(id)
+            .bind(count),
+            SubmissionRequest::AllAccepted => sqlx::query(select_submissions!(
+                "WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = $1)"
+            ))
+            .bind(accepted),
+            // The results not in `result_codes` are still being judged, or unknown yet.
+            SubmissionRequest::InvalidResult { from_second } => sqlx::query(select_submissions!(
+                "WHERE result NOT IN (SELECT raw_result FROM result_codes) \
+                 AND epoch_second >= $1 ORDER BY id DESC"
+            ))
+            .bind(from_second),
+            SubmissionRequest::ByIds { ids } => {
+                sqlx::query(select_submissions!("WHERE id = ANY($1)")).bind(ids)
+            }
+            SubmissionRequest::Shortest { problem_ids } => sqlx::query(select_submissions!(
+                "WHERE id IN (SELECT submission_id FROM shortest WHERE problem_id = ANY($1))"
+            ))
+            .bind(problem_ids),
+            SubmissionRequest::ShortestFromTime { from_second } => {
+                sqlx::query(select_submissions!(
+                    "WHERE id IN (SELECT submission_id FROM shortest) \
+                     AND epoch_second >= $1 ORDER BY id"
+                ))
+                .bind(from_second)
+            }
+            SubmissionRequest::UsersProblemsTime {
+                user_ids,
+                problem_ids,
+                from_second,
+                to_second,
+            } => sqlx::query(select_submissions!(
+                "WHERE user_id = ANY($1) AND problem_id = ANY($2) \
+                 AND epoch_second BETWEEN $3 AND $4 LIMIT 2000"
+            ))
+            .bind(user_ids)
+            .bind(problem_ids)
+            .bind(from_second)
+            .bind(to_second),
+            SubmissionRequest::ProblemsTime {
+                problem_ids,
+                from_second,
+                to_second,
+            } => sqlx::query(select_submissions!(
+                "WHERE problem_id = ANY($1) AND epoch_second BETWEEN $2 AND $3"
+            ))
+            .bind(problem_ids)
+            .bind(from_second)
+            .bind(to_second),
+        };
+        let rows = query.fetch_all(self).await?;
+        rows.iter().map(submission_from_row).collect()
+    }
+
+    async fn get_user_submission_count(&self, user_id: &str) -> Result<i64> {
+        let count = sqlx::query_scalar("SELECT count FROM submission_count WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(self)
+            .await?;
+        Ok(count)
+    }
+}
+
+fn submission_from_row(row: &PgRow) -> Result<Submission> {
+    Ok(Submission {
+        id: row.try_get("id")?,
+        epoch_second: row.try_get("epoch_second")?,
+        problem_id: row.try_get("problem_id")?,
+        contest_id: row.try_get("contest_id")?,
+        user_id: row.try_get("user_id")?,
+        language: row.try_get("language")?,
+        point: Point::from_hundredths(row.try_get("point_hundredths")?),
+        length: row.try_get("length")?,
+        result: row.try_get("result")?,
+        execution_time: row.try_get("execution_time")?,
+    })
 }
@@ -0,0 +1,75 @@
+use super::schema::{contests, submission_codes, submissions};
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+/// The source codes of the submissions fetched from AtCoder, which are kept so that AtCoder is
+/// asked for each submission only once.
+pub trait SubmissionCodeClient {
+    /// Returns the contest of the submission if the submission is public, i.e. the contest has
+    /// ended by `now`.
+    fn load_public_submission_contest(
+        &self,
+        submission_id: i64,
+        now: i64,
+    ) -> Result<Option<String>>;
+    fn load_submission_code(&self, submission_id: i64) -> Result<Option<(String, i64)>>;
+    fn save_submission_code(&self, submission_id: i64, code: &str, now: i64) -> Result<()>;
+}
+
+impl SubmissionCodeClient for PgConnection {
+    fn load_public_submission_contest(
+        &self,
+        submission_id: i64,
+        now: i64,
+    ) -> Result<Option<String>> {
+        let contest_id = match submissions::table
+            .find(submission_id)
+            .select(submissions::contest_id)
+            .first::<String>(self)
+            .optional()?
+        {
+            Some(contest_id) => contest_id,
+            None => return Ok(None),
+        };
+        let (start_epoch_second, duration_second) = match contests::table
+            .find(&contest_id)
+            .select((contests::start_epoch_second, contests::duration_second))
+            .first::<(i64, i64)>(self)
+            .optional()?
+        {
+            Some(contest) => contest,
+            None => return Ok(None),
+        };
+        if start_epoch_second + duration_second <= now {
+            Ok(Some(contest_id))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn load_submission_code(&self, submission_id: i64) -> Result<Option<(String, i64)>> {
+        let code = submission_codes::table
+            .find(submission_id)
+            .select((
+                submission_codes::code,
+                submission_codes::fetched_epoch_second,
+            ))
+            .first::<(String, i64)>(self)
+            .optional()?;
+        Ok(code)
+    }
+
+    fn save_submission_code(&self, submission_id: i64, code: &str, now: i64) -> Result<()> {
+        insert_into(submission_codes::table)
+            .values((
+                submission_codes::submission_id.eq(submission_id),
+                submission_codes::code.eq(code),
+                submission_codes::fetched_epoch_second.eq(now),
+            ))
+            .on_conflict_do_nothing()
+            .execute(self)?;
+        Ok(())
+    }
+}
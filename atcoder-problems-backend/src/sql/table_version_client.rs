@@ -0,0 +1,44 @@
+use super::models::TableVersion;
+use super::schema::table_versions;
+use crate::error::Result;
+
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use diesel::{insert_into, PgConnection};
+
+pub trait TableVersionClient {
+    /// Increments the versions of the tables, so that the caches derived from them become stale.
+    fn bump_table_versions(&self, names: &[&str], now: i64) -> Result<()>;
+    fn load_table_versions(&self) -> Result<Vec<TableVersion>>;
+}
+
+impl TableVersionClient for PgConnection {
+    fn bump_table_versions(&self, names: &[&str], now: i64) -> Result<()> {
+        let values = names
+            .iter()
+            .map(|name| TableVersion {
+                name: (*name).to_owned(),
+                version: 1,
+                updated_epoch_second: now,
+            })
+            .collect::<Vec<_>>();
+        insert_into(table_versions::table)
+            .values(&values)
+            .on_conflict(table_versions::name)
+            .do_update()
+            .set((
+                table_versions::version.eq(table_versions::version + 1),
+                table_versions::updated_epoch_second
+                    .eq(excluded(table_versions::updated_epoch_second)),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn load_table_versions(&self) -> Result<Vec<TableVersion>> {
+        let versions = table_versions::table
+            .order_by(table_versions::name)
+            .load::<TableVersion>(self)?;
+        Ok(versions)
+    }
+}
@@ -0,0 +1,108 @@
+use super::insert_chunks;
+use super::models::ValidationViolation;
+use super::schema::validation_violations;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel::{delete, insert_into, sql_query, PgConnection};
+
+/// The checks of the invariants, each of which selects the violations.
+const CHECKS: [&str; 5] = [
+    r"
+    SELECT
+        'accepted_count' AS check_name,
+        COALESCE(a.user_id, s.user_id) AS subject,
+        format('accepted_count is %s, but %s distinct problems are accepted',
+            COALESCE(a.problem_count, 0), COALESCE(s.problem_count, 0)) AS message,
+        $1 AS detected_epoch_second
+    FROM accepted_count AS a
+    FULL OUTER JOIN (
+        SELECT user_id, COUNT(DISTINCT problem_id) AS problem_count
+        FROM submissions WHERE result IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted') GROUP BY user_id
+    ) AS s ON a.user_id = s.user_id
+    WHERE COALESCE(a.problem_count, 0) <> COALESCE(s.problem_count, 0)",
+    r"
+    SELECT
+        'first' AS check_name,
+        t.problem_id AS subject,
+        format('submission %s is %s', t.submission_id, COALESCE(s.result, 'missing')) AS message,
+        $1 AS detected_epoch_second
+    FROM first AS t LEFT JOIN submissions AS s ON s.id = t.submission_id
+    WHERE s.result IS NULL OR s.result NOT IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')",
+    r"
+    SELECT
+        'fastest' AS check_name,
+        t.problem_id AS subject,
+        format('submission %s is %s', t.submission_id, COALESCE(s.result, 'missing')) AS message,
+        $1 AS detected_epoch_second
+    FROM fastest AS t LEFT JOIN submissions AS s ON s.id = t.submission_id
+    WHERE s.result IS NULL OR s.result NOT IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')",
+    r"
+    SELECT
+        'shortest' AS check_name,
+        t.problem_id AS subject,
+        format('submission %s is %s', t.submission_id, COALESCE(s.result, 'missing')) AS message,
+        $1 AS detected_epoch_second
+    FROM shortest AS t LEFT JOIN submissions AS s ON s.id = t.submission_id
+    WHERE s.result IS NULL OR s.result NOT IN (SELECT raw_result FROM result_codes WHERE verdict = 'accepted')",
+    r"
+    SELECT
+        'points' AS check_name,
+        problem_id AS subject,
+        format('point is %s', point) AS message,
+        $1 AS detected_epoch_second
+    FROM points WHERE point < 0
+    UNION ALL
+    SELECT
+        'rated_point_sum' AS check_name,
+        user_id AS subject,
+        format('point_sum is %s', point_sum) AS message,
+        $1 AS detected_epoch_second
+    FROM rated_point_sum WHERE point_sum < 0",
+];
+
+pub trait ValidationClient {
+    /// Returns the violations of the invariants of the aggregate tables, which are detected at
+    /// `now`.
+    fn find_violations(&self, now: i64) -> Result<Vec<ValidationViolation>>;
+    /// Replaces the stored violations with the latest ones.
+    fn save_violations(&self, violations: &[ValidationViolation]) -> Result<()>;
+    fn load_violations(&self) -> Result<Vec<ValidationViolation>>;
+}
+
+impl ValidationClient for PgConnection {
+    fn find_violations(&self, now: i64) -> Result<Vec<ValidationViolation>> {
+        let mut violations = vec![];
+        for check in CHECKS.iter() {
+            violations.extend(
+                sql_query(*check)
+                    .bind::<BigInt, _>(now)
+                    .load::<ValidationViolation>(self)?,
+            );
+        }
+        Ok(violations)
+    }
+
+    fn save_violations(&self, violations: &[ValidationViolation]) -> Result<()> {
+        self.transaction::<_, http_types::Error, _>(|| {
+            delete(validation_violations::table).execute(self)?;
+            for segment in insert_chunks(violations, 4).into_iter() {
+                insert_into(validation_violations::table)
+                    .values(segment)
+                    .execute(self)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load_violations(&self) -> Result<Vec<ValidationViolation>> {
+        let violations = validation_violations::table
+            .order_by((
+                validation_violations::check_name,
+                validation_violations::subject,
+            ))
+            .load::<ValidationViolation>(self)?;
+        Ok(violations)
+    }
+}
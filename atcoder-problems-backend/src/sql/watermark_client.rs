@@ -0,0 +1,80 @@
+use super::models::{Submission, Watermark};
+use super::schema::updater_watermarks;
+use crate::error::Result;
+
+use diesel::prelude::*;
+use diesel::{insert_into, update, PgConnection};
+
+impl Watermark {
+    /// Returns the watermark at the submission of the largest id, or `None` if there are no
+    /// submissions.
+    pub fn latest(submissions: &[Submission]) -> Option<Self> {
+        submissions
+            .iter()
+            .max_by_key(|submission| submission.id)
+            .map(|submission| Watermark {
+                last_epoch_second: submission.epoch_second,
+                last_submission_id: submission.id,
+            })
+    }
+}
+
+/// The checkpoints of the incremental updaters, which process the submissions after the ones
+/// processed by their previous runs.
+pub trait WatermarkClient {
+    fn load_watermark(&self, name: &str) -> Result<Option<Watermark>>;
+
+    /// Moves the watermark of `name` from `from`, which is `None` if it does not exist yet, to
+    /// `to`. The watermark is not moved if another updater has moved it since `from` was loaded,
+    /// so that a stale run never moves it backward. Returns whether the watermark is moved.
+    fn advance_watermark(
+        &self,
+        name: &str,
+        from: Option<&Watermark>,
+        to: &Watermark,
+    ) -> Result<bool>;
+}
+
+impl WatermarkClient for PgConnection {
+    fn load_watermark(&self, name: &str) -> Result<Option<Watermark>> {
+        let watermark = updater_watermarks::table
+            .find(name)
+            .select((
+                updater_watermarks::last_epoch_second,
+                updater_watermarks::last_submission_id,
+            ))
+            .first::<Watermark>(self)
+            .optional()?;
+        Ok(watermark)
+    }
+
+    fn advance_watermark(
+        &self,
+        name: &str,
+        from: Option<&Watermark>,
+        to: &Watermark,
+    ) -> Result<bool> {
+        let count = match from {
+            Some(from) => update(
+                updater_watermarks::table
+                    .filter(updater_watermarks::name.eq(name))
+                    .filter(updater_watermarks::last_epoch_second.eq(from.last_epoch_second))
+                    .filter(updater_watermarks::last_submission_id.eq(from.last_submission_id)),
+            )
+            .set((
+                updater_watermarks::last_epoch_second.eq(to.last_epoch_second),
+                updater_watermarks::last_submission_id.eq(to.last_submission_id),
+            ))
+            .execute(self)?,
+            None => insert_into(updater_watermarks::table)
+                .values((
+                    updater_watermarks::name.eq(name),
+                    updater_watermarks::last_epoch_second.eq(to.last_epoch_second),
+                    updater_watermarks::last_submission_id.eq(to.last_submission_id),
+                ))
+                .on_conflict_do_nothing()
+                .execute(self)?,
+        };
+        Ok(count > 0)
+    }
+}
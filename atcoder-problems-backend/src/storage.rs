@@ -0,0 +1,60 @@
+mod local;
+mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+use crate::config::{S3Config, StorageConfig};
+use crate::error::Result;
+
+use std::io::Write;
+use std::sync::Arc;
+
+/// Where the static dumps and the backups are stored. The paths start with `/`.
+pub trait Storage: Send + Sync {
+    /// Returns the stored object, or `None` if there is no object at `path`.
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>>;
+    fn put(&self, path: &str, data: &[u8], content_type: &str) -> Result<()>;
+
+    /// Opens a writer which stores the object in parts, for the objects too large to hold in
+    /// memory. The object is stored only when the writer is finished.
+    fn create(&self, path: &str, content_type: &str) -> Result<Box<dyn ObjectWriter>>;
+
+    /// Returns the URL where the object at `path` is served.
+    fn public_url(&self, path: &str) -> String;
+
+    /// Returns the paths of the objects starting with `prefix` in the ascending order.
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+    fn delete(&self, path: &str) -> Result<()>;
+
+    /// Stores the data unless it is the same as the stored one, and returns whether it is stored.
+    fn update(&self, path: &str, data: &[u8], content_type: &str) -> Result<bool> {
+        let old_data = self.get(path).unwrap_or_else(|e| {
+            log::error!("{:?}", e);
+            None
+        });
+        if old_data.as_deref() != Some(data) {
+            log::info!("Uploading new data ...");
+            self.put(path, data, content_type)?;
+            Ok(true)
+        } else {
+            log::info!("No update on {}", path);
+            Ok(false)
+        }
+    }
+}
+
+pub trait ObjectWriter: Write {
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// Returns the storage selected by `storage.backend`.
+pub fn connect(config: &StorageConfig, s3_config: &S3Config) -> Result<Arc<dyn Storage>> {
+    match config.backend.as_str() {
+        "local" => Ok(Arc::new(LocalStorage::new(
+            &config.local_root,
+            &config.local_public_url,
+        ))),
+        _ => Ok(Arc::new(S3Storage::new(s3_config)?)),
+    }
+}
@@ -0,0 +1,127 @@
+use super::{ObjectWriter, Storage};
+use crate::error::Result;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Stores the objects as the files under `root`, e.g. for the development or a mirror which
+/// serves the files by itself.
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new<P: AsRef<Path>>(root: P, public_url: &str) -> Self {
+        Self {
+            root: root.as_ref().to_owned(),
+            public_url: public_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    fn file_path(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    fn prepare(&self, path: &str) -> io::Result<PathBuf> {
+        let file_path = self.file_path(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(file_path)
+    }
+}
+
+impl Storage for LocalStorage {
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.file_path(path)) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put(&self, path: &str, data: &[u8], content_type: &str) -> Result<()> {
+        let mut writer = self.create(path, content_type)?;
+        writer.write_all(data)?;
+        writer.finish()
+    }
+
+    fn create(&self, path: &str, _: &str) -> Result<Box<dyn ObjectWriter>> {
+        let file_path = self.prepare(path)?;
+        let mut temporary_path = file_path.clone().into_os_string();
+        temporary_path.push(PARTIAL_SUFFIX);
+        let temporary_path = PathBuf::from(temporary_path);
+        let file = BufWriter::new(File::create(&temporary_path)?);
+        Ok(Box::new(LocalObjectWriter {
+            file,
+            temporary_path,
+            file_path,
+        }))
+    }
+
+    fn public_url(&self, path: &str) -> String {
+        format!("{}{}", self.public_url, path)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut paths = vec![];
+        if self.root.exists() {
+            list_files(&self.root, "", &mut paths)?;
+        }
+        let mut paths = paths
+            .into_iter()
+            .filter(|path| path.starts_with(prefix) && !path.ends_with(PARTIAL_SUFFIX))
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        fs::remove_file(self.file_path(path))?;
+        Ok(())
+    }
+}
+
+/// Appends the paths of the files under `directory`, which is at `path` in the storage.
+fn list_files(directory: &Path, path: &str, paths: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let entry_path = format!("{}/{}", path, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), &entry_path, paths)?;
+        } else {
+            paths.push(entry_path);
+        }
+    }
+    Ok(())
+}
+
+/// Writes to a temporary file, and renames it when finished, so that the readers never see a
+/// partially written file.
+struct LocalObjectWriter {
+    file: BufWriter<File>,
+    temporary_path: PathBuf,
+    file_path: PathBuf,
+}
+
+impl Write for LocalObjectWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl ObjectWriter for LocalObjectWriter {
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.file.flush()?;
+        fs::rename(&self.temporary_path, &self.file_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+use super::{ObjectWriter, Storage};
+use crate::config::S3Config;
+use crate::error::Result;
+
+use http_types::StatusCode;
+use s3::bucket::Bucket;
+use s3::creds::Credentials;
+use s3::serde_types::Part;
+use std::io::{self, Write};
+
+/// The data larger than this is uploaded in parts, since a single PUT of a large dump is slow
+/// to retry and S3 rejects the objects over 5GiB.
+const MULTIPART_THRESHOLD: usize = 64 * 1024 * 1024;
+/// S3 requires the parts except the last one to be at least 5MiB.
+const PART_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct S3Storage {
+    bucket: Bucket,
+    public_url: String,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3Config) -> Result<Self> {
+        let region = config.region.parse()?;
+        let credentials = Credentials::default()?;
+        // The bucket name has dots, which the TLS certificate of the virtual-hosted style does
+        // not cover.
+        let bucket = Bucket::new(&config.bucket, region, credentials)?.with_path_style();
+        Ok(Self {
+            bucket,
+            public_url: config.public_url.clone(),
+        })
+    }
+
+    fn start_upload(&self, path: &str, content_type: &str) -> Result<MultipartUpload> {
+        let response = self.bucket.initiate_multipart_upload(path, content_type)?;
+        log::info!("Started the multipart upload of {}", path);
+        Ok(MultipartUpload {
+            path: path.to_owned(),
+            upload_id: response.upload_id,
+        })
+    }
+}
+
+impl Storage for S3Storage {
+    fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let response = self.bucket.get_object(path)?;
+        match response.status_code() {
+            200..=299 => Ok(Some(response.to_vec())),
+            404 => Ok(None),
+            status => Err(status_error(status, path)),
+        }
+    }
+
+    fn put(&self, path: &str, data: &[u8], content_type: &str) -> Result<()> {
+        if data.len() > MULTIPART_THRESHOLD {
+            let upload = self.start_upload(path, content_type)?;
+            let mut writer = Box::new(MultipartWriter::new((self, upload), PART_SIZE));
+            writer.write_all(data)?;
+            return writer.finish();
+        }
+        let response = self
+            .bucket
+            .put_object_with_content_type(path, data, content_type)?;
+        match response.status_code() {
+            200..=299 => Ok(()),
+            status => Err(status_error(status, path)),
+        }
+    }
+
+    fn create(&self, path: &str, content_type: &str) -> Result<Box<dyn ObjectWriter>> {
+        let upload = self.start_upload(path, content_type)?;
+        Ok(Box::new(MultipartWriter::new(
+            (self.clone(), upload),
+            PART_SIZE,
+        )))
+    }
+
+    fn public_url(&self, path: &str) -> String {
+        format!("{}{}", self.public_url, path)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.trim_start_matches('/').to_owned();
+        let mut paths = self
+            .bucket
+            .list(prefix, None)?
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .map(|object| format!("/{}", object.key))
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        let response = self.bucket.delete_object(path)?;
+        match response.status_code() {
+            200..=299 => Ok(()),
+            status => Err(status_error(status, path)),
+        }
+    }
+}
+
+fn status_error(status: u16, path: &str) -> http_types::Error {
+    http_types::Error::from_str(
+        StatusCode::InternalServerError,
+        format!("S3 returned {} for {}", status, path),
+    )
+}
+
+#[derive(Clone)]
+struct MultipartUpload {
+    path: String,
+    upload_id: String,
+}
+
+/// Uploads the parts of a multipart upload, separated from `MultipartWriter` so that the
+/// buffering can be tested without S3.
+trait PartUploader {
+    /// Uploads the part and returns its ETag.
+    fn upload_part(&mut self, part_number: usize, data: Vec<u8>) -> Result<String>;
+    fn complete(&mut self, etags: &[String]) -> Result<()>;
+    fn abort(&mut self);
+}
+
+impl<S: std::borrow::Borrow<S3Storage>> PartUploader for (S, MultipartUpload) {
+    fn upload_part(&mut self, part_number: usize, data: Vec<u8>) -> Result<String> {
+        let (storage, upload) = (self.0.borrow(), &self.1);
+        let part = storage.bucket.put_multipart_chunk(
+            data,
+            &upload.path,
+            part_number as u32,
+            &upload.upload_id,
+            "application/octet-stream",
+        )?;
+        Ok(part.etag)
+    }
+
+    fn complete(&mut self, etags: &[String]) -> Result<()> {
+        let (storage, upload) = (self.0.borrow(), &self.1);
+        let parts = etags
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| Part {
+                part_number: i as u32 + 1,
+                etag: etag.clone(),
+            })
+            .collect();
+        let response =
+            storage
+                .bucket
+                .complete_multipart_upload(&upload.path, &upload.upload_id, parts)?;
+        // S3 may report an error of the completion in the body of 200 OK.
+        let body = String::from_utf8_lossy(response.as_slice());
+        if !(200..=299).contains(&response.status_code()) || body.contains("<Error>") {
+            return Err(http_types::Error::from_str(
+                StatusCode::InternalServerError,
+                format!("Failed to complete the upload of {}: {}", upload.path, body),
+            ));
+        }
+        log::info!("Uploaded {} in {} parts", upload.path, etags.len());
+        Ok(())
+    }
+
+    fn abort(&mut self) {
+        let (storage, upload) = (self.0.borrow(), &self.1);
+        if let Err(e) = storage.bucket.abort_upload(&upload.path, &upload.upload_id) {
+            log::error!("Failed to abort the upload of {}: {:?}", upload.path, e);
+        }
+    }
+}
+
+/// Buffers the written data and uploads it every `part_size` bytes. The upload is aborted
+/// unless it is finished, so that S3 does not keep the uploaded parts.
+struct MultipartWriter<U: PartUploader> {
+    uploader: U,
+    buffer: Vec<u8>,
+    part_size: usize,
+    etags: Vec<String>,
+    completed: bool,
+}
+
+impl<U: PartUploader> MultipartWriter<U> {
+    fn new(uploader: U, part_size: usize) -> Self {
+        Self {
+            uploader,
+            buffer: Vec::with_capacity(part_size),
+            part_size,
+            etags: vec![],
+            completed: false,
+        }
+    }
+
+    fn upload_buffer(&mut self) -> Result<()> {
+        let data = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.part_size));
+        let etag = self.uploader.upload_part(self.etags.len() + 1, data)?;
+        self.etags.push(etag);
+        Ok(())
+    }
+}
+
+impl<U: PartUploader> Write for MultipartWriter<U> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = std::cmp::min(buf.len(), self.part_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..length]);
+        if self.buffer.len() == self.part_size {
+            self.upload_buffer()
+                .map_err(|e| io::Error::other(e.to_string()))?;
+        }
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<U: PartUploader> ObjectWriter for MultipartWriter<U> {
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        // S3 requires at least one part, which may be empty if it is the only one.
+        if !self.buffer.is_empty() || self.etags.is_empty() {
+            self.upload_buffer()?;
+        }
+        self.uploader.complete(&self.etags)?;
+        self.completed = true;
+        Ok(())
+    }
+}
+
+impl<U: PartUploader> Drop for MultipartWriter<U> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.uploader.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct Log {
+        parts: Vec<(usize, Vec<u8>)>,
+        completed: Option<Vec<String>>,
+        aborted: bool,
+    }
+
+    struct MockUploader(Rc<RefCell<Log>>);
+
+    impl PartUploader for MockUploader {
+        fn upload_part(&mut self, part_number: usize, data: Vec<u8>) -> Result<String> {
+            self.0.borrow_mut().parts.push((part_number, data));
+            Ok(format!("etag{}", part_number))
+        }
+        fn complete(&mut self, etags: &[String]) -> Result<()> {
+            self.0.borrow_mut().completed = Some(etags.to_vec());
+            Ok(())
+        }
+        fn abort(&mut self) {
+            self.0.borrow_mut().aborted = true;
+        }
+    }
+
+    #[test]
+    fn test_multipart_writer() {
+        let log = Rc::new(RefCell::new(Log::default()));
+        let mut writer = Box::new(MultipartWriter::new(MockUploader(log.clone()), 4));
+        writer.write_all(b"abcdefghij").unwrap();
+        assert_eq!(log.borrow().parts.len(), 2);
+        writer.finish().unwrap();
+
+        let log = log.borrow();
+        assert_eq!(
+            log.parts,
+            vec![
+                (1, b"abcd".to_vec()),
+                (2, b"efgh".to_vec()),
+                (3, b"ij".to_vec())
+            ]
+        );
+        assert_eq!(
+            log.completed,
+            Some(vec![
+                "etag1".to_owned(),
+                "etag2".to_owned(),
+                "etag3".to_owned()
+            ])
+        );
+        assert!(!log.aborted);
+    }
+
+    #[test]
+    fn test_multipart_writer_aborts_unfinished_upload() {
+        let log = Rc::new(RefCell::new(Log::default()));
+        let mut writer = Box::new(MultipartWriter::new(MockUploader(log.clone()), 4));
+        writer.write_all(b"abcdef").unwrap();
+        drop(writer);
+        assert!(log.borrow().aborted);
+        assert_eq!(log.borrow().completed, None);
+
+        let log = Rc::new(RefCell::new(Log::default()));
+        Box::new(MultipartWriter::new(MockUploader(log.clone()), 4))
+            .finish()
+            .unwrap();
+        assert_eq!(log.borrow().parts, vec![(1, vec![])]);
+    }
+}
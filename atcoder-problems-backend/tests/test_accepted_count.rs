@@ -1,11 +1,13 @@
 use atcoder_problems_backend::sql::models::{Submission, UserProblemCount};
 use atcoder_problems_backend::sql::AcceptedCountClient;
+use diesel::connection::SimpleConnection;
 
 mod utils;
 
 #[test]
 fn test_accepted_count() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     let submissions = [
         Submission {
             id: 1,
@@ -67,4 +69,11 @@ fn test_accepted_count() {
     assert_eq!(conn.get_accepted_count_rank(2).unwrap(), 1);
 
     assert!(conn.get_users_accepted_count("non_existing_user").is_none());
+
+    // The banned users do not push down the others.
+    conn.batch_execute(
+        "INSERT INTO internal_banned_users (user_id, banned_epoch_second) VALUES ('user2', 0);",
+    )
+    .unwrap();
+    assert_eq!(conn.get_accepted_count_rank(2).unwrap(), 0);
 }
@@ -0,0 +1,74 @@
+use atcoder_problems_backend::sql::models::UserAchievement;
+use atcoder_problems_backend::sql::AchievementClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_achievement() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO max_streaks (user_id, streak) VALUES ('user1', 100), ('user2', 99);
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_1', 'abc001', 'A. A'),
+            ('abc001_2', 'abc001', 'B. B'),
+            ('abc001_3', 'abc001', 'C. C'),
+            ('abc001_4', 'abc001', 'D. D'),
+            ('arc001_1', 'arc001', 'A. A');
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc001', 'abc001_1'),
+            ('abc001', 'abc001_2'),
+            ('abc001', 'abc001_3'),
+            ('abc001', 'abc001_4'),
+            ('arc001', 'arc001_1');
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'abc001_1', 'abc001', 'user2', 'Rust', 100.0, 20, 'AC'),
+            (2, 100, 'abc001_2', 'abc001', 'user2', 'Rust', 200.0, 20, 'AC'),
+            (3, 100, 'abc001_3', 'abc001', 'user2', 'Rust', 0.0, 20, 'WA'),
+            (4, 100, 'abc001_1', 'abc001', 'user3', 'Rust', 100.0, 20, 'AC');
+        ",
+    )
+    .unwrap();
+
+    conn.update_achievements(1000).unwrap();
+    assert_eq!(
+        conn.load_user_achievements("user1").unwrap(),
+        vec![UserAchievement {
+            user_id: "user1".to_owned(),
+            achievement: "streak_100".to_owned(),
+            epoch_second: 1000,
+        }]
+    );
+    assert!(conn.load_user_achievements("user2").unwrap().is_empty());
+
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (5, 200, 'abc001_3', 'abc001', 'user2', 'Rust', 300.0, 20, 'AC');
+        UPDATE max_streaks SET streak = 1 WHERE user_id = 'user1';
+        ",
+    )
+    .unwrap();
+    conn.update_achievements(2000).unwrap();
+
+    // The achievement is kept with the time when it was earned.
+    assert_eq!(
+        conn.load_user_achievements("user1").unwrap()[0].epoch_second,
+        1000
+    );
+    assert_eq!(
+        conn.load_user_achievements("user2").unwrap(),
+        vec![UserAchievement {
+            user_id: "user2".to_owned(),
+            achievement: "abc_a_to_c".to_owned(),
+            epoch_second: 2000,
+        }]
+    );
+    assert!(conn.load_user_achievements("user3").unwrap().is_empty());
+}
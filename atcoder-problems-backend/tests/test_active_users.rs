@@ -0,0 +1,57 @@
+use atcoder_problems_backend::sql::{ActiveUserClient, HolderKind, HolderRankingClient};
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+fn ranked_users(conn: &diesel::PgConnection, active_since: Option<i64>) -> Vec<String> {
+    conn.load_holder_ranking(HolderKind::First, 0, 10, active_since)
+        .unwrap()
+        .into_iter()
+        .map(|ranked| ranked.user_id)
+        .collect()
+}
+
+#[test]
+fn test_active_users() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user2', 'Rust', 100.0, 20, 'AC'),
+            (3, 300, 'problem3', 'contest1', 'user2', 'Rust', 100.0, 20, 'WA');
+        INSERT INTO first (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 1),
+            ('contest1', 'problem2', 2);
+        ",
+    )
+    .unwrap();
+
+    conn.update_active_users().unwrap();
+    assert_eq!(ranked_users(&conn, None), vec!["user1", "user2"]);
+    assert_eq!(ranked_users(&conn, Some(100)), vec!["user1", "user2"]);
+    assert_eq!(ranked_users(&conn, Some(250)), vec!["user2"]);
+    assert_eq!(
+        conn.get_users_holder_rank(HolderKind::First, "user1", Some(250))
+            .unwrap(),
+        None
+    );
+
+    // Only the new submissions are aggregated, and they move the users into the period.
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (4, 400, 'problem3', 'contest1', 'user1', 'Rust', 100.0, 20, 'WA');
+        ",
+    )
+    .unwrap();
+    assert_eq!(ranked_users(&conn, Some(350)), Vec::<String>::new());
+    conn.update_active_users().unwrap();
+    assert_eq!(ranked_users(&conn, Some(350)), vec!["user1"]);
+    assert_eq!(ranked_users(&conn, Some(250)), vec!["user1", "user2"]);
+}
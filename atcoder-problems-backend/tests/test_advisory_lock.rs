@@ -0,0 +1,42 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::sql::AdvisoryLockClient;
+use diesel::{Connection, PgConnection};
+
+mod utils;
+
+#[test]
+fn test_advisory_lock() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let other = PgConnection::establish(database.url()).unwrap();
+
+    let result = conn
+        .with_advisory_lock("update_step:accepted_count", || {
+            let locked = other
+                .try_with_advisory_lock("update_step:accepted_count", || Ok(()))
+                .unwrap();
+            assert_eq!(locked, None, "The lock is held by the other connection.");
+
+            let locked = other
+                .try_with_advisory_lock("update_step:streak_count", || Ok(1))
+                .unwrap();
+            assert_eq!(locked, Some(1), "The locks of the other names are free.");
+            Ok(2)
+        })
+        .unwrap();
+    assert_eq!(result, 2);
+
+    let locked = other
+        .try_with_advisory_lock("update_step:accepted_count", || Ok(3))
+        .unwrap();
+    assert_eq!(locked, Some(3), "The lock is released after the closure.");
+
+    let failed = conn.with_advisory_lock("update_step:accepted_count", || -> Result<()> {
+        Err(http_types::Error::from_str(500, "failed"))
+    });
+    assert!(failed.is_err());
+    let locked = other
+        .try_with_advisory_lock("update_step:accepted_count", || Ok(()))
+        .unwrap();
+    assert_eq!(locked, Some(()), "The lock is released after the failure.");
+}
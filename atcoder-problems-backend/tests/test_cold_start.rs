@@ -0,0 +1,83 @@
+use atcoder_problems_backend::jobs::estimate_cold_start_difficulties;
+use atcoder_problems_backend::sql::models::ProblemModel;
+use atcoder_problems_backend::sql::ProblemModelClient;
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+#[test]
+fn test_estimate_cold_start_difficulties() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc200', 10000, 6000, 'AtCoder Beginner Contest 200', ' ~ 1999'),
+            ('abc100', 0, 6000, 'AtCoder Beginner Contest 100', ' ~ 1999');
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc200', 'abc200_a'),
+            ('abc200', 'abc200_b'),
+            ('abc200', 'abc200_c'),
+            ('abc100', 'abc100_a');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc200_c', 2000.0, FALSE);
+        ",
+    )
+    .unwrap();
+    let mut sql = String::new();
+    for i in 0..20 {
+        let rating = 100 * i;
+        sql += &format!(
+            "INSERT INTO predicted_rating (user_id, rating) VALUES ('user{}', {});",
+            i, rating
+        );
+        // Everyone solves A, and the users of 1000 or more solve B.
+        sql += &format!(
+            r"
+            INSERT INTO submissions
+                (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+            VALUES
+                ({a}, 10100, 'abc200_a', 'abc200', 'user{i}', 'Rust', 100.0, 1, 'AC'),
+                ({b}, 10200, 'abc200_b', 'abc200', 'user{i}', 'Rust', 0.0, 1, '{result}'),
+                ({c}, 20000, 'abc200_b', 'abc200', 'user{i}', 'Rust', 100.0, 1, 'AC');",
+            a = 3 * i,
+            b = 3 * i + 1,
+            c = 3 * i + 2,
+            i = i,
+            result = if rating >= 1000 { "AC" } else { "WA" },
+        );
+    }
+    conn.batch_execute(&sql).unwrap();
+
+    assert_eq!(
+        estimate_cold_start_difficulties(&conn, 1_000_000).unwrap(),
+        0
+    );
+    assert_eq!(estimate_cold_start_difficulties(&conn, 17000).unwrap(), 2);
+
+    let provisional = conn.load_provisional_difficulties().unwrap();
+    assert_eq!(provisional.len(), 2);
+    assert_eq!(provisional[0].problem_id, "abc200_a");
+    assert_eq!(provisional[0].sample_size, 20);
+    assert_eq!(provisional[1].problem_id, "abc200_b");
+    assert!(provisional[0].difficulty < provisional[1].difficulty);
+    assert!((provisional[1].difficulty - 950.0).abs() < 200.0);
+
+    let models = conn.load_problem_models().unwrap();
+    let model = |problem_id: &str| models.iter().find(|m| m.problem_id == problem_id).unwrap();
+    assert!(model("abc200_a").is_experimental);
+    assert_eq!(
+        model("abc200_c"),
+        &ProblemModel {
+            problem_id: "abc200_c".to_owned(),
+            difficulty: Some(2000.0),
+            is_experimental: false,
+        }
+    );
+
+    // The provisional difficulties are estimated again.
+    assert_eq!(estimate_cold_start_difficulties(&conn, 17000).unwrap(), 2);
+
+    conn.delete_provisional_difficulties(&["abc200_a"]).unwrap();
+    assert_eq!(estimate_cold_start_difficulties(&conn, 17000).unwrap(), 1);
+}
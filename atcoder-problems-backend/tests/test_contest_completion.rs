@@ -0,0 +1,72 @@
+use atcoder_problems_backend::sql::models::{ContestProblem, Submission, UserContestCompletion};
+use atcoder_problems_backend::sql::{ContestCompletionClient, ContestProblemClient};
+
+mod utils;
+
+#[test]
+fn test_contest_completion() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let contest_problems = vec![
+        ("abc001", "abc001_a"),
+        ("abc001", "abc001_b"),
+        ("abc002", "abc002_a"),
+        ("abc002", "shared"),
+        ("arc001", "shared"),
+    ]
+    .into_iter()
+    .map(|(contest_id, problem_id)| ContestProblem {
+        contest_id: contest_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+    })
+    .collect::<Vec<_>>();
+    conn.insert_contest_problem(&contest_problems).unwrap();
+
+    let submissions = vec![
+        (1, "user1", "abc001_a"),
+        (2, "user1", "abc001_a"),
+        (3, "user1", "abc001_b"),
+        (4, "user1", "shared"),
+        (5, "user1", "unknown"),
+        (6, "user2", "abc002_a"),
+    ]
+    .into_iter()
+    .map(|(id, user_id, problem_id)| Submission {
+        id,
+        user_id: user_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+        result: "AC".to_owned(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_contest_completion(&submissions).unwrap();
+
+    let completion = |contest_id: &str, solved_count, problem_count| UserContestCompletion {
+        user_id: "user1".to_owned(),
+        contest_id: contest_id.to_owned(),
+        solved_count,
+        problem_count,
+    };
+    assert_eq!(
+        conn.load_users_contest_completion("user1", false).unwrap(),
+        vec![
+            completion("abc001", 2, 2),
+            completion("abc002", 1, 2),
+            completion("arc001", 1, 1),
+        ]
+    );
+    assert_eq!(
+        conn.load_users_contest_completion("user1", true).unwrap(),
+        vec![completion("abc001", 2, 2), completion("arc001", 1, 1)]
+    );
+    assert_eq!(
+        conn.load_users_contest_completion("user2", false)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(conn
+        .load_users_contest_completion("user2", true)
+        .unwrap()
+        .is_empty());
+}
@@ -5,7 +5,8 @@ mod utils;
 
 #[test]
 fn test_contest_problem() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     assert!(conn.load_contest_problem().unwrap().is_empty());
 
     conn.insert_contest_problem(&vec![ContestProblem {
@@ -0,0 +1,77 @@
+use atcoder_problems_backend::sql::models::{ContestProblemStats, ContestStats};
+use atcoder_problems_backend::sql::ContestStatsClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_contest_stats() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc001', 1000, 100, 'ABC 001', '-'),
+            ('abc002', 2000, 100, 'ABC 002', '-');
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc001', 'abc001_a'),
+            ('abc001', 'abc001_b');
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 1010, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 1020, 'abc001_b', 'abc001', 'user1', 'Rust', 200.0, 20, 'AC'),
+            (3, 1030, 'abc001_a', 'abc001', 'user2', 'Rust', 0.0, 20, 'WA'),
+            (4, 1040, 'abc001_a', 'abc001', 'user2', 'Rust', 100.0, 20, 'AC'),
+            (5, 1050, 'abc001_b', 'abc001', 'user2', 'Rust', 0.0, 20, 'WA'),
+            (6, 1060, 'abc001_b', 'abc001', 'user3', 'Rust', 0.0, 20, 'WA'),
+            -- after the contest
+            (7, 1100, 'abc001_b', 'abc001', 'user2', 'Rust', 200.0, 20, 'AC'),
+            (8, 1200, 'abc001_a', 'abc001', 'user4', 'Rust', 100.0, 20, 'AC');
+        ",
+    )
+    .unwrap();
+
+    conn.update_contest_stats().unwrap();
+    let (stats, problems) = conn.load_contest_stats("abc001").unwrap().unwrap();
+    assert_eq!(
+        stats,
+        ContestStats {
+            contest_id: "abc001".to_owned(),
+            participant_count: 3,
+            full_solve_count: 1,
+        }
+    );
+    assert_eq!(
+        problems,
+        vec![
+            ContestProblemStats {
+                contest_id: "abc001".to_owned(),
+                problem_id: "abc001_a".to_owned(),
+                submitter_count: 2,
+                ac_user_count: 2,
+            },
+            ContestProblemStats {
+                contest_id: "abc001".to_owned(),
+                problem_id: "abc001_b".to_owned(),
+                submitter_count: 3,
+                ac_user_count: 1,
+            },
+        ]
+    );
+    assert!(conn.load_contest_stats("abc002").unwrap().is_none());
+
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (9, 1070, 'abc001_b', 'abc001', 'user2', 'Rust', 200.0, 20, 'AC');
+        ",
+    )
+    .unwrap();
+    conn.update_contest_stats().unwrap();
+    let (stats, problems) = conn.load_contest_stats("abc001").unwrap().unwrap();
+    assert_eq!(stats.full_solve_count, 2);
+    assert_eq!(problems[1].ac_user_count, 2);
+}
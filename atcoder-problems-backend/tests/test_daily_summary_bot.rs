@@ -0,0 +1,73 @@
+use async_std::task::block_on;
+use async_trait::async_trait;
+use atcoder_problems_backend::bot::{DailySummaryBot, Poster, Templates};
+use atcoder_problems_backend::error::Result;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use std::sync::{Arc, Mutex};
+
+mod utils;
+
+const NOW: i64 = 1_600_000_000;
+const DAY: i64 = 24 * 3600;
+
+#[derive(Default)]
+struct MockPoster(Arc<Mutex<Vec<String>>>);
+
+#[async_trait]
+impl Poster for MockPoster {
+    async fn post(&self, text: &str) -> Result<()> {
+        self.0.lock().unwrap().push(text.to_owned());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_daily_summary_bot() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(&format!(
+        r#"
+        INSERT INTO problems (id, contest_id, title)
+        VALUES ('abc100_a', 'abc100', 'A. Happy Birthday!'), ('abc100_b', 'abc100', 'B. Ringo');
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, {yesterday}, 'abc100_a', 'abc100', 'alice', 'Perl', 100.0, 10, 'AC'),
+            (2, {old}, 'abc100_b', 'abc100', 'bob', 'Perl', 100.0, 20, 'AC'),
+            (3, {yesterday}, 'abc100_c', 'abc100', 'cheater', 'Perl', 100.0, 5, 'AC');
+        INSERT INTO shortest (contest_id, problem_id, submission_id)
+        VALUES ('abc100', 'abc100_a', 1), ('abc100', 'abc100_b', 2), ('abc100', 'abc100_c', 3);
+        INSERT INTO max_streaks (user_id, streak)
+        VALUES ('alice', 100), ('bob', 200), ('cheater', 1000);
+        INSERT INTO internal_banned_users (user_id, banned_epoch_second) VALUES ('cheater', 0);
+        "#,
+        yesterday = NOW - DAY / 2,
+        old = NOW - DAY * 2,
+    ))
+    .unwrap();
+
+    let templates = Templates {
+        streak_item: "#{rank} {user_id}: {streak}".to_owned(),
+        ..Templates::default()
+    };
+    let poster = MockPoster::default();
+    let posts = poster.0.clone();
+    let bot = DailySummaryBot::new(
+        PgConnection::establish(database.url()).unwrap(),
+        poster,
+        templates,
+    );
+    assert_eq!(block_on(bot.run(NOW)).unwrap(), 2);
+
+    let posts = posts.lock().unwrap();
+    assert_eq!(
+        posts[0],
+        "New shortest codes on AtCoder today:\nA. Happy Birthday!: alice (10 bytes, Perl)"
+    );
+    assert_eq!(
+        posts[1],
+        "Longest streaks on AtCoder:\n#1 bob: 200\n#2 alice: 100"
+    );
+}
@@ -0,0 +1,62 @@
+use atcoder_problems_backend::jobs::{backup_database, restore_database, DATABASE_BACKUP_PREFIX};
+use atcoder_problems_backend::storage::{LocalStorage, Storage};
+use diesel::connection::SimpleConnection;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use rand::Rng;
+
+mod utils;
+
+#[test]
+fn test_backup_and_restore_database() {
+    use atcoder_problems_backend::sql::schema::submissions;
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'problem1', 'contest1', 'user2', 'C++', 100.0, 30, 'WA');
+    "#,
+    )
+    .unwrap();
+
+    let mut rng = rand::thread_rng();
+    let root = std::env::temp_dir().join(format!("atcoder-problems-backup-{}", rng.gen::<u64>()));
+    let storage = LocalStorage::new(&root, "");
+    let count = |conn: &PgConnection| {
+        submissions::table
+            .select(count_star())
+            .first::<i64>(conn)
+            .unwrap()
+    };
+
+    let first = backup_database(database.url(), &storage, 2).unwrap();
+    conn.batch_execute("DELETE FROM submissions WHERE id = 2")
+        .unwrap();
+    let second = backup_database(database.url(), &storage, 2).unwrap();
+    conn.batch_execute("DELETE FROM submissions").unwrap();
+    let third = backup_database(database.url(), &storage, 2).unwrap();
+    assert_eq!(
+        storage.list(DATABASE_BACKUP_PREFIX).unwrap(),
+        vec![second.clone(), third],
+        "Only the latest 2 backups are kept."
+    );
+    assert!(storage.get(&first).unwrap().is_none());
+    drop(conn);
+
+    restore_database(database.url(), &storage, Some(&second)).unwrap();
+    let conn = PgConnection::establish(database.url()).unwrap();
+    assert_eq!(count(&conn), 1);
+    drop(conn);
+
+    restore_database(database.url(), &storage, None).unwrap();
+    let conn = PgConnection::establish(database.url()).unwrap();
+    assert_eq!(count(&conn), 0, "The latest backup is restored by default.");
+
+    assert!(restore_database(database.url(), &storage, Some("/backups/none.dump")).is_err());
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,73 @@
+use atcoder_problems_backend::sql::models::{ProblemModel, Submission, UserDifficultyCount};
+use atcoder_problems_backend::sql::{DifficultyCountClient, ProblemModelClient};
+
+mod utils;
+
+#[test]
+fn test_difficulty_count() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.update_problem_models(&[
+        ProblemModel {
+            problem_id: "problem1".to_owned(),
+            difficulty: Some(-100.0),
+            is_experimental: false,
+        },
+        ProblemModel {
+            problem_id: "problem2".to_owned(),
+            difficulty: Some(350.0),
+            is_experimental: false,
+        },
+        ProblemModel {
+            problem_id: "problem3".to_owned(),
+            difficulty: Some(1600.0),
+            is_experimental: false,
+        },
+        ProblemModel {
+            problem_id: "problem4".to_owned(),
+            difficulty: None,
+            is_experimental: false,
+        },
+    ])
+    .unwrap();
+    let submissions = vec![
+        (1, "user1", "problem1"),
+        (2, "user1", "problem1"),
+        (3, "user1", "problem2"),
+        (4, "user1", "problem3"),
+        (5, "user1", "problem4"),
+        (6, "user1", "problem5"),
+        (7, "user2", "problem3"),
+    ]
+    .into_iter()
+    .map(|(id, user_id, problem_id)| Submission {
+        id,
+        user_id: user_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+        result: "AC".to_owned(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_difficulty_count(&submissions).unwrap();
+
+    assert_eq!(
+        conn.load_users_difficulty_count("user1").unwrap(),
+        vec![
+            UserDifficultyCount {
+                user_id: "user1".to_owned(),
+                difficulty_from: 0,
+                problem_count: 2,
+            },
+            UserDifficultyCount {
+                user_id: "user1".to_owned(),
+                difficulty_from: 1600,
+                problem_count: 1,
+            },
+        ]
+    );
+    assert_eq!(conn.load_users_difficulty_count("user2").unwrap().len(), 1);
+    assert!(conn
+        .load_users_difficulty_count("user3")
+        .unwrap()
+        .is_empty());
+}
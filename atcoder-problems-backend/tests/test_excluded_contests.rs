@@ -0,0 +1,89 @@
+use atcoder_problems_backend::sql::models::ExcludedContest;
+use atcoder_problems_backend::sql::schema::{first, points};
+use atcoder_problems_backend::sql::{
+    ExcludedContestClient, ProblemInfoUpdater, ProblemsSubmissionUpdater,
+};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+
+mod utils;
+
+#[test]
+fn test_excluded_contests() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert!(conn.load_excluded_contests().unwrap().is_empty());
+
+    conn.exclude_contest("contest2", "April Fools", 1).unwrap();
+    conn.exclude_contest("contest1", "experimental", 2).unwrap();
+    conn.exclude_contest("contest2", "April Fools 2020", 3)
+        .unwrap();
+    assert_eq!(
+        conn.load_excluded_contests().unwrap(),
+        vec![
+            ExcludedContest {
+                contest_id: "contest1".to_owned(),
+                reason: "experimental".to_owned(),
+                created_epoch_second: 2,
+            },
+            ExcludedContest {
+                contest_id: "contest2".to_owned(),
+                reason: "April Fools 2020".to_owned(),
+                created_epoch_second: 1,
+            },
+        ]
+    );
+
+    conn.include_contest("contest1").unwrap();
+    let excluded = conn.load_excluded_contests().unwrap();
+    assert_eq!(excluded.len(), 1);
+    assert_eq!(excluded[0].contest_id, "contest2");
+}
+
+#[test]
+fn test_excluded_contests_in_aggregates() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc001', 1468670400, 100, '', 'All'),
+            ('april_fool', 1468670400, 100, '', 'All');
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc001', 'abc001_a'),
+            ('april_fool', 'april_fool_a');
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 1468670410, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 1468670410, 'april_fool_a', 'april_fool', 'user1', 'Rust', 100.0, 20, 'AC');
+        ",
+    )
+    .unwrap();
+    let aggregated = |conn: &PgConnection| {
+        let points = points::table
+            .select(points::problem_id)
+            .order_by(points::problem_id)
+            .load::<String>(conn)
+            .unwrap();
+        let first = first::table
+            .select(first::problem_id)
+            .order_by(first::problem_id)
+            .load::<String>(conn)
+            .unwrap();
+        (points, first)
+    };
+
+    conn.update_problem_points().unwrap();
+    conn.update_submissions_of_problems().unwrap();
+    let both = vec!["abc001_a".to_owned(), "april_fool_a".to_owned()];
+    assert_eq!(aggregated(&conn), (both.clone(), both));
+
+    // The aggregates already made are removed by the next runs.
+    conn.exclude_contest("april_fool", "April Fools", 0)
+        .unwrap();
+    conn.update_problem_points().unwrap();
+    conn.update_submissions_of_problems().unwrap();
+    let abc = vec!["abc001_a".to_owned()];
+    assert_eq!(aggregated(&conn), (abc.clone(), abc));
+}
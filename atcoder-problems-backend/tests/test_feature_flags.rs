@@ -0,0 +1,37 @@
+use atcoder_problems_backend::feature_flags::{Feature, FeatureFlags, FeatureState};
+use atcoder_problems_backend::sql::FeatureFlagClient;
+use std::time::Duration;
+
+mod utils;
+
+#[test]
+fn test_feature_flags() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert!(conn.load_feature_flags().unwrap().is_empty());
+
+    let flags = FeatureFlags::new(Duration::from_secs(3600));
+    assert!(flags.is_enabled(&conn, Feature::JudgeSummary).unwrap());
+
+    conn.set_feature_flag("judge_summary", false, 1).unwrap();
+    assert!(
+        flags.is_enabled(&conn, Feature::JudgeSummary).unwrap(),
+        "The cached flags are used until they expire."
+    );
+    flags.invalidate();
+    assert!(!flags.is_enabled(&conn, Feature::JudgeSummary).unwrap());
+    assert_eq!(
+        flags.load_states(&conn).unwrap(),
+        vec![FeatureState {
+            name: "judge_summary",
+            enabled: false,
+        }]
+    );
+
+    conn.set_feature_flag("judge_summary", true, 2).unwrap();
+    let flags = FeatureFlags::new(Duration::from_secs(0));
+    assert!(flags.is_enabled(&conn, Feature::JudgeSummary).unwrap());
+    let stored = conn.load_feature_flags().unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].updated_epoch_second, 2);
+}
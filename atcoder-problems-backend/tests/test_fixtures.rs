@@ -0,0 +1,51 @@
+use atcoder_problems_backend::fixtures::{FixtureOptions, Fixtures};
+use atcoder_problems_backend::jobs::update_users;
+use atcoder_problems_backend::sql::{AcceptedCountClient, SimpleClient};
+use std::collections::{BTreeMap, BTreeSet};
+
+mod utils;
+
+#[test]
+fn test_fixtures() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let fixtures = Fixtures::generate(&FixtureOptions {
+        user_count: 20,
+        contest_count: 3,
+        problems_per_contest: 4,
+        submission_count: 2_000,
+        rejudge_rate: 0.05,
+        ..FixtureOptions::default()
+    });
+    fixtures.insert(&conn).unwrap();
+    assert_eq!(conn.load_contests().unwrap().len(), 3);
+    assert_eq!(conn.load_problems().unwrap().len(), 12);
+
+    // The results after the rejudges.
+    let mut results = fixtures
+        .submissions
+        .iter()
+        .map(|s| (s.id, s))
+        .collect::<BTreeMap<_, _>>();
+    for rejudged in fixtures.rejudged.iter() {
+        results.insert(rejudged.id, rejudged);
+    }
+    let mut solved = BTreeMap::new();
+    for s in results.values().filter(|s| s.result == "AC") {
+        solved
+            .entry(s.user_id.as_str())
+            .or_insert_with(BTreeSet::new)
+            .insert(s.problem_id.as_str());
+    }
+
+    let user_ids = solved.keys().copied().collect::<Vec<_>>();
+    update_users(&conn, &user_ids).unwrap();
+    for (user_id, problems) in solved.iter() {
+        assert_eq!(
+            conn.get_users_accepted_count(user_id),
+            Some(problems.len() as i32),
+            "The accepted count of {} reflects the rejudges.",
+            user_id
+        );
+    }
+}
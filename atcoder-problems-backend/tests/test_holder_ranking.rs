@@ -0,0 +1,106 @@
+use atcoder_problems_backend::sql::models::RankedUserCount;
+use atcoder_problems_backend::sql::{HolderKind, HolderRankingClient};
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_holder_ranking() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 100, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (3, 100, 'problem3', 'contest1', 'user2', 'Rust', 100.0, 20, 'AC'),
+            (4, 100, 'problem4', 'contest1', 'user3', 'Rust', 100.0, 20, 'AC'),
+            (5, 100, 'problem5', 'contest1', 'user3', 'Rust', 100.0, 20, 'AC');
+        INSERT INTO first (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 1),
+            ('contest1', 'problem2', 2),
+            ('contest1', 'problem3', 3),
+            ('contest1', 'problem4', 4),
+            ('contest1', 'problem5', 5);
+        INSERT INTO fastest (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 3);
+        ",
+    )
+    .unwrap();
+
+    let ranking = conn
+        .load_holder_ranking(HolderKind::First, 0, 10, None)
+        .unwrap();
+    assert_eq!(
+        ranking,
+        vec![
+            RankedUserCount {
+                user_id: "user1".to_owned(),
+                count: 2,
+                rank: 0,
+            },
+            RankedUserCount {
+                user_id: "user3".to_owned(),
+                count: 2,
+                rank: 0,
+            },
+            RankedUserCount {
+                user_id: "user2".to_owned(),
+                count: 1,
+                rank: 1,
+            },
+        ]
+    );
+    let ranking = conn
+        .load_holder_ranking(HolderKind::First, 2, 3, None)
+        .unwrap();
+    assert_eq!(ranking.len(), 1);
+    assert_eq!(ranking[0].user_id, "user2");
+
+    assert_eq!(
+        conn.get_users_holder_rank(HolderKind::First, "user3", None)
+            .unwrap()
+            .unwrap()
+            .rank,
+        0
+    );
+    assert_eq!(
+        conn.get_users_holder_rank(HolderKind::Fastest, "user2", None)
+            .unwrap(),
+        Some(RankedUserCount {
+            user_id: "user2".to_owned(),
+            count: 1,
+            rank: 0,
+        })
+    );
+    assert!(conn
+        .get_users_holder_rank(HolderKind::Fastest, "user1", None)
+        .unwrap()
+        .is_none());
+    assert!(conn
+        .load_holder_ranking(HolderKind::Shortest, 0, 10, None)
+        .unwrap()
+        .is_empty());
+
+    // The banned users are not ranked, and do not push down the others.
+    conn.batch_execute(
+        "INSERT INTO internal_banned_users (user_id, banned_epoch_second) VALUES ('user1', 0);",
+    )
+    .unwrap();
+    let ranking = conn
+        .load_holder_ranking(HolderKind::First, 0, 10, None)
+        .unwrap();
+    assert_eq!(
+        ranking
+            .iter()
+            .map(|user| (user.user_id.as_str(), user.rank))
+            .collect::<Vec<_>>(),
+        vec![("user3", 0), ("user2", 1)]
+    );
+    assert!(conn
+        .get_users_holder_rank(HolderKind::First, "user1", None)
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::jobs::{Job, JobScheduler};
+use atcoder_problems_backend::sql::{JobRunClient, JobStatus};
+use std::sync::{Arc, Mutex};
+
+mod utils;
+
+struct MockJob {
+    name: &'static str,
+    fail: bool,
+    run_count: Arc<Mutex<usize>>,
+}
+
+#[async_trait(?Send)]
+impl Job for MockJob {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(&self, _: &str) -> Result<()> {
+        *self.run_count.lock().unwrap() += 1;
+        if self.fail {
+            Err(http_types::Error::from_str(500, "failed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn tick(scheduler: &JobScheduler, now: i64) -> usize {
+    let handles = scheduler.tick(now).unwrap();
+    let count = handles.len();
+    for handle in handles.into_iter() {
+        handle.join().unwrap();
+    }
+    count
+}
+
+#[test]
+fn test_job_run_client() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let id = conn.start_job_run("job", 100).unwrap().unwrap();
+    assert_eq!(conn.start_job_run("job", 200).unwrap(), None);
+    assert!(conn.start_job_run("other", 200).unwrap().is_some());
+
+    conn.finish_job_run(id, JobStatus::Succeeded, None, 300)
+        .unwrap();
+    let next = conn.start_job_run("job", 400).unwrap().unwrap();
+    assert_ne!(id, next);
+
+    let runs = conn.load_latest_job_runs().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].job_name, "job");
+    assert_eq!(runs[0].started_epoch_second, 400);
+    assert_eq!(runs[0].status, "running");
+
+    assert_eq!(conn.abandon_running_job_runs(500).unwrap(), 2);
+    let runs = conn.load_latest_job_runs().unwrap();
+    assert!(runs.iter().all(|run| run.status == "abandoned"));
+}
+
+#[test]
+fn test_job_scheduler() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let success_count = Arc::new(Mutex::new(0));
+    let failure_count = Arc::new(Mutex::new(0));
+    let scheduler = JobScheduler::new(database.url())
+        .add(
+            MockJob {
+                name: "success",
+                fail: false,
+                run_count: success_count.clone(),
+            },
+            60,
+        )
+        .add(
+            MockJob {
+                name: "failure",
+                fail: true,
+                run_count: failure_count.clone(),
+            },
+            3600,
+        );
+
+    assert_eq!(tick(&scheduler, 1000), 2);
+    assert_eq!(tick(&scheduler, 1059), 0);
+    assert_eq!(tick(&scheduler, 1060), 1);
+    assert_eq!(*success_count.lock().unwrap(), 2);
+    assert_eq!(*failure_count.lock().unwrap(), 1);
+
+    let runs = conn.load_latest_job_runs().unwrap();
+    assert_eq!(runs[0].job_name, "failure");
+    assert_eq!(runs[0].status, "failed");
+    assert!(runs[0].message.is_some());
+    assert!(runs[0].finished_epoch_second.is_some());
+    assert_eq!(runs[1].job_name, "success");
+    assert_eq!(runs[1].status, "succeeded");
+    assert_eq!(runs[1].started_epoch_second, 1060);
+
+    // A job does not start while its previous run is running.
+    conn.start_job_run("success", 1100).unwrap().unwrap();
+    assert_eq!(tick(&scheduler, 2000), 0);
+    assert_eq!(*success_count.lock().unwrap(), 2);
+}
@@ -0,0 +1,67 @@
+use atcoder_problems_backend::sql::models::{JudgeProblem, JudgeSubmission};
+use atcoder_problems_backend::sql::{Judge, JudgeClient};
+
+mod utils;
+
+#[test]
+fn test_judge_submissions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let submission = |id: i64, user_id: &str, result: &str| JudgeSubmission {
+        judge: "codeforces".to_owned(),
+        id,
+        problem_id: "1000A".to_owned(),
+        user_id: user_id.to_owned(),
+        result: result.to_owned(),
+        ..Default::default()
+    };
+    conn.update_judge_submissions(&[
+        submission(1, "user1", "WJ"),
+        submission(2, "user1", "WA"),
+        submission(3, "user2", "AC"),
+    ])
+    .unwrap();
+    assert!(conn
+        .get_judge_accepted_submissions(Judge::Codeforces, "user1")
+        .unwrap()
+        .is_empty());
+
+    conn.update_judge_submissions(&[submission(1, "user1", "AC")])
+        .unwrap();
+    let accepted = conn
+        .get_judge_accepted_submissions(Judge::Codeforces, "user1")
+        .unwrap();
+    assert_eq!(accepted, vec![submission(1, "user1", "AC")]);
+    assert_eq!(
+        conn.get_judge_accepted_submissions(Judge::Codeforces, "user2")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(conn
+        .get_judge_accepted_submissions(Judge::AtCoder, "user2")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_judge_problems() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let problem = JudgeProblem {
+        judge: "codeforces".to_owned(),
+        id: "1000A".to_owned(),
+        title: "A. Problem".to_owned(),
+        difficulty: None,
+    };
+    assert_eq!(
+        conn.update_judge_problems(std::slice::from_ref(&problem))
+            .unwrap(),
+        1
+    );
+    let rated = JudgeProblem {
+        difficulty: Some(800.0),
+        ..problem
+    };
+    assert_eq!(conn.update_judge_problems(&[rated]).unwrap(), 1);
+}
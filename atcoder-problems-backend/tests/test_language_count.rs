@@ -5,7 +5,8 @@ pub mod utils;
 
 #[test]
 fn test_language_count() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     let submissions = [
         Submission {
             id: 1,
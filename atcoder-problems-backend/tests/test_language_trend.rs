@@ -0,0 +1,55 @@
+use atcoder_problems_backend::sql::models::{LanguageTrend, Submission};
+use atcoder_problems_backend::sql::LanguageTrendClient;
+
+mod utils;
+
+#[test]
+fn test_language_trend() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let submissions = vec![
+        // 2020-01-01 00:00:00 JST
+        (1, 1_577_804_400, "user1", "Rust (1.42.0)"),
+        (2, 1_577_804_400, "user1", "Rust (1.15.1)"),
+        (3, 1_577_804_400, "user2", "Rust (1.42.0)"),
+        (4, 1_577_804_400, "user2", "C++14 (GCC 5.4.1)"),
+        // 2019-12-31 23:59:59 JST
+        (5, 1_577_804_399, "user1", "Rust (1.15.1)"),
+    ]
+    .into_iter()
+    .map(|(id, epoch_second, user_id, language)| Submission {
+        id,
+        epoch_second,
+        user_id: user_id.to_owned(),
+        language: language.to_owned(),
+        result: "AC".to_owned(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_language_trends(&submissions).unwrap();
+
+    assert_eq!(
+        conn.load_language_trends(Some("Rust")).unwrap(),
+        vec![
+            LanguageTrend {
+                month: "2019-12".to_owned(),
+                simplified_language: "Rust".to_owned(),
+                submission_count: 1,
+                user_count: 1,
+            },
+            LanguageTrend {
+                month: "2020-01".to_owned(),
+                simplified_language: "Rust".to_owned(),
+                submission_count: 3,
+                user_count: 2,
+            },
+        ]
+    );
+    let trends = conn.load_language_trends(None).unwrap();
+    assert_eq!(trends.len(), 3);
+    assert_eq!(trends[1].simplified_language, "C++");
+    assert!(conn
+        .load_language_trends(Some("Python"))
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,49 @@
+use atcoder_problems_backend::jobs::{run_update_step, update_users, UpdateStep};
+use atcoder_problems_backend::sql::{AcceptedCountClient, MaintenanceClient};
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_maintenance_client() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert_eq!(conn.load_latest_submission_epoch_second().unwrap(), None);
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 300, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (3, 200, 'problem1', 'contest1', 'user2', 'C++', 100.0, 30, 'AC');
+    "#,
+    )
+    .unwrap();
+    assert_eq!(
+        conn.load_latest_submission_epoch_second().unwrap(),
+        Some(300)
+    );
+
+    conn.rebuild_indexes(Some("submissions")).unwrap();
+    conn.rebuild_indexes(None).unwrap();
+    assert!(conn
+        .rebuild_indexes(Some("submissions; DROP TABLE submissions"))
+        .is_err());
+
+    update_users(&conn, &["user1"]).unwrap();
+    assert_eq!(conn.get_users_accepted_count("user1"), Some(2));
+    assert_eq!(
+        conn.get_users_accepted_count("user2"),
+        None,
+        "Only the given user is recomputed."
+    );
+
+    run_update_step(&conn, UpdateStep::AcceptedCount).unwrap();
+    assert_eq!(conn.get_users_accepted_count("user2"), Some(1));
+    assert_eq!(
+        UpdateStep::parse("accepted_count"),
+        Some(UpdateStep::AcceptedCount)
+    );
+    assert_eq!(UpdateStep::parse("unknown"), None);
+}
@@ -0,0 +1,125 @@
+use atcoder_problems_backend::sql::models::{
+    Contest, ContestProblem, MarathonBestScore, MarathonRank, Point, Submission,
+};
+use atcoder_problems_backend::sql::{
+    ContestProblemClient, MarathonScoreClient, RatedPointSumClient, SimpleClient,
+};
+
+mod utils;
+
+fn contest(id: &str, title: &str) -> Contest {
+    Contest {
+        id: id.to_owned(),
+        title: title.to_owned(),
+        start_epoch_second: 1_600_000_000,
+        duration_second: 1000,
+        rate_change: "All".to_owned(),
+    }
+}
+
+#[test]
+fn test_marathon_score() {
+    assert!(contest("ahc001", "AtCoder Heuristic Contest 001").is_heuristic());
+    assert!(contest("chokudai001", "Chokudai Contest 001").is_heuristic());
+    assert!(!contest("abc001", "AtCoder Beginner Contest 001").is_heuristic());
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.insert_contests(&[
+        contest("ahc001", "AtCoder Heuristic Contest 001"),
+        contest("abc001", "AtCoder Beginner Contest 001"),
+    ])
+    .unwrap();
+
+    let submissions = vec![
+        (1, "user1", "ahc001_a", "ahc001", 100.0),
+        (2, "user1", "ahc001_a", "ahc001", 300.0),
+        (3, "user1", "ahc001_a", "ahc001", 300.0),
+        (4, "user1", "ahc001_a", "ahc001", 200.0),
+        (5, "user2", "ahc001_a", "ahc001", 500.0),
+        (6, "user3", "ahc001_a", "ahc001", 300.0),
+        (7, "user1", "abc001_a", "abc001", 100.0),
+    ]
+    .into_iter()
+    .map(|(id, user_id, problem_id, contest_id, point)| Submission {
+        id,
+        epoch_second: id * 10,
+        user_id: user_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+        contest_id: contest_id.to_owned(),
+        point: Point::from(point),
+        result: "AC".to_owned(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_marathon_scores(&submissions).unwrap();
+
+    assert_eq!(
+        conn.load_users_marathon_scores("user1").unwrap(),
+        vec![MarathonBestScore {
+            user_id: "user1".to_owned(),
+            problem_id: "ahc001_a".to_owned(),
+            contest_id: "ahc001".to_owned(),
+            score: 300.0,
+            submission_id: 2,
+            epoch_second: 20,
+        }],
+        "The first submission of the best score is kept, and ABC is not a heuristic contest."
+    );
+
+    let rank = |user_id: &str, score, rank| MarathonRank {
+        contest_id: "ahc001".to_owned(),
+        user_id: user_id.to_owned(),
+        score,
+        rank,
+    };
+    assert_eq!(
+        conn.load_marathon_leaderboard("ahc001").unwrap(),
+        vec![
+            rank("user2", 500.0, 1),
+            rank("user1", 300.0, 2),
+            rank("user3", 300.0, 2),
+        ]
+    );
+    assert!(conn.load_marathon_leaderboard("abc001").unwrap().is_empty());
+
+    conn.insert_contest_problem(&[
+        ContestProblem {
+            contest_id: "ahc001".to_owned(),
+            problem_id: "ahc001_a".to_owned(),
+        },
+        ContestProblem {
+            contest_id: "abc001".to_owned(),
+            problem_id: "abc001_a".to_owned(),
+        },
+    ])
+    .unwrap();
+    conn.update_rated_point_sum(&submissions).unwrap();
+    assert_eq!(
+        conn.get_users_rated_point_sum("user1"),
+        Some(Point::from(100.0)),
+        "The scores of the heuristic contests are not in the point sums."
+    );
+    assert_eq!(conn.get_users_rated_point_sum("user2"), None);
+
+    // The delta update of a user ranks the contest again.
+    let better = Submission {
+        id: 8,
+        epoch_second: 80,
+        user_id: "user3".to_owned(),
+        problem_id: "ahc001_a".to_owned(),
+        contest_id: "ahc001".to_owned(),
+        point: Point::from(600.0),
+        result: "AC".to_owned(),
+        ..Default::default()
+    };
+    conn.update_marathon_scores(&[better]).unwrap();
+    assert_eq!(
+        conn.load_marathon_leaderboard("ahc001").unwrap(),
+        vec![
+            rank("user3", 600.0, 1),
+            rank("user2", 500.0, 2),
+            rank("user1", 300.0, 3),
+        ]
+    );
+}
@@ -0,0 +1,462 @@
+use async_std::task::block_on;
+use async_trait::async_trait;
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::notification::{
+    DailyDigestGenerator, EmailChannel, EmailSender, NotificationDispatcher, NotificationFeeder,
+    WebhookSender, NOTIFICATION_WATERMARK,
+};
+use atcoder_problems_backend::sql::internal::notification_manager::{
+    EventType, NotificationManager, WebhookFormat,
+};
+use atcoder_problems_backend::sql::models::Watermark;
+use atcoder_problems_backend::sql::WatermarkClient;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+mod utils;
+
+const DAY: i64 = 18500;
+
+/// Lets the feeder start from the first submission.
+fn initialize_watermark(conn: &PgConnection) {
+    let first = Watermark {
+        last_epoch_second: 0,
+        last_submission_id: 0,
+    };
+    conn.advance_watermark(NOTIFICATION_WATERMARK, None, &first)
+        .unwrap();
+}
+
+/// Returns 1:00 in JST of the day.
+fn epoch_second(day: i64) -> i64 {
+    day * 86400 - 8 * 3600
+}
+
+fn insert_submission(
+    conn: &PgConnection,
+    id: i64,
+    epoch_second: i64,
+    problem_id: &str,
+    user_id: &str,
+    length: i32,
+    result: &str,
+) {
+    conn.batch_execute(&format!(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            ({}, {}, '{}', 'contest', '{}', 'Rust', 100.0, {}, '{}');
+        "#,
+        id, epoch_second, problem_id, user_id, length, result
+    ))
+    .unwrap();
+}
+
+#[derive(Default)]
+struct MockSender {
+    requests: Arc<Mutex<Vec<(String, String, String)>>>,
+    fail_first: Mutex<bool>,
+}
+
+#[async_trait]
+impl WebhookSender for MockSender {
+    async fn send(&self, url: &str, signature: &str, body: &str) -> Result<()> {
+        let mut fail_first = self.fail_first.lock().unwrap();
+        if *fail_first {
+            *fail_first = false;
+            return Err(http_types::Error::from_str(500, "failed"));
+        }
+        self.requests
+            .lock()
+            .unwrap()
+            .push((url.to_owned(), signature.to_owned(), body.to_owned()));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_notification() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO internal_users (internal_user_id, atcoder_user_id)
+        VALUES ('u1', 'alice'), ('u2', 'bob'), ('u3', NULL);
+        "#,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u1",
+        "https://example.com/alice",
+        "secret",
+        &[
+            EventType::NewAc,
+            EventType::StreakMilestone,
+            EventType::ShortestOvertaken,
+        ],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u2",
+        "https://example.com/bob",
+        "secret",
+        &[EventType::NewAc],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u3",
+        "https://example.com/u3",
+        "secret",
+        &[EventType::NewAc],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+    assert!(conn
+        .register_webhook(
+            "u1",
+            "http://example.com",
+            "secret",
+            &[EventType::NewAc],
+            WebhookFormat::Json,
+            0
+        )
+        .is_err());
+    assert_eq!(conn.get_webhooks("u1").unwrap().len(), 1);
+
+    // alice has solved a new problem every day for 6 days.
+    for i in 1..=6 {
+        let problem_id = format!("p{}", i);
+        insert_submission(
+            &conn,
+            i,
+            epoch_second(DAY - 7 + i),
+            &problem_id,
+            "alice",
+            100,
+            "AC",
+        );
+    }
+    insert_submission(&conn, 7, epoch_second(DAY - 1), "q", "alice", 100, "AC");
+    conn.batch_execute(
+        "INSERT INTO shortest (contest_id, problem_id, submission_id) VALUES ('contest', 'q', 7);",
+    )
+    .unwrap();
+
+    let feeder = NotificationFeeder::new(PgConnection::establish(database.url()).unwrap());
+    let now = epoch_second(DAY);
+    assert_eq!(feeder.feed(now).unwrap(), 0);
+    assert_eq!(
+        conn.load_watermark(NOTIFICATION_WATERMARK)
+            .unwrap()
+            .map(|w| w.last_submission_id),
+        Some(7)
+    );
+
+    insert_submission(&conn, 8, epoch_second(DAY), "p7", "alice", 100, "AC");
+    insert_submission(&conn, 9, epoch_second(DAY), "p1", "alice", 100, "AC");
+    insert_submission(&conn, 10, epoch_second(DAY), "q", "bob", 50, "AC");
+    insert_submission(&conn, 11, epoch_second(DAY), "p8", "alice", 100, "WA");
+
+    assert_eq!(feeder.feed(now).unwrap(), 4);
+    assert_eq!(
+        conn.load_watermark(NOTIFICATION_WATERMARK)
+            .unwrap()
+            .map(|w| w.last_submission_id),
+        Some(10)
+    );
+    assert_eq!(feeder.feed(now).unwrap(), 0);
+
+    let sender = MockSender::default();
+    *sender.fail_first.lock().unwrap() = true;
+    let dispatcher =
+        NotificationDispatcher::new(PgConnection::establish(database.url()).unwrap(), sender);
+    assert_eq!(block_on(dispatcher.dispatch(now)).unwrap(), 3);
+    assert_eq!(block_on(dispatcher.dispatch(now)).unwrap(), 0);
+    assert_eq!(block_on(dispatcher.dispatch(now + 60)).unwrap(), 1);
+    assert_eq!(block_on(dispatcher.dispatch(now + 3600)).unwrap(), 0);
+}
+
+#[test]
+fn test_notification_payload() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        "INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES ('u1', 'alice');",
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u1",
+        "https://example.com/alice",
+        "secret",
+        &[EventType::StreakMilestone],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u1",
+        "https://discord.com/api/webhooks/1/token",
+        "secret",
+        &[EventType::StreakMilestone],
+        WebhookFormat::Discord,
+        0,
+    )
+    .unwrap();
+    assert!(conn
+        .register_webhook(
+            "u1",
+            "https://example.com/slack",
+            "secret",
+            &[EventType::StreakMilestone],
+            WebhookFormat::Slack,
+            0,
+        )
+        .is_err());
+    conn.batch_execute(
+        r#"
+        INSERT INTO problems (id, contest_id, title) VALUES ('p7', 'contest', 'G. Seven');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental)
+        VALUES ('p7', 1300.0, FALSE);
+        "#,
+    )
+    .unwrap();
+    initialize_watermark(&conn);
+    for i in 1..=7 {
+        let problem_id = format!("p{}", i);
+        insert_submission(
+            &conn,
+            i,
+            epoch_second(DAY - 7 + i),
+            &problem_id,
+            "alice",
+            100,
+            "AC",
+        );
+    }
+
+    let feeder = NotificationFeeder::new(PgConnection::establish(database.url()).unwrap());
+    assert_eq!(feeder.feed(epoch_second(DAY)).unwrap(), 2);
+
+    let sender = MockSender::default();
+    let requests = sender.requests.clone();
+    let dispatcher =
+        NotificationDispatcher::new(PgConnection::establish(database.url()).unwrap(), sender);
+    assert_eq!(block_on(dispatcher.dispatch(epoch_second(DAY))).unwrap(), 2);
+    let mut requests = requests.lock().unwrap();
+    requests.sort();
+    let (url, _, body) = &requests[0];
+    assert_eq!(url, "https://discord.com/api/webhooks/1/token");
+    let body: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["embeds"][0]["title"], "G. Seven");
+    assert_eq!(
+        body["embeds"][0]["description"],
+        "alice reached a 7-day streak by solving G. Seven"
+    );
+
+    let (url, signature, body) = &requests[1];
+    assert_eq!(url, "https://example.com/alice");
+    assert!(signature.starts_with("sha256="));
+    let body: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["event"], "streak_milestone");
+    assert_eq!(body["user_id"], "alice");
+    assert_eq!(body["submission_id"], 7);
+    assert_eq!(body["streak"], 7);
+    assert_eq!(body["problem_title"], "G. Seven");
+    assert_eq!(body["difficulty"], 1300.0);
+}
+
+#[test]
+fn test_daily_digest() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO internal_users (internal_user_id, atcoder_user_id)
+        VALUES ('u1', 'alice'), ('u2', 'bob');
+        INSERT INTO internal_watched_users (internal_user_id, watched_user_id)
+        VALUES ('u1', 'bob'), ('u1', 'carol');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('p1', 400.0, FALSE),
+            ('p2', 800.0, FALSE),
+            ('p3', 1200.0, FALSE),
+            ('p4', 1000.0, FALSE);
+        "#,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u1",
+        "https://example.com/alice",
+        "secret",
+        &[EventType::DailyDigest],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+    conn.register_webhook(
+        "u2",
+        "https://example.com/bob",
+        "secret",
+        &[EventType::NewAc],
+        WebhookFormat::Json,
+        0,
+    )
+    .unwrap();
+
+    insert_submission(&conn, 1, epoch_second(DAY - 2), "p1", "alice", 100, "AC");
+    insert_submission(&conn, 2, epoch_second(DAY - 1), "p2", "alice", 100, "AC");
+    insert_submission(&conn, 3, epoch_second(DAY - 1), "p1", "alice", 100, "AC");
+    insert_submission(&conn, 4, epoch_second(DAY - 1), "p3", "alice", 100, "WA");
+    insert_submission(&conn, 5, epoch_second(DAY - 1), "p1", "bob", 100, "AC");
+    insert_submission(&conn, 6, epoch_second(DAY), "p3", "bob", 100, "AC");
+
+    let generator = DailyDigestGenerator::new(PgConnection::establish(database.url()).unwrap());
+    let now = epoch_second(DAY);
+    assert_eq!(generator.generate(now).unwrap(), 1);
+    assert_eq!(generator.generate(now).unwrap(), 0);
+
+    let day_epoch_second = epoch_second(DAY - 1) - 3600;
+    let digest = conn
+        .get_daily_digest("alice", day_epoch_second)
+        .unwrap()
+        .unwrap();
+    let digest: Value = serde_json::from_str(&digest).unwrap();
+    assert_eq!(digest["event"], "daily_digest");
+    assert_eq!(digest["solved_problem_ids"], serde_json::json!(["p2"]));
+    assert_eq!(digest["streak"], 2);
+    assert_eq!(
+        digest["rivals"],
+        serde_json::json!([
+            {"user_id": "bob", "solved_count": 1},
+            {"user_id": "carol", "solved_count": 0}
+        ])
+    );
+    assert_eq!(digest["recommended_problem_id"], "p4");
+    assert!(conn
+        .get_daily_digest("bob", day_epoch_second)
+        .unwrap()
+        .is_none());
+
+    let sender = MockSender::default();
+    let requests = sender.requests.clone();
+    let dispatcher =
+        NotificationDispatcher::new(PgConnection::establish(database.url()).unwrap(), sender);
+    assert_eq!(block_on(dispatcher.dispatch(now)).unwrap(), 1);
+    assert_eq!(requests.lock().unwrap()[0].0, "https://example.com/alice");
+}
+
+#[derive(Default)]
+struct MockEmailSender {
+    emails: Arc<Mutex<Vec<(String, String, String)>>>,
+}
+
+#[async_trait]
+impl EmailSender for MockEmailSender {
+    async fn send(&self, address: &str, subject: &str, html: &str) -> Result<()> {
+        self.emails
+            .lock()
+            .unwrap()
+            .push((address.to_owned(), subject.to_owned(), html.to_owned()));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_email_notification() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        "INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES ('u1', 'alice');",
+    )
+    .unwrap();
+    assert!(conn
+        .register_email("u1", "not an address", &[EventType::NewAc], 0)
+        .is_err());
+    assert!(conn
+        .register_email(
+            "u1",
+            "alice@example.com",
+            &[EventType::EmailVerification],
+            0
+        )
+        .is_err());
+    assert!(conn
+        .register_webhook(
+            "u1",
+            "mailto:alice@example.com",
+            "secret",
+            &[EventType::NewAc],
+            WebhookFormat::Email,
+            0
+        )
+        .is_err());
+
+    let (id, verification_token) = conn
+        .register_email("u1", "alice@example.com", &[EventType::NewAc], 0)
+        .unwrap();
+    conn.enqueue_deliveries(
+        &[(
+            id,
+            EventType::EmailVerification,
+            format!(
+                r#"{{"event": "email_verification", "token": "{}"}}"#,
+                verification_token
+            ),
+        )],
+        0,
+    )
+    .unwrap();
+    assert!(conn.get_subscriptions().unwrap().is_empty());
+
+    let sender = MockEmailSender::default();
+    let emails = sender.emails.clone();
+    let dispatcher = NotificationDispatcher::new(
+        PgConnection::establish(database.url()).unwrap(),
+        MockSender::default(),
+    )
+    .with_email(EmailChannel {
+        sender: Box::new(sender),
+        api_url: "https://example.com".to_owned(),
+    });
+    assert_eq!(block_on(dispatcher.dispatch(0)).unwrap(), 1);
+    {
+        let emails = emails.lock().unwrap();
+        let (address, _, html) = &emails[0];
+        assert_eq!(address, "alice@example.com");
+        assert!(html.contains(&format!(
+            "https://example.com/internal-api/email/verify?token={}",
+            verification_token
+        )));
+    }
+
+    assert!(conn.verify_email("wrong").is_err());
+    conn.verify_email(&verification_token).unwrap();
+    assert!(conn.verify_email(&verification_token).is_err());
+    assert_eq!(conn.get_subscriptions().unwrap().len(), 1);
+
+    initialize_watermark(&conn);
+    insert_submission(&conn, 1, epoch_second(DAY), "p1", "alice", 100, "AC");
+    let feeder = NotificationFeeder::new(PgConnection::establish(database.url()).unwrap());
+    assert_eq!(feeder.feed(epoch_second(DAY)).unwrap(), 1);
+    assert_eq!(block_on(dispatcher.dispatch(epoch_second(DAY))).unwrap(), 1);
+    let unsubscribe_token = {
+        let emails = emails.lock().unwrap();
+        let (_, subject, html) = &emails[1];
+        assert_eq!(subject, "alice solved p1");
+        let start = html.find("unsubscribe?token=").unwrap() + "unsubscribe?token=".len();
+        let end = start + html[start..].find('"').unwrap();
+        html[start..end].to_owned()
+    };
+
+    conn.unsubscribe_email(&unsubscribe_token).unwrap();
+    assert!(conn.get_subscriptions().unwrap().is_empty());
+    assert!(conn.get_webhooks("u1").unwrap().is_empty());
+}
@@ -0,0 +1,40 @@
+use atcoder_problems_backend::sql::{PeerStats, PeerStatsClient};
+
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+#[test]
+fn test_load_peer_stats() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO accepted_count (user_id, problem_count) VALUES
+        ('user', 100), ('peer1', 90), ('peer2', 110), ('other', 200);
+        INSERT INTO difficulty_count (user_id, difficulty_from, problem_count) VALUES
+        ('user', 0, 100), ('peer1', 0, 80), ('peer1', 400, 10), ('peer2', 0, 110), ('other', 0, 200);
+        INSERT INTO contest_completion (user_id, contest_id, solved_count, problem_count) VALUES
+        ('user', 'abc001', 4, 4), ('peer1', 'abc001', 3, 4), ('peer2', 'abc001', 4, 4),
+        ('peer2', 'arc001', 1, 4), ('other', 'arc001', 4, 4);
+        ",
+    )
+    .unwrap();
+
+    let stats = conn.load_peer_stats("user", 80, 120).unwrap();
+    assert_eq!(
+        stats,
+        PeerStats {
+            user_count: 2,
+            difficulty_counts: vec![(0, 190), (400, 10)].into_iter().collect(),
+            contest_solved_counts: vec![("abc001".to_owned(), 7), ("arc001".to_owned(), 1)]
+                .into_iter()
+                .collect(),
+        }
+    );
+
+    assert_eq!(
+        conn.load_peer_stats("user", 300, 400).unwrap(),
+        PeerStats::default()
+    );
+}
@@ -1,4 +1,4 @@
-use atcoder_problems_backend::sql::models::{Contest, Submission};
+use atcoder_problems_backend::sql::models::{Contest, Point, Submission};
 use atcoder_problems_backend::sql::schema::{points, solver};
 use atcoder_problems_backend::sql::{ProblemInfoUpdater, SimpleClient, SubmissionClient};
 
@@ -14,16 +14,17 @@ fn get_solver(conn: &PgConnection) -> Vec<(String, i32)> {
         .unwrap()
 }
 
-fn get_points(conn: &PgConnection) -> Vec<(String, Option<f64>)> {
+fn get_points(conn: &PgConnection) -> Vec<(String, Option<Point>)> {
     points::table
         .select((points::problem_id, points::point))
-        .load::<(String, Option<f64>)>(conn)
+        .load::<(String, Option<Point>)>(conn)
         .unwrap()
 }
 
 #[test]
 fn test_update_problem_solver_count() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
 
     assert!(get_solver(&conn).is_empty());
 
@@ -71,7 +72,8 @@ fn test_update_problem_points() {
     let contest_id = "contest";
     let problem_id = "problem";
 
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     conn.insert_contests(&[Contest {
         id: contest_id.to_string(),
         start_epoch_second: 1468670400,
@@ -86,18 +88,36 @@ fn test_update_problem_points() {
 
     conn.update_submissions(&[Submission {
         id: 0,
-        point: 0.0,
+        point: Point::from(0.0),
         problem_id: problem_id.to_string(),
         contest_id: contest_id.to_string(),
         ..Default::default()
     }])
     .unwrap();
     conn.update_problem_points().unwrap();
-    assert_eq!(get_points(&conn), vec![("problem".to_string(), Some(0.0))]);
+    assert_eq!(
+        get_points(&conn),
+        vec![("problem".to_string(), Some(Point::from(0.0)))]
+    );
 
     conn.update_submissions(&[Submission {
         id: 1,
-        point: 100.0,
+        point: Point::from(100.0),
+        problem_id: problem_id.to_string(),
+        contest_id: contest_id.to_string(),
+        ..Default::default()
+    }])
+    .unwrap();
+    conn.update_problem_points().unwrap();
+    assert_eq!(
+        get_points(&conn),
+        vec![("problem".to_string(), Some(Point::from(100.0)))]
+    );
+
+    // The partial scores of the crawled submissions are kept in the hundredths.
+    conn.update_submissions(&[Submission {
+        id: 2,
+        point: Point::from(150.750_000_01),
         problem_id: problem_id.to_string(),
         contest_id: contest_id.to_string(),
         ..Default::default()
@@ -106,6 +126,105 @@ fn test_update_problem_points() {
     conn.update_problem_points().unwrap();
     assert_eq!(
         get_points(&conn),
-        vec![("problem".to_string(), Some(100.0))]
+        vec![("problem".to_string(), Some(Point::from(150.75)))]
+    );
+}
+
+#[test]
+fn test_update_solve_time() {
+    use atcoder_problems_backend::sql::schema::solve_time;
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.insert_contests(&[Contest {
+        id: "contest".to_string(),
+        start_epoch_second: 0,
+        duration_second: 100,
+        ..Default::default()
+    }])
+    .unwrap();
+    let submissions = vec![
+        // The submission in the contest is excluded.
+        (0, 50, "user1", "AC"),
+        (1, 100, "user2", "WA"),
+        (2, 160, "user2", "AC"),
+        (3, 200, "user3", "WA"),
+        (4, 300, "user3", "WA"),
+        (5, 400, "user3", "AC"),
+        (6, 500, "user3", "AC"),
+        (7, 200, "user4", "AC"),
+        // The user who has not solved it is excluded.
+        (8, 200, "user5", "WA"),
+    ]
+    .into_iter()
+    .map(|(id, epoch_second, user_id, result)| Submission {
+        id,
+        epoch_second,
+        user_id: user_id.to_string(),
+        result: result.to_string(),
+        problem_id: "problem".to_string(),
+        contest_id: "contest".to_string(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_submissions(&submissions).unwrap();
+    conn.update_solve_time().unwrap();
+
+    let solve_time = solve_time::table
+        .select((
+            solve_time::problem_id,
+            solve_time::median_second,
+            solve_time::user_count,
+        ))
+        .load::<(String, f64, i32)>(&conn)
+        .unwrap();
+    assert_eq!(solve_time, vec![("problem".to_string(), 60.0, 3)]);
+}
+
+#[test]
+fn test_update_length_distribution() {
+    use atcoder_problems_backend::sql::models::LengthDistribution;
+    use atcoder_problems_backend::sql::schema::length_distributions;
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let submissions = vec![
+        // Only the shortest AC of each user is counted.
+        (0, "user1", 100, "AC"),
+        (1, "user1", 80, "AC"),
+        (2, "user2", 120, "AC"),
+        (3, "user3", 200, "AC"),
+        (4, "user4", 10, "WA"),
+        (5, "user5", 180, "AC"),
+    ]
+    .into_iter()
+    .map(|(id, user_id, length, result)| Submission {
+        id,
+        user_id: user_id.to_string(),
+        length,
+        result: result.to_string(),
+        problem_id: "problem".to_string(),
+        ..Default::default()
+    })
+    .collect::<Vec<_>>();
+    conn.update_submissions(&submissions).unwrap();
+    conn.update_length_distribution().unwrap();
+
+    let distributions = length_distributions::table
+        .load::<LengthDistribution>(&conn)
+        .unwrap();
+    assert_eq!(
+        distributions,
+        vec![LengthDistribution {
+            problem_id: "problem".to_string(),
+            user_count: 4,
+            min_length: 80,
+            first_quartile: 110.0,
+            median: 150.0,
+            third_quartile: 185.0,
+            max_length: 200,
+            bucket_width: 13,
+            bucket_counts: vec![1, 0, 0, 1, 0, 0, 0, 1, 0, 1],
+        }]
     );
 }
@@ -0,0 +1,93 @@
+use atcoder_problems_backend::sql::models::{ProblemModel, ProblemModelDiagnostics};
+use atcoder_problems_backend::sql::ProblemModelClient;
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+fn model(problem_id: &str, difficulty: Option<f64>) -> ProblemModel {
+    ProblemModel {
+        problem_id: problem_id.to_owned(),
+        difficulty,
+        is_experimental: false,
+    }
+}
+
+#[test]
+fn test_update_problem_models() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.update_problem_models(&[model("problem_1", Some(100.0)), model("problem_2", None)])
+        .unwrap();
+    conn.update_problem_models(&[model("problem_1", Some(200.0))])
+        .unwrap();
+
+    let models = conn.load_problem_models().unwrap();
+    assert_eq!(models.len(), 2);
+    assert!(models.contains(&model("problem_1", Some(200.0))));
+    assert!(models.contains(&model("problem_2", None)));
+}
+
+#[test]
+fn test_update_problem_model_diagnostics() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let diagnostics = |problem_id: &str, sample_size| ProblemModelDiagnostics {
+        problem_id: problem_id.to_owned(),
+        sample_size,
+        log_likelihood: Some(-10.0),
+        variance: None,
+    };
+    conn.update_problem_model_diagnostics(&[
+        diagnostics("problem_1", 10),
+        diagnostics("problem_2", 20),
+    ])
+    .unwrap();
+    conn.update_problem_model_diagnostics(&[diagnostics("problem_1", 30)])
+        .unwrap();
+
+    assert_eq!(
+        conn.load_problem_model_diagnostics().unwrap(),
+        vec![diagnostics("problem_1", 30), diagnostics("problem_2", 20)]
+    );
+}
+
+#[test]
+fn test_select_unsolved_problems() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.update_problem_models(&[
+        model("problem_1", Some(100.0)),
+        model("problem_2", Some(200.0)),
+        model("problem_3", Some(300.0)),
+        model("problem_4", Some(400.0)),
+        model("problem_5", Some(500.0)),
+        model("problem_6", Some(600.0)),
+        model("problem_7", None),
+    ])
+    .unwrap();
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+        (1, 0, 'problem_2', '', 'user_1', '', 0, 0, 'AC'),
+        (2, 0, 'problem_3', '', 'user_2', '', 0, 0, 'WA'),
+        (3, 0, 'problem_4', '', 'user_2', '', 0, 0, 'AC'),
+        (4, 0, 'problem_5', '', 'user_3', '', 0, 0, 'AC');
+    ",
+    )
+    .unwrap();
+
+    let selected = conn
+        .select_unsolved_problems(&["user_1", "user_2"], 150.0, 1000.0, 10)
+        .unwrap();
+    assert_eq!(selected, vec!["problem_3", "problem_5", "problem_6"]);
+
+    let selected = conn
+        .select_unsolved_problems(&["user_1", "user_2"], 0.0, 1000.0, 2)
+        .unwrap();
+    assert_eq!(selected, vec!["problem_1", "problem_6"]);
+
+    let selected = conn
+        .select_unsolved_problems(&["user_1", "user_2", "user_3"], 0.0, 1000.0, 1)
+        .unwrap();
+    assert_eq!(selected, vec!["problem_3"]);
+}
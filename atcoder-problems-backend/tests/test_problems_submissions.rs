@@ -1,5 +1,7 @@
 use atcoder_problems_backend::sql::models::Submission;
-use atcoder_problems_backend::sql::schema::{fastest, first, shortest, submissions};
+use atcoder_problems_backend::sql::schema::{
+    fastest, first, first_practice, shortest, submissions,
+};
 use atcoder_problems_backend::sql::ProblemsSubmissionUpdater;
 use diesel::dsl::*;
 use diesel::prelude::*;
@@ -13,6 +15,17 @@ fn get_first(conn: &PgConnection) -> Vec<(String, String, i64)> {
         .unwrap()
 }
 
+fn get_first_practice(conn: &PgConnection) -> Vec<(String, String, i64)> {
+    first_practice::table
+        .select((
+            first_practice::contest_id,
+            first_practice::problem_id,
+            first_practice::submission_id,
+        ))
+        .load::<(String, String, i64)>(conn)
+        .unwrap()
+}
+
 fn get_shortest(conn: &PgConnection) -> Vec<(String, String, i64)> {
     shortest::table
         .select((
@@ -46,8 +59,9 @@ fn insert_submissions(conn: &PgConnection, submissions: &[Submission]) {
 fn test_problem_info_aggregator() {
     use diesel::connection::SimpleConnection;
 
-    fn setup_contests() -> PgConnection {
-        let conn = utils::initialize_and_connect_to_test_sql();
+    fn setup_contests() -> (utils::TestDatabase, PgConnection) {
+        let database = utils::TestDatabase::new();
+        let conn = database.connect();
         conn.batch_execute(
             r#"
                 INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
@@ -55,7 +69,7 @@ fn test_problem_info_aggregator() {
             "#,
         )
         .unwrap();
-        conn
+        (database, conn)
     }
     let ignored_submission = vec![Submission {
         id: 0,
@@ -89,7 +103,7 @@ fn test_problem_info_aggregator() {
     }];
 
     {
-        let conn = setup_contests();
+        let (_database, conn) = setup_contests();
 
         insert_submissions(&conn, &ignored_submission);
         conn.update_submissions_of_problems().unwrap();
@@ -113,7 +127,7 @@ fn test_problem_info_aggregator() {
         assert_eq!(first[0].2, submissions1[0].id);
     }
     {
-        let conn = setup_contests();
+        let (_database, conn) = setup_contests();
 
         insert_submissions(&conn, &submissions2);
         conn.update_submissions_of_problems().unwrap();
@@ -133,7 +147,7 @@ fn test_problem_info_aggregator() {
     }
 
     {
-        let conn = setup_contests();
+        let (_database, conn) = setup_contests();
         insert_submissions(&conn, &submissions1);
         conn.update_submissions_of_problems().unwrap();
         let shortest = get_shortest(&conn);
@@ -152,7 +166,7 @@ fn test_problem_info_aggregator() {
     }
 
     {
-        let conn = setup_contests();
+        let (_database, conn) = setup_contests();
 
         insert_submissions(&conn, &submissions2);
         conn.update_submissions_of_problems().unwrap();
@@ -171,3 +185,106 @@ fn test_problem_info_aggregator() {
         assert_eq!(fastest[0].2, submissions1[0].id);
     }
 }
+
+#[test]
+fn test_first_practice() {
+    use diesel::connection::SimpleConnection;
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+            INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('contest1', 100, 100, '', '');
+        "#,
+    )
+    .unwrap();
+    let submission = |id: i64, epoch_second: i64| Submission {
+        id,
+        problem_id: "problem1".to_owned(),
+        contest_id: "contest1".to_owned(),
+        epoch_second,
+        result: "AC".to_owned(),
+        ..Default::default()
+    };
+
+    insert_submissions(&conn, &[submission(1, 150)]);
+    conn.update_submissions_of_problems().unwrap();
+    assert_eq!(get_first(&conn)[0].2, 1);
+    assert!(
+        get_first_practice(&conn).is_empty(),
+        "The submissions in the contest are not in practice."
+    );
+
+    insert_submissions(&conn, &[submission(2, 250), submission(3, 300)]);
+    conn.update_submissions_of_problems().unwrap();
+    assert_eq!(get_first(&conn)[0].2, 1);
+    assert_eq!(
+        get_first_practice(&conn),
+        vec![("contest1".to_owned(), "problem1".to_owned(), 2)]
+    );
+}
+
+#[test]
+fn test_shortest_history() {
+    use atcoder_problems_backend::jobs::{run_update_step, UpdateStep};
+    use atcoder_problems_backend::sql::models::ShortestRecord;
+    use atcoder_problems_backend::sql::ProblemDetailClient;
+    use diesel::connection::SimpleConnection;
+
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+            INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('contest1', 1, 0, '', '');
+        "#,
+    )
+    .unwrap();
+    let submission = |id: i64, user_id: &str, length: i32| Submission {
+        id,
+        problem_id: "problem1".to_owned(),
+        contest_id: "contest1".to_owned(),
+        user_id: user_id.to_owned(),
+        epoch_second: id * 10,
+        length,
+        result: "AC".to_owned(),
+        ..Default::default()
+    };
+
+    insert_submissions(&conn, &[submission(1, "user1", 100)]);
+    conn.update_submissions_of_problems().unwrap();
+    insert_submissions(&conn, &[submission(2, "user2", 100)]);
+    conn.update_submissions_of_problems().unwrap();
+    insert_submissions(&conn, &[submission(3, "user2", 50)]);
+    // The rebuild in the shadow tables records the change too.
+    run_update_step(&conn, UpdateStep::SubmissionsOfProblems).unwrap();
+    run_update_step(&conn, UpdateStep::SubmissionsOfProblems).unwrap();
+
+    assert_eq!(
+        conn.load_shortest_history("problem1").unwrap(),
+        vec![
+            ShortestRecord {
+                problem_id: "problem1".to_owned(),
+                epoch_second: 10,
+                previous_submission_id: None,
+                previous_user_id: None,
+                previous_length: None,
+                submission_id: 1,
+                user_id: "user1".to_owned(),
+                length: 100,
+            },
+            ShortestRecord {
+                problem_id: "problem1".to_owned(),
+                epoch_second: 30,
+                previous_submission_id: Some(1),
+                previous_user_id: Some("user1".to_owned()),
+                previous_length: Some(100),
+                submission_id: 3,
+                user_id: "user2".to_owned(),
+                length: 50,
+            },
+        ]
+    );
+    assert!(conn.load_shortest_history("problem2").unwrap().is_empty());
+}
@@ -0,0 +1,51 @@
+use atcoder_problems_backend::jobs::purge_deleted;
+use diesel::connection::SimpleConnection;
+use diesel::{sql_query, RunQueryDsl};
+
+pub mod utils;
+
+const DAY: i64 = 24 * 3600;
+
+#[test]
+fn test_purge_deleted() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let now = 100 * DAY;
+    conn.batch_execute(&format!(
+        r"
+        INSERT INTO internal_users (internal_user_id) VALUES ('owner');
+        INSERT INTO internal_problem_lists (internal_list_id, internal_user_id, deleted_epoch_second)
+        VALUES
+            ('alive', 'owner', NULL),
+            ('recent', 'owner', {recent}),
+            ('expired', 'owner', {expired});
+        INSERT INTO internal_problem_list_items (internal_list_id, problem_id) VALUES
+            ('expired', 'problem_1');
+        INSERT INTO internal_virtual_contests
+            (id, internal_user_id, start_epoch_second, duration_second, deleted_epoch_second)
+        VALUES
+            ('alive', 'owner', 0, 100, NULL),
+            ('recent', 'owner', 0, 100, {recent}),
+            ('expired', 'owner', 0, 100, {expired});
+        ",
+        recent = now - DAY,
+        expired = now - 31 * DAY,
+    ))
+    .unwrap();
+
+    assert_eq!(purge_deleted(&conn, now).unwrap(), 2);
+    assert_eq!(purge_deleted(&conn, now).unwrap(), 0);
+
+    let count = |query: &str| sql_query(query).execute(&conn).unwrap();
+    assert_eq!(count("SELECT * FROM internal_problem_lists"), 2);
+    assert_eq!(
+        count("SELECT * FROM internal_problem_lists WHERE internal_list_id = 'expired'"),
+        0
+    );
+    assert_eq!(count("SELECT * FROM internal_problem_list_items"), 0);
+    assert_eq!(count("SELECT * FROM internal_virtual_contests"), 2);
+    assert_eq!(
+        count("SELECT * FROM internal_virtual_contests WHERE id = 'expired'"),
+        0
+    );
+}
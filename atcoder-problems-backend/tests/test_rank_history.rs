@@ -0,0 +1,57 @@
+use atcoder_problems_backend::sql::models::RankHistory;
+use atcoder_problems_backend::sql::RankHistoryClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_rank_history() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO accepted_count (user_id, problem_count) VALUES
+            ('user1', 10), ('user2', 20), ('user3', 10);
+        INSERT INTO rated_point_sum (user_id, point_sum) VALUES
+            ('user1', 1000.0), ('user4', 500.0);
+        ",
+    )
+    .unwrap();
+
+    // 2020-01-01 08:59:59 JST
+    conn.record_rank_history(1_577_836_799).unwrap();
+    conn.batch_execute("UPDATE accepted_count SET problem_count = 30 WHERE user_id = 'user1'")
+        .unwrap();
+    // 2020-01-01 23:59:59 JST, which overwrites the snapshot of the day.
+    conn.record_rank_history(1_577_890_799).unwrap();
+    conn.batch_execute("UPDATE accepted_count SET problem_count = 5 WHERE user_id = 'user1'")
+        .unwrap();
+    // 2020-01-02 00:00:00 JST
+    conn.record_rank_history(1_577_890_800).unwrap();
+
+    assert_eq!(
+        conn.load_rank_history("user1").unwrap(),
+        vec![
+            RankHistory {
+                user_id: "user1".to_owned(),
+                epoch_second: 1_577_804_400,
+                accepted_count_rank: Some(0),
+                rated_point_sum_rank: Some(0),
+            },
+            RankHistory {
+                user_id: "user1".to_owned(),
+                epoch_second: 1_577_890_800,
+                accepted_count_rank: Some(2),
+                rated_point_sum_rank: Some(0),
+            },
+        ]
+    );
+
+    let history = conn.load_rank_history("user3").unwrap();
+    assert_eq!(history[0].accepted_count_rank, Some(2));
+    assert_eq!(history[0].rated_point_sum_rank, None);
+    let history = conn.load_rank_history("user4").unwrap();
+    assert_eq!(history[0].accepted_count_rank, None);
+    assert_eq!(history[0].rated_point_sum_rank, Some(1));
+    assert!(conn.load_rank_history("user5").unwrap().is_empty());
+}
@@ -1,8 +1,12 @@
-use atcoder_problems_backend::sql::models::{Contest, ContestProblem, Submission, UserSum};
+use atcoder_problems_backend::sql::models::{
+    Contest, ContestProblem, Point, RankedUserSum, Submission, UserSum,
+};
 use atcoder_problems_backend::sql::schema::{contest_problem, contests, rated_point_sum};
 use atcoder_problems_backend::sql::RatedPointSumClient;
+use diesel::connection::SimpleConnection;
 use diesel::dsl::*;
 use diesel::prelude::*;
+use diesel::sql_types::Text;
 
 mod utils;
 
@@ -11,7 +15,8 @@ const UNRATED_STATE: &str = "-";
 
 #[test]
 fn test_update_rated_point_sum() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
 
     let user_id = "user";
     let rated_contest = "rated_contest";
@@ -66,7 +71,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 0,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem1".to_string(),
             contest_id: rated_contest.to_string(),
             ..Default::default()
@@ -74,7 +79,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 1,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem1".to_string(),
             contest_id: rated_contest.to_string(),
             ..Default::default()
@@ -82,7 +87,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 2,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem2".to_string(),
             contest_id: unrated_contest1.to_string(),
             ..Default::default()
@@ -90,7 +95,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 3,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem3".to_string(),
             contest_id: unrated_contest2.to_string(),
             ..Default::default()
@@ -98,7 +103,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 4,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem4".to_string(),
             contest_id: rated_contest.to_string(),
             ..Default::default()
@@ -106,7 +111,7 @@ fn test_update_rated_point_sum() {
         Submission {
             id: 5,
             user_id: user_id.to_string(),
-            point: 100.0,
+            point: Point::from(100.0),
             problem_id: "problem5".to_string(),
             contest_id: same_contest_unrated.to_string(),
             ..Default::default()
@@ -147,11 +152,178 @@ fn test_update_rated_point_sum() {
     let sums = rated_point_sum::table.load::<UserSum>(&conn).unwrap();
     assert_eq!(sums.len(), 1);
     assert_eq!(sums[0].user_id, user_id.to_string());
-    assert_eq!(sums[0].point_sum, 300.0);
-    assert_eq!(conn.get_users_rated_point_sum(user_id).unwrap(), 300.0);
-    assert_eq!(conn.get_rated_point_sum_rank(300.0).unwrap(), 0);
+    assert_eq!(sums[0].point_sum, Point::from(300.0));
+    assert_eq!(
+        conn.get_users_rated_point_sum(user_id).unwrap(),
+        Point::from(300.0)
+    );
+    assert_eq!(
+        conn.get_rated_point_sum_rank(Point::from(300.0)).unwrap(),
+        0
+    );
 
     assert!(conn
         .get_users_rated_point_sum("non_existing_user")
         .is_none());
 }
+
+#[test]
+fn test_update_rated_point_sum_with_partial_scores() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    insert_into(contests::table)
+        .values(vec![Contest {
+            id: "contest".to_string(),
+            start_epoch_second: FIRST_AGC_EPOCH_SECOND,
+            duration_second: 1000,
+            title: "Partial Score Contest".to_string(),
+            rate_change: "All".to_string(),
+        }])
+        .execute(&conn)
+        .unwrap();
+    insert_into(contest_problem::table)
+        .values(
+            (1..=3)
+                .map(|i| ContestProblem {
+                    problem_id: format!("problem{}", i),
+                    contest_id: "contest".to_string(),
+                })
+                .collect::<Vec<_>>(),
+        )
+        .execute(&conn)
+        .unwrap();
+
+    let submission = |id: i64, user_id: &str, problem_id: &str, point: f64| Submission {
+        id,
+        user_id: user_id.to_string(),
+        problem_id: problem_id.to_string(),
+        contest_id: "contest".to_string(),
+        point: Point::from(point),
+        ..Default::default()
+    };
+    let submissions = vec![
+        submission(0, "user1", "problem1", 0.1),
+        submission(1, "user1", "problem2", 0.2),
+        // The best point of the problem is counted.
+        submission(2, "user1", "problem3", 100.5),
+        submission(3, "user1", "problem3", 50.25),
+        submission(4, "user2", "problem1", 100.5),
+        submission(5, "user2", "problem2", 0.3),
+    ];
+    conn.update_rated_point_sum(&submissions).unwrap();
+
+    assert_eq!(
+        conn.get_users_rated_point_sum("user1").unwrap(),
+        Point::from(100.8)
+    );
+    assert_eq!(
+        conn.get_users_rated_point_sum("user2").unwrap(),
+        Point::from(100.8)
+    );
+    let ranking = conn.load_rated_point_sum_in_range(0, 10, None).unwrap();
+    assert!(
+        ranking.iter().all(|user| user.rank == 0),
+        "The same sums of the fractional points are tied."
+    );
+    let stored = rated_point_sum::table
+        .select(sql::<Text>("point_sum::TEXT"))
+        .order_by(rated_point_sum::user_id)
+        .load::<String>(&conn)
+        .unwrap();
+    assert_eq!(stored, vec!["100.80", "100.80"]);
+}
+
+#[test]
+fn test_rated_point_sum_ranking() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    insert_into(rated_point_sum::table)
+        .values(vec![
+            (
+                rated_point_sum::user_id.eq("user1"),
+                rated_point_sum::point_sum.eq(Point::from(300.0)),
+            ),
+            (
+                rated_point_sum::user_id.eq("user2"),
+                rated_point_sum::point_sum.eq(Point::from(200.0)),
+            ),
+            (
+                rated_point_sum::user_id.eq("user3"),
+                rated_point_sum::point_sum.eq(Point::from(300.0)),
+            ),
+            (
+                rated_point_sum::user_id.eq("user4"),
+                rated_point_sum::point_sum.eq(Point::from(100.0)),
+            ),
+        ])
+        .execute(&conn)
+        .unwrap();
+
+    let ranking = conn.load_rated_point_sum_in_range(0, 10, None).unwrap();
+    assert_eq!(
+        ranking
+            .iter()
+            .map(|user| (user.user_id.as_str(), user.rank))
+            .collect::<Vec<_>>(),
+        vec![("user1", 0), ("user3", 0), ("user2", 1), ("user4", 2)]
+    );
+
+    let ranking = conn.load_rated_point_sum_in_range(1, 3, None).unwrap();
+    assert_eq!(
+        ranking,
+        vec![
+            RankedUserSum {
+                user_id: "user3".to_owned(),
+                point_sum: Point::from(300.0),
+                rank: 0,
+            },
+            RankedUserSum {
+                user_id: "user2".to_owned(),
+                point_sum: Point::from(200.0),
+                rank: 1,
+            },
+        ]
+    );
+    assert!(conn
+        .load_rated_point_sum_in_range(4, 10, None)
+        .unwrap()
+        .is_empty());
+
+    conn.batch_execute(
+        r"
+        INSERT INTO active_users (user_id, last_submission_epoch_second) VALUES
+            ('user1', 100), ('user2', 200), ('user4', 300);
+        ",
+    )
+    .unwrap();
+    let ranking = conn
+        .load_rated_point_sum_in_range(0, 10, Some(200))
+        .unwrap();
+    assert_eq!(
+        ranking
+            .iter()
+            .map(|user| (user.user_id.as_str(), user.rank))
+            .collect::<Vec<_>>(),
+        vec![("user2", 0), ("user4", 1)]
+    );
+
+    // The banned users are not ranked, and do not push down the others.
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_banned_users (user_id, banned_epoch_second) VALUES ('user1', 0);
+        ",
+    )
+    .unwrap();
+    let ranking = conn.load_rated_point_sum_in_range(0, 10, None).unwrap();
+    assert_eq!(
+        ranking
+            .iter()
+            .map(|user| (user.user_id.as_str(), user.rank))
+            .collect::<Vec<_>>(),
+        vec![("user3", 0), ("user2", 1), ("user4", 2)]
+    );
+    assert_eq!(
+        conn.get_rated_point_sum_rank(Point::from(200.0)).unwrap(),
+        1
+    );
+}
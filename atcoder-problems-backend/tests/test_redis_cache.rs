@@ -0,0 +1,126 @@
+use async_std::io::{prelude::*, BufReader};
+use async_std::net::{TcpListener, TcpStream};
+use async_std::task;
+use atcoder_problems_backend::cache::{Cache, RedisCache};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Store = Arc<Mutex<BTreeMap<Vec<u8>, Vec<u8>>>>;
+
+async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<Vec<u8>>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count = line.trim_end().strip_prefix('*')?.parse::<usize>().ok()?;
+    let mut args = vec![];
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let length = line.trim_end().strip_prefix('$')?.parse::<usize>().ok()?;
+        let mut arg = vec![0; length + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(length);
+        args.push(arg);
+    }
+    Some(args)
+}
+
+fn bulk(value: &[u8]) -> Vec<u8> {
+    let mut reply = format!("${}\r\n", value.len()).into_bytes();
+    reply.extend_from_slice(value);
+    reply.extend_from_slice(b"\r\n");
+    reply
+}
+
+/// Serves GET, SET, SCAN and DEL with the subset of the Redis protocol used by `RedisCache`.
+/// SCAN returns all the keys on the first page and none on the second, to exercise the cursor.
+async fn serve(stream: TcpStream, store: Store, commands: Arc<Mutex<Vec<String>>>) {
+    let mut reader = BufReader::new(stream);
+    while let Some(args) = read_command(&mut reader).await {
+        let name = String::from_utf8(args[0].clone()).unwrap();
+        commands.lock().unwrap().push(name.clone());
+        let reply = match name.as_str() {
+            "SELECT" => b"+OK\r\n".to_vec(),
+            "GET" => match store.lock().unwrap().get(&args[1]) {
+                Some(value) => bulk(value),
+                None => b"$-1\r\n".to_vec(),
+            },
+            "SET" => {
+                assert_eq!(args[3], b"PX");
+                store
+                    .lock()
+                    .unwrap()
+                    .insert(args[1].clone(), args[2].clone());
+                b"+OK\r\n".to_vec()
+            }
+            "SCAN" => {
+                let prefix = args[3].strip_suffix(b"*").unwrap().to_vec();
+                let keys = match args[1].as_slice() {
+                    b"0" => store
+                        .lock()
+                        .unwrap()
+                        .keys()
+                        .filter(|key| key.starts_with(&prefix))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    _ => vec![],
+                };
+                let next: &[u8] = if keys.is_empty() { b"0" } else { b"1" };
+                let mut reply = b"*2\r\n".to_vec();
+                reply.extend(bulk(next));
+                reply.extend(format!("*{}\r\n", keys.len()).into_bytes());
+                for key in keys {
+                    reply.extend(bulk(&key));
+                }
+                reply
+            }
+            "DEL" => {
+                let mut store = store.lock().unwrap();
+                let count = args[1..]
+                    .iter()
+                    .filter(|key| store.remove(*key).is_some())
+                    .count();
+                format!(":{}\r\n", count).into_bytes()
+            }
+            _ => b"-ERR unknown command\r\n".to_vec(),
+        };
+        reader.get_mut().write_all(&reply).await.unwrap();
+    }
+}
+
+#[async_std::test]
+async fn test_redis_cache() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let store: Store = Arc::new(Mutex::new(BTreeMap::new()));
+    let commands = Arc::new(Mutex::new(vec![]));
+    {
+        let store = store.clone();
+        let commands = commands.clone();
+        task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            serve(stream, store, commands).await;
+        });
+    }
+
+    let cache = RedisCache::open(&format!("redis://127.0.0.1:{}/1", port)).unwrap();
+    let ttl = Duration::from_secs(60);
+    assert_eq!(cache.get("user_info:a").await.unwrap(), None);
+    cache.set("user_info:a", "{\"a\":1}", ttl).await.unwrap();
+    cache.set("user_info:b", "2", ttl).await.unwrap();
+    cache.set("other", "3", ttl).await.unwrap();
+    assert_eq!(
+        cache.get("user_info:a").await.unwrap(),
+        Some("{\"a\":1}".to_owned())
+    );
+
+    cache.invalidate("user_info:").await.unwrap();
+    assert_eq!(cache.get("user_info:a").await.unwrap(), None);
+    assert_eq!(cache.get("user_info:b").await.unwrap(), None);
+    assert_eq!(cache.get("other").await.unwrap(), Some("3".to_owned()));
+    assert_eq!(store.lock().unwrap().len(), 1);
+
+    let commands = commands.lock().unwrap();
+    assert_eq!(commands[0], "SELECT", "The database is selected once.");
+    assert_eq!(commands.iter().filter(|c| *c == "SELECT").count(), 1);
+}
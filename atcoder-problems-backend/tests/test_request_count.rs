@@ -0,0 +1,38 @@
+use atcoder_problems_backend::sql::models::RequestCount;
+use atcoder_problems_backend::sql::RequestCountClient;
+
+mod utils;
+
+fn count(hour_epoch_second: i64, route: &str, client: &str, request_count: i64) -> RequestCount {
+    RequestCount {
+        hour_epoch_second,
+        route: route.to_owned(),
+        client: client.to_owned(),
+        request_count,
+    }
+}
+
+#[test]
+fn test_request_counts() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.add_request_counts(&[
+        count(0, "/atcoder-api/v3/user/ac_rank", "tool", 3),
+        count(3600, "/atcoder-api/v3/user/ac_rank", "tool", 1),
+        count(3600, "/atcoder-api/v3/user/ac_rank", "unknown", 2),
+    ])
+    .unwrap();
+    conn.add_request_counts(&[count(3600, "/atcoder-api/v3/user/ac_rank", "tool", 4)])
+        .unwrap();
+
+    assert_eq!(
+        conn.load_request_counts(0).unwrap(),
+        vec![
+            count(3600, "/atcoder-api/v3/user/ac_rank", "tool", 5),
+            count(3600, "/atcoder-api/v3/user/ac_rank", "unknown", 2),
+            count(0, "/atcoder-api/v3/user/ac_rank", "tool", 3),
+        ]
+    );
+    assert_eq!(conn.load_request_counts(3600).unwrap().len(), 2);
+    assert!(conn.load_request_counts(7200).unwrap().is_empty());
+}
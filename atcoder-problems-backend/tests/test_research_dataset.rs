@@ -0,0 +1,68 @@
+use atcoder_problems_backend::research_dataset::{
+    ExportOptions, Pseudonymizer, ResearchDatasetExporter,
+};
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, PgConnection};
+use serde_json::Value;
+
+mod utils;
+
+#[test]
+fn test_research_dataset() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (3, 300, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 10, 'WA'),
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'problem1', 'contest1', 'user2', 'C++', 100.0, 30, 'AC');
+    "#,
+    )
+    .unwrap();
+
+    let export = |options| {
+        let exporter = ResearchDatasetExporter::new(
+            PgConnection::establish(database.url()).unwrap(),
+            Pseudonymizer::new("salt".to_owned()),
+            options,
+        );
+        let data = String::from_utf8(exporter.export().unwrap()).unwrap();
+        data.lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let rows = export(ExportOptions::default());
+    assert_eq!(rows.len(), 3);
+    assert_eq!(
+        rows.iter()
+            .map(|row| row["epoch_second"].as_i64().unwrap())
+            .collect::<Vec<_>>(),
+        vec![100, 200, 300]
+    );
+    assert_eq!(rows[0]["user"], rows[2]["user"]);
+    assert_ne!(rows[0]["user"], rows[1]["user"]);
+    assert!(!data_contains_user_id(&rows));
+    assert!(rows[0].get("id").is_none());
+    assert_eq!(rows[1]["language"], "C++");
+    assert_eq!(rows[1]["length"], 30);
+
+    let rows = export(ExportOptions {
+        include_length: false,
+        include_language: false,
+    });
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|row| row.get("language").is_none()));
+    assert!(rows.iter().all(|row| row.get("length").is_none()));
+    assert_eq!(rows[1]["result"], "AC");
+}
+
+fn data_contains_user_id(rows: &[Value]) -> bool {
+    rows.iter().any(|row| {
+        let row = row.to_string();
+        row.contains("user1") || row.contains("user2")
+    })
+}
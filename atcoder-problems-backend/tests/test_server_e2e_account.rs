@@ -0,0 +1,133 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_delete_account() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::{sql_query, PgConnection};
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut contest_ids = vec![];
+    for title in ["joined", "alone"].iter() {
+        let body = surf::post(url("/internal-api/contest/create", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({
+                "title": title,
+                "memo": "",
+                "start_epoch_second": 1,
+                "duration_second": 2,
+            }))?
+            .recv_json::<Value>()
+            .await?;
+        contest_ids.push(body["contest_id"].as_str().unwrap().to_owned());
+    }
+    let response = surf::post(url("/internal-api/list/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"list_name": "a"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/watch/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"user_id": "rival"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(&format!(
+        r"
+        INSERT INTO internal_users (internal_user_id) VALUES ('other');
+        INSERT INTO internal_virtual_contest_participants (internal_virtual_contest_id, internal_user_id) VALUES ('{}', 'other');
+        ",
+        contest_ids[0]
+    ))
+    .unwrap();
+
+    let response = surf::delete(url("/internal-api/account?transfer_contests=true", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let count = |query: &str| sql_query(query).execute(&conn).unwrap();
+    assert_eq!(
+        count("SELECT * FROM internal_users WHERE internal_user_id = '0'"),
+        0
+    );
+    assert_eq!(count("SELECT * FROM internal_problem_lists"), 0);
+    assert_eq!(count("SELECT * FROM internal_watched_users"), 0);
+    assert_eq!(
+        count(&format!(
+            "SELECT * FROM internal_virtual_contests WHERE id = '{}' AND internal_user_id = 'other'",
+            contest_ids[0]
+        )),
+        1
+    );
+    assert_eq!(
+        count(&format!(
+            "SELECT * FROM internal_virtual_contests WHERE id = '{}'",
+            contest_ids[1]
+        )),
+        0
+    );
+
+    let response = surf::delete(url("/internal-api/account", port)).await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,402 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_admin() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::{sql_query, PgConnection};
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::get(url("/internal-api/admin/ban/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 403);
+
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r"
+        UPDATE internal_users SET is_admin = TRUE WHERE internal_user_id = '0';
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change)
+        VALUES ('abc001', 0, 0, '', '');
+        ",
+    )
+    .unwrap();
+
+    let response = surf::get(url("/internal-api/admin/ban/list", port)).await?;
+    assert_eq!(response.status(), 403);
+
+    let body = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title": "spam",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+        }))?
+        .recv_json::<Value>()
+        .await?;
+    let contest_id = body["contest_id"].as_str().unwrap().to_owned();
+    let body = surf::post(url("/internal-api/list/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"list_name": "spam"}))?
+        .recv_json::<Value>()
+        .await?;
+    let list_id = body["internal_list_id"].as_str().unwrap().to_owned();
+
+    let response = surf::post(url("/internal-api/admin/contest/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/admin/list/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_list_id": list_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let count = |query: &str| sql_query(query).execute(&conn).unwrap();
+    assert_eq!(count("SELECT * FROM internal_virtual_contests"), 0);
+    assert_eq!(count("SELECT * FROM internal_problem_lists"), 0);
+
+    let response = surf::post(url("/internal-api/admin/contest/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    for user_id in ["spammer", "cheater"].iter() {
+        let response = surf::post(url("/internal-api/admin/ban/add", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({ "user_id": user_id }))?
+            .await?;
+        assert!(response.status().is_success(), "{:?}", response);
+    }
+    let response = surf::post(url("/internal-api/admin/ban/remove", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"user_id": "spammer"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let banned = surf::get(url("/internal-api/admin/ban/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(banned, json!(["cheater"]));
+
+    let response = surf::post(url("/internal-api/admin/recrawl", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"contest_id": "abc001"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/admin/recrawl", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"contest_id": "unknown"}))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+    assert_eq!(
+        count("SELECT * FROM internal_recrawl_requests WHERE contest_id = 'abc001'"),
+        1
+    );
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_audit_log() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::PgConnection;
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let body = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title": "audited",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+        }))?
+        .recv_json::<Value>()
+        .await?;
+    let contest_id = body["contest_id"].as_str().unwrap().to_owned();
+    let response = surf::post(url("/internal-api/contest/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "id": contest_id,
+            "title": "edited",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/list/create", port))
+        .body_json(&json!({"list_name": "anonymous"}))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let response = surf::get(url("/internal-api/admin/audit_log", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 403);
+
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute("UPDATE internal_users SET is_admin = TRUE WHERE internal_user_id = '0';")
+        .unwrap();
+
+    let logs = surf::get(url(
+        "/internal-api/admin/audit_log?keyword=contest/update",
+        port,
+    ))
+    .set_header("Cookie", cookie_header.as_str())
+    .recv_json::<Value>()
+    .await?;
+    let logs = logs.as_array().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["internal_user_id"], json!("0"));
+    assert_eq!(logs[0]["method"], json!("POST"));
+    assert_eq!(logs[0]["path"], json!("/internal-api/contest/update"));
+    assert_eq!(logs[0]["status"], json!(200));
+    assert_eq!(logs[0]["client_ip"], json!("127.0.0.1"));
+    let payload_summary = logs[0]["payload_summary"].as_str().unwrap();
+    assert!(payload_summary
+        .starts_with("fields: duration_second, id, memo, start_epoch_second, title ("));
+    assert!(!payload_summary.contains("edited"));
+
+    let logs = surf::get(url("/internal-api/admin/audit_log?user=0&count=1", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(logs.as_array().unwrap().len(), 1);
+    let logs = surf::get(url("/internal-api/admin/audit_log", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(logs.as_array().unwrap().len(), 2);
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_feature_flags() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::PgConnection;
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute("UPDATE internal_users SET is_admin = TRUE WHERE internal_user_id = '0';")
+        .unwrap();
+
+    let summary_url = url("/atcoder-api/v3/user/judge_summary?atcoder=user", port);
+    assert_eq!(surf::get(&summary_url).await?.status(), 200);
+    let flags = surf::get(url("/internal-api/admin/feature_flags/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(flags, json!([{"name": "judge_summary", "enabled": true}]));
+
+    let response = surf::post(url("/internal-api/admin/feature_flags/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"name": "judge_summary", "enabled": false}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    assert_eq!(surf::get(&summary_url).await?.status(), 404);
+    let flags = surf::get(url("/internal-api/admin/feature_flags/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(flags, json!([{"name": "judge_summary", "enabled": false}]));
+
+    let response = surf::post(url("/internal-api/admin/feature_flags/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"name": "unknown", "enabled": false}))?
+        .await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_excluded_contests() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::PgConnection;
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let list_url = url("/internal-api/admin/excluded_contests/list", port);
+    let response = surf::get(&list_url)
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 403);
+
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute("UPDATE internal_users SET is_admin = TRUE WHERE internal_user_id = '0';")
+        .unwrap();
+    let response = surf::post(url("/internal-api/admin/excluded_contests/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"contest_id": "april_fool", "reason": "April Fools"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let contests = surf::get(&list_url)
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(contests.as_array().unwrap().len(), 1);
+    assert_eq!(contests[0]["contest_id"], "april_fool");
+    assert_eq!(contests[0]["reason"], "April Fools");
+
+    let response = surf::post(url("/internal-api/admin/excluded_contests/remove", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"contest_id": "april_fool"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let contests = surf::get(&list_url)
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(contests, json!([]));
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_validation_violations() -> Result<()> {
+    use atcoder_problems_backend::jobs::validate;
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::PgConnection;
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r"
+        UPDATE internal_users SET is_admin = TRUE WHERE internal_user_id = '0';
+        INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 3);",
+    )
+    .unwrap();
+    validate(&conn, 100).unwrap();
+
+    let violations = surf::get(url("/internal-api/admin/validation_violations", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        violations,
+        json!([{
+            "check_name": "accepted_count",
+            "subject": "user1",
+            "message": "accepted_count is 3, but 0 distinct problems are accepted",
+            "detected_epoch_second": 100
+        }])
+    );
+
+    let response = surf::get(url("/internal-api/admin/validation_violations", port)).await?;
+    assert_eq!(response.status(), 403);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use atcoder_problems_backend::config::{Config, QueryConfig, ServerConfig};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server_with_config, Authentication};
+
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_daily_quota() -> Result<()> {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        let config = Config {
+            server: ServerConfig {
+                port,
+                ..ServerConfig::default()
+            },
+            query: QueryConfig {
+                anonymous_daily_quota: 2,
+                token_daily_quota: 3,
+                ..QueryConfig::default()
+            },
+            ..Config::default()
+        };
+        run_server_with_config(pool, MockAuth, &config)
+            .await
+            .unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let user_info = url("/atcoder-api/v2/user_info?user=u1", port);
+    let usage = url("/atcoder-api/v3/internal/usage", port);
+
+    let body = surf::get(&usage).recv_json::<Value>().await?;
+    assert_eq!(body["kind"], json!("anonymous"));
+    assert_eq!(body["limit"], json!(2));
+    assert_eq!(body["remaining"], json!(2));
+
+    for remaining in &["1", "0"] {
+        let response = surf::get(&user_info).await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.header("x-ratelimit-limit").unwrap(), "2");
+        assert_eq!(
+            response.header("x-ratelimit-remaining").unwrap(),
+            *remaining
+        );
+    }
+    let response = surf::get(&user_info).await?;
+    assert_eq!(response.status(), 429);
+    assert!(response.header("retry-after").is_some());
+
+    let body = surf::get(&usage).recv_json::<Value>().await?;
+    assert_eq!(body["used"], json!(3));
+    assert_eq!(body["remaining"], json!(0));
+
+    // The requests with an API token have the quota of their own.
+    let response = surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie = response
+        .header("set-cookie")
+        .unwrap()
+        .as_str()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_owned();
+    let response = surf::get(&user_info)
+        .set_header("Cookie", cookie.as_str())
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.header("x-ratelimit-limit").unwrap(), "3");
+    assert_eq!(response.header("x-ratelimit-remaining").unwrap(), "2");
+
+    let body = surf::get(&usage)
+        .set_header("Cookie", cookie.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(body["kind"], json!("token"));
+    assert_eq!(body["used"], json!(1));
+
+    let response = surf::get(&user_info)
+        .set_header("Cookie", "token=unknown-token")
+        .await?;
+    assert_eq!(
+        response.status(),
+        429,
+        "An unknown token is counted as anonymous."
+    );
+
+    server.cancel().await;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+async fn login(port: u16) -> Result<String> {
+    let response = surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 302);
+    let cookie = response.header("set-cookie").unwrap().as_str();
+    let token = cookie
+        .split(';')
+        .next()
+        .unwrap()
+        .trim_start_matches("token=")
+        .to_owned();
+    Ok(token)
+}
+
+#[async_std::test]
+async fn test_issued_token() -> Result<()> {
+    use diesel::prelude::*;
+    use diesel::{sql_query, PgConnection};
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let token = login(port).await?;
+    assert_ne!(token, VALID_TOKEN);
+    assert_ne!(token, login(port).await?);
+
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    let stored = sql_query("SELECT * FROM internal_api_tokens WHERE internal_user_id = '0'")
+        .execute(&conn)
+        .unwrap();
+    assert_eq!(stored, 2);
+    let raw = sql_query(format!(
+        "SELECT * FROM internal_api_tokens WHERE token_hash = '{}'",
+        token
+    ))
+    .execute(&conn)
+    .unwrap();
+    assert_eq!(raw, 0);
+
+    let user = surf::get(url("/internal-api/user/get", port))
+        .set_header("Cookie", format!("token={}", token))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(user["internal_user_id"], json!("0"));
+
+    let response = surf::get(url("/internal-api/user/get", port))
+        .set_header("Cookie", "token=unknown-token")
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_named_token_scopes() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    let login_cookie = format!("token={}", login(port).await?);
+
+    let response = surf::post(url("/internal-api/token/create", port))
+        .set_header("Cookie", login_cookie.as_str())
+        .body_json(&json!({"name": "bot", "scopes": ["admin"]}))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let created = surf::post(url("/internal-api/token/create", port))
+        .set_header("Cookie", login_cookie.as_str())
+        .body_json(&json!({"name": "bot", "scopes": ["list-write"]}))?
+        .recv_json::<Value>()
+        .await?;
+    let token_id = created["id"].as_str().unwrap().to_owned();
+    let named_cookie = format!("token={}", created["token"].as_str().unwrap());
+
+    let tokens = surf::get(url("/internal-api/token/list", port))
+        .set_header("Cookie", login_cookie.as_str())
+        .recv_json::<Value>()
+        .await?;
+    let tokens = tokens.as_array().unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0]["scopes"], json!(null));
+    assert_eq!(tokens[1]["id"], json!(token_id));
+    assert_eq!(tokens[1]["name"], json!("bot"));
+    assert_eq!(tokens[1]["scopes"], json!(["list-write"]));
+    assert!(tokens[1].get("token").is_none());
+    assert!(tokens[1].get("token_hash").is_none());
+
+    // named tokens can not manage tokens.
+    let response = surf::get(url("/internal-api/token/list", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .await?;
+    assert_eq!(response.status(), 403);
+
+    let response = surf::post(url("/internal-api/list/create", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .body_json(&json!({"list_name": "a"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .body_json(&json!({
+            "title": "contest",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+        }))?
+        .await?;
+    assert_eq!(response.status(), 403);
+    let response = surf::post(url("/internal-api/watch/add", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .body_json(&json!({"user_id": "rival"}))?
+        .await?;
+    assert_eq!(response.status(), 403);
+    let response = surf::get(url("/internal-api/contest/my", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/token/revoke", port))
+        .set_header("Cookie", login_cookie.as_str())
+        .body_json(&json!({ "id": token_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", named_cookie.as_str())
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
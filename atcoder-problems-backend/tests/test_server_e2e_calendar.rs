@@ -0,0 +1,131 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_calendar() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::prelude::*;
+    use diesel::PgConnection;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let port = setup();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(&format!(
+        r#"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change)
+        VALUES
+            ('abc100', 1529154000, 6000, 'AtCoder Beginner Contest 100', ' ~ 1199'),
+            ('abc999', {}, 6000, 'AtCoder Beginner Contest 999, Final', ' ~ 1999');
+        "#,
+        now + 86400
+    ))
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let mut response = surf::get(url("/feed/contests.ics", port)).await?;
+    assert_eq!(
+        response.header("content-type").unwrap().as_str(),
+        "text/calendar; charset=utf-8"
+    );
+    let body = response.body_string().await?;
+    assert!(body.contains("SUMMARY:AtCoder Beginner Contest 999\\, Final\r\n"));
+    assert!(!body.contains("abc100"));
+
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+    let body = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title": "virtual",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+        }))?
+        .recv_json::<Value>()
+        .await?;
+    let contest_id = body["contest_id"].as_str().unwrap().to_owned();
+    let response = surf::post(url("/internal-api/contest/join", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let body = surf::post(url("/internal-api/token/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"name": "calendar", "scopes": ["read-only"]}))?
+        .recv_json::<Value>()
+        .await?;
+    let token = body["token"].as_str().unwrap().to_owned();
+
+    let body = surf::get(url(&format!("/feed/calendar.ics?token={}", token), port))
+        .recv_string()
+        .await?;
+    assert_eq!(body.matches("BEGIN:VEVENT").count(), 2);
+    let virtual_contest = body
+        .find(&format!("UID:virtual-{}@kenkoooo.com", contest_id))
+        .unwrap();
+    let abc999 = body.find("UID:atcoder-abc999@kenkoooo.com").unwrap();
+    assert!(virtual_contest < abc999);
+    assert!(body.contains("DTSTART:19700101T000001Z\r\n"));
+
+    let response = surf::get(url("/feed/calendar.ics?token=unknown", port)).await?;
+    assert_eq!(response.status(), 403);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_classroom() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r"
+            INSERT INTO problems (id, contest_id, title) VALUES
+            ('problem_1', 'contest', ''), ('problem_2', 'contest', '');
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 500, 'problem_1', 'contest', 'member_1', '', 100, 0, 'AC'),
+            (2, 1500, 'problem_2', 'contest', 'member_1', '', 100, 0, 'AC'),
+            (3, 600, 'problem_1', 'contest', 'member_1', '', 100, 0, 'AC'),
+            (4, 500, 'problem_1', 'contest', 'member_2', '', 100, 0, 'WA');
+        ",
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/group/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "group_name": "classroom" }))?
+        .recv_json::<Value>()
+        .await?;
+    let group_id = response["internal_group_id"].as_str().unwrap().to_owned();
+    let response = surf::post(url("/internal-api/group/member/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_group_id": group_id,
+            "user_ids": ["member_1", "member_2"]
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/classroom/assignment/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_group_id": group_id,
+            "title": "week 1",
+            "deadline_epoch_second": 1000,
+            "problem_ids": ["problem_1", "unknown"]
+        }))?
+        .await?;
+    assert_eq!(response.status(), 400);
+    let response = surf::post(url("/internal-api/classroom/assignment/create", port))
+        .body_json(&json!({
+            "internal_group_id": group_id,
+            "title": "week 1",
+            "deadline_epoch_second": 1000,
+            "problem_ids": ["problem_1"]
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/classroom/assignment/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_group_id": group_id,
+            "title": "week 1",
+            "deadline_epoch_second": 1000,
+            "problem_ids": ["problem_1", "problem_2"]
+        }))?
+        .recv_json::<Value>()
+        .await?;
+    let assignment_id = response["internal_assignment_id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    let assignments = surf::get(url(
+        &format!(
+            "/internal-api/classroom/assignment/list?internal_group_id={}",
+            group_id
+        ),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(assignments.as_array().unwrap().len(), 1);
+    assert_eq!(assignments[0]["title"], "week 1");
+    assert_eq!(
+        assignments[0]["problem_ids"],
+        json!(["problem_1", "problem_2"])
+    );
+
+    let progress_url = url(
+        &format!(
+            "/internal-api/classroom/assignment/progress/{}",
+            assignment_id
+        ),
+        port,
+    );
+    let progress = surf::get(&progress_url).recv_json::<Value>().await?;
+    assert_eq!(progress["assignment"]["deadline_epoch_second"], 1000);
+    assert_eq!(
+        progress["members"],
+        json!([
+            {
+                "user_id": "member_1",
+                "on_time_count": 1,
+                "problems": [
+                    {"problem_id": "problem_1", "first_ac_epoch_second": 500, "status": "on_time"},
+                    {"problem_id": "problem_2", "first_ac_epoch_second": 1500, "status": "late"}
+                ]
+            },
+            {
+                "user_id": "member_2",
+                "on_time_count": 0,
+                "problems": [
+                    {"problem_id": "problem_1", "first_ac_epoch_second": null, "status": "unsolved"},
+                    {"problem_id": "problem_2", "first_ac_epoch_second": null, "status": "unsolved"}
+                ]
+            }
+        ])
+    );
+
+    let response = surf::post(url("/internal-api/classroom/assignment/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_assignment_id": assignment_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::get(&progress_url).await?;
+    assert_eq!(response.status(), 404);
+
+    server.race(ready(())).await;
+    Ok(())
+}
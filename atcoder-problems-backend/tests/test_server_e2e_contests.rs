@@ -0,0 +1,256 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use diesel::connection::SimpleConnection;
+use rand::Rng;
+use serde_json::Value;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc001', 1381579200, 7200, 'AtCoder Beginner Contest 001', '-'),
+            ('abc150', 1578150000, 6000, 'AtCoder Beginner Contest 150', ' ~ 1999'),
+            ('abc151', 1578747600, 6000, 'AtCoder Beginner Contest 151', ' ~ 1999'),
+            ('agc041', 1577544000, 10800, 'AtCoder Grand Contest 041', 'All'),
+            ('keyence2020', 1579352400, 7200, 'KEYENCE Programming Contest 2020', 'All'),
+            ('past201912-open', 1577577600, 18000, '第二回 アルゴリズム実技検定', '-'),
+            ('chokudai004', 1571544000, 28800, 'Chokudai Contest 004', '-');
+        ",
+    )
+    .unwrap();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_get_contests() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let get = |query: &str| {
+        let url = url(&format!("/atcoder-api/v3/contests?{}", query), port);
+        async move {
+            let mut response = surf::get(url).await?;
+            let total_count = response
+                .header("x-total-count")
+                .map(|values| values[0].as_str().to_owned());
+            let contests = response.body_json::<Vec<Value>>().await?;
+            let ids = contests
+                .iter()
+                .map(|contest| contest["id"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            Ok::<_, http_types::Error>((ids, total_count))
+        }
+    };
+
+    let (ids, total_count) = get("").await?;
+    assert_eq!(
+        ids,
+        vec![
+            "keyence2020",
+            "abc151",
+            "abc150",
+            "past201912-open",
+            "agc041",
+            "chokudai004",
+            "abc001"
+        ]
+    );
+    assert_eq!(total_count.as_deref(), Some("7"));
+
+    assert_eq!(
+        get("category=ABC").await?.0,
+        vec!["abc151", "abc150", "abc001"]
+    );
+    assert_eq!(
+        get("category=Other%20Rated%20Contests").await?.0,
+        vec!["keyence2020"]
+    );
+    assert_eq!(get("category=PAST").await?.0, vec!["past201912-open"]);
+    assert_eq!(get("category=Marathon").await?.0, vec!["chokudai004"]);
+    assert_eq!(
+        get("category=ABC&from=2020-01-05").await?.0,
+        vec!["abc151", "abc150"]
+    );
+    assert_eq!(get("category=ABC&from=2020-01-06").await?.0, vec!["abc151"]);
+    assert_eq!(
+        get("rated=true").await?.0,
+        vec!["keyence2020", "abc151", "abc150", "agc041"]
+    );
+    assert_eq!(get("rated=false&category=ABC").await?.0, vec!["abc001"]);
+    assert_eq!(
+        get("rated_for=1999").await?.0,
+        vec!["keyence2020", "abc151", "abc150", "agc041"]
+    );
+    assert_eq!(
+        get("rated_for=2000").await?.0,
+        vec!["keyence2020", "agc041"]
+    );
+
+    let (ids, total_count) = get("per_page=3&page=1").await?;
+    assert_eq!(ids, vec!["past201912-open", "agc041", "chokudai004"]);
+    assert_eq!(total_count.as_deref(), Some("7"));
+    assert!(get("per_page=3&page=3").await?.0.is_empty());
+
+    for query in &[
+        "from=2020/01/01",
+        "category=XYZ",
+        "per_page=0",
+        "per_page=1001",
+        "rated_for=abc",
+    ] {
+        let response = surf::get(url(&format!("/atcoder-api/v3/contests?{}", query), port)).await?;
+        assert_eq!(response.status(), 400, "{}", query);
+    }
+
+    server.cancel().await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_get_contest_difficulties() -> Result<()> {
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r"
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc151', 'abc151_a'),
+            ('abc151', 'abc151_b'),
+            ('abc151', 'abc151_c'),
+            ('abc151', 'abc151_d');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc151_a', 400.0, FALSE),
+            ('abc151_c', 1200.0, FALSE);
+        INSERT INTO contest_problem_stats (contest_id, problem_id, submitter_count, ac_user_count)
+        VALUES
+            ('abc151', 'abc151_a', 4000, 3599),
+            ('abc151', 'abc151_b', 3000, 1000),
+            ('abc151', 'abc151_c', 2000, 599);
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let difficulties: Vec<Value> =
+        surf::get(url("/atcoder-api/v3/contest/difficulties?id=abc151", port))
+            .recv_json()
+            .await?;
+    assert_eq!(difficulties.len(), 4);
+    assert_eq!(difficulties[0]["problem_id"], "abc151_a");
+    assert_eq!(difficulties[0]["difficulty"], 400.0);
+    assert_eq!(difficulties[0]["is_interpolated"], false);
+    assert_eq!(difficulties[1]["problem_id"], "abc151_b");
+    let interpolated = difficulties[1]["difficulty"].as_f64().unwrap();
+    assert!(interpolated > 400.0 && interpolated < 1200.0);
+    assert_eq!(difficulties[1]["is_interpolated"], true);
+    assert_eq!(difficulties[2]["difficulty"], 1200.0);
+    assert_eq!(difficulties[3]["problem_id"], "abc151_d");
+    assert_eq!(difficulties[3]["difficulty"], Value::Null);
+    assert_eq!(difficulties[3]["is_interpolated"], false);
+
+    let response = surf::get(url("/atcoder-api/v3/contest/difficulties?id=abc999", port)).await?;
+    assert_eq!(response.status(), 404);
+    let response = surf::get(url("/atcoder-api/v3/contest/difficulties", port)).await?;
+    assert_eq!(response.status(), 400);
+
+    server.cancel().await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_get_contest_table() -> Result<()> {
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r"
+        INSERT INTO contest_problem (contest_id, problem_id) VALUES
+            ('abc150', 'abc150_a'),
+            ('abc150', 'abc150_b'),
+            ('abc151', 'abc151_a'),
+            ('abc151', 'abc151_b'),
+            ('agc041', 'agc041_a');
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 1578150100, 'abc150_a', 'abc150', 'user1', 'Rust', 100.0, 1, 'AC'),
+            (2, 1578150200, 'abc150_b', 'abc150', 'user1', 'Rust', 0.0, 1, 'WA'),
+            (3, 1578747700, 'abc151_a', 'abc151', 'user1', 'Rust', 0.0, 1, 'WA'),
+            (4, 1578800000, 'abc151_a', 'abc151', 'user1', 'Rust', 100.0, 1, 'AC'),
+            (5, 1578900000, 'abc151_a', 'abc151', 'user1', 'Rust', 100.0, 1, 'AC'),
+            (6, 1578747700, 'abc151_b', 'abc151', 'user2', 'Rust', 100.0, 1, 'AC');
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let table: Vec<Value> = surf::get(url(
+        "/atcoder-api/v3/user/contest_table?user=user1&category=ABC",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    let contest_ids = table
+        .iter()
+        .map(|row| row["contest_id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(contest_ids, vec!["abc151", "abc150", "abc001"]);
+
+    let statuses = |row: &Value| {
+        row["problems"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cell| cell["status"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(statuses(&table[0]), vec!["ac_after", "untouched"]);
+    assert_eq!(table[0]["problems"][0]["first_ac_epoch_second"], 1578800000);
+    assert_eq!(statuses(&table[1]), vec!["ac_in_contest", "tried"]);
+    assert!(statuses(&table[2]).is_empty());
+
+    let response = surf::get(url("/atcoder-api/v3/user/contest_table", port)).await?;
+    assert_eq!(response.status(), 400);
+
+    server.cancel().await;
+    Ok(())
+}
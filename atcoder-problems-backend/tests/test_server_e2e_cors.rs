@@ -0,0 +1,73 @@
+use atcoder_problems_backend::config::{Config, ServerConfig};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server_with_config, Authentication};
+
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use rand::Rng;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_cors_origins() -> Result<()> {
+    use std::time::Duration;
+
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        let config = Config {
+            server: ServerConfig {
+                port,
+                cors_origins: vec!["https://kenkoooo.com".to_owned()],
+                ..ServerConfig::default()
+            },
+            ..Config::default()
+        };
+        run_server_with_config(pool, MockAuth, &config)
+            .await
+            .unwrap();
+    });
+    task::sleep(Duration::from_millis(1000)).await;
+
+    let response = surf::get(url("/atcoder-api/v3/dumps", port))
+        .set_header("Origin", "https://kenkoooo.com")
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .header("access-control-allow-origin")
+            .unwrap()
+            .as_str(),
+        "https://kenkoooo.com"
+    );
+
+    let response = surf::get(url("/atcoder-api/v3/dumps", port))
+        .set_header("Origin", "https://example.com")
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert!(response.header("access-control-allow-origin").is_none());
+
+    let response = surf::get(url("/atcoder-api/v3/dumps", port)).await?;
+    assert!(response.header("access-control-allow-origin").is_none());
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use rand::Rng;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_dumps() -> Result<()> {
+    use atcoder_problems_backend::sql::models::Dump;
+    use atcoder_problems_backend::sql::DumpClient;
+    use serde_json::{json, Value};
+
+    let conn = utils::initialize_and_connect_to_test_sql();
+    let dump = |name: &str, sha256: &str, generated_epoch_second: i64| Dump {
+        name: name.to_owned(),
+        url: format!("https://kenkoooo.com/atcoder/resources/{}", name),
+        size: 100,
+        sha256: sha256.to_owned(),
+        generated_epoch_second,
+    };
+    conn.update_dump(&dump("problems.json", "aaaa", 0)).unwrap();
+    conn.update_dump(&dump("contests.json", "bbbb", 0)).unwrap();
+    conn.update_dump(&dump("problems.json", "cccc", 1529154000))
+        .unwrap();
+    assert_eq!(conn.load_dumps().unwrap().len(), 2);
+
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let response = surf::get(url("/atcoder-api/v3/dumps", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        response,
+        json!([
+            {
+                "name": "contests.json",
+                "url": "https://kenkoooo.com/atcoder/resources/contests.json",
+                "size": 100,
+                "sha256": "bbbb",
+                "generated_at": "1970-01-01T00:00:00Z"
+            },
+            {
+                "name": "problems.json",
+                "url": "https://kenkoooo.com/atcoder/resources/problems.json",
+                "size": 100,
+                "sha256": "cccc",
+                "generated_at": "2018-06-16T13:00:00Z"
+            }
+        ])
+    );
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use rand::Rng;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_feed() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r#"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change)
+        VALUES
+            ('abc100', 1529154000, 6000, 'AtCoder Beginner Contest 100', ' ~ 1199'),
+            ('abc101', 1529758800, 6000, 'AtCoder Beginner Contest 101', ' ~ 1199');
+        INSERT INTO problems (id, contest_id, title)
+        VALUES
+            ('abc100_a', 'abc100', 'A. Happy Birthday!'),
+            ('abc101_a', 'abc101', 'A. Eating Symbols Easy'),
+            ('orphan_a', 'orphan', 'A. <Orphan> & Lost');
+        "#,
+    )
+    .unwrap();
+
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let mut response = surf::get(url("/feed/contests.atom", port)).await?;
+    assert_eq!(
+        response.header("content-type").unwrap().as_str(),
+        "application/atom+xml; charset=utf-8"
+    );
+    let body = response.body_string().await?;
+    assert!(body.contains("<updated>2018-06-23T13:00:00Z</updated>"));
+    let abc101 = body.find("AtCoder Beginner Contest 101").unwrap();
+    let abc100 = body.find("AtCoder Beginner Contest 100").unwrap();
+    assert!(abc101 < abc100);
+
+    let body = surf::get(url("/feed/problems.atom", port))
+        .recv_string()
+        .await?;
+    assert_eq!(body.matches("<entry>").count(), 2);
+    assert!(body.contains("<id>https://atcoder.jp/contests/abc101/tasks/abc101_a</id>"));
+    assert!(body.find("abc101_a").unwrap() < body.find("abc100_a").unwrap());
+    assert!(!body.contains("Orphan"));
+
+    server.race(ready(())).await;
+    Ok(())
+}
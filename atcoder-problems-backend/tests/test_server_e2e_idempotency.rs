@@ -0,0 +1,166 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_idempotency_key() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let contest = json!({
+        "title": "contest title",
+        "memo": "",
+        "start_epoch_second": 1,
+        "duration_second": 2
+    });
+    let mut contest_ids = vec![];
+    for _ in 0..2 {
+        let mut response = surf::post(url("/internal-api/contest/create", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .set_header("Idempotency-Key", "create-1")
+            .body_json(&contest)?
+            .await?;
+        assert!(response.status().is_success());
+        let body = response.body_json::<Value>().await?;
+        contest_ids.push(body["contest_id"].as_str().unwrap().to_owned());
+    }
+    assert_eq!(contest_ids[0], contest_ids[1]);
+    let response = surf::get(url("/internal-api/contest/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response.as_array().unwrap().len(), 1);
+
+    // The key can not be reused with another request.
+    let response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .set_header("Idempotency-Key", "create-1")
+        .body_json(&json!({
+            "title": "another title",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2
+        }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::UnprocessableEntity);
+
+    // A failed request does not consume the key.
+    let response = surf::post(url("/internal-api/list/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .set_header("Idempotency-Key", "list-1")
+        .body_json(&json!({ "list_name": "a".repeat(257) }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::BadRequest);
+    for _ in 0..2 {
+        let response = surf::post(url("/internal-api/list/create", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .set_header("Idempotency-Key", "list-1")
+            .body_json(&json!({ "list_name": "a" }))?
+            .await?;
+        assert!(response.status().is_success());
+    }
+    let response = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response.as_array().unwrap().len(), 1);
+
+    // The requests without the key are not deduplicated.
+    for _ in 0..2 {
+        let response = surf::post(url("/internal-api/list/create", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({ "list_name": "b" }))?
+            .await?;
+        assert!(response.status().is_success());
+    }
+    let response = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response.as_array().unwrap().len(), 3);
+
+    // A request in progress blocks the retries, but an abandoned one does not.
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        "UPDATE internal_idempotency_keys SET status = NULL, response_body = NULL WHERE idempotency_key = 'list-1';",
+    )
+    .unwrap();
+    let retry = || {
+        surf::post(url("/internal-api/list/create", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .set_header("Idempotency-Key", "list-1")
+            .body_json(&json!({ "list_name": "a" }))
+    };
+    let response = retry()?.await?;
+    assert_eq!(response.status(), StatusCode::Conflict);
+    conn.batch_execute(
+        "UPDATE internal_idempotency_keys SET created_epoch_second = created_epoch_second - 3600 WHERE idempotency_key = 'list-1';",
+    )
+    .unwrap();
+    let response = retry()?.await?;
+    assert!(response.status().is_success());
+    let response = retry()?.await?;
+    assert_eq!(response.header("idempotency-replayed").unwrap(), "true");
+    let response = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response.as_array().unwrap().len(), 4);
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use rand::Rng;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_judge_summary() -> Result<()> {
+    use atcoder_problems_backend::sql::models::{JudgeSubmission, Submission};
+    use atcoder_problems_backend::sql::{JudgeClient, SubmissionClient};
+    use serde_json::{json, Value};
+
+    const DAY: i64 = 24 * 3600;
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.update_submissions(&[
+        Submission {
+            id: 1,
+            epoch_second: DAY,
+            problem_id: "abc001_a".to_owned(),
+            user_id: "kenkoooo".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        },
+        Submission {
+            id: 2,
+            epoch_second: 3 * DAY,
+            problem_id: "abc001_b".to_owned(),
+            user_id: "kenkoooo".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+    conn.update_judge_submissions(&[
+        JudgeSubmission {
+            judge: "codeforces".to_owned(),
+            id: 1,
+            epoch_second: 2 * DAY,
+            problem_id: "1000A".to_owned(),
+            user_id: "kenkoooo_cf".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        },
+        JudgeSubmission {
+            judge: "codeforces".to_owned(),
+            id: 2,
+            epoch_second: 2 * DAY,
+            problem_id: "1000B".to_owned(),
+            user_id: "kenkoooo_cf".to_owned(),
+            result: "WA".to_owned(),
+            ..Default::default()
+        },
+        JudgeSubmission {
+            judge: "aoj".to_owned(),
+            id: 1,
+            epoch_second: 4 * DAY,
+            problem_id: "ITP1_1_A".to_owned(),
+            user_id: "kenkoooo".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        },
+        JudgeSubmission {
+            judge: "yukicoder".to_owned(),
+            id: (1 << 32) | 10,
+            epoch_second: 10 * DAY,
+            problem_id: "10".to_owned(),
+            user_id: "kenkoooo".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let summary: Value = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?atcoder=kenkoooo&codeforces=kenkoooo_cf",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    assert_eq!(
+        summary,
+        json!({
+            "accepted_count": 3,
+            "max_streak": 3,
+            "judges": [
+                {"judge": "atcoder", "user_id": "kenkoooo", "accepted_count": 2, "max_streak": 1},
+                {"judge": "codeforces", "user_id": "kenkoooo_cf", "accepted_count": 1, "max_streak": 1},
+            ]
+        })
+    );
+
+    let summary: Value = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?atcoder=kenkoooo&codeforces=kenkoooo_cf&judge=atcoder",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    assert_eq!(summary["accepted_count"], json!(2));
+    assert_eq!(summary["judges"].as_array().unwrap().len(), 1);
+
+    let summary: Value = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?atcoder=kenkoooo&codeforces=kenkoooo_cf&aoj=kenkoooo",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    assert_eq!(summary["accepted_count"], json!(4));
+    assert_eq!(summary["max_streak"], json!(4));
+    let summary: Value = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?atcoder=kenkoooo&aoj=kenkoooo&judge=aoj",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    assert_eq!(
+        summary,
+        json!({
+            "accepted_count": 1,
+            "max_streak": 1,
+            "judges": [
+                {"judge": "aoj", "user_id": "kenkoooo", "accepted_count": 1, "max_streak": 1},
+            ]
+        })
+    );
+
+    let summary: Value = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?aoj=kenkoooo&yukicoder=kenkoooo",
+        port,
+    ))
+    .recv_json()
+    .await?;
+    assert_eq!(summary["accepted_count"], json!(2));
+    assert_eq!(summary["judges"][1]["judge"], json!("yukicoder"));
+    assert_eq!(summary["judges"][1]["accepted_count"], json!(1));
+
+    let response = surf::get(url(
+        "/atcoder-api/v3/user/judge_summary?atcoder=kenkoooo&judge=unknown",
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_webhook() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "https://example.com/hook",
+            "secret": "secret",
+            "event_types": ["new_ac", "unknown"],
+        }))?
+        .await?;
+    assert_eq!(response.status(), 400);
+
+    let response = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "http://example.com/hook",
+            "secret": "secret",
+            "event_types": ["new_ac"],
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "https://example.com/hook",
+            "secret": "secret",
+            "event_types": ["new_ac"],
+            "format": "slack",
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "https://example.com/hook",
+            "secret": "secret",
+            "event_types": ["new_ac"],
+            "format": "unknown",
+        }))?
+        .await?;
+    assert_eq!(response.status(), 400);
+
+    let response = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "https://hooks.slack.com/services/T/B/X",
+            "secret": "secret",
+            "event_types": ["new_ac"],
+            "format": "slack",
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let body = surf::post(url("/internal-api/notification/webhook/register", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "url": "https://example.com/hook",
+            "secret": "secret",
+            "event_types": ["new_ac", "streak_milestone"],
+        }))?
+        .recv_json::<Value>()
+        .await?;
+    let webhook_id = body["id"].as_str().unwrap().to_owned();
+
+    let webhooks = surf::get(url("/internal-api/notification/webhook/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(webhooks.as_array().unwrap().len(), 2);
+    assert_eq!(webhooks[0]["format"], json!("slack"));
+    assert_eq!(webhooks[1]["id"], json!(webhook_id));
+    assert_eq!(webhooks[1]["url"], json!("https://example.com/hook"));
+    assert_eq!(
+        webhooks[1]["event_types"],
+        json!(["new_ac", "streak_milestone"])
+    );
+    assert_eq!(webhooks[1]["format"], json!("json"));
+    assert!(webhooks[1].get("secret").is_none());
+
+    let response = surf::get(url("/internal-api/notification/webhook/list", port)).await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let response = surf::post(url("/internal-api/notification/webhook/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "id": webhook_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let webhooks = surf::get(url("/internal-api/notification/webhook/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(webhooks.as_array().unwrap().len(), 1);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -69,7 +69,7 @@ async fn test_list() -> Result<()> {
         .skip(1)
         .next()
         .unwrap();
-    assert_eq!(token, VALID_TOKEN);
+    assert_ne!(token, VALID_TOKEN);
 
     let response = surf::get(url("/internal-api/list/my", port))
         .set_header("Cookie", format!("token={}", token))
@@ -282,6 +282,48 @@ async fn test_list_item() -> Result<()> {
         ])
     );
 
+    let response = surf::post(url("/internal-api/list/item/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_list_id": internal_list_id,
+            "problem_id": "problem_2",
+            "memo": "memo_2"
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let list = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        list,
+        json!([
+            {
+                "internal_list_id": internal_list_id,
+                "internal_list_name": "a",
+                "internal_user_id": "0",
+                "items": [{"problem_id": "problem_2", "memo":"memo_2"}]
+            }
+        ])
+    );
+
+    let response = surf::post(url("/internal-api/list/item/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_list_id": "unknown_list",
+            "problem_id": "problem_1"
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/list/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_list_id": "unknown_list",
+            "name": "b"
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
     server.race(ready(())).await;
     Ok(())
 }
@@ -341,6 +383,27 @@ async fn test_list_delete() -> Result<()> {
         .await?;
     assert!(list.as_array().unwrap().is_empty());
 
+    let response = surf::post(url("/internal-api/list/restore", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&map)?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let list = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(list[0]["internal_list_id"], internal_list_id, "{:?}", list);
+    assert_eq!(list[0]["items"][0]["problem_id"], "problem_1", "{:?}", list);
+
+    let mut map = BTreeMap::new();
+    map.insert("internal_list_id", "unknown");
+    let response = surf::post(url("/internal-api/list/restore", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&map)?
+        .await?;
+    assert_eq!(response.status(), 404);
+
     server.race(ready(())).await;
     Ok(())
 }
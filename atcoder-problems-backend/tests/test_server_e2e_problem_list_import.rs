@@ -0,0 +1,149 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_import_export_list() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO problems (id, contest_id, title) VALUES
+            ('problem_1', 'contest', 'A. Problem 1'),
+            ('problem_2', 'contest', 'B. Problem 2');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut response = surf::post(url("/internal-api/list/import", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "format": "json",
+            "data": json!({
+                "internal_list_name": "imported",
+                "items": [{"problem_id": "problem_1", "memo": "memo_1"}, {"problem_id": "problem_2"}]
+            }).to_string()
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let value: Value = response.body_json().await?;
+    let internal_list_id = value["internal_list_id"].as_str().unwrap().to_owned();
+
+    let exported = surf::get(url(
+        &format!("/internal-api/list/export/{}", internal_list_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(
+        exported,
+        json!({
+            "internal_list_name": "imported",
+            "items": [
+                {"problem_id": "problem_1", "memo": "memo_1"},
+                {"problem_id": "problem_2", "memo": ""}
+            ]
+        })
+    );
+    let exported = surf::get(url(
+        &format!("/internal-api/list/export/{}?format=text", internal_list_id),
+        port,
+    ))
+    .recv_string()
+    .await?;
+    assert_eq!(exported, "problem_1\nproblem_2\n");
+
+    let mut response = surf::post(url("/internal-api/list/import", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "format": "text",
+            "data": exported,
+            "list_name": "from text"
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let value: Value = response.body_json().await?;
+    let internal_list_id = value["internal_list_id"].as_str().unwrap().to_owned();
+    let list = surf::get(url(
+        &format!("/internal-api/list/get/{}", internal_list_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(list["internal_list_name"], json!("from text"));
+    assert_eq!(list["items"].as_array().unwrap().len(), 2);
+
+    let response = surf::post(url("/internal-api/list/import", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "format": "text",
+            "data": "problem_1\nunknown_problem\n",
+        }))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+    let lists = surf::get(url("/internal-api/list/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(lists.as_array().unwrap().len(), 2);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_shared_list() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 100, 'problem_1', '', 'viewer', '', 100, 0, 'AC'),
+            (2, 200, 'problem_2', '', 'viewer', '', 100, 0, 'WA'),
+            (3, 300, 'problem_2', '', 'other_user', '', 100, 0, 'AC');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut response = surf::post(url("/internal-api/list/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"list_name":"a"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let value: Value = response.body_json().await?;
+    let internal_list_id = value["internal_list_id"].as_str().unwrap().to_owned();
+    for problem_id in ["problem_1", "problem_2"].iter() {
+        let response = surf::post(url("/internal-api/list/item/add", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({
+                "internal_list_id": internal_list_id,
+                "problem_id": problem_id,
+                "memo": "memo"
+            }))?
+            .await?;
+        assert!(response.status().is_success(), "{:?}", response);
+    }
+
+    let mut response = surf::post(url("/internal-api/list/share/generate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_list_id": internal_list_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let value: Value = response.body_json().await?;
+    let share_token = value["share_token"].as_str().unwrap().to_owned();
+
+    // generating again returns the same token.
+    let value = surf::post(url("/internal-api/list/share/generate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_list_id": internal_list_id }))?
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(value["share_token"].as_str().unwrap(), share_token);
+
+    let list = surf::get(url(
+        &format!("/internal-api/list/shared/{}?user=viewer", share_token),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(
+        list,
+        json!({
+            "internal_list_name": "a",
+            "items": [
+                {"problem_id": "problem_1", "memo": "memo", "solved": true},
+                {"problem_id": "problem_2", "memo": "memo", "solved": false}
+            ]
+        })
+    );
+
+    let list = surf::get(url(
+        &format!("/internal-api/list/shared/{}", share_token),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(list["items"][0]["solved"], json!(false));
+
+    let response = surf::post(url("/internal-api/list/share/revoke", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_list_id": internal_list_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::get(url(
+        &format!("/internal-api/list/shared/{}", share_token),
+        port,
+    ))
+    .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_problem_notes_and_tags() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/note/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"problem_id": "problem_1", "note": "use segment tree"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    for (problem_id, tag) in [
+        ("problem_1", "DP"),
+        ("problem_1", "revisit"),
+        ("problem_2", "DP"),
+        ("problem_2", "DP"),
+    ]
+    .iter()
+    {
+        let response = surf::post(url("/internal-api/note/tag/add", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({"problem_id": problem_id, "tag": tag}))?
+            .await?;
+        assert!(response.status().is_success(), "{:?}", response);
+    }
+    let response = surf::post(url("/internal-api/note/tag/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"problem_id": "problem_2", "tag": " "}))?
+        .await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    let mut notes = surf::get(url("/internal-api/note/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert!(notes[0]["updated_epoch_second"].is_i64());
+    notes[0]["updated_epoch_second"] = json!(0);
+    assert_eq!(
+        notes,
+        json!([
+            {
+                "problem_id": "problem_1",
+                "note": "use segment tree",
+                "updated_epoch_second": 0,
+                "tags": ["DP", "revisit"]
+            },
+            {
+                "problem_id": "problem_2",
+                "note": "",
+                "updated_epoch_second": null,
+                "tags": ["DP"]
+            }
+        ])
+    );
+
+    let tagged = surf::get(url("/internal-api/note/tagged?tag=DP", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    let problem_ids = tagged
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["problem_id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(problem_ids, vec!["problem_1", "problem_2"]);
+
+    let response = surf::post(url("/internal-api/note/tag/remove", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"problem_id": "problem_1", "tag": "DP"}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/note/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({"problem_id": "problem_1", "note": ""}))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+
+    let notes = surf::get(url("/internal-api/note/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        notes,
+        json!([
+            {"problem_id": "problem_1", "note": "", "updated_epoch_second": null, "tags": ["revisit"]},
+            {"problem_id": "problem_2", "note": "", "updated_epoch_second": null, "tags": ["DP"]}
+        ])
+    );
+    let tagged = surf::get(url("/internal-api/note/tagged?tag=DP", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(tagged.as_array().unwrap().len(), 1);
+
+    let response = surf::get(url("/internal-api/note/my", port)).await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,399 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use diesel::connection::SimpleConnection;
+use rand::Rng;
+use serde_json::Value;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_a', 'abc001', 'A. Shortest Path'),
+            ('abc001_b', 'abc001', 'B. Longest Path'),
+            ('abc002_a', 'abc002', 'A. Sum of Numbers');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc001_a', 100.0, FALSE),
+            ('abc001_b', 1200.0, FALSE);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'abc001_b', 'abc001', 'user1', 'Rust', 100.0, 20, 'WA');
+        ",
+    )
+    .unwrap();
+    random_port()
+}
+
+fn random_port() -> u16 {
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_search_problems() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let search = |query: &str| {
+        let url = url(&format!("/atcoder-api/v3/problems/search?{}", query), port);
+        async move {
+            let problems = surf::get(url).recv_json::<Vec<Value>>().await?;
+            let ids = problems
+                .iter()
+                .map(|problem| problem["id"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            Ok::<_, http_types::Error>(ids)
+        }
+    };
+
+    assert_eq!(search("").await?, vec!["abc001_a", "abc001_b", "abc002_a"]);
+    assert_eq!(search("q=path").await?, vec!["abc001_a", "abc001_b"]);
+    assert_eq!(search("q=shortest%20path").await?, vec!["abc001_a"]);
+    assert_eq!(search("q=abc002_a").await?, vec!["abc002_a"]);
+    assert_eq!(
+        search("difficulty_from=1000").await?,
+        vec!["abc001_b"],
+        "The problems without the difficulty are excluded by the difficulty filters."
+    );
+    assert_eq!(search("difficulty_to=1000").await?, vec!["abc001_a"]);
+    assert_eq!(search("solved_by=user1").await?, vec!["abc001_a"]);
+    assert_eq!(
+        search("q=path&not_solved_by=user1").await?,
+        vec!["abc001_b"]
+    );
+    assert_eq!(
+        search("solved_by=&not_solved_by=").await?,
+        vec!["abc001_a", "abc001_b", "abc002_a"]
+    );
+
+    let problems = surf::get(url("/atcoder-api/v3/problems/search?q=sum", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        problems,
+        serde_json::json!([{
+            "id": "abc002_a",
+            "contest_id": "abc002",
+            "title": "A. Sum of Numbers",
+            "difficulty": null
+        }])
+    );
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_detailed_problems() -> Result<()> {
+    let port = random_port();
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_a', 'abc001', 'A. Shortest Path'),
+            ('abc002_a', 'abc002', 'A. Sum of Numbers');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc001_a', 100.0, FALSE);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result, execution_time)
+        VALUES
+            (1, 100, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC', 10),
+            (2, 200, 'abc001_a', 'abc001', 'user2', 'Rust', 100.0, 10, 'AC', 30);
+        INSERT INTO first (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 1);
+        INSERT INTO fastest (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 1);
+        INSERT INTO shortest (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 2);
+        INSERT INTO points (problem_id, point) VALUES ('abc001_a', 100.0);
+        INSERT INTO solver (problem_id, user_count) VALUES ('abc001_a', 2);
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let mut response = surf::get(url("/atcoder-api/v3/problems/detailed", port)).await?;
+    let tag = response.header("etag").unwrap()[0].as_str().to_owned();
+    let problems = response.body_json::<Value>().await?;
+    assert_eq!(
+        problems,
+        serde_json::json!([
+            {
+                "id": "abc001_a",
+                "contest_id": "abc001",
+                "title": "A. Shortest Path",
+                "shortest_submission_id": 2,
+                "shortest_problem_id": "abc001_a",
+                "shortest_contest_id": "abc001",
+                "shortest_user_id": "user2",
+                "fastest_submission_id": 1,
+                "fastest_problem_id": "abc001_a",
+                "fastest_contest_id": "abc001",
+                "fastest_user_id": "user1",
+                "first_submission_id": 1,
+                "first_problem_id": "abc001_a",
+                "first_contest_id": "abc001",
+                "first_user_id": "user1",
+                "source_code_length": 10,
+                "execution_time": 10,
+                "point": 100.0,
+                "predict": null,
+                "solver_count": 2,
+                "difficulty": 100.0,
+                "is_experimental": false
+            },
+            {
+                "id": "abc002_a",
+                "contest_id": "abc002",
+                "title": "A. Sum of Numbers",
+                "shortest_submission_id": null,
+                "shortest_problem_id": null,
+                "shortest_contest_id": null,
+                "shortest_user_id": null,
+                "fastest_submission_id": null,
+                "fastest_problem_id": null,
+                "fastest_contest_id": null,
+                "fastest_user_id": null,
+                "first_submission_id": null,
+                "first_problem_id": null,
+                "first_contest_id": null,
+                "first_user_id": null,
+                "source_code_length": null,
+                "execution_time": null,
+                "point": null,
+                "predict": null,
+                "solver_count": null,
+                "difficulty": null,
+                "is_experimental": null
+            }
+        ])
+    );
+
+    // The cached response is served until the tables are updated.
+    conn.batch_execute("UPDATE solver SET user_count = 3")
+        .unwrap();
+    let problems = surf::get(url("/atcoder-api/v3/problems/detailed", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(problems[0]["solver_count"], 2);
+
+    let response = surf::get(url("/atcoder-api/v3/problems/detailed", port))
+        .set_header("if-none-match", &tag)
+        .await?;
+    assert_eq!(response.status(), 304);
+
+    conn.batch_execute(
+        "INSERT INTO table_versions (name, version, updated_epoch_second) VALUES ('solver', 1, 0)",
+    )
+    .unwrap();
+    let mut response = surf::get(url("/atcoder-api/v3/problems/detailed", port))
+        .set_header("if-none-match", &tag)
+        .await?;
+    assert_eq!(response.status(), 200);
+    let problems = response.body_json::<Value>().await?;
+    assert_eq!(problems[0]["solver_count"], 3);
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_problem_detail() -> Result<()> {
+    let port = random_port();
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_a', 'abc001', 'A. Shortest Path'),
+            ('abc002_a', 'abc002', 'A. Sum of Numbers');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc001_a', 100.0, TRUE);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result, execution_time)
+        VALUES
+            (1, 1577804400, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC', 10),
+            (2, 1577890799, 'abc001_a', 'abc001', 'user2', 'Rust', 100.0, 10, 'AC', 30),
+            (3, 1577890800, 'abc001_a', 'abc001', 'user3', 'Rust', 0.0, 5, 'WA', 5),
+            (4, 1577890800, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 15, 'AC', 20),
+            (5, 1577977200, 'abc001_a', 'abc001', 'user3', 'Rust', 100.0, 30, 'AC', 40);
+        INSERT INTO first (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 1);
+        INSERT INTO first_practice (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 4);
+        INSERT INTO fastest (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 1);
+        INSERT INTO shortest (contest_id, problem_id, submission_id) VALUES ('abc001', 'abc001_a', 2);
+        INSERT INTO points (problem_id, point) VALUES ('abc001_a', 100.0);
+        INSERT INTO solver (problem_id, user_count) VALUES ('abc001_a', 3);
+        INSERT INTO solve_time (problem_id, median_second, user_count) VALUES ('abc001_a', 60.0, 2);
+        INSERT INTO length_distributions (
+            problem_id, user_count, min_length, first_quartile, median, third_quartile,
+            max_length, bucket_width, bucket_counts
+        ) VALUES ('abc001_a', 3, 10, 12.5, 15.0, 22.5, 30, 3, '{1, 0, 1, 0, 0, 0, 0, 0, 0, 1}');
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let problem = surf::get(url("/atcoder-api/v3/problem?id=abc001_a", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(problem["id"], "abc001_a");
+    assert_eq!(problem["contest_id"], "abc001");
+    assert_eq!(problem["title"], "A. Shortest Path");
+    assert_eq!(problem["difficulty"], 100.0);
+    assert_eq!(problem["is_experimental"], true);
+    assert_eq!(problem["point"], 100.0);
+    assert_eq!(problem["solver_count"], 3);
+    assert_eq!(problem["solve_time_median_second"], 60.0);
+    assert_eq!(problem["solve_time_user_count"], 2);
+    assert_eq!(problem["length_distribution"]["median"], 15.0);
+    assert_eq!(
+        problem["length_distribution"]["bucket_counts"],
+        serde_json::json!([1, 0, 1, 0, 0, 0, 0, 0, 0, 1])
+    );
+    assert_eq!(problem["first"]["id"], 1);
+    assert_eq!(problem["first"]["user_id"], "user1");
+    assert_eq!(problem["first_practice"]["id"], 4);
+    assert_eq!(problem["fastest"]["execution_time"], 10);
+    assert_eq!(problem["shortest"]["user_id"], "user2");
+    assert_eq!(problem["shortest"]["length"], 10);
+    assert_eq!(
+        problem["solver_trend"],
+        serde_json::json!([
+            // 2020-01-01 00:00 JST
+            {"epoch_second": 1577804400, "new_solver_count": 2},
+            // 2020-01-03 00:00 JST
+            {"epoch_second": 1577977200, "new_solver_count": 1}
+        ])
+    );
+
+    let problem = surf::get(url("/atcoder-api/v3/problem?id=abc002_a", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(problem["difficulty"], Value::Null);
+    assert_eq!(problem["solver_count"], Value::Null);
+    assert_eq!(problem["solve_time_median_second"], Value::Null);
+    assert_eq!(problem["first"], Value::Null);
+    assert_eq!(problem["first_practice"], Value::Null);
+    assert_eq!(problem["solver_trend"], serde_json::json!([]));
+
+    let response = surf::get(url("/atcoder-api/v3/problem?id=abc003_a", port)).await?;
+    assert_eq!(response.status(), 404);
+    let response = surf::get(url("/atcoder-api/v3/problem", port)).await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_unsolved_problems() -> Result<()> {
+    let port = random_port();
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_a', 'abc001', 'A. Shortest Path'),
+            ('abc001_b', 'abc001', 'B. Longest Path'),
+            ('abc001_c', 'abc001', 'C. Path'),
+            ('arc001_a', 'arc001', 'A. Sum of Numbers');
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc001', 0, 6000, 'AtCoder Beginner Contest 001', '-'),
+            ('arc001', 0, 6000, 'AtCoder Regular Contest 001', '-');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc001_a', 100.0, FALSE),
+            ('abc001_b', 1200.0, FALSE),
+            ('arc001_a', 800.0, FALSE);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'abc001_b', 'abc001', 'user1', 'Rust', 100.0, 20, 'WA');
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let unsolved = |query: &str| {
+        let url = url(&format!("/atcoder-api/v3/user/unsolved?{}", query), port);
+        async move {
+            let problems = surf::get(url).recv_json::<Vec<Value>>().await?;
+            let ids = problems
+                .iter()
+                .map(|problem| problem["id"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            Ok::<_, http_types::Error>(ids)
+        }
+    };
+
+    assert_eq!(
+        unsolved("user=user1").await?,
+        vec!["arc001_a", "abc001_b", "abc001_c"]
+    );
+    assert_eq!(
+        unsolved("user=user2").await?,
+        vec!["abc001_a", "arc001_a", "abc001_b", "abc001_c"]
+    );
+    assert_eq!(
+        unsolved("user=user1&difficulty_from=0&difficulty_to=1000").await?,
+        vec!["arc001_a"]
+    );
+    assert_eq!(
+        unsolved("user=user1&category=ABC").await?,
+        vec!["abc001_b", "abc001_c"]
+    );
+    assert_eq!(
+        unsolved("user=user2&category=ARC&difficulty_to=1000").await?,
+        vec!["arc001_a"]
+    );
+
+    let response = surf::get(url("/atcoder-api/v3/user/unsolved", port)).await?;
+    assert_eq!(response.status(), 400);
+    let response = surf::get(url(
+        "/atcoder-api/v3/user/unsolved?user=user1&category=XYZ",
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use atcoder_problems_backend::config::{Config, QueryConfig, ServerConfig};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server_with_config, Authentication};
+
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use diesel::connection::SimpleConnection;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_query_timeout_and_circuit_breaker() -> Result<()> {
+    let conn = utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        let config = Config {
+            server: ServerConfig {
+                port,
+                ..ServerConfig::default()
+            },
+            query: QueryConfig {
+                timeout_millis: 500,
+                submissions_timeout_millis: 500,
+                breaker_failure_threshold: 2,
+                breaker_open_second: 3600,
+                ..QueryConfig::default()
+            },
+            ..Config::default()
+        };
+        run_server_with_config(pool, MockAuth, &config)
+            .await
+            .unwrap();
+    });
+    task::sleep(Duration::from_millis(1000)).await;
+
+    let user_info = "/atcoder-api/v2/user_info?user=u1";
+    let response = surf::get(url(user_info, port)).await?;
+    assert_eq!(response.status(), 200);
+    assert!(response.header("warning").is_none());
+
+    // Stalls the queries of the handlers.
+    conn.batch_execute("BEGIN; LOCK TABLE table_versions, submissions IN ACCESS EXCLUSIVE MODE;")
+        .unwrap();
+
+    let mut response = surf::get(url(user_info, port)).await?;
+    assert_eq!(
+        response.status(),
+        200,
+        "The last response is served while the database is unavailable."
+    );
+    assert_eq!(
+        response.header("warning").unwrap(),
+        "110 - \"Response is Stale\""
+    );
+    assert_eq!(
+        response.body_json::<serde_json::Value>().await?["user_id"],
+        "u1"
+    );
+
+    let response = surf::get(url("/atcoder-api/v3/recent", port)).await?;
+    assert_eq!(response.status(), 503);
+
+    conn.batch_execute("ROLLBACK;").unwrap();
+    let started = Instant::now();
+    let response = surf::get(url("/atcoder-api/v3/recent", port)).await?;
+    assert_eq!(
+        response.status(),
+        503,
+        "The circuit breaker is opened after the consecutive timeouts."
+    );
+    assert!(started.elapsed() < Duration::from_millis(500));
+    Ok(())
+}
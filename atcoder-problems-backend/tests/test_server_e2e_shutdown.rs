@@ -0,0 +1,49 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use rand::Rng;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_graceful_shutdown() -> Result<()> {
+    use atcoder_problems_backend::shutdown;
+    use std::time::Duration;
+
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(Duration::from_millis(1000)).await;
+
+    let response = surf::get(url("/healthcheck", port)).await?;
+    assert_eq!(response.status(), 200);
+
+    shutdown::request();
+    async_std::future::timeout(Duration::from_secs(5), server).await?;
+    assert!(surf::get(url("/healthcheck", port)).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use atcoder_problems_backend::config::{Config, ServerConfig, SubmissionCodeConfig};
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server_with_config, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+/// Serves the submission pages of AtCoder, and counts the requests.
+async fn run_mock_atcoder(port: u16, requests: Arc<AtomicUsize>) {
+    let mut app = tide::with_state(requests);
+    app.at("/contests/:contest_id/submissions/:submission_id")
+        .get(|request: tide::Request<Arc<AtomicUsize>>| async move {
+            request.state().fetch_add(1, Ordering::SeqCst);
+            let submission_id = request.param::<String>("submission_id")?;
+            let code = match submission_id.as_str() {
+                "1" | "4" => "int main() { return 0 &amp;&amp; 1; }".to_owned(),
+                _ => "x".repeat(100),
+            };
+            // The page of the submission 4 is too large, though its code is short.
+            let padding = match submission_id.as_str() {
+                "4" => " ".repeat(2 * 1024 * 1024),
+                _ => String::new(),
+            };
+            let html = format!(
+                r#"<pre id="submission-code" class="prettyprint linenums">{}</pre>{}"#,
+                code, padding
+            );
+            Ok(tide::Response::new(StatusCode::Ok).body_string(html))
+        });
+    app.listen(format!("localhost:{}", port)).await.unwrap();
+}
+
+#[async_std::test]
+async fn test_submission_code() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let atcoder_port = port - 10000;
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('contest_ended', 0, 100, '', '-'),
+            ('contest_running', 0, 10000000000, '', '-');
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 10, 'problem_1', 'contest_ended', 'user', '', 100, 0, 'AC'),
+            (2, 10, 'problem_2', 'contest_running', 'user', '', 100, 0, 'AC'),
+            (3, 10, 'problem_1', 'contest_ended', 'user', '', 100, 0, 'AC'),
+            (4, 10, 'problem_1', 'contest_ended', 'user', '', 100, 0, 'AC');
+        "#,
+    )
+    .unwrap();
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let mock = task::spawn(run_mock_atcoder(atcoder_port, requests.clone()));
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        let config = Config {
+            server: ServerConfig {
+                port,
+                ..ServerConfig::default()
+            },
+            submission_code: SubmissionCodeConfig {
+                atcoder_url: format!("http://localhost:{}", atcoder_port),
+                max_code_bytes: 64,
+                fetch_interval_millis: 1_000,
+                max_wait_millis: 0,
+                ..SubmissionCodeConfig::default()
+            },
+            ..Config::default()
+        };
+        run_server_with_config(pool, MockAuth, &config)
+            .await
+            .unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::get(url("/internal-api/submission/code/1", port)).await?;
+    assert_eq!(response.status(), 401);
+
+    for submission_id in [2, 99].iter() {
+        let response = surf::get(url(
+            &format!("/internal-api/submission/code/{}", submission_id),
+            port,
+        ))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+        assert_eq!(
+            response.status(),
+            404,
+            "The code of the submission {} is not public.",
+            submission_id
+        );
+    }
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+    let response = surf::get(url("/internal-api/submission/code/3", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 413);
+
+    let response = surf::get(url("/internal-api/submission/code/1", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.header("retry-after").unwrap(), "1");
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    task::sleep(std::time::Duration::from_millis(1100)).await;
+    for _ in 0..2 {
+        let code = surf::get(url("/internal-api/submission/code/1", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .recv_json::<Value>()
+            .await?;
+        assert_eq!(code["contest_id"], "contest_ended");
+        assert_eq!(code["code"], "int main() { return 0 && 1; }");
+    }
+    assert_eq!(
+        requests.load(Ordering::SeqCst),
+        2,
+        "The second request is served from the database."
+    );
+
+    task::sleep(std::time::Duration::from_millis(1100)).await;
+    let response = surf::get(url("/internal-api/submission/code/4", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), 413);
+
+    server.race(mock).race(ready(())).await;
+    Ok(())
+}
@@ -1,14 +1,15 @@
 use atcoder_problems_backend::error::Result;
 use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
-use atcoder_problems_backend::sql::models::Submission;
+use atcoder_problems_backend::sql::models::{Point, Submission};
 use atcoder_problems_backend::sql::schema::*;
+use atcoder_problems_backend::sql::{SubmissionArchiveClient, SubmissionClient};
 
 use async_std::future::ready;
 use async_std::prelude::*;
 use async_std::task;
 use async_trait::async_trait;
 use atcoder_problems_backend::server::GitHubUserResponse;
-use diesel::{insert_into, ExpressionMethods, PgConnection, RunQueryDsl};
+use diesel::{insert_into, Connection, ExpressionMethods, PgConnection, RunQueryDsl};
 use rand::Rng;
 
 pub mod utils;
@@ -50,7 +51,7 @@ fn prepare_data_set(conn: &PgConnection) {
     insert_into(rated_point_sum::table)
         .values(vec![(
             rated_point_sum::user_id.eq("u1"),
-            rated_point_sum::point_sum.eq(1.0),
+            rated_point_sum::point_sum.eq(Point::from(1.0)),
         )])
         .execute(conn)
         .unwrap();
@@ -118,6 +119,22 @@ async fn test_user_submissions() -> Result<()> {
     assert_eq!(submissions.len(), 5);
     assert!(submissions.iter().all(|s| s.user_id.as_str() == "u2"));
 
+    let conn = PgConnection::establish(utils::SQL_URL)?;
+    assert_eq!(conn.archive_submissions(3)?, 3);
+    let submissions: Vec<Submission> = surf::get(url("/atcoder-api/results?user=u1", port))
+        .await?
+        .body_json()
+        .await?;
+    assert_eq!(submissions.len(), 2);
+    let submissions: Vec<Submission> = surf::get(url(
+        "/atcoder-api/results?user=u1&include_archived=true",
+        port,
+    ))
+    .await?
+    .body_json()
+    .await?;
+    assert_eq!(submissions.len(), 5);
+
     server.race(ready(())).await;
     Ok(())
 }
@@ -235,3 +252,82 @@ async fn test_users_and_time() -> Result<()> {
     server.race(ready(())).await;
     Ok(())
 }
+
+#[async_std::test]
+async fn test_submission_stream() -> Result<()> {
+    use serde_json::Value;
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let mut ids = vec![];
+    let mut cursor: Option<String> = None;
+    loop {
+        let path = match &cursor {
+            Some(cursor) => format!(
+                "/atcoder-api/v3/submissions/stream?count=3&cursor={}",
+                cursor
+            ),
+            None => "/atcoder-api/v3/submissions/stream?count=3".to_owned(),
+        };
+        let batch = surf::get(url(&path, port)).recv_json::<Value>().await?;
+        let submissions = batch["submissions"].as_array().unwrap();
+        let next = batch["cursor"].as_str().unwrap().to_owned();
+        if submissions.is_empty() {
+            assert_eq!(cursor.as_deref(), Some(next.as_str()));
+            break;
+        }
+        assert!(submissions.len() <= 3);
+        ids.extend(submissions.iter().map(|s| s["id"].as_i64().unwrap()));
+        cursor = Some(next);
+    }
+    assert_eq!(ids, vec![0, 1, 2, 3, 100, 4, 5, 6, 7, 200]);
+
+    // A rejudged submission comes again after the cursor, but an unchanged one does not.
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    let submission = |result: &str| Submission {
+        id: 2,
+        epoch_second: 2,
+        problem_id: "p1".to_owned(),
+        contest_id: "c1".to_owned(),
+        user_id: "u1".to_owned(),
+        result: result.to_owned(),
+        ..Default::default()
+    };
+    conn.update_submissions(&[
+        submission("WA"),
+        Submission {
+            id: 3,
+            ..submission("AC")
+        },
+    ])
+    .unwrap();
+    let cursor = cursor.unwrap();
+    let batch = surf::get(url(
+        &format!("/atcoder-api/v3/submissions/stream?cursor={}", cursor),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    let submissions = batch["submissions"].as_array().unwrap();
+    assert_eq!(submissions.len(), 1);
+    assert_eq!(submissions[0]["id"], 2);
+    assert_eq!(submissions[0]["result"], "WA");
+    assert_ne!(batch["cursor"].as_str().unwrap(), cursor);
+
+    let response = surf::get(url(
+        "/atcoder-api/v3/submissions/stream?cursor=invalid",
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 400);
+    let response = surf::get(url("/atcoder-api/v3/submissions/stream?count=0", port)).await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use diesel::connection::SimpleConnection;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+#[async_std::test]
+async fn test_user_comparison() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let port = rng.gen::<u16>() % 30000 + 30000;
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.batch_execute(
+        r"
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('abc001_a', 'abc001', 'A'),
+            ('abc001_b', 'abc001', 'B'),
+            ('abc001_c', 'abc001', 'C'),
+            ('abc001_d', 'abc001', 'D');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('abc001_a', 100.0, FALSE),
+            ('abc001_b', 500.0, FALSE),
+            ('abc001_c', 900.0, FALSE);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'abc001_a', 'abc001', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (3, 300, 'abc001_b', 'abc001', 'user1', 'Rust', 200.0, 20, 'AC'),
+            (4, 400, 'abc001_a', 'abc001', 'user2', 'Rust', 100.0, 20, 'AC'),
+            (5, 500, 'abc001_c', 'abc001', 'user2', 'Rust', 0.0, 20, 'WA');
+        ",
+    )
+    .unwrap();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let comparison = surf::get(url("/atcoder-api/v3/compare?users=user1,user2", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        comparison,
+        json!({
+            "users": [
+                {"user_id": "user1", "accepted_count": 2, "only_solved": ["abc001_b"]},
+                {"user_id": "user2", "accepted_count": 1, "only_solved": []}
+            ],
+            "difficulty_buckets": [
+                {"difficulty_from": 0, "difficulty_to": 400, "solved_counts": {"user1": 1, "user2": 1}},
+                {"difficulty_from": 400, "difficulty_to": 800, "solved_counts": {"user1": 1, "user2": 0}},
+                {"difficulty_from": 800, "difficulty_to": 1200, "solved_counts": {"user1": 0, "user2": 0}}
+            ],
+            "unsolved_by_all": ["abc001_c", "abc001_d"]
+        })
+    );
+
+    let comparison = surf::get(url("/atcoder-api/v3/compare?users=user2,,user2", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(comparison["users"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        comparison["unsolved_by_all"],
+        json!(["abc001_b", "abc001_c", "abc001_d"])
+    );
+
+    let response = surf::get(url("/atcoder-api/v3/compare?users=,", port)).await?;
+    assert_eq!(response.status(), 400);
+    let users = (0..11)
+        .map(|i| format!("user{}", i))
+        .collect::<Vec<_>>()
+        .join(",");
+    let response = surf::get(url(
+        &format!("/atcoder-api/v3/compare?users={}", users),
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 400);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_user_group() -> Result<()> {
+    use chrono::Utc;
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    let now = Utc::now().timestamp();
+    conn.batch_execute(&format!(
+        r"
+            INSERT INTO accepted_count (user_id, problem_count) VALUES
+            ('member_1', 10), ('member_2', 20), ('other_user', 30);
+            INSERT INTO rated_point_sum (user_id, point_sum) VALUES ('member_1', 300.0);
+            INSERT INTO max_streaks (user_id, streak) VALUES ('member_1', 5), ('member_2', 5);
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 100, 'problem_1', '', 'member_1', '', 100, 0, 'AC'),
+            (2, {recent}, 'problem_1', '', 'member_1', '', 100, 0, 'AC'),
+            (3, {recent}, 'problem_2', '', 'member_1', '', 100, 0, 'AC'),
+            (4, {recent}, 'problem_1', '', 'member_2', '', 100, 0, 'WA');
+        ",
+        recent = now - 3600,
+    ))
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/group/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "group_name": "club" }))?
+        .recv_json::<Value>()
+        .await?;
+    let group_id = response["internal_group_id"].as_str().unwrap().to_owned();
+    let response = surf::post(url("/internal-api/group/member/add", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "internal_group_id": group_id,
+            "user_ids": ["member_2", " member_1 ", "member_3"]
+        }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let response = surf::post(url("/internal-api/group/member/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_group_id": group_id, "user_id": "member_3" }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let groups = surf::get(url("/internal-api/group/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(
+        groups,
+        json!([{
+            "internal_group_id": group_id,
+            "group_name": "club",
+            "members": ["member_1", "member_2"]
+        }])
+    );
+
+    let ranking = |kind: &str| {
+        surf::get(url(
+            &format!(
+                "/internal-api/group_ranking/{}?internal_group_id={}",
+                kind, group_id
+            ),
+            port,
+        ))
+        .recv_json::<Value>()
+    };
+    let ac = ranking("ac").await?;
+    assert_eq!(ac[0]["user_id"], "member_2");
+    assert_eq!(ac[0]["accepted_count"], 20);
+    assert_eq!(ac[0]["rank"], 0);
+    assert_eq!(ac[1]["user_id"], "member_1");
+    assert_eq!(ac[1]["rank"], 1);
+    assert_eq!(ac.as_array().unwrap().len(), 2);
+
+    let points = ranking("points").await?;
+    assert_eq!(points[0]["user_id"], "member_1");
+    assert_eq!(points[1]["rated_point_sum"], 0.0);
+
+    let streak = ranking("streak").await?;
+    assert_eq!(streak[0]["rank"], 0);
+    assert_eq!(streak[1]["rank"], 0);
+
+    // Only the problem newly solved in the week is counted.
+    let weekly = ranking("weekly").await?;
+    assert_eq!(weekly[0]["user_id"], "member_1");
+    assert_eq!(weekly[0]["weekly_accepted_count"], 1);
+    assert_eq!(weekly[1]["weekly_accepted_count"], 0);
+
+    // The banned members are not ranked, and do not push down the others.
+    conn.batch_execute(
+        "INSERT INTO internal_banned_users (user_id, banned_epoch_second) VALUES ('member_2', 0);",
+    )
+    .unwrap();
+    let ac = ranking("ac").await?;
+    assert_eq!(ac[0]["user_id"], "member_1");
+    assert_eq!(ac[0]["rank"], 0);
+    assert_eq!(ac.as_array().unwrap().len(), 1);
+
+    let response = surf::get(url(
+        &format!(
+            "/internal-api/group_ranking/unknown?internal_group_id={}",
+            group_id
+        ),
+        port,
+    ))
+    .await?;
+    assert_eq!(response.status(), 400);
+
+    let response = surf::post(url("/internal-api/group/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "internal_group_id": group_id }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    assert_eq!(ranking("ac").await?, json!([]));
+    let groups = surf::get(url("/internal-api/group/my", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(groups, json!([]));
+
+    server.race(ready(())).await;
+    Ok(())
+}
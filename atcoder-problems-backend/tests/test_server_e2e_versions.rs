@@ -0,0 +1,110 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use atcoder_problems_backend::sql::TableVersionClient;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, _: &str) -> Result<String> {
+        unimplemented!()
+    }
+    async fn get_user_id(&self, _: &str) -> Result<GitHubUserResponse> {
+        unimplemented!()
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_versions() -> Result<()> {
+    let conn = utils::initialize_and_connect_to_test_sql();
+    conn.bump_table_versions(&["accepted_count", "points"], 100)
+        .unwrap();
+    conn.bump_table_versions(&["points"], 200).unwrap();
+
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let expected = json!([
+        {"name": "accepted_count", "version": 1, "updated_epoch_second": 100},
+        {"name": "points", "version": 2, "updated_epoch_second": 200}
+    ]);
+    let response = surf::get(url("/atcoder-api/v3/versions", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response, expected);
+
+    let mut response = surf::get(url("/atcoder-api/v3/versions/stream", port)).await?;
+    assert_eq!(
+        response.header("content-type").unwrap(),
+        "text/event-stream"
+    );
+    let mut buf = vec![0; 1024];
+    let mut body = String::new();
+    while !body.ends_with("\n\n") {
+        let size = response.read(&mut buf).await?;
+        body.push_str(std::str::from_utf8(&buf[..size])?);
+    }
+    let lines = body.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "event:versions");
+    let versions = serde_json::from_str::<Value>(lines[1].trim_start_matches("data:"))?;
+    assert_eq!(versions, expected, "The first event contains all versions.");
+
+    server.race(ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_user_info_etag() -> Result<()> {
+    let conn = utils::initialize_and_connect_to_test_sql();
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+
+    let path = "/atcoder-api/v2/user_info?user=u1";
+    let response = surf::get(url(path, port)).await?;
+    assert!(response.status().is_success());
+    let tag = response.header("etag").unwrap().to_owned();
+    assert_eq!(tag, "\"0-0\"");
+
+    let response = surf::get(url(path, port))
+        .set_header("if-none-match", tag.as_str())
+        .await?;
+    assert_eq!(response.status(), 304);
+
+    conn.bump_table_versions(&["accepted_count"], 100).unwrap();
+    let response = surf::get(url(path, port))
+        .set_header("if-none-match", tag.as_str())
+        .await?;
+    assert!(response.status().is_success());
+    assert_eq!(response.header("etag").unwrap(), "\"1-0\"");
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -332,3 +332,646 @@ async fn test_virtual_contest_visibility() -> Result<()> {
     server.race(async_std::future::ready(())).await;
     Ok(())
 }
+
+#[async_std::test]
+async fn test_virtual_contest_team() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 110, 'problem_1', '', 'user_a', '', 100, 0, 'WA'),
+            (2, 120, 'problem_1', '', 'user_b', '', 100, 0, 'AC'),
+            (3, 130, 'problem_2', '', 'user_c', '', 200, 0, 'AC'),
+            (4, 140, 'problem_1', '', 'user_c', '', 100, 0, 'AC'),
+            (5, 300, 'problem_2', '', 'user_a', '', 200, 0, 'AC');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title":"team contest",
+            "memo": "",
+            "start_epoch_second": 100,
+            "duration_second": 100
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let contest_id = body["contest_id"].as_str().unwrap();
+
+    let response = surf::post(url("/internal-api/contest/item/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "problems": [{"id":"problem_1"}, {"id": "problem_2", "point": 500}],
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let mut response = surf::post(url("/internal-api/contest/team/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_name": "team A",
+            "members": ["user_a", "user_b"]
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let team_a = body["team_id"].as_str().unwrap().to_owned();
+
+    let mut response = surf::post(url("/internal-api/contest/team/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_name": "team C",
+            "members": ["user_x"]
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let team_c = body["team_id"].as_str().unwrap().to_owned();
+
+    let response = surf::post(url("/internal-api/contest/team/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_id": team_c,
+            "team_name": "team C",
+            "members": ["user_c"]
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/team/create", port))
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_name": "no auth",
+            "members": []
+        }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/team/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_name": "a".repeat(256),
+            "members": []
+        }))?
+        .await?;
+    assert_eq!(response.status(), 400);
+
+    // A user can not be a member of two teams of the contest.
+    let response = surf::post(url("/internal-api/contest/team/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_name": "team D",
+            "members": ["user_a"]
+        }))?
+        .await?;
+    assert_eq!(response.status(), 409);
+    let response = surf::post(url("/internal-api/contest/team/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_id": team_c,
+            "team_name": "team C",
+            "members": ["user_c", "user_b"]
+        }))?
+        .await?;
+    assert_eq!(response.status(), 409);
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/team/list/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response.as_array().unwrap().len(), 2);
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/team/standings/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response.as_array().unwrap().len(), 2);
+    assert_eq!(response[0]["id"].as_str().unwrap(), team_c);
+    assert_eq!(response[0]["score"], json!(600.0));
+    assert_eq!(response[0]["penalty_second"], json!(40));
+    assert_eq!(response[1]["id"].as_str().unwrap(), team_a);
+    assert_eq!(response[1]["score"], json!(100.0));
+    assert_eq!(response[1]["penalty_second"], json!(320));
+    assert_eq!(
+        response[1]["problems"]["problem_1"],
+        json!({
+            "accepted": true,
+            "penalties": 1,
+            "point": 100.0,
+            "elapsed_second": 20,
+            "solver": "user_b"
+        })
+    );
+
+    let response = surf::post(url("/internal-api/contest/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "id": contest_id,
+            "title":"team contest",
+            "memo": "",
+            "start_epoch_second": 100,
+            "duration_second": 100,
+            "scoring": "unknown"
+        }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "id": contest_id,
+            "title":"team contest",
+            "memo": "",
+            "start_epoch_second": 100,
+            "duration_second": 100,
+            "scoring": "icpc"
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/team/standings/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response[0]["id"].as_str().unwrap(), team_c);
+    assert_eq!(response[0]["score"], json!(2.0));
+    assert_eq!(response[0]["penalty_second"], json!(70));
+    assert_eq!(response[1]["id"].as_str().unwrap(), team_a);
+    assert_eq!(response[1]["score"], json!(1.0));
+    assert_eq!(response[1]["penalty_second"], json!(20 + 1200));
+
+    let mut response = surf::get(url(
+        &format!("/internal-api/contest/team/standings/{}/stream", contest_id),
+        port,
+    ))
+    .await?;
+    assert!(response.status().is_success());
+    let body = response.body_string().await?;
+    let lines = body.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "event:standings");
+    let rows = serde_json::from_str::<Value>(lines[1].trim_start_matches("data:"))?;
+    assert_eq!(rows.as_array().unwrap().len(), 2);
+    assert_eq!(rows[0]["id"].as_str().unwrap(), team_c);
+
+    let response = surf::post(url("/internal-api/contest/team/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_id": team_a,
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/team/list/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(
+        response,
+        json!([{"id": team_c, "team_name": "team C", "members": ["user_c"]}])
+    );
+
+    let response = surf::post(url("/internal-api/user/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "atcoder_user_id": "user_a" }))?
+        .await?;
+    assert!(response.status().is_success());
+    let response = surf::post(url("/internal-api/contest/join", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .await?;
+    assert!(response.status().is_success());
+    let response = surf::post(url("/internal-api/contest/team/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "team_id": team_c,
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/team/standings/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response.as_array().unwrap().len(), 1);
+    assert_eq!(response[0]["id"], json!("user_a"));
+    assert_eq!(response[0]["members"], json!(["user_a"]));
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_private_virtual_contest_invite_token() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO internal_users (internal_user_id) VALUES ('other_owner');
+            INSERT INTO internal_virtual_contests (id, internal_user_id, start_epoch_second, duration_second, is_public, invite_only, invite_token)
+            VALUES ('other_contest', 'other_owner', 1, 2, FALSE, TRUE, 'other_token');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/contest/join", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": "other_contest" }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/join", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": "other_contest", "invite_token": "wrong_token" }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::get(url(
+        "/internal-api/contest/team/standings/other_contest",
+        port,
+    ))
+    .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::get(url(
+        "/internal-api/contest/team/standings/other_contest?invite_token=other_token",
+        port,
+    ))
+    .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/invite/generate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": "other_contest" }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/join", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": "other_contest", "invite_token": "other_token" }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title":"private",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2,
+            "is_public": false,
+            "invite_only": true
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let contest_id = body["contest_id"].as_str().unwrap();
+
+    let response = surf::post(url("/internal-api/contest/invite/generate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .recv_json::<Value>()
+        .await?;
+    let invite_token = response["invite_token"].as_str().unwrap().to_owned();
+
+    let response = surf::post(url("/internal-api/contest/invite/generate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response["invite_token"].as_str().unwrap(), invite_token);
+
+    let response = surf::get(url(
+        &format!(
+            "/internal-api/contest/team/standings/{}?invite_token={}",
+            contest_id, invite_token
+        ),
+        port,
+    ))
+    .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/invite/rotate", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .recv_json::<Value>()
+        .await?;
+    let rotated_token = response["invite_token"].as_str().unwrap().to_owned();
+    assert_ne!(rotated_token, invite_token);
+
+    let response = surf::get(url(
+        &format!(
+            "/internal-api/contest/team/standings/{}?invite_token={}",
+            contest_id, invite_token
+        ),
+        port,
+    ))
+    .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/invite/revoke", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!(
+            "/internal-api/contest/team/standings/{}?invite_token={}",
+            contest_id, rotated_token
+        ),
+        port,
+    ))
+    .await?;
+    assert!(!response.status().is_success());
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_virtual_contest_announcement() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title":"contest",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let contest_id = body["contest_id"].as_str().unwrap();
+
+    let response = surf::post(url("/internal-api/contest/announcement/post", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id, "message": "" }))?
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/announcement/post", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id, "message": "Problem A is fixed." }))?
+        .recv_json::<Value>()
+        .await?;
+    let announcement_id = response["announcement_id"].as_str().unwrap().to_owned();
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/announcement/list/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response.as_array().unwrap().len(), 1);
+    assert_eq!(response[0]["id"].as_str().unwrap(), announcement_id);
+    assert_eq!(response[0]["message"], "Problem A is fixed.");
+
+    let response = surf::post(url("/internal-api/contest/announcement/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id, "announcement_id": announcement_id }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/announcement/list/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response, json!([]));
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_clone_virtual_contest() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO internal_users (internal_user_id) VALUES ('other_owner');
+            INSERT INTO internal_virtual_contests (id, title, memo, internal_user_id, start_epoch_second, duration_second, mode, is_public, invite_only, scoring)
+            VALUES
+            ('weekly', 'weekly contest', 'memo', 'other_owner', 100, 6000, 'lockout', TRUE, FALSE, 'icpc'),
+            ('private', 'private contest', '', 'other_owner', 100, 6000, NULL, FALSE, TRUE, NULL);
+            INSERT INTO internal_virtual_contest_items (problem_id, internal_virtual_contest_id, user_defined_point, user_defined_order)
+            VALUES ('problem_1', 'weekly', 100, 0), ('problem_2', 'weekly', NULL, 1);
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/contest/clone?id=private", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert!(!response.status().is_success());
+
+    let response = surf::get(url("/internal-api/contest/get/private", port)).await?;
+    assert_eq!(response.status(), StatusCode::NotFound);
+    let response = surf::get(url("/internal-api/contest/get/private", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .await?;
+    assert_eq!(response.status(), StatusCode::NotFound);
+    let response = surf::get(url("/internal-api/contest/team/list/private", port)).await?;
+    assert_eq!(response.status(), StatusCode::NotFound);
+    let response = surf::get(url("/internal-api/contest/get/weekly", port)).await?;
+    assert!(response.status().is_success());
+
+    let response = surf::post(url("/internal-api/contest/clone?id=weekly", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    let contest_id = response["contest_id"].as_str().unwrap().to_owned();
+    assert_ne!(contest_id, "weekly");
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/get/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(
+        response,
+        json!({
+            "owner_user_id": "0",
+            "duration_second": 6000,
+            "start_epoch_second": 100,
+            "memo": "memo",
+            "title": "weekly contest",
+            "id": contest_id,
+            "participants": [],
+            "problems": [{"id":"problem_1", "point":100, "order":0}, {"id": "problem_2", "point":null, "order":1}],
+            "mode": "lockout",
+        })
+    );
+
+    let response = surf::get(url("/internal-api/contest/recent", port))
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(response.as_array().unwrap().len(), 1);
+    assert_eq!(response[0]["id"], "weekly");
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_virtual_contest_validation() -> Result<()> {
+    let port = setup();
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title": "",
+            "memo": "a".repeat(256),
+            "start_epoch_second": 1,
+            "duration_second": -1
+        }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::BadRequest);
+    let body = response.body_json::<Value>().await?;
+    assert_eq!(body["kind"], "validation");
+    let fields = body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["field"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(fields, vec!["title", "memo", "duration_second"]);
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "title": "contest title" }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::BadRequest);
+    let body = response.body_json::<Value>().await?;
+    assert_eq!(body["fields"], json!([]));
+
+    let response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_string(format!(r#"{{"title": "{}"}}"#, "a".repeat(2 * 1024 * 1024)))
+        .set_header("Content-Type", "application/json")
+        .await?;
+    assert_eq!(response.status(), StatusCode::PayloadTooLarge);
+
+    let mut response = surf::get(url("/internal-api/contest/get/unknown", port)).await?;
+    assert_eq!(response.status(), StatusCode::NotFound);
+    let body = response.body_json::<Value>().await?;
+    assert_eq!(body["kind"], "not_found");
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .body_json(&json!({
+            "title": "contest title",
+            "memo": "",
+            "start_epoch_second": 1,
+            "duration_second": 2
+        }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::Unauthorized);
+    let body = response.body_json::<Value>().await?;
+    assert_eq!(body["kind"], "unauthorized");
+
+    let problems = (0..101)
+        .map(|i| json!({"id": format!("problem_{}", i), "point": null, "order": null}))
+        .collect::<Vec<_>>();
+    let response = surf::post(url("/internal-api/contest/item/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": "unknown", "problems": problems }))?
+        .await?;
+    assert_eq!(response.status(), StatusCode::BadRequest);
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
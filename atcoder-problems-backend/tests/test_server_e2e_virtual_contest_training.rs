@@ -0,0 +1,180 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_training_virtual_contest() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 150, 'problem_1', '', 'training_user', '', 100, 0, 'AC'),
+            (2, 120, 'problem_2', '', 'training_user', '', 100, 0, 'AC'),
+            (3, 200, 'problem_2', '', 'training_user', '', 100, 0, 'WA'),
+            (4, 300, 'problem_2', '', 'training_user', '', 100, 0, 'AC'),
+            (5, 400, 'problem_3', '', 'other_user', '', 100, 0, 'AC');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    let response = surf::post(url("/internal-api/user/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "atcoder_user_id": "training_user" }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title":"training",
+            "memo": "",
+            "start_epoch_second": 100,
+            "duration_second": 0,
+            "mode": "training"
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+    let body = response.body_json::<Value>().await?;
+    let contest_id = body["contest_id"].as_str().unwrap().to_owned();
+
+    let response = surf::post(url("/internal-api/contest/item/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "contest_id": contest_id,
+            "problems": [{"id":"problem_3", "order": 2}, {"id":"problem_1", "order": 0}, {"id": "problem_2", "order": 1}],
+        }))?
+        .await?;
+    assert!(response.status().is_success());
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/training/progress/{}", contest_id),
+        port,
+    ))
+    .set_header("Cookie", cookie_header.as_str())
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response, json!({"solved": [], "unlocked": ["problem_1"]}));
+
+    let response = surf::post(url("/internal-api/contest/training/progress/update", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "contest_id": contest_id }))?
+        .recv_json::<Value>()
+        .await?;
+    let expected = json!({
+        "solved": [
+            {"problem_id": "problem_1", "solved_epoch_second": 150},
+            {"problem_id": "problem_2", "solved_epoch_second": 300}
+        ],
+        "unlocked": ["problem_1", "problem_2", "problem_3"]
+    });
+    assert_eq!(response, expected);
+
+    let response = surf::get(url(
+        &format!("/internal-api/contest/training/progress/{}", contest_id),
+        port,
+    ))
+    .set_header("Cookie", cookie_header.as_str())
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response, expected);
+
+    // The locked problems are hidden from anyone but the owner.
+    let response = surf::get(url(
+        &format!("/internal-api/contest/get/{}", contest_id),
+        port,
+    ))
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response["problems"].as_array().unwrap().len(), 1);
+    assert_eq!(response["problems"][0]["id"], "problem_1");
+    let response = surf::get(url(
+        &format!("/internal-api/contest/get/{}", contest_id),
+        port,
+    ))
+    .set_header("Cookie", cookie_header.as_str())
+    .recv_json::<Value>()
+    .await?;
+    assert_eq!(response["problems"].as_array().unwrap().len(), 3);
+
+    let mut response = surf::post(url("/internal-api/contest/create", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({
+            "title":"normal",
+            "memo": "",
+            "start_epoch_second": 100,
+            "duration_second": 100
+        }))?
+        .await?;
+    let body = response.body_json::<Value>().await?;
+    let normal_contest_id = body["contest_id"].as_str().unwrap().to_owned();
+    let response = surf::get(url(
+        &format!(
+            "/internal-api/contest/training/progress/{}",
+            normal_contest_id
+        ),
+        port,
+    ))
+    .set_header("Cookie", cookie_header.as_str())
+    .await?;
+    assert!(!response.status().is_success());
+
+    server.race(async_std::future::ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use atcoder_problems_backend::error::Result;
+use atcoder_problems_backend::server::{initialize_pool, run_server, Authentication};
+
+use async_std::future::ready;
+use async_std::prelude::*;
+use async_std::task;
+use async_trait::async_trait;
+use atcoder_problems_backend::server::GitHubUserResponse;
+use http_types::StatusCode;
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub mod utils;
+
+#[derive(Clone)]
+struct MockAuth;
+
+const VALID_CODE: &str = "VALID-CODE";
+const VALID_TOKEN: &str = "VALID-TOKEN";
+
+#[async_trait]
+impl Authentication for MockAuth {
+    async fn get_token(&self, code: &str) -> Result<String> {
+        match code {
+            VALID_CODE => Ok(VALID_TOKEN.to_owned()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+    async fn get_user_id(&self, token: &str) -> Result<GitHubUserResponse> {
+        match token {
+            VALID_TOKEN => Ok(GitHubUserResponse::default()),
+            _ => Err(http_types::Error::from_str(StatusCode::Forbidden, "error")),
+        }
+    }
+}
+
+fn url(path: &str, port: u16) -> String {
+    format!("http://localhost:{}{}", port, path)
+}
+
+fn setup() -> u16 {
+    utils::initialize_and_connect_to_test_sql();
+    let mut rng = rand::thread_rng();
+    rng.gen::<u16>() % 30000 + 30000
+}
+
+#[async_std::test]
+async fn test_watch_list() -> Result<()> {
+    use diesel::connection::SimpleConnection;
+    use diesel::{Connection, PgConnection};
+
+    let port = setup();
+    let conn = PgConnection::establish(utils::SQL_URL).unwrap();
+    conn.batch_execute(
+        r#"
+            INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+            (1, 100, 'problem_1', '', 'rival_1', '', 100, 0, 'AC'),
+            (2, 200, 'problem_2', '', 'rival_1', '', 100, 0, 'WA'),
+            (3, 300, 'problem_2', '', 'rival_2', '', 100, 0, 'AC'),
+            (4, 400, 'problem_3', '', 'other_user', '', 100, 0, 'AC');
+        "#,
+    )
+    .unwrap();
+
+    let server = task::spawn(async move {
+        let pool = initialize_pool(utils::SQL_URL).unwrap();
+        run_server(pool, MockAuth, port).await.unwrap();
+    });
+    task::sleep(std::time::Duration::from_millis(1000)).await;
+    surf::get(url(
+        &format!("/internal-api/authorize?code={}", VALID_CODE),
+        port,
+    ))
+    .await?;
+    let cookie_header = format!("token={}", VALID_TOKEN);
+
+    for user_id in ["rival_2", "rival_1", "rival_1"].iter() {
+        let response = surf::post(url("/internal-api/watch/add", port))
+            .set_header("Cookie", cookie_header.as_str())
+            .body_json(&json!({ "user_id": user_id }))?
+            .await?;
+        assert!(response.status().is_success(), "{:?}", response);
+    }
+    let users = surf::get(url("/internal-api/watch/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(users, json!(["rival_1", "rival_2"]));
+
+    let feed = surf::get(url("/internal-api/watch/feed", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    let ids = feed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["id"].as_i64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![3, 1]);
+
+    let feed = surf::get(url("/internal-api/watch/feed?count=1", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(feed.as_array().unwrap().len(), 1);
+
+    let response = surf::post(url("/internal-api/watch/delete", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .body_json(&json!({ "user_id": "rival_2" }))?
+        .await?;
+    assert!(response.status().is_success(), "{:?}", response);
+    let users = surf::get(url("/internal-api/watch/list", port))
+        .set_header("Cookie", cookie_header.as_str())
+        .recv_json::<Value>()
+        .await?;
+    assert_eq!(users, json!(["rival_1"]));
+
+    let response = surf::get(url("/internal-api/watch/feed", port)).await?;
+    assert!(!response.status().is_success(), "{:?}", response);
+
+    server.race(ready(())).await;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use atcoder_problems_backend::jobs::{run_update_step, UpdateStep};
+use atcoder_problems_backend::sql::{AcceptedCountClient, ShadowTableClient};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+
+mod utils;
+
+#[test]
+fn test_rebuild_in_shadow_tables() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 10), ('user2', 20);
+        CREATE VIEW accepted_users AS SELECT user_id FROM accepted_count;
+        ",
+    )
+    .unwrap();
+
+    let reader = PgConnection::establish(database.url()).unwrap();
+    conn.rebuild_in_shadow_tables(&["accepted_count"], || {
+        conn.batch_execute(
+            "INSERT INTO accepted_count (user_id, problem_count) VALUES ('user3', 1)",
+        )?;
+        assert_eq!(
+            conn.load_accepted_count().unwrap().len(),
+            1,
+            "The rebuild writes into the empty shadow table."
+        );
+        assert_eq!(
+            reader.load_accepted_count().unwrap().len(),
+            2,
+            "The readers see the live table during the rebuild."
+        );
+        Ok(())
+    })
+    .unwrap();
+    let count = reader.load_accepted_count().unwrap();
+    assert_eq!(count.len(), 1);
+    assert_eq!(count[0].user_id, "user3");
+    assert_eq!(reader.get_users_accepted_count("user3"), Some(1));
+    let accepted_users = diesel::sql_query("SELECT user_id FROM accepted_users")
+        .execute(&reader)
+        .unwrap();
+    assert_eq!(accepted_users, 1, "The views on the live table are kept.");
+
+    let result = conn.rebuild_in_shadow_tables(&["accepted_count"], || {
+        Err(http_types::Error::from_str(500, "failed"))
+    });
+    assert!(result.is_err());
+    assert_eq!(
+        conn.load_accepted_count().unwrap().len(),
+        1,
+        "The live table is kept if the rebuild fails."
+    );
+
+    assert!(conn
+        .rebuild_in_shadow_tables(&["accepted_count; DROP TABLE submissions"], || Ok(()))
+        .is_err());
+}
+
+#[test]
+fn test_rebuild_drops_stale_rows() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC');
+        INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 1), ('removed', 5);
+        ",
+    )
+    .unwrap();
+
+    run_update_step(&conn, UpdateStep::AcceptedCount).unwrap();
+    assert_eq!(conn.get_users_accepted_count("user1"), Some(2));
+    assert_eq!(conn.get_users_accepted_count("removed"), None);
+}
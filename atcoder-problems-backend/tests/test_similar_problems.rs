@@ -0,0 +1,56 @@
+use atcoder_problems_backend::sql::models::SimilarProblem;
+use atcoder_problems_backend::sql::SimilarProblemClient;
+
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+#[test]
+fn test_similar_problems() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    // user1 to user3 solve problem1 and problem2 on the same day, and problem3 a week later.
+    // user4 solves problem1 and problem3 on the same day.
+    conn.batch_execute(
+        r"
+        INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+        (1, 1000, 'problem1', '', 'user1', '', 100, 0, 'AC'),
+        (2, 2000, 'problem2', '', 'user1', '', 100, 0, 'AC'),
+        (3, 700000, 'problem3', '', 'user1', '', 100, 0, 'AC'),
+        (4, 1000, 'problem1', '', 'user2', '', 100, 0, 'AC'),
+        (5, 50000, 'problem2', '', 'user2', '', 100, 0, 'AC'),
+        (6, 700000, 'problem3', '', 'user2', '', 100, 0, 'AC'),
+        (7, 3000, 'problem2', '', 'user3', '', 100, 0, 'AC'),
+        (8, 4000, 'problem1', '', 'user3', '', 100, 0, 'AC'),
+        (9, 700000, 'problem3', '', 'user3', '', 100, 0, 'AC'),
+        (10, 1000, 'problem1', '', 'user4', '', 100, 0, 'AC'),
+        (11, 2000, 'problem3', '', 'user4', '', 100, 0, 'AC'),
+        (12, 100, 'problem3', '', 'user4', '', 100, 0, 'WA');
+        ",
+    )
+    .unwrap();
+
+    conn.update_similar_problems().unwrap();
+    assert_eq!(
+        conn.load_similar_problems("problem1").unwrap(),
+        vec![SimilarProblem {
+            problem_id: "problem1".to_owned(),
+            similar_problem_id: "problem2".to_owned(),
+            co_solver_count: 3,
+            score: 3.0 / 12.0f64.sqrt(),
+        }]
+    );
+    assert_eq!(
+        conn.load_similar_problems("problem2").unwrap()[0].similar_problem_id,
+        "problem1"
+    );
+    assert!(
+        conn.load_similar_problems("problem3").unwrap().is_empty(),
+        "The problems solved together by fewer than 3 users are not similar."
+    );
+
+    conn.batch_execute("DELETE FROM submissions WHERE user_id = 'user3'")
+        .unwrap();
+    conn.update_similar_problems().unwrap();
+    assert!(conn.load_similar_problems("problem1").unwrap().is_empty());
+}
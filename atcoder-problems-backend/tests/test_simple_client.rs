@@ -1,11 +1,12 @@
-use atcoder_problems_backend::sql::models::{Contest, Problem};
+use atcoder_problems_backend::sql::models::{Contest, ContestRatedRange, Problem, RatedRange};
 use atcoder_problems_backend::sql::SimpleClient;
 
 pub mod utils;
 
 #[test]
 fn test_insert_contests() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     assert!(conn.load_contests().unwrap().is_empty());
     conn.insert_contests(&vec![Contest {
         id: "contest1".to_string(),
@@ -31,7 +32,8 @@ fn test_insert_contests() {
 
 #[test]
 fn test_insert_problems() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     assert!(conn.load_problems().unwrap().is_empty());
     conn.insert_problems(&vec![Problem {
         id: "problem1".to_string(),
@@ -50,3 +52,62 @@ fn test_insert_problems() {
     }])
     .unwrap();
 }
+
+#[test]
+fn test_load_contest_rated_ranges() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let contest = |id: &str, start_epoch_second: i64, rate_change: &str| Contest {
+        id: id.to_string(),
+        start_epoch_second,
+        duration_second: 0,
+        title: "".to_string(),
+        rate_change: rate_change.to_string(),
+    };
+    let contests = vec![
+        contest("abc001", 1381579200, "-"),
+        contest("abc150", 1578150000, " ~ 1999"),
+        contest("agc041", 1577544000, "All"),
+        contest("arc104", 1601726400, " ~ 2799"),
+        contest("arc165", 1694952000, "1200 ~ 2799"),
+        contest("past201912-open", 1577577600, "-"),
+        contest("old", 0, "All"),
+    ];
+    conn.insert_contests(&contests).unwrap();
+
+    let ranges = conn.load_contest_rated_ranges().unwrap();
+    let range = |id: &str, rated, min_rating, max_rating| ContestRatedRange {
+        contest_id: id.to_string(),
+        rated,
+        min_rating,
+        max_rating,
+    };
+    assert_eq!(
+        ranges,
+        vec![
+            range("abc001", false, None, None),
+            range("abc150", true, None, Some(1999)),
+            range("agc041", true, None, None),
+            range("arc104", true, None, Some(2799)),
+            range("arc165", true, Some(1200), Some(2799)),
+            range("old", false, None, None),
+            range("past201912-open", false, None, None),
+        ]
+    );
+
+    // The database parses the rate changes in the same way as the backend.
+    let mut contests = contests;
+    contests.sort_by(|a, b| a.id.cmp(&b.id));
+    for (contest, range) in contests.iter().zip(ranges.iter()) {
+        assert_eq!(
+            contest.rated_range(),
+            Some(RatedRange {
+                min_rating: range.min_rating,
+                max_rating: range.max_rating,
+            })
+            .filter(|_| range.rated),
+            "{}",
+            contest.id
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use atcoder_problems_backend::sql::models::SiteStats;
+use atcoder_problems_backend::sql::SiteStatsClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+const DAY_SECOND: i64 = 24 * 3600;
+
+#[test]
+fn test_site_stats() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert_eq!(conn.load_site_stats().unwrap(), None);
+
+    let now = 100 * DAY_SECOND;
+    conn.batch_execute(&format!(
+        r"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, {week}, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, {month}, 'problem1', 'contest1', 'user2', 'Rust', 100.0, 20, 'WA'),
+            (3, {old}, 'problem1', 'contest1', 'user3', 'Rust', 100.0, 20, 'AC'),
+            (4, {week}, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC');
+        INSERT INTO submission_count (user_id, count) VALUES ('user1', 2), ('user2', 1), ('user3', 1);
+        INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 2), ('user3', 1);
+        INSERT INTO problems (id, contest_id, title) VALUES
+            ('problem1', 'contest1', ''),
+            ('problem2', 'contest1', ''),
+            ('problem3', 'contest1', '');
+        ",
+        week = now - DAY_SECOND,
+        month = now - 10 * DAY_SECOND,
+        old = now - 40 * DAY_SECOND,
+    ))
+    .unwrap();
+
+    conn.update_site_stats(now).unwrap();
+    let expected = SiteStats {
+        submission_count: 4,
+        accepted_count: 3,
+        weekly_active_user_count: 1,
+        monthly_active_user_count: 2,
+        problem_count: 3,
+        latest_submission_epoch_second: Some(now - DAY_SECOND),
+        updated_epoch_second: now,
+    };
+    assert_eq!(conn.load_site_stats().unwrap(), Some(expected.clone()));
+
+    // The statistics are replaced, not added.
+    conn.update_site_stats(now + 1).unwrap();
+    assert_eq!(
+        conn.load_site_stats().unwrap(),
+        Some(SiteStats {
+            updated_epoch_second: now + 1,
+            ..expected
+        })
+    );
+}
@@ -0,0 +1,66 @@
+use atcoder_problems_backend::sql::models::Standing;
+use atcoder_problems_backend::sql::StandingsClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+fn standing(contest_id: &str, user_id: &str, problem_id: &str, rank: i32, score: f64) -> Standing {
+    Standing {
+        contest_id: contest_id.to_owned(),
+        user_id: user_id.to_owned(),
+        problem_id: problem_id.to_owned(),
+        rank,
+        is_rated: true,
+        score,
+        penalty: 0,
+        elapsed_second: Some(100),
+    }
+}
+
+#[test]
+fn test_standings_client() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO contests (id, start_epoch_second, duration_second, title, rate_change) VALUES
+            ('abc001', 1000, 100, 'ABC 001', '-'),
+            ('abc002', 2000, 100, 'ABC 002', '-'),
+            ('abc003', 3000, 100, 'ABC 003', '-');
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        conn.get_contests_without_standings(2500, 10).unwrap(),
+        vec!["abc002".to_owned(), "abc001".to_owned()]
+    );
+    assert_eq!(
+        conn.get_contests_without_standings(2500, 1).unwrap(),
+        vec!["abc002".to_owned()]
+    );
+
+    conn.update_standings(&[
+        standing("abc001", "user2", "abc001_a", 2, 100.0),
+        standing("abc001", "user1", "abc001_a", 1, 100.0),
+        standing("abc001", "user1", "abc001_b", 1, 0.0),
+    ])
+    .unwrap();
+    conn.update_standings(&[standing("abc001", "user1", "abc001_b", 1, 200.0)])
+        .unwrap();
+
+    let standings = conn.load_standings("abc001").unwrap();
+    assert_eq!(
+        standings,
+        vec![
+            standing("abc001", "user1", "abc001_a", 1, 100.0),
+            standing("abc001", "user1", "abc001_b", 1, 200.0),
+            standing("abc001", "user2", "abc001_a", 2, 100.0),
+        ]
+    );
+    assert!(conn.load_standings("abc002").unwrap().is_empty());
+    assert_eq!(
+        conn.get_contests_without_standings(5000, 10).unwrap(),
+        vec!["abc003".to_owned(), "abc002".to_owned()]
+    );
+}
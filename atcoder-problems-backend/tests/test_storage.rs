@@ -0,0 +1,86 @@
+use atcoder_problems_backend::jobs::{backup_submissions, SUBMISSIONS_BACKUP_PATH};
+use atcoder_problems_backend::storage::{LocalStorage, Storage};
+use diesel::connection::SimpleConnection;
+use flate2::read::GzDecoder;
+use rand::Rng;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+mod utils;
+
+fn temporary_root() -> PathBuf {
+    let mut rng = rand::thread_rng();
+    std::env::temp_dir().join(format!("atcoder-problems-storage-{}", rng.gen::<u64>()))
+}
+
+#[test]
+fn test_local_storage() {
+    let root = temporary_root();
+    let storage = LocalStorage::new(&root, "http://localhost:8080/dumps/");
+    assert_eq!(
+        storage.public_url("/resources/ac.json"),
+        "http://localhost:8080/dumps/resources/ac.json"
+    );
+
+    assert_eq!(storage.get("/resources/ac.json").unwrap(), None);
+    assert!(storage
+        .update("/resources/ac.json", b"[]", "application/json")
+        .unwrap());
+    assert!(!storage
+        .update("/resources/ac.json", b"[]", "application/json")
+        .unwrap());
+    assert_eq!(
+        std::fs::read(root.join("resources/ac.json")).unwrap(),
+        b"[]"
+    );
+
+    let mut writer = storage.create("/backups/large.txt", "text/plain").unwrap();
+    writer.write_all(b"first ").unwrap();
+    writer.write_all(b"second").unwrap();
+    assert_eq!(
+        storage.get("/backups/large.txt").unwrap(),
+        None,
+        "The object is not visible until the writer is finished."
+    );
+    writer.finish().unwrap();
+    assert_eq!(
+        storage.get("/backups/large.txt").unwrap(),
+        Some(b"first second".to_vec())
+    );
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_backup_submissions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result, execution_time)
+        VALUES
+            (2, 200, 'problem1', 'contest1', 'user2', 'Rust, 1.42', 100.0, 30, 'WA', 10),
+            (1, 100, 'problem1', 'contest1', 'user1', 'C++', 100.0, 20, 'AC', NULL);
+    "#,
+    )
+    .unwrap();
+
+    let root = temporary_root();
+    let storage = LocalStorage::new(&root, "");
+    backup_submissions(&conn, &storage).unwrap();
+
+    let data = storage.get(SUBMISSIONS_BACKUP_PATH).unwrap().unwrap();
+    let mut csv = String::new();
+    GzDecoder::new(data.as_slice())
+        .read_to_string(&mut csv)
+        .unwrap();
+    assert_eq!(
+        csv,
+        "id,epoch_second,problem_id,contest_id,user_id,language,point,length,result,execution_time\n\
+         1,100,problem1,contest1,user1,C++,100,20,AC,\n\
+         2,200,problem1,contest1,user2,\"Rust, 1.42\",100,30,WA,10\n"
+    );
+
+    std::fs::remove_dir_all(root).unwrap();
+}
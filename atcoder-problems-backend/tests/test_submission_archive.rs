@@ -0,0 +1,67 @@
+use atcoder_problems_backend::jobs::archive_submissions;
+use atcoder_problems_backend::sql::{SubmissionArchiveClient, SubmissionClient, SubmissionRequest};
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+const YEAR_SECOND: i64 = 365 * 24 * 3600;
+
+#[test]
+fn test_archive_submissions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust (1.42.0)', 1.0, 1, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user1', 'Python3 (3.8.2)', 1.0, 1, 'WA'),
+            (3, 300, 'problem1', 'contest1', 'user2', 'Rust (1.42.0)', 1.0, 1, 'AC'),
+            (4, 5000, 'problem1', 'contest1', 'user1', 'Rust (1.42.0)', 1.0, 1, 'AC');
+    "#,
+    )
+    .unwrap();
+
+    let now = 1000 + 5 * YEAR_SECOND;
+    assert_eq!(archive_submissions(&conn, 5, now).unwrap(), 3);
+    assert_eq!(archive_submissions(&conn, 5, now).unwrap(), 0);
+
+    let submissions = conn
+        .get_submissions(SubmissionRequest::UserAll { user_id: "user1" })
+        .unwrap();
+    assert_eq!(submissions.len(), 1);
+    assert_eq!(submissions[0].id, 4);
+
+    let mut archived = conn
+        .get_user_archived_submissions("user1", None)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect::<Vec<_>>();
+    archived.sort();
+    assert_eq!(archived, vec![1, 2]);
+    let archived = conn
+        .get_user_archived_submissions("user1", Some("Rust"))
+        .unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].id, 1);
+    assert_eq!(archived[0].language, "Rust (1.42.0)");
+
+    // The submission crawled again is archived again with the new result.
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (2, 200, 'problem2', 'contest1', 'user1', 'Python3 (3.8.2)', 1.0, 1, 'AC');
+    "#,
+    )
+    .unwrap();
+    assert_eq!(archive_submissions(&conn, 5, now).unwrap(), 1);
+    let archived = conn
+        .get_user_archived_submissions("user1", Some("Python"))
+        .unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].result, "AC");
+}
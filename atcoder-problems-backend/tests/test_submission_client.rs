@@ -1,11 +1,15 @@
-use atcoder_problems_backend::sql::models::Submission;
-use atcoder_problems_backend::sql::{SubmissionClient, SubmissionRequest};
+use atcoder_problems_backend::sql::models::{Point, ResultCode, Submission, Verdict};
+use atcoder_problems_backend::sql::schema::result_codes;
+use atcoder_problems_backend::sql::{AsyncSubmissionClient, SubmissionClient, SubmissionRequest};
 use diesel::connection::SimpleConnection;
+use diesel::RunQueryDsl;
+use sqlx::PgPool;
 pub mod utils;
 
 #[test]
 fn test_submission_client() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     conn.batch_execute(
         r#"
         INSERT INTO submissions
@@ -82,6 +86,16 @@ fn test_submission_client() {
     let submissions = conn.get_submissions(request).unwrap();
     assert_eq!(submissions.len(), 2);
 
+    let request = SubmissionRequest::UsersRecentAccepted {
+        user_ids: &["user1", "user2"],
+        count: 2,
+    };
+    let submissions = conn.get_submissions(request).unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![4, 2]
+    );
+
     conn.update_submission_count().unwrap();
     assert_eq!(conn.get_user_submission_count("user1").unwrap(), 3);
     assert_eq!(conn.get_user_submission_count("user2").unwrap(), 1);
@@ -101,11 +115,185 @@ fn test_submission_client() {
     let request = SubmissionRequest::InvalidResult { from_second: 2 };
     let submissions = conn.get_submissions(request).unwrap();
     assert_eq!(submissions.len(), 1);
+
+    let request = SubmissionRequest::AcceptedAfterId {
+        from_id: 1,
+        count: 10,
+    };
+    let submissions = conn.get_submissions(request).unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![2, 4]
+    );
+
+    let request = SubmissionRequest::AfterId {
+        from_id: 1,
+        count: 2,
+    };
+    let submissions = conn.get_submissions(request).unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+
+    let request = SubmissionRequest::AfterTimeAndId {
+        epoch_second: 2,
+        id: 6,
+        count: 3,
+    };
+    let submissions = conn.get_submissions(request).unwrap();
+    assert_eq!(
+        submissions.iter().map(|s| s.id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    conn.batch_execute(
+        r#"
+        INSERT INTO shortest (contest_id, problem_id, submission_id)
+        VALUES ('contest1', 'problem1', 2), ('contest1', 'problem2', 5);
+    "#,
+    )
+    .unwrap();
+    let request = SubmissionRequest::Shortest {
+        problem_ids: &["problem1"],
+    };
+    let submissions = conn.get_submissions(request).unwrap();
+    assert_eq!(submissions.len(), 1);
+    assert_eq!(submissions[0].id, 2);
+}
+
+#[test]
+fn test_user_language_submissions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust (1.42.0)', 1.0, 1, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user1', 'Rust (1.15.1)', 1.0, 1, 'WA'),
+            (3, 300, 'problem1', 'contest1', 'user1', 'Python3 (3.8.2)', 1.0, 1, 'AC'),
+            (4, 400, 'problem1', 'contest1', 'user2', 'Rust (1.42.0)', 1.0, 1, 'AC'),
+            (5, 500, 'problem1', 'contest1', 'user1', 'Perl6 (Rakudo 2018.12)', 1.0, 1, 'AC');
+    "#,
+    )
+    .unwrap();
+
+    let get = |user_id, simplified_language| {
+        let mut ids = conn
+            .get_submissions(SubmissionRequest::UserLanguage {
+                user_id,
+                simplified_language,
+            })
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    assert_eq!(get("user1", "Rust"), vec![1, 2]);
+    assert_eq!(get("user1", "Python"), vec![3]);
+    assert_eq!(get("user1", "Perl6"), vec![5]);
+    assert_eq!(get("user2", "Rust"), vec![4]);
+    assert!(get("user2", "Python").is_empty());
+}
+
+#[async_std::test]
+async fn test_async_submission_client() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result, execution_time)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust (1.42.0)', 100.0, 1, 'AC', 10),
+            (2, 200, 'problem1', 'contest1', 'user2', 'C++', 100.0, 2, 'AC', NULL),
+            (3, 300, 'problem2', 'contest1', 'user1', 'Rust (1.15.1)', 12.34, 3, 'WA', 20),
+            (4, 300, 'problem2', 'contest1', 'user1', 'Python3 (3.8.2)', 0.0, 4, 'AC', 30),
+            (5, 500, 'problem3', 'contest1', 'user3', 'C++', 0.0, 5, '23/42 TLE', NULL);
+        INSERT INTO shortest (contest_id, problem_id, submission_id)
+        VALUES ('contest1', 'problem1', 1), ('contest1', 'problem2', 4);
+        INSERT INTO submission_count (user_id, count) VALUES ('user1', 3);
+    "#,
+    )
+    .unwrap();
+    let pool = PgPool::connect(database.url()).await.unwrap();
+
+    let requests = vec![
+        SubmissionRequest::UserAll { user_id: "user1" },
+        SubmissionRequest::UserLanguage {
+            user_id: "user1",
+            simplified_language: "Rust",
+        },
+        SubmissionRequest::UsersAccepted {
+            user_ids: &["user1", "user2"],
+        },
+        SubmissionRequest::FromTime {
+            from_second: 200,
+            count: 2,
+        },
+        SubmissionRequest::RecentAccepted { count: 2 },
+        SubmissionRequest::RecentAll { count: 3 },
+        SubmissionRequest::UsersRecentAccepted {
+            user_ids: &["user1"],
+            count: 1,
+        },
+        SubmissionRequest::AcceptedAfterId {
+            from_id: 1,
+            count: 10,
+        },
+        SubmissionRequest::AfterId {
+            from_id: 2,
+            count: 2,
+        },
+        SubmissionRequest::AfterTimeAndId {
+            epoch_second: 300,
+            id: 3,
+            count: 10,
+        },
+        SubmissionRequest::InvalidResult { from_second: 0 },
+        SubmissionRequest::AllAccepted,
+        SubmissionRequest::ByIds { ids: &[1, 3, 6] },
+        SubmissionRequest::Shortest {
+            problem_ids: &["problem2"],
+        },
+        SubmissionRequest::ShortestFromTime { from_second: 200 },
+        SubmissionRequest::UsersProblemsTime {
+            user_ids: &["user1", "user2"],
+            problem_ids: &["problem1", "problem2"],
+            from_second: 200,
+            to_second: 300,
+        },
+        SubmissionRequest::ProblemsTime {
+            problem_ids: &["problem2", "problem3"],
+            from_second: 0,
+            to_second: 400,
+        },
+    ];
+    for request in requests {
+        let description = format!("{:?}", request);
+        let expected = conn.get_submissions(request).unwrap();
+        let submissions = pool.get_submissions(request).await.unwrap();
+        assert!(!expected.is_empty(), "{}", description);
+        assert_eq!(
+            serde_json::to_value(&submissions).unwrap(),
+            serde_json::to_value(&expected).unwrap(),
+            "{}",
+            description
+        );
+    }
+
+    assert_eq!(pool.get_user_submission_count("user1").await.unwrap(), 3);
+    assert!(pool.get_user_submission_count("user2").await.is_err());
 }
 
 #[test]
 fn test_update_submission_count() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     conn.batch_execute(
         r#"
         INSERT INTO submissions
@@ -122,12 +310,13 @@ fn test_update_submission_count() {
 
 #[test]
 fn test_update_submissions() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     conn.update_submissions(&[Submission {
         id: 0,
         user_id: "old_user_name".to_owned(),
         result: "WJ".to_owned(),
-        point: 0.0,
+        point: Point::from(0.0),
         execution_time: None,
         ..Default::default()
     }])
@@ -141,7 +330,7 @@ fn test_update_submissions() {
     assert_eq!(submissions.len(), 1);
     assert_eq!(submissions[0].user_id, "old_user_name".to_owned());
     assert_eq!(submissions[0].result, "WJ".to_owned());
-    assert_eq!(submissions[0].point, 0.0);
+    assert_eq!(submissions[0].point, Point::from(0.0));
     assert_eq!(submissions[0].execution_time, None);
 
     let submissions = conn
@@ -155,7 +344,7 @@ fn test_update_submissions() {
         id: 0,
         user_id: "new_user_name".to_owned(),
         result: "AC".to_owned(),
-        point: 100.0,
+        point: Point::from(100.0),
         execution_time: Some(1),
         ..Default::default()
     }])
@@ -176,6 +365,78 @@ fn test_update_submissions() {
     assert_eq!(submissions.len(), 1);
     assert_eq!(submissions[0].user_id, "new_user_name".to_owned());
     assert_eq!(submissions[0].result, "AC".to_owned());
-    assert_eq!(submissions[0].point, 100.0);
+    assert_eq!(submissions[0].point, Point::from(100.0));
     assert_eq!(submissions[0].execution_time, Some(1));
 }
+
+#[test]
+fn test_update_many_submissions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let submissions = (0..10_000)
+        .map(|id| Submission {
+            id,
+            user_id: "user".to_owned(),
+            result: "AC".to_owned(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        conn.update_submissions(&submissions).unwrap(),
+        10_000,
+        "The submissions are inserted in the chunks under the limit of the bind parameters."
+    );
+    assert_eq!(conn.get_user_submission_count("user").unwrap_or(0), 0);
+    conn.update_submission_count().unwrap();
+    assert_eq!(conn.get_user_submission_count("user").unwrap(), 10_000);
+}
+
+#[test]
+fn test_result_codes() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    let mut stored = result_codes::table
+        .load::<ResultCode>(&conn)
+        .unwrap()
+        .into_iter()
+        .map(|code| (code.raw_result, code.verdict))
+        .collect::<Vec<_>>();
+    stored.sort();
+    let mut expected = Verdict::RESULT_CODES
+        .iter()
+        .map(|(raw_result, verdict)| (raw_result.to_string(), verdict.name().to_string()))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(
+        stored, expected,
+        "result_codes is the same as Verdict::RESULT_CODES."
+    );
+
+    assert_eq!(Verdict::from_result("AC"), Verdict::Accepted);
+    assert_eq!(Verdict::from_result("CE"), Verdict::CompileError);
+    assert_eq!(Verdict::from_result("WJ"), Verdict::Pending);
+    assert_eq!(Verdict::from_result("3/10"), Verdict::Pending);
+    assert!(!Verdict::from_result("WR").is_final());
+    assert!(Verdict::from_result("NG").is_final());
+
+    // A new raw result is accepted once it is mapped in `result_codes`.
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 1.0, 1, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user1', 'Rust', 1.0, 1, 'OK');
+    "#,
+    )
+    .unwrap();
+    let accepted = || {
+        conn.get_submissions(SubmissionRequest::AllAccepted)
+            .unwrap()
+            .len()
+    };
+    assert_eq!(accepted(), 1);
+    conn.batch_execute("INSERT INTO result_codes (raw_result, verdict) VALUES ('OK', 'accepted')")
+        .unwrap();
+    assert_eq!(accepted(), 2);
+}
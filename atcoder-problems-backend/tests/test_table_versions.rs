@@ -0,0 +1,48 @@
+use atcoder_problems_backend::jobs::{run_update_step, UpdateStep};
+use atcoder_problems_backend::sql::TableVersionClient;
+
+mod utils;
+
+#[test]
+fn test_table_versions() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert!(conn.load_table_versions().unwrap().is_empty());
+
+    conn.bump_table_versions(&["points", "accepted_count"], 100)
+        .unwrap();
+    conn.bump_table_versions(&["points"], 200).unwrap();
+    let versions = conn
+        .load_table_versions()
+        .unwrap()
+        .into_iter()
+        .map(|version| (version.name, version.version, version.updated_epoch_second))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        versions,
+        vec![
+            ("accepted_count".to_owned(), 1, 100),
+            ("points".to_owned(), 2, 200),
+        ]
+    );
+
+    run_update_step(&conn, UpdateStep::SubmissionsOfProblems).unwrap();
+    let names = conn
+        .load_table_versions()
+        .unwrap()
+        .into_iter()
+        .map(|version| version.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "accepted_count",
+            "fastest",
+            "first",
+            "first_practice",
+            "points",
+            "shortest"
+        ],
+        "The update steps bump the versions of the tables they update."
+    );
+}
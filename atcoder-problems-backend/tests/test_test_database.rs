@@ -0,0 +1,45 @@
+use atcoder_problems_backend::jobs::update_users;
+use atcoder_problems_backend::sql::{AcceptedCountClient, SimpleClient, SubmissionClient};
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, PgConnection};
+
+mod utils;
+
+#[test]
+fn test_test_database() {
+    let first = utils::TestDatabase::new();
+    let second = utils::TestDatabase::new();
+    assert_ne!(first.url(), second.url());
+
+    let conn = first.connect();
+    utils::seed_contest(&conn, "contest1", 0, 100, &["problem1", "problem2"]);
+    utils::seed_submissions(
+        &conn,
+        "contest1",
+        &[
+            (1, 10, "problem1", "user1", "AC"),
+            (2, 20, "problem2", "user1", "WA"),
+            (3, 30, "problem1", "user2", "AC"),
+        ],
+    );
+    conn.update_submission_count().unwrap();
+    update_users(&conn, &["user1"]).unwrap();
+    assert_eq!(conn.load_contests().unwrap().len(), 1);
+    assert_eq!(conn.load_problems().unwrap().len(), 2);
+    assert_eq!(conn.get_user_submission_count("user1").unwrap(), 2);
+    assert_eq!(conn.get_users_accepted_count("user1"), Some(1));
+
+    let conn = second.connect();
+    assert!(
+        conn.load_contests().unwrap().is_empty(),
+        "The databases do not share the rows."
+    );
+
+    let url = first.url().to_owned();
+    drop(first);
+    assert!(PgConnection::establish(&url).is_err());
+    assert!(
+        conn.batch_execute("SELECT 1;").is_ok(),
+        "Dropping a database does not affect the others."
+    );
+}
@@ -0,0 +1,77 @@
+use atcoder_problems_backend::jobs::{update_tier, UpdateTier};
+use atcoder_problems_backend::sql::models::Watermark;
+use atcoder_problems_backend::sql::{
+    AcceptedCountClient, JobRunClient, LanguageCountClient, WatermarkClient,
+};
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_update_tier() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 300, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (3, 200, 'problem1', 'contest1', 'user2', 'C++', 100.0, 30, 'AC');
+    "#,
+    )
+    .unwrap();
+
+    update_tier(&conn, UpdateTier::Fast).unwrap();
+    assert_eq!(conn.get_users_accepted_count("user1"), Some(2));
+    assert_eq!(conn.get_users_accepted_count("user2"), Some(1));
+    assert!(
+        conn.load_language_count().unwrap().is_empty(),
+        "The language counts are in the slow tier."
+    );
+
+    update_tier(&conn, UpdateTier::Slow).unwrap();
+    update_tier(&conn, UpdateTier::Slow).unwrap();
+    assert_eq!(conn.load_language_count().unwrap().len(), 2);
+
+    let stats = conn.load_update_tier_stats().unwrap();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].tier, "fast");
+    assert_eq!(stats[0].run_count, 1);
+    assert_eq!(stats[0].last_user_count, 2);
+    assert_eq!(stats[1].tier, "slow");
+    assert_eq!(stats[1].run_count, 2);
+    assert_eq!(
+        stats[1].last_user_count, 0,
+        "The second run starts from the watermark of the first one."
+    );
+    assert!(stats[1].total_duration_millis >= stats[1].last_duration_millis);
+    assert_eq!(
+        conn.load_watermark("update_tier:fast")
+            .unwrap()
+            .map(|w| w.last_submission_id),
+        Some(3)
+    );
+
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (4, 50, 'problem2', 'contest1', 'user2', 'C++', 100.0, 30, 'AC');
+    "#,
+    )
+    .unwrap();
+    update_tier(&conn, UpdateTier::Fast).unwrap();
+    assert_eq!(conn.get_users_accepted_count("user2"), Some(2));
+    let stats = conn.load_update_tier_stats().unwrap();
+    assert_eq!(stats[0].last_user_count, 1);
+    assert_eq!(
+        conn.load_watermark("update_tier:fast").unwrap(),
+        Some(Watermark {
+            last_epoch_second: 50,
+            last_submission_id: 4,
+        })
+    );
+}
@@ -0,0 +1,54 @@
+use atcoder_problems_backend::sql::internal::user_verification_manager::{
+    PendingVerification, UserVerificationManager,
+};
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+#[test]
+fn test_user_verification() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES
+        ('user_1', 'atcoder_1'),
+        ('user_2', NULL);
+    ",
+    )
+    .unwrap();
+
+    assert!(conn.start_verification("user_2", "code", 100).is_err());
+    assert_eq!(
+        conn.start_verification("user_1", "code_1", 100).unwrap(),
+        "atcoder_1"
+    );
+    assert_eq!(conn.get_verified_atcoder_user_id("user_1").unwrap(), None);
+
+    let expected = vec![PendingVerification {
+        internal_user_id: "user_1".to_owned(),
+        atcoder_user_id: "atcoder_1".to_owned(),
+        verification_code: "code_1".to_owned(),
+    }];
+    assert_eq!(conn.get_pending_verifications(100).unwrap(), expected);
+    assert!(conn.get_pending_verifications(101).unwrap().is_empty());
+
+    conn.complete_verification("user_1", "atcoder_1", 200)
+        .unwrap();
+    assert!(conn.get_pending_verifications(0).unwrap().is_empty());
+    assert_eq!(
+        conn.get_verified_atcoder_user_id("user_1").unwrap(),
+        Some("atcoder_1".to_owned())
+    );
+
+    // changing the AtCoder user id invalidates the verification.
+    conn.batch_execute(
+        "UPDATE internal_users SET atcoder_user_id = 'atcoder_x' WHERE internal_user_id = 'user_1'",
+    )
+    .unwrap();
+    assert_eq!(conn.get_verified_atcoder_user_id("user_1").unwrap(), None);
+
+    // a new verification replaces the completed one.
+    conn.start_verification("user_1", "code_2", 300).unwrap();
+    assert_eq!(conn.get_pending_verifications(0).unwrap().len(), 1);
+}
@@ -0,0 +1,77 @@
+use atcoder_problems_backend::jobs::validate;
+use atcoder_problems_backend::sql::ValidationClient;
+use diesel::connection::SimpleConnection;
+
+mod utils;
+
+#[test]
+fn test_validate() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r#"
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 100, 'problem1', 'contest1', 'user1', 'Rust', 100.0, 20, 'AC'),
+            (2, 200, 'problem2', 'contest1', 'user1', 'Rust', 100.0, 20, 'WA'),
+            (3, 300, 'problem1', 'contest1', 'user2', 'Rust', 100.0, 20, 'AC');
+        INSERT INTO accepted_count (user_id, problem_count) VALUES ('user1', 1), ('user3', 1);
+        INSERT INTO first (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 1), ('contest1', 'problem2', 2);
+        INSERT INTO fastest (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 4);
+        INSERT INTO shortest (contest_id, problem_id, submission_id) VALUES
+            ('contest1', 'problem1', 1);
+        INSERT INTO points (problem_id, point) VALUES ('problem1', 100.0), ('problem2', -100.0);
+        INSERT INTO rated_point_sum (user_id, point_sum) VALUES ('user1', 100.0);
+    "#,
+    )
+    .unwrap();
+
+    let violations = validate(&conn, 1000).unwrap();
+    let mut summary = violations
+        .iter()
+        .map(|v| {
+            (
+                v.check_name.as_str(),
+                v.subject.as_str(),
+                v.message.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    summary.sort();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "accepted_count",
+                "user2",
+                "accepted_count is 0, but 1 distinct problems are accepted"
+            ),
+            (
+                "accepted_count",
+                "user3",
+                "accepted_count is 1, but 0 distinct problems are accepted"
+            ),
+            ("fastest", "problem1", "submission 4 is missing"),
+            ("first", "problem2", "submission 2 is WA"),
+            ("points", "problem2", "point is -100.00"),
+        ]
+    );
+    assert!(violations.iter().all(|v| v.detected_epoch_second == 1000));
+    assert_eq!(conn.load_violations().unwrap().len(), 5);
+
+    conn.batch_execute(
+        r"
+        UPDATE accepted_count SET problem_count = 0 WHERE user_id = 'user3';
+        DELETE FROM points WHERE problem_id = 'problem2';",
+    )
+    .unwrap();
+    validate(&conn, 2000).unwrap();
+    assert_eq!(
+        conn.load_violations().unwrap().len(),
+        3,
+        "The fixed violations are removed."
+    );
+}
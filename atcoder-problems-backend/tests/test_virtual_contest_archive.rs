@@ -0,0 +1,75 @@
+use atcoder_problems_backend::jobs::archive_virtual_contests;
+use atcoder_problems_backend::sql::internal::virtual_contest_result_manager::VirtualContestResultManager;
+use diesel::connection::SimpleConnection;
+
+pub mod utils;
+
+#[test]
+fn test_archive_virtual_contests() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES
+            ('owner', NULL), ('u1', 'user_a'), ('u2', 'user_b'), ('u3', 'user_c');
+        INSERT INTO internal_virtual_contests (id, internal_user_id, start_epoch_second, duration_second)
+        VALUES
+            ('ended', 'owner', 100, 100),
+            ('running', 'owner', 400, 1000);
+        INSERT INTO internal_virtual_contests (id, internal_user_id, start_epoch_second, duration_second, mode)
+        VALUES ('training', 'owner', 100, 0, 'training');
+        INSERT INTO internal_virtual_contest_items (problem_id, internal_virtual_contest_id) VALUES
+            ('problem_1', 'ended'),
+            ('problem_2', 'ended'),
+            ('problem_1', 'running');
+        INSERT INTO internal_virtual_contest_participants (internal_virtual_contest_id, internal_user_id)
+        VALUES
+            ('ended', 'u1'), ('ended', 'u2'), ('ended', 'u3'), ('running', 'u1');
+        INSERT INTO problem_models (problem_id, difficulty, is_experimental) VALUES
+            ('problem_1', 1000.0, FALSE),
+            ('problem_2', 2000.0, FALSE);
+        INSERT INTO predicted_rating (user_id, rating) VALUES ('user_a', 1000.0);
+        INSERT INTO submissions
+            (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result)
+        VALUES
+            (1, 110, 'problem_1', '', 'user_a', 'Rust', 100.0, 20, 'AC'),
+            (2, 150, 'problem_2', '', 'user_a', 'Rust', 200.0, 20, 'AC'),
+            (3, 120, 'problem_1', '', 'user_b', 'Rust', 100.0, 20, 'AC'),
+            (4, 130, 'problem_2', '', 'user_b', 'Rust', 0.0, 20, 'WA'),
+            -- after the contest
+            (5, 250, 'problem_2', '', 'user_b', 'Rust', 200.0, 20, 'AC');
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(conn.get_results("ended").unwrap(), None);
+    assert_eq!(archive_virtual_contests(&conn, 500).unwrap(), 1);
+    assert_eq!(archive_virtual_contests(&conn, 500).unwrap(), 0);
+    assert_eq!(conn.get_results("running").unwrap(), None);
+    assert_eq!(conn.get_results("training").unwrap(), None);
+
+    let results = conn.get_results("ended").unwrap().unwrap();
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0].user_id, "user_a");
+    assert_eq!(results[0].rank, 0);
+    assert_eq!(results[0].score, 300.0);
+    assert_eq!(results[0].solved_count, 2);
+    assert_eq!(results[0].performance, Some(5000.0));
+    let rating = results[0].simulated_rating.unwrap();
+    assert!((rating - 1400.0).abs() < 1e-6);
+
+    assert_eq!(results[1].user_id, "user_b");
+    assert_eq!(results[1].rank, 1);
+    assert_eq!(results[1].score, 100.0);
+    assert_eq!(results[1].solved_count, 1);
+    let performance = results[1].performance.unwrap();
+    assert!((performance - 1500.0).abs() < 1e-6);
+    let rating = results[1].simulated_rating.unwrap();
+    assert!(rating > 0.0 && rating < 400.0);
+
+    assert_eq!(results[2].user_id, "user_c");
+    assert_eq!(results[2].rank, 2);
+    assert_eq!(results[2].solved_count, 0);
+    assert_eq!(results[2].performance, Some(0.0));
+}
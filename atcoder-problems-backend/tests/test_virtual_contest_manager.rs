@@ -5,7 +5,8 @@ pub mod utils;
 
 #[test]
 fn test_update_streak_ranking() {
-    let conn = utils::initialize_and_connect_to_test_sql();
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
     conn.batch_execute(
         r"
         INSERT INTO internal_users (internal_user_id) VALUES ('user');
@@ -33,4 +34,75 @@ fn test_update_streak_ranking() {
         conn.get_running_contest_problems(125).unwrap(),
         vec!["problem_2".to_owned()]
     );
+
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_virtual_contests (id, internal_user_id, start_epoch_second, duration_second, mode)
+        VALUES ('training', 'user', 50, 0, 'training');
+        INSERT INTO internal_virtual_contest_items (problem_id, internal_virtual_contest_id)
+        VALUES ('problem_3', 'training');
+    ",
+    )
+    .unwrap();
+    assert_eq!(
+        conn.get_running_contest_problems(3).unwrap(),
+        vec!["problem_1".to_owned()]
+    );
+    assert_eq!(
+        conn.get_running_contest_problems(10_000).unwrap(),
+        vec!["problem_3".to_owned()]
+    );
+}
+
+#[test]
+fn test_check_contest_access() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_users (internal_user_id) VALUES ('owner'), ('user_1'), ('user_2');
+        INSERT INTO internal_virtual_contests (id, internal_user_id, start_epoch_second, duration_second, is_public, invite_only)
+        VALUES
+        ('public', 'owner', 0, 100, TRUE, FALSE),
+        ('unlisted', 'owner', 0, 100, FALSE, FALSE),
+        ('private', 'owner', 0, 100, FALSE, TRUE);
+        INSERT INTO internal_virtual_contest_participants (internal_virtual_contest_id, internal_user_id)
+        VALUES ('private', 'user_1');
+    ",
+    )
+    .unwrap();
+
+    assert!(conn.check_contest_access("public", None, None).is_ok());
+    assert!(conn.check_contest_access("unlisted", None, None).is_ok());
+    assert!(conn.check_contest_access("private", None, None).is_err());
+    assert!(conn
+        .check_contest_access("private", Some("owner"), None)
+        .is_ok());
+    assert!(conn
+        .check_contest_access("private", Some("user_1"), None)
+        .is_ok());
+    assert!(conn
+        .check_contest_access("private", Some("user_2"), None)
+        .is_err());
+
+    assert!(conn
+        .update_invite_token("private", "user_1", Some("token"))
+        .is_err());
+    conn.update_invite_token("private", "owner", Some("token"))
+        .unwrap();
+    assert!(conn
+        .check_contest_access("private", Some("user_2"), Some("token"))
+        .is_ok());
+    assert!(conn
+        .check_contest_access("private", None, Some("wrong"))
+        .is_err());
+
+    conn.update_invite_token("private", "owner", None).unwrap();
+    assert!(conn
+        .check_contest_access("private", Some("user_2"), Some("token"))
+        .is_err());
+
+    assert!(conn.update_invite_only("private", "user_1", false).is_err());
+    conn.update_invite_only("private", "owner", false).unwrap();
+    assert!(conn.check_contest_access("private", None, None).is_ok());
 }
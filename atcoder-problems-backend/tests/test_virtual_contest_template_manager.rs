@@ -0,0 +1,117 @@
+use atcoder_problems_backend::sql::internal::virtual_contest_manager::VirtualContestManager;
+use atcoder_problems_backend::sql::internal::virtual_contest_template_manager::VirtualContestTemplateManager;
+use atcoder_problems_backend::sql::models::ProblemModel;
+use atcoder_problems_backend::sql::schema::internal_virtual_contest_announcements as v_announcements;
+use atcoder_problems_backend::sql::ProblemModelClient;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+
+pub mod utils;
+
+#[test]
+fn test_instantiate_template() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    conn.batch_execute(
+        r"
+        INSERT INTO internal_users (internal_user_id, atcoder_user_id) VALUES ('owner', 'owner_atcoder');
+        INSERT INTO submissions (id, epoch_second, problem_id, contest_id, user_id, language, point, length, result) VALUES
+        (1, 0, 'problem_2', '', 'owner_atcoder', '', 0, 0, 'AC');
+    ",
+    )
+    .unwrap();
+    let models = vec![
+        ("problem_1", 100.0),
+        ("problem_2", 800.0),
+        ("problem_3", 850.0),
+    ]
+    .into_iter()
+    .map(|(problem_id, difficulty)| ProblemModel {
+        problem_id: problem_id.to_owned(),
+        difficulty: Some(difficulty),
+        is_experimental: false,
+    })
+    .collect::<Vec<_>>();
+    conn.update_problem_models(&models).unwrap();
+
+    assert!(conn
+        .create_template(
+            "owner",
+            "weekly",
+            "",
+            7,
+            21 * 60,
+            6000,
+            None,
+            true,
+            &[100.0]
+        )
+        .is_err());
+    let template_id = conn
+        .create_template(
+            "owner",
+            "weekly",
+            "memo",
+            5,
+            21 * 60,
+            6000,
+            None,
+            true,
+            &[100.0, 800.0],
+        )
+        .unwrap();
+
+    // 2020-06-06 (Sat) 12:00:00 JST
+    let now = 1_591_412_400;
+    let templates = conn.get_own_templates("owner").unwrap();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].id, template_id);
+    let contest_id = conn
+        .instantiate_template(&templates[0], now)
+        .unwrap()
+        .unwrap();
+
+    let contest = conn.get_single_contest_info(&contest_id).unwrap();
+    let contest = serde_json::to_value(&contest).unwrap();
+    assert_eq!(contest["start_epoch_second"], 1_591_444_800);
+    let problems = serde_json::to_value(conn.get_contest_items(&contest_id).unwrap()).unwrap();
+    assert_eq!(problems[0]["id"], "problem_1");
+    assert_eq!(problems[1]["id"], "problem_3");
+    let announcements = v_announcements::table
+        .filter(v_announcements::internal_virtual_contest_id.eq(&contest_id))
+        .select((v_announcements::message, v_announcements::epoch_second))
+        .load::<(String, i64)>(&conn)
+        .unwrap();
+    assert_eq!(
+        announcements,
+        vec![(
+            "This contest is scheduled from the weekly template \"weekly\".".to_owned(),
+            now
+        )]
+    );
+
+    let templates = conn.load_templates().unwrap();
+    assert!(conn
+        .instantiate_template(&templates[0], now)
+        .unwrap()
+        .is_none());
+
+    conn.delete_template("owner", &template_id).unwrap();
+    assert!(conn.load_templates().unwrap().is_empty());
+
+    conn.create_template(
+        "owner",
+        "too hard",
+        "",
+        5,
+        21 * 60,
+        6000,
+        None,
+        true,
+        &[4000.0],
+    )
+    .unwrap();
+    let templates = conn.load_templates().unwrap();
+    assert!(conn.instantiate_template(&templates[0], now).is_err());
+    assert_eq!(conn.get_own_contests("owner").unwrap().len(), 1);
+}
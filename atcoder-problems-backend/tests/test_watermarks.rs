@@ -0,0 +1,47 @@
+use atcoder_problems_backend::sql::models::Watermark;
+use atcoder_problems_backend::sql::WatermarkClient;
+
+mod utils;
+
+fn watermark(last_epoch_second: i64, last_submission_id: i64) -> Watermark {
+    Watermark {
+        last_epoch_second,
+        last_submission_id,
+    }
+}
+
+#[test]
+fn test_watermarks() {
+    let database = utils::TestDatabase::new();
+    let conn = database.connect();
+    assert_eq!(conn.load_watermark("updater").unwrap(), None);
+
+    assert!(conn
+        .advance_watermark("updater", None, &watermark(100, 1))
+        .unwrap());
+    assert!(
+        !conn
+            .advance_watermark("updater", None, &watermark(200, 2))
+            .unwrap(),
+        "The watermark has been created by another updater."
+    );
+    assert_eq!(
+        conn.load_watermark("updater").unwrap(),
+        Some(watermark(100, 1))
+    );
+
+    assert!(conn
+        .advance_watermark("updater", Some(&watermark(100, 1)), &watermark(300, 3))
+        .unwrap());
+    assert!(
+        !conn
+            .advance_watermark("updater", Some(&watermark(100, 1)), &watermark(200, 2))
+            .unwrap(),
+        "A stale updater must not move the watermark backward."
+    );
+    assert_eq!(
+        conn.load_watermark("updater").unwrap(),
+        Some(watermark(300, 3))
+    );
+    assert_eq!(conn.load_watermark("another").unwrap(), None);
+}
@@ -1,6 +1,10 @@
 SET client_encoding = 'UTF8';
 
 DROP TABLE IF EXISTS submissions;
+-- The order of the insertions and the updates of the submissions, which the mirrors follow to
+-- replicate them including the rejudges. The archived submissions keep it in their default.
+DROP SEQUENCE IF EXISTS submissions_change_sequence CASCADE;
+CREATE SEQUENCE submissions_change_sequence;
 CREATE TABLE submissions (
   id            BIGINT NOT NULL,
   epoch_second  BIGINT NOT NULL,
@@ -8,14 +12,76 @@ CREATE TABLE submissions (
   contest_id    VARCHAR(255) NOT NULL,
   user_id       VARCHAR(255) NOT NULL,
   language      VARCHAR(255) NOT NULL,
-  point         DOUBLE PRECISION NOT NULL,
+  point         NUMERIC(18, 2) NOT NULL,
   length        INT NOT NULL,
   result        VARCHAR(255) NOT NULL,
   execution_time  INT,
+  simplified_language VARCHAR(255) NOT NULL DEFAULT '',
+  change_sequence BIGINT NOT NULL DEFAULT nextval('submissions_change_sequence'),
   PRIMARY KEY (id)
 );
 CREATE INDEX ON submissions (user_id);
-CREATE INDEX ON submissions (epoch_second);
+CREATE INDEX ON submissions (epoch_second, id);
+CREATE UNIQUE INDEX ON submissions (change_sequence);
+CREATE INDEX ON submissions (problem_id);
+CREATE INDEX ON submissions (user_id, simplified_language);
+
+-- Simplifies the language of a submission as `simplify_language` of the backend does.
+CREATE OR REPLACE FUNCTION simplify_submission_language() RETURNS TRIGGER AS $$
+BEGIN
+  NEW.simplified_language := CASE
+    WHEN NEW.language LIKE 'Perl6%' THEN 'Perl6'
+    ELSE regexp_replace(NEW.language, '\d* \(.*\)', '')
+  END;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER simplify_submission_language BEFORE INSERT OR UPDATE OF language ON submissions
+  FOR EACH ROW EXECUTE PROCEDURE simplify_submission_language();
+
+-- Moves an inserted or updated submission to the end of `submissions_change_sequence`, unless
+-- the upsert of the crawlers changes nothing. The writers are serialized by the advisory lock
+-- until they commit, so that the changes are committed in the order of their sequence numbers,
+-- and the mirrors following them never skip a change committed after a later one.
+CREATE OR REPLACE FUNCTION sequence_submission_change() RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_advisory_xact_lock(hashtext('submissions_change_sequence'));
+  NEW.change_sequence := nextval('submissions_change_sequence');
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER sequence_submission_insert BEFORE INSERT ON submissions
+  FOR EACH ROW EXECUTE PROCEDURE sequence_submission_change();
+CREATE TRIGGER sequence_submission_change BEFORE UPDATE ON submissions
+  FOR EACH ROW WHEN (OLD.* IS DISTINCT FROM NEW.*) EXECUTE PROCEDURE sequence_submission_change();
+
+-- The submissions moved out of `submissions` by the archival, which the aggregates do not read.
+DROP TABLE IF EXISTS submissions_archive;
+CREATE TABLE submissions_archive (LIKE submissions INCLUDING DEFAULTS);
+ALTER TABLE submissions_archive ADD PRIMARY KEY (id);
+CREATE INDEX ON submissions_archive (user_id, simplified_language);
+
+-- The canonical verdicts of the raw results of the submissions, which are the same as
+-- `Verdict::RESULT_CODES` of the backend. The results not in this table are pending.
+DROP TABLE IF EXISTS result_codes;
+CREATE TABLE result_codes (
+  raw_result    VARCHAR(255) NOT NULL,
+  verdict       VARCHAR(255) NOT NULL,
+  PRIMARY KEY (raw_result)
+);
+CREATE INDEX ON result_codes (verdict);
+INSERT INTO result_codes (raw_result, verdict) VALUES
+  ('AC', 'accepted'),
+  ('WA', 'wrong_answer'),
+  ('TLE', 'time_limit_exceeded'),
+  ('MLE', 'memory_limit_exceeded'),
+  ('OLE', 'output_limit_exceeded'),
+  ('QLE', 'query_limit_exceeded'),
+  ('RE', 'runtime_error'),
+  ('CE', 'compile_error'),
+  ('PE', 'presentation_error'),
+  ('IE', 'internal_error'),
+  ('NG', 'other');
 
 DROP TABLE IF EXISTS problems;
 CREATE TABLE problems (
@@ -24,6 +90,7 @@ CREATE TABLE problems (
   title         VARCHAR(255) NOT NULL,
   PRIMARY KEY (id)
 );
+CREATE INDEX ON problems USING GIN (to_tsvector('simple', title));
 
 DROP TABLE IF EXISTS contests;
 CREATE TABLE contests (
@@ -32,9 +99,29 @@ CREATE TABLE contests (
   duration_second       BIGINT       NOT NULL,
   title                 VARCHAR(255) NOT NULL,
   rate_change           VARCHAR(255) NOT NULL,
+  rated                 BOOLEAN      NOT NULL DEFAULT FALSE,
+  rated_min_rating      INT,
+  rated_max_rating      INT,
   PRIMARY KEY (id)
 );
 
+-- Parses the rate change of a contest as `RatedRange::parse` of the backend does. The contests
+-- before the first AGC are unrated, and the omitted bounds are NULL.
+CREATE OR REPLACE FUNCTION parse_contest_rate_change() RETURNS TRIGGER AS $$
+DECLARE
+  bounds TEXT[];
+BEGIN
+  NEW.rated := NEW.start_epoch_second >= 1468670400 AND btrim(NEW.rate_change) <> '-';
+  bounds := regexp_match(NEW.rate_change, '^\s*(\d*)\s*~\s*(\d*)\s*$');
+  NEW.rated_min_rating := CASE WHEN NEW.rated THEN NULLIF(bounds[1], '')::INT END;
+  NEW.rated_max_rating := CASE WHEN NEW.rated THEN NULLIF(bounds[2], '')::INT END;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER parse_contest_rate_change
+  BEFORE INSERT OR UPDATE OF rate_change, start_epoch_second ON contests
+  FOR EACH ROW EXECUTE PROCEDURE parse_contest_rate_change();
+
 DROP TABLE IF EXISTS solver;
 CREATE TABLE solver (
   problem_id            VARCHAR(255)  NOT NULL,
@@ -42,6 +129,42 @@ CREATE TABLE solver (
   PRIMARY KEY (problem_id)
 );
 
+DROP TABLE IF EXISTS solve_time;
+CREATE TABLE solve_time (
+  problem_id            VARCHAR(255) NOT NULL,
+  median_second         DOUBLE PRECISION NOT NULL,
+  user_count            INT NOT NULL,
+  PRIMARY KEY (problem_id)
+);
+
+-- The distribution of the lengths of the shortest AC submissions of the users who solved each
+-- problem: the quartiles and the numbers of the users in 10 buckets of `bucket_width` from
+-- `min_length`.
+DROP TABLE IF EXISTS length_distributions;
+CREATE TABLE length_distributions (
+  problem_id            VARCHAR(255) NOT NULL,
+  user_count            INT NOT NULL,
+  min_length            INT NOT NULL,
+  first_quartile        DOUBLE PRECISION NOT NULL,
+  median                DOUBLE PRECISION NOT NULL,
+  third_quartile        DOUBLE PRECISION NOT NULL,
+  max_length            INT NOT NULL,
+  bucket_width          INT NOT NULL,
+  bucket_counts         INT[] NOT NULL,
+  PRIMARY KEY (problem_id)
+);
+
+-- The problems frequently solved by the same users around the same time as each problem, where
+-- `score` is the number of such users normalized by the numbers of the solvers of both problems.
+DROP TABLE IF EXISTS similar_problems;
+CREATE TABLE similar_problems (
+  problem_id            VARCHAR(255) NOT NULL,
+  similar_problem_id    VARCHAR(255) NOT NULL,
+  co_solver_count       BIGINT NOT NULL,
+  score                 DOUBLE PRECISION NOT NULL,
+  PRIMARY KEY (problem_id, similar_problem_id)
+);
+
 DROP TABLE IF EXISTS shortest;
 CREATE TABLE shortest (
   contest_id    VARCHAR(255)  NOT NULL,
@@ -50,6 +173,19 @@ CREATE TABLE shortest (
   PRIMARY KEY (problem_id)
 );
 
+DROP TABLE IF EXISTS shortest_history;
+CREATE TABLE shortest_history (
+  problem_id              VARCHAR(255) NOT NULL,
+  epoch_second            BIGINT NOT NULL,
+  previous_submission_id  BIGINT,
+  previous_user_id        VARCHAR(255),
+  previous_length         INT,
+  submission_id           BIGINT NOT NULL,
+  user_id                 VARCHAR(255) NOT NULL,
+  length                  INT NOT NULL,
+  PRIMARY KEY (problem_id, submission_id)
+);
+
 DROP TABLE IF EXISTS fastest;
 CREATE TABLE fastest (
   contest_id    VARCHAR(255)  NOT NULL,
@@ -66,6 +202,25 @@ CREATE TABLE first (
   PRIMARY KEY (problem_id)
 );
 
+-- The first AC submission of each problem after the end of the contest, i.e. the first upsolver.
+DROP TABLE IF EXISTS first_practice;
+CREATE TABLE first_practice (
+  contest_id    VARCHAR(255)  NOT NULL,
+  problem_id    VARCHAR(255)  NOT NULL,
+  submission_id BIGINT  NOT NULL,
+  PRIMARY KEY (problem_id)
+);
+
+-- The time of the latest submission of each user, by which the rankings can be limited to the
+-- active users.
+DROP TABLE IF EXISTS active_users;
+CREATE TABLE active_users (
+  user_id                         VARCHAR(255) NOT NULL,
+  last_submission_epoch_second    BIGINT NOT NULL,
+  PRIMARY KEY (user_id)
+);
+CREATE INDEX ON active_users (last_submission_epoch_second);
+
 DROP TABLE IF EXISTS accepted_count;
 CREATE TABLE accepted_count (
   user_id       VARCHAR(255)  NOT NULL,
@@ -76,7 +231,7 @@ CREATE TABLE accepted_count (
 DROP TABLE IF EXISTS points;
 CREATE TABLE points (
   problem_id            VARCHAR(255) NOT NULL,
-  point                 DOUBLE PRECISION,
+  point                 NUMERIC(18, 2),
   predict                 DOUBLE PRECISION,
   PRIMARY KEY (problem_id)
 );
@@ -84,7 +239,7 @@ CREATE TABLE points (
 DROP TABLE IF EXISTS rated_point_sum;
 CREATE TABLE rated_point_sum (
   user_id         VARCHAR(255) NOT NULL,
-  point_sum       DOUBLE PRECISION NOT NULL,
+  point_sum       NUMERIC(18, 2) NOT NULL,
   PRIMARY KEY (user_id)
 );
 
@@ -96,6 +251,56 @@ CREATE TABLE language_count (
   PRIMARY KEY (user_id, simplified_language)
 );
 
+DROP TABLE IF EXISTS language_trends;
+CREATE TABLE language_trends (
+  month                 VARCHAR(7) NOT NULL,
+  simplified_language   VARCHAR(255) NOT NULL,
+  submission_count      BIGINT NOT NULL,
+  user_count            INT NOT NULL,
+  PRIMARY KEY (month, simplified_language)
+);
+
+DROP TABLE IF EXISTS difficulty_count;
+CREATE TABLE difficulty_count (
+  user_id               VARCHAR(255) NOT NULL,
+  difficulty_from       BIGINT NOT NULL,
+  problem_count         INT NOT NULL,
+  PRIMARY KEY (user_id, difficulty_from)
+);
+
+DROP TABLE IF EXISTS contest_completion;
+CREATE TABLE contest_completion (
+  user_id               VARCHAR(255) NOT NULL,
+  contest_id            VARCHAR(255) NOT NULL,
+  solved_count          INT NOT NULL,
+  problem_count         INT NOT NULL,
+  PRIMARY KEY (user_id, contest_id)
+);
+
+-- The best score of each user for each problem of the heuristic contests, which is not counted in
+-- the point sums.
+DROP TABLE IF EXISTS marathon_best_scores;
+CREATE TABLE marathon_best_scores (
+  user_id               VARCHAR(255) NOT NULL,
+  problem_id            VARCHAR(255) NOT NULL,
+  contest_id            VARCHAR(255) NOT NULL,
+  score                 DOUBLE PRECISION NOT NULL,
+  submission_id         BIGINT NOT NULL,
+  epoch_second          BIGINT NOT NULL,
+  PRIMARY KEY (user_id, problem_id)
+);
+CREATE INDEX ON marathon_best_scores (contest_id);
+
+-- The users of each heuristic contest ranked by the sum of their best scores of the problems.
+DROP TABLE IF EXISTS marathon_leaderboard;
+CREATE TABLE marathon_leaderboard (
+  contest_id            VARCHAR(255) NOT NULL,
+  user_id               VARCHAR(255) NOT NULL,
+  score                 DOUBLE PRECISION NOT NULL,
+  rank                  BIGINT NOT NULL,
+  PRIMARY KEY (contest_id, user_id)
+);
+
 DROP TABLE IF EXISTS predicted_rating;
 CREATE TABLE predicted_rating (
   user_id               VARCHAR(255) NOT NULL,
@@ -110,6 +315,23 @@ CREATE TABLE contest_problem (
   PRIMARY KEY (contest_id, problem_id)
 );
 
+DROP TABLE IF EXISTS contest_stats;
+CREATE TABLE contest_stats (
+  contest_id            VARCHAR(255) NOT NULL,
+  participant_count     INT NOT NULL,
+  full_solve_count      INT NOT NULL,
+  PRIMARY KEY (contest_id)
+);
+
+DROP TABLE IF EXISTS contest_problem_stats;
+CREATE TABLE contest_problem_stats (
+  contest_id            VARCHAR(255) NOT NULL,
+  problem_id            VARCHAR(255) NOT NULL,
+  submitter_count       INT NOT NULL,
+  ac_user_count         INT NOT NULL,
+  PRIMARY KEY (contest_id, problem_id)
+);
+
 DROP TABLE IF EXISTS max_streaks;
 CREATE TABLE max_streaks (
   user_id               VARCHAR(255) NOT NULL,
@@ -124,21 +346,248 @@ CREATE TABLE submission_count (
   PRIMARY KEY (user_id)
 );
 
+-- The statistics of the whole site, which has only one row. `accepted_count` is the number of
+-- the pairs of the users and the problems they solved.
+DROP TABLE IF EXISTS site_stats;
+CREATE TABLE site_stats (
+  submission_count                BIGINT NOT NULL,
+  accepted_count                  BIGINT NOT NULL,
+  weekly_active_user_count        INT NOT NULL,
+  monthly_active_user_count       INT NOT NULL,
+  problem_count                   INT NOT NULL,
+  latest_submission_epoch_second  BIGINT,
+  updated_epoch_second            BIGINT NOT NULL
+);
+
+DROP TABLE IF EXISTS problem_models;
+CREATE TABLE problem_models (
+  problem_id            VARCHAR(255) NOT NULL,
+  difficulty            DOUBLE PRECISION,
+  is_experimental       BOOLEAN NOT NULL DEFAULT FALSE,
+  PRIMARY KEY (problem_id)
+);
+
+DROP TABLE IF EXISTS problem_model_diagnostics;
+CREATE TABLE problem_model_diagnostics (
+  problem_id            VARCHAR(255) NOT NULL,
+  sample_size           INT NOT NULL,
+  log_likelihood        DOUBLE PRECISION,
+  variance              DOUBLE PRECISION,
+  PRIMARY KEY (problem_id)
+);
+
+-- The difficulties estimated from the results of the contests before the problem models have them.
+DROP TABLE IF EXISTS provisional_difficulties;
+CREATE TABLE provisional_difficulties (
+  problem_id              VARCHAR(255) NOT NULL,
+  difficulty              DOUBLE PRECISION NOT NULL,
+  sample_size             INT NOT NULL,
+  estimated_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (problem_id)
+);
+
+-- Submissions and problems of the other judges, e.g. Codeforces:
+DROP TABLE IF EXISTS judge_submissions;
+CREATE TABLE judge_submissions (
+  judge         VARCHAR(255) NOT NULL,
+  id            BIGINT NOT NULL,
+  epoch_second  BIGINT NOT NULL,
+  problem_id    VARCHAR(255) NOT NULL,
+  user_id       VARCHAR(255) NOT NULL,
+  language      VARCHAR(255) NOT NULL,
+  result        VARCHAR(255) NOT NULL,
+  PRIMARY KEY (judge, id)
+);
+CREATE INDEX ON judge_submissions (judge, user_id);
+
+DROP TABLE IF EXISTS judge_problems;
+CREATE TABLE judge_problems (
+  judge         VARCHAR(255) NOT NULL,
+  id            VARCHAR(255) NOT NULL,
+  title         VARCHAR(255) NOT NULL,
+  difficulty    DOUBLE PRECISION,
+  PRIMARY KEY (judge, id)
+);
+
+DROP TABLE IF EXISTS dumps;
+CREATE TABLE dumps (
+  name                    VARCHAR(255) NOT NULL,
+  url                     VARCHAR(2048) NOT NULL,
+  size                    BIGINT NOT NULL,
+  sha256                  VARCHAR(64) NOT NULL,
+  generated_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (name)
+);
+
+DROP TABLE IF EXISTS job_runs;
+CREATE TABLE job_runs (
+  id                      SERIAL PRIMARY KEY,
+  job_name                VARCHAR(255) NOT NULL,
+  status                  VARCHAR(255) NOT NULL,
+  message                 TEXT,
+  started_epoch_second    BIGINT NOT NULL,
+  finished_epoch_second   BIGINT
+);
+CREATE INDEX ON job_runs (job_name, started_epoch_second);
+CREATE UNIQUE INDEX ON job_runs (job_name) WHERE status = 'running';
+
+-- The latest submission processed by each incremental updater, e.g. a tier of the delta updates,
+-- from which its next run starts.
+DROP TABLE IF EXISTS updater_watermarks;
+CREATE TABLE updater_watermarks (
+  name                    VARCHAR(255) NOT NULL,
+  last_epoch_second       BIGINT NOT NULL,
+  last_submission_id      BIGINT NOT NULL,
+  PRIMARY KEY (name)
+);
+
+DROP TABLE IF EXISTS update_tier_stats;
+CREATE TABLE update_tier_stats (
+  tier                        VARCHAR(255) NOT NULL,
+  run_count                   BIGINT NOT NULL,
+  total_duration_millis       BIGINT NOT NULL,
+  last_duration_millis        BIGINT NOT NULL,
+  last_user_count             INT NOT NULL,
+  last_finished_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (tier)
+);
+
+-- The contests excluded from the aggregates of the points, the difficulties and the first, fastest
+-- and shortest submissions, e.g. April Fools contests and experimental rounds.
+DROP TABLE IF EXISTS excluded_contests;
+CREATE TABLE excluded_contests (
+  contest_id              VARCHAR(255) NOT NULL,
+  reason                  TEXT NOT NULL,
+  created_epoch_second    BIGINT NOT NULL,
+  PRIMARY KEY (contest_id)
+);
+
+DROP TABLE IF EXISTS feature_flags;
+CREATE TABLE feature_flags (
+  name                    VARCHAR(255) NOT NULL,
+  enabled                 BOOLEAN NOT NULL,
+  updated_epoch_second    BIGINT NOT NULL,
+  PRIMARY KEY (name)
+);
+
+-- The numbers of the requests of each route from each client app in each hour, where the client
+-- app is named by the `X-Client` header.
+DROP TABLE IF EXISTS request_counts;
+CREATE TABLE request_counts (
+  hour_epoch_second       BIGINT NOT NULL,
+  route                   VARCHAR(255) NOT NULL,
+  client                  VARCHAR(255) NOT NULL,
+  request_count           BIGINT NOT NULL,
+  PRIMARY KEY (hour_epoch_second, route, client)
+);
+
+DROP TABLE IF EXISTS validation_violations;
+CREATE TABLE validation_violations (
+  check_name              VARCHAR(255) NOT NULL,
+  subject                 VARCHAR(255) NOT NULL,
+  message                 TEXT NOT NULL,
+  detected_epoch_second   BIGINT NOT NULL,
+  PRIMARY KEY (check_name, subject)
+);
+
+DROP TABLE IF EXISTS table_versions;
+CREATE TABLE table_versions (
+  name                    VARCHAR(255) NOT NULL,
+  version                 BIGINT NOT NULL,
+  updated_epoch_second    BIGINT NOT NULL,
+  PRIMARY KEY (name)
+);
+
+DROP TABLE IF EXISTS rank_history;
+CREATE TABLE rank_history (
+  user_id                 VARCHAR(255) NOT NULL,
+  epoch_second            BIGINT NOT NULL,
+  accepted_count_rank     BIGINT,
+  rated_point_sum_rank    BIGINT,
+  PRIMARY KEY (user_id, epoch_second)
+);
+
+DROP TABLE IF EXISTS standings;
+CREATE TABLE standings (
+  contest_id              VARCHAR(255) NOT NULL,
+  user_id                 VARCHAR(255) NOT NULL,
+  problem_id              VARCHAR(255) NOT NULL,
+  rank                    INTEGER NOT NULL,
+  is_rated                BOOLEAN NOT NULL,
+  score                   DOUBLE PRECISION NOT NULL,
+  penalty                 INTEGER NOT NULL,
+  elapsed_second          BIGINT,
+  PRIMARY KEY (contest_id, user_id, problem_id)
+);
+CREATE INDEX ON standings (problem_id);
+
+DROP TABLE IF EXISTS achievements;
+CREATE TABLE achievements (
+  user_id                 VARCHAR(255) NOT NULL,
+  achievement             VARCHAR(255) NOT NULL,
+  epoch_second            BIGINT NOT NULL,
+  PRIMARY KEY (user_id, achievement)
+);
+
+-- The source codes of the submissions fetched from AtCoder on demand, so that AtCoder is not asked
+-- for the same submission again.
+DROP TABLE IF EXISTS submission_codes;
+CREATE TABLE submission_codes (
+  submission_id           BIGINT NOT NULL,
+  code                    TEXT NOT NULL,
+  fetched_epoch_second    BIGINT NOT NULL,
+  PRIMARY KEY (submission_id)
+);
+
 -- For internal services:
 DROP TABLE IF EXISTS internal_problem_list_items;
 DROP TABLE IF EXISTS internal_problem_lists;
 
+DROP TABLE IF EXISTS internal_virtual_contest_results;
+DROP TABLE IF EXISTS internal_virtual_contest_archives;
+DROP TABLE IF EXISTS internal_virtual_contest_training_progress;
+DROP TABLE IF EXISTS internal_virtual_contest_announcements;
+DROP TABLE IF EXISTS internal_virtual_contest_templates;
+DROP TABLE IF EXISTS internal_virtual_contest_team_members;
+DROP TABLE IF EXISTS internal_virtual_contest_teams;
 DROP TABLE IF EXISTS internal_virtual_contest_participants;
 DROP TABLE IF EXISTS internal_virtual_contest_items;
 DROP TABLE IF EXISTS internal_virtual_contests;
 
 DROP TABLE IF EXISTS internal_progress_reset;
 
+DROP TABLE IF EXISTS internal_problem_tags;
+DROP TABLE IF EXISTS internal_problem_notes;
+
+DROP TABLE IF EXISTS internal_watched_users;
+
+DROP TABLE IF EXISTS internal_assignment_problems;
+DROP TABLE IF EXISTS internal_assignments;
+DROP TABLE IF EXISTS internal_user_group_members;
+DROP TABLE IF EXISTS internal_user_groups;
+
+DROP TABLE IF EXISTS internal_user_verifications;
+
+DROP TABLE IF EXISTS internal_api_tokens;
+
+DROP TABLE IF EXISTS internal_banned_users;
+DROP TABLE IF EXISTS internal_recrawl_requests;
+
+DROP TABLE IF EXISTS internal_audit_log;
+
+DROP TABLE IF EXISTS internal_daily_digests;
+DROP TABLE IF EXISTS internal_webhook_deliveries;
+DROP TABLE IF EXISTS internal_webhooks;
+
+DROP TABLE IF EXISTS internal_idempotency_keys;
+DROP TABLE IF EXISTS internal_api_usage;
+
 DROP TABLE IF EXISTS internal_users;
 
 CREATE TABLE internal_users (
   internal_user_id      VARCHAR(255) NOT NULL,
   atcoder_user_id       VARCHAR(255) DEFAULT NULL,
+  is_admin              BOOLEAN NOT NULL DEFAULT FALSE,
   PRIMARY KEY (internal_user_id)
 );
 
@@ -146,9 +595,12 @@ CREATE TABLE internal_problem_lists (
   internal_list_id      VARCHAR(255) NOT NULL,
   internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
   internal_list_name    VARCHAR(255) DEFAULT '',
+  share_token           VARCHAR(255) DEFAULT NULL,
+  deleted_epoch_second  BIGINT DEFAULT NULL,
   PRIMARY KEY (internal_list_id)
 );
 CREATE INDEX ON internal_problem_lists (internal_user_id);
+CREATE UNIQUE INDEX ON internal_problem_lists (share_token);
 
 CREATE TABLE internal_problem_list_items (
   internal_list_id      VARCHAR(255) REFERENCES internal_problem_lists ON DELETE CASCADE ON UPDATE CASCADE,
@@ -167,6 +619,10 @@ CREATE TABLE internal_virtual_contests (
   duration_second       BIGINT       NOT NULL,
   mode      VARCHAR(255) DEFAULT NULL,
   is_public boolean NOT NULL DEFAULT TRUE,
+  invite_only boolean NOT NULL DEFAULT FALSE,
+  invite_token VARCHAR(255) DEFAULT NULL,
+  scoring   VARCHAR(255) DEFAULT NULL,
+  deleted_epoch_second  BIGINT DEFAULT NULL,
   PRIMARY KEY (id)
 );
 CREATE INDEX ON internal_virtual_contests (internal_user_id);
@@ -188,6 +644,74 @@ CREATE TABLE internal_virtual_contest_participants (
 );
 CREATE INDEX ON internal_virtual_contest_participants (internal_user_id);
 
+CREATE TABLE internal_virtual_contest_teams (
+  id                          VARCHAR(255) NOT NULL,
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  team_name                   VARCHAR(255) NOT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_virtual_contest_teams (internal_virtual_contest_id);
+
+CREATE TABLE internal_virtual_contest_team_members (
+  team_id                     VARCHAR(255) REFERENCES internal_virtual_contest_teams(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  internal_virtual_contest_id VARCHAR(255) NOT NULL,
+  atcoder_user_id             VARCHAR(255) NOT NULL,
+  PRIMARY KEY (team_id, atcoder_user_id),
+  -- A user is a member of at most one team of a contest.
+  UNIQUE (internal_virtual_contest_id, atcoder_user_id)
+);
+
+CREATE TABLE internal_virtual_contest_announcements (
+  id                          VARCHAR(255) NOT NULL,
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  message                     TEXT NOT NULL,
+  epoch_second                BIGINT NOT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_virtual_contest_announcements (internal_virtual_contest_id);
+
+CREATE TABLE internal_virtual_contest_training_progress (
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  internal_user_id            VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  problem_id                  VARCHAR(255) NOT NULL,
+  solved_epoch_second         BIGINT NOT NULL,
+  PRIMARY KEY (internal_virtual_contest_id, internal_user_id, problem_id)
+);
+
+CREATE TABLE internal_virtual_contest_archives (
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  archived_epoch_second       BIGINT NOT NULL,
+  PRIMARY KEY (internal_virtual_contest_id)
+);
+
+CREATE TABLE internal_virtual_contest_results (
+  internal_virtual_contest_id VARCHAR(255) REFERENCES internal_virtual_contests(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  user_id                     VARCHAR(255) NOT NULL,
+  rank                        BIGINT NOT NULL,
+  score                       DOUBLE PRECISION NOT NULL,
+  penalty_second              BIGINT NOT NULL,
+  solved_count                INTEGER NOT NULL,
+  performance                 DOUBLE PRECISION,
+  simulated_rating            DOUBLE PRECISION,
+  PRIMARY KEY (internal_virtual_contest_id, user_id)
+);
+
+CREATE TABLE internal_virtual_contest_templates (
+  id                      VARCHAR(255) NOT NULL,
+  internal_user_id        VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  title                   VARCHAR(255) NOT NULL DEFAULT '',
+  memo                    VARCHAR(255) NOT NULL DEFAULT '',
+  weekday                 INT NOT NULL,
+  start_minute_of_day     INT NOT NULL,
+  duration_second         BIGINT NOT NULL,
+  mode                    VARCHAR(255) DEFAULT NULL,
+  is_public               BOOLEAN NOT NULL DEFAULT TRUE,
+  problem_difficulties    DOUBLE PRECISION[] NOT NULL,
+  last_start_epoch_second BIGINT DEFAULT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_virtual_contest_templates (internal_user_id);
+
 CREATE TABLE internal_progress_reset (
   internal_user_id    VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
   problem_id          VARCHAR(255) NOT NULL,
@@ -195,3 +719,156 @@ CREATE TABLE internal_progress_reset (
   PRIMARY KEY (internal_user_id, problem_id)
 );
 CREATE INDEX ON internal_progress_reset (internal_user_id);
+
+CREATE TABLE internal_problem_notes (
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  problem_id            VARCHAR(255) NOT NULL,
+  note                  TEXT NOT NULL,
+  updated_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (internal_user_id, problem_id)
+);
+
+CREATE TABLE internal_problem_tags (
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  problem_id            VARCHAR(255) NOT NULL,
+  tag                   VARCHAR(255) NOT NULL,
+  tagged_epoch_second   BIGINT NOT NULL,
+  PRIMARY KEY (internal_user_id, problem_id, tag)
+);
+CREATE INDEX ON internal_problem_tags (internal_user_id, tag);
+
+CREATE TABLE internal_watched_users (
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  watched_user_id       VARCHAR(255) NOT NULL,
+  PRIMARY KEY (internal_user_id, watched_user_id)
+);
+
+CREATE TABLE internal_user_verifications (
+  internal_user_id        VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  atcoder_user_id         VARCHAR(255) NOT NULL,
+  verification_code       VARCHAR(255) NOT NULL,
+  created_epoch_second    BIGINT NOT NULL,
+  verified_epoch_second   BIGINT DEFAULT NULL,
+  PRIMARY KEY (internal_user_id)
+);
+
+CREATE TABLE internal_api_tokens (
+  token_hash            VARCHAR(255) NOT NULL,
+  id                    VARCHAR(255) NOT NULL,
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  name                  VARCHAR(255) NOT NULL DEFAULT '',
+  scopes                VARCHAR(255)[] DEFAULT NULL,
+  created_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (token_hash)
+);
+CREATE UNIQUE INDEX ON internal_api_tokens (id);
+CREATE INDEX ON internal_api_tokens (internal_user_id);
+
+CREATE TABLE internal_banned_users (
+  user_id               VARCHAR(255) NOT NULL,
+  banned_epoch_second   BIGINT NOT NULL,
+  PRIMARY KEY (user_id)
+);
+
+CREATE TABLE internal_recrawl_requests (
+  contest_id              VARCHAR(255) NOT NULL,
+  requested_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (contest_id)
+);
+
+CREATE TABLE internal_audit_log (
+  id                    BIGSERIAL NOT NULL,
+  internal_user_id      VARCHAR(255) NOT NULL REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  method                VARCHAR(255) NOT NULL,
+  path                  VARCHAR(255) NOT NULL,
+  status                INTEGER NOT NULL,
+  payload_summary       TEXT NOT NULL,
+  epoch_second          BIGINT NOT NULL,
+  client_ip             VARCHAR(255),
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_audit_log (internal_user_id);
+
+CREATE TABLE internal_webhooks (
+  id                    VARCHAR(255) NOT NULL,
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  url                   VARCHAR(2048) NOT NULL,
+  secret                VARCHAR(255) NOT NULL,
+  event_types           VARCHAR(255)[] NOT NULL,
+  format                VARCHAR(255) NOT NULL DEFAULT 'json',
+  created_epoch_second  BIGINT NOT NULL,
+  verification_token    VARCHAR(255) DEFAULT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_webhooks (internal_user_id);
+
+CREATE TABLE internal_webhook_deliveries (
+  id                          BIGSERIAL NOT NULL,
+  webhook_id                  VARCHAR(255) REFERENCES internal_webhooks ON DELETE CASCADE ON UPDATE CASCADE,
+  event_type                  VARCHAR(255) NOT NULL,
+  payload                     TEXT NOT NULL,
+  attempt_count               INTEGER NOT NULL DEFAULT 0,
+  next_attempt_epoch_second   BIGINT DEFAULT NULL,
+  delivered_epoch_second      BIGINT DEFAULT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX ON internal_webhook_deliveries (next_attempt_epoch_second);
+
+CREATE TABLE internal_daily_digests (
+  atcoder_user_id       VARCHAR(255) NOT NULL,
+  day_epoch_second      BIGINT NOT NULL,
+  payload               TEXT NOT NULL,
+  PRIMARY KEY (atcoder_user_id, day_epoch_second)
+);
+
+CREATE TABLE internal_idempotency_keys (
+  internal_user_id      VARCHAR(255) REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  idempotency_key       VARCHAR(255) NOT NULL,
+  request_hash          VARCHAR(255) NOT NULL,
+  status                INTEGER DEFAULT NULL,
+  response_body         TEXT DEFAULT NULL,
+  created_epoch_second  BIGINT NOT NULL,
+  PRIMARY KEY (internal_user_id, idempotency_key)
+);
+
+-- The numbers of the requests to the public API of each day, where `subject` is `token:` and the
+-- hash of an API token, or `ip:` and the address of an anonymous client.
+CREATE TABLE internal_api_usage (
+  subject               VARCHAR(255) NOT NULL,
+  day_epoch_second      BIGINT NOT NULL,
+  request_count         BIGINT NOT NULL,
+  PRIMARY KEY (subject, day_epoch_second)
+);
+
+-- The groups of AtCoder users, e.g. clubs and classrooms, which have the rankings of their own.
+CREATE TABLE internal_user_groups (
+  internal_group_id     VARCHAR(255) NOT NULL,
+  internal_user_id      VARCHAR(255) NOT NULL REFERENCES internal_users ON DELETE CASCADE ON UPDATE CASCADE,
+  group_name            VARCHAR(255) NOT NULL DEFAULT '',
+  PRIMARY KEY (internal_group_id)
+);
+CREATE INDEX ON internal_user_groups (internal_user_id);
+
+CREATE TABLE internal_user_group_members (
+  internal_group_id     VARCHAR(255) REFERENCES internal_user_groups ON DELETE CASCADE ON UPDATE CASCADE,
+  user_id               VARCHAR(255) NOT NULL,
+  PRIMARY KEY (internal_group_id, user_id)
+);
+
+-- The assignments of the problems by the coaches to their groups, which should be solved by the
+-- deadlines.
+CREATE TABLE internal_assignments (
+  internal_assignment_id    VARCHAR(255) NOT NULL,
+  internal_group_id         VARCHAR(255) NOT NULL REFERENCES internal_user_groups ON DELETE CASCADE ON UPDATE CASCADE,
+  title                     VARCHAR(255) NOT NULL DEFAULT '',
+  deadline_epoch_second     BIGINT NOT NULL,
+  created_epoch_second      BIGINT NOT NULL,
+  PRIMARY KEY (internal_assignment_id)
+);
+CREATE INDEX ON internal_assignments (internal_group_id);
+
+CREATE TABLE internal_assignment_problems (
+  internal_assignment_id    VARCHAR(255) REFERENCES internal_assignments ON DELETE CASCADE ON UPDATE CASCADE,
+  problem_id                VARCHAR(255) NOT NULL,
+  PRIMARY KEY (internal_assignment_id, problem_id)
+);
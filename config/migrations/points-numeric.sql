@@ -0,0 +1,11 @@
+-- Changes the points of `database-definition.sql` from `DOUBLE PRECISION` to `NUMERIC(18, 2)`, so
+-- that the partial scores and the sums of them are kept exactly. The existing points are rounded
+-- to the hundredths, which is the precision of the points of AtCoder.
+BEGIN;
+
+ALTER TABLE submissions ALTER COLUMN point TYPE NUMERIC(18, 2) USING ROUND(point::NUMERIC, 2);
+ALTER TABLE submissions_archive ALTER COLUMN point TYPE NUMERIC(18, 2) USING ROUND(point::NUMERIC, 2);
+ALTER TABLE points ALTER COLUMN point TYPE NUMERIC(18, 2) USING ROUND(point::NUMERIC, 2);
+ALTER TABLE rated_point_sum ALTER COLUMN point_sum TYPE NUMERIC(18, 2) USING ROUND(point_sum::NUMERIC, 2);
+
+COMMIT;
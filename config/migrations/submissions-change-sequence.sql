@@ -0,0 +1,37 @@
+-- Adds `change_sequence` of `database-definition.sql` to the existing `submissions` and
+-- `submissions_archive`. The existing submissions are sequenced in the order of their ids, so
+-- that the mirrors start from the oldest ones.
+BEGIN;
+
+CREATE SEQUENCE IF NOT EXISTS submissions_change_sequence;
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS change_sequence BIGINT;
+UPDATE submissions
+SET change_sequence = ordered.sequence
+FROM (SELECT id, row_number() OVER (ORDER BY id) AS sequence FROM submissions) AS ordered
+WHERE submissions.id = ordered.id AND submissions.change_sequence IS NULL;
+SELECT setval('submissions_change_sequence', COALESCE((SELECT max(change_sequence) FROM submissions), 0) + 1, FALSE);
+ALTER TABLE submissions ALTER COLUMN change_sequence SET DEFAULT nextval('submissions_change_sequence');
+ALTER TABLE submissions ALTER COLUMN change_sequence SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS submissions_change_sequence_idx ON submissions (change_sequence);
+CREATE INDEX IF NOT EXISTS submissions_epoch_second_id_idx ON submissions (epoch_second, id);
+
+-- The archived submissions keep the column in their default, since `submissions_archive` has
+-- the same columns as `submissions`.
+ALTER TABLE submissions_archive ADD COLUMN IF NOT EXISTS change_sequence BIGINT NOT NULL DEFAULT nextval('submissions_change_sequence');
+
+CREATE OR REPLACE FUNCTION sequence_submission_change() RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_advisory_xact_lock(hashtext('submissions_change_sequence'));
+  NEW.change_sequence := nextval('submissions_change_sequence');
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+DROP TRIGGER IF EXISTS sequence_submission_insert ON submissions;
+CREATE TRIGGER sequence_submission_insert BEFORE INSERT ON submissions
+  FOR EACH ROW EXECUTE PROCEDURE sequence_submission_change();
+DROP TRIGGER IF EXISTS sequence_submission_change ON submissions;
+CREATE TRIGGER sequence_submission_change BEFORE UPDATE ON submissions
+  FOR EACH ROW WHEN (OLD.* IS DISTINCT FROM NEW.*) EXECUTE PROCEDURE sequence_submission_change();
+
+COMMIT;
@@ -0,0 +1,4 @@
+-- Adds `invite_only` of `database-definition.sql` to the existing `internal_virtual_contests`.
+-- The existing contests stay accessible by their links, and `is_public` keeps only whether they
+-- are listed.
+ALTER TABLE internal_virtual_contests ADD COLUMN IF NOT EXISTS invite_only boolean NOT NULL DEFAULT FALSE;